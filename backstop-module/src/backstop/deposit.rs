@@ -1,20 +1,85 @@
-use crate::{contract::require_nonnegative, dependencies::TokenClient, emissions, storage};
-use soroban_sdk::{Address, Env};
+use crate::{
+    contract::require_nonnegative,
+    dependencies::TokenClient,
+    emissions,
+    errors::BackstopError,
+    storage::{self, UserLock},
+};
+use soroban_sdk::{map, panic_with_error, Address, Env, Map};
+
+use super::{
+    deposit_cap::require_under_deposit_cap,
+    deposit_gate::require_allowed_depositor,
+    lock::{lock_shares, settle_lock},
+    pool_interest::update_pool_interest,
+};
 
 /// Perform a deposit into the backstop module
+///
+/// ### Panics
+/// If the user is not allowed to deposit into the pool's backstop, or the deposit would put
+/// the user's deposits in the pool over the pool's deposit caps
 pub fn execute_deposit(e: &Env, from: &Address, pool_address: &Address, amount: i128) -> i128 {
     require_nonnegative(e, amount);
+
+    let backstop_token_client = TokenClient::new(e, &storage::get_backstop_token(e));
+    backstop_token_client.transfer(from, &e.current_contract_address(), &amount);
+
+    mint_shares(e, from, pool_address, amount)
+}
+
+/// Perform a deposit into multiple pools' backstops with a single transfer of backstop tokens
+///
+/// Returns a map of each pool to the number of shares minted
+///
+/// ### Arguments
+/// * `from` - The address depositing
+/// * `deposits` - A map of each pool to the amount of backstop tokens to deposit
+///
+/// ### Panics
+/// If no deposits are included, any amount is negative, the user is not allowed to deposit into
+/// any pool's backstop, or any deposit would put the user's deposits in a pool over the pool's
+/// deposit caps
+pub fn execute_batch_deposit(
+    e: &Env,
+    from: &Address,
+    deposits: &Map<Address, i128>,
+) -> Map<Address, i128> {
+    if deposits.is_empty() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    let mut total: i128 = 0;
+    for (_, amount) in deposits.iter() {
+        require_nonnegative(e, amount);
+        total += amount;
+    }
+    let backstop_token_client = TokenClient::new(e, &storage::get_backstop_token(e));
+    backstop_token_client.transfer(from, &e.current_contract_address(), &total);
+
+    let mut minted: Map<Address, i128> = map![e];
+    for (pool_address, amount) in deposits.iter() {
+        let to_mint = mint_shares(e, from, &pool_address, amount);
+        minted.set(pool_address, to_mint);
+    }
+    minted
+}
+
+/// Mint shares of a pool's backstop for backstop tokens already transferred to the backstop
+fn mint_shares(e: &Env, from: &Address, pool_address: &Address, amount: i128) -> i128 {
+    require_allowed_depositor(e, pool_address, from);
+
     let mut pool_balance = storage::get_pool_balance(e, pool_address);
     let mut user_balance = storage::get_user_balance(e, pool_address, from);
 
     emissions::update_emissions(e, pool_address, &pool_balance, from, &user_balance, false);
-
-    let backstop_token_client = TokenClient::new(e, &storage::get_backstop_token(e));
-    backstop_token_client.transfer(from, &e.current_contract_address(), &amount);
+    update_pool_interest(e, pool_address, from, &user_balance);
+    settle_lock(e, pool_address, from, &mut user_balance);
 
     let to_mint = pool_balance.convert_to_shares(amount);
     pool_balance.deposit(amount, to_mint);
     user_balance.add_shares(to_mint);
+    require_under_deposit_cap(e, pool_address, &mut pool_balance, &user_balance);
 
     storage::set_pool_balance(e, pool_address, &pool_balance);
     storage::set_user_balance(e, pool_address, from, &user_balance);
@@ -22,11 +87,30 @@ pub fn execute_deposit(e: &Env, from: &Address, pool_address: &Address, amount:
     to_mint
 }
 
+/// Perform a deposit into the backstop module and lock the minted shares for a lock period
+///
+/// Returns (the number of shares minted, the user's updated lock)
+pub fn execute_deposit_locked(
+    e: &Env,
+    from: &Address,
+    pool_address: &Address,
+    amount: i128,
+    lock_days: u32,
+) -> (i128, UserLock) {
+    let to_mint = execute_deposit(e, from, pool_address, amount);
+    let user_lock = lock_shares(e, pool_address, from, to_mint, lock_days);
+    (to_mint, user_lock)
+}
+
 #[cfg(test)]
 mod tests {
     use soroban_sdk::{testutils::Address as _, Address};
 
-    use crate::{backstop::execute_donate, testutils::create_backstop_token};
+    use crate::{
+        backstop::{execute_donate, get_deposit_exposure, set_deposit_cap},
+        storage::DepositCap,
+        testutils::create_backstop_token,
+    };
 
     use super::*;
 
@@ -124,4 +208,196 @@ mod tests {
             execute_deposit(&e, &samwise, &pool_0_id, -100);
         });
     }
+
+    #[test]
+    fn test_execute_deposit_under_cap() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        e.as_contract(&backstop_address, || {
+            set_deposit_cap(
+                &e,
+                &pool_0_id,
+                &DepositCap {
+                    max_tokens: 50_0000000,
+                    max_share: 0_5000000,
+                    min_pool_tokens: 40_0000000,
+                },
+            );
+
+            // the share cap does not apply until the pool holds the minimum tokens
+            execute_deposit(&e, &samwise, &pool_0_id, 30_0000000);
+            execute_deposit(&e, &frodo, &pool_0_id, 30_0000000);
+
+            let exposure = get_deposit_exposure(&e, &pool_0_id, &samwise);
+            assert_eq!(exposure.tokens, 30_0000000);
+            assert_eq!(exposure.share, 0_5000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(13)")]
+    fn test_execute_deposit_over_token_cap() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        e.as_contract(&backstop_address, || {
+            set_deposit_cap(
+                &e,
+                &pool_0_id,
+                &DepositCap {
+                    max_tokens: 50_0000000,
+                    max_share: 0,
+                    min_pool_tokens: 0,
+                },
+            );
+
+            execute_deposit(&e, &samwise, &pool_0_id, 30_0000000);
+            execute_deposit(&e, &samwise, &pool_0_id, 30_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(13)")]
+    fn test_execute_deposit_over_share_cap() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        e.as_contract(&backstop_address, || {
+            set_deposit_cap(
+                &e,
+                &pool_0_id,
+                &DepositCap {
+                    max_tokens: 0,
+                    max_share: 0_5000000,
+                    min_pool_tokens: 40_0000000,
+                },
+            );
+
+            execute_deposit(&e, &frodo, &pool_0_id, 30_0000000);
+            execute_deposit(&e, &samwise, &pool_0_id, 40_0000000);
+        });
+    }
+
+    #[test]
+    fn test_execute_batch_deposit() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let pool_1_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        // initialize pool 0 with funds + some profit
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &frodo, &pool_0_id, 25_0000000);
+            execute_donate(&e, &frodo, &pool_0_id, 25_0000000);
+        });
+
+        e.as_contract(&backstop_address, || {
+            let deposits = map![
+                &e,
+                (pool_0_id.clone(), 30_0000000),
+                (pool_1_id.clone(), 70_0000000)
+            ];
+            let minted = execute_batch_deposit(&e, &samwise, &deposits);
+            assert_eq!(minted.len(), 2);
+            assert_eq!(minted.get_unchecked(pool_0_id.clone()), 15_0000000);
+            assert_eq!(minted.get_unchecked(pool_1_id.clone()), 70_0000000);
+
+            let new_pool_0_balance = storage::get_pool_balance(&e, &pool_0_id);
+            assert_eq!(new_pool_0_balance.shares, 40_0000000);
+            assert_eq!(new_pool_0_balance.tokens, 80_0000000);
+
+            let new_pool_1_balance = storage::get_pool_balance(&e, &pool_1_id);
+            assert_eq!(new_pool_1_balance.shares, 70_0000000);
+            assert_eq!(new_pool_1_balance.tokens, 70_0000000);
+
+            let new_user_balance_0 = storage::get_user_balance(&e, &pool_0_id, &samwise);
+            assert_eq!(new_user_balance_0.shares, 15_0000000);
+            let new_user_balance_1 = storage::get_user_balance(&e, &pool_1_id, &samwise);
+            assert_eq!(new_user_balance_1.shares, 70_0000000);
+
+            assert_eq!(
+                backstop_token_client.balance(&backstop_address),
+                150_0000000
+            );
+            assert_eq!(backstop_token_client.balance(&samwise), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(11)")]
+    fn test_execute_batch_deposit_negative_tokens() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let pool_1_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        e.as_contract(&backstop_address, || {
+            let deposits = map![&e, (pool_0_id, 50_0000000), (pool_1_id, -10_0000000)];
+            execute_batch_deposit(&e, &samwise, &deposits);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(1)")]
+    fn test_execute_batch_deposit_empty() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&backstop_address, || {
+            execute_batch_deposit(&e, &samwise, &map![&e]);
+        });
+    }
 }
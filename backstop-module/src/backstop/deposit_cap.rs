@@ -0,0 +1,92 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{contracttype, panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::SCALAR_7,
+    errors::BackstopError,
+    storage::{self, DepositCap},
+};
+
+use super::{PoolBalance, UserBalance};
+
+/// A user's exposure to a pool's backstop
+#[derive(Clone)]
+#[contracttype]
+pub struct DepositExposure {
+    pub tokens: i128, // the backstop tokens the user's shares are worth
+    pub share: i128,  // the user's share of the pool's backstop, expressed in 7 decimals
+}
+
+/// Set the caps on the backstop deposits each address can hold for a pool. A cap with no
+/// limits removes the caps.
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `cap` - The deposit caps
+///
+/// ### Panics
+/// If a limit is negative or the percentage limit is over 100%
+pub fn set_deposit_cap(e: &Env, pool_address: &Address, cap: &DepositCap) {
+    if cap.max_tokens < 0 || cap.min_pool_tokens < 0 || cap.max_share as i128 > SCALAR_7 {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    if cap.max_tokens == 0 && cap.max_share == 0 {
+        storage::del_deposit_cap(e, pool_address);
+    } else {
+        storage::set_deposit_cap(e, pool_address, cap);
+    }
+}
+
+/// Require that a user's deposits in a pool's backstop are within the pool's deposit caps
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `pool_balance` - The pool's balance after the deposit
+/// * `user_balance` - The user's balance after the deposit
+///
+/// ### Panics
+/// If the user's deposits exceed the absolute cap, or the percentage cap once the pool's
+/// backstop holds at least the cap's minimum tokens
+pub fn require_under_deposit_cap(
+    e: &Env,
+    pool_address: &Address,
+    pool_balance: &mut PoolBalance,
+    user_balance: &UserBalance,
+) {
+    if let Some(cap) = storage::get_deposit_cap(e, pool_address) {
+        let exposure = calc_exposure(pool_balance, user_balance);
+        if cap.max_tokens > 0 && exposure.tokens > cap.max_tokens {
+            panic_with_error!(e, BackstopError::DepositCapExceeded);
+        }
+        if cap.max_share > 0
+            && pool_balance.tokens >= cap.min_pool_tokens
+            && exposure.share > cap.max_share as i128
+        {
+            panic_with_error!(e, BackstopError::DepositCapExceeded);
+        }
+    }
+}
+
+/// Fetch a user's exposure to a pool's backstop
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `user` - The user
+pub fn get_deposit_exposure(e: &Env, pool_address: &Address, user: &Address) -> DepositExposure {
+    let mut pool_balance = storage::get_pool_balance(e, pool_address);
+    let user_balance = storage::get_user_balance(e, pool_address, user);
+    calc_exposure(&mut pool_balance, &user_balance)
+}
+
+fn calc_exposure(pool_balance: &mut PoolBalance, user_balance: &UserBalance) -> DepositExposure {
+    let tokens = pool_balance.convert_to_tokens(user_balance.shares);
+    let share = if pool_balance.shares == 0 {
+        0
+    } else {
+        user_balance
+            .shares
+            .fixed_div_floor(pool_balance.shares, SCALAR_7)
+            .unwrap_optimized()
+    };
+    DepositExposure { tokens, share }
+}
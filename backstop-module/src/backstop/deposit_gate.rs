@@ -0,0 +1,116 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{dependencies::PoolClient, errors::BackstopError, storage};
+
+/// Set if deposits into a pool's backstop are limited to the users on the pool's allowlist
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `gated` - If deposits are limited to the pool's allowlist
+pub fn set_deposit_gated(e: &Env, pool_address: &Address, gated: bool) {
+    if gated {
+        storage::set_deposit_gated(e, pool_address);
+    } else {
+        storage::del_deposit_gated(e, pool_address);
+    }
+}
+
+/// Require that a user is allowed to deposit into a pool's backstop
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `user` - The address of the user
+///
+/// ### Panics
+/// If deposits into the pool's backstop are gated and the pool does not allow the user
+pub fn require_allowed_depositor(e: &Env, pool_address: &Address, user: &Address) {
+    if storage::get_deposit_gated(e, pool_address)
+        && !PoolClient::new(e, pool_address).is_allowed(user)
+    {
+        panic_with_error!(e, BackstopError::NotAuthorized);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, Symbol};
+
+    use crate::{backstop::execute_deposit, testutils::create_backstop_token};
+
+    use super::*;
+
+    /// A permissioned pool that only allows a single user
+    #[contract]
+    struct MockPool;
+
+    #[contractimpl]
+    impl MockPool {
+        pub fn set_allowed(e: Env, user: Address) {
+            e.storage()
+                .instance()
+                .set(&Symbol::new(&e, "Allowed"), &user);
+        }
+
+        pub fn is_allowed(e: Env, user: Address) -> bool {
+            e.storage()
+                .instance()
+                .get::<Symbol, Address>(&Symbol::new(&e, "Allowed"))
+                == Some(user)
+        }
+    }
+
+    #[test]
+    fn test_require_allowed_depositor() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let pool_id = e.register_contract(None, MockPool {});
+        MockPoolClient::new(&e, &pool_id).set_allowed(&samwise);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        e.as_contract(&backstop_address, || {
+            // deposits are not gated by default
+            execute_deposit(&e, &frodo, &pool_id, 10_0000000);
+
+            set_deposit_gated(&e, &pool_id, true);
+            assert!(storage::get_deposit_gated(&e, &pool_id));
+            execute_deposit(&e, &samwise, &pool_id, 10_0000000);
+
+            set_deposit_gated(&e, &pool_id, false);
+            assert!(!storage::get_deposit_gated(&e, &pool_id));
+            require_allowed_depositor(&e, &pool_id, &frodo);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(5)")]
+    fn test_execute_deposit_not_allowed() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let pool_id = e.register_contract(None, MockPool {});
+        MockPoolClient::new(&e, &pool_id).set_allowed(&samwise);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        e.as_contract(&backstop_address, || {
+            set_deposit_gated(&e, &pool_id, true);
+            execute_deposit(&e, &frodo, &pool_id, 10_0000000);
+        });
+    }
+}
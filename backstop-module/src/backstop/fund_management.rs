@@ -1,10 +1,19 @@
-use crate::{contract::require_nonnegative, dependencies::TokenClient, storage};
-use soroban_sdk::{Address, Env};
-
-use super::require_is_from_pool_factory;
-
-/// Perform a draw from a pool's backstop
-pub fn execute_draw(e: &Env, pool_address: &Address, amount: i128, to: &Address) {
+use crate::{
+    constants::DRAW_DISPUTE_WINDOW,
+    contract::require_nonnegative,
+    dependencies::TokenClient,
+    errors::BackstopError,
+    storage::{self, PendingDraw},
+};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use super::{lock::distribute_interest, require_is_from_pool_factory};
+
+/// Perform a draw from a pool's backstop. The drawn tokens are removed from the pool's
+/// balance immediately, but are held in escrow until the dispute window passes.
+///
+/// Returns the id of the pending draw
+pub fn execute_draw(e: &Env, pool_address: &Address, amount: i128, to: &Address) -> u32 {
     require_nonnegative(e, amount);
     require_is_from_pool_factory(e, pool_address);
 
@@ -13,8 +22,65 @@ pub fn execute_draw(e: &Env, pool_address: &Address, amount: i128, to: &Address)
     pool_balance.withdraw(e, amount, 0);
     storage::set_pool_balance(e, pool_address, &pool_balance);
 
+    let id = storage::next_draw_id(e);
+    storage::set_pending_draw(
+        e,
+        id,
+        &PendingDraw {
+            pool: pool_address.clone(),
+            to: to.clone(),
+            amount,
+            unlock_time: e.ledger().timestamp() + DRAW_DISPUTE_WINDOW,
+        },
+    );
+    storage::set_pending_draw_total(e, &(storage::get_pending_draw_total(e) + amount));
+    id
+}
+
+/// Release a pending draw to its recipient once the dispute window has passed
+///
+/// Returns the released draw
+///
+/// ### Panics
+/// If the draw does not exist or the dispute window has not passed
+pub fn execute_release_draw(e: &Env, id: u32) -> PendingDraw {
+    let draw = load_pending_draw(e, id);
+    if draw.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, BackstopError::NotExpired);
+    }
+    storage::del_pending_draw(e, id);
+    storage::set_pending_draw_total(e, &(storage::get_pending_draw_total(e) - draw.amount));
+
     let backstop_token = TokenClient::new(e, &storage::get_backstop_token(e));
-    backstop_token.transfer(&e.current_contract_address(), to, &amount);
+    backstop_token.transfer(&e.current_contract_address(), &draw.to, &draw.amount);
+    draw
+}
+
+/// Reverse a pending draw during its dispute window, returning the tokens to the pool's backstop
+///
+/// Returns the reversed draw
+///
+/// ### Panics
+/// If the draw does not exist or the dispute window has passed
+pub fn execute_reverse_draw(e: &Env, id: u32) -> PendingDraw {
+    let draw = load_pending_draw(e, id);
+    if draw.unlock_time <= e.ledger().timestamp() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    storage::del_pending_draw(e, id);
+    storage::set_pending_draw_total(e, &(storage::get_pending_draw_total(e) - draw.amount));
+
+    let mut pool_balance = storage::get_pool_balance(e, &draw.pool);
+    pool_balance.deposit(draw.amount, 0);
+    storage::set_pool_balance(e, &draw.pool, &pool_balance);
+    draw
+}
+
+fn load_pending_draw(e: &Env, id: u32) -> PendingDraw {
+    match storage::get_pending_draw(e, id) {
+        Some(draw) => draw,
+        None => panic_with_error!(e, BackstopError::BadRequest),
+    }
 }
 
 /// Perform a donation to a pool's backstop
@@ -25,7 +91,7 @@ pub fn execute_donate(e: &Env, from: &Address, pool_address: &Address, amount: i
     backstop_token.transfer(from, &e.current_contract_address(), &amount);
 
     let mut pool_balance = storage::get_pool_balance(e, pool_address);
-    pool_balance.deposit(amount, 0);
+    distribute_interest(e, pool_address, &mut pool_balance, amount);
     storage::set_pool_balance(e, pool_address, &pool_balance);
 }
 
@@ -35,7 +101,7 @@ mod tests {
 
     use crate::{
         backstop::execute_deposit,
-        testutils::{create_backstop_token, create_mock_pool_factory},
+        testutils::{create_backstop_token, create_mock_pool_factory, set_timestamp},
     };
 
     use super::*;
@@ -100,6 +166,7 @@ mod tests {
     fn test_execute_draw() {
         let e = Env::default();
         e.mock_all_auths();
+        set_timestamp(&e, 0);
 
         let backstop_address = Address::random(&e);
         let pool_0_id = Address::random(&e);
@@ -119,13 +186,128 @@ mod tests {
         });
 
         e.as_contract(&backstop_address, || {
-            execute_draw(&e, &pool_0_id, 30_0000000, &samwise);
+            let id = execute_draw(&e, &pool_0_id, 30_0000000, &samwise);
+            assert_eq!(id, 0);
 
             let new_pool_balance = storage::get_pool_balance(&e, &pool_0_id);
             assert_eq!(new_pool_balance.shares, 50_0000000);
             assert_eq!(new_pool_balance.tokens, 20_0000000);
+            // the drawn tokens are held in escrow during the dispute window
+            assert_eq!(backstop_token_client.balance(&backstop_address), 50_0000000);
+            assert_eq!(backstop_token_client.balance(&samwise), 0);
+            let draw = storage::get_pending_draw(&e, id).unwrap();
+            assert_eq!(draw.amount, 30_0000000);
+            assert_eq!(draw.unlock_time, DRAW_DISPUTE_WINDOW);
+            assert_eq!(storage::get_pending_draw_total(&e), 30_0000000);
+        });
+
+        set_timestamp(&e, DRAW_DISPUTE_WINDOW);
+        e.as_contract(&backstop_address, || {
+            execute_release_draw(&e, 0);
+
             assert_eq!(backstop_token_client.balance(&backstop_address), 20_0000000);
             assert_eq!(backstop_token_client.balance(&samwise), 30_0000000);
+            assert!(storage::get_pending_draw(&e, 0).is_none());
+            assert_eq!(storage::get_pending_draw_total(&e), 0);
+        });
+    }
+
+    #[test]
+    fn test_execute_reverse_draw() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 0);
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &frodo, &pool_0_id, 50_0000000);
+            execute_draw(&e, &pool_0_id, 30_0000000, &samwise);
+        });
+
+        set_timestamp(&e, DRAW_DISPUTE_WINDOW - 1);
+        e.as_contract(&backstop_address, || {
+            let draw = execute_reverse_draw(&e, 0);
+            assert_eq!(draw.to, samwise);
+
+            let new_pool_balance = storage::get_pool_balance(&e, &pool_0_id);
+            assert_eq!(new_pool_balance.shares, 50_0000000);
+            assert_eq!(new_pool_balance.tokens, 50_0000000);
+            assert_eq!(backstop_token_client.balance(&backstop_address), 50_0000000);
+            assert!(storage::get_pending_draw(&e, 0).is_none());
+            assert_eq!(storage::get_pending_draw_total(&e), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(3)")]
+    fn test_execute_release_draw_during_dispute_window() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 0);
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &frodo, &pool_0_id, 50_0000000);
+            execute_draw(&e, &pool_0_id, 30_0000000, &samwise);
+        });
+
+        set_timestamp(&e, DRAW_DISPUTE_WINDOW - 1);
+        e.as_contract(&backstop_address, || {
+            execute_release_draw(&e, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(1)")]
+    fn test_execute_reverse_draw_after_dispute_window() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 0);
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &frodo, &pool_0_id, 50_0000000);
+            execute_draw(&e, &pool_0_id, 30_0000000, &samwise);
+        });
+
+        set_timestamp(&e, DRAW_DISPUTE_WINDOW);
+        e.as_contract(&backstop_address, || {
+            execute_reverse_draw(&e, 0);
         });
     }
 
@@ -0,0 +1,322 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::SCALAR_7,
+    emissions,
+    errors::BackstopError,
+    storage::{self, UserLock},
+};
+
+use super::{pool_interest::update_pool_interest, PoolBalance, UserBalance};
+
+/// Fetch the bonus multiplier for a lock period, expressed in 7 decimals
+///
+/// ### Panics
+/// If the lock period is not 30, 90 or 180 days
+fn lock_multiplier(e: &Env, lock_days: u32) -> i128 {
+    match lock_days {
+        30 => 1_1000000,
+        90 => 1_2500000,
+        180 => 1_5000000,
+        _ => panic_with_error!(e, BackstopError::BadRequest),
+    }
+}
+
+/// Lock shares deposited by a user. Locked shares cannot be queued for withdrawal until the
+/// lock expires, and earn a bonus weight on the depositor's emission and interest share.
+///
+/// If the user already has an active lock, the shares are added to it and the lock is
+/// extended to the later of the two unlock times.
+///
+/// The user's lock MUST be settled before calling this function.
+///
+/// Returns the updated lock
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `user` - The owner of the shares
+/// * `shares` - The amount of shares to lock
+/// * `lock_days` - The lock period, in days (30, 90 or 180)
+///
+/// ### Panics
+/// If the lock period is invalid
+pub fn lock_shares(
+    e: &Env,
+    pool_address: &Address,
+    user: &Address,
+    shares: i128,
+    lock_days: u32,
+) -> UserLock {
+    let bonus = shares
+        .fixed_mul_floor(lock_multiplier(e, lock_days) - SCALAR_7, SCALAR_7)
+        .unwrap_optimized();
+    let unlock = e.ledger().timestamp() + u64::from(lock_days) * 24 * 60 * 60;
+
+    let mut pool_lock = storage::get_pool_lock(e, pool_address);
+    let user_lock = match storage::get_user_lock(e, pool_address, user) {
+        Some(user_lock) => UserLock {
+            shares: user_lock.shares + shares,
+            bonus: user_lock.bonus + bonus,
+            unlock: user_lock.unlock.max(unlock),
+            index: user_lock.index,
+        },
+        None => UserLock {
+            shares,
+            bonus,
+            unlock,
+            index: pool_lock.index,
+        },
+    };
+    pool_lock.bonus += bonus;
+
+    storage::set_pool_lock(e, pool_address, &pool_lock);
+    storage::set_user_lock(e, pool_address, user, &user_lock);
+    user_lock
+}
+
+/// Settle a user's locked deposit. Accrued bonus shares are added to the user's balance,
+/// and the lock is removed if it has expired.
+///
+/// The user's emissions MUST be updated before calling this function, and the user balance
+/// stored afterwards.
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `user` - The owner of the deposit
+/// * `user_balance` - The user's balance
+pub fn settle_lock(
+    e: &Env,
+    pool_address: &Address,
+    user: &Address,
+    user_balance: &mut UserBalance,
+) {
+    let mut user_lock = match storage::get_user_lock(e, pool_address, user) {
+        Some(user_lock) => user_lock,
+        None => return,
+    };
+    let mut pool_lock = storage::get_pool_lock(e, pool_address);
+
+    let bonus_shares = user_lock
+        .bonus
+        .fixed_mul_floor(pool_lock.index - user_lock.index, SCALAR_7)
+        .unwrap_optimized();
+    user_balance.add_shares(bonus_shares);
+    pool_lock.unassigned -= bonus_shares;
+
+    if user_lock.unlock <= e.ledger().timestamp() {
+        pool_lock.bonus -= user_lock.bonus;
+        storage::del_user_lock(e, pool_address, user);
+    } else {
+        user_lock.index = pool_lock.index;
+        storage::set_user_lock(e, pool_address, user, &user_lock);
+    }
+    storage::set_pool_lock(e, pool_address, &pool_lock);
+}
+
+/// Fetch the amount of shares a user currently has locked
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `user` - The owner of the deposit
+pub fn get_locked_shares(e: &Env, pool_address: &Address, user: &Address) -> i128 {
+    match storage::get_user_lock(e, pool_address, user) {
+        Some(user_lock) if user_lock.unlock > e.ledger().timestamp() => user_lock.shares,
+        _ => 0,
+    }
+}
+
+/// Fetch the weight a pool's backstop distributes emissions and interest by. Locked deposits
+/// earn on their bonus weight in addition to their shares. Bonus shares that have not been
+/// settled to a locked depositor are excluded, as no depositor earns on them yet.
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `pool_balance` - The pool's balance
+pub fn get_pool_weight(e: &Env, pool_address: &Address, pool_balance: &PoolBalance) -> i128 {
+    let pool_lock = storage::get_pool_lock(e, pool_address);
+    pool_balance.shares - pool_lock.unassigned + pool_lock.bonus
+}
+
+/// Add interest to a pool's backstop, boosting the share of locked deposits by their bonus
+/// weight. The bonus portion is minted as shares and distributed to locked depositors as
+/// their locks are settled. Until then, the bonus shares are unassigned and do not change the
+/// pool's weight.
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `pool_balance` - The pool's balance
+/// * `amount` - The amount of backstop tokens being added
+pub fn distribute_interest(
+    e: &Env,
+    pool_address: &Address,
+    pool_balance: &mut PoolBalance,
+    amount: i128,
+) {
+    let mut pool_lock = storage::get_pool_lock(e, pool_address);
+    if pool_lock.bonus == 0 || pool_balance.shares == 0 {
+        pool_balance.deposit(amount, 0);
+        return;
+    }
+
+    let bonus_tokens = amount
+        .fixed_mul_floor(
+            pool_lock.bonus,
+            get_pool_weight(e, pool_address, pool_balance),
+        )
+        .unwrap_optimized();
+    pool_balance.deposit(amount - bonus_tokens, 0);
+    let bonus_shares = pool_balance.convert_to_shares(bonus_tokens);
+    pool_balance.deposit(bonus_tokens, bonus_shares);
+
+    pool_lock.index += bonus_shares
+        .fixed_div_floor(pool_lock.bonus, SCALAR_7)
+        .unwrap_optimized();
+    pool_lock.unassigned += bonus_shares;
+    storage::set_pool_lock(e, pool_address, &pool_lock);
+}
+
+/// Settle a user's locked deposit on behalf of anyone, removing the lock's bonus weight
+/// once it has expired
+pub fn execute_settle_lock(e: &Env, pool_address: &Address, user: &Address) {
+    let pool_balance = storage::get_pool_balance(e, pool_address);
+    let mut user_balance = storage::get_user_balance(e, pool_address, user);
+
+    emissions::update_emissions(e, pool_address, &pool_balance, user, &user_balance, false);
+    update_pool_interest(e, pool_address, user, &user_balance);
+    settle_lock(e, pool_address, user, &mut user_balance);
+
+    storage::set_user_balance(e, pool_address, user, &user_balance);
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::testutils::Address as _;
+
+    use crate::{
+        backstop::{
+            execute_deposit, execute_deposit_locked, execute_donate, execute_queue_withdrawal,
+        },
+        testutils::{create_backstop_token, set_timestamp},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_lock_bonus_interest() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+        set_timestamp(&e, 10000);
+
+        let backstop_address = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        backstop_token_client.mint(&frodo, &100_0000000);
+        backstop_token_client.mint(&bombadil, &50_0000000);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &frodo, &pool_address, 100_0000000);
+            execute_deposit_locked(&e, &samwise, &pool_address, 100_0000000, 180);
+
+            let user_lock = storage::get_user_lock(&e, &pool_address, &samwise).unwrap();
+            assert_eq!(user_lock.shares, 100_0000000);
+            assert_eq!(user_lock.bonus, 50_0000000);
+            assert_eq!(user_lock.unlock, 10000 + 180 * 24 * 60 * 60);
+            assert_eq!(storage::get_pool_lock(&e, &pool_address).bonus, 50_0000000);
+
+            // 10 of the 50 tokens donated are the bonus weight's share
+            execute_donate(&e, &bombadil, &pool_address, 50_0000000);
+            let pool_balance = storage::get_pool_balance(&e, &pool_address);
+            assert_eq!(pool_balance.tokens, 250_0000000);
+            assert_eq!(pool_balance.shares, 208_3333333);
+            let pool_lock = storage::get_pool_lock(&e, &pool_address);
+            assert_eq!(pool_lock.index, 0_1666666);
+            assert_eq!(pool_lock.unassigned, 8_3333333);
+            assert_eq!(
+                get_pool_weight(&e, &pool_address, &pool_balance),
+                250_0000000
+            );
+
+            execute_settle_lock(&e, &pool_address, &samwise);
+            let user_balance = storage::get_user_balance(&e, &pool_address, &samwise);
+            assert_eq!(user_balance.shares, 108_3333300);
+            assert_eq!(storage::get_pool_lock(&e, &pool_address).unassigned, 33);
+            assert!(storage::get_user_lock(&e, &pool_address, &samwise).is_some());
+            assert_eq!(get_locked_shares(&e, &pool_address, &samwise), 100_0000000);
+        });
+
+        set_timestamp(&e, 10000 + 180 * 24 * 60 * 60);
+        e.as_contract(&backstop_address, || {
+            execute_settle_lock(&e, &pool_address, &samwise);
+            assert!(storage::get_user_lock(&e, &pool_address, &samwise).is_none());
+            assert_eq!(storage::get_pool_lock(&e, &pool_address).bonus, 0);
+            assert_eq!(get_locked_shares(&e, &pool_address, &samwise), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(12)")]
+    fn test_queue_withdrawal_locked_shares() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+        set_timestamp(&e, 10000);
+
+        let backstop_address = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_address, 50_0000000);
+            execute_deposit_locked(&e, &samwise, &pool_address, 50_0000000, 30);
+
+            // only the unlocked shares can be queued
+            execute_queue_withdrawal(&e, &samwise, &pool_address, 50_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool_address, 1);
+        });
+    }
+
+    #[test]
+    fn test_lock_shares_extends_lock() {
+        let e = Env::default();
+        set_timestamp(&e, 10000);
+
+        let backstop_address = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&backstop_address, || {
+            lock_shares(&e, &pool_address, &samwise, 10_0000000, 90);
+            let user_lock = lock_shares(&e, &pool_address, &samwise, 10_0000000, 30);
+            assert_eq!(user_lock.shares, 20_0000000);
+            assert_eq!(user_lock.bonus, 3_5000000);
+            assert_eq!(user_lock.unlock, 10000 + 90 * 24 * 60 * 60);
+            assert_eq!(storage::get_pool_lock(&e, &pool_address).bonus, 3_5000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_lock_shares_invalid_period() {
+        let e = Env::default();
+        let backstop_address = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&backstop_address, || {
+            lock_shares(&e, &pool_address, &samwise, 10_0000000, 60);
+        });
+    }
+}
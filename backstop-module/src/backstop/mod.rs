@@ -1,11 +1,32 @@
 mod deposit;
-pub use deposit::execute_deposit;
+pub use deposit::{execute_batch_deposit, execute_deposit, execute_deposit_locked};
+
+mod deposit_cap;
+pub use deposit_cap::{
+    get_deposit_exposure, require_under_deposit_cap, set_deposit_cap, DepositExposure,
+};
+
+mod deposit_gate;
+pub use deposit_gate::{require_allowed_depositor, set_deposit_gated};
 
 mod fund_management;
-pub use fund_management::{execute_donate, execute_draw};
+pub use fund_management::{
+    execute_donate, execute_draw, execute_release_draw, execute_reverse_draw,
+};
 
 mod withdrawal;
-pub use withdrawal::{execute_dequeue_withdrawal, execute_queue_withdrawal, execute_withdraw};
+pub use withdrawal::{
+    execute_batch_withdraw, execute_dequeue_withdrawal, execute_queue_withdrawal, execute_withdraw,
+};
+
+mod token_swap;
+pub use token_swap::{execute_cancel_token_swap, execute_queue_token_swap, execute_token_swap};
+
+mod lock;
+pub use lock::{execute_settle_lock, get_pool_weight};
+
+mod pool_interest;
+pub use pool_interest::{execute_claim_pool_interest, execute_distribute_pool_interest};
 
 mod pool;
 pub use pool::{require_is_from_pool_factory, PoolBalance};
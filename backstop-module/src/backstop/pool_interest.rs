@@ -0,0 +1,227 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map};
+
+use crate::{
+    constants::SCALAR_7,
+    contract::require_nonnegative,
+    dependencies::TokenClient,
+    errors::BackstopError,
+    storage::{self, UserPoolInterest},
+};
+
+use super::{lock::get_pool_weight, require_is_from_pool_factory, UserBalance};
+
+/// Distribute interest in a pool asset to the pool's backstop depositors in kind. The pool must
+/// have approved the backstop to transfer the interest. Depositors earn the interest pro-rata
+/// by the same weight as their emissions, and claim it in the asset it was distributed in.
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `asset` - The asset the interest is paid in
+/// * `amount` - The amount of interest
+///
+/// ### Panics
+/// If the pool was not deployed by the pool factory, the asset is the backstop token, or the
+/// pool's backstop has no depositors to distribute the interest to
+pub fn execute_distribute_pool_interest(
+    e: &Env,
+    pool_address: &Address,
+    asset: &Address,
+    amount: i128,
+) {
+    require_nonnegative(e, amount);
+    require_is_from_pool_factory(e, pool_address);
+    // interest in the backstop token is donated to the pool's backstop instead
+    if *asset == storage::get_backstop_token(e) {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    let pool_balance = storage::get_pool_balance(e, pool_address);
+    let pool_weight = get_pool_weight(e, pool_address, &pool_balance);
+    if pool_weight == 0 {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    TokenClient::new(e, asset).transfer_from(
+        &e.current_contract_address(),
+        pool_address,
+        &e.current_contract_address(),
+        &amount,
+    );
+
+    let mut indexes = storage::get_pool_interest(e, pool_address);
+    let additional_idx = amount
+        .fixed_div_floor(pool_weight, SCALAR_7)
+        .unwrap_optimized();
+    indexes.set(
+        asset.clone(),
+        indexes.get(asset.clone()).unwrap_or(0) + additional_idx,
+    );
+    storage::set_pool_interest(e, pool_address, &indexes);
+}
+
+/// Accrue a user's share of the interest distributed in kind to a pool's backstop. Must be
+/// called before the user's shares or lock bonus change.
+///
+/// Returns the user's updated interest, or None if no interest has been distributed to the
+/// pool's backstop
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `user` - The address of the user
+/// * `user_balance` - The user's balance before the change
+pub fn update_pool_interest(
+    e: &Env,
+    pool_address: &Address,
+    user: &Address,
+    user_balance: &UserBalance,
+) -> Option<UserPoolInterest> {
+    let indexes = storage::get_pool_interest(e, pool_address);
+    if indexes.is_empty() {
+        return None;
+    }
+
+    let user_weight = match storage::get_user_lock(e, pool_address, user) {
+        Some(user_lock) => user_balance.shares + user_lock.bonus,
+        None => user_balance.shares,
+    };
+    // the user's weight has not changed since their last update, so any asset missing from
+    // their indexes has been earned since its first distribution
+    let mut user_interest =
+        storage::get_user_pool_interest(e, pool_address, user).unwrap_or(UserPoolInterest {
+            index: map![e],
+            accrued: map![e],
+        });
+    for (asset, index) in indexes.iter() {
+        let user_index = user_interest.index.get(asset.clone()).unwrap_or(0);
+        if index == user_index {
+            continue;
+        }
+        if user_weight != 0 {
+            let to_accrue = user_weight
+                .fixed_mul_floor(index - user_index, SCALAR_7)
+                .unwrap_optimized();
+            user_interest.accrued.set(
+                asset.clone(),
+                user_interest.accrued.get(asset.clone()).unwrap_or(0) + to_accrue,
+            );
+        }
+        user_interest.index.set(asset, index);
+    }
+    storage::set_user_pool_interest(e, pool_address, user, &user_interest);
+    Some(user_interest)
+}
+
+/// Claim a user's share of the interest distributed in kind to a pool's backstop
+///
+/// Returns a map of each asset to the amount of interest claimed
+///
+/// ### Arguments
+/// * `from` - The address of the user
+/// * `pool_address` - The address of the pool
+/// * `to` - The address receiving the interest
+pub fn execute_claim_pool_interest(
+    e: &Env,
+    from: &Address,
+    pool_address: &Address,
+    to: &Address,
+) -> Map<Address, i128> {
+    let user_balance = storage::get_user_balance(e, pool_address, from);
+    let mut user_interest = match update_pool_interest(e, pool_address, from, &user_balance) {
+        Some(user_interest) => user_interest,
+        None => return map![e],
+    };
+
+    let mut claimed: Map<Address, i128> = map![e];
+    for (asset, amount) in user_interest.accrued.iter() {
+        if amount > 0 {
+            TokenClient::new(e, &asset).transfer(&e.current_contract_address(), to, &amount);
+            claimed.set(asset, amount);
+        }
+    }
+    user_interest.accrued = map![e];
+    storage::set_user_pool_interest(e, pool_address, from, &user_interest);
+    claimed
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::testutils::Address as _;
+
+    use crate::{
+        backstop::{execute_deposit, execute_queue_withdrawal},
+        testutils::{create_backstop_token, create_mock_pool_factory, create_token},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_pool_interest() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        backstop_token_client.mint(&frodo, &100_0000000);
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+        let (asset, asset_client) = create_token(&e, &bombadil);
+        asset_client.mint(&pool_0_id, &100_0000000);
+        asset_client.approve(&pool_0_id, &backstop_address, &100_0000000, &1000000);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 30_0000000);
+            execute_deposit(&e, &frodo, &pool_0_id, 10_0000000);
+
+            execute_distribute_pool_interest(&e, &pool_0_id, &asset, 8_0000000);
+            assert_eq!(asset_client.balance(&backstop_address), 8_0000000);
+
+            // a new deposit does not change the interest already earned, and queued shares
+            // continue to earn interest
+            execute_deposit(&e, &frodo, &pool_0_id, 40_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool_0_id, 30_0000000);
+            execute_distribute_pool_interest(&e, &pool_0_id, &asset, 8_0000000);
+
+            let claimed = execute_claim_pool_interest(&e, &samwise, &pool_0_id, &merry);
+            assert_eq!(claimed.get_unchecked(asset.clone()), 6_0000000 + 3_0000000);
+            let claimed = execute_claim_pool_interest(&e, &frodo, &pool_0_id, &frodo);
+            assert_eq!(claimed.get_unchecked(asset.clone()), 2_0000000 + 5_0000000);
+            assert_eq!(asset_client.balance(&merry), 9_0000000);
+            assert_eq!(asset_client.balance(&frodo), 7_0000000);
+
+            // nothing is left to claim
+            let claimed = execute_claim_pool_interest(&e, &samwise, &pool_0_id, &merry);
+            assert!(claimed.is_empty());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(1)")]
+    fn test_distribute_pool_interest_no_depositors() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        create_backstop_token(&e, &backstop_address, &bombadil);
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+        let (asset, asset_client) = create_token(&e, &bombadil);
+        asset_client.mint(&pool_0_id, &100_0000000);
+        asset_client.approve(&pool_0_id, &backstop_address, &100_0000000, &1000000);
+
+        e.as_contract(&backstop_address, || {
+            execute_distribute_pool_interest(&e, &pool_0_id, &asset, 8_0000000);
+        });
+    }
+}
@@ -0,0 +1,331 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map, Vec};
+
+use crate::{
+    constants::TOKEN_SWAP_TIMELOCK,
+    dependencies::{LPMigratorClient, TokenClient},
+    errors::BackstopError,
+    storage::{self, BackstopTokenSwap},
+};
+
+/// Queue a swap of the backstop token to a new token. The swap can be executed once the
+/// timelock has passed, giving depositors time to exit.
+///
+/// ### Arguments
+/// * `new_token` - The new backstop token
+/// * `migrator` - The contract converting the current backstop token to the new token
+///
+/// ### Panics
+/// If the new token is the current backstop token
+pub fn execute_queue_token_swap(
+    e: &Env,
+    new_token: &Address,
+    migrator: &Address,
+) -> BackstopTokenSwap {
+    if *new_token == storage::get_backstop_token(e) {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    let token_swap = BackstopTokenSwap {
+        new_token: new_token.clone(),
+        migrator: migrator.clone(),
+        unlock_time: e.ledger().timestamp() + TOKEN_SWAP_TIMELOCK,
+    };
+    storage::set_token_swap(e, &token_swap);
+    token_swap
+}
+
+/// Cancel the queued backstop token swap
+///
+/// ### Panics
+/// If no swap is queued
+pub fn execute_cancel_token_swap(e: &Env) {
+    if storage::get_token_swap(e).is_none() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    storage::del_token_swap(e);
+}
+
+/// Execute the queued backstop token swap. The entire balance of the current backstop token
+/// is converted by the migrator, and the new tokens are split between the pools pro rata
+/// to their current token balances. Pool shares are unaffected.
+///
+/// Returns (the current backstop tokens converted, the new tokens received, the new token balance of each pool)
+///
+/// ### Arguments
+/// * `pools` - Every pool with a backstop balance
+///
+/// ### Panics
+/// If no swap is queued, the timelock has not passed, a draw is pending, the pools are invalid
+/// or do not hold every token tracked in pool balances, or the migrator does not return any
+/// new tokens
+pub fn execute_token_swap(e: &Env, pools: &Vec<Address>) -> (i128, i128, Map<Address, i128>) {
+    let token_swap = match storage::get_token_swap(e) {
+        Some(token_swap) => token_swap,
+        None => panic_with_error!(e, BackstopError::BadRequest),
+    };
+    if token_swap.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, BackstopError::NotExpired);
+    }
+    // pending draws are owed in the current token and must settle before it is converted
+    if storage::get_pending_draw_total(e) > 0 {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    let mut total_tokens: i128 = 0;
+    let mut pool_tokens: Map<Address, i128> = map![e];
+    for pool in pools.iter() {
+        if pool_tokens.contains_key(pool.clone()) {
+            panic_with_error!(e, BackstopError::BadRequest);
+        }
+        let pool_balance = storage::get_pool_balance(e, &pool);
+        total_tokens += pool_balance.tokens;
+        pool_tokens.set(pool, pool_balance.tokens);
+    }
+
+    // an omitted pool would keep its balance in the current token's units, and its share of
+    // the new tokens would go to the listed pools
+    if total_tokens != storage::get_total_pool_tokens(e) {
+        panic_with_error!(e, BackstopError::InvalidBalance);
+    }
+
+    let old_token = TokenClient::new(e, &storage::get_backstop_token(e));
+    let new_token = TokenClient::new(e, &token_swap.new_token);
+    let old_balance = old_token.balance(&e.current_contract_address());
+    if total_tokens <= 0 || total_tokens > old_balance {
+        panic_with_error!(e, BackstopError::InvalidBalance);
+    }
+
+    let pre_swap_balance = new_token.balance(&e.current_contract_address());
+    old_token.transfer(
+        &e.current_contract_address(),
+        &token_swap.migrator,
+        &old_balance,
+    );
+    LPMigratorClient::new(e, &token_swap.migrator)
+        .migrate(&e.current_contract_address(), &old_balance);
+    let new_balance = new_token.balance(&e.current_contract_address()) - pre_swap_balance;
+    if new_balance <= 0 {
+        panic_with_error!(e, BackstopError::InvalidBalance);
+    }
+
+    // any tokens held outside of pool balances are split between the pools
+    let mut new_pool_tokens: Map<Address, i128> = map![e];
+    for (pool, tokens) in pool_tokens.iter() {
+        let mut pool_balance = storage::get_pool_balance(e, &pool);
+        pool_balance.tokens = tokens
+            .fixed_mul_floor(new_balance, total_tokens)
+            .unwrap_optimized();
+        storage::set_pool_balance(e, &pool, &pool_balance);
+        new_pool_tokens.set(pool, pool_balance.tokens);
+    }
+
+    storage::set_backstop_token(e, &token_swap.new_token);
+    storage::del_token_swap(e);
+    (old_balance, new_balance, new_pool_tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, vec, Symbol};
+
+    use crate::{
+        backstop::execute_deposit,
+        testutils::{create_backstop_token, create_token, set_timestamp},
+    };
+
+    use super::*;
+
+    /// Converts the backstop token into the new token at a 2:1 rate
+    #[contract]
+    struct MockMigrator;
+
+    #[contractimpl]
+    impl MockMigrator {
+        pub fn set_token(e: Env, token: Address) {
+            e.storage()
+                .instance()
+                .set(&Symbol::new(&e, "Token"), &token);
+        }
+
+        pub fn migrate(e: Env, to: Address, amount: i128) {
+            let token: Address = e
+                .storage()
+                .instance()
+                .get(&Symbol::new(&e, "Token"))
+                .unwrap();
+            TokenClient::new(&e, &token).transfer(
+                &e.current_contract_address(),
+                &to,
+                &(amount * 2),
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_token_swap() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 1000);
+
+        let backstop_id = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let pool_1_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (old_token_id, old_token_client) = create_backstop_token(&e, &backstop_id, &bombadil);
+        old_token_client.mint(&samwise, &100_0000000);
+        old_token_client.mint(&frodo, &100_0000000);
+        let (new_token_id, new_token_client) = create_token(&e, &bombadil);
+
+        let migrator_id = e.register_contract(None, MockMigrator {});
+        MockMigratorClient::new(&e, &migrator_id).set_token(&new_token_id);
+        new_token_client.mint(&migrator_id, &1000_0000000);
+
+        e.as_contract(&backstop_id, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 60_0000000);
+            execute_deposit(&e, &frodo, &pool_1_id, 40_0000000);
+
+            let token_swap = execute_queue_token_swap(&e, &new_token_id, &migrator_id);
+            assert_eq!(token_swap.unlock_time, 1000 + TOKEN_SWAP_TIMELOCK);
+        });
+
+        set_timestamp(&e, 1000 + TOKEN_SWAP_TIMELOCK);
+        e.as_contract(&backstop_id, || {
+            let (old_balance, new_balance, new_pool_tokens) =
+                execute_token_swap(&e, &vec![&e, pool_0_id.clone(), pool_1_id.clone()]);
+            assert_eq!(old_balance, 100_0000000);
+            assert_eq!(new_balance, 200_0000000);
+            assert_eq!(
+                new_pool_tokens.get_unchecked(pool_0_id.clone()),
+                120_0000000
+            );
+            assert_eq!(new_pool_tokens.get_unchecked(pool_1_id.clone()), 80_0000000);
+
+            let pool_0_balance = storage::get_pool_balance(&e, &pool_0_id);
+            assert_eq!(pool_0_balance.tokens, 120_0000000);
+            assert_eq!(pool_0_balance.shares, 60_0000000);
+            assert_eq!(storage::get_total_pool_tokens(&e), 200_0000000);
+            assert_eq!(storage::get_backstop_token(&e), new_token_id);
+            assert!(storage::get_token_swap(&e).is_none());
+        });
+        assert_eq!(old_token_client.balance(&backstop_id), 0);
+        assert_eq!(new_token_client.balance(&backstop_id), 200_0000000);
+        assert_eq!(old_token_client.balance(&migrator_id), 100_0000000);
+        assert_ne!(old_token_id, new_token_id);
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(3)")]
+    fn test_execute_token_swap_before_unlock() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 1000);
+
+        let backstop_id = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (_, old_token_client) = create_backstop_token(&e, &backstop_id, &bombadil);
+        old_token_client.mint(&samwise, &100_0000000);
+        let (new_token_id, _) = create_token(&e, &bombadil);
+        let migrator_id = e.register_contract(None, MockMigrator {});
+
+        e.as_contract(&backstop_id, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 60_0000000);
+            execute_queue_token_swap(&e, &new_token_id, &migrator_id);
+        });
+
+        set_timestamp(&e, 1000 + TOKEN_SWAP_TIMELOCK - 1);
+        e.as_contract(&backstop_id, || {
+            execute_token_swap(&e, &vec![&e, pool_0_id.clone()]);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_execute_token_swap_duplicate_pool() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 1000);
+
+        let backstop_id = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (_, old_token_client) = create_backstop_token(&e, &backstop_id, &bombadil);
+        old_token_client.mint(&samwise, &100_0000000);
+        let (new_token_id, _) = create_token(&e, &bombadil);
+        let migrator_id = e.register_contract(None, MockMigrator {});
+
+        e.as_contract(&backstop_id, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 60_0000000);
+            execute_queue_token_swap(&e, &new_token_id, &migrator_id);
+        });
+
+        set_timestamp(&e, 1000 + TOKEN_SWAP_TIMELOCK);
+        e.as_contract(&backstop_id, || {
+            execute_token_swap(&e, &vec![&e, pool_0_id.clone(), pool_0_id.clone()]);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_execute_token_swap_missing_pool() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 1000);
+
+        let backstop_id = Address::random(&e);
+        let pool_0_id = Address::random(&e);
+        let pool_1_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (_, old_token_client) = create_backstop_token(&e, &backstop_id, &bombadil);
+        old_token_client.mint(&samwise, &100_0000000);
+        old_token_client.mint(&frodo, &100_0000000);
+        let (new_token_id, _) = create_token(&e, &bombadil);
+        let migrator_id = e.register_contract(None, MockMigrator {});
+
+        e.as_contract(&backstop_id, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 60_0000000);
+            execute_deposit(&e, &frodo, &pool_1_id, 40_0000000);
+            execute_queue_token_swap(&e, &new_token_id, &migrator_id);
+        });
+
+        set_timestamp(&e, 1000 + TOKEN_SWAP_TIMELOCK);
+        e.as_contract(&backstop_id, || {
+            execute_token_swap(&e, &vec![&e, pool_0_id.clone()]);
+        });
+    }
+
+    #[test]
+    fn test_execute_cancel_token_swap() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        create_backstop_token(&e, &backstop_id, &bombadil);
+        let (new_token_id, _) = create_token(&e, &bombadil);
+        let migrator_id = Address::random(&e);
+
+        e.as_contract(&backstop_id, || {
+            execute_queue_token_swap(&e, &new_token_id, &migrator_id);
+            assert!(storage::get_token_swap(&e).is_some());
+
+            execute_cancel_token_swap(&e);
+            assert!(storage::get_token_swap(&e).is_none());
+        });
+    }
+}
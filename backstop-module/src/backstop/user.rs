@@ -38,6 +38,15 @@ impl UserBalance {
 
     /***** Withdrawal Queue Management *****/
 
+    /// Fetch the total amount of shares queued for withdrawal
+    pub fn queued_shares(&self) -> i128 {
+        let mut q4w_amt: i128 = 0;
+        for q4w in self.q4w.iter() {
+            q4w_amt += q4w.amount
+        }
+        q4w_amt
+    }
+
     /// Queue new shares for withdraw for the user
     ///
     /// Returns the new Q4W object
@@ -48,12 +57,7 @@ impl UserBalance {
     /// ### Errors
     /// If the amount to queue is greater than the available shares
     pub fn queue_shares_for_withdrawal(&mut self, e: &Env, to_q: i128) {
-        let mut q4w_amt: i128 = 0;
-        for q4w in self.q4w.iter() {
-            q4w_amt += q4w.amount
-        }
-
-        if self.shares - q4w_amt < to_q {
+        if self.shares - self.queued_shares() < to_q {
             panic_with_error!(e, BackstopError::InvalidBalance);
         }
 
@@ -1,7 +1,14 @@
-use crate::{contract::require_nonnegative, dependencies::TokenClient, emissions, storage};
-use soroban_sdk::{unwrap::UnwrapOptimized, Address, Env};
-
-use super::Q4W;
+use crate::{
+    contract::require_nonnegative, dependencies::TokenClient, emissions, errors::BackstopError,
+    storage,
+};
+use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map};
+
+use super::{
+    lock::{get_locked_shares, settle_lock},
+    pool_interest::update_pool_interest,
+    Q4W,
+};
 
 /// Perform a queue for withdraw from the backstop module
 pub fn execute_queue_withdrawal(
@@ -15,6 +22,14 @@ pub fn execute_queue_withdrawal(
     let mut pool_balance = storage::get_pool_balance(e, pool_address);
     let mut user_balance = storage::get_user_balance(e, pool_address, from);
 
+    emissions::update_emissions(e, pool_address, &pool_balance, from, &user_balance, false);
+    update_pool_interest(e, pool_address, from, &user_balance);
+    settle_lock(e, pool_address, from, &mut user_balance);
+    let unlocked = user_balance.shares - get_locked_shares(e, pool_address, from);
+    if unlocked - user_balance.queued_shares() < amount {
+        panic_with_error!(e, BackstopError::DepositLocked);
+    }
+
     user_balance.queue_shares_for_withdrawal(e, amount);
     pool_balance.queue_for_withdraw(amount);
 
@@ -42,10 +57,59 @@ pub fn execute_dequeue_withdrawal(e: &Env, from: &Address, pool_address: &Addres
 pub fn execute_withdraw(e: &Env, from: &Address, pool_address: &Address, amount: i128) -> i128 {
     require_nonnegative(e, amount);
 
+    let to_return = burn_shares(e, from, pool_address, amount);
+
+    let backstop_token_client = TokenClient::new(e, &storage::get_backstop_token(e));
+    backstop_token_client.transfer(&e.current_contract_address(), from, &to_return);
+
+    to_return
+}
+
+/// Perform a withdraw from multiple pools' backstops with a single transfer of backstop tokens
+///
+/// Returns a map of each pool to the amount of backstop tokens withdrawn
+///
+/// ### Arguments
+/// * `from` - The address withdrawing
+/// * `withdrawals` - A map of each pool to the amount of shares to withdraw
+///
+/// ### Panics
+/// If no withdrawals are included, any amount is negative, or the user does not have enough
+/// expired queued shares in any pool
+pub fn execute_batch_withdraw(
+    e: &Env,
+    from: &Address,
+    withdrawals: &Map<Address, i128>,
+) -> Map<Address, i128> {
+    if withdrawals.is_empty() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    let mut total: i128 = 0;
+    let mut withdrawn: Map<Address, i128> = map![e];
+    for (pool_address, amount) in withdrawals.iter() {
+        require_nonnegative(e, amount);
+        let to_return = burn_shares(e, from, &pool_address, amount);
+        total += to_return;
+        withdrawn.set(pool_address, to_return);
+    }
+
+    let backstop_token_client = TokenClient::new(e, &storage::get_backstop_token(e));
+    backstop_token_client.transfer(&e.current_contract_address(), from, &total);
+
+    withdrawn
+}
+
+/// Burn queued shares of a pool's backstop. The backstop tokens are not transferred.
+///
+/// Returns the amount of backstop tokens the shares were worth
+fn burn_shares(e: &Env, from: &Address, pool_address: &Address, amount: i128) -> i128 {
     let mut pool_balance = storage::get_pool_balance(e, pool_address);
     let mut user_balance = storage::get_user_balance(e, pool_address, from);
 
     emissions::update_emissions(e, pool_address, &pool_balance, from, &user_balance, false);
+    update_pool_interest(e, pool_address, from, &user_balance);
+    settle_lock(e, pool_address, from, &mut user_balance);
 
     user_balance.withdraw_shares(e, amount);
 
@@ -55,9 +119,6 @@ pub fn execute_withdraw(e: &Env, from: &Address, pool_address: &Address, amount:
     storage::set_user_balance(e, pool_address, from, &user_balance);
     storage::set_pool_balance(e, pool_address, &pool_balance);
 
-    let backstop_token_client = TokenClient::new(e, &storage::get_backstop_token(e));
-    backstop_token_client.transfer(&e.current_contract_address(), from, &to_return);
-
     to_return
 }
 
@@ -390,4 +451,136 @@ mod tests {
             execute_withdraw(&e, &samwise, &pool_address, -42_0000000);
         });
     }
+
+    #[test]
+    fn test_execute_batch_withdraw() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_address = Address::random(&e);
+        let pool_1_address = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &200_0000000);
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 1,
+            sequence_number: 200,
+            timestamp: 10000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        // setup pools with queues for withdrawal and allow pool 0's backstop to incur a profit
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_0_address, 100_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool_0_address, 42_0000000);
+            execute_donate(&e, &samwise, &pool_0_address, 50_0000000);
+            execute_deposit(&e, &samwise, &pool_1_address, 50_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool_1_address, 20_0000000);
+        });
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 1,
+            sequence_number: 200,
+            timestamp: 10000 + 30 * 24 * 60 * 60 + 1,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        e.as_contract(&backstop_address, || {
+            let withdrawals = map![
+                &e,
+                (pool_0_address.clone(), 42_0000000),
+                (pool_1_address.clone(), 20_0000000)
+            ];
+            let withdrawn = execute_batch_withdraw(&e, &samwise, &withdrawals);
+            assert_eq!(withdrawn.len(), 2);
+            assert_eq!(withdrawn.get_unchecked(pool_0_address.clone()), 63_0000000);
+            assert_eq!(withdrawn.get_unchecked(pool_1_address.clone()), 20_0000000);
+
+            let new_pool_0_balance = storage::get_pool_balance(&e, &pool_0_address);
+            assert_eq!(new_pool_0_balance.q4w, 0);
+            assert_eq!(new_pool_0_balance.shares, 58_0000000);
+            assert_eq!(new_pool_0_balance.tokens, 87_0000000);
+
+            let new_pool_1_balance = storage::get_pool_balance(&e, &pool_1_address);
+            assert_eq!(new_pool_1_balance.q4w, 0);
+            assert_eq!(new_pool_1_balance.shares, 30_0000000);
+            assert_eq!(new_pool_1_balance.tokens, 30_0000000);
+
+            let new_user_balance_1 = storage::get_user_balance(&e, &pool_1_address, &samwise);
+            assert_eq!(new_user_balance_1.shares, 30_0000000);
+            assert_eq!(new_user_balance_1.q4w.len(), 0);
+
+            assert_eq!(
+                backstop_token_client.balance(&backstop_address),
+                117_0000000
+            );
+            assert_eq!(backstop_token_client.balance(&samwise), 83_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_execute_batch_withdraw_not_expired() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = Address::random(&e);
+        let pool_0_address = Address::random(&e);
+        let pool_1_address = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &200_0000000);
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 1,
+            sequence_number: 200,
+            timestamp: 10000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_0_address, 100_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool_0_address, 42_0000000);
+            execute_deposit(&e, &samwise, &pool_1_address, 50_0000000);
+        });
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 1,
+            sequence_number: 200,
+            timestamp: 10000 + 30 * 24 * 60 * 60 + 1,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        // pool 1 has no queued shares, so the entire batch fails
+        e.as_contract(&backstop_address, || {
+            let withdrawals = map![
+                &e,
+                (pool_0_address.clone(), 42_0000000),
+                (pool_1_address.clone(), 20_0000000)
+            ];
+            execute_batch_withdraw(&e, &samwise, &withdrawals);
+        });
+    }
 }
@@ -3,3 +3,10 @@ pub const SCALAR_7: i128 = 1_0000000;
 
 // The approximate deployment date of the backstop module TODO: pick one
 pub const BACKSTOP_EPOCH: u64 = 1441065600;
+
+// The delay before a queued backstop token swap can be executed, longer than the withdrawal
+// queue so depositors can exit before the swap
+pub const TOKEN_SWAP_TIMELOCK: u64 = 31 * 24 * 60 * 60;
+
+// The window after a draw from a pool's backstop during which the guardian can reverse it
+pub const DRAW_DISPUTE_WINDOW: u64 = 24 * 60 * 60;
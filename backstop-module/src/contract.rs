@@ -1,8 +1,10 @@
 use crate::{
-    backstop::{self, PoolBalance, UserBalance, Q4W},
+    backstop::{self, DepositExposure, PoolBalance, UserBalance, Q4W},
     emissions,
     errors::BackstopError,
-    storage,
+    storage::{
+        self, BackstopTokenSwap, DepositCap, PendingDraw, RzChallenge, RzChallengeConfig, UserLock,
+    },
 };
 use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Map, Symbol, Vec};
 
@@ -16,6 +18,8 @@ pub trait BackstopModuleTrait {
     /// Initialize the backstop module
     ///
     /// ### Arguments
+    /// * `admin` - The Address for the admin, who can swap the backstop token and set the guardian,
+    ///   deposit caps, deposit gating and reward zone challenge config
     /// * `backstop_token` - The backstop token ID - generally an LP token where 1 of the tokens is BLND
     /// * `blnd_token` - The BLND token ID
     /// * `pool_factory` - The pool factory ID
@@ -25,6 +29,7 @@ pub trait BackstopModuleTrait {
     /// If initialize has already been called
     fn initialize(
         e: Env,
+        admin: Address,
         backstop_token: Address,
         blnd_token: Address,
         pool_factory: Address,
@@ -43,6 +48,46 @@ pub trait BackstopModuleTrait {
     /// * `amount` - The amount of tokens to deposit
     fn deposit(e: Env, from: Address, pool_address: Address, amount: i128) -> i128;
 
+    /// Deposit backstop tokens from "from" into the backstop of a pool and lock the minted
+    /// shares. Locked shares cannot be queued for withdrawal until the lock expires, and
+    /// earn a bonus on the depositor's emission and interest share of:
+    /// * 30 days - 1.1x
+    /// * 90 days - 1.25x
+    /// * 180 days - 1.5x
+    ///
+    /// Returns the number of backstop pool shares minted
+    ///
+    /// ### Arguments
+    /// * `from` - The address depositing into the backstop
+    /// * `pool_address` - The address of the pool
+    /// * `amount` - The amount of tokens to deposit
+    /// * `lock_days` - The lock period, in days
+    ///
+    /// ### Errors
+    /// If the lock period is not 30, 90 or 180 days
+    fn deposit_locked(
+        e: Env,
+        from: Address,
+        pool_address: Address,
+        amount: i128,
+        lock_days: u32,
+    ) -> i128;
+
+    /// Settle the locked deposit of a user, paying out any accrued bonus shares and removing
+    /// the lock's bonus once it has expired. Can be called by anyone.
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    /// * `user` - The owner of the locked deposit
+    fn settle_lock(e: Env, pool_address: Address, user: Address);
+
+    /// Fetch the locked deposit of a user, if any
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    /// * `user` - The owner of the locked deposit
+    fn user_lock(e: Env, pool_address: Address, user: Address) -> Option<UserLock>;
+
     /// Queue deposited pool shares from "from" for withdraw from a backstop of a pool
     ///
     /// Returns the created queue for withdrawal
@@ -71,6 +116,33 @@ pub trait BackstopModuleTrait {
     /// * `amount` - The amount of shares to withdraw
     fn withdraw(e: Env, from: Address, pool_address: Address, amount: i128) -> i128;
 
+    /// Deposit backstop tokens from "from" into the backstop of multiple pools, with a single
+    /// transfer of backstop tokens
+    ///
+    /// Returns a map of each pool to the number of backstop pool shares minted
+    ///
+    /// ### Arguments
+    /// * `from` - The address depositing into the backstop
+    /// * `deposits` - A map of each pool to the amount of backstop tokens to deposit
+    ///
+    /// ### Errors
+    /// If no deposits are included, or any deposit is invalid
+    fn batch_deposit(e: Env, from: Address, deposits: Map<Address, i128>) -> Map<Address, i128>;
+
+    /// Withdraw shares from "from"s withdraw queue for multiple pools' backstops, with a single
+    /// transfer of backstop tokens
+    ///
+    /// Returns a map of each pool to the amount of backstop tokens withdrawn
+    ///
+    /// ### Arguments
+    /// * `from` - The address whose deposits are being withdrawn
+    /// * `withdrawals` - A map of each pool to the amount of shares to withdraw
+    ///
+    /// ### Errors
+    /// If no withdrawals are included, or any withdrawal is invalid
+    fn batch_withdraw(e: Env, from: Address, withdrawals: Map<Address, i128>)
+        -> Map<Address, i128>;
+
     /// Fetch the balance of backstop shares of a pool for the user
     ///
     /// ### Arguments
@@ -107,6 +179,36 @@ pub trait BackstopModuleTrait {
     /// Fetch the reward zone
     fn get_rz(e: Env) -> Vec<Address>;
 
+    /// Snapshot a challenger pool's backstop against the weakest pool in a full reward zone. Once
+    /// the challenger has stayed ahead of the weakest pool by the challenge margin for the
+    /// challenge period, the weakest pool is swapped out of the reward zone for the challenger.
+    ///
+    /// Returns the pool removed from the reward zone, if the challenge succeeded
+    ///
+    /// ### Arguments
+    /// * `challenger` - The address of the pool challenging for a reward zone slot
+    ///
+    /// ### Errors
+    /// If challenges are disabled, the reward zone is not full, or the challenger is already in
+    /// the reward zone
+    fn rz_challenge(e: Env, challenger: Address) -> Option<Address>;
+
+    /// Fetch a pool's ongoing reward zone challenge, if any
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    fn get_rz_challenge(e: Env, pool_address: Address) -> Option<RzChallenge>;
+
+    /// (Admin only) Set the requirements for a pool to challenge its way into a full reward
+    /// zone. A config with a period of zero disables challenges.
+    ///
+    /// ### Arguments
+    /// * `config` - The margin, period and snapshot interval of a challenge
+    ///
+    /// ### Errors
+    /// If the caller is not the admin or the snapshot interval is invalid
+    fn set_rz_challenge_config(e: Env, config: RzChallengeConfig);
+
     /// Fetch the EPS (emissions per second) and expiration for the current distribution window of a pool
     /// in a tuple where (EPS, expiration)
     fn pool_eps(e: Env, pool_address: Address) -> (i128, u64);
@@ -124,12 +226,35 @@ pub trait BackstopModuleTrait {
     /// If an invalid pool address is included
     fn claim(e: Env, from: Address, pool_addresses: Vec<Address>, to: Address);
 
+    /// Claim backstop deposit emissions from a list of pools for `from`, with a single transfer
+    /// of BLND
+    ///
+    /// Returns a map of each pool to the amount of BLND emissions claimed from it
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user claiming emissions
+    /// * `pool_addresses` - The Vec of addresses to claim backstop deposit emissions from
+    /// * `to` - The Address to send to emissions to
+    ///
+    /// ### Errors
+    /// If no pool addresses are included
+    fn batch_claim(
+        e: Env,
+        from: Address,
+        pool_addresses: Vec<Address>,
+        to: Address,
+    ) -> Map<Address, i128>;
+
     /// Fetch the drop list
     fn drop_list(e: Env) -> Map<Address, i128>;
 
     /********** Fund Management *********/
 
-    /// Take backstop token from a pools backstop
+    /// Take backstop token from a pools backstop. The tokens are removed from the pool's backstop
+    /// immediately, but are held in escrow for a dispute window, during which the guardian
+    /// can reverse the draw, before they can be released to `to`.
+    ///
+    /// Returns the id of the pending draw
     ///
     /// ### Arguments
     /// * `from` - The address of the pool drawing tokens from the backstop
@@ -139,7 +264,87 @@ pub trait BackstopModuleTrait {
     ///
     /// ### Errors
     /// If the pool does not have enough backstop tokens
-    fn draw(e: Env, pool_address: Address, amount: i128, to: Address);
+    fn draw(e: Env, pool_address: Address, amount: i128, to: Address) -> u32;
+
+    /// Release a pending draw to its recipient once the dispute window has passed
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the pending draw
+    ///
+    /// ### Errors
+    /// If the draw does not exist or the dispute window has not passed
+    fn release_draw(e: Env, id: u32);
+
+    /// (Guardian only) Reverse an erroneous pending draw during its dispute window, such as
+    /// one caused by an oracle failure, returning the tokens to the pool's backstop. The pool
+    /// that drew the tokens must also authorize the reversal, such that it can reverse the rest
+    /// of the action the draw paid for, like returning a bad debt auction filler's liabilities.
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the pending draw
+    ///
+    /// ### Errors
+    /// If the caller is not the guardian, the pool does not authorize the reversal, the draw does
+    /// not exist, or the dispute window has passed
+    fn reverse_draw(e: Env, id: u32);
+
+    /// Fetch a pending draw, if it exists
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the pending draw
+    fn get_pending_draw(e: Env, id: u32) -> Option<PendingDraw>;
+
+    /// (Admin only) Set the guardian, who can reverse pending draws. Defaults to the admin.
+    ///
+    /// ### Arguments
+    /// * `guardian` - The Address for the guardian
+    ///
+    /// ### Errors
+    /// If the caller is not the admin
+    fn set_guardian(e: Env, guardian: Address);
+
+    /// (Admin only) Set the caps on the backstop deposits each address can hold for a pool, such
+    /// that a single depositor cannot dominate the pool's backstop. A cap with no limits removes
+    /// the caps.
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    /// * `cap` - The absolute and percentage caps on each address's deposits
+    ///
+    /// ### Errors
+    /// If the caller is not the admin or the caps are invalid
+    fn set_deposit_cap(e: Env, pool_address: Address, cap: DepositCap);
+
+    /// Fetch the deposit caps for a pool, if any are set
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    fn get_deposit_cap(e: Env, pool_address: Address) -> Option<DepositCap>;
+
+    /// (Admin only) Set if deposits into a pool's backstop are limited to the users on the pool's
+    /// allowlist, for permissioned pools deployed for known participants
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    /// * `gated` - If deposits are limited to the pool's allowlist
+    ///
+    /// ### Errors
+    /// If the caller is not the admin
+    fn set_deposit_gated(e: Env, pool_address: Address, gated: bool);
+
+    /// Fetch if deposits into a pool's backstop are limited to the pool's allowlist
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    fn get_deposit_gated(e: Env, pool_address: Address) -> bool;
+
+    /// Fetch a user's exposure to a pool's backstop, as the tokens their shares are worth and
+    /// their share of the pool's backstop
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    /// * `user` - The user
+    fn deposit_exposure(e: Env, pool_address: Address, user: Address) -> DepositExposure;
 
     /// Sends backstop tokens from "from" to a pools backstop
     ///
@@ -153,6 +358,77 @@ pub trait BackstopModuleTrait {
     /// ### Errors
     /// If the `pool_address` is not valid
     fn donate(e: Env, from: Address, pool_address: Address, amount: i128);
+
+    /// (Pool only) Distribute interest in a pool asset to the pool's backstop depositors in kind.
+    /// The pool must approve the backstop to transfer the interest first. Depositors earn the
+    /// interest pro-rata to their shares, including any lock bonus, and claim it with
+    /// `claim_pool_interest`.
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool distributing the interest
+    /// * `asset` - The asset the interest is paid in
+    /// * `amount` - The amount of interest
+    ///
+    /// ### Errors
+    /// If the `pool_address` is not valid, the asset is the backstop token, or the pool's
+    /// backstop has no depositors
+    fn distribute_pool_interest(e: Env, pool_address: Address, asset: Address, amount: i128);
+
+    /// Claim the interest distributed in kind to a pool's backstop that "from" has earned
+    ///
+    /// Returns a map of each asset to the amount of interest claimed
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the depositor claiming interest
+    /// * `pool_address` - The address of the pool
+    /// * `to` - The address to send the interest to
+    fn claim_pool_interest(
+        e: Env,
+        from: Address,
+        pool_address: Address,
+        to: Address,
+    ) -> Map<Address, i128>;
+
+    /// Fetch the interest index of each asset distributed in kind to a pool's backstop
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    fn get_pool_interest(e: Env, pool_address: Address) -> Map<Address, i128>;
+
+    /********** Token Migration *********/
+
+    /// (Admin only) Queue a swap of the backstop token to a new token, such as an upgraded LP token.
+    /// The swap can be executed once the timelock has passed.
+    ///
+    /// Returns the queued swap
+    ///
+    /// ### Arguments
+    /// * `new_token` - The new backstop token
+    /// * `migrator` - The contract converting the current backstop token to the new token
+    ///
+    /// ### Errors
+    /// If the caller is not the admin or the new token is the current backstop token
+    fn queue_token_swap(e: Env, new_token: Address, migrator: Address) -> BackstopTokenSwap;
+
+    /// (Admin only) Cancel the queued backstop token swap
+    ///
+    /// ### Errors
+    /// If the caller is not the admin or no swap is queued
+    fn cancel_token_swap(e: Env);
+
+    /// (Admin only) Execute the queued backstop token swap, converting the balance of every pool
+    /// to the new token
+    ///
+    /// ### Arguments
+    /// * `pools` - Every pool with a backstop balance
+    ///
+    /// ### Errors
+    /// If the caller is not the admin, no swap is queued, the timelock has not passed, or the
+    /// conversion fails
+    fn token_swap(e: Env, pools: Vec<Address>);
+
+    /// Fetch the queued backstop token swap, if any
+    fn get_token_swap(e: Env) -> Option<BackstopTokenSwap>;
 }
 
 /// @dev
@@ -162,6 +438,7 @@ pub trait BackstopModuleTrait {
 impl BackstopModuleTrait for BackstopModule {
     fn initialize(
         e: Env,
+        admin: Address,
         backstop_token: Address,
         blnd_token: Address,
         pool_factory: Address,
@@ -171,6 +448,7 @@ impl BackstopModuleTrait for BackstopModule {
             panic_with_error!(e, BackstopError::AlreadyInitialized);
         }
 
+        storage::set_admin(&e, &admin);
         storage::set_backstop_token(&e, &backstop_token);
         storage::set_blnd_token(&e, &blnd_token);
         storage::set_pool_factory(&e, &pool_factory);
@@ -192,6 +470,39 @@ impl BackstopModuleTrait for BackstopModule {
         to_mint
     }
 
+    fn deposit_locked(
+        e: Env,
+        from: Address,
+        pool_address: Address,
+        amount: i128,
+        lock_days: u32,
+    ) -> i128 {
+        storage::bump_instance(&e);
+        from.require_auth();
+
+        let (to_mint, user_lock) =
+            backstop::execute_deposit_locked(&e, &from, &pool_address, amount, lock_days);
+
+        e.events().publish(
+            (Symbol::new(&e, "deposit_locked"), pool_address, from),
+            (amount, to_mint, user_lock.unlock),
+        );
+        to_mint
+    }
+
+    fn settle_lock(e: Env, pool_address: Address, user: Address) {
+        storage::bump_instance(&e);
+
+        backstop::execute_settle_lock(&e, &pool_address, &user);
+
+        e.events()
+            .publish((Symbol::new(&e, "settle_lock"), pool_address), user);
+    }
+
+    fn user_lock(e: Env, pool_address: Address, user: Address) -> Option<UserLock> {
+        storage::get_user_lock(&e, &pool_address, &user)
+    }
+
     fn queue_withdrawal(e: Env, from: Address, pool_address: Address, amount: i128) -> Q4W {
         storage::bump_instance(&e);
         from.require_auth();
@@ -230,6 +541,48 @@ impl BackstopModuleTrait for BackstopModule {
         to_withdraw
     }
 
+    fn batch_deposit(e: Env, from: Address, deposits: Map<Address, i128>) -> Map<Address, i128> {
+        storage::bump_instance(&e);
+        from.require_auth();
+
+        let minted = backstop::execute_batch_deposit(&e, &from, &deposits);
+
+        for (pool_address, to_mint) in minted.iter() {
+            e.events().publish(
+                (
+                    Symbol::new(&e, "deposit"),
+                    pool_address.clone(),
+                    from.clone(),
+                ),
+                (deposits.get_unchecked(pool_address), to_mint),
+            );
+        }
+        minted
+    }
+
+    fn batch_withdraw(
+        e: Env,
+        from: Address,
+        withdrawals: Map<Address, i128>,
+    ) -> Map<Address, i128> {
+        storage::bump_instance(&e);
+        from.require_auth();
+
+        let withdrawn = backstop::execute_batch_withdraw(&e, &from, &withdrawals);
+
+        for (pool_address, to_withdraw) in withdrawn.iter() {
+            e.events().publish(
+                (
+                    Symbol::new(&e, "withdraw"),
+                    pool_address.clone(),
+                    from.clone(),
+                ),
+                (withdrawals.get_unchecked(pool_address), to_withdraw),
+            );
+        }
+        withdrawn
+    }
+
     fn user_balance(e: Env, pool: Address, user: Address) -> UserBalance {
         storage::get_user_balance(&e, &pool, &user)
     }
@@ -261,6 +614,36 @@ impl BackstopModuleTrait for BackstopModule {
         storage::get_reward_zone(&e)
     }
 
+    fn rz_challenge(e: Env, challenger: Address) -> Option<Address> {
+        storage::bump_instance(&e);
+        let removed = emissions::execute_rz_challenge(&e, &challenger);
+
+        e.events().publish(
+            (Symbol::new(&e, "rz_challenge"), challenger.clone()),
+            removed.clone(),
+        );
+        if let Some(to_remove) = removed.clone() {
+            e.events()
+                .publish((Symbol::new(&e, "rw_zone"),), (challenger, to_remove));
+        }
+        removed
+    }
+
+    fn get_rz_challenge(e: Env, pool_address: Address) -> Option<RzChallenge> {
+        storage::get_rz_challenge(&e, &pool_address)
+    }
+
+    fn set_rz_challenge_config(e: Env, config: RzChallengeConfig) {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        emissions::set_rz_challenge_config(&e, &config);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_rz_challenge_config"), admin), config);
+    }
+
     fn pool_eps(e: Env, pool_address: Address) -> (i128, u64) {
         (
             storage::get_pool_eps(&e, &pool_address),
@@ -277,22 +660,121 @@ impl BackstopModuleTrait for BackstopModule {
         e.events().publish((Symbol::new(&e, "claim"), from), amount);
     }
 
+    fn batch_claim(
+        e: Env,
+        from: Address,
+        pool_addresses: Vec<Address>,
+        to: Address,
+    ) -> Map<Address, i128> {
+        storage::bump_instance(&e);
+        from.require_auth();
+
+        let claimed = emissions::execute_batch_claim(&e, &from, &pool_addresses, &to);
+
+        for (pool_address, amount) in claimed.iter() {
+            e.events().publish(
+                (Symbol::new(&e, "claim"), from.clone(), pool_address),
+                amount,
+            );
+        }
+        claimed
+    }
+
     fn drop_list(e: Env) -> Map<Address, i128> {
         storage::get_drop_list(&e)
     }
 
     /********** Fund Management *********/
 
-    fn draw(e: Env, pool_address: Address, amount: i128, to: Address) {
+    fn draw(e: Env, pool_address: Address, amount: i128, to: Address) -> u32 {
         // TODO: Unit test this once `env.recorded_top_authorizations()`
         //       can be executed from WASM, or add `test_auth` file
         storage::bump_instance(&e);
         pool_address.require_auth();
 
-        backstop::execute_draw(&e, &pool_address, amount, &to);
+        let id = backstop::execute_draw(&e, &pool_address, amount, &to);
 
         e.events()
-            .publish((Symbol::new(&e, "draw"), pool_address), (to, amount));
+            .publish((Symbol::new(&e, "draw"), pool_address), (to, amount, id));
+        id
+    }
+
+    fn release_draw(e: Env, id: u32) {
+        storage::bump_instance(&e);
+
+        let draw = backstop::execute_release_draw(&e, id);
+
+        e.events().publish(
+            (Symbol::new(&e, "release_draw"), draw.pool),
+            (draw.to, draw.amount, id),
+        );
+    }
+
+    fn reverse_draw(e: Env, id: u32) {
+        storage::bump_instance(&e);
+        let guardian = storage::get_guardian(&e).unwrap_or_else(|| storage::get_admin(&e));
+        guardian.require_auth();
+
+        let draw = backstop::execute_reverse_draw(&e, id);
+        draw.pool.require_auth();
+
+        e.events().publish(
+            (Symbol::new(&e, "reverse_draw"), draw.pool, guardian),
+            (draw.to, draw.amount, id),
+        );
+    }
+
+    fn get_pending_draw(e: Env, id: u32) -> Option<PendingDraw> {
+        storage::get_pending_draw(&e, id)
+    }
+
+    fn set_guardian(e: Env, guardian: Address) {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        storage::set_guardian(&e, &guardian);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_guardian"), admin), guardian);
+    }
+
+    fn set_deposit_cap(e: Env, pool_address: Address, cap: DepositCap) {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        backstop::set_deposit_cap(&e, &pool_address, &cap);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_deposit_cap"), pool_address, admin),
+            cap,
+        );
+    }
+
+    fn get_deposit_cap(e: Env, pool_address: Address) -> Option<DepositCap> {
+        storage::get_deposit_cap(&e, &pool_address)
+    }
+
+    fn set_deposit_gated(e: Env, pool_address: Address, gated: bool) {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        backstop::set_deposit_gated(&e, &pool_address, gated);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_deposit_gated"), pool_address, admin),
+            gated,
+        );
+    }
+
+    fn get_deposit_gated(e: Env, pool_address: Address) -> bool {
+        storage::get_deposit_gated(&e, &pool_address)
+    }
+
+    fn deposit_exposure(e: Env, pool_address: Address, user: Address) -> DepositExposure {
+        backstop::get_deposit_exposure(&e, &pool_address, &user)
     }
 
     fn donate(e: Env, from: Address, pool_address: Address, amount: i128) {
@@ -303,6 +785,89 @@ impl BackstopModuleTrait for BackstopModule {
         e.events()
             .publish((Symbol::new(&e, "donate"), pool_address, from), (amount));
     }
+
+    fn distribute_pool_interest(e: Env, pool_address: Address, asset: Address, amount: i128) {
+        storage::bump_instance(&e);
+        pool_address.require_auth();
+
+        backstop::execute_distribute_pool_interest(&e, &pool_address, &asset, amount);
+        e.events().publish(
+            (Symbol::new(&e, "distribute_pool_interest"), pool_address),
+            (asset, amount),
+        );
+    }
+
+    fn claim_pool_interest(
+        e: Env,
+        from: Address,
+        pool_address: Address,
+        to: Address,
+    ) -> Map<Address, i128> {
+        storage::bump_instance(&e);
+        from.require_auth();
+
+        let claimed = backstop::execute_claim_pool_interest(&e, &from, &pool_address, &to);
+        e.events().publish(
+            (Symbol::new(&e, "claim_pool_interest"), pool_address, from),
+            claimed.clone(),
+        );
+        claimed
+    }
+
+    fn get_pool_interest(e: Env, pool_address: Address) -> Map<Address, i128> {
+        storage::get_pool_interest(&e, &pool_address)
+    }
+
+    /********** Token Migration *********/
+
+    fn queue_token_swap(e: Env, new_token: Address, migrator: Address) -> BackstopTokenSwap {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let token_swap = backstop::execute_queue_token_swap(&e, &new_token, &migrator);
+
+        e.events().publish(
+            (Symbol::new(&e, "queue_token_swap"), admin),
+            (new_token, migrator, token_swap.unlock_time),
+        );
+        token_swap
+    }
+
+    fn cancel_token_swap(e: Env) {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        backstop::execute_cancel_token_swap(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_token_swap"), admin), ());
+    }
+
+    fn token_swap(e: Env, pools: Vec<Address>) {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let old_token = storage::get_backstop_token(&e);
+        let (old_balance, new_balance, pool_tokens) = backstop::execute_token_swap(&e, &pools);
+
+        e.events().publish(
+            (Symbol::new(&e, "token_swap"), admin),
+            (
+                old_token,
+                storage::get_backstop_token(&e),
+                old_balance,
+                new_balance,
+                pool_tokens,
+            ),
+        );
+    }
+
+    fn get_token_swap(e: Env) -> Option<BackstopTokenSwap> {
+        storage::get_token_swap(&e)
+    }
 }
 
 /// Require that an incoming amount is not negative
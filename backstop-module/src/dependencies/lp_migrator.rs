@@ -0,0 +1,13 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface for a contract that converts the backstop token to a new backstop token
+#[contractclient(name = "LPMigratorClient")]
+pub trait LPMigrator {
+    /// Convert `amount` of the current backstop token, already transferred to the migrator,
+    /// into the new backstop token and send the proceeds to `to`
+    ///
+    /// ### Arguments
+    /// * `to` - The address to send the new backstop tokens to
+    /// * `amount` - The amount of the current backstop token to convert
+    fn migrate(e: Env, to: Address, amount: i128);
+}
@@ -7,3 +7,9 @@ mod pool_factory;
 pub use pool_factory::Client as PoolFactoryClient;
 #[cfg(any(test, feature = "testutils"))]
 pub use token::WASM as POOL_FACTORY_WASM;
+
+mod lp_migrator;
+pub use lp_migrator::LPMigratorClient;
+
+mod pool;
+pub use pool::PoolClient;
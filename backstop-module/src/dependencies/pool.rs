@@ -0,0 +1,11 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface for the allowlist of a permissioned pool
+#[contractclient(name = "PoolClient")]
+pub trait Pool {
+    /// Fetch if a user is allowed to interact with the pool
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn is_allowed(e: Env, user: Address) -> bool;
+}
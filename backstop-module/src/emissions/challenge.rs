@@ -0,0 +1,336 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::SCALAR_7,
+    errors::BackstopError,
+    storage::{self, RzChallenge, RzChallengeConfig},
+};
+
+use super::manager::{is_reward_zone_locked, max_reward_zone_len};
+
+/// Set the requirements a challenger pool must meet to take the reward zone slot of the weakest
+/// incumbent pool. A config with a period of zero disables challenges.
+///
+/// ### Arguments
+/// * `config` - The reward zone challenge config
+///
+/// ### Panics
+/// If the snapshot interval is zero or longer than the period
+pub fn set_rz_challenge_config(e: &Env, config: &RzChallengeConfig) {
+    if config.period == 0 {
+        storage::del_rz_challenge_config(e);
+        return;
+    }
+    if config.interval == 0 || config.interval > config.period {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    storage::set_rz_challenge_config(e, config);
+}
+
+/// Snapshot a challenger pool's backstop against the weakest pool in a full reward zone. The
+/// challenger is ahead if its backstop tokens exceed the weakest incumbent's by the configured
+/// margin. Once the challenger has been ahead at every snapshot for the configured period, it
+/// is swapped into the reward zone in place of the weakest incumbent.
+///
+/// Falling behind at a snapshot ends the challenge, and a gap between snapshots longer than the
+/// configured interval restarts it.
+///
+/// Returns the pool swapped out of the reward zone, if the challenge succeeded
+///
+/// ### Arguments
+/// * `challenger` - The address of the pool challenging for a reward zone slot
+///
+/// ### Panics
+/// If challenges are disabled, the reward zone is not full, or the challenger is already in
+/// the reward zone
+pub fn execute_rz_challenge(e: &Env, challenger: &Address) -> Option<Address> {
+    let config = match storage::get_rz_challenge_config(e) {
+        Some(config) => config,
+        None => panic_with_error!(e, BackstopError::BadRequest),
+    };
+    let mut reward_zone = storage::get_reward_zone(e);
+    if reward_zone.contains(challenger.clone()) {
+        panic_with_error!(e, BackstopError::InvalidRewardZoneEntry);
+    }
+    // pools can be added directly while there is room in the reward zone
+    if max_reward_zone_len(e) > i128(reward_zone.len()) {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    // find the weakest incumbent
+    let mut weakest_index = 0;
+    let mut weakest_tokens = i128::MAX;
+    for (index, pool) in reward_zone.iter().enumerate() {
+        let tokens = storage::get_pool_balance(e, &pool).tokens;
+        if tokens < weakest_tokens {
+            weakest_index = index as u32;
+            weakest_tokens = tokens;
+        }
+    }
+
+    let required_tokens = weakest_tokens
+        .fixed_mul_floor(SCALAR_7 + i128(config.margin), SCALAR_7)
+        .unwrap_optimized();
+    if storage::get_pool_balance(e, challenger).tokens <= required_tokens {
+        storage::del_rz_challenge(e, challenger);
+        return None;
+    }
+
+    let now = e.ledger().timestamp();
+    let start = match storage::get_rz_challenge(e, challenger) {
+        Some(challenge) if now - challenge.last_snapshot <= config.interval => challenge.start,
+        _ => now,
+    };
+    if now - start >= config.period && !is_reward_zone_locked(e) {
+        let incumbent = reward_zone.get_unchecked(weakest_index);
+        reward_zone.set(weakest_index, challenger.clone());
+        storage::set_reward_zone(e, &reward_zone);
+        storage::set_pool_eps(e, &incumbent, &0);
+        // emissions data is not updated. Emissions will be set on the next emission cycle
+        storage::del_rz_challenge(e, challenger);
+        return Some(incumbent);
+    }
+
+    storage::set_rz_challenge(
+        e,
+        challenger,
+        &RzChallenge {
+            start,
+            last_snapshot: now,
+        },
+    );
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, vec, Vec};
+
+    use crate::{backstop::PoolBalance, constants::BACKSTOP_EPOCH, testutils::set_timestamp};
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn set_tokens(e: &Env, pool: &Address, tokens: i128) {
+        storage::set_pool_balance(
+            e,
+            pool,
+            &PoolBalance {
+                shares: tokens,
+                tokens,
+                q4w: 0,
+            },
+        );
+    }
+
+    /// Setup a full reward zone where the pool at index 7 is the weakest, with 100 tokens, and
+    /// every other pool has 200 tokens. Returns the reward zone.
+    fn setup(e: &Env, backstop: &Address, weakest: &Address) -> Vec<Address> {
+        let mut reward_zone: Vec<Address> = vec![e];
+        for index in 0..10 {
+            if index == 7 {
+                reward_zone.push_back(weakest.clone());
+            } else {
+                reward_zone.push_back(Address::random(e));
+            }
+        }
+        e.as_contract(backstop, || {
+            storage::set_reward_zone(e, &reward_zone);
+            for pool in reward_zone.iter() {
+                set_tokens(e, &pool, 200);
+                storage::set_pool_eps(e, &pool, &1);
+            }
+            set_tokens(e, weakest, 100);
+            storage::set_rz_challenge_config(
+                e,
+                &RzChallengeConfig {
+                    margin: 0_1000000,
+                    period: 7 * DAY,
+                    interval: DAY,
+                },
+            );
+        });
+        reward_zone
+    }
+
+    #[test]
+    fn test_rz_challenge() {
+        let e = Env::default();
+        set_timestamp(&e, BACKSTOP_EPOCH);
+
+        let backstop = Address::random(&e);
+        let weakest = Address::random(&e);
+        let challenger = Address::random(&e);
+        let mut reward_zone = setup(&e, &backstop, &weakest);
+
+        e.as_contract(&backstop, || {
+            // swaps are allowed in the last 5 days of an emission cycle
+            storage::set_next_emission_cycle(&e, &(BACKSTOP_EPOCH + 8 * DAY));
+            set_tokens(&e, &challenger, 111);
+
+            assert!(execute_rz_challenge(&e, &challenger).is_none());
+            let challenge = storage::get_rz_challenge(&e, &challenger).unwrap();
+            assert_eq!(challenge.start, BACKSTOP_EPOCH);
+            assert_eq!(challenge.last_snapshot, BACKSTOP_EPOCH);
+        });
+
+        for day in 1..7 {
+            set_timestamp(&e, BACKSTOP_EPOCH + day * DAY);
+            e.as_contract(&backstop, || {
+                assert!(execute_rz_challenge(&e, &challenger).is_none());
+                let challenge = storage::get_rz_challenge(&e, &challenger).unwrap();
+                assert_eq!(challenge.start, BACKSTOP_EPOCH);
+                assert_eq!(challenge.last_snapshot, BACKSTOP_EPOCH + day * DAY);
+            });
+        }
+
+        set_timestamp(&e, BACKSTOP_EPOCH + 7 * DAY);
+        e.as_contract(&backstop, || {
+            assert_eq!(execute_rz_challenge(&e, &challenger), Some(weakest.clone()));
+            assert!(storage::get_rz_challenge(&e, &challenger).is_none());
+            assert_eq!(storage::get_pool_eps(&e, &weakest), 0);
+            reward_zone.set(7, challenger.clone());
+            assert_eq!(storage::get_reward_zone(&e), reward_zone);
+        });
+    }
+
+    #[test]
+    fn test_rz_challenge_falls_behind() {
+        let e = Env::default();
+        set_timestamp(&e, BACKSTOP_EPOCH);
+
+        let backstop = Address::random(&e);
+        let weakest = Address::random(&e);
+        let challenger = Address::random(&e);
+        setup(&e, &backstop, &weakest);
+
+        e.as_contract(&backstop, || {
+            set_tokens(&e, &challenger, 111);
+            assert!(execute_rz_challenge(&e, &challenger).is_none());
+            assert!(storage::get_rz_challenge(&e, &challenger).is_some());
+        });
+
+        set_timestamp(&e, BACKSTOP_EPOCH + DAY);
+        e.as_contract(&backstop, || {
+            // 110 tokens does not exceed the weakest incumbent by more than the margin
+            set_tokens(&e, &challenger, 110);
+            assert!(execute_rz_challenge(&e, &challenger).is_none());
+            assert!(storage::get_rz_challenge(&e, &challenger).is_none());
+        });
+    }
+
+    #[test]
+    fn test_rz_challenge_snapshot_gap_restarts() {
+        let e = Env::default();
+        set_timestamp(&e, BACKSTOP_EPOCH);
+
+        let backstop = Address::random(&e);
+        let weakest = Address::random(&e);
+        let challenger = Address::random(&e);
+        setup(&e, &backstop, &weakest);
+
+        e.as_contract(&backstop, || {
+            set_tokens(&e, &challenger, 111);
+            assert!(execute_rz_challenge(&e, &challenger).is_none());
+        });
+
+        set_timestamp(&e, BACKSTOP_EPOCH + 7 * DAY);
+        e.as_contract(&backstop, || {
+            assert!(execute_rz_challenge(&e, &challenger).is_none());
+            let challenge = storage::get_rz_challenge(&e, &challenger).unwrap();
+            assert_eq!(challenge.start, BACKSTOP_EPOCH + 7 * DAY);
+            assert!(!storage::get_reward_zone(&e).contains(challenger.clone()));
+        });
+    }
+
+    #[test]
+    fn test_rz_challenge_waits_for_unlocked_reward_zone() {
+        let e = Env::default();
+        set_timestamp(&e, BACKSTOP_EPOCH);
+
+        let backstop = Address::random(&e);
+        let weakest = Address::random(&e);
+        let challenger = Address::random(&e);
+        setup(&e, &backstop, &weakest);
+
+        e.as_contract(&backstop, || {
+            storage::set_next_emission_cycle(&e, &(BACKSTOP_EPOCH + 14 * DAY));
+            set_tokens(&e, &challenger, 111);
+            assert!(execute_rz_challenge(&e, &challenger).is_none());
+        });
+
+        for day in 1..=8 {
+            set_timestamp(&e, BACKSTOP_EPOCH + day * DAY);
+            e.as_contract(&backstop, || {
+                assert!(execute_rz_challenge(&e, &challenger).is_none());
+            });
+        }
+
+        set_timestamp(&e, BACKSTOP_EPOCH + 9 * DAY);
+        e.as_contract(&backstop, || {
+            assert_eq!(execute_rz_challenge(&e, &challenger), Some(weakest.clone()));
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_rz_challenge_reward_zone_not_full() {
+        let e = Env::default();
+        set_timestamp(&e, BACKSTOP_EPOCH);
+
+        let backstop = Address::random(&e);
+        let challenger = Address::random(&e);
+
+        e.as_contract(&backstop, || {
+            storage::set_reward_zone(&e, &vec![&e, Address::random(&e)]);
+            storage::set_rz_challenge_config(
+                &e,
+                &RzChallengeConfig {
+                    margin: 0_1000000,
+                    period: 7 * DAY,
+                    interval: DAY,
+                },
+            );
+            set_tokens(&e, &challenger, 111);
+            execute_rz_challenge(&e, &challenger);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(4)")]
+    fn test_rz_challenge_already_in_reward_zone() {
+        let e = Env::default();
+        set_timestamp(&e, BACKSTOP_EPOCH);
+
+        let backstop = Address::random(&e);
+        let weakest = Address::random(&e);
+        let reward_zone = setup(&e, &backstop, &weakest);
+
+        e.as_contract(&backstop, || {
+            execute_rz_challenge(&e, &reward_zone.get_unchecked(0));
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_set_rz_challenge_config_interval_too_long() {
+        let e = Env::default();
+        let backstop = Address::random(&e);
+
+        e.as_contract(&backstop, || {
+            set_rz_challenge_config(
+                &e,
+                &RzChallengeConfig {
+                    margin: 0_1000000,
+                    period: DAY,
+                    interval: 2 * DAY,
+                },
+            );
+        });
+    }
+}
@@ -3,7 +3,7 @@ use fixed_point_math::FixedPoint;
 use soroban_sdk::{unwrap::UnwrapOptimized, Address, Env};
 
 use crate::{
-    backstop::{PoolBalance, UserBalance},
+    backstop::{get_pool_weight, PoolBalance, UserBalance},
     constants::SCALAR_7,
     storage::{self, BackstopEmissionsData, UserEmissionData},
 };
@@ -39,10 +39,11 @@ pub fn update_emission_data(
     };
     let emis_data = storage::get_backstop_emis_data(e, pool_id).unwrap_optimized(); // exists if config is written to
 
+    let pool_weight = get_pool_weight(e, pool_id, pool_balance);
     if emis_data.last_time >= emis_config.expiration
         || e.ledger().timestamp() == emis_data.last_time
         || emis_config.eps == 0
-        || pool_balance.shares == 0
+        || pool_weight == 0
     {
         // emis_data already updated or expired
         return Some(emis_data);
@@ -55,7 +56,7 @@ pub fn update_emission_data(
     };
 
     let additional_idx = (i128(max_timestamp - emis_data.last_time) * i128(emis_config.eps))
-        .fixed_div_floor(pool_balance.shares, SCALAR_7)
+        .fixed_div_floor(pool_weight, SCALAR_7)
         .unwrap_optimized();
     let new_data = BackstopEmissionsData {
         index: additional_idx + emis_data.index,
@@ -73,12 +74,15 @@ fn update_user_emissions(
     user_balance: &UserBalance,
     to_claim: bool,
 ) -> i128 {
+    let user_weight = match storage::get_user_lock(e, pool, user) {
+        Some(user_lock) => user_balance.shares + user_lock.bonus,
+        None => user_balance.shares,
+    };
     if let Some(user_data) = storage::get_user_emis_data(e, pool, user) {
         if user_data.index != emis_data.index || to_claim {
             let mut accrual = user_data.accrued;
-            if user_balance.shares != 0 {
-                let to_accrue = user_balance
-                    .shares
+            if user_weight != 0 {
+                let to_accrue = user_weight
                     .fixed_mul_floor(emis_data.index - user_data.index, SCALAR_7)
                     .unwrap_optimized();
                 accrual += to_accrue;
@@ -86,13 +90,12 @@ fn update_user_emissions(
             return set_user_emissions(e, pool, user, emis_data.index, accrual, to_claim);
         }
         0
-    } else if user_balance.shares == 0 {
+    } else if user_weight == 0 {
         // first time the user registered an action with the asset since emissions were added
         return set_user_emissions(e, pool, user, emis_data.index, 0, to_claim);
     } else {
         // user had tokens before emissions began, they are due any historical emissions
-        let to_accrue = user_balance
-            .shares
+        let to_accrue = user_weight
             .fixed_mul_floor(emis_data.index, SCALAR_7)
             .unwrap_optimized();
         return set_user_emissions(e, pool, user, emis_data.index, to_accrue, to_claim);
@@ -14,7 +14,7 @@ use super::update_emission_data;
 /// Add a pool to the reward zone. If the reward zone is full, attempt to swap it with the pool to remove.
 pub fn add_to_reward_zone(e: &Env, to_add: Address, to_remove: Address) {
     let mut reward_zone = storage::get_reward_zone(e);
-    let max_rz_len = 10 + (i128(e.ledger().timestamp() - BACKSTOP_EPOCH) >> 23); // bit-shift 23 is ~97 day interval
+    let max_rz_len = max_reward_zone_len(e);
 
     // ensure an entity in the reward zone cannot be included twice
     if reward_zone.contains(to_add.clone()) {
@@ -26,10 +26,7 @@ pub fn add_to_reward_zone(e: &Env, to_add: Address, to_remove: Address) {
         // TODO: Once there is a defined limit of "backstop minimum", ensure it is reached!
         reward_zone.push_front(to_add.clone());
     } else {
-        // don't allow rz modifications within 48 hours of the start of an emission cycle
-        // if pools don't adopt their emissions within this time frame and get swapped, the tokens will be lost
-        let next_distribution = storage::get_next_emission_cycle(e);
-        if next_distribution != 0 && e.ledger().timestamp() < next_distribution - 5 * 24 * 60 * 60 {
+        if is_reward_zone_locked(e) {
             panic_with_error!(e, BackstopError::BadRequest);
         }
 
@@ -56,6 +53,19 @@ pub fn add_to_reward_zone(e: &Env, to_add: Address, to_remove: Address) {
     storage::set_reward_zone(e, &reward_zone);
 }
 
+/// Fetch the maximum number of pools the reward zone can hold
+pub(super) fn max_reward_zone_len(e: &Env) -> i128 {
+    10 + (i128(e.ledger().timestamp() - BACKSTOP_EPOCH) >> 23) // bit-shift 23 is ~97 day interval
+}
+
+/// Check if pools in the reward zone cannot currently be swapped out
+pub(super) fn is_reward_zone_locked(e: &Env) -> bool {
+    // don't allow rz modifications within 48 hours of the start of an emission cycle
+    // if pools don't adopt their emissions within this time frame and get swapped, the tokens will be lost
+    let next_distribution = storage::get_next_emission_cycle(e);
+    next_distribution != 0 && e.ledger().timestamp() < next_distribution - 5 * 24 * 60 * 60
+}
+
 /// Update the backstop for the next emission cycle from the Emitter
 #[allow(clippy::zero_prefixed_literal)]
 pub fn update_emission_cycle(e: &Env) {
@@ -1,5 +1,8 @@
+mod challenge;
+pub use challenge::{execute_rz_challenge, set_rz_challenge_config};
+
 mod claim;
-pub use claim::execute_claim;
+pub use claim::{execute_batch_claim, execute_claim};
 
 mod distributor;
 pub use distributor::{update_emission_data, update_emissions};
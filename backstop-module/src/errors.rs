@@ -13,4 +13,6 @@ pub enum BackstopError {
     AlreadyInitialized = 7,
     NotPool = 10,
     NegativeAmount = 11,
+    DepositLocked = 12,
+    DepositCapExceeded = 13,
 }
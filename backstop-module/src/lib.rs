@@ -12,9 +12,11 @@ mod errors;
 mod storage;
 mod testutils;
 
-pub use backstop::{PoolBalance, UserBalance, Q4W};
+pub use backstop::{DepositExposure, PoolBalance, UserBalance, Q4W};
 pub use contract::*;
 pub use errors::BackstopError;
 pub use storage::{
-    BackstopDataKey, BackstopEmissionConfig, BackstopEmissionsData, PoolUserKey, UserEmissionData,
+    BackstopDataKey, BackstopEmissionConfig, BackstopEmissionsData, BackstopTokenSwap, DepositCap,
+    PendingDraw, PoolLockData, PoolUserKey, RzChallenge, RzChallengeConfig, UserEmissionData,
+    UserLock, UserPoolInterest,
 };
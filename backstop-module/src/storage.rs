@@ -33,6 +33,79 @@ pub struct UserEmissionData {
     pub accrued: i128,
 }
 
+/// A queued swap of the backstop token
+#[derive(Clone)]
+#[contracttype]
+pub struct BackstopTokenSwap {
+    pub new_token: Address, // the new backstop token
+    pub migrator: Address,  // the contract converting the current backstop token to the new token
+    pub unlock_time: u64,   // the timestamp the swap can be executed after
+}
+
+/// The deposit lock bonus state of a pool's backstop
+#[derive(Clone)]
+#[contracttype]
+pub struct PoolLockData {
+    pub bonus: i128,      // the total bonus weight of active locked deposits
+    pub index: i128, // the bonus shares distributed per unit of bonus weight, expressed in 7 decimals
+    pub unassigned: i128, // the bonus shares minted but not yet settled to a locked depositor
+}
+
+/// A user's locked backstop deposit
+#[derive(Clone)]
+#[contracttype]
+pub struct UserLock {
+    pub shares: i128, // the amount of shares locked
+    pub bonus: i128,  // the bonus weight earned by the locked shares
+    pub unlock: u64,  // the timestamp the shares unlock at
+    pub index: i128,  // the pool's bonus index the user last accrued bonus shares at
+}
+
+/// A draw from a pool's backstop held in escrow until its dispute window passes
+#[derive(Clone)]
+#[contracttype]
+pub struct PendingDraw {
+    pub pool: Address,    // the pool that drew from its backstop
+    pub to: Address,      // the address receiving the backstop tokens
+    pub amount: i128,     // the amount of backstop tokens drawn
+    pub unlock_time: u64, // the timestamp the draw can be released after
+}
+
+/// The caps on the backstop deposits each address can hold for a pool
+#[derive(Clone)]
+#[contracttype]
+pub struct DepositCap {
+    pub max_tokens: i128, // the max tokens an address can hold, or 0 for no limit
+    pub max_share: u32,   // the max pool share an address can hold, or 0 for no limit
+    pub min_pool_tokens: i128, // the tokens the pool needs before the share limit applies
+}
+
+/// The requirements a challenger pool must meet to take the reward zone slot of the weakest
+/// incumbent pool
+#[derive(Clone)]
+#[contracttype]
+pub struct RzChallengeConfig {
+    pub margin: u32, // the amount the challenger must exceed the incumbent by, in 7 decimals
+    pub period: u64, // the time the challenger must stay ahead of the incumbent, in seconds
+    pub interval: u64, // the maximum time between snapshots of a challenge, in seconds
+}
+
+/// A challenger pool's snapshots of being ahead of the weakest pool in the reward zone
+#[derive(Clone)]
+#[contracttype]
+pub struct RzChallenge {
+    pub start: u64,         // the timestamp of the first snapshot of the challenge
+    pub last_snapshot: u64, // the timestamp of the most recent snapshot of the challenge
+}
+
+/// A user's share of the interest distributed in kind to a pool's backstop
+#[derive(Clone)]
+#[contracttype]
+pub struct UserPoolInterest {
+    pub index: Map<Address, i128>, // the pool's interest index of each asset the user last accrued at
+    pub accrued: Map<Address, i128>, // the unclaimed interest of each asset
+}
+
 /********** Storage Key Types **********/
 
 #[derive(Clone)]
@@ -47,6 +120,7 @@ pub struct PoolUserKey {
 pub enum BackstopDataKey {
     UserBalance(PoolUserKey),
     PoolBalance(Address),
+    PoolTotal,
     NextEmis,
     RewardZone,
     PoolEPS(Address),
@@ -57,6 +131,20 @@ pub enum BackstopDataKey {
     PoolFact,
     BLNDTkn,
     DropList,
+    Admin,
+    TknSwap,
+    PoolLock(Address),
+    UserLock(PoolUserKey),
+    Guardian,
+    Draw(u32),
+    DrawId,
+    DrawTotal,
+    DepCap(Address),
+    DepGate(Address),
+    PoolInt(Address),
+    UserPoolInt(PoolUserKey),
+    RzChlCfg,
+    RzChl(Address),
 }
 
 /****************************
@@ -68,6 +156,122 @@ pub fn bump_instance(e: &Env) {
     e.storage().instance().bump(INSTANCE_BUMP_AMOUNT);
 }
 
+/********** Admin **********/
+
+/// Fetch the current admin Address
+pub fn get_admin(e: &Env) -> Address {
+    e.storage()
+        .persistent()
+        .bump(&BackstopDataKey::Admin, SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get::<BackstopDataKey, Address>(&BackstopDataKey::Admin)
+        .unwrap_optimized()
+}
+
+/// Set a new admin
+///
+/// ### Arguments
+/// * `new_admin` - The Address for the admin
+pub fn set_admin(e: &Env, new_admin: &Address) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, Address>(&BackstopDataKey::Admin, new_admin);
+}
+
+/// Fetch the guardian Address, or None if no guardian is set
+pub fn get_guardian(e: &Env) -> Option<Address> {
+    let key = BackstopDataKey::Guardian;
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, Address>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set a new guardian
+///
+/// ### Arguments
+/// * `guardian` - The Address for the guardian
+pub fn set_guardian(e: &Env, guardian: &Address) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, Address>(&BackstopDataKey::Guardian, guardian);
+}
+
+/// Fetch the deposit caps for a pool, or None if deposits are not capped
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit caps are for
+pub fn get_deposit_cap(e: &Env, pool: &Address) -> Option<DepositCap> {
+    let key = BackstopDataKey::DepCap(pool.clone());
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, DepositCap>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set the deposit caps for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit caps are for
+/// * `cap` - The deposit caps
+pub fn set_deposit_cap(e: &Env, pool: &Address, cap: &DepositCap) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, DepositCap>(&BackstopDataKey::DepCap(pool.clone()), cap);
+}
+
+/// Remove the deposit caps for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit caps are for
+pub fn del_deposit_cap(e: &Env, pool: &Address) {
+    e.storage()
+        .persistent()
+        .remove(&BackstopDataKey::DepCap(pool.clone()));
+}
+
+/// Fetch if deposits into a pool's backstop are limited to the pool's allowlist
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn get_deposit_gated(e: &Env, pool: &Address) -> bool {
+    let key = BackstopDataKey::DepGate(pool.clone());
+    if let Some(gated) = e.storage().persistent().get::<BackstopDataKey, bool>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return gated;
+    }
+    false
+}
+
+/// Set deposits into a pool's backstop as limited to the pool's allowlist
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn set_deposit_gated(e: &Env, pool: &Address) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, bool>(&BackstopDataKey::DepGate(pool.clone()), &true);
+}
+
+/// Remove the allowlist limit on deposits into a pool's backstop
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn del_deposit_gated(e: &Env, pool: &Address) {
+    e.storage()
+        .persistent()
+        .remove(&BackstopDataKey::DepGate(pool.clone()));
+}
+
 /********** External Contracts **********/
 
 /// Fetch the pool factory id
@@ -141,6 +345,34 @@ pub fn set_backstop_token(e: &Env, backstop_token_id: &Address) {
         .set::<BackstopDataKey, Address>(&BackstopDataKey::BckstpTkn, backstop_token_id);
 }
 
+/// Fetch the queued backstop token swap, or None
+pub fn get_token_swap(e: &Env) -> Option<BackstopTokenSwap> {
+    let key = BackstopDataKey::TknSwap;
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, BackstopTokenSwap>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set the queued backstop token swap
+///
+/// ### Arguments
+/// * `token_swap` - The queued backstop token swap
+pub fn set_token_swap(e: &Env, token_swap: &BackstopTokenSwap) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, BackstopTokenSwap>(&BackstopDataKey::TknSwap, token_swap);
+}
+
+/// Delete the queued backstop token swap
+pub fn del_token_swap(e: &Env) {
+    e.storage().persistent().remove(&BackstopDataKey::TknSwap);
+}
+
 /********** User Shares **********/
 
 /// Fetch the balance's for a given user
@@ -198,16 +430,198 @@ pub fn get_pool_balance(e: &Env, pool: &Address) -> PoolBalance {
         })
 }
 
-/// Set the balances for a pool
+/// Set the balances for a pool. The total tokens held by all pools is updated to match.
 ///
 /// ### Arguments
 /// * `pool` - The pool the deposit is associated with
 /// * `balance` - The pool balances
 pub fn set_pool_balance(e: &Env, pool: &Address, balance: &PoolBalance) {
     let key = BackstopDataKey::PoolBalance(pool.clone());
+    let prev_tokens = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, PoolBalance>(&key)
+        .map_or(0, |prev_balance| prev_balance.tokens);
     e.storage()
         .persistent()
         .set::<BackstopDataKey, PoolBalance>(&key, balance);
+    e.storage().persistent().set::<BackstopDataKey, i128>(
+        &BackstopDataKey::PoolTotal,
+        &(get_total_pool_tokens(e) + balance.tokens - prev_tokens),
+    );
+}
+
+/// Fetch the total backstop tokens held by all pools
+pub fn get_total_pool_tokens(e: &Env) -> i128 {
+    let key = BackstopDataKey::PoolTotal;
+    let result = e.storage().persistent().get::<BackstopDataKey, i128>(&key);
+    match result {
+        Some(total) => {
+            e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+            total
+        }
+        None => 0,
+    }
+}
+
+/********** Deposit Locks **********/
+
+/// Fetch the deposit lock bonus state for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the locks are associated with
+pub fn get_pool_lock(e: &Env, pool: &Address) -> PoolLockData {
+    let key = BackstopDataKey::PoolLock(pool.clone());
+    if let Some(result) = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, PoolLockData>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        result
+    } else {
+        PoolLockData {
+            bonus: 0,
+            index: 0,
+            unassigned: 0,
+        }
+    }
+}
+
+/// Set the deposit lock bonus state for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the locks are associated with
+/// * `pool_lock` - The deposit lock bonus state
+pub fn set_pool_lock(e: &Env, pool: &Address, pool_lock: &PoolLockData) {
+    let key = BackstopDataKey::PoolLock(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, PoolLockData>(&key, pool_lock);
+}
+
+/// Fetch a user's locked deposit for a pool, or None
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit is associated with
+/// * `user` - The owner of the deposit
+pub fn get_user_lock(e: &Env, pool: &Address, user: &Address) -> Option<UserLock> {
+    let key = BackstopDataKey::UserLock(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, UserLock>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set a user's locked deposit for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit is associated with
+/// * `user` - The owner of the deposit
+/// * `lock` - The locked deposit
+pub fn set_user_lock(e: &Env, pool: &Address, user: &Address, lock: &UserLock) {
+    let key = BackstopDataKey::UserLock(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, UserLock>(&key, lock);
+}
+
+/// Delete a user's locked deposit for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit is associated with
+/// * `user` - The owner of the deposit
+pub fn del_user_lock(e: &Env, pool: &Address, user: &Address) {
+    let key = BackstopDataKey::UserLock(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    e.storage().persistent().remove(&key);
+}
+
+/********** Pending Draws **********/
+
+/// Fetch a pending draw, or None
+///
+/// ### Arguments
+/// * `id` - The id of the draw
+pub fn get_pending_draw(e: &Env, id: u32) -> Option<PendingDraw> {
+    let key = BackstopDataKey::Draw(id);
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, PendingDraw>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set a pending draw
+///
+/// ### Arguments
+/// * `id` - The id of the draw
+/// * `draw` - The pending draw
+pub fn set_pending_draw(e: &Env, id: u32, draw: &PendingDraw) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, PendingDraw>(&BackstopDataKey::Draw(id), draw);
+}
+
+/// Delete a pending draw
+///
+/// ### Arguments
+/// * `id` - The id of the draw
+pub fn del_pending_draw(e: &Env, id: u32) {
+    e.storage().persistent().remove(&BackstopDataKey::Draw(id));
+}
+
+/// Fetch the next draw id and increment the counter
+pub fn next_draw_id(e: &Env) -> u32 {
+    let key = BackstopDataKey::DrawId;
+    let id = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, u32>(&key)
+        .unwrap_or(0);
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, u32>(&key, &(id + 1));
+    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    id
+}
+
+/// Fetch the total backstop tokens held in escrow for pending draws
+pub fn get_pending_draw_total(e: &Env) -> i128 {
+    let key = BackstopDataKey::DrawTotal;
+    let result = e.storage().persistent().get::<BackstopDataKey, i128>(&key);
+    match result {
+        Some(total) => {
+            e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+            total
+        }
+        None => 0,
+    }
+}
+
+/// Set the total backstop tokens held in escrow for pending draws
+///
+/// ### Arguments
+/// * `total` - The total backstop tokens held in escrow
+pub fn set_pending_draw_total(e: &Env, total: &i128) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, i128>(&BackstopDataKey::DrawTotal, total);
 }
 
 /********** Distribution / Reward Zone **********/
@@ -256,6 +670,71 @@ pub fn set_reward_zone(e: &Env, reward_zone: &Vec<Address>) {
         .set::<BackstopDataKey, Vec<Address>>(&BackstopDataKey::RewardZone, reward_zone);
 }
 
+/// Fetch the reward zone challenge config, or None if challenges are disabled
+pub fn get_rz_challenge_config(e: &Env) -> Option<RzChallengeConfig> {
+    let key = BackstopDataKey::RzChlCfg;
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, RzChallengeConfig>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set the reward zone challenge config
+///
+/// ### Arguments
+/// * `config` - The reward zone challenge config
+pub fn set_rz_challenge_config(e: &Env, config: &RzChallengeConfig) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, RzChallengeConfig>(&BackstopDataKey::RzChlCfg, config);
+}
+
+/// Delete the reward zone challenge config
+pub fn del_rz_challenge_config(e: &Env) {
+    e.storage().persistent().remove(&BackstopDataKey::RzChlCfg);
+}
+
+/// Fetch a pool's reward zone challenge, or None
+///
+/// ### Arguments
+/// * `pool` - The challenging pool
+pub fn get_rz_challenge(e: &Env, pool: &Address) -> Option<RzChallenge> {
+    let key = BackstopDataKey::RzChl(pool.clone());
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, RzChallenge>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set a pool's reward zone challenge
+///
+/// ### Arguments
+/// * `pool` - The challenging pool
+/// * `challenge` - The challenge
+pub fn set_rz_challenge(e: &Env, pool: &Address, challenge: &RzChallenge) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, RzChallenge>(&BackstopDataKey::RzChl(pool.clone()), challenge);
+}
+
+/// Delete a pool's reward zone challenge
+///
+/// ### Arguments
+/// * `pool` - The challenging pool
+pub fn del_rz_challenge(e: &Env, pool: &Address) {
+    e.storage()
+        .persistent()
+        .remove(&BackstopDataKey::RzChl(pool.clone()));
+}
+
 /// Get current emissions EPS the backstop is distributing to the pool
 ///
 /// ### Arguments
@@ -381,6 +860,82 @@ pub fn set_user_emis_data(
         .set::<BackstopDataKey, UserEmissionData>(&key, user_emis_data);
 }
 
+/********** Backstop Depositor Interest **********/
+
+/// Fetch the interest index of each asset distributed in kind to a pool's backstop
+///
+/// ### Arguments
+/// * `pool` - The pool the interest is distributed for
+pub fn get_pool_interest(e: &Env, pool: &Address) -> Map<Address, i128> {
+    let key = BackstopDataKey::PoolInt(pool.clone());
+    match e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, Map<Address, i128>>(&key)
+    {
+        Some(indexes) => {
+            e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+            indexes
+        }
+        None => Map::new(e),
+    }
+}
+
+/// Set the interest index of each asset distributed in kind to a pool's backstop
+///
+/// ### Arguments
+/// * `pool` - The pool the interest is distributed for
+/// * `indexes` - The interest index of each asset
+pub fn set_pool_interest(e: &Env, pool: &Address, indexes: &Map<Address, i128>) {
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, Map<Address, i128>>(
+            &BackstopDataKey::PoolInt(pool.clone()),
+            indexes,
+        );
+}
+
+/// Fetch a user's share of the interest distributed in kind to a pool's backstop
+///
+/// ### Arguments
+/// * `pool` - The pool the interest is distributed for
+/// * `user` - The user's address
+pub fn get_user_pool_interest(e: &Env, pool: &Address, user: &Address) -> Option<UserPoolInterest> {
+    let key = BackstopDataKey::UserPoolInt(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    let result = e
+        .storage()
+        .persistent()
+        .get::<BackstopDataKey, UserPoolInterest>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set a user's share of the interest distributed in kind to a pool's backstop
+///
+/// ### Arguments
+/// * `pool` - The pool the interest is distributed for
+/// * `user` - The user's address
+/// * `interest` - The user's share of the interest
+pub fn set_user_pool_interest(
+    e: &Env,
+    pool: &Address,
+    user: &Address,
+    interest: &UserPoolInterest,
+) {
+    let key = BackstopDataKey::UserPoolInt(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, UserPoolInterest>(&key, interest);
+}
+
 /********** Drop Emissions **********/
 
 /// Get the current pool addresses that are in the drop list and the amount of the initial distribution they receive
@@ -6,7 +6,11 @@ use crate::{
     storage::{self},
 };
 
-use soroban_sdk::{testutils::Address as _, unwrap::UnwrapOptimized, Address, Env, IntoVal, Vec};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, LedgerInfo},
+    unwrap::UnwrapOptimized,
+    Address, Env, IntoVal, Vec,
+};
 
 use mock_pool_factory::{MockPoolFactory, MockPoolFactoryClient};
 
@@ -58,6 +62,22 @@ pub(crate) fn create_mock_pool_factory<'a>(
     )
 }
 
+/********** Ledger Helpers **********/
+
+/// Set the ledger timestamp, keeping the sequence number and entry expirations constant
+pub(crate) fn set_timestamp(e: &Env, timestamp: u64) {
+    e.ledger().set(LedgerInfo {
+        timestamp,
+        protocol_version: 1,
+        sequence_number: 100,
+        network_id: Default::default(),
+        base_reserve: 10,
+        min_temp_entry_expiration: 10,
+        min_persistent_entry_expiration: 10,
+        max_entry_expiration: 2000000,
+    });
+}
+
 /********** Comparison Helpers **********/
 
 pub(crate) fn assert_eq_vec_q4w(actual: &Vec<Q4W>, expected: &Vec<Q4W>) {
@@ -132,6 +132,7 @@ mod tests {
         let backstop_token_client = TokenClient::new(&e, &backstop_token);
 
         backstop_client.initialize(
+            &bombadil,
             &backstop_token,
             &Address::random(&e),
             &Address::random(&e),
@@ -179,6 +180,7 @@ mod tests {
         let backstop_token_client = TokenClient::new(&e, &backstop_token);
 
         backstop_client.initialize(
+            &bombadil,
             &backstop_token,
             &Address::random(&e),
             &Address::random(&e),
@@ -227,6 +229,7 @@ mod tests {
         ];
 
         backstop_client.initialize(
+            &bombadil,
             &backstop_token,
             &Address::random(&e),
             &Address::random(&e),
@@ -280,6 +283,7 @@ mod tests {
         ];
 
         backstop_client.initialize(
+            &bombadil,
             &backstop_token,
             &Address::random(&e),
             &Address::random(&e),
@@ -326,6 +330,7 @@ mod tests {
         ];
 
         backstop_client.initialize(
+            &bombadil,
             &backstop_token,
             &Address::random(&e),
             &Address::random(&e),
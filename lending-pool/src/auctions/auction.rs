@@ -1,18 +1,23 @@
 use crate::{
     constants::SCALAR_7,
     errors::PoolError,
-    pool::{Pool, PositionData, User},
+    pool::{
+        get_trusted_credit, record_liquidation, require_allowed, FactorBasis, Pool, PositionData,
+        User,
+    },
     storage,
+    validator::require_not_denied,
 };
 use cast::i128;
 use fixed_point_math::FixedPoint;
 use soroban_sdk::{
-    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map,
+    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map, Vec,
 };
 
 use super::{
     backstop_interest_auction::{create_interest_auction_data, fill_interest_auction},
     bad_debt_auction::{create_bad_debt_auction_data, fill_bad_debt_auction},
+    stats::{record_auction_created, record_auction_fill, record_auction_history},
     user_liquidation_auction::{create_user_liq_auction_data, fill_user_liq_auction},
 };
 
@@ -35,12 +40,92 @@ impl AuctionType {
     }
 }
 
+#[derive(Clone, PartialEq)]
+#[repr(u32)]
+pub enum AuctionMode {
+    Sequence = 0,
+    Timestamp = 1,
+}
+
+impl AuctionMode {
+    pub fn from_u32(e: &Env, value: u32) -> Self {
+        match value {
+            0 => AuctionMode::Sequence,
+            1 => AuctionMode::Timestamp,
+            _ => panic_with_error!(e, PoolError::BadRequest),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+#[repr(u32)]
+pub enum AuctionValuation {
+    Raw = 0,
+    Effective = 1,
+}
+
+impl AuctionValuation {
+    pub fn from_u32(e: &Env, value: u32) -> Self {
+        match value {
+            0 => AuctionValuation::Raw,
+            1 => AuctionValuation::Effective,
+            _ => panic_with_error!(e, PoolError::BadRequest),
+        }
+    }
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct AuctionData {
     pub bid: Map<Address, i128>,
     pub lot: Map<Address, i128>,
     pub block: u32,
+    pub timestamp: u64,
+    pub mode: u32,
+    pub valuation: u32,
+}
+
+/// Set the auction modifier mode for the pool. The mode is stored on each auction when it is
+/// created, such that changing the pool's mode does not reprice auctions already in progress.
+///
+/// ### Arguments
+/// * `mode` - The auction modifier mode (0 = ledger sequence, 1 = ledger timestamp)
+///
+/// ### Panics
+/// If the mode is not a valid AuctionMode
+pub fn set_auction_mode(e: &Env, mode: u32) {
+    AuctionMode::from_u32(e, mode);
+    storage::set_auction_mode(e, &mode);
+}
+
+/// Set the valuation used to size liquidation auctions for the pool
+///
+/// ### Arguments
+/// * `valuation` - The auction valuation (0 = raw oracle value, 1 = factor weighted value)
+///
+/// ### Panics
+/// If the valuation is not a valid AuctionValuation
+pub fn set_auction_valuation(e: &Env, valuation: u32) {
+    AuctionValuation::from_u32(e, valuation);
+    storage::set_auction_valuation(e, &valuation);
+}
+
+/// Set the order collateral assets are seized in by user liquidations. Collateral not in the
+/// priority is seized after the prioritized collateral, in reserve order. An empty priority
+/// seizes all collateral pro-rata.
+///
+/// ### Arguments
+/// * `priority` - The collateral assets, in the order they are seized
+///
+/// ### Panics
+/// If an asset is not a reserve or is included more than once
+pub fn set_liquidation_priority(e: &Env, priority: Vec<Address>) {
+    for (i, asset) in priority.iter().enumerate() {
+        if !storage::has_res(e, &asset) || priority.first_index_of(&asset) != Some(i as u32) {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+    }
+    storage::set_liq_priority(e, &priority);
 }
 
 /// Create an auction. Stores the resulting auction to the ledger to begin on the next block
@@ -86,17 +171,21 @@ pub fn create_liquidation(e: &Env, user: &Address, percent_liquidated: u64) -> A
         user,
         &auction_data,
     );
+    record_liquidation(e, user);
+    record_auction_created(e);
 
     auction_data
 }
 
-/// Delete a liquidation auction if the user being liquidated is no longer eligible for liquidation.
+/// Delete a liquidation auction if the user being liquidated has recovered to the pool's
+/// liquidation deletion health factor. Any uncollateralized credit is counted as collateral,
+/// as it is when the auction is created.
 ///
 /// ### Arguments
-/// * `auction_type` - The type of auction being created
+/// * `user` - The user being liquidated
 ///
 /// ### Panics
-/// If no auction exists for the user or if the user is still eligible for liquidation.
+/// If no auction exists for the user or if the user is below the deletion health factor.
 pub fn delete_liquidation(e: &Env, user: &Address) {
     if !storage::has_auction(e, &(AuctionType::UserLiquidation as u32), user) {
         panic_with_error!(e, PoolError::BadRequest);
@@ -104,11 +193,53 @@ pub fn delete_liquidation(e: &Env, user: &Address) {
 
     let mut pool = Pool::load(e);
     let positions = storage::get_user_positions(e, user);
-    let position_data = PositionData::calculate_from_positions(e, &mut pool, &positions);
-    position_data.require_healthy(e);
+    let position_data =
+        PositionData::calculate_for_user(e, &mut pool, user, &positions, FactorBasis::Liquidation);
+    position_data.require_min_hf(
+        e,
+        get_trusted_credit(e, user),
+        storage::get_pool_config(e).liq_hf_high,
+    );
     storage::del_auction(e, &(AuctionType::UserLiquidation as u32), user);
 }
 
+/// Load an auction from the ledger
+///
+/// When multiple fills of the same auction land in one ledger, they are applied in the order
+/// they are executed. A fill that completes the auction deletes it, such that any later fill
+/// fails with `AuctionNotFound` instead of a generic error. Partial fills reduce the auction,
+/// and later fills apply their percentage to the remaining auction.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user involved in the auction
+///
+/// ### Panics
+/// If the auction does not exist
+pub fn load_auction(e: &Env, auction_type: u32, user: &Address) -> AuctionData {
+    if !storage::has_auction(e, &auction_type, user) {
+        panic_with_error!(e, PoolError::AuctionNotFound);
+    }
+    storage::get_auction(e, &auction_type, user)
+}
+
+/// Quote the portion of an auction that would be filled at the current block
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction to quote
+/// * `user` - The user involved in the auction
+/// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
+///
+/// ### Panics
+/// If the auction does not exist or the percentage is invalid
+pub fn quote_fill(e: &Env, auction_type: u32, user: &Address, percent_filled: u64) -> AuctionData {
+    let auction_data = load_auction(e, auction_type, user);
+    if percent_filled > 100 || percent_filled == 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    scale_auction(e, &auction_data, percent_filled).0
+}
+
 /// Fills the auction from the invoker. The filler is expected to maintain allowances to both
 /// the pool and the backstop module.
 ///
@@ -122,8 +253,10 @@ pub fn delete_liquidation(e: &Env, user: &Address) {
 /// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
 ///
 /// ### Panics
-/// If the auction does not exist, or if the pool is unable to fulfill either side
-/// of the auction quote
+/// If the auction does not exist, if the pool is unable to fulfill either side
+/// of the auction quote, if the pool is permissioned and the filler is not on the allowlist, if
+/// the filler is denied by the pool's denylist, or if the auction is a user liquidation and the
+/// pool only fills liquidations cooperatively
 pub fn fill(
     e: &Env,
     pool: &mut Pool,
@@ -132,23 +265,76 @@ pub fn fill(
     filler_state: &mut User,
     percent_filled: u64,
 ) {
-    let auction_data = storage::get_auction(e, &auction_type, user);
+    // permissioned pools only allow allowlisted fillers to acquire auction lots
+    require_allowed(e, &filler_state.address);
+    require_not_denied(e, &filler_state.address);
+    let auction_data = load_auction(e, auction_type, user);
     if percent_filled > 100 || percent_filled == 0 {
         panic_with_error!(e, PoolError::BadRequest);
     }
+    // liquidations are only filled by settling fill bids while cooperative fills are enabled
+    if auction_type == AuctionType::UserLiquidation as u32
+        && storage::get_coop_fill_window(e).is_some()
+    {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    fill_fraction(
+        e,
+        pool,
+        auction_type,
+        user,
+        &auction_data,
+        filler_state,
+        i128(percent_filled) * 1_00000,
+    );
+}
 
-    let (to_fill_auction, remaining_auction) = scale_auction(e, &auction_data, percent_filled);
+/// Fill a fraction of an auction for a filler, without validating the filler or the fraction
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `auction_type` - The type of auction to fill
+/// * `user` - The user involved in the auction
+/// * `auction_data` - The auction being filled
+/// * `filler_state` - The Address filling the auction
+/// * `fraction_filled` - The fraction of the auction being filled, expressed in 7 decimals
+pub(super) fn fill_fraction(
+    e: &Env,
+    pool: &mut Pool,
+    auction_type: u32,
+    user: &Address,
+    auction_data: &AuctionData,
+    filler_state: &mut User,
+    fraction_filled: i128,
+) {
+    let (to_fill_auction, remaining_auction) =
+        scale_auction_by_fraction(e, auction_data, fraction_filled);
     match AuctionType::from_u32(auction_type) {
         AuctionType::UserLiquidation => {
-            fill_user_liq_auction(e, pool, &to_fill_auction, user, filler_state)
-        }
-        AuctionType::BadDebtAuction => {
-            fill_bad_debt_auction(e, pool, &to_fill_auction, filler_state)
+            fill_user_liq_auction(e, pool, &to_fill_auction, user, filler_state);
+            record_auction_fill(e, pool, &to_fill_auction, remaining_auction.is_none());
         }
+        AuctionType::BadDebtAuction => fill_bad_debt_auction(
+            e,
+            pool,
+            &to_fill_auction,
+            filler_state,
+            remaining_auction.is_none(),
+        ),
         AuctionType::InterestAuction => {
             fill_interest_auction(e, pool, &to_fill_auction, &filler_state.address)
         }
     };
+    record_auction_history(
+        e,
+        pool,
+        auction_type,
+        user,
+        &filler_state.address,
+        &to_fill_auction,
+        remaining_auction.is_none(),
+    );
 
     if let Some(auction_to_store) = remaining_auction {
         storage::set_auction(e, &auction_type, user, &auction_to_store);
@@ -157,8 +343,8 @@ pub fn fill(
     }
 }
 
-/// Scale the auction based on the percent being filled and the amount of blocks that have passed
-/// since the auction began.
+/// Scale the auction based on the percent being filled and the amount of blocks or time that
+/// has passed since the auction began.
 ///
 /// ### Arguments
 /// * `auction_data` - The auction data to scale
@@ -167,49 +353,49 @@ pub fn fill(
 /// Returns the (Scaled Auction, Remaining Auction) such that:
 /// - Scaled Auction is the auction data scaled
 /// - Remaining Auction is the leftover auction data that will be stored in the ledger, or deleted if None
-#[allow(clippy::zero_prefixed_literal)]
 fn scale_auction(
     e: &Env,
     auction_data: &AuctionData,
     percent_filled: u64,
+) -> (AuctionData, Option<AuctionData>) {
+    // scale to decimal form in 7 decimals from percentage
+    scale_auction_by_fraction(e, auction_data, i128(percent_filled) * 1_00000)
+}
+
+/// Scale the auction based on the fraction being filled, expressed in 7 decimals, and the
+/// amount of blocks or time that has passed since the auction began.
+///
+/// Returns the (Scaled Auction, Remaining Auction), as `scale_auction`
+pub(super) fn scale_auction_by_fraction(
+    e: &Env,
+    auction_data: &AuctionData,
+    fraction_filled: i128,
 ) -> (AuctionData, Option<AuctionData>) {
     let mut to_fill_auction = AuctionData {
         bid: map![e],
         lot: map![e],
         block: auction_data.block,
+        timestamp: auction_data.timestamp,
+        mode: auction_data.mode,
+        valuation: auction_data.valuation,
     };
     let mut remaining_auction = AuctionData {
         bid: map![e],
         lot: map![e],
         block: auction_data.block,
+        timestamp: auction_data.timestamp,
+        mode: auction_data.mode,
+        valuation: auction_data.valuation,
     };
 
-    // determine block based auction modifiers
-    let bid_modifier: i128;
-    let lot_modifier: i128;
-    let per_block_scalar: i128 = 0_0050000; // modifier moves 0.5% every block
-    let block_dif = i128(e.ledger().sequence() - auction_data.block);
-    if block_dif > 200 {
-        // lot 100%, bid scaling down from 100% to 0%
-        lot_modifier = SCALAR_7;
-        if block_dif < 400 {
-            bid_modifier = SCALAR_7 - (block_dif - 200) * per_block_scalar;
-        } else {
-            bid_modifier = 0;
-        }
-    } else {
-        // lot scaling from 0% to 100%, bid 100%
-        lot_modifier = block_dif * per_block_scalar;
-        bid_modifier = SCALAR_7;
-    }
+    let (bid_modifier, lot_modifier) = get_fill_modifiers(e, auction_data);
 
     // scale the auction
-    let percent_filled_i128 = i128(percent_filled) * 1_00000; // scale to decimal form in 7 decimals from percentage
     for (asset, amount) in auction_data.bid.iter() {
         // apply percent scalar and store remainder to base auction
         // round up to avoid rounding exploits
         let to_fill_base = amount
-            .fixed_mul_ceil(percent_filled_i128, SCALAR_7)
+            .fixed_mul_ceil(fraction_filled, SCALAR_7)
             .unwrap_optimized();
         let remaining_base = amount - to_fill_base;
         if remaining_base > 0 {
@@ -227,7 +413,7 @@ fn scale_auction(
         // apply percent scalar and store remainder to base auction
         // round down to avoid rounding exploits
         let to_fill_base = amount
-            .fixed_mul_floor(percent_filled_i128, SCALAR_7)
+            .fixed_mul_floor(fraction_filled, SCALAR_7)
             .unwrap_optimized();
         let remaining_base = amount - to_fill_base;
         if remaining_base > 0 {
@@ -249,15 +435,74 @@ fn scale_auction(
     }
 }
 
+/// Calculate the bid and lot modifiers for an auction based on the auction's mode
+///
+/// In sequence mode, the modifiers move 0.5% every block, starting from the block after the
+/// auction was created. In timestamp mode, the modifiers move 0.1% every second, such that
+/// auction durations are consistent in real time regardless of ledger close times, and at least
+/// one second must pass after the auction was created before it can be filled.
+///
+/// Returns (bid_modifier, lot_modifier) expressed in 7 decimals
+///
+/// ### Arguments
+/// * `auction_data` - The auction data
+///
+/// ### Panics
+/// If the auction has not started yet
+#[allow(clippy::zero_prefixed_literal)]
+fn get_fill_modifiers(e: &Env, auction_data: &AuctionData) -> (i128, i128) {
+    let step_scalar: i128;
+    let step_dif: i128;
+    match AuctionMode::from_u32(e, auction_data.mode) {
+        AuctionMode::Sequence => {
+            if e.ledger().sequence() < auction_data.block {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            step_scalar = 0_0050000; // modifier moves 0.5% every block
+            step_dif = i128(e.ledger().sequence() - auction_data.block);
+        }
+        AuctionMode::Timestamp => {
+            if e.ledger().timestamp() <= auction_data.timestamp {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            step_scalar = 0_0010000; // modifier moves 0.1% every second
+            step_dif = i128(e.ledger().timestamp() - auction_data.timestamp);
+        }
+    }
+    let steps_per_phase = SCALAR_7 / step_scalar;
+
+    if step_dif > steps_per_phase {
+        // lot 100%, bid scaling down from 100% to 0%
+        if step_dif < 2 * steps_per_phase {
+            (
+                SCALAR_7 - (step_dif - steps_per_phase) * step_scalar,
+                SCALAR_7,
+            )
+        } else {
+            (0, SCALAR_7)
+        }
+    } else {
+        // lot scaling from 0% to 100%, bid 100%
+        (SCALAR_7, step_dif * step_scalar)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::{pool::Positions, storage::PoolConfig, testutils};
+    use crate::{
+        auctions::get_recent_auctions,
+        constants::AUCTION_HISTORY_LENGTH,
+        pool::Positions,
+        storage::{AuctionRecord, PoolConfig, TrustedBorrower},
+        testutils,
+    };
 
     use super::*;
     use soroban_sdk::{
         map,
         testutils::{Address as _, Ledger, LedgerInfo},
+        vec,
     };
 
     #[test]
@@ -355,6 +600,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
@@ -444,10 +691,12 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
-            let pool = Pool::load(&e);
+            let mut pool = Pool::load(&e);
             let mut reserve_0 = pool.load_reserve(&e, &underlying_0);
             reserve_0.backstop_credit += 100_0000000;
             reserve_0.store(&e);
@@ -544,6 +793,8 @@ mod tests {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_user_positions(&e, &samwise, &positions);
@@ -552,6 +803,7 @@ mod tests {
             e.budget().reset_unlimited();
             create_liquidation(&e, &samwise, liq_pct);
             assert!(storage::has_auction(&e, &0, &samwise));
+            assert_eq!(storage::get_auction_stats(&e).created, 1);
         });
     }
     #[test]
@@ -614,11 +866,16 @@ mod tests {
             bid: map![&e],
             lot: map![&e],
             block: 100,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_id, || {
             storage::set_pool_config(&e, &pool_config);
@@ -642,14 +899,13 @@ mod tests {
     #[test]
     #[should_panic]
     //#[should_panic(expected = "ContractError(10)")]
-    fn test_delete_user_liquidation_invalid_hf() {
+    fn test_delete_user_liquidation_below_hf_high() {
         let e = Env::default();
         e.mock_all_auths();
         let pool_id = Address::random(&e);
 
         let bombadil = Address::random(&e);
         let samwise = Address::random(&e);
-
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
         let (reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(&e);
         testutils::create_reserve(
@@ -676,7 +932,7 @@ mod tests {
         oracle_client.set_price(&underlying_1, &5_0000000);
 
         // setup user (collateralize reserve 0 and borrow reserve 1)
-        let collateral_amount = 15_0000000;
+        let collateral_amount = 17_8000000;
         let liability_amount = 20_0000000;
         let positions: Positions = Positions {
             collateral: map![&e, (reserve_config_0.index, collateral_amount)],
@@ -687,22 +943,20 @@ mod tests {
             bid: map![&e],
             lot: map![&e],
             block: 100,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0500000,
         };
         e.as_contract(&pool_id, || {
             storage::set_pool_config(&e, &pool_config);
             storage::set_user_positions(&e, &samwise, &positions);
-
-            storage::set_auction(
-                &e,
-                &(AuctionType::UserLiquidation as u32),
-                &samwise,
-                &auction_data,
-            );
             storage::set_auction(
                 &e,
                 &(AuctionType::UserLiquidation as u32),
@@ -710,47 +964,24 @@ mod tests {
                 &auction_data,
             );
 
+            // the user is healthy, but has not recovered to the deletion health factor
             delete_liquidation(&e, &samwise);
-            assert!(storage::has_auction(
-                &e,
-                &(AuctionType::UserLiquidation as u32),
-                &samwise
-            ));
         });
     }
 
     #[test]
-    fn test_fill() {
+    fn test_delete_user_liquidation_with_trusted_credit() {
         let e = Env::default();
-
         e.mock_all_auths();
-        e.ledger().set(LedgerInfo {
-            timestamp: 12345,
-            protocol_version: 1,
-            sequence_number: 175,
-            network_id: Default::default(),
-            base_reserve: 10,
-            min_temp_entry_expiration: 10,
-            min_persistent_entry_expiration: 10,
-            max_entry_expiration: 2000000,
-        });
+        let pool_id = Address::random(&e);
 
         let bombadil = Address::random(&e);
         let samwise = Address::random(&e);
-        let frodo = Address::random(&e);
-
-        let pool_address = Address::random(&e);
-
-        let (oracle_address, _) = testutils::create_mock_oracle(&e);
-
-        // creating reserves for a pool exhausts the budget
-        e.budget().reset_unlimited();
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(&e);
-        reserve_config_0.index = 0;
+        let (reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(&e);
         testutils::create_reserve(
             &e,
-            &pool_address,
+            &pool_id,
             &underlying_0,
             &reserve_config_0,
             &reserve_data_0,
@@ -761,79 +992,428 @@ mod tests {
         reserve_config_1.index = 1;
         testutils::create_reserve(
             &e,
-            &pool_address,
+            &pool_id,
             &underlying_1,
             &reserve_config_1,
             &reserve_data_1,
         );
 
-        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta(&e);
-        reserve_config_2.index = 2;
-        testutils::create_reserve(
-            &e,
-            &pool_address,
-            &underlying_2,
-            &reserve_config_2,
-            &reserve_data_2,
-        );
-        e.budget().reset_unlimited();
+        let (oracle_id, oracle_client) = testutils::create_mock_oracle(&e);
+        oracle_client.set_price(&underlying_0, &10_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
 
+        // setup user (collateralize reserve 0 and borrow reserve 1)
+        let collateral_amount = 17_8000000;
+        let liability_amount = 20_0000000;
+        let positions: Positions = Positions {
+            collateral: map![&e, (reserve_config_0.index, collateral_amount)],
+            liabilities: map![&e, (reserve_config_1.index, liability_amount)],
+            supply: map![&e],
+        };
         let auction_data = AuctionData {
-            bid: map![&e, (underlying_2.clone(), 1_2375000)],
-            lot: map![
-                &e,
-                (underlying_0.clone(), 30_5595329),
-                (underlying_1.clone(), 1_5395739)
-            ],
-            block: 176,
+            bid: map![&e],
+            lot: map![&e],
+            block: 100,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
-            oracle: oracle_address,
+            oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0500000,
         };
-        let positions: Positions = Positions {
-            collateral: map![
-                &e,
-                (reserve_config_0.index, 90_9100000),
-                (reserve_config_1.index, 04_5800000),
-            ],
-            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
-            supply: map![&e],
-        };
-        e.as_contract(&pool_address, || {
-            storage::set_user_positions(&e, &samwise, &positions);
+        e.as_contract(&pool_id, || {
             storage::set_pool_config(&e, &pool_config);
-            storage::set_auction(&e, &0, &samwise, &auction_data);
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_trusted_borrower(
+                &e,
+                &samwise,
+                &TrustedBorrower {
+                    cap: 10_0000000,
+                    premium: 0,
+                    last_time: 0,
+                },
+            );
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
 
-            e.ledger().set(LedgerInfo {
-                timestamp: 12345 + 200 * 5,
-                protocol_version: 1,
-                sequence_number: 176 + 200,
-                network_id: Default::default(),
-                base_reserve: 10,
-                min_temp_entry_expiration: 10,
-                min_persistent_entry_expiration: 10,
-                max_entry_expiration: 2000000,
-            });
-            e.budget().reset_unlimited();
-            let mut pool = Pool::load(&e);
-            let mut frodo_state = User::load(&e, &frodo);
-            fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 100);
-            let has_auction = storage::has_auction(&e, &0, &samwise);
-            assert_eq!(has_auction, false);
+            // the credit line brings the user above the deletion health factor
+            delete_liquidation(&e, &samwise);
+            assert!(!storage::has_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise
+            ));
         });
     }
 
     #[test]
-    fn test_partial_fill() {
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(10)")]
+    fn test_delete_user_liquidation_invalid_hf() {
         let e = Env::default();
-
         e.mock_all_auths();
-        e.ledger().set(LedgerInfo {
-            timestamp: 12345,
-            protocol_version: 1,
+        let pool_id = Address::random(&e);
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(
+            &e,
+            &pool_id,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta(&e);
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_id,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (oracle_id, oracle_client) = testutils::create_mock_oracle(&e);
+        oracle_client.set_price(&underlying_0, &10_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
+
+        // setup user (collateralize reserve 0 and borrow reserve 1)
+        let collateral_amount = 15_0000000;
+        let liability_amount = 20_0000000;
+        let positions: Positions = Positions {
+            collateral: map![&e, (reserve_config_0.index, collateral_amount)],
+            liabilities: map![&e, (reserve_config_1.index, liability_amount)],
+            supply: map![&e],
+        };
+        let auction_data = AuctionData {
+            bid: map![&e],
+            lot: map![&e],
+            block: 100,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
+
+            delete_liquidation(&e, &samwise);
+            assert!(storage::has_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise
+            ));
+        });
+    }
+
+    #[test]
+    fn test_fill() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let pool_address = Address::random(&e);
+
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(&e);
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta(&e);
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta(&e);
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+        e.budget().reset_unlimited();
+        oracle_client.set_price(&underlying_2, &5_0000000);
+
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_2.clone(), 1_2375000)],
+            lot: map![
+                &e,
+                (underlying_0.clone(), 30_5595329),
+                (underlying_1.clone(), 1_5395739)
+            ],
+            block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 12345 + 200 * 5,
+                protocol_version: 1,
+                sequence_number: 176 + 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_expiration: 10,
+                min_persistent_entry_expiration: 10,
+                max_entry_expiration: 2000000,
+            });
+            e.budget().reset_unlimited();
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 100);
+            let has_auction = storage::has_auction(&e, &0, &samwise);
+            assert_eq!(has_auction, false);
+
+            let stats = storage::get_auction_stats(&e);
+            assert_eq!(stats.fills, 1);
+            assert_eq!(stats.completed, 1);
+            assert_eq!(stats.fill_blocks, 200);
+            assert_eq!(stats.avg_fill_blocks, 200);
+            assert!(stats.liquidated_value >= 6_1875000 && stats.liquidated_value < 6_2000000);
+
+            let history = get_recent_auctions(&e, 0, 5);
+            assert_eq!(history.len(), 1);
+            let record = history.get_unchecked(0);
+            assert_eq!(record.user, samwise);
+            assert_eq!(record.filler, frodo);
+            assert_eq!(record.block, 176);
+            assert_eq!(record.fill_block, 376);
+            assert_eq!(record.value, stats.liquidated_value);
+            assert!(record.completed);
+        });
+    }
+
+    /// Create a liquidation auction for samwise that is open to fills at the current ledger,
+    /// such that multiple fills can be submitted against it within one ledger
+    fn setup_contended_auction(e: &Env) -> (Address, Address) {
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(e);
+        let samwise = Address::random(e);
+        let pool_address = Address::random(e);
+
+        let (oracle_address, _) = testutils::create_mock_oracle(e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(e);
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta(e);
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+        e.budget().reset_unlimited();
+
+        let auction_data = AuctionData {
+            bid: map![e, (underlying_1.clone(), 1_2375000)],
+            lot: map![e, (underlying_0.clone(), 30_5595329)],
+            block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions: Positions = Positions {
+            collateral: map![e, (reserve_config_0.index, 90_9100000)],
+            liabilities: map![e, (reserve_config_1.index, 02_7500000)],
+            supply: map![e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(e, &samwise, &positions);
+            storage::set_pool_config(e, &pool_config);
+            storage::set_auction(e, &0, &samwise, &auction_data);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 200 * 5,
+            protocol_version: 1,
+            sequence_number: 176 + 200,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        (pool_address, samwise)
+    }
+
+    /// Submit a fill as its own transaction, loading and storing the pool state
+    fn fill_as_transaction(
+        e: &Env,
+        pool_address: &Address,
+        user: &Address,
+        filler: &Address,
+        percent_filled: u64,
+    ) {
+        e.as_contract(pool_address, || {
+            e.budget().reset_unlimited();
+            let mut pool = Pool::load(e);
+            let mut filler_state = User::load(e, filler);
+            fill(e, &mut pool, 0, user, &mut filler_state, percent_filled);
+            filler_state.store(e);
+            pool.store_cached_reserves(e);
+        });
+    }
+
+    #[test]
+    fn test_fill_contention_partial_fills_same_ledger() {
+        let e = Env::default();
+        let (pool_address, samwise) = setup_contended_auction(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+
+        // the second fill applies its percentage to the auction remaining after the first
+        fill_as_transaction(&e, &pool_address, &samwise, &frodo, 50);
+        fill_as_transaction(&e, &pool_address, &samwise, &merry, 100);
+
+        e.as_contract(&pool_address, || {
+            assert!(!storage::has_auction(&e, &0, &samwise));
+            let frodo_positions = storage::get_user_positions(&e, &frodo);
+            let merry_positions = storage::get_user_positions(&e, &merry);
+            assert_eq!(frodo_positions.liabilities.get_unchecked(1), 0_6187500);
+            assert_eq!(merry_positions.liabilities.get_unchecked(1), 0_6187500);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(109)")]
+    fn test_fill_contention_second_fill_fails() {
+        let e = Env::default();
+        let (pool_address, samwise) = setup_contended_auction(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+
+        // the first fill completes the auction, so the second cannot find it
+        fill_as_transaction(&e, &pool_address, &samwise, &frodo, 100);
+        fill_as_transaction(&e, &pool_address, &samwise, &merry, 100);
+    }
+
+    #[test]
+    fn test_partial_fill() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
             sequence_number: 175,
             network_id: Default::default(),
             base_reserve: 10,
@@ -894,11 +1474,16 @@ mod tests {
                 (underlying_1.clone(), 1_5395739)
             ],
             block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -937,6 +1522,9 @@ mod tests {
                     (underlying_1.clone(), 1_1546805)
                 ],
                 block: 176,
+                timestamp: 12345,
+                mode: 0,
+                valuation: 0,
             };
             let new_auction = storage::get_auction(&e, &0, &samwise);
             assert_eq!(new_auction.bid, expected_new_auction_data.bid);
@@ -1014,11 +1602,16 @@ mod tests {
                 (underlying_1.clone(), 1_000_0000)
             ],
             block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -1057,6 +1650,9 @@ mod tests {
                     (underlying_1.clone(), 750_0000)
                 ],
                 block: 176,
+                timestamp: 12345,
+                mode: 0,
+                valuation: 0,
             };
 
             // Partial fill 2 - 66% @ 100% mods
@@ -1087,6 +1683,9 @@ mod tests {
                     (underlying_1.clone(), 0_2475000)
                 ],
                 block: 176,
+                timestamp: 12345,
+                mode: 0,
+                valuation: 0,
             };
             let new_auction = storage::get_auction(&e, &0, &samwise);
             assert_eq!(new_auction.bid, expected_new_auction_data.bid);
@@ -1135,9 +1734,137 @@ mod tests {
     }
 
     #[test]
-    // #[should_panic(expected = "ContractError(2)")]
+    // #[should_panic(expected = "ContractError(2)")]
+    #[should_panic]
+    fn test_fill_fails_pct_too_large() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let pool_address = Address::random(&e);
+
+        let (oracle_address, _) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(&e);
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta(&e);
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta(&e);
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_2.clone(), 1_2375000)],
+            lot: map![
+                &e,
+                (underlying_0.clone(), 30_5595329),
+                (underlying_1.clone(), 1_5395739)
+            ],
+            block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 12345 + 200 * 5,
+                protocol_version: 1,
+                sequence_number: 176 + 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_expiration: 10,
+                min_persistent_entry_expiration: 10,
+                max_entry_expiration: 2000000,
+            });
+            e.budget().reset_unlimited();
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 101);
+
+            let expected_new_auction_data = AuctionData {
+                bid: map![&e, (underlying_2.clone(), 9281250)],
+                lot: map![
+                    &e,
+                    (underlying_0.clone(), 22_9196497),
+                    (underlying_1.clone(), 1_1546805)
+                ],
+                block: 176,
+                timestamp: 12345,
+                mode: 0,
+                valuation: 0,
+            };
+            let new_auction = storage::get_auction(&e, &0, &samwise);
+            assert_eq!(new_auction.bid, expected_new_auction_data.bid);
+            assert_eq!(new_auction.lot, expected_new_auction_data.lot);
+            assert_eq!(new_auction.block, expected_new_auction_data.block);
+        });
+    }
+
+    #[test]
+    // #[should_panic(expected = "ContractError(1)")]
     #[should_panic]
-    fn test_fill_fails_pct_too_large() {
+    fn test_fill_fails_filler_not_allowed() {
         let e = Env::default();
 
         e.mock_all_auths();
@@ -1203,11 +1930,16 @@ mod tests {
                 (underlying_1.clone(), 1_5395739)
             ],
             block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -1222,6 +1954,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
             storage::set_auction(&e, &0, &samwise, &auction_data);
+            storage::set_permissioned(&e, &true);
 
             e.ledger().set(LedgerInfo {
                 timestamp: 12345 + 200 * 5,
@@ -1236,21 +1969,7 @@ mod tests {
             e.budget().reset_unlimited();
             let mut pool = Pool::load(&e);
             let mut frodo_state = User::load(&e, &frodo);
-            fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 101);
-
-            let expected_new_auction_data = AuctionData {
-                bid: map![&e, (underlying_2.clone(), 9281250)],
-                lot: map![
-                    &e,
-                    (underlying_0.clone(), 22_9196497),
-                    (underlying_1.clone(), 1_1546805)
-                ],
-                block: 176,
-            };
-            let new_auction = storage::get_auction(&e, &0, &samwise);
-            assert_eq!(new_auction.bid, expected_new_auction_data.bid);
-            assert_eq!(new_auction.lot, expected_new_auction_data.lot);
-            assert_eq!(new_auction.block, expected_new_auction_data.block);
+            fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 25);
         });
     }
 
@@ -1325,11 +2044,16 @@ mod tests {
                 (underlying_1.clone(), 1_5395739)
             ],
             block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -1368,6 +2092,9 @@ mod tests {
                     (underlying_1.clone(), 1_1546805)
                 ],
                 block: 176,
+                timestamp: 12345,
+                mode: 0,
+                valuation: 0,
             };
             let new_auction = storage::get_auction(&e, &0, &samwise);
             assert_eq!(new_auction.bid, expected_new_auction_data.bid);
@@ -1387,6 +2114,9 @@ mod tests {
             bid: map![&e, (underlying_0.clone(), 100_0000000)],
             lot: map![&e, (underlying_1.clone(), 100_0000000)],
             block: 1000,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
 
         // 0 blocks
@@ -1506,6 +2236,9 @@ mod tests {
             bid: map![&e, (underlying_0.clone(), 25_0000005)],
             lot: map![&e, (underlying_1.clone(), 25_0000005)],
             block: 1000,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
 
         // 0 blocks
@@ -1624,4 +2357,315 @@ mod tests {
             12_5000003
         );
     }
+
+    #[test]
+    fn test_scale_auction_timestamp_mode() {
+        let e = Env::default();
+        let underlying_0 = Address::random(&e);
+        let underlying_1 = Address::random(&e);
+
+        let base_auction_data = AuctionData {
+            bid: map![&e, (underlying_0.clone(), 100_0000000)],
+            lot: map![&e, (underlying_1.clone(), 100_0000000)],
+            block: 1000,
+            timestamp: 12345,
+            mode: 1,
+            valuation: 0,
+        };
+
+        // 500 seconds, but 1000 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 500,
+            protocol_version: 1,
+            sequence_number: 2000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            100_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            50_0000000
+        );
+        assert!(remaining_auction.is_none());
+
+        // 1500 seconds
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 1500,
+            protocol_version: 1,
+            sequence_number: 2000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            50_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            100_0000000
+        );
+        assert!(remaining_auction.is_none());
+
+        // 2000 seconds
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 2000,
+            protocol_version: 1,
+            sequence_number: 2000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        assert_eq!(scaled_auction.bid.len(), 0);
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            100_0000000
+        );
+        assert!(remaining_auction.is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_scale_auction_timestamp_mode_same_second() {
+        let e = Env::default();
+        let underlying_0 = Address::random(&e);
+        let underlying_1 = Address::random(&e);
+
+        let base_auction_data = AuctionData {
+            bid: map![&e, (underlying_0.clone(), 100_0000000)],
+            lot: map![&e, (underlying_1.clone(), 100_0000000)],
+            block: 1000,
+            timestamp: 12345,
+            mode: 1,
+            valuation: 0,
+        };
+
+        // the auction is filled in a later block, but in the same second it was created
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 1001,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        scale_auction(&e, &base_auction_data, 100);
+    }
+
+    #[test]
+    fn test_scale_auction_ignores_pool_mode_change() {
+        let e = Env::default();
+        let pool_address = Address::random(&e);
+        let underlying_0 = Address::random(&e);
+        let underlying_1 = Address::random(&e);
+
+        let base_auction_data = AuctionData {
+            bid: map![&e, (underlying_0.clone(), 100_0000000)],
+            lot: map![&e, (underlying_1.clone(), 100_0000000)],
+            block: 1000,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+
+        // 100 blocks, but 2000 seconds
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 2000,
+            protocol_version: 1,
+            sequence_number: 1100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        e.as_contract(&pool_address, || {
+            // the pool switched to timestamp mode after the auction was created
+            set_auction_mode(&e, 1);
+
+            let (scaled_auction, _) = scale_auction(&e, &base_auction_data, 100);
+            assert_eq!(
+                scaled_auction.bid.get_unchecked(underlying_0.clone()),
+                100_0000000
+            );
+            assert_eq!(
+                scaled_auction.lot.get_unchecked(underlying_1.clone()),
+                50_0000000
+            );
+        });
+    }
+
+    #[test]
+    fn test_get_recent_auctions() {
+        let e = Env::default();
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            assert_eq!(get_recent_auctions(&e, 1, 5).len(), 0);
+
+            let mut history = vec![&e];
+            for i in 0..AUCTION_HISTORY_LENGTH {
+                history.push_back(AuctionRecord {
+                    user: samwise.clone(),
+                    filler: frodo.clone(),
+                    block: 100 + i,
+                    fill_block: 150 + i,
+                    value: 1_0000000,
+                    completed: true,
+                });
+            }
+            storage::set_auction_history(&e, 1, &history);
+
+            let recent = get_recent_auctions(&e, 1, 3);
+            assert_eq!(recent.len(), 3);
+            assert_eq!(
+                recent.get_unchecked(0).block,
+                100 + AUCTION_HISTORY_LENGTH - 3
+            );
+            assert_eq!(
+                recent.get_unchecked(2).block,
+                100 + AUCTION_HISTORY_LENGTH - 1
+            );
+
+            let all = get_recent_auctions(&e, 1, 100);
+            assert_eq!(all.len(), AUCTION_HISTORY_LENGTH);
+            assert_eq!(get_recent_auctions(&e, 0, 100).len(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(104)")]
+    fn test_get_recent_auctions_invalid_type() {
+        let e = Env::default();
+        let pool_address = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            get_recent_auctions(&e, 3, 5);
+        });
+    }
+
+    #[test]
+    fn test_set_auction_mode() {
+        let e = Env::default();
+        let pool_id = Address::random(&e);
+
+        e.as_contract(&pool_id, || {
+            assert_eq!(storage::get_auction_mode(&e), 0);
+
+            set_auction_mode(&e, 1);
+            assert_eq!(storage::get_auction_mode(&e), 1);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_auction_mode_invalid() {
+        let e = Env::default();
+        let pool_id = Address::random(&e);
+
+        e.as_contract(&pool_id, || {
+            set_auction_mode(&e, 2);
+        });
+    }
+
+    #[test]
+    fn test_set_auction_valuation() {
+        let e = Env::default();
+        let pool_id = Address::random(&e);
+
+        e.as_contract(&pool_id, || {
+            assert_eq!(storage::get_auction_valuation(&e), 0);
+
+            set_auction_valuation(&e, 1);
+            assert_eq!(storage::get_auction_valuation(&e), 1);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_auction_valuation_invalid() {
+        let e = Env::default();
+        let pool_id = Address::random(&e);
+
+        e.as_contract(&pool_id, || {
+            set_auction_valuation(&e, 2);
+        });
+    }
+
+    #[test]
+    fn test_set_liquidation_priority() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        let pool_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool_id, &underlying_0, &reserve_config, &reserve_data);
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool_id, &underlying_1, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool_id, || {
+            assert!(storage::get_liq_priority(&e).is_empty());
+
+            let priority = vec![&e, underlying_1.clone(), underlying_0.clone()];
+            set_liquidation_priority(&e, priority.clone());
+            assert_eq!(storage::get_liq_priority(&e), priority);
+
+            set_liquidation_priority(&e, vec![&e]);
+            assert!(storage::get_liq_priority(&e).is_empty());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_liquidation_priority_duplicate() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        let pool_id = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool_id, &underlying_0, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool_id, || {
+            set_liquidation_priority(&e, vec![&e, underlying_0.clone(), underlying_0.clone()]);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_liquidation_priority_not_reserve() {
+        let e = Env::default();
+        let pool_id = Address::random(&e);
+
+        e.as_contract(&pool_id, || {
+            set_liquidation_priority(&e, vec![&e, Address::random(&e)]);
+        });
+    }
 }
@@ -3,48 +3,40 @@ use crate::{
 };
 use cast::i128;
 use fixed_point_math::FixedPoint;
-use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map};
 
-use super::{AuctionData, AuctionType};
+use super::{escrow::quarantine_lot, AuctionData, AuctionType, AuctionValuation};
 
 pub fn create_interest_auction_data(e: &Env, backstop: &Address) -> AuctionData {
     if storage::has_auction(e, &(AuctionType::InterestAuction as u32), backstop) {
         panic_with_error!(e, PoolError::AuctionInProgress);
     }
+    // swept interest is distributed to the backstop's depositors in kind instead
+    if storage::get_direct_interest(e) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
 
     let mut pool = Pool::load(e);
-    let mut auction_data = AuctionData {
-        lot: map![e],
-        bid: map![e],
-        block: e.ledger().sequence() + 1,
-    };
-
-    let reserve_list = storage::get_res_list(e);
-    let mut interest_value = 0; // expressed in the oracle's decimals
-    for i in 0..reserve_list.len() {
-        let res_asset_address = reserve_list.get_unchecked(i);
-        // don't store updated reserve data back to ledger. This will occur on the the auction's fill.
-        let reserve = pool.load_reserve(e, &res_asset_address);
-        if reserve.backstop_credit > 0 {
-            let asset_to_base = pool.load_price(e, &res_asset_address);
-            interest_value += i128(asset_to_base)
-                .fixed_mul_floor(reserve.backstop_credit, reserve.scalar)
-                .unwrap_optimized();
-            auction_data
-                .lot
-                .set(res_asset_address, reserve.backstop_credit);
-        }
-    }
+    let (lot, interest_value) = calc_interest_lot(e, &mut pool);
 
     // Ensure that the interest value is at least 200 USDC
-    if interest_value <= (200 * 10i128.pow(pool.load_price_decimals(e))) {
+    if interest_value <= min_interest_value(e, &mut pool) {
         panic_with_error!(e, PoolError::InterestTooSmall);
     }
 
-    if auction_data.lot.is_empty() || interest_value == 0 {
+    if lot.is_empty() || interest_value == 0 {
         panic_with_error!(e, PoolError::BadRequest);
     }
 
+    let mut auction_data = AuctionData {
+        lot,
+        bid: map![e],
+        block: e.ledger().sequence() + 1,
+        timestamp: e.ledger().timestamp(),
+        mode: storage::get_auction_mode(e),
+        valuation: AuctionValuation::Raw as u32,
+    };
+
     let usdc_token = storage::get_usdc_token(e);
     let usdc_to_base = pool.load_price(e, &usdc_token);
     let bid_amount = interest_value
@@ -58,6 +50,37 @@ pub fn create_interest_auction_data(e: &Env, backstop: &Address) -> AuctionData
     auction_data
 }
 
+/// Calculate the lot of backstop credit available to an interest auction
+///
+/// Returns (the lot, the value of the lot expressed in the oracle's decimals)
+pub fn calc_interest_lot(e: &Env, pool: &mut Pool) -> (Map<Address, i128>, i128) {
+    let mut lot: Map<Address, i128> = map![e];
+    let reserve_list = storage::get_res_list(e);
+    let mut interest_value = 0; // expressed in the oracle's decimals
+    for i in 0..reserve_list.len() {
+        let res_asset_address = reserve_list.get_unchecked(i);
+        // don't store updated reserve data back to ledger. This will occur on the the auction's fill.
+        let reserve = pool.load_reserve(e, &res_asset_address);
+        // credit held in escrow for previous fillers is not available
+        let available_credit =
+            reserve.backstop_credit - storage::get_escrow_total(e, &res_asset_address);
+        if available_credit > 0 {
+            let asset_to_base = pool.load_price(e, &res_asset_address);
+            interest_value += i128(asset_to_base)
+                .fixed_mul_floor(available_credit, reserve.scalar)
+                .unwrap_optimized();
+            lot.set(res_asset_address, available_credit);
+        }
+    }
+    (lot, interest_value)
+}
+
+/// Fetch the value the backstop credit must exceed to create an interest auction, expressed
+/// in the oracle's decimals
+pub fn min_interest_value(e: &Env, pool: &mut Pool) -> i128 {
+    200 * 10i128.pow(pool.load_price_decimals(e))
+}
+
 pub fn fill_interest_auction(
     e: &Env,
     pool: &mut Pool,
@@ -69,16 +92,23 @@ pub fn fill_interest_auction(
     // let backstop_client = BackstopClient::new(&e, &backstop_address);
     // backstop_client.donate(&filler, &e.current_contract_id(), &bid_amount_modified);
 
-    // lot contains underlying tokens, but the backstop credit must be updated on the reserve
+    // lot contains underlying tokens, but the backstop credit must be updated on the reserve.
+    // If a transfer fails (e.g. the asset is frozen for the filler), the lot is quarantined
+    // in escrow for the filler to claim later instead of reverting the fill.
     for (res_asset_address, lot_amount) in auction_data.lot.iter() {
         let mut reserve = pool.load_reserve(e, &res_asset_address);
-        reserve.backstop_credit -= lot_amount;
-        reserve.store(e);
-        TokenClient::new(e, &res_asset_address).transfer(
+        let lot_amount_out = pool.to_underlying_down(e, &res_asset_address, lot_amount);
+        let transfer_result = TokenClient::new(e, &res_asset_address).try_transfer(
             &e.current_contract_address(),
             filler,
-            &lot_amount,
+            &lot_amount_out,
         );
+        if let Ok(Ok(())) = transfer_result {
+            reserve.backstop_credit -= lot_amount;
+        } else {
+            quarantine_lot(e, filler, &res_asset_address, lot_amount);
+        }
+        reserve.store(e);
     }
 }
 
@@ -121,6 +151,9 @@ mod tests {
             bid: map![&e],
             lot: map![&e],
             block: 50,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         e.as_contract(&pool_address, || {
             storage::set_auction(
@@ -215,10 +248,12 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
-            let pool = Pool::load(&e);
+            let mut pool = Pool::load(&e);
             let mut reserve_0 = pool.load_reserve(&e, &underlying_0);
             reserve_0.backstop_credit += 10_0000000;
             reserve_0.store(&e);
@@ -315,10 +350,12 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
-            let pool = Pool::load(&e);
+            let mut pool = Pool::load(&e);
             let mut reserve_0 = pool.load_reserve(&e, &underlying_0);
             reserve_0.backstop_credit += 100_0000000;
             reserve_0.store(&e);
@@ -415,11 +452,13 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
 
-            let pool = Pool::load(&e);
+            let mut pool = Pool::load(&e);
             let mut reserve_0 = pool.load_reserve(&e, &underlying_0);
             reserve_0.backstop_credit += 100_0000000;
             reserve_0.store(&e);
@@ -515,6 +554,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let mut auction_data = AuctionData {
             bid: map![&e, (usdc_id.clone(), 952_0000000)],
@@ -524,6 +565,9 @@ mod tests {
                 (underlying_1.clone(), 25_0000000)
             ],
             block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         usdc_client.mint(&samwise, &95_2000000);
         //samwise increase allowance for pool
@@ -568,4 +612,112 @@ mod tests {
             assert_eq!(reserve_0.backstop_credit, 0);
         });
     }
+
+    #[test]
+    fn test_fill_interest_auction_quarantines_failed_transfer() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 301,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let pool_address = Address::random(&e);
+        let (oracle_id, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta(&e);
+        reserve_data_0.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+        underlying_0_client.mint(&pool_address, &1_000_0000000);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta(&e);
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+        underlying_1_client.mint(&pool_address, &1_000_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let auction_data = AuctionData {
+            bid: map![&e],
+            lot: map![
+                &e,
+                (underlying_0.clone(), 100_0000000),
+                (underlying_1.clone(), 25_0000000)
+            ],
+            block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        // samwise can no longer receive underlying_1
+        underlying_1_client.set_authorized(&samwise, &false);
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+            let mut reserve_0 = pool.load_reserve(&e, &underlying_0);
+            reserve_0.backstop_credit += 100_0000000;
+            reserve_0.store(&e);
+            // underlying_1 accrues more credit than the lot before the fill
+            let mut reserve_1 = pool.load_reserve(&e, &underlying_1);
+            reserve_1.backstop_credit += 30_0000000;
+            reserve_1.store(&e);
+
+            fill_interest_auction(&e, &mut pool, &auction_data, &samwise);
+            assert_eq!(underlying_0_client.balance(&samwise), 100_0000000);
+            assert_eq!(underlying_1_client.balance(&samwise), 0);
+            assert_eq!(pool.load_reserve(&e, &underlying_0).backstop_credit, 0);
+            assert_eq!(
+                pool.load_reserve(&e, &underlying_1).backstop_credit,
+                30_0000000
+            );
+
+            // the failed lot is held in escrow for samwise
+            let escrow = storage::get_user_escrow(&e, &samwise);
+            assert_eq!(escrow.len(), 1);
+            assert_eq!(escrow.get_unchecked(underlying_1.clone()), 25_0000000);
+            assert_eq!(storage::get_escrow_total(&e, &underlying_1), 25_0000000);
+            assert_eq!(storage::get_escrow_total(&e, &underlying_0), 0);
+
+            // only the credit not held in escrow is available to the next auction
+            let (lot, interest_value) = calc_interest_lot(&e, &mut pool);
+            assert_eq!(lot.len(), 1);
+            assert_eq!(lot.get_unchecked(underlying_1.clone()), 5_0000000);
+            assert_eq!(interest_value, 5_0000000);
+        });
+    }
 }
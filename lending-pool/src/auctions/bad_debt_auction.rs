@@ -1,15 +1,15 @@
 use crate::{
     constants::SCALAR_7,
-    dependencies::BackstopClient,
+    dependencies::VersionedBackstopClient,
     errors::PoolError,
     pool::{burn_backstop_bad_debt, Pool, User},
-    storage,
+    storage::{self, BadDebtFill},
 };
 use cast::i128;
 use fixed_point_math::FixedPoint;
 use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env};
 
-use super::{AuctionData, AuctionType};
+use super::{AuctionData, AuctionType, AuctionValuation};
 
 pub fn create_bad_debt_auction_data(e: &Env, backstop: &Address) -> AuctionData {
     if storage::has_auction(e, &(AuctionType::BadDebtAuction as u32), backstop) {
@@ -20,6 +20,9 @@ pub fn create_bad_debt_auction_data(e: &Env, backstop: &Address) -> AuctionData
         bid: map![e],
         lot: map![e],
         block: e.ledger().sequence() + 1,
+        timestamp: e.ledger().timestamp(),
+        mode: storage::get_auction_mode(e),
+        valuation: AuctionValuation::Raw as u32,
     };
 
     let mut pool = Pool::load(e);
@@ -42,7 +45,7 @@ pub fn create_bad_debt_auction_data(e: &Env, backstop: &Address) -> AuctionData
         panic_with_error!(e, PoolError::BadRequest);
     }
 
-    let backstop_client = BackstopClient::new(e, backstop);
+    let backstop_client = VersionedBackstopClient::new(e, backstop);
     let backstop_token = backstop_client.backstop_token();
     // TODO: This won't have an oracle entry. Once an LP implementation exists, unwrap base from LP
     let backstop_token_to_base = pool.load_price(e, &backstop_token);
@@ -59,12 +62,24 @@ pub fn create_bad_debt_auction_data(e: &Env, backstop: &Address) -> AuctionData
     auction_data
 }
 
+/// Fill a bad debt auction. The filler assumes the bid liabilities from the backstop and receives
+/// the lot of backstop tokens. Partial fills are expected to be pre-scaled, such that the filler
+/// assumes a portion of the debt and receives a proportional amount of backstop tokens.
+///
+/// The lot is held in escrow by the backstop during its dispute window. The fill is recorded
+/// against the draw, such that a reversal of the draw also returns the liabilities to the backstop.
+///
+/// ### Arguments
+/// * `auction_data` - The (scaled) auction being filled
+/// * `filler_state` - The user filling the auction
+/// * `is_final_fill` - If this fill completes the auction
 #[allow(clippy::inconsistent_digit_grouping)]
 pub fn fill_bad_debt_auction(
     e: &Env,
     pool: &mut Pool,
     auction_data: &AuctionData,
     filler_state: &mut User,
+    is_final_fill: bool,
 ) {
     let backstop_address = storage::get_backstop(e);
     let mut backstop_state = User::load(e, &backstop_address);
@@ -73,18 +88,31 @@ pub fn fill_bad_debt_auction(
     backstop_state.rm_positions(e, pool, map![e], auction_data.bid.clone());
     filler_state.add_positions(e, pool, map![e], auction_data.bid.clone());
 
-    let backstop_client = BackstopClient::new(e, &backstop_address);
+    let backstop_client = VersionedBackstopClient::new(e, &backstop_address);
     let backstop_token_id = backstop_client.backstop_token();
-    let lot_amount = auction_data.lot.get(backstop_token_id).unwrap_optimized();
-    let backstop_client = BackstopClient::new(e, &backstop_address);
-    backstop_client.draw(
-        &e.current_contract_address(),
-        &lot_amount,
-        &filler_state.address,
-    );
-
-    // If the backstop still has liabilities and less than 10% of the backstop threshold burn bad debt
-    if !backstop_state.positions.liabilities.is_empty() 
+    // the lot can be scaled to zero early in the auction or for small partial fills
+    let lot_amount = auction_data.lot.get(backstop_token_id).unwrap_or(0);
+    if lot_amount > 0 {
+        if let Some(draw_id) = backstop_client.draw(
+            &e.current_contract_address(),
+            &lot_amount,
+            &filler_state.address,
+        ) {
+            storage::set_bad_debt_fill(
+                e,
+                draw_id,
+                &BadDebtFill {
+                    filler: filler_state.address.clone(),
+                    bid: auction_data.bid.clone(),
+                },
+            );
+        }
+    }
+
+    // If the auction is complete, and the backstop still has liabilities and less than 10% of the
+    // backstop threshold burn bad debt. Partial fills leave the remaining liabilities up for auction.
+    if is_final_fill
+        && !backstop_state.positions.liabilities.is_empty()
             //TODO: this token check needs to check k-value of pool balance LP tokens
         && backstop_client.pool_balance(&e.current_contract_address()).tokens < 20_000_000_0000
     {
@@ -93,6 +121,47 @@ pub fn fill_bad_debt_auction(
     backstop_state.store(e);
 }
 
+/// Reverse a bad debt auction fill paid by a pending backstop draw. The backstop returns the lot
+/// to the pool's backstop, and the liabilities the filler assumed are returned to the backstop.
+/// If the filler has since repaid part of the liabilities, only the remainder is returned.
+///
+/// Returns the reversed fill
+///
+/// ### Arguments
+/// * `draw_id` - The id of the backstop draw that paid the fill
+///
+/// ### Panics
+/// If no fill was paid by the draw, or the backstop is unable to reverse the draw
+pub fn execute_reverse_bad_debt_fill(e: &Env, draw_id: u32) -> BadDebtFill {
+    let fill = match storage::get_bad_debt_fill(e, draw_id) {
+        Some(fill) => fill,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    storage::del_bad_debt_fill(e, draw_id);
+
+    let backstop_address = storage::get_backstop(e);
+    VersionedBackstopClient::new(e, &backstop_address).reverse_draw(&draw_id);
+
+    let mut pool = Pool::load(e);
+    let mut filler_state = User::load(e, &fill.filler);
+    let mut backstop_state = User::load(e, &backstop_address);
+    let mut liabilities = map![e];
+    for (asset, amount) in fill.bid.iter() {
+        let reserve = pool.load_reserve(e, &asset);
+        let remaining = filler_state.get_liabilities(reserve.index).min(amount);
+        if remaining > 0 {
+            liabilities.set(asset, remaining);
+        }
+    }
+    filler_state.rm_positions(e, &mut pool, map![e], liabilities.clone());
+    backstop_state.add_positions(e, &mut pool, map![e], liabilities);
+
+    pool.store_cached_reserves(e);
+    filler_state.store(e);
+    backstop_state.store(e);
+    fill
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -136,6 +205,9 @@ mod tests {
             bid: map![&e],
             lot: map![&e],
             block: 50,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         e.as_contract(&pool_address, || {
             storage::set_auction(
@@ -244,6 +316,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
@@ -355,6 +429,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
@@ -468,6 +544,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
@@ -558,11 +636,16 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let mut auction_data = AuctionData {
             bid: map![&e, (underlying_0, 10_0000000), (underlying_1, 2_5000000)],
             lot: map![&e, (backstop_token_id.clone(), 47_6000000)],
             block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let positions: Positions = Positions {
             collateral: map![&e],
@@ -594,9 +677,18 @@ mod tests {
             );
             let mut pool = Pool::load(&e);
             let mut samwise_state = User::load(&e, &samwise);
-            fill_bad_debt_auction(&e, &mut pool, &mut auction_data, &mut samwise_state);
-            assert_eq!(backstop_token_client.balance(&backstop_address), 47_6000000);
-            assert_eq!(backstop_token_client.balance(&samwise), 47_6000000);
+            fill_bad_debt_auction(&e, &mut pool, &mut auction_data, &mut samwise_state, true);
+            // the drawn backstop tokens are held in escrow during the dispute window
+            assert_eq!(backstop_token_client.balance(&backstop_address), 95_2000000);
+            assert_eq!(backstop_token_client.balance(&samwise), 0);
+            assert_eq!(
+                backstop_client.get_pending_draw(&0).unwrap().amount,
+                47_6000000
+            );
+            // the fill is recorded against the draw, such that both legs can be reversed
+            let fill = storage::get_bad_debt_fill(&e, 0).unwrap_optimized();
+            assert_eq!(fill.filler, samwise);
+            assert_eq!(fill.bid, auction_data.bid);
             let samwise_positions = samwise_state.positions;
             assert_eq!(
                 samwise_positions
@@ -617,6 +709,169 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_reverse_bad_debt_fill() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 51,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let pool_address = Address::random(&e);
+        let (backstop_token_id, backstop_token_client) =
+            testutils::create_token_contract(&e, &bombadil);
+        let (backstop_address, backstop_client) = testutils::create_backstop(&e);
+        testutils::setup_backstop(
+            &e,
+            &pool_address,
+            &backstop_address,
+            &backstop_token_id,
+            &Address::random(&e),
+        );
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta(&e);
+        reserve_data_0.d_rate = 1_100_000_000;
+        reserve_data_0.last_time = 12345;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta(&e);
+        reserve_data_1.d_rate = 1_200_000_000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = testutils::default_reserve_meta(&e);
+        reserve_data_2.b_rate = 1_100_000_000;
+        reserve_data_2.last_time = 12345;
+        reserve_config_2.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let mut auction_data = AuctionData {
+            bid: map![&e, (underlying_0, 10_0000000), (underlying_1, 2_5000000)],
+            lot: map![&e, (backstop_token_id.clone(), 47_6000000)],
+            block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        let positions: Positions = Positions {
+            collateral: map![&e],
+            liabilities: map![
+                &e,
+                (reserve_config_0.index, 10_0000000),
+                (reserve_config_1.index, 2_5000000)
+            ],
+            supply: map![&e],
+        };
+        backstop_token_client.mint(&samwise, &95_2000000);
+        backstop_token_client.approve(&samwise, &backstop_address, &i128::MAX, &1000000);
+        backstop_client.deposit(&samwise, &pool_address, &95_2000000);
+        e.as_contract(&pool_address, || {
+            storage::set_auction(
+                &e,
+                &(AuctionType::BadDebtAuction as u32),
+                &backstop_address,
+                &auction_data,
+            );
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &backstop_address, &positions);
+
+            backstop_token_client.approve(
+                &pool_address,
+                &backstop_address,
+                &(u64::MAX as i128),
+                &1000000,
+            );
+            let mut pool = Pool::load(&e);
+            let mut samwise_state = User::load(&e, &samwise);
+            fill_bad_debt_auction(&e, &mut pool, &mut auction_data, &mut samwise_state, true);
+            pool.store_cached_reserves(&e);
+            // samwise repays part of the liabilities before the fill is reversed
+            let mut reserve_1 = pool.load_reserve(&e, &underlying_1);
+            samwise_state.remove_liabilities(&e, &mut reserve_1, 1_5000000);
+            reserve_1.store(&e);
+            samwise_state.store(&e);
+
+            let fill = execute_reverse_bad_debt_fill(&e, 0);
+            assert_eq!(fill.filler, samwise);
+            assert!(storage::get_bad_debt_fill(&e, 0).is_none());
+            assert!(backstop_client.get_pending_draw(&0).is_none());
+            assert_eq!(backstop_token_client.balance(&backstop_address), 95_2000000);
+            assert_eq!(backstop_token_client.balance(&samwise), 0);
+
+            let samwise_positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(samwise_positions.liabilities.len(), 0);
+            let backstop_positions = storage::get_user_positions(&e, &backstop_address);
+            assert_eq!(
+                backstop_positions
+                    .liabilities
+                    .get(reserve_config_0.index)
+                    .unwrap_optimized(),
+                10_0000000
+            );
+            assert_eq!(
+                backstop_positions
+                    .liabilities
+                    .get(reserve_config_1.index)
+                    .unwrap_optimized(),
+                1_0000000
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_reverse_bad_debt_fill_no_fill() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool_address = Address::random(&e);
+        e.as_contract(&pool_address, || {
+            execute_reverse_bad_debt_fill(&e, 0);
+        });
+    }
+
     #[test]
     fn test_fill_bad_debt_auction_leftover_debt() {
         let e = Env::default();
@@ -691,6 +946,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let mut auction_data = AuctionData {
             bid: map![
@@ -700,6 +957,9 @@ mod tests {
             ],
             lot: map![&e, (backstop_token_id.clone(), 47_6000000)],
             block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let positions: Positions = Positions {
             collateral: map![&e],
@@ -731,9 +991,14 @@ mod tests {
             );
             let mut pool = Pool::load(&e);
             let mut samwise_state = User::load(&e, &samwise);
-            fill_bad_debt_auction(&e, &mut pool, &mut auction_data, &mut samwise_state);
-            assert_eq!(backstop_token_client.balance(&backstop_address), 47_6000000);
-            assert_eq!(backstop_token_client.balance(&samwise), 47_6000000);
+            fill_bad_debt_auction(&e, &mut pool, &mut auction_data, &mut samwise_state, true);
+            // the drawn backstop tokens are held in escrow during the dispute window
+            assert_eq!(backstop_token_client.balance(&backstop_address), 95_2000000);
+            assert_eq!(backstop_token_client.balance(&samwise), 0);
+            assert_eq!(
+                backstop_client.get_pending_draw(&0).unwrap().amount,
+                47_6000000
+            );
             let samwise_positions = samwise_state.positions;
             assert_eq!(
                 samwise_positions
@@ -754,6 +1019,163 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_fill_bad_debt_auction_partial_fill() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 51,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let pool_address = Address::random(&e);
+        let (backstop_token_id, backstop_token_client) =
+            testutils::create_token_contract(&e, &bombadil);
+        let (backstop_address, backstop_client) = testutils::create_backstop(&e);
+        testutils::setup_backstop(
+            &e,
+            &pool_address,
+            &backstop_address,
+            &backstop_token_id,
+            &Address::random(&e),
+        );
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta(&e);
+        reserve_data_0.d_rate = 1_100_000_000;
+        reserve_data_0.last_time = 12345;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta(&e);
+        reserve_data_1.d_rate = 1_200_000_000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = testutils::default_reserve_meta(&e);
+        reserve_data_2.b_rate = 1_100_000_000;
+        reserve_data_2.last_time = 12345;
+        reserve_config_2.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let mut auction_data = AuctionData {
+            bid: map![&e, (underlying_0, 2_5000000), (underlying_1, 6250000)],
+            lot: map![&e, (backstop_token_id.clone(), 23_8000000)],
+            block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        let positions: Positions = Positions {
+            collateral: map![&e],
+            liabilities: map![
+                &e,
+                (reserve_config_0.index, 10_0000000),
+                (reserve_config_1.index, 2_5000000)
+            ],
+            supply: map![&e],
+        };
+        backstop_token_client.mint(&samwise, &95_2000000);
+        backstop_token_client.approve(&samwise, &backstop_address, &i128::MAX, &1000000);
+        backstop_client.deposit(&samwise, &pool_address, &95_2000000);
+        e.as_contract(&pool_address, || {
+            storage::set_auction(
+                &e,
+                &(AuctionType::BadDebtAuction as u32),
+                &backstop_address,
+                &auction_data,
+            );
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &backstop_address, &positions);
+
+            backstop_token_client.approve(
+                &pool_address,
+                &backstop_address,
+                &(u64::MAX as i128),
+                &1000000,
+            );
+            let mut pool = Pool::load(&e);
+            let mut samwise_state = User::load(&e, &samwise);
+            fill_bad_debt_auction(&e, &mut pool, &mut auction_data, &mut samwise_state, false);
+            // the drawn backstop tokens are held in escrow during the dispute window
+            assert_eq!(backstop_token_client.balance(&backstop_address), 95_2000000);
+            assert_eq!(backstop_token_client.balance(&samwise), 0);
+            assert_eq!(
+                backstop_client.get_pending_draw(&0).unwrap().amount,
+                23_8000000
+            );
+            let samwise_positions = samwise_state.positions;
+            assert_eq!(
+                samwise_positions
+                    .liabilities
+                    .get(reserve_config_0.index)
+                    .unwrap_optimized(),
+                2_5000000
+            );
+            assert_eq!(
+                samwise_positions
+                    .liabilities
+                    .get(reserve_config_1.index)
+                    .unwrap_optimized(),
+                6250000
+            );
+            // remaining liabilities are not burnt until the auction is complete
+            let backstop_positions = storage::get_user_positions(&e, &backstop_address);
+            assert_eq!(
+                backstop_positions
+                    .liabilities
+                    .get(reserve_config_0.index)
+                    .unwrap_optimized(),
+                10_0000000 - 2_5000000
+            );
+            assert_eq!(
+                backstop_positions
+                    .liabilities
+                    .get(reserve_config_1.index)
+                    .unwrap_optimized(),
+                2_5000000 - 6250000
+            );
+        });
+    }
+
     #[test]
     fn test_fill_bad_debt_auction_leftover_debt_sufficient_balance() {
         let e = Env::default();
@@ -828,6 +1250,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let mut auction_data = AuctionData {
             bid: map![
@@ -837,6 +1261,9 @@ mod tests {
             ],
             lot: map![&e, (backstop_token_id.clone(), 47_6000000)],
             block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let positions: Positions = Positions {
             collateral: map![&e],
@@ -868,12 +1295,17 @@ mod tests {
             );
             let mut pool = Pool::load(&e);
             let mut samwise_state = User::load(&e, &samwise);
-            fill_bad_debt_auction(&e, &mut pool, &mut auction_data, &mut samwise_state);
+            fill_bad_debt_auction(&e, &mut pool, &mut auction_data, &mut samwise_state, true);
+            // the drawn backstop tokens are held in escrow during the dispute window
             assert_eq!(
                 backstop_token_client.balance(&backstop_address),
-                100_047_6000000
+                100_095_2000000
+            );
+            assert_eq!(backstop_token_client.balance(&samwise), 0);
+            assert_eq!(
+                backstop_client.get_pending_draw(&0).unwrap().amount,
+                47_6000000
             );
-            assert_eq!(backstop_token_client.balance(&samwise), 47_6000000);
             let samwise_positions = samwise_state.positions;
             assert_eq!(
                 samwise_positions
@@ -0,0 +1,408 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map};
+
+use crate::{
+    constants::SCALAR_7,
+    errors::PoolError,
+    pool::{get_trusted_credit, require_allowed, FactorBasis, Pool, PositionData, User},
+    storage::{self, FillBids},
+    validator::{exceeds_max_leverage, require_not_denied},
+};
+
+use super::{
+    auction::{fill_fraction, load_auction, scale_auction_by_fraction},
+    AuctionData, AuctionType,
+};
+
+/// Set the number of blocks fill bids are collected for before a user liquidation auction is
+/// filled cooperatively. While set, liquidations can only be filled by settling bids, and the
+/// auction is split between the bidders instead of going to the first filler. A window of zero
+/// removes cooperative fills.
+///
+/// ### Arguments
+/// * `window` - The number of blocks bids are collected for
+pub fn set_coop_fill_window(e: &Env, window: u32) {
+    if window == 0 {
+        storage::del_coop_fill_window(e);
+    } else {
+        storage::set_coop_fill_window(e, &window);
+    }
+}
+
+/// Register a bid to fill a percentage of a user liquidation auction. The first bid opens a
+/// window of the pool's cooperative fill window blocks, and bids registered before it closes are
+/// settled together. Registering again replaces the filler's previous bid.
+///
+/// Bids left unsettled for a full window after the window closes are discarded by the next bid.
+///
+/// ### Arguments
+/// * `filler` - The address of the filler
+/// * `user` - The user being liquidated
+/// * `percent_filled` - The percentage being bid for as a number (i.e. 15 => 15%)
+///
+/// ### Panics
+/// If cooperative fills are not enabled, the auction does not exist, the percentage is invalid,
+/// the filler is not allowed to fill auctions, or the window is closed but not yet settled
+pub fn execute_register_fill_bid(e: &Env, filler: &Address, user: &Address, percent_filled: u64) {
+    let window = match storage::get_coop_fill_window(e) {
+        Some(window) => window,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    require_allowed(e, filler);
+    require_not_denied(e, filler);
+    load_auction(e, AuctionType::UserLiquidation as u32, user);
+    if percent_filled > 100 || percent_filled == 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let sequence = e.ledger().sequence();
+    let mut fill_bids = match storage::get_fill_bids(e, user) {
+        Some(fill_bids) if sequence < fill_bids.block + 2 * window => {
+            if sequence >= fill_bids.block + window {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            fill_bids
+        }
+        _ => FillBids {
+            block: sequence,
+            bids: map![e],
+        },
+    };
+    fill_bids.bids.set(filler.clone(), percent_filled);
+    storage::set_fill_bids(e, user, &fill_bids);
+}
+
+/// Settle the bids registered for a user liquidation auction once the window has closed. Each
+/// bidder fills their percentage of the auction at the current block, and if the bids total over
+/// 100%, each bid is pro-rated such that the auction is filled in full.
+///
+/// Bidders whose positions would be unhealthy or exceed the pool's maximum leverage after their
+/// fill are skipped, and their portion remains in the auction.
+///
+/// Returns a map of each filler to the fraction of the auction they filled, in 7 decimals
+///
+/// ### Arguments
+/// * `user` - The user being liquidated
+///
+/// ### Panics
+/// If cooperative fills are not enabled, or no bids are waiting to be settled
+pub fn execute_settle_fill_bids(e: &Env, user: &Address) -> Map<Address, i128> {
+    let window = match storage::get_coop_fill_window(e) {
+        Some(window) => window,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let fill_bids = match storage::get_fill_bids(e, user) {
+        Some(fill_bids) => fill_bids,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let sequence = e.ledger().sequence();
+    if sequence < fill_bids.block + window || sequence >= fill_bids.block + 2 * window {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::del_fill_bids(e, user);
+
+    let mut filled: Map<Address, i128> = map![e];
+    let auction_type = AuctionType::UserLiquidation as u32;
+    // the auction was filled or deleted before the bids were settled
+    if !storage::has_auction(e, &auction_type, user) {
+        return filled;
+    }
+
+    let mut total: u64 = 0;
+    for (_, percent_filled) in fill_bids.bids.iter() {
+        total += percent_filled;
+    }
+    let mut pool = Pool::load(e);
+    // the fraction of the auction at settlement that has not been filled
+    let mut remaining = SCALAR_7;
+    let mut skipped = false;
+    let last_index = fill_bids.bids.len() - 1;
+    for (index, (filler, percent_filled)) in fill_bids.bids.iter().enumerate() {
+        let fraction = if total > 100 {
+            i128(percent_filled) * SCALAR_7 / i128(total)
+        } else {
+            i128(percent_filled) * 1_00000
+        };
+        // fill the auction in full with the last bid if no bid was skipped to avoid leaving dust
+        let fraction_of_remaining = if total >= 100 && index as u32 == last_index && !skipped {
+            SCALAR_7
+        } else {
+            fraction
+                .fixed_div_floor(remaining, SCALAR_7)
+                .unwrap_optimized()
+                .min(SCALAR_7)
+        };
+
+        let auction_data = storage::get_auction(e, &auction_type, user);
+        let (to_fill_auction, _) =
+            scale_auction_by_fraction(e, &auction_data, fraction_of_remaining);
+        let mut filler_state = User::load(e, &filler);
+        if !can_take_fill(e, &mut pool, &filler_state, &to_fill_auction) {
+            skipped = true;
+            continue;
+        }
+
+        fill_fraction(
+            e,
+            &mut pool,
+            auction_type,
+            user,
+            &auction_data,
+            &mut filler_state,
+            fraction_of_remaining,
+        );
+        filler_state.store(e);
+        filled.set(filler, fraction);
+        remaining -= fraction;
+        if !storage::has_auction(e, &auction_type, user) {
+            break;
+        }
+    }
+
+    pool.store_cached_reserves(e);
+    filled
+}
+
+/// Check if a filler's positions are healthy and within the pool's maximum leverage after
+/// taking on a liquidation auction's lot and bid, without modifying the pool
+fn can_take_fill(
+    e: &Env,
+    pool: &mut Pool,
+    filler_state: &User,
+    auction_data: &AuctionData,
+) -> bool {
+    let mut positions = filler_state.positions.clone();
+    for (asset, amount) in auction_data.lot.iter() {
+        let index = pool.load_reserve(e, &asset).index;
+        positions
+            .collateral
+            .set(index, positions.collateral.get(index).unwrap_or(0) + amount);
+    }
+    for (asset, amount) in auction_data.bid.iter() {
+        let index = pool.load_reserve(e, &asset).index;
+        positions.liabilities.set(
+            index,
+            positions.liabilities.get(index).unwrap_or(0) + amount,
+        );
+    }
+
+    let credit = get_trusted_credit(e, &filler_state.address);
+    let position_data = PositionData::calculate_for_user(
+        e,
+        pool,
+        &filler_state.address,
+        &positions,
+        FactorBasis::Borrow,
+    );
+    if position_data.liability_base > 0 && position_data.is_at_or_below_hf(credit, 1_0000100) {
+        return false;
+    }
+    !exceeds_max_leverage(e, &position_data, credit)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pool::Positions,
+        storage::{self, PoolConfig},
+        testutils,
+    };
+
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    fn set_sequence(e: &Env, sequence_number: u32) {
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + (sequence_number - 176) as u64 * 5,
+            protocol_version: 1,
+            sequence_number,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+    }
+
+    /// Setup a user liquidation auction against "samwise" that started at block 176, with a
+    /// cooperative fill window of 10 blocks. Returns the pool address, the user, and the lot and
+    /// bid assets.
+    fn setup_coop_auction(e: &Env) -> (Address, Address, Address, Address) {
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        set_sequence(e, 176);
+
+        let bombadil = Address::random(e);
+        let samwise = Address::random(e);
+        let pool_address = Address::random(e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(e);
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta(e);
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+        e.budget().reset_unlimited();
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+
+        let auction_data = AuctionData {
+            bid: map![e, (underlying_1.clone(), 1_2375000)],
+            lot: map![e, (underlying_0.clone(), 30_5595329)],
+            block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions: Positions = Positions {
+            collateral: map![e, (reserve_config_0.index, 90_9100000)],
+            liabilities: map![e, (reserve_config_1.index, 02_7500000)],
+            supply: map![e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(e, &samwise, &positions);
+            storage::set_pool_config(e, &pool_config);
+            storage::set_auction(e, &0, &samwise, &auction_data);
+            set_coop_fill_window(e, 10);
+        });
+        (pool_address, samwise, underlying_0, underlying_1)
+    }
+
+    #[test]
+    fn test_settle_fill_bids_pro_rated() {
+        let e = Env::default();
+        let (pool_address, samwise, _, _) = setup_coop_auction(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+
+        // the bids total 150%, so frodo fills 2/3 of the auction and merry 1/3
+        set_sequence(&e, 366);
+        e.as_contract(&pool_address, || {
+            execute_register_fill_bid(&e, &frodo, &samwise, 100);
+            execute_register_fill_bid(&e, &merry, &samwise, 50);
+        });
+
+        set_sequence(&e, 376);
+        e.as_contract(&pool_address, || {
+            let filled = execute_settle_fill_bids(&e, &samwise);
+            assert_eq!(filled.get_unchecked(frodo.clone()), 0_6666666);
+            assert_eq!(filled.get_unchecked(merry.clone()), 0_3333333);
+            assert!(!storage::has_auction(&e, &0, &samwise));
+            assert!(storage::get_fill_bids(&e, &samwise).is_none());
+
+            // the auction is filled in full at the settlement block, split by the pro-rated
+            // fractions up to rounding
+            let frodo_positions = storage::get_user_positions(&e, &frodo);
+            let merry_positions = storage::get_user_positions(&e, &merry);
+            let frodo_bid = frodo_positions.liabilities.get_unchecked(1);
+            let merry_bid = merry_positions.liabilities.get_unchecked(1);
+            assert_eq!(frodo_bid + merry_bid, 1_2375000);
+            assert!((frodo_bid - 2 * merry_bid).abs() <= 5);
+            let frodo_lot = frodo_positions.collateral.get_unchecked(0);
+            let merry_lot = merry_positions.collateral.get_unchecked(0);
+            assert_eq!(frodo_lot + merry_lot, 30_5595329);
+            assert!((frodo_lot - 2 * merry_lot).abs() <= 100);
+        });
+    }
+
+    #[test]
+    fn test_settle_fill_bids_skips_unhealthy_filler() {
+        let e = Env::default();
+        let (pool_address, samwise, _, underlying_1) = setup_coop_auction(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+
+        set_sequence(&e, 366);
+        e.as_contract(&pool_address, || {
+            execute_register_fill_bid(&e, &frodo, &samwise, 50);
+            execute_register_fill_bid(&e, &merry, &samwise, 50);
+            // merry takes on debt that the auction's lot cannot cover
+            storage::set_user_positions(
+                &e,
+                &merry,
+                &Positions {
+                    collateral: map![&e],
+                    liabilities: map![&e, (1, 100_0000000)],
+                    supply: map![&e],
+                },
+            );
+        });
+
+        set_sequence(&e, 376);
+        e.as_contract(&pool_address, || {
+            let filled = execute_settle_fill_bids(&e, &samwise);
+            assert_eq!(filled.len(), 1);
+            assert_eq!(filled.get_unchecked(frodo.clone()), 0_5000000);
+
+            // merry's portion remains in the auction
+            let auction = storage::get_auction(&e, &0, &samwise);
+            assert_eq!(auction.bid.get_unchecked(underlying_1), 0_6187500);
+            let merry_positions = storage::get_user_positions(&e, &merry);
+            assert_eq!(merry_positions.liabilities.get_unchecked(1), 100_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_register_fill_bid_window_closed() {
+        let e = Env::default();
+        let (pool_address, samwise, _, _) = setup_coop_auction(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+
+        set_sequence(&e, 366);
+        e.as_contract(&pool_address, || {
+            execute_register_fill_bid(&e, &frodo, &samwise, 50);
+        });
+
+        set_sequence(&e, 376);
+        e.as_contract(&pool_address, || {
+            execute_register_fill_bid(&e, &merry, &samwise, 50);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_settle_fill_bids_window_open() {
+        let e = Env::default();
+        let (pool_address, samwise, _, _) = setup_coop_auction(&e);
+        let frodo = Address::random(&e);
+
+        set_sequence(&e, 366);
+        e.as_contract(&pool_address, || {
+            execute_register_fill_bid(&e, &frodo, &samwise, 50);
+        });
+
+        set_sequence(&e, 375);
+        e.as_contract(&pool_address, || {
+            execute_settle_fill_bids(&e, &samwise);
+        });
+    }
+}
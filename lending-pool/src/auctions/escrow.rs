@@ -0,0 +1,130 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{dependencies::TokenClient, errors::PoolError, pool::Pool, storage};
+
+/// Quarantine an auction lot that could not be transferred to the filler into escrow.
+///
+/// The escrowed amount remains part of the reserve's backstop credit until claimed, such that
+/// it is not distributed to suppliers, and is excluded from future interest auctions.
+///
+/// ### Arguments
+/// * `filler` - The address of the auction filler
+/// * `asset` - The underlying asset of the lot
+/// * `amount` - The amount of backstop credit owed to the filler
+pub(super) fn quarantine_lot(e: &Env, filler: &Address, asset: &Address, amount: i128) {
+    let mut escrow = storage::get_user_escrow(e, filler);
+    escrow.set(
+        asset.clone(),
+        escrow.get(asset.clone()).unwrap_or(0) + amount,
+    );
+    storage::set_user_escrow(e, filler, &escrow);
+    storage::set_escrow_total(e, asset, storage::get_escrow_total(e, asset) + amount);
+}
+
+/// Claim an auction lot held in escrow for a user
+///
+/// Returns the amount of underlying tokens transferred
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `asset` - The underlying asset of the lot
+///
+/// ### Panics
+/// If the user has nothing in escrow for the asset, or if the transfer fails
+pub fn execute_claim_escrow(e: &Env, user: &Address, asset: &Address) -> i128 {
+    let mut escrow = storage::get_user_escrow(e, user);
+    let amount = escrow.get(asset.clone()).unwrap_or(0);
+    if amount <= 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    escrow.remove(asset.clone());
+    storage::set_user_escrow(e, user, &escrow);
+    storage::set_escrow_total(e, asset, storage::get_escrow_total(e, asset) - amount);
+
+    let mut pool = Pool::load(e);
+    let mut reserve = pool.load_reserve(e, asset);
+    reserve.backstop_credit -= amount;
+    reserve.store(e);
+
+    let amount_out = pool.to_underlying_down(e, asset, amount);
+    TokenClient::new(e, asset).transfer(&e.current_contract_address(), user, &amount_out);
+    amount_out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    #[test]
+    fn test_claim_escrow() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        reserve_data.backstop_credit = 10_0000000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_0_client.mint(&pool_address, &10_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            quarantine_lot(&e, &samwise, &underlying_0, 4_0000000);
+            assert_eq!(storage::get_escrow_total(&e, &underlying_0), 4_0000000);
+
+            let amount = execute_claim_escrow(&e, &samwise, &underlying_0);
+            assert_eq!(amount, 4_0000000);
+            assert_eq!(underlying_0_client.balance(&samwise), 4_0000000);
+            assert_eq!(storage::get_escrow_total(&e, &underlying_0), 0);
+            assert!(storage::get_user_escrow(&e, &samwise).is_empty());
+            let reserve_data = storage::get_res_data(&e, &underlying_0);
+            assert_eq!(reserve_data.backstop_credit, 6_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_claim_escrow_empty() {
+        let e = Env::default();
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let asset = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            execute_claim_escrow(&e, &samwise, &asset);
+        });
+    }
+}
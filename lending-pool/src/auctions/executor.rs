@@ -0,0 +1,248 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{
+    dependencies::FillExecutorClient,
+    errors::PoolError,
+    pool::{get_trusted_credit, FactorBasis, Pool, PositionData, Positions, User},
+    validator::require_max_leverage,
+};
+
+use super::{fill, quote_fill, AuctionType};
+
+/// Fill an auction on behalf of an executor contract. The executor receives a callback with the
+/// quote for the fill and must approve it before the fill is executed, which allows the executor
+/// to fund the fill during the callback instead of holding a pre-funded balance.
+///
+/// The callback occurs before the pool loads any state, such that the fill is executed
+/// against any changes the executor makes during the callback.
+///
+/// Returns the executor's positions after the fill
+///
+/// ### Arguments
+/// * `executor` - The address of the executor contract filling the auction
+/// * `auction_type` - The type of auction to fill
+/// * `user` - The user involved in the auction
+/// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
+///
+/// ### Panics
+/// If the auction does not exist, the executor rejects the quote, or the executor's positions
+/// are unhealthy after the fill
+pub fn execute_fill_with_executor(
+    e: &Env,
+    executor: &Address,
+    auction_type: u32,
+    user: &Address,
+    percent_filled: u64,
+) -> Positions {
+    if auction_type > AuctionType::InterestAuction as u32 {
+        panic_with_error!(e, PoolError::InvalidAuctionType);
+    }
+    let quote = quote_fill(e, auction_type, user, percent_filled);
+    let approved = FillExecutorClient::new(e, executor).exec_fill(
+        &e.current_contract_address(),
+        &auction_type,
+        user,
+        &quote,
+    );
+    if !approved {
+        panic_with_error!(e, PoolError::ExecutorRejected);
+    }
+
+    let mut pool = Pool::load(e);
+    let mut executor_state = User::load(e, executor);
+    fill(
+        e,
+        &mut pool,
+        auction_type,
+        user,
+        &mut executor_state,
+        percent_filled,
+    );
+    if auction_type != AuctionType::InterestAuction as u32 {
+        let credit = get_trusted_credit(e, executor);
+        let position_data = PositionData::calculate_for_user(
+            e,
+            &mut pool,
+            executor,
+            &executor_state.positions,
+            FactorBasis::Borrow,
+        );
+        position_data.require_healthy_with_credit(e, credit);
+        require_max_leverage(e, &position_data, credit);
+    }
+
+    pool.store_cached_reserves(e);
+    executor_state.store(e);
+    executor_state.positions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        auctions::AuctionData,
+        storage::{self, PoolConfig},
+        testutils,
+    };
+
+    use super::*;
+    use soroban_sdk::{
+        contract, contractimpl, map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Symbol,
+    };
+
+    #[contract]
+    struct MockExecutor;
+
+    #[contractimpl]
+    impl MockExecutor {
+        pub fn set_approve(e: Env, approve: bool) {
+            e.storage()
+                .instance()
+                .set(&Symbol::new(&e, "approve"), &approve);
+        }
+
+        pub fn exec_fill(
+            e: Env,
+            _pool: Address,
+            _auction_type: u32,
+            _user: Address,
+            quote: AuctionData,
+        ) -> bool {
+            e.storage()
+                .instance()
+                .set(&Symbol::new(&e, "quote"), &quote);
+            e.storage()
+                .instance()
+                .get(&Symbol::new(&e, "approve"))
+                .unwrap_or(false)
+        }
+    }
+
+    fn setup_liquidation(e: &Env, approve: bool) -> (Address, Address, Address) {
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(e);
+        let samwise = Address::random(e);
+        let pool_address = Address::random(e);
+        let executor = e.register_contract(None, MockExecutor {});
+        MockExecutorClient::new(e, &executor).set_approve(&approve);
+
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta(e);
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta(e);
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+
+        let auction_data = AuctionData {
+            bid: map![e, (underlying_1.clone(), 10_0000000)],
+            lot: map![e, (underlying_0.clone(), 20_0000000)],
+            block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions = Positions {
+            collateral: map![e, (reserve_config_0.index, 20_0000000)],
+            liabilities: map![e, (reserve_config_1.index, 15_0000000)],
+            supply: map![e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(e, &samwise, &positions);
+            storage::set_pool_config(e, &pool_config);
+            storage::set_auction(
+                e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
+        });
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 200 * 5,
+            protocol_version: 1,
+            sequence_number: 176 + 200,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        (pool_address, executor, samwise)
+    }
+
+    #[test]
+    fn test_fill_with_executor() {
+        let e = Env::default();
+        let (pool_address, executor, samwise) = setup_liquidation(&e, true);
+
+        e.as_contract(&pool_address, || {
+            let positions = execute_fill_with_executor(&e, &executor, 0, &samwise, 100);
+            assert_eq!(positions.collateral.get_unchecked(0), 20_0000000);
+            assert_eq!(positions.liabilities.get_unchecked(1), 10_0000000);
+            assert!(!storage::has_auction(&e, &0, &samwise));
+            assert_eq!(
+                storage::get_user_positions(&e, &executor).collateral,
+                positions.collateral
+            );
+        });
+        e.as_contract(&executor, || {
+            let quote: AuctionData = e
+                .storage()
+                .instance()
+                .get(&Symbol::new(&e, "quote"))
+                .unwrap();
+            assert_eq!(quote.lot.len(), 1);
+            assert_eq!(quote.bid.len(), 1);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(108)")]
+    fn test_fill_with_executor_rejected() {
+        let e = Env::default();
+        let (pool_address, executor, samwise) = setup_liquidation(&e, false);
+
+        e.as_contract(&pool_address, || {
+            execute_fill_with_executor(&e, &executor, 0, &samwise, 100);
+        });
+    }
+}
@@ -1,6 +1,19 @@
 mod auction;
 mod backstop_interest_auction;
 mod bad_debt_auction;
+mod cooperative;
+mod escrow;
+mod executor;
+mod precheck;
+#[cfg(test)]
+mod simulation;
+mod stats;
 mod user_liquidation_auction;
 
 pub use auction::*;
+pub use bad_debt_auction::execute_reverse_bad_debt_fill;
+pub use cooperative::{execute_register_fill_bid, execute_settle_fill_bids, set_coop_fill_window};
+pub use escrow::execute_claim_escrow;
+pub use executor::execute_fill_with_executor;
+pub use precheck::{can_create_auction, AuctionCheck};
+pub use stats::get_recent_auctions;
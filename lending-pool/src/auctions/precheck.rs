@@ -0,0 +1,383 @@
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+use crate::{
+    errors::PoolError,
+    pool::{get_trusted_credit, FactorBasis, Pool, PositionData},
+    storage,
+};
+
+use super::{
+    backstop_interest_auction::{calc_interest_lot, min_interest_value},
+    AuctionType,
+};
+
+/// The result of checking if an auction can be created
+#[derive(Clone, PartialEq)]
+#[contracttype]
+pub enum AuctionCheck {
+    // The auction can be created
+    Ready,
+    // An auction of the type already exists for the user
+    AuctionExists,
+    // The user is above the pool's liquidation health factor, so they cannot be liquidated
+    UserHealthy,
+    // The backstop does not hold any bad debt
+    NoBadDebt,
+    // No backstop credit is available to auction
+    NoInterest,
+    // The available backstop credit is not valuable enough to auction
+    BelowThreshold,
+    // The pool distributes backstop credit to the backstop's depositors instead of auctioning it
+    DirectInterest,
+}
+
+/// Check if an auction can be created, without creating it. Allows keepers to avoid
+/// submitting `create` calls that would fail.
+///
+/// A `Ready` user liquidation can still fail to be created if the percent liquidated
+/// is not sized correctly for the user.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user being liquidated. Bad debt and interest auctions are always created
+///            for the backstop, so the user is ignored for those types.
+///
+/// ### Panics
+/// If the auction type is invalid
+pub fn can_create_auction(e: &Env, auction_type: u32, user: &Address) -> AuctionCheck {
+    if auction_type > AuctionType::InterestAuction as u32 {
+        panic_with_error!(e, PoolError::InvalidAuctionType);
+    }
+    let auction_user = match AuctionType::from_u32(auction_type) {
+        AuctionType::UserLiquidation => user.clone(),
+        _ => storage::get_backstop(e),
+    };
+    if storage::has_auction(e, &auction_type, &auction_user) {
+        return AuctionCheck::AuctionExists;
+    }
+
+    let mut pool = Pool::load(e);
+    match AuctionType::from_u32(auction_type) {
+        AuctionType::UserLiquidation => {
+            let positions = storage::get_user_positions(e, &auction_user);
+            if positions.liabilities.is_empty() {
+                return AuctionCheck::UserHealthy;
+            }
+            let position_data = PositionData::calculate_for_user(
+                e,
+                &mut pool,
+                &auction_user,
+                &positions,
+                FactorBasis::Liquidation,
+            );
+            let liq_hf_low = storage::get_pool_config(e).liq_hf_low;
+            if !position_data.is_at_or_below_hf(get_trusted_credit(e, &auction_user), liq_hf_low) {
+                return AuctionCheck::UserHealthy;
+            }
+        }
+        AuctionType::BadDebtAuction => {
+            let backstop_positions = storage::get_user_positions(e, &auction_user);
+            if !backstop_positions
+                .liabilities
+                .iter()
+                .any(|(_, balance)| balance > 0)
+            {
+                return AuctionCheck::NoBadDebt;
+            }
+        }
+        AuctionType::InterestAuction => {
+            if storage::get_direct_interest(e) {
+                return AuctionCheck::DirectInterest;
+            }
+            let (lot, interest_value) = calc_interest_lot(e, &mut pool);
+            if lot.is_empty() {
+                return AuctionCheck::NoInterest;
+            }
+            if interest_value <= min_interest_value(e, &mut pool) {
+                return AuctionCheck::BelowThreshold;
+            }
+        }
+    }
+    AuctionCheck::Ready
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{auctions::AuctionData, pool::Positions, storage::PoolConfig, testutils};
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    fn setup(e: &Env, pool_address: &Address) -> (Address, Address) {
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(e);
+        let (oracle_id, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta(e);
+        reserve_data_0.last_time = 12345;
+        testutils::create_reserve(
+            e,
+            pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta(e);
+        reserve_data_1.last_time = 12345;
+        testutils::create_reserve(
+            e,
+            pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &10_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(pool_address, || {
+            storage::set_pool_config(e, &pool_config);
+            storage::set_backstop(e, &Address::random(e));
+        });
+        (underlying_0, underlying_1)
+    }
+
+    fn default_auction(e: &Env) -> AuctionData {
+        AuctionData {
+            bid: map![e],
+            lot: map![e],
+            block: 50,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        }
+    }
+
+    #[test]
+    fn test_can_create_auction_user_liquidation() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        setup(&e, &pool_address);
+
+        e.as_contract(&pool_address, || {
+            // no liabilities
+            assert!(can_create_auction(&e, 0, &samwise) == AuctionCheck::UserHealthy);
+
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 100_0000000)],
+                    liabilities: map![&e, (1, 5_0000000)],
+                    supply: map![&e],
+                },
+            );
+            assert!(can_create_auction(&e, 0, &samwise) == AuctionCheck::UserHealthy);
+
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 100_0000000)],
+                    liabilities: map![&e, (1, 6_0000000)],
+                    supply: map![&e],
+                },
+            );
+            assert!(can_create_auction(&e, 0, &samwise) == AuctionCheck::Ready);
+
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &default_auction(&e),
+            );
+            assert!(can_create_auction(&e, 0, &samwise) == AuctionCheck::AuctionExists);
+        });
+    }
+
+    #[test]
+    fn test_can_create_auction_user_liquidation_below_liq_hf_low() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        setup(&e, &pool_address);
+
+        e.as_contract(&pool_address, || {
+            let mut pool_config = storage::get_pool_config(&e);
+            pool_config.liq_hf_low = 0_9000000;
+            storage::set_pool_config(&e, &pool_config);
+
+            // health factor of 0.9375
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 100_0000000)],
+                    liabilities: map![&e, (1, 6_0000000)],
+                    supply: map![&e],
+                },
+            );
+            assert!(can_create_auction(&e, 0, &samwise) == AuctionCheck::UserHealthy);
+
+            // health factor of 0.8823
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 100_0000000)],
+                    liabilities: map![&e, (1, 6_3750000)],
+                    supply: map![&e],
+                },
+            );
+            assert!(can_create_auction(&e, 0, &samwise) == AuctionCheck::Ready);
+        });
+    }
+
+    #[test]
+    fn test_can_create_auction_user_liquidation_liq_threshold() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool_address);
+
+        e.as_contract(&pool_address, || {
+            // health factor of 0.9375 against the collateral factor
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 100_0000000)],
+                    liabilities: map![&e, (1, 6_0000000)],
+                    supply: map![&e],
+                },
+            );
+            assert!(can_create_auction(&e, 0, &samwise) == AuctionCheck::Ready);
+
+            // health factor of 1.0625 against the liquidation threshold
+            storage::set_res_liq_threshold(&e, &underlying_0, &0_8500000);
+            assert!(can_create_auction(&e, 0, &samwise) == AuctionCheck::UserHealthy);
+        });
+    }
+
+    #[test]
+    fn test_can_create_auction_bad_debt() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        setup(&e, &pool_address);
+
+        e.as_contract(&pool_address, || {
+            assert!(can_create_auction(&e, 1, &samwise) == AuctionCheck::NoBadDebt);
+
+            let backstop = storage::get_backstop(&e);
+            storage::set_user_positions(
+                &e,
+                &backstop,
+                &Positions {
+                    collateral: map![&e],
+                    liabilities: map![&e, (1, 1_0000000)],
+                    supply: map![&e],
+                },
+            );
+            assert!(can_create_auction(&e, 1, &samwise) == AuctionCheck::Ready);
+
+            storage::set_auction(
+                &e,
+                &(AuctionType::BadDebtAuction as u32),
+                &backstop,
+                &default_auction(&e),
+            );
+            assert!(can_create_auction(&e, 1, &samwise) == AuctionCheck::AuctionExists);
+        });
+    }
+
+    #[test]
+    fn test_can_create_auction_interest() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        let (underlying_0, underlying_1) = setup(&e, &pool_address);
+
+        e.as_contract(&pool_address, || {
+            assert!(can_create_auction(&e, 2, &samwise) == AuctionCheck::NoInterest);
+
+            let mut pool = Pool::load(&e);
+            let mut reserve_0 = pool.load_reserve(&e, &underlying_0);
+            reserve_0.backstop_credit += 50_0000000;
+            reserve_0.store(&e);
+            assert!(can_create_auction(&e, 2, &samwise) == AuctionCheck::BelowThreshold);
+
+            let mut reserve_1 = pool.load_reserve(&e, &underlying_1);
+            reserve_1.backstop_credit += 20_0000000;
+            reserve_1.store(&e);
+            assert!(can_create_auction(&e, 2, &samwise) == AuctionCheck::Ready);
+
+            storage::set_direct_interest(&e, &true);
+            assert!(can_create_auction(&e, 2, &samwise) == AuctionCheck::DirectInterest);
+            storage::set_direct_interest(&e, &false);
+
+            storage::set_auction(
+                &e,
+                &(AuctionType::InterestAuction as u32),
+                &storage::get_backstop(&e),
+                &default_auction(&e),
+            );
+            assert!(can_create_auction(&e, 2, &samwise) == AuctionCheck::AuctionExists);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(104)")]
+    fn test_can_create_auction_invalid_type() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        setup(&e, &pool_address);
+
+        e.as_contract(&pool_address, || {
+            can_create_auction(&e, 3, &samwise);
+        });
+    }
+}
@@ -0,0 +1,242 @@
+//! Keeper simulations of user liquidation auctions.
+//!
+//! Each simulation creates a liquidation auction and steps the ledger block by block with a
+//! `KeeperSim`, where keepers fill the auction once the quoted lot is worth enough more than the
+//! quoted bid to cover their required margin. The block the auction is filled at shows how the
+//! auction parameters price liquidations for keepers with different margins and market moves.
+use core::cell::Cell;
+
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{
+    map,
+    testutils::{Address as _, Ledger, LedgerInfo},
+    unwrap::UnwrapOptimized,
+    Address, Env, Map,
+};
+
+use crate::{
+    constants::SCALAR_7,
+    pool::{Pool, Positions, User},
+    storage::{self, PoolConfig},
+    testutils::{self, KeeperSim},
+};
+
+use super::{fill, quote_fill, AuctionData};
+
+/// Create a user liquidation auction for "samwise" at block 176, with a lot of reserves 0 and 1
+/// worth ~32.1 and a bid of reserve 2 worth ~6.19. Returns the underlying assets of the reserves
+/// and the mock oracle.
+fn setup<'a>(
+    e: &'a Env,
+    pool_address: &Address,
+    samwise: &Address,
+) -> (Address, Address, Address, mock_oracle::MockOracleClient<'a>) {
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+    e.ledger().set(LedgerInfo {
+        timestamp: 12345,
+        protocol_version: 1,
+        sequence_number: 176,
+        network_id: Default::default(),
+        base_reserve: 10,
+        min_temp_entry_expiration: 10,
+        min_persistent_entry_expiration: 10,
+        max_entry_expiration: 2000000,
+    });
+
+    let bombadil = Address::random(e);
+    let (oracle_address, oracle_client) = testutils::create_mock_oracle(e);
+
+    let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+    let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+    reserve_data.last_time = 12345;
+    testutils::create_reserve(
+        e,
+        pool_address,
+        &underlying_0,
+        &reserve_config,
+        &reserve_data,
+    );
+
+    let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+    let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+    reserve_data.last_time = 12345;
+    testutils::create_reserve(
+        e,
+        pool_address,
+        &underlying_1,
+        &reserve_config,
+        &reserve_data,
+    );
+
+    let (underlying_2, _) = testutils::create_token_contract(e, &bombadil);
+    let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+    reserve_data.last_time = 12345;
+    testutils::create_reserve(
+        e,
+        pool_address,
+        &underlying_2,
+        &reserve_config,
+        &reserve_data,
+    );
+
+    oracle_client.set_price(&underlying_0, &1_0000000);
+    oracle_client.set_price(&underlying_1, &1_0000000);
+    oracle_client.set_price(&underlying_2, &5_0000000);
+
+    let pool_config = PoolConfig {
+        oracle: oracle_address,
+        bstop_rate: 0_100_000_000,
+        status: 0,
+        liq_hf_low: 1_0000000,
+        liq_hf_high: 1_0000100,
+    };
+    let auction_data = AuctionData {
+        bid: map![e, (underlying_2.clone(), 1_2375000)],
+        lot: map![
+            e,
+            (underlying_0.clone(), 30_5595329),
+            (underlying_1.clone(), 1_5395739)
+        ],
+        block: 176,
+        timestamp: 12345,
+        mode: 0,
+        valuation: 0,
+    };
+    e.as_contract(pool_address, || {
+        storage::set_pool_config(e, &pool_config);
+        storage::set_user_positions(
+            e,
+            samwise,
+            &Positions {
+                collateral: map![e, (0, 90_9100000), (1, 04_5800000)],
+                liabilities: map![e, (2, 02_7500000)],
+                supply: map![e],
+            },
+        );
+        storage::set_auction(e, &0, samwise, &auction_data);
+    });
+    (underlying_0, underlying_1, underlying_2, oracle_client)
+}
+
+fn value(e: &Env, pool: &mut Pool, assets: &Map<Address, i128>) -> i128 {
+    let mut value = 0;
+    for (asset, amount) in assets.iter() {
+        let price = pool.load_price(e, &asset);
+        value += amount.fixed_mul_floor(price, SCALAR_7).unwrap_optimized();
+    }
+    value
+}
+
+/// A keeper that fills the entire auction for "samwise" once the quoted lot is worth at least
+/// "margin" more than the quoted bid, and records the block it filled at in "filled"
+fn margin_keeper<'a>(
+    samwise: &'a Address,
+    filler: &'a Address,
+    margin: i128,
+    filled: &'a Cell<Option<u32>>,
+) -> impl FnMut(&Env, u32) + 'a {
+    move |e, block| {
+        if !storage::has_auction(e, &0, samwise) {
+            return;
+        }
+        let mut pool = Pool::load(e);
+        let quote = quote_fill(e, 0, samwise, 100);
+        let lot_value = value(e, &mut pool, &quote.lot);
+        let bid_value = value(e, &mut pool, &quote.bid);
+        if lot_value
+            >= bid_value
+                .fixed_mul_ceil(SCALAR_7 + margin, SCALAR_7)
+                .unwrap_optimized()
+        {
+            let mut filler_state = User::load(e, filler);
+            fill(e, &mut pool, 0, samwise, &mut filler_state, 100);
+            pool.store_cached_reserves(e);
+            filler_state.store(e);
+            filled.set(Some(block));
+        }
+    }
+}
+
+#[test]
+fn test_keeper_sim_lowest_margin_fills_first() {
+    let e = Env::default();
+    let pool_address = Address::random(&e);
+    let samwise = Address::random(&e);
+    let frodo = Address::random(&e);
+    let merry = Address::random(&e);
+    setup(&e, &pool_address, &samwise);
+
+    let frodo_filled = Cell::new(None);
+    let merry_filled = Cell::new(None);
+    let mut sim = KeeperSim::new(&e, &pool_address);
+    // merry requires a 50% margin and is run first, frodo requires a 10% margin
+    sim.add_keeper(margin_keeper(&samwise, &merry, 0_5000000, &merry_filled));
+    sim.add_keeper(margin_keeper(&samwise, &frodo, 0_1000000, &frodo_filled));
+
+    // the lot reaches 110% of the bid's value after 43 blocks (21.5% of the lot)
+    let blocks = sim.run_until(400, |e| !storage::has_auction(e, &0, &samwise));
+    assert_eq!(blocks, Some(43));
+    assert_eq!(sim.blocks(), 43);
+    assert_eq!(frodo_filled.get(), Some(43));
+    assert_eq!(merry_filled.get(), None);
+
+    e.as_contract(&pool_address, || {
+        let frodo_positions = storage::get_user_positions(&e, &frodo);
+        assert_eq!(frodo_positions.liabilities.get_unchecked(2), 1_2375000);
+        assert_eq!(frodo_positions.collateral.get_unchecked(0), 6_5702995);
+        assert!(storage::get_user_positions(&e, &merry)
+            .collateral
+            .is_empty());
+
+        let stats = storage::get_auction_stats(&e);
+        assert_eq!(stats.fills, 1);
+        assert_eq!(stats.fill_blocks, 43);
+    });
+}
+
+#[test]
+fn test_keeper_sim_falling_collateral_delays_fill() {
+    let e = Env::default();
+    let pool_address = Address::random(&e);
+    let samwise = Address::random(&e);
+    let frodo = Address::random(&e);
+    let (underlying_0, _, _, oracle_client) = setup(&e, &pool_address, &samwise);
+
+    let frodo_filled = Cell::new(None);
+    let mut sim = KeeperSim::new(&e, &pool_address);
+    // the market sells off the lot's main asset by 0.1% of its starting price each block
+    sim.add_user(|_, block| {
+        oracle_client.set_price(&underlying_0, &(1_0000000 - 0_0010000 * i128::from(block)));
+    });
+    sim.add_keeper(margin_keeper(&samwise, &frodo, 0_1000000, &frodo_filled));
+
+    let blocks = sim.run_until(400, |e| !storage::has_auction(e, &0, &samwise));
+    assert_eq!(blocks, Some(45));
+    assert_eq!(frodo_filled.get(), Some(45));
+}
+
+#[test]
+fn test_keeper_sim_high_margin_waits_for_bid_decay() {
+    let e = Env::default();
+    let pool_address = Address::random(&e);
+    let samwise = Address::random(&e);
+    let frodo = Address::random(&e);
+    setup(&e, &pool_address, &samwise);
+
+    let frodo_filled = Cell::new(None);
+    let mut sim = KeeperSim::new(&e, &pool_address);
+    // a 900% margin is only met once the bid has decayed to below 1/10th of the lot's value,
+    // 97 blocks after the lot is fully scaled
+    sim.add_keeper(margin_keeper(&samwise, &frodo, 9_0000000, &frodo_filled));
+
+    assert_eq!(
+        sim.run_until(296, |e| !storage::has_auction(e, &0, &samwise)),
+        None
+    );
+    assert_eq!(frodo_filled.get(), None);
+    assert_eq!(
+        sim.run_until(20, |e| !storage::has_auction(e, &0, &samwise)),
+        Some(297)
+    );
+}
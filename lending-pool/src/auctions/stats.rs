@@ -0,0 +1,136 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env, Vec};
+
+use crate::{
+    constants::AUCTION_HISTORY_LENGTH,
+    errors::PoolError,
+    pool::Pool,
+    storage::{self, AuctionRecord},
+};
+
+use super::{AuctionData, AuctionType};
+
+/// Record the creation of a liquidation auction in the pool's auction statistics
+pub fn record_auction_created(e: &Env) {
+    let mut stats = storage::get_auction_stats(e);
+    stats.created += 1;
+    storage::set_auction_stats(e, &stats);
+}
+
+/// Record a fill of a liquidation auction in the pool's auction statistics. The liquidated
+/// value is the raw oracle value of the liabilities the filler assumed. Liabilities without
+/// a valid price are not valued, such that the statistics can never block a fill.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `filled_auction` - The (scaled) auction being filled
+/// * `is_final_fill` - If this fill completes the auction
+pub fn record_auction_fill(
+    e: &Env,
+    pool: &mut Pool,
+    filled_auction: &AuctionData,
+    is_final_fill: bool,
+) {
+    let liquidated_value = value_liabilities(e, pool, filled_auction);
+
+    let mut stats = storage::get_auction_stats(e);
+    stats.fills += 1;
+    if is_final_fill {
+        stats.completed += 1;
+    }
+    stats.fill_blocks += u64::from(e.ledger().sequence().saturating_sub(filled_auction.block));
+    stats.avg_fill_blocks = (stats.fill_blocks / u64::from(stats.fills)) as u32;
+    stats.liquidated_value += liquidated_value;
+    storage::set_auction_stats(e, &stats);
+}
+
+/// Record a fill of an auction in the recent auction history of its type. Only the last
+/// `AUCTION_HISTORY_LENGTH` fills of each type are kept.
+///
+/// The value of the fill is the raw oracle value of the reserve assets involved. For
+/// liquidation and bad debt auctions, these are the liabilities the filler assumed, and for
+/// interest auctions, the backstop credit the filler received. Assets without a valid price
+/// are not valued, such that the history can never block a fill.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `auction_type` - The type of auction being filled
+/// * `user` - The Address whose assets are being auctioned
+/// * `filler` - The Address filling the auction
+/// * `filled_auction` - The (scaled) auction being filled
+/// * `is_final_fill` - If this fill completes the auction
+pub fn record_auction_history(
+    e: &Env,
+    pool: &mut Pool,
+    auction_type: u32,
+    user: &Address,
+    filler: &Address,
+    filled_auction: &AuctionData,
+    is_final_fill: bool,
+) {
+    let value = match AuctionType::from_u32(auction_type) {
+        AuctionType::InterestAuction => value_credit(e, pool, filled_auction),
+        _ => value_liabilities(e, pool, filled_auction),
+    };
+
+    let mut history = storage::get_auction_history(e, auction_type);
+    history.push_back(AuctionRecord {
+        user: user.clone(),
+        filler: filler.clone(),
+        block: filled_auction.block,
+        fill_block: e.ledger().sequence(),
+        value,
+        completed: is_final_fill,
+    });
+    if history.len() > AUCTION_HISTORY_LENGTH {
+        history.pop_front();
+    }
+    storage::set_auction_history(e, auction_type, &history);
+}
+
+/// Fetch up to the last "count" fills of an auction type, oldest first
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `count` - The maximum number of fills to fetch
+///
+/// ### Panics
+/// If the auction type is invalid
+pub fn get_recent_auctions(e: &Env, auction_type: u32, count: u32) -> Vec<AuctionRecord> {
+    if auction_type > AuctionType::InterestAuction as u32 {
+        panic_with_error!(e, PoolError::InvalidAuctionType);
+    }
+    let history = storage::get_auction_history(e, auction_type);
+    if count >= history.len() {
+        return history;
+    }
+    history.slice(history.len() - count..)
+}
+
+/// Value the liabilities (dTokens) of an auction's bid
+fn value_liabilities(e: &Env, pool: &mut Pool, auction: &AuctionData) -> i128 {
+    let mut value = 0;
+    for (asset, d_tokens) in auction.bid.iter() {
+        let reserve = pool.load_reserve(e, &asset);
+        if let Some(asset_to_base) = pool.try_load_price(e, &asset) {
+            value += asset_to_base
+                .fixed_mul_floor(reserve.to_asset_from_d_token(d_tokens), reserve.scalar)
+                .unwrap_optimized();
+        }
+    }
+    value
+}
+
+/// Value the backstop credit (underlying tokens) of an auction's lot
+fn value_credit(e: &Env, pool: &mut Pool, auction: &AuctionData) -> i128 {
+    let mut value = 0;
+    for (asset, amount) in auction.lot.iter() {
+        let reserve = pool.load_reserve(e, &asset);
+        if let Some(asset_to_base) = pool.try_load_price(e, &asset) {
+            value += asset_to_base
+                .fixed_mul_floor(amount, reserve.scalar)
+                .unwrap_optimized();
+        }
+    }
+    value
+}
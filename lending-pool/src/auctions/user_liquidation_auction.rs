@@ -1,14 +1,14 @@
 use cast::i128;
 use fixed_point_math::FixedPoint;
 use soroban_sdk::unwrap::UnwrapOptimized;
-use soroban_sdk::{map, panic_with_error, Address, Env};
+use soroban_sdk::{map, panic_with_error, vec, Address, Env, Map, Vec};
 
 use crate::auctions::auction::AuctionData;
 use crate::constants::SCALAR_7;
-use crate::pool::{Pool, PositionData, User};
-use crate::{errors::PoolError, storage};
+use crate::pool::{get_trusted_credit, FactorBasis, Pool, PositionData, User};
+use crate::{emissions, errors::PoolError, storage};
 
-use super::AuctionType;
+use super::{AuctionType, AuctionValuation};
 
 // TODO: Revalidate math with alternative decimal reserve
 pub fn create_user_liq_auction_data(
@@ -23,45 +23,68 @@ pub fn create_user_liq_auction_data(
         panic_with_error!(e, PoolError::InvalidLiquidation);
     }
     let percent_liquidated_i128 = i128(percent_liquidated) * 1_00000; // scale to decimal form with 7 decimals
+    let valuation_u32 = storage::get_auction_valuation(e);
+    let valuation = AuctionValuation::from_u32(e, valuation_u32);
 
     let mut liquidation_quote = AuctionData {
         bid: map![e],
         lot: map![e],
         block: e.ledger().sequence() + 1,
+        timestamp: e.ledger().timestamp(),
+        mode: storage::get_auction_mode(e),
+        valuation: valuation_u32,
     };
     let mut pool = Pool::load(e);
     let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
 
     let mut user_state = User::load(e, user);
     let reserve_list = storage::get_res_list(e);
-    let position_data = PositionData::calculate_from_positions(e, &mut pool, &user_state.positions);
-
-    // ensure the user has less collateral than liabilities
-    if position_data.liability_base < position_data.collateral_base {
+    let position_data = PositionData::calculate_for_user(
+        e,
+        &mut pool,
+        user,
+        &user_state.positions,
+        FactorBasis::Liquidation,
+    );
+
+    // ensure the user is at or below the pool's liquidation health factor, including any
+    // trusted credit
+    let liq_hf_low = storage::get_pool_config(e).liq_hf_low;
+    if !position_data.is_at_or_below_hf(get_trusted_credit(e, user), liq_hf_low) {
         panic_with_error!(e, PoolError::InvalidLiquidation);
     }
 
     // ensure liquidation size is fair and the collateral is large enough to allow for the auction to price the liquidation
-    let avg_cf = position_data
-        .collateral_base
-        .fixed_div_floor(position_data.collateral_raw, oracle_scalar)
-        .unwrap_optimized();
-    // avg_lf is the inverse of the average liability factor
-    let avg_lf = position_data
-        .liability_base
-        .fixed_div_floor(position_data.liability_raw, oracle_scalar)
-        .unwrap_optimized();
-    let est_incentive = (SCALAR_7 - avg_cf.fixed_div_ceil(avg_lf, SCALAR_7).unwrap_optimized())
-        .fixed_div_ceil(2_0000000, SCALAR_7)
-        .unwrap_optimized()
-        + SCALAR_7;
-
-    let est_withdrawn_collateral = position_data
-        .liability_raw
-        .fixed_mul_floor(percent_liquidated_i128, oracle_scalar)
-        .unwrap_optimized()
-        .fixed_mul_floor(est_incentive, SCALAR_7)
-        .unwrap_optimized();
+    let est_withdrawn_collateral = match valuation {
+        AuctionValuation::Raw => {
+            let avg_cf = position_data
+                .collateral_base
+                .fixed_div_floor(position_data.collateral_raw, oracle_scalar)
+                .unwrap_optimized();
+            // avg_lf is the inverse of the average liability factor
+            let avg_lf = position_data
+                .liability_base
+                .fixed_div_floor(position_data.liability_raw, oracle_scalar)
+                .unwrap_optimized();
+            let factor_ratio = avg_cf.fixed_div_ceil(avg_lf, SCALAR_7).unwrap_optimized();
+            let est_incentive = (SCALAR_7 - factor_ratio)
+                .fixed_div_ceil(2_0000000, SCALAR_7)
+                .unwrap_optimized()
+                + SCALAR_7;
+            position_data
+                .liability_raw
+                .fixed_mul_floor(percent_liquidated_i128, oracle_scalar)
+                .unwrap_optimized()
+                .fixed_mul_floor(est_incentive, SCALAR_7)
+                .unwrap_optimized()
+        }
+        // the lot covers the factor weighted value of the bid, such that the liability
+        // factor provides the liquidation incentive
+        AuctionValuation::Effective => position_data
+            .liability_base
+            .fixed_mul_floor(percent_liquidated_i128, oracle_scalar)
+            .unwrap_optimized(),
+    };
     let mut est_withdrawn_collateral_pct = est_withdrawn_collateral
         .fixed_div_ceil(position_data.collateral_raw, oracle_scalar)
         .unwrap_optimized();
@@ -69,16 +92,27 @@ pub fn create_user_liq_auction_data(
         est_withdrawn_collateral_pct = 1_0000000;
     }
 
-    for (asset, amount) in user_state.positions.collateral.iter() {
-        let res_asset_address = reserve_list.get_unchecked(asset);
-        // Note: we multiply balance by estimated withdrawn collateral percent to allow
-        //       smoother scaling of liquidation modifiers
-        let b_tokens_removed = amount
-            .fixed_mul_ceil(est_withdrawn_collateral_pct, SCALAR_7)
-            .unwrap_optimized();
-        liquidation_quote
-            .lot
-            .set(res_asset_address, b_tokens_removed);
+    let priority = storage::get_liq_priority(e);
+    if priority.is_empty() {
+        for (asset, amount) in user_state.positions.collateral.iter() {
+            let res_asset_address = reserve_list.get_unchecked(asset);
+            // Note: we multiply balance by estimated withdrawn collateral percent to allow
+            //       smoother scaling of liquidation modifiers
+            let b_tokens_removed = amount
+                .fixed_mul_ceil(est_withdrawn_collateral_pct, SCALAR_7)
+                .unwrap_optimized();
+            liquidation_quote
+                .lot
+                .set(res_asset_address, b_tokens_removed);
+        }
+    } else {
+        liquidation_quote.lot = build_priority_lot(
+            e,
+            &mut pool,
+            &user_state.positions.collateral,
+            &priority,
+            est_withdrawn_collateral,
+        );
     }
 
     for (asset, amount) in user_state.positions.liabilities.iter() {
@@ -103,8 +137,14 @@ pub fn create_user_liq_auction_data(
             liquidation_quote.lot.clone(),
             liquidation_quote.bid.clone(),
         );
-        let new_hf = PositionData::calculate_from_positions(e, &mut pool, &user_state.positions)
-            .as_health_factor();
+        let new_hf = PositionData::calculate_for_user(
+            e,
+            &mut pool,
+            user,
+            &user_state.positions,
+            FactorBasis::Liquidation,
+        )
+        .as_health_factor();
 
         //check if liq is too large
         if new_hf > 1_1500000 {
@@ -118,6 +158,55 @@ pub fn create_user_liq_auction_data(
     liquidation_quote
 }
 
+/// Build the lot of a user liquidation by seizing the user's collateral in the pool's
+/// liquidation priority, followed by any remaining collateral in reserve order, until the
+/// raw value of the seized collateral covers the estimated withdrawn collateral
+fn build_priority_lot(
+    e: &Env,
+    pool: &mut Pool,
+    collateral: &Map<u32, i128>,
+    priority: &Vec<Address>,
+    withdrawn_value: i128,
+) -> Map<Address, i128> {
+    let reserve_list = storage::get_res_list(e);
+    let mut order: Vec<u32> = vec![e];
+    for asset in priority.iter() {
+        let index = storage::get_res_config(e, &asset).index;
+        if collateral.contains_key(index) {
+            order.push_back(index);
+        }
+    }
+    for (index, _) in collateral.iter() {
+        if !order.contains(&index) {
+            order.push_back(index);
+        }
+    }
+
+    let mut lot = map![e];
+    let mut remaining = withdrawn_value;
+    for index in order.iter() {
+        if remaining <= 0 {
+            break;
+        }
+        let asset = reserve_list.get_unchecked(index);
+        let b_tokens = collateral.get_unchecked(index);
+        let reserve = pool.load_reserve(e, &asset);
+        let value = pool
+            .load_price(e, &asset)
+            .fixed_mul_floor(reserve.to_asset_from_b_token(b_tokens), reserve.scalar)
+            .unwrap_optimized();
+        if value <= remaining {
+            lot.set(asset, b_tokens);
+            remaining -= value;
+        } else {
+            let b_tokens_removed = b_tokens.fixed_mul_ceil(remaining, value).unwrap_optimized();
+            lot.set(asset, b_tokens_removed);
+            remaining = 0;
+        }
+    }
+    lot
+}
+
 pub fn fill_user_liq_auction(
     e: &Env,
     pool: &mut Pool,
@@ -129,6 +218,12 @@ pub fn fill_user_liq_auction(
     user_state.rm_positions(e, pool, auction_data.lot.clone(), auction_data.bid.clone());
     filler_state.add_positions(e, pool, auction_data.lot.clone(), auction_data.bid.clone());
     user_state.store(e);
+    emissions::settle_liquidated_emissions(
+        e,
+        &user_state,
+        &filler_state.address,
+        &auction_data.lot,
+    );
 }
 
 #[cfg(test)]
@@ -173,11 +268,16 @@ mod tests {
             bid: map![&e],
             lot: map![&e],
             block: 50,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
@@ -276,6 +376,8 @@ mod tests {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_address, || {
             storage::set_user_positions(&e, &samwise, &positions);
@@ -292,6 +394,112 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_create_user_liquidation_auction_effective_valuation() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let pool_address = Address::random(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta(&e);
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta(&e);
+        reserve_data_1.b_rate = 1_200_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = testutils::default_reserve_meta(&e);
+        reserve_data_2.last_time = 12345;
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_price(&underlying_0, &2_0000000);
+        oracle_client.set_price(&underlying_1, &4_0000000);
+        oracle_client.set_price(&underlying_2, &50_0000000);
+
+        let liq_pct = 45;
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_auction_valuation(&e, &1);
+
+            e.budget().reset_unlimited();
+            let result = create_user_liq_auction_data(&e, &samwise, liq_pct);
+            assert_eq!(result.block, 51);
+            assert_eq!(result.valuation, 1);
+            assert_eq!(result.bid.get_unchecked(underlying_2), 1_2375000);
+            assert_eq!(result.bid.len(), 1);
+            assert_eq!(result.lot.get_unchecked(underlying_0), 36_1995620);
+            assert_eq!(result.lot.get_unchecked(underlying_1), 1_8237157);
+            assert_eq!(result.lot.len(), 2);
+        });
+    }
+
     #[test]
     #[should_panic]
     //#[should_panic(expected = "ContractError(105)")]
@@ -372,6 +580,8 @@ mod tests {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -470,6 +680,8 @@ mod tests {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -569,6 +781,8 @@ mod tests {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -673,11 +887,16 @@ mod tests {
                 (underlying_1.clone(), 1_5395739)
             ],
             block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -837,11 +1056,16 @@ mod tests {
                 (underlying_1.clone(), 1_5395739)
             ],
             block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -878,4 +1102,109 @@ mod tests {
             assert_eq!(samwise_hf, 1_1458978);
         });
     }
+
+    #[test]
+    fn test_create_user_liquidation_auction_priority() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let pool_address = Address::random(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta(&e);
+        reserve_data_0.last_time = 12345;
+        reserve_config_0.c_factor = 0_9500000;
+        reserve_config_0.l_factor = 1_0000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta(&e);
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.c_factor = 0_5000000;
+        reserve_config_1.l_factor = 1_0000000;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = testutils::default_reserve_meta(&e);
+        reserve_data_2.last_time = 12345;
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_9500000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+        e.budget().reset_unlimited();
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &10_0000000);
+        oracle_client.set_price(&underlying_2, &1_0000000);
+
+        let liq_pct = 70;
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 50_0000000),
+                (reserve_config_1.index, 10_0000000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 100_0000000),],
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_liq_priority(&e, &vec![&e, underlying_0.clone()]);
+
+            // the stable collateral is fully seized before the volatile collateral
+            e.budget().reset_unlimited();
+            let result = create_user_liq_auction_data(&e, &samwise, liq_pct);
+            assert_eq!(result.block, 51);
+            assert_eq!(result.bid.get_unchecked(underlying_2), 70_0000000);
+            assert_eq!(result.bid.len(), 1);
+            assert_eq!(result.lot.get_unchecked(underlying_0), 50_0000000);
+            assert_eq!(result.lot.get_unchecked(underlying_1), 3_3387500);
+            assert_eq!(result.lot.len(), 2);
+        });
+    }
 }
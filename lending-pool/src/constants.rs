@@ -8,3 +8,63 @@ pub const SCALAR_7: i128 = 1_0000000;
 
 // seconds per year
 pub const SECONDS_PER_YEAR: i128 = 31536000;
+
+// the minimum number of ledgers between pool heartbeat events
+pub const HEARTBEAT_INTERVAL: u32 = 720;
+
+// the number of heartbeat utilization samples kept for each reserve
+pub const UTIL_HISTORY_LENGTH: u32 = 24;
+
+// the number of config versions kept for each reserve
+pub const RESERVE_CONFIG_HISTORY_LENGTH: u32 = 10;
+
+// the number of fills kept in the recent auction history of each auction type
+pub const AUCTION_HISTORY_LENGTH: u32 = 20;
+
+// seconds per compounding period used when quoting annual percentage yields
+pub const SECONDS_PER_DAY: u64 = 86400;
+
+// the delay before protocol-owned liquidity can be withdrawn from a reserve
+pub const POL_WITHDRAW_TIMELOCK: u64 = 7 * 24 * 60 * 60;
+
+// the delay before a withdrawal above a reserve's large withdrawal threshold can be executed
+pub const LARGE_WITHDRAW_DELAY: u64 = 60 * 60;
+
+// the default health factor a user liquidation can be created at or below, in 7 decimals
+pub const DEFAULT_LIQ_HF_LOW: u32 = 1_0000000;
+
+// the default health factor a user liquidation can be deleted at or above, in 7 decimals
+pub const DEFAULT_LIQ_HF_HIGH: u32 = 1_0000100;
+
+// the version of the pool's storage layout, incremented on any change that requires a migration
+pub const STORAGE_VERSION: u32 = 1;
+
+// the version of the position snapshot format, incremented on any change to its layout
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+// the version of the risk manifest format, incremented on any change to its layout
+pub const RISK_MANIFEST_VERSION: u32 = 1;
+
+// the length of the period a new oracle is read alongside the current oracle before it can be set
+pub const ORACLE_DUAL_READ_PERIOD: u64 = 3 * 24 * 60 * 60;
+
+// the maximum deviation between the current and new oracle's prices during an oracle change, in 7 decimals
+pub const MAX_ORACLE_DEVIATION: i128 = 500_000; // 5%
+
+// the backstop interface before draws were held in escrow, where `draw` returns nothing
+pub const BACKSTOP_INTERFACE_V0: u32 = 0;
+
+// the current backstop interface, where `draw` returns the id of the pending draw
+pub const BACKSTOP_INTERFACE_V1: u32 = 1;
+
+// the request amount that withdraws a user's entire balance of a reserve, regardless of its value
+pub const MAX_AMOUNT: i128 = i128::MAX;
+
+// the reserve pause flag that disables supplying the reserve, as supply or collateral
+pub const PAUSE_SUPPLY: u32 = 1 << 0;
+
+// the reserve pause flag that disables borrowing the reserve
+pub const PAUSE_BORROW: u32 = 1 << 1;
+
+// the reserve pause flag that disables withdrawing the reserve, as supply or collateral
+pub const PAUSE_WITHDRAW: u32 = 1 << 2;
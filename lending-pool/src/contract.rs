@@ -1,9 +1,17 @@
 use crate::{
-    auctions::{self, AuctionData},
-    emissions::{self, ReserveEmissionMetadata},
-    pool::{self, Positions, Request},
+    auctions::{self, AuctionCheck, AuctionData},
+    emissions::{self, ReserveEmissionMetadata, ReserveEmissionTarget},
+    pool::{
+        self, AdminAction, BorrowQuote, PositionRisk, PositionSnapshot, Positions, Request,
+        ReserveRates, RiskManifest, UtilReport,
+    },
+    soft_liquidation,
     storage::{
-        self, PoolConfig, ReserveConfig, ReserveData, ReserveEmissionsConfig, ReserveEmissionsData,
+        self, AttachedEmission, AuctionRecord, AuctionStats, EModeCategory, FillBids,
+        IsolationConfig, PendingOracle, PolWithdrawal, PoolConfig, PoolInitConfig,
+        PriceClampConfig, PriceObservation, QueuedWithdrawal, ReserveConfig, ReserveConfigVersion,
+        ReserveData, ReserveEmissionsConfig, ReserveEmissionsData, ReserveExposure, SoftLiqConfig,
+        SweepConfig, TrustedBorrower, UserHistory, UserVesting,
     },
 };
 use soroban_sdk::{contract, contractimpl, Address, Env, Map, Symbol, Vec};
@@ -15,30 +23,26 @@ use soroban_sdk::{contract, contractimpl, Address, Env, Map, Symbol, Vec};
 pub struct Pool;
 
 pub trait PoolTrait {
-    /// Initialize the pool
+    /// Initialize the pool. Re-invoking with the identical config is a no-op, such that a
+    /// deployment that retries its transactions does not fail on a double initialization.
     ///
     /// ### Arguments
-    /// Creator supplied:
-    /// * `admin` - The Address for the admin
-    /// * `name` - The name of the pool
-    /// * `oracle` - The contract address of the oracle
-    /// * `backstop_take_rate` - The take rate for the backstop in stroops
+    /// * `config` - The config of the pool
+    ///     Creator supplied:
+    ///     * `admin` - The Address for the admin
+    ///     * `name` - The name of the pool
+    ///     * `oracle` - The contract address of the oracle
+    ///     * `bstop_rate` - The take rate for the backstop in stroops
+    ///     * `permissioned` - If only allowlisted users can submit requests and fill auctions
     ///
-    /// Pool Factory supplied:
-    /// * `backstop_id` - The contract address of the pool's backstop module
-    /// * `blnd_id` - The contract ID of the BLND token
-    /// * `usdc_id` - The contract ID of the BLND token
-    #[allow(clippy::too_many_arguments)]
-    fn initialize(
-        e: Env,
-        admin: Address,
-        name: Symbol,
-        oracle: Address,
-        bstop_rate: u64,
-        backstop_id: Address,
-        blnd_id: Address,
-        usdc_id: Address,
-    );
+    ///     Pool Factory supplied:
+    ///     * `backstop` - The contract address of the pool's backstop module
+    ///     * `blnd_id` - The contract ID of the BLND token
+    ///     * `usdc_id` - The contract ID of the USDC token
+    ///
+    /// ### Panics
+    /// If the pool is already initialized with a different config, or the config is invalid
+    fn initialize(e: Env, config: PoolInitConfig);
 
     /// (Admin only) Update the pool
     ///
@@ -49,336 +53,2782 @@ pub trait PoolTrait {
     /// If the caller is not the admin
     fn update_pool(e: Env, backstiop_take_rate: u64);
 
-    /// (Admin only) Initialize a reserve in the pool
+    /// Compare the prices of every reserve from the current and pending oracle. If they
+    /// disagree by more than the maximum deviation, the pending oracle change is blocked.
+    ///
+    /// Returns true if the oracles agree
+    ///
+    /// ### Panics
+    /// If no oracle change is pending
+    fn check_oracle(e: Env) -> bool;
+
+    /// (Admin only) Set the pending oracle as the pool's oracle once the dual-read period
+    /// has passed. Oracle changes are queued with an approved `AdminAction::QueueOracle`, such
+    /// that they require the co-signer's approval, if one is set.
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, no oracle change is pending, the dual-read period has not
+    /// passed, or the oracles disagreed during the dual-read period or disagree now
+    fn set_oracle(e: Env);
+
+    /// (Admin only) Cancel the pending oracle change
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or no oracle change is pending
+    fn cancel_oracle(e: Env);
+
+    /// Fetch the pending oracle change, if one exists
+    fn get_pending_oracle(e: Env) -> Option<PendingOracle>;
+
+    /// (Admin only) Set the maximum leverage a user can take on, as the ratio of their effective
+    /// liabilities to their effective equity, checked whenever a submit requires a health check
     ///
     /// ### Arguments
-    /// * `asset` - The underlying asset to add as a reserve
-    /// * `config` - The ReserveConfig for the reserve
+    /// * `max_leverage` - The maximum leverage, expressed in 7 decimals. 0 removes the limit.
     ///
     /// ### Panics
-    /// If the caller is not the admin or the reserve is already setup
-    fn init_reserve(e: Env, asset: Address, metadata: ReserveConfig);
+    /// If the caller is not the admin or the max leverage is negative
+    fn set_max_leverage(e: Env, max_leverage: i128);
 
-    /// (Admin only) Update a reserve in the pool
+    /// Fetch the maximum leverage a user can take on, if one is set
+    fn get_max_leverage(e: Env) -> Option<i128>;
+
+    /// (Admin only) Set the minimum liability value a user with liabilities can hold, such that
+    /// positions too small to be profitably liquidated cannot be created. Checked whenever a
+    /// submit changes a user's liabilities, and a user can always repay in full.
     ///
     /// ### Arguments
-    /// * `asset` - The underlying asset to add as a reserve
-    /// * `config` - The ReserveConfig for the reserve
+    /// * `min_debt` - The minimum liability value, in the oracle's base asset. 0 removes the
+    ///                minimum.
     ///
     /// ### Panics
-    /// If the caller is not the admin or the reserve does not exist
-    fn update_reserve(e: Env, asset: Address, config: ReserveConfig);
+    /// If the caller is not the admin or the min debt is negative
+    fn set_min_debt(e: Env, min_debt: i128);
 
-    /// Fetch the reserve configuration for a reserve
+    /// Fetch the minimum liability value a user with liabilities can hold, if one is set
+    fn get_min_debt(e: Env) -> Option<i128>;
+
+    /// (Admin only) Set the borrowing limits of users with collateral in an isolated reserve.
+    /// Isolated users can only borrow from the borrowable reserves, and their total liability
+    /// value is capped by the debt cap.
     ///
     /// ### Arguments
-    /// * `asset` - The underlying asset to add as a reserve
-    fn get_reserve_config(e: Env, asset: Address) -> ReserveConfig;
+    /// * `config` - The debt cap, in the oracle's base asset, and the borrowable reserves
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the debt cap is negative, or a borrowable asset is not
+    /// a reserve
+    fn set_isolation_config(e: Env, config: IsolationConfig);
 
-    /// Fetch the reserve data for a reserve
+    /// Fetch the borrowing limits of isolated users, if any are set
+    fn get_isolation_config(e: Env) -> Option<IsolationConfig>;
+
+    /// (Admin only) Set an e-mode category of correlated assets. Users opted into the category
+    /// whose collateral and liabilities are all in the category's assets use the category's
+    /// factors in place of each reserve's.
     ///
     /// ### Arguments
-    /// * `asset` - The underlying asset to add as a reserve
-    fn get_reserve_data(e: Env, asset: Address) -> ReserveData;
+    /// * `category_id` - The id of the e-mode category
+    /// * `category` - The factors and assets of the category. No assets removes the category.
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, an asset is not a reserve, or the factors are invalid
+    fn set_e_mode_category(e: Env, category_id: u32, category: EModeCategory);
 
-    /// Submit a set of requests to the pool where 'from' takes on the position, 'sender' sends any
-    /// required tokens to the pool and 'to' receives any tokens sent from the pool
+    /// Fetch an e-mode category, if it exists
     ///
-    /// Returns the new positions for 'from'
+    /// ### Arguments
+    /// * `category_id` - The id of the e-mode category
+    fn get_e_mode_category(e: Env, category_id: u32) -> Option<EModeCategory>;
+
+    /// Opt into an e-mode category, or out of e-mode with `None`
     ///
     /// ### Arguments
-    /// * `from` - The address of the user whose positions are being modified
-    /// * `spender` - The address of the user who is sending tokens to the pool
-    /// * `to` - The address of the user who is receiving tokens from the pool
-    /// * `requests` - A vec of requests to be processed
+    /// * `user` - The address of the user
+    /// * `category_id` - The id of the e-mode category, or `None` to leave e-mode
     ///
     /// ### Panics
-    /// If the request is not able to be completed for cases like insufficient funds or invalid health factor
-    fn submit(
-        e: Env,
-        from: Address,
-        spender: Address,
-        to: Address,
-        requests: Vec<Request>,
-    ) -> Positions;
+    /// If the user does not authorize the change, the category does not exist, or the user's
+    /// positions are unhealthy after the change
+    fn set_user_e_mode(e: Env, user: Address, category_id: Option<u32>);
 
-    /// Manage bad debt. Debt is considered "bad" if there is no longer has any collateral posted.
+    /// Enable or disable the user's balance of a reserve as collateral. A disabled balance is held
+    /// as uncollateralized supply and does not count towards the user's health factor.
     ///
-    /// To manage a user's bad debt, all collateralized reserves for the user must be liquidated
-    /// before debt can be transferred to the backstop.
+    /// Equivalent to a `submit` of a single enable collateral (14) or disable collateral (15)
+    /// request by the user.
     ///
-    /// To manage a backstop's bad debt, the backstop module must be below a critical threshold
-    /// to allow bad debt to be burnt.
+    /// Returns the new positions for the user
     ///
     /// ### Arguments
-    /// * `user` - The user who currently possesses bad debt
+    /// * `user` - The address of the user
+    /// * `asset` - The underlying asset of the reserve
+    /// * `enabled` - If the user's balance of the reserve is used as collateral
     ///
     /// ### Panics
-    /// If the user has collateral posted
-    fn bad_debt(e: Env, user: Address);
+    /// If the user does not authorize the change, has no balance of the reserve to move, or the
+    /// request fails for any of the reasons a submit would
+    fn set_collateral(e: Env, user: Address, asset: Address, enabled: bool) -> Positions;
 
-    /// Update the pool status based on the backstop state
-    /// * 0 = active - if the minimum backstop deposit has been reached
-    /// * 1 = on ice - if the minimum backstop deposit has not been reached
-    ///                or 25% of backstop deposits are queued for withdrawal
-    /// * 2 = frozen - if 50% of backstop deposits are queued for withdrawal
+    /// Fetch the id of the e-mode category a user has opted into, if any
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_user_e_mode(e: Env, user: Address) -> Option<u32>;
+
+    /// (Admin only) Set the band the price used to value each asset can move within an
+    /// interval, relative to the asset's last observed price. Smooths single ledger oracle
+    /// spikes out of health checks, while sustained moves pass through over several intervals.
+    ///
+    /// ### Arguments
+    /// * `config` - The price clamp config. A max change of 0 removes the clamp.
     ///
     /// ### Panics
-    /// If the pool is currently of status 3, "admin-freeze", where only the admin
-    /// can perform a status update via `set_status`
-    fn update_status(e: Env) -> u32;
+    /// If the caller is not the admin, the max change is over 100%, or the interval is 0
+    fn set_price_clamp_config(e: Env, config: PriceClampConfig);
 
-    /// (Admin only) Pool status is changed to "pool_status"
-    /// * 0 = active
-    /// * 1 = on ice
-    /// * 2 = frozen
-    /// * 3 = admin frozen (only the admin can unfreeze)
+    /// Fetch the price clamp config, if one is set
+    fn get_price_clamp_config(e: Env) -> Option<PriceClampConfig>;
+
+    /// Fetch the last clamped and raw price observed for an asset, if any
     ///
     /// ### Arguments
-    /// * 'pool_status' - The pool status to be set
+    /// * `asset` - The address of the asset
+    fn get_price_observation(e: Env, asset: Address) -> Option<PriceObservation>;
+
+    /// (Admin only) Set the backstop deposits required before borrowing is enabled. Until the
+    /// backstop reaches the floor, the pool is supply-only and borrow requests are rejected.
+    ///
+    /// ### Arguments
+    /// * `min_backstop` - The minimum backstop deposits, in backstop tokens. 0 removes the floor.
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the floor is negative
+    fn set_min_backstop(e: Env, min_backstop: i128);
+
+    /// Fetch the backstop deposits required before borrowing is enabled, if a floor is set
+    fn get_min_backstop(e: Env) -> Option<i128>;
+
+    /// Fetch the backstop tokens that still need to be deposited before borrowing is enabled.
+    /// Returns 0 if no floor is set or the floor has been reached.
+    fn get_backstop_shortfall(e: Env) -> i128;
+
+    /// (Admin only) Set the health factors user liquidations can be created and deleted at.
+    /// A liquidation can only be created at or below `hf_low`, but can only be deleted once the
+    /// user recovers to `hf_high`, preventing auction churn around a single threshold.
+    ///
+    /// ### Arguments
+    /// * `hf_low` - The health factor a liquidation can be created at or below, in 7 decimals
+    /// * `hf_high` - The health factor a liquidation can be deleted at or above, in 7 decimals
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, `hf_low` is zero or above 1, or `hf_high` is not above
+    /// `hf_low`
+    fn set_liquidation_thresholds(e: Env, hf_low: u32, hf_high: u32);
+
+    /// (Admin only) Set if the pool rejects submits that round trip an asset, by supplying and
+    /// then borrowing, or borrowing and then withdrawing, the same asset. Round trips can be used
+    /// to manipulate a reserve within a single transaction, but are also used by legitimate
+    /// strategies, so they are allowed by default.
+    ///
+    /// ### Arguments
+    /// * `block` - If round trips are rejected
     ///
     /// ### Panics
     /// If the caller is not the admin
-    fn set_status(e: Env, pool_status: u32);
+    fn set_block_round_trips(e: Env, block: bool);
 
-    /// Fetch the configuration of the pool
-    fn get_pool_config(e: Env) -> PoolConfig;
+    /// Fetch if the pool rejects submits that round trip an asset
+    fn get_block_round_trips(e: Env) -> bool;
 
-    /********* Emission Functions **********/
+    /// (Admin only) Set the interface version the pool uses to call its backstop, such that
+    /// the pool can interoperate with the previous backstop interface during a staged upgrade
+    ///
+    /// ### Arguments
+    /// * `version` - The backstop interface version, 0 for the interface before draws were
+    ///               held in escrow and 1 for the current interface
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the version is not a known backstop interface
+    fn set_backstop_version(e: Env, version: u32);
 
-    /// Fetch the next emission configuration
-    fn get_emissions_config(e: Env) -> Map<u32, u64>;
+    /// Fetch the interface version the pool uses to call its backstop
+    fn get_backstop_version(e: Env) -> u32;
 
-    /// Update emissions for reserves for the next emission cycle
+    /// (Admin only) Set the co-signer of the pool. Once a co-signer is set, high impact admin
+    /// actions require the approval of both the admin and the co-signer.
     ///
-    /// Needs to be performed each emission cycle, as determined by the expiration
+    /// ### Arguments
+    /// * `co_signer` - The address of the co-signer
     ///
-    /// Returns the expiration timestamp
-    fn update_emissions(e: Env) -> u64;
+    /// ### Panics
+    /// If the caller is not the admin, a co-signer is already set, or the co-signer is the admin
+    fn set_co_signer(e: Env, co_signer: Address);
 
-    /// (Admin only) Set the emission configuration for the pool
+    /// (Admin or co-signer only) Approve a high impact admin action. The action is executed
+    /// once both the admin and the co-signer have approved it.
     ///
-    /// Changes will be applied in the next pool `update_emissions`, and affect the next emission cycle
+    /// Returns true if the action was executed
     ///
     /// ### Arguments
-    /// * `res_emission_metadata` - A vector of ReserveEmissionMetadata to update metadata to
+    /// * `from` - The admin or co-signer approving the action
+    /// * `action` - The action to approve
     ///
     /// ### Panics
-    /// * If the caller is not the admin
-    /// * If the sum of ReserveEmissionMetadata shares is greater than 1
-    fn set_emissions_config(e: Env, res_emission_metadata: Vec<ReserveEmissionMetadata>);
+    /// If the caller is not the admin or co-signer
+    fn approve_action(e: Env, from: Address, action: AdminAction) -> bool;
 
-    /// Claims outstanding emissions for the caller for the given reserve's
-    ///
-    /// Returns the number of tokens claimed
+    /// Revoke an approval for a high impact admin action that has not been executed
     ///
     /// ### Arguments
-    /// * `from` - The address claiming
-    /// * `reserve_token_ids` - Vector of reserve token ids
-    /// * `to` - The Address to send the claimed tokens to
-    fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128;
+    /// * `from` - The address revoking their approval
+    /// * `action` - The action to revoke the approval for
+    ///
+    /// ### Panics
+    /// If the caller has not approved the action
+    fn revoke_approval(e: Env, from: Address, action: AdminAction);
 
-    /***** Reserve Emission Functions *****/
+    /// Fetch the co-signer of the pool, if one is set
+    fn get_co_signer(e: Env) -> Option<Address>;
 
-    /// Fetch the emission details for a given reserve token
+    /// (Admin only) Migrate the pool's storage to the current layout after the pool's wasm
+    /// has been upgraded with an approved `AdminAction::Upgrade`. In-flight auctions are
+    /// migrated along with the pool's configuration.
+    ///
+    /// Returns the storage version the pool was migrated from
     ///
     /// ### Arguments
-    /// * `asset` - The contract address of the asset backing the reserve
-    /// * `token_type` - The type of reserve token (0 for dToken / 1 for bToken)
-    fn get_reserve_emissions(
-        e: Env,
-        asset: Address,
-        token_type: u32,
-    ) -> Option<(ReserveEmissionsConfig, ReserveEmissionsData)>;
+    /// * `auction_users` - The users with an in-flight liquidation auction
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the pool's storage is already at the current version,
+    /// or a user in `auction_users` does not have a liquidation auction
+    fn migrate(e: Env, auction_users: Vec<Address>) -> u32;
 
-    /***** Auction / Liquidation Functions *****/
+    /// Fetch the version of the pool's storage layout
+    fn get_storage_version(e: Env) -> u32;
 
-    /// Creates a new user liquidation auction
+    /// (Admin only) Add or remove a user from the allowlist of a permissioned pool
     ///
     /// ### Arguments
-    /// * `user` - The user getting liquidated through the auction
-    /// * `percent_liquidated` - The percent of the user's position being liquidated as a percentage (15 => 15%)
+    /// * `user` - The address of the user
+    /// * `allowed` - If the user is allowed to submit requests and fill auctions
     ///
     /// ### Panics
-    /// If the user liquidation auction was unable to be created
-    fn new_liquidation_auction(e: Env, user: Address, percent_liquidated: u64) -> AuctionData;
+    /// If the caller is not the admin or the pool is not permissioned
+    fn set_allowed(e: Env, user: Address, allowed: bool);
 
-    /// Delete a user liquidation auction if the user is no longer eligible to be liquidated.
+    /// Fetch if a user can submit requests and fill auctions against the pool
     ///
     /// ### Arguments
-    /// * `user` - The user getting liquidated through the auction
+    /// * `user` - The address of the user
+    fn is_allowed(e: Env, user: Address) -> bool;
+
+    /// (Admin only) Set the denylist contract used to screen addresses, such as a sanctions
+    /// list. Denied addresses cannot submit requests, claim emissions, or fill auctions.
+    ///
+    /// ### Arguments
+    /// * `denylist` - The address of the denylist contract, or None to remove it
     ///
     /// ### Panics
-    /// If the user is still eligible to be liquidated state or the auction doesn't exist
-    fn del_liquidation_auction(e: Env, user: Address);
+    /// If the caller is not the admin
+    fn set_denylist(e: Env, denylist: Option<Address>);
 
-    /// Fetch an auction from the ledger. Returns a quote based on the current block.
+    /// Fetch the denylist contract used to screen addresses, if one is set
+    fn get_denylist(e: Env) -> Option<Address>;
+
+    /// Fetch the addresses that have approved an admin action
     ///
     /// ### Arguments
-    /// * `auction_type` - The type of auction
-    /// * `user` - The Address involved in the auction
+    /// * `action` - The admin action
+    fn get_approvals(e: Env, action: AdminAction) -> Vec<Address>;
+
+    /// (Admin only) Initialize a reserve in the pool
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset to add as a reserve
+    /// * `config` - The ReserveConfig for the reserve
     ///
     /// ### Panics
-    /// If the auction does not exist
-    fn get_auction(e: Env, auction_type: u32, user: Address) -> AuctionData;
+    /// If the caller is not the admin, the reserve is already setup, or the config's decimals
+    /// do not match the underlying token's decimals
+    fn init_reserve(e: Env, asset: Address, metadata: ReserveConfig);
 
-    /// Creates a new auction
+    /// (Admin only) Update a reserve in the pool
     ///
     /// ### Arguments
-    /// * `auction_type` - The type of auction
+    /// * `asset` - The underlying asset to add as a reserve
+    /// * `config` - The ReserveConfig for the reserve
     ///
     /// ### Panics
-    /// If the auction was unable to be created
-    fn new_auction(e: Env, auction_type: u32) -> AuctionData;
-}
+    /// If the caller is not the admin or the reserve does not exist
+    fn update_reserve(e: Env, asset: Address, config: ReserveConfig);
 
-#[contractimpl]
-impl PoolTrait for Pool {
-    #[allow(clippy::too_many_arguments)]
-    fn initialize(
-        e: Env,
-        admin: Address,
-        name: Symbol,
-        oracle: Address,
-        bstop_rate: u64,
-        backstop_id: Address,
-        blnd_id: Address,
-        usdc_id: Address,
-    ) {
-        admin.require_auth();
+    /// (Admin only) Update several reserves in the pool at once. Every config is validated
+    /// before any reserve is updated, such that the change is applied to all of the reserves
+    /// or none of them.
+    ///
+    /// ### Arguments
+    /// * `configs` - The new ReserveConfig of each reserve, by underlying asset
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, no configs are supplied, any of the reserves do not
+    /// exist, or any of the configs are invalid
+    fn update_reserves(e: Env, configs: Map<Address, ReserveConfig>);
+
+    /// (Admin only) Set the liquidation threshold of a reserve. Borrowing is bounded by the
+    /// reserve's collateral factor, while positions are only eligible for liquidation once
+    /// they exceed the liquidation threshold. Reserves without a threshold are liquidated at
+    /// their collateral factor.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `liq_threshold` - The liquidation threshold, expressed in 7 decimals, or 0 to remove it
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, or the threshold is below
+    /// the reserve's collateral factor or above 100%
+    fn set_reserve_liq_threshold(e: Env, asset: Address, liq_threshold: u32);
 
-        pool::execute_initialize(
-            &e,
-            &admin,
-            &name,
-            &oracle,
-            &bstop_rate,
-            &backstop_id,
-            &blnd_id,
-            &usdc_id,
-        );
-    }
+    /// (Admin only) Set an exchange rate adapter for a reserve whose underlying token rebases
+    /// or accrues yield externally. All pool accounting for the reserve is normalized into the
+    /// adapter's share unit.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `adapter` - The contract address of the exchange rate adapter
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, or the reserve
+    /// already has outstanding supply or liabilities
+    fn set_reserve_adapter(e: Env, asset: Address, adapter: Address);
 
-    fn update_pool(e: Env, backstop_take_rate: u64) {
-        storage::bump_instance(&e);
-        let admin = storage::get_admin(&e);
-        admin.require_auth();
+    /// Redeem the caller's b_tokens for their pro-rata share of the underlying tokens held by
+    /// a wound down pool, bypassing the reserve's utilization checks. The reserve's liabilities
+    /// must be settled before any supplier can redeem.
+    ///
+    /// Returns the amount of underlying tokens redeemed
+    ///
+    /// ### Arguments
+    /// * `from` - The address redeeming their b_tokens
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the pool is not wound down, the reserve has outstanding liabilities, the caller has
+    /// outstanding liabilities, or the caller has no b_tokens for the reserve
+    fn redeem_frozen(e: Env, from: Address, asset: Address) -> i128;
 
-        pool::execute_update_pool(&e, backstop_take_rate);
+    /// (Admin only) Set the maximum utilization rate allowed after a withdrawal from a reserve,
+    /// such that a large withdrawal cannot push utilization to a critical level
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `max_util` - The maximum utilization rate after a withdrawal, expressed in 7 decimals,
+    ///                or 0 to remove the cap
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, or the cap is below
+    /// the reserve's max utilization or above 100%
+    fn set_reserve_withdraw_util(e: Env, asset: Address, max_util: u32);
 
-        e.events()
-            .publish((Symbol::new(&e, "update_pool"), admin), backstop_take_rate);
-    }
+    /// Fetch the maximum amount of underlying tokens a user can withdraw from a reserve,
+    /// based on their position and the reserve's maximum utilization after a withdrawal.
+    /// Does not consider the user's health factor.
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `asset` - The underlying asset of the reserve
+    fn get_max_withdrawal(e: Env, user: Address, asset: Address) -> i128;
 
-    fn init_reserve(e: Env, asset: Address, config: ReserveConfig) {
-        storage::bump_instance(&e);
-        let admin = storage::get_admin(&e);
-        admin.require_auth();
+    /// (Admin only) Set the amount of underlying tokens above which a withdrawal from a reserve
+    /// must be queued before it can be submitted, such that large drains are visible before
+    /// they execute. Withdrawals at or below the threshold remain instant.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `threshold` - The amount of underlying tokens, or 0 to remove the threshold
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, or the threshold is negative
+    fn set_reserve_large_withdrawal(e: Env, asset: Address, threshold: i128);
+
+    /// (Admin only) Set the maximum liability value that can be backed by a collateral reserve
+    /// pool-wide, such that a single risky asset cannot back most of the pool's debt. The
+    /// reserve's collateral held by users with liabilities is tracked in blendTokens and valued
+    /// at its effective collateral value when checked. Submits that increase a user's risk are
+    /// rejected while any capped reserve the user holds as collateral is over its cap.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `cap` - The maximum liability value, denominated in the oracle's base asset, or 0 to
+    ///           remove the cap
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, or the cap is negative
+    fn set_reserve_exposure_cap(e: Env, asset: Address, cap: i128);
 
-        pool::initialize_reserve(&e, &asset, &config);
+    /// Fetch the exposure cap of a collateral reserve and the blendTokens held as collateral by
+    /// users with liabilities
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_reserve_exposure(e: Env, asset: Address) -> Option<ReserveExposure>;
 
-        e.events()
-            .publish((Symbol::new(&e, "init_reserve"), admin), asset);
-    }
+    /// (Admin only) Pause supplying, borrowing, or withdrawing a single reserve, independent of
+    /// the pool's status. Repayments and liquidations are never paused.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `flags` - The bit flags of the paused actions, where 1 pauses supplying, 2 pauses
+    ///             borrowing, and 4 pauses withdrawing, or 0 to unpause the reserve
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, or the flags are invalid
+    fn set_reserve_pause_flags(e: Env, asset: Address, flags: u32);
 
-    fn update_reserve(e: Env, asset: Address, config: ReserveConfig) {
-        storage::bump_instance(&e);
-        let admin = storage::get_admin(&e);
-        admin.require_auth();
+    /// Fetch the pause flags of a reserve
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_reserve_pause_flags(e: Env, asset: Address) -> u32;
 
-        pool::execute_update_reserve(&e, &asset, &config);
+    /// (Admin only) Set a one-time origination fee charged on borrows from a reserve. The fee is
+    /// added to the borrower's debt and split between the backstop and the reserve's suppliers
+    /// by the pool's backstop rate.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `fee` - The fee, as a percentage of the borrowed amount expressed in 7 decimals, or 0
+    ///           to remove the fee
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, or the fee is above 100%
+    fn set_reserve_borrow_fee(e: Env, asset: Address, fee: u32);
 
-        e.events()
-            .publish((Symbol::new(&e, "update_reserve"), admin), asset);
-    }
+    /// Fetch the origination fee charged on borrows from a reserve, if one is set
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_reserve_borrow_fee(e: Env, asset: Address) -> Option<u32>;
 
-    fn get_reserve_config(e: Env, asset: Address) -> ReserveConfig {
-        storage::get_res_config(&e, &asset)
-    }
+    /// Queue a withdrawal from a reserve above the reserve's large withdrawal threshold. Once
+    /// the delay has passed, the withdrawal can be submitted and the queued withdrawal is consumed.
+    /// Replaces any existing queued withdrawal for the reserve.
+    ///
+    /// Returns the queued withdrawal
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user
+    /// * `asset` - The underlying asset of the reserve
+    /// * `amount` - The maximum amount of underlying tokens to withdraw
+    ///
+    /// ### Panics
+    /// If the reserve does not exist or the amount is not positive
+    fn queue_withdrawal(e: Env, from: Address, asset: Address, amount: i128) -> QueuedWithdrawal;
 
-    fn get_reserve_data(e: Env, asset: Address) -> ReserveData {
-        storage::get_res_data(&e, &asset)
+    /// Cancel a queued withdrawal from a reserve
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the reserve does not exist or no withdrawal is queued
+    fn cancel_withdrawal(e: Env, from: Address, asset: Address);
+
+    /// Fetch a user's queued withdrawal from a reserve, if one exists
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `asset` - The underlying asset of the reserve
+    fn get_queued_withdrawal(e: Env, user: Address, asset: Address) -> Option<QueuedWithdrawal>;
+
+    /// (Admin only) Supply protocol-owned liquidity from the admin into a reserve. The liquidity
+    /// is tracked separately from user positions, earns interest, and is excluded from the
+    /// reserve's emissions.
+    ///
+    /// Returns the amount of b_tokens minted
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `amount` - The amount of underlying tokens to supply
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, the amount is not positive,
+    /// or supplying is not allowed by the pool status or the reserve's pause flags
+    fn supply_pol(e: Env, asset: Address, amount: i128) -> i128;
+
+    /// (Admin only) Queue a withdrawal of protocol-owned liquidity from a reserve, which can be
+    /// executed once the timelock has passed
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `b_tokens` - The amount of protocol-owned b_tokens to withdraw
+    /// * `to` - The address receiving the underlying tokens
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the amount exceeds the protocol-owned liquidity, or a
+    /// withdrawal is already queued for the reserve
+    fn queue_pol_withdrawal(e: Env, asset: Address, b_tokens: i128, to: Address) -> PolWithdrawal;
+
+    /// (Admin only) Cancel the queued withdrawal of protocol-owned liquidity from a reserve
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or no withdrawal is queued
+    fn cancel_pol_withdrawal(e: Env, asset: Address);
+
+    /// (Admin only) Execute the queued withdrawal of protocol-owned liquidity from a reserve
+    ///
+    /// Returns the amount of underlying tokens withdrawn
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, no withdrawal is queued, the timelock has not passed,
+    /// or the withdrawal would push the reserve's utilization above its maximum
+    fn withdraw_pol(e: Env, asset: Address) -> i128;
+
+    /// Fetch the protocol-owned b_tokens of a reserve
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_pol(e: Env, asset: Address) -> i128;
+
+    /// Fetch the queued withdrawal of protocol-owned liquidity from a reserve, if one exists
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_pol_withdrawal(e: Env, asset: Address) -> Option<PolWithdrawal>;
+
+    /// Fetch the reserve configuration for a reserve
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset to add as a reserve
+    fn get_reserve_config(e: Env, asset: Address) -> ReserveConfig;
+
+    /// Fetch the recent config versions of a reserve, oldest first, along with the ledger
+    /// and address that set each version
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_reserve_config_history(e: Env, asset: Address) -> Vec<ReserveConfigVersion>;
+
+    /// Fetch the reserve data for a reserve
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset to add as a reserve
+    fn get_reserve_data(e: Env, asset: Address) -> ReserveData;
+
+    /// Fetch the current interest rates for a reserve, as annual rates, daily compounded
+    /// annual yields, and per second rates
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_reserve_rates(e: Env, asset: Address) -> ReserveRates;
+
+    /// Quote the utilization, annual borrow rate and origination fee of a reserve after a
+    /// borrow, to preview the impact of the borrow before submitting it
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `amount` - The amount of underlying tokens to borrow
+    ///
+    /// ### Panics
+    /// If the reserve does not exist or the amount is negative
+    fn quote_borrow(e: Env, asset: Address, amount: i128) -> BorrowQuote;
+
+    /// Fetch a report of a reserve's time weighted average utilization against its target
+    /// utilization over the recent heartbeat samples, along with the trajectory of its rate
+    /// modifier, to inform tuning of the reserve's `util` and `reactivity`
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the reserve does not exist
+    fn get_util_report(e: Env, asset: Address) -> UtilReport;
+
+    /// Fetch a versioned manifest of every risk relevant parameter of the pool, including the
+    /// pool's oracle and auction config and each reserve's factors, caps and thresholds, such
+    /// that risk monitors can compare configs across pools and over time with a single call
+    fn get_risk_manifest(e: Env) -> RiskManifest;
+
+    /// Fetch the positions for a user
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_positions(e: Env, user: Address) -> Positions;
+
+    /// Check if a user is using a reserve as collateral
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `reserve_index` - The index of the reserve
+    fn is_collateral(e: Env, user: Address, reserve_index: u32) -> bool;
+
+    /// Check if a user is borrowing from a reserve
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `reserve_index` - The index of the reserve
+    fn is_borrowing(e: Env, user: Address, reserve_index: u32) -> bool;
+
+    /// Fetch the indexes of the reserves a user has any position in, sorted in ascending order
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn active_reserves(e: Env, user: Address) -> Vec<u32>;
+
+    /// Fetch the liquidation risk for a user's positions, including the price drop of each
+    /// collateral asset that would make the user liquidatable and an estimate of the number
+    /// of days of interest accrual until the user is liquidatable
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_position_risk(e: Env, user: Address) -> PositionRisk;
+
+    /// Fetch the history counters for a user, if the user has interacted with the pool
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_user_history(e: Env, user: Address) -> Option<UserHistory>;
+
+    /// (Opt-in) Emit a versioned snapshot of the user's current positions, valued in underlying
+    /// tokens and the oracle's base asset, along with their history counters. The snapshot is
+    /// published as a "position_snapshot" event for consumption by off-chain indexers, such
+    /// as credit scoring services. Returns the snapshot.
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user opting in to the snapshot
+    ///
+    /// ### Panics
+    /// If the user does not authorize the snapshot or a price is unavailable for any of
+    /// their positions
+    fn snapshot_position(e: Env, user: Address) -> PositionSnapshot;
+
+    /// Submit a set of requests to the pool where 'from' takes on the position, 'sender' sends any
+    /// required tokens to the pool and 'to' receives any tokens sent from the pool
+    ///
+    /// A withdraw or withdraw collateral request with an amount of `i128::MAX` withdraws the
+    /// user's entire balance of the reserve at the current b_rate, and a repay request with an
+    /// amount of `i128::MAX` repays the user's entire liability at the current d_rate.
+    ///
+    /// A min tokens (10) or max tokens (11) request bounds the b_tokens or d_tokens minted or
+    /// burnt by the request before it for the same reserve, such as the minimum b_tokens minted
+    /// by a supply or the maximum d_tokens minted by a borrow, protecting the user against
+    /// b_rate and d_rate drift between quoting and execution.
+    ///
+    /// An expiration request (13) with the last ledger sequence the requests can be executed at
+    /// as its amount aborts the submit once the ledger is past it, such that stale transactions
+    /// cannot execute after large rate or price moves. It is checked before any request in the
+    /// batch is processed, wherever it is placed, and its address is ignored.
+    ///
+    /// An enable collateral (14) or disable collateral (15) request moves the user's entire
+    /// supply of the reserve into collateral, or their entire collateral into supply.
+    ///
+    /// Returns the new positions for 'from'
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user whose positions are being modified
+    /// * `spender` - The address of the user who is sending tokens to the pool
+    /// * `to` - The address of the user who is receiving tokens from the pool
+    /// * `requests` - A vec of requests to be processed
+    ///
+    /// ### Panics
+    /// If the request is not able to be completed for cases like insufficient funds or invalid health factor
+    fn submit(
+        e: Env,
+        from: Address,
+        spender: Address,
+        to: Address,
+        requests: Vec<Request>,
+    ) -> Positions;
+
+    /// Move collateral from one reserve to another in a single transaction. The withdrawn
+    /// collateral is sent to the executor contract, which receives an `exec_swap` callback during
+    /// which it must transfer "asset_in" to the pool. The tokens received are supplied as
+    /// collateral for "from", and the health factor is only checked against the final positions.
+    ///
+    /// Returns the new positions for "from"
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user whose collateral is being swapped
+    /// * `executor` - The address of the executor contract performing the swap
+    /// * `asset_out` - The underlying asset of the collateral being withdrawn
+    /// * `amount_out` - The amount of "asset_out" to withdraw
+    /// * `asset_in` - The underlying asset of the collateral being supplied
+    /// * `min_amount_in` - The minimum amount of "asset_in" the executor must transfer to the pool
+    ///
+    /// ### Panics
+    /// If the executor transfers less than "min_amount_in", or the new positions are rejected for
+    /// any of the reasons a submit would be
+    fn swap_collateral(
+        e: Env,
+        from: Address,
+        executor: Address,
+        asset_out: Address,
+        amount_out: i128,
+        asset_in: Address,
+        min_amount_in: i128,
+    ) -> Positions;
+
+    /// Set the amount of underlying tokens "delegate" can borrow from a reserve on behalf of
+    /// "owner" via `submit_delegated`. Setting the allowance to zero removes it.
+    ///
+    /// ### Arguments
+    /// * `owner` - The address of the position owner
+    /// * `delegate` - The address allowed to borrow on the owner's behalf
+    /// * `asset` - The underlying asset of the reserve
+    /// * `amount` - The amount of underlying tokens the delegate can borrow
+    ///
+    /// ### Panics
+    /// If the owner does not authorize the allowance, the owner and delegate are the same, the
+    /// asset is not a reserve, or the amount is negative
+    fn set_borrow_allowance(
+        e: Env,
+        owner: Address,
+        delegate: Address,
+        asset: Address,
+        amount: i128,
+    );
+
+    /// Fetch the amount of underlying tokens "delegate" can borrow from a reserve on behalf
+    /// of "owner"
+    ///
+    /// ### Arguments
+    /// * `owner` - The address of the position owner
+    /// * `delegate` - The address allowed to borrow on the owner's behalf
+    /// * `asset` - The underlying asset of the reserve
+    fn get_borrow_allowance(e: Env, owner: Address, delegate: Address, asset: Address) -> i128;
+
+    /// Submit a set of borrow requests as "delegate" where "owner" takes on the liabilities
+    /// against their collateral and "to" receives the borrowed tokens. Each borrow, along with its
+    /// origination fee, is deducted from the allowance "owner" has granted "delegate" for the
+    /// reserve.
+    ///
+    /// Only borrow (4), max borrow apr (9), min tokens (10), max tokens (11) and expiration (13)
+    /// requests can be delegated.
+    ///
+    /// Returns the new positions for "owner"
+    ///
+    /// ### Arguments
+    /// * `delegate` - The address borrowing on behalf of the owner
+    /// * `owner` - The address of the user whose positions are being modified
+    /// * `to` - The address receiving the borrowed tokens
+    /// * `requests` - A vec of requests to be processed
+    ///
+    /// ### Panics
+    /// If any request cannot be delegated, the borrows and their fees exceed the delegate's
+    /// allowance, the pool is permissioned and the delegate is not on the allowlist, or the
+    /// request is not able to be completed for the reasons a submit would not be
+    fn submit_delegated(
+        e: Env,
+        delegate: Address,
+        owner: Address,
+        to: Address,
+        requests: Vec<Request>,
+    ) -> Positions;
+
+    /// Manage bad debt. Debt is considered "bad" if there is no longer has any collateral posted.
+    ///
+    /// To manage a user's bad debt, all collateralized reserves for the user must be liquidated
+    /// before debt can be transferred to the backstop.
+    ///
+    /// To manage a backstop's bad debt, the backstop module must be below a critical threshold
+    /// to allow bad debt to be burnt.
+    ///
+    /// ### Arguments
+    /// * `user` - The user who currently possesses bad debt
+    ///
+    /// ### Panics
+    /// If the user has collateral posted
+    fn bad_debt(e: Env, user: Address);
+
+    /// Update the pool status based on the backstop state
+    /// * 0 = active - if the minimum backstop deposit and the pool's floor have been reached
+    /// * 1 = on ice - if the minimum backstop deposit or the pool's floor has not been reached
+    ///                or 25% of backstop deposits are queued for withdrawal
+    /// * 2 = frozen - if 50% of backstop deposits are queued for withdrawal
+    ///
+    /// ### Panics
+    /// If the pool is currently of status 3, "admin-freeze", where only the admin
+    /// can perform a status update via `set_status`, or status 4, "wound down"
+    fn update_status(e: Env) -> u32;
+
+    /// (Admin only) Pool status is changed to "pool_status"
+    /// * 0 = active
+    /// * 1 = on ice
+    /// * 2 = frozen
+    /// * 3 = admin frozen (only the admin can unfreeze)
+    ///
+    /// ### Arguments
+    /// * 'pool_status' - The pool status to be set
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the status is invalid, or the pool has been wound down
+    fn set_status(e: Env, pool_status: u32);
+
+    /// (Admin only) Permanently wind down the pool by setting its status to 4, "wound down".
+    /// Borrowing and supplying are disabled and the status can never be changed again.
+    /// Liabilities can still be repaid or liquidated, and suppliers can exit through
+    /// `redeem_frozen` once a reserve's liabilities are settled.
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the pool has already been wound down
+    fn wind_down(e: Env);
+
+    /// (Admin or co-signer only) Freeze interest accrual for every reserve during an incident.
+    /// Interest is accrued up to the current ledger, after which rates are pinned and the time
+    /// spent frozen is excluded from future accrual.
+    ///
+    /// ### Arguments
+    /// * `from` - The admin or co-signer freezing interest accrual
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or co-signer, or interest is already frozen
+    fn freeze_interest(e: Env, from: Address);
+
+    /// (Admin only) Lift the interest freeze, resuming interest accrual from the current ledger
+    ///
+    /// Returns the number of seconds interest accrual was frozen for
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or interest is not frozen
+    fn unfreeze_interest(e: Env) -> u64;
+
+    /// Fetch the timestamp interest accrual was frozen at, if it is frozen
+    fn get_interest_freeze(e: Env) -> Option<u64>;
+
+    /// (Admin only) Set the mode used to scale auctions created after the change over time.
+    /// Auctions already in progress keep the mode they were created with.
+    /// * 0 = ledger sequence (0.5% per block)
+    /// * 1 = ledger timestamp (0.1% per second)
+    ///
+    /// ### Arguments
+    /// * `mode` - The auction modifier mode to be set
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the mode is invalid
+    fn set_auction_mode(e: Env, mode: u32);
+
+    /// (Admin only) Set the valuation used to size user liquidation auctions. The valuation
+    /// is recorded on each auction when it is created.
+    /// * 0 = raw oracle value, with an incentive estimated from the user's average factors
+    /// * 1 = factor weighted value, where the lot covers the effective value of the bid
+    ///
+    /// ### Arguments
+    /// * `valuation` - The auction valuation to be set
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the valuation is invalid
+    fn set_auction_valuation(e: Env, valuation: u32);
+
+    /// (Admin only) Set the order collateral assets are seized in by user liquidations, such
+    /// that less volatile collateral can be seized first. Collateral not in the priority is
+    /// seized after the prioritized collateral, in reserve order. An empty priority seizes all
+    /// collateral pro-rata.
+    ///
+    /// ### Arguments
+    /// * `priority` - The collateral assets, in the order they are seized
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, or an asset is not a reserve or is included more than once
+    fn set_liquidation_priority(e: Env, priority: Vec<Address>);
+
+    /// Fetch the order collateral assets are seized in by user liquidations
+    fn get_liquidation_priority(e: Env) -> Vec<Address>;
+
+    /// Fetch the configuration of the pool
+    fn get_pool_config(e: Env) -> PoolConfig;
+
+    /********* Trusted Borrower Functions **********/
+
+    /// (Admin only) Set or update an uncollateralized credit facility for a trusted borrower.
+    /// Only available in pools built with the `trusted-borrowers` feature.
+    ///
+    /// ### Arguments
+    /// * `borrower` - The address of the borrower
+    /// * `cap` - The maximum uncollateralized liability value, denominated in the oracle's base asset
+    /// * `premium` - The additional interest rate charged on liabilities, expressed in 7 decimals
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the feature is not enabled, or the parameters are invalid
+    fn set_trusted_borrower(e: Env, borrower: Address, cap: i128, premium: u32);
+
+    /// (Admin only) Remove the credit facility for a trusted borrower
+    ///
+    /// ### Arguments
+    /// * `borrower` - The address of the borrower
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the feature is not enabled, or the borrower is not trusted
+    fn remove_trusted_borrower(e: Env, borrower: Address);
+
+    /// Fetch the credit facility for a borrower, if one exists
+    ///
+    /// ### Arguments
+    /// * `borrower` - The address of the borrower
+    fn get_trusted_borrower(e: Env, borrower: Address) -> Option<TrustedBorrower>;
+
+    /********* Emission Functions **********/
+
+    /// Fetch the next emission configuration
+    fn get_emissions_config(e: Env) -> Map<u32, u64>;
+
+    /// Update emissions for reserves for the next emission cycle
+    ///
+    /// Needs to be performed each emission cycle, as determined by the expiration
+    ///
+    /// Returns the expiration timestamp
+    fn update_emissions(e: Env) -> u64;
+
+    /// (Admin only) Set the emission configuration for the pool
+    ///
+    /// Changes will be applied in the next pool `update_emissions`, and affect the next emission cycle
+    ///
+    /// ### Arguments
+    /// * `res_emission_metadata` - A vector of ReserveEmissionMetadata to update metadata to
+    ///
+    /// ### Panics
+    /// * If the caller is not the admin
+    /// * If the sum of ReserveEmissionMetadata shares is greater than 1
+    fn set_emissions_config(e: Env, res_emission_metadata: Vec<ReserveEmissionMetadata>);
+
+    /// (Admin only) Set the emission configuration for the pool such that each reserve token
+    /// approximately receives a target emission APR, based on the current reserve supplies, the
+    /// current prices, and the pool's eps. If the targets require more than the pool's eps, the
+    /// shares are scaled down proportionally.
+    ///
+    /// Changes will be applied in the next pool `update_emissions`, and affect the next emission cycle
+    ///
+    /// Returns the ReserveEmissionMetadata that was set
+    ///
+    /// ### Arguments
+    /// * `targets` - A vector of ReserveEmissionTarget with the target APR of each reserve token
+    ///
+    /// ### Panics
+    /// * If the caller is not the admin
+    /// * If the pool is not receiving emissions or a price is stale
+    fn set_emissions_by_target_apr(
+        e: Env,
+        targets: Vec<ReserveEmissionTarget>,
+    ) -> Vec<ReserveEmissionMetadata>;
+
+    /// (Admin or co-signer only) Pause the pool's emissions, for use when the emission token or
+    /// its distribution has an issue. Emissions are accrued up to the current ledger, after which
+    /// emission indexes are pinned and claims are rejected. Lending operations are not affected.
+    ///
+    /// ### Arguments
+    /// * `from` - The admin or co-signer pausing emissions
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or co-signer, or emissions are already paused
+    fn pause_emissions(e: Env, from: Address);
+
+    /// (Admin only) Lift the emissions pause. Emissions resume accruing from the current ledger,
+    /// such that nothing is emitted for the paused window.
+    ///
+    /// Returns the number of seconds emissions were paused for
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or emissions are not paused
+    fn resume_emissions(e: Env) -> u64;
+
+    /// Fetch the timestamp emissions were paused at, if they are paused
+    fn get_emissions_pause(e: Env) -> Option<u64>;
+
+    /// Claims outstanding emissions for the caller for the given reserve's. Only the given reserve
+    /// tokens are claimed, so emissions for any other reserve tokens continue to accrue.
+    ///
+    /// Returns the number of tokens claimed
+    ///
+    /// ### Arguments
+    /// * `from` - The address claiming
+    /// * `reserve_token_ids` - Vector of reserve token ids
+    /// * `to` - The Address to send the claimed tokens to
+    ///
+    /// ### Panics
+    /// If emissions are paused, or a reserve token id is invalid or included more than once
+    fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128;
+
+    /// Claims outstanding emissions for the caller for a page of the pool's reserve tokens,
+    /// starting at the reserve token id `cursor`. Allows users with positions in many reserves
+    /// to claim across several transactions.
+    ///
+    /// Returns (the number of tokens claimed, the cursor of the next page or None if the last
+    /// reserve token was claimed)
+    ///
+    /// ### Arguments
+    /// * `from` - The address claiming
+    /// * `cursor` - The reserve token id to start the page at
+    /// * `limit` - The maximum number of reserve tokens to claim
+    /// * `to` - The Address to send the claimed tokens to
+    ///
+    /// ### Panics
+    /// If the cursor is past the last reserve token or the limit is zero
+    fn claim_page(
+        e: Env,
+        from: Address,
+        cursor: u32,
+        limit: u32,
+        to: Address,
+    ) -> (i128, Option<u32>);
+
+    /// Claims outstanding emissions for the caller for the given reserve's, and swaps the
+    /// emissions released to the caller for a pool asset through the pool's claim swap adapter.
+    /// The swapped tokens are sent to the caller.
+    ///
+    /// Returns (the number of tokens claimed, the amount of "asset_out" received)
+    ///
+    /// ### Arguments
+    /// * `from` - The address claiming
+    /// * `reserve_token_ids` - Vector of reserve token ids
+    /// * `asset_out` - The underlying asset of the reserve to swap the emissions for
+    /// * `min_amount_out` - The minimum amount of "asset_out" to receive
+    ///
+    /// ### Panics
+    /// If no claim swap adapter is set, "asset_out" is not a reserve, the swap returns less than
+    /// "min_amount_out", or the claim fails
+    fn claim_and_swap(
+        e: Env,
+        from: Address,
+        reserve_token_ids: Vec<u32>,
+        asset_out: Address,
+        min_amount_out: i128,
+    ) -> (i128, i128);
+
+    /// (Admin only) Set the swap adapter claimed emissions can be swapped through
+    ///
+    /// ### Arguments
+    /// * `adapter` - The contract address of the swap adapter
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn set_claim_swap_adapter(e: Env, adapter: Address);
+
+    /// Fetch the swap adapter claimed emissions can be swapped through, if one is set
+    fn get_claim_swap_adapter(e: Env) -> Option<Address>;
+
+    /// Approve an operator to claim emissions on behalf of the caller, such as a vault manager.
+    /// The operator can only claim, and the claimed emissions are always sent to the caller.
+    /// Replaces any existing operator.
+    ///
+    /// ### Arguments
+    /// * `from` - The address approving the operator
+    /// * `operator` - The address of the operator
+    ///
+    /// ### Panics
+    /// If the operator is the caller
+    fn set_claim_operator(e: Env, from: Address, operator: Address);
+
+    /// Remove the operator approved to claim emissions on behalf of the caller
+    ///
+    /// ### Arguments
+    /// * `from` - The address removing their operator
+    fn remove_claim_operator(e: Env, from: Address);
+
+    /// Fetch the operator a user has approved to claim emissions on their behalf, if one exists
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_claim_operator(e: Env, user: Address) -> Option<Address>;
+
+    /// Claims outstanding emissions for a user by their approved operator for the given reserve's.
+    /// The claimed emissions are sent to the user.
+    ///
+    /// Returns the number of tokens claimed
+    ///
+    /// ### Arguments
+    /// * `operator` - The address of the operator
+    /// * `from` - The address of the user being claimed for
+    /// * `reserve_token_ids` - Vector of reserve token ids
+    ///
+    /// ### Panics
+    /// If the operator is not approved by the user, or a reserve token id is invalid or
+    /// included more than once
+    fn claim_for(e: Env, operator: Address, from: Address, reserve_token_ids: Vec<u32>) -> i128;
+
+    /// (Admin only) Set the vesting schedule for claimed emissions. Claimed emissions
+    /// stream linearly to the claimer over the period, after the cliff has passed.
+    ///
+    /// ### Arguments
+    /// * `period` - The length of time claimed emissions stream over, in seconds (0 disables vesting)
+    /// * `cliff` - The length of time before any vested emissions can be released, in seconds
+    /// * `penalty` - The portion of unvested emissions slashed on early exit, expressed in 7 decimals
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the vesting schedule is invalid
+    fn set_vesting_config(e: Env, period: u64, cliff: u64, penalty: u32);
+
+    /// (Admin only) Set how the accrued emissions of a liquidated user's seized collateral
+    /// are settled when a liquidation auction is filled. Only the share of the accrued emissions
+    /// proportional to the seized bTokens is settled.
+    ///
+    /// ### Arguments
+    /// * `mode` - 0 to leave them claimable by the user, 1 to include them in the lot for the filler,
+    ///            or 2 to claim them for the user
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the mode is not supported
+    fn set_liquidation_emissions(e: Env, mode: u32);
+
+    /// Release vested emissions for the caller
+    ///
+    /// Returns the number of tokens released
+    ///
+    /// ### Arguments
+    /// * `from` - The address with vesting emissions
+    /// * `to` - The Address to send the released tokens to
+    fn claim_vested(e: Env, from: Address, to: Address) -> i128;
+
+    /// Exit vesting early for the caller. Vested emissions are released in full and
+    /// the early exit penalty is slashed from the unvested emissions.
+    ///
+    /// Returns the number of tokens released
+    ///
+    /// ### Arguments
+    /// * `from` - The address with vesting emissions
+    /// * `to` - The Address to send the released tokens to
+    fn exit_vesting(e: Env, from: Address, to: Address) -> i128;
+
+    /// Fetch the vesting emissions for a user, if they exist
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_vesting(e: Env, user: Address) -> Option<UserVesting>;
+
+    /// (Admin only) Approve a reward token for attached emissions, with the minimum amount of
+    /// reward tokens a stream must deposit, or remove its approval
+    ///
+    /// ### Arguments
+    /// * `token` - The reward token
+    /// * `min_amount` - The minimum amount a stream must deposit, or None to remove the approval
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the token is a reserve asset, or the minimum amount is
+    /// negative
+    fn set_attached_min(e: Env, token: Address, min_amount: Option<i128>);
+
+    /// Fetch the minimum stream amount of a reward token approved for attached emissions, if
+    /// the token is approved
+    ///
+    /// ### Arguments
+    /// * `token` - The reward token
+    fn get_attached_min(e: Env, token: Address) -> Option<i128>;
+
+    /// Attach an emission stream of an approved reward token to a reserve's b or d token. The
+    /// caller deposits the full amount of reward tokens for the stream (eps * duration), and is
+    /// refunded any amount left unclaimed when the stream is swept.
+    ///
+    /// Returns the id of the attached emission stream
+    ///
+    /// ### Arguments
+    /// * `from` - The address funding the stream
+    /// * `reserve_token_id` - The reserve token to attach the stream to
+    /// * `token` - The reward token being emitted
+    /// * `eps` - The reward tokens emitted per second
+    /// * `duration` - The length of the stream, in seconds
+    ///
+    /// ### Panics
+    /// If the reserve token does not exist, the stream is empty, the reward token is a reserve
+    /// asset or is not approved, the stream is below the token's minimum amount, or the reserve
+    /// token already has the maximum number of unexpired streams attached
+    fn attach_emission(
+        e: Env,
+        from: Address,
+        reserve_token_id: u32,
+        token: Address,
+        eps: u64,
+        duration: u64,
+    ) -> u32;
+
+    /// Claims outstanding attached emissions for the caller for the given reserve's
+    ///
+    /// Returns a map of reward token to the number of tokens claimed
+    ///
+    /// ### Arguments
+    /// * `from` - The address claiming
+    /// * `reserve_token_ids` - Vector of reserve token ids
+    /// * `to` - The Address to send the claimed tokens to
+    fn claim_attached(
+        e: Env,
+        from: Address,
+        reserve_token_ids: Vec<u32>,
+        to: Address,
+    ) -> Map<Address, i128>;
+
+    /// Fetch the emission streams attached to a reserve token
+    ///
+    /// ### Arguments
+    /// * `reserve_token_id` - The reserve token id
+    fn get_attached_emissions(e: Env, reserve_token_id: u32) -> Vec<AttachedEmission>;
+
+    /// Sweep the streams attached to a reserve token that have been expired for longer than the
+    /// claim grace period, refunding any reward tokens left unclaimed to each stream's funder.
+    /// Callable by anyone.
+    ///
+    /// Returns a map of each swept stream id to the amount refunded
+    ///
+    /// ### Arguments
+    /// * `reserve_token_id` - The reserve token id
+    fn sweep_attached(e: Env, reserve_token_id: u32) -> Map<u32, i128>;
+
+    /***** Reserve Emission Functions *****/
+
+    /// Fetch the emission details for a given reserve token
+    ///
+    /// ### Arguments
+    /// * `asset` - The contract address of the asset backing the reserve
+    /// * `token_type` - The type of reserve token (0 for dToken / 1 for bToken)
+    fn get_reserve_emissions(
+        e: Env,
+        asset: Address,
+        token_type: u32,
+    ) -> Option<(ReserveEmissionsConfig, ReserveEmissionsData)>;
+
+    /// Fetch the annualized emission rate for a given reserve token, valuing a year of emissions
+    /// at the current eps against the reserve token's supply using the oracle's prices
+    ///
+    /// Returns the emission APR expressed in 7 decimals
+    ///
+    /// ### Arguments
+    /// * `asset` - The contract address of the asset backing the reserve
+    /// * `res_type` - The type of reserve token (0 for dToken / 1 for bToken)
+    fn get_emission_apr(e: Env, asset: Address, res_type: u32) -> i128;
+
+    /***** Auction / Liquidation Functions *****/
+
+    /// Creates a new user liquidation auction. The user must be at or below the pool's
+    /// `liq_hf_low` health factor.
+    ///
+    /// ### Arguments
+    /// * `user` - The user getting liquidated through the auction
+    /// * `percent_liquidated` - The percent of the user's position being liquidated as a percentage (15 => 15%)
+    ///
+    /// ### Panics
+    /// If the user liquidation auction was unable to be created
+    fn new_liquidation_auction(e: Env, user: Address, percent_liquidated: u64) -> AuctionData;
+
+    /// Delete a user liquidation auction if the user has recovered to at least the pool's
+    /// `liq_hf_high` health factor.
+    ///
+    /// ### Arguments
+    /// * `user` - The user getting liquidated through the auction
+    ///
+    /// ### Panics
+    /// If the user is still eligible to be liquidated state or the auction doesn't exist
+    fn del_liquidation_auction(e: Env, user: Address);
+
+    /// Fetch an auction from the ledger. Returns a quote based on the current block.
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction
+    /// * `user` - The Address involved in the auction
+    ///
+    /// ### Panics
+    /// If the auction does not exist
+    fn get_auction(e: Env, auction_type: u32, user: Address) -> AuctionData;
+
+    /// Fetch the pool's liquidation auction statistics, including the number of auctions
+    /// created and filled, the average number of blocks before a fill, and the total
+    /// value liquidated
+    fn get_auction_stats(e: Env) -> AuctionStats;
+
+    /// Fetch up to the last "count" fills of an auction type, oldest first. Each record holds
+    /// the auction's creation and fill blocks, the oracle value of the reserve assets filled,
+    /// and the user and filler involved. Only a limited number of recent fills are kept.
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction
+    /// * `count` - The maximum number of fills to fetch
+    ///
+    /// ### Panics
+    /// If the auction type is invalid
+    fn get_recent_auctions(e: Env, auction_type: u32, count: u32) -> Vec<AuctionRecord>;
+
+    /// Fill an auction on behalf of an executor contract. The executor receives an `exec_fill`
+    /// callback with the quote for the fill, during which it can fund the fill, and must
+    /// return true to approve it.
+    ///
+    /// Returns the executor's positions after the fill
+    ///
+    /// ### Arguments
+    /// * `executor` - The address of the executor contract filling the auction
+    /// * `auction_type` - The type of auction to fill
+    /// * `user` - The Address involved in the auction
+    /// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
+    ///
+    /// ### Panics
+    /// If the auction does not exist, the executor rejects the quote, or the executor's
+    /// positions are unhealthy after the fill
+    fn fill_with_executor(
+        e: Env,
+        executor: Address,
+        auction_type: u32,
+        user: Address,
+        percent_filled: u64,
+    ) -> Positions;
+
+    /// (Admin only) Set the number of blocks fill bids are collected for before a user
+    /// liquidation auction is filled cooperatively. While set, liquidations can only be filled
+    /// by registering bids and settling them once the window closes, and the auction is split
+    /// between the bidders instead of going to the first filler.
+    ///
+    /// ### Arguments
+    /// * `window` - The number of blocks bids are collected for, or 0 to fill directly
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn set_coop_fill_window(e: Env, window: u32);
+
+    /// Fetch the number of blocks fill bids are collected for, if cooperative fills are enabled
+    fn get_coop_fill_window(e: Env) -> Option<u32>;
+
+    /// Register a bid to fill a percentage of a user liquidation auction when it is settled.
+    /// The first bid for an auction opens the window, and registering again replaces the
+    /// filler's bid.
+    ///
+    /// ### Arguments
+    /// * `filler` - The Address filling the auction
+    /// * `user` - The Address being liquidated
+    /// * `percent_filled` - The percentage being bid for as a number (i.e. 15 => 15%)
+    ///
+    /// ### Panics
+    /// If the filler does not authorize the bid, cooperative fills are not enabled, the auction
+    /// does not exist, the percentage is invalid, or the window is closed but not yet settled
+    fn register_fill_bid(e: Env, filler: Address, user: Address, percent_filled: u64);
+
+    /// Settle the bids registered for a user liquidation auction once the window has closed.
+    /// Bids totalling over 100% are pro-rated, and bidders whose positions would be unhealthy
+    /// after their fill are skipped. Can be called by anyone.
+    ///
+    /// Returns a map of each filler to the fraction of the auction they filled, in 7 decimals
+    ///
+    /// ### Arguments
+    /// * `user` - The Address being liquidated
+    ///
+    /// ### Panics
+    /// If cooperative fills are not enabled, or no bids are waiting to be settled
+    fn settle_fill_bids(e: Env, user: Address) -> Map<Address, i128>;
+
+    /// Fetch the bids registered for a user liquidation auction, if any
+    ///
+    /// ### Arguments
+    /// * `user` - The Address being liquidated
+    fn get_fill_bids(e: Env, user: Address) -> Option<FillBids>;
+
+    /// (Admin only) Set the soft liquidation band of a collateral reserve. Users with a health
+    /// factor within the band can have their collateral gradually converted to repay their
+    /// liabilities at a discount that grows through the band. An `upper_hf` of 0 removes the band.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the collateral reserve
+    /// * `config` - The soft liquidation band
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the reserve does not exist, or the band is invalid
+    fn set_soft_liquidation(e: Env, asset: Address, config: SoftLiqConfig);
+
+    /// Fetch the soft liquidation band of a collateral reserve, if one is set
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the collateral reserve
+    fn get_soft_liquidation(e: Env, asset: Address) -> Option<SoftLiqConfig>;
+
+    /// Soft liquidate a user within the soft liquidation band of a collateral reserve. The filler
+    /// assumes the user's liabilities and receives the user's collateral at the band's discount.
+    ///
+    /// Returns the amount of collateral b_tokens received by the filler
+    ///
+    /// ### Arguments
+    /// * `filler` - The address assuming the liabilities
+    /// * `user` - The user being soft liquidated
+    /// * `collateral_asset` - The underlying asset of the collateral reserve with the band
+    /// * `liability_asset` - The underlying asset of the liability being repaid
+    /// * `d_tokens` - The amount of the user's d_tokens to assume
+    ///
+    /// ### Panics
+    /// If the user is not within the band, the conversion does not improve the user's
+    /// health factor, or the filler is unhealthy afterwards
+    fn soft_liquidate(
+        e: Env,
+        filler: Address,
+        user: Address,
+        collateral_asset: Address,
+        liability_asset: Address,
+        d_tokens: i128,
+    ) -> i128;
+
+    /// Creates a new auction
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction
+    ///
+    /// ### Panics
+    /// If the auction was unable to be created
+    fn new_auction(e: Env, auction_type: u32) -> AuctionData;
+
+    /// Check if an auction can be created, without creating it. Returns the reason the
+    /// auction cannot be created, or `Ready` if it can.
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction
+    /// * `user` - The user being liquidated. Ignored for bad debt and interest auctions, which
+    ///            are created for the backstop.
+    ///
+    /// ### Panics
+    /// If the auction type is invalid
+    fn can_create_auction(e: Env, auction_type: u32, user: Address) -> AuctionCheck;
+
+    /// Claim an auction lot held in escrow after its transfer failed during a fill
+    ///
+    /// Returns the amount of underlying tokens transferred
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the auction filler
+    /// * `asset` - The underlying asset of the lot
+    ///
+    /// ### Panics
+    /// If `from` has nothing in escrow for the asset or the transfer fails
+    fn claim_escrow(e: Env, from: Address, asset: Address) -> i128;
+
+    /// Fetch the auction lots held in escrow for a user, as a map of asset to the
+    /// amount of backstop credit owed
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_escrow(e: Env, user: Address) -> Map<Address, i128>;
+
+    /// (Backstop guardian only) Reverse a bad debt auction fill during the dispute window of the
+    /// backstop draw that paid its lot. The backstop returns the lot to the pool's backstop and the
+    /// liabilities the filler assumed are returned to the backstop, such that both legs of the
+    /// fill are reversed together. Liabilities the filler has since repaid are not returned.
+    ///
+    /// ### Arguments
+    /// * `draw_id` - The id of the backstop draw that paid the fill
+    ///
+    /// ### Panics
+    /// If the caller is not the backstop's guardian, no fill was paid by the draw, or the
+    /// draw's dispute window has passed
+    fn reverse_bad_debt_fill(e: Env, draw_id: u32);
+
+    /// (Admin only) Set the schedule on which accrued backstop credit can be swept to the backstop
+    ///
+    /// ### Arguments
+    /// * `interval` - The minimum time between sweeps, in seconds
+    /// * `threshold` - The backstop credit value that allows a sweep before the interval has
+    ///                 passed, in the oracle's base asset. A threshold of 0 disables early sweeps.
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the threshold is negative
+    fn set_sweep_config(e: Env, interval: u64, threshold: i128);
+
+    /// Fetch the interest sweep config, if one exists
+    fn get_sweep_config(e: Env) -> Option<SweepConfig>;
+
+    /// Distribute the backstop credit accrued by each reserve in kind to the backstop's
+    /// depositors, instead of waiting for an interest auction. Only available with direct
+    /// interest enabled. Callable by anyone once the sweep interval has passed since the last
+    /// sweep, or earlier if the value of the accrued credit exceeds the sweep threshold.
+    ///
+    /// Returns a map of the underlying tokens swept, by asset
+    ///
+    /// ### Panics
+    /// If direct interest is not enabled, no sweep config is set, an interest auction is in
+    /// progress, there is no credit to sweep, the sweep schedule has not been met, or the
+    /// backstop has no depositors
+    fn sweep_interest(e: Env) -> Map<Address, i128>;
+
+    /// Fetch the backstop credit accrued by each reserve that has not been swept or auctioned,
+    /// as a map of asset to underlying tokens
+    fn get_unswept_interest(e: Env) -> Map<Address, i128>;
+
+    /// (Admin only) Set if swept interest is distributed in kind to the backstop's depositors,
+    /// who claim it from the backstop, instead of being auctioned. Interest auctions cannot be
+    /// created while direct interest is enabled.
+    ///
+    /// Enabling direct interest distributes the credit already accrued for interest auctions to
+    /// the backstop's depositors. Disabling it carries any unswept credit over to the next
+    /// interest auction.
+    ///
+    /// ### Arguments
+    /// * `direct` - If swept interest is distributed in kind
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, direct interest is being enabled while an interest auction
+    /// is in progress, or the accrued credit cannot be distributed to the backstop
+    fn set_direct_interest(e: Env, direct: bool);
+
+    /// Fetch if swept interest is distributed in kind to the backstop's depositors
+    fn get_direct_interest(e: Env) -> bool;
+}
+
+#[contractimpl]
+impl PoolTrait for Pool {
+    fn initialize(e: Env, config: PoolInitConfig) {
+        config.admin.require_auth();
+
+        pool::execute_initialize(&e, &config);
+    }
+
+    fn update_pool(e: Env, backstop_take_rate: u64) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_update_pool(&e, backstop_take_rate);
+
+        e.events()
+            .publish((Symbol::new(&e, "update_pool"), admin), backstop_take_rate);
+    }
+
+    fn check_oracle(e: Env) -> bool {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+
+        let agrees = pool::execute_check_oracle(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "check_oracle"),), agrees);
+        agrees
+    }
+
+    fn set_oracle(e: Env) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let oracle = pool::execute_set_oracle(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_oracle"), admin), oracle);
+    }
+
+    fn cancel_oracle(e: Env) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_oracle(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_oracle"), admin), ());
+    }
+
+    // @dev: view
+    fn get_pending_oracle(e: Env) -> Option<PendingOracle> {
+        storage::get_pending_oracle(&e)
+    }
+
+    fn set_max_leverage(e: Env, max_leverage: i128) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_max_leverage(&e, max_leverage);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_max_leverage"), admin), max_leverage);
+    }
+
+    // @dev: view
+    fn get_max_leverage(e: Env) -> Option<i128> {
+        storage::get_max_leverage(&e)
+    }
+
+    fn set_min_debt(e: Env, min_debt: i128) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_min_debt(&e, min_debt);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_min_debt"), admin), min_debt);
+    }
+
+    // @dev: view
+    fn get_min_debt(e: Env) -> Option<i128> {
+        storage::get_min_debt(&e)
+    }
+
+    fn set_isolation_config(e: Env, config: IsolationConfig) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_isolation_config(&e, &config);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_isolation_config"), admin), config);
+    }
+
+    // @dev: view
+    fn get_isolation_config(e: Env) -> Option<IsolationConfig> {
+        storage::get_isolation_config(&e)
+    }
+
+    fn set_e_mode_category(e: Env, category_id: u32, category: EModeCategory) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_e_mode_category(&e, category_id, &category);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_e_mode_category"), admin),
+            (category_id, category),
+        );
+    }
+
+    // @dev: view
+    fn get_e_mode_category(e: Env, category_id: u32) -> Option<EModeCategory> {
+        storage::get_e_mode_category(&e, category_id)
+    }
+
+    fn set_user_e_mode(e: Env, user: Address, category_id: Option<u32>) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        user.require_auth();
+
+        pool::execute_set_user_e_mode(&e, &user, category_id);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_user_e_mode"), user), category_id);
+    }
+
+    fn set_collateral(e: Env, user: Address, asset: Address, enabled: bool) -> Positions {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        user.require_auth();
+
+        pool::execute_set_collateral(&e, &user, &asset, enabled)
+    }
+
+    // @dev: view
+    fn get_user_e_mode(e: Env, user: Address) -> Option<u32> {
+        storage::get_user_e_mode(&e, &user)
+    }
+
+    fn set_price_clamp_config(e: Env, config: PriceClampConfig) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_price_clamp_config(&e, &config);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_price_clamp_config"), admin),
+            (config.max_change, config.interval),
+        );
+    }
+
+    // @dev: view
+    fn get_price_clamp_config(e: Env) -> Option<PriceClampConfig> {
+        storage::get_price_clamp_config(&e)
+    }
+
+    // @dev: view
+    fn get_price_observation(e: Env, asset: Address) -> Option<PriceObservation> {
+        storage::get_price_observation(&e, &asset)
+    }
+
+    fn set_min_backstop(e: Env, min_backstop: i128) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_min_backstop(&e, min_backstop);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_min_backstop"), admin), min_backstop);
+    }
+
+    // @dev: view
+    fn get_min_backstop(e: Env) -> Option<i128> {
+        storage::get_min_backstop(&e)
+    }
+
+    // @dev: view
+    fn get_backstop_shortfall(e: Env) -> i128 {
+        pool::get_backstop_shortfall(&e)
+    }
+
+    fn set_liquidation_thresholds(e: Env, hf_low: u32, hf_high: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_liquidation_thresholds(&e, hf_low, hf_high);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_liquidation_thresholds"), admin),
+            (hf_low, hf_high),
+        );
+    }
+
+    fn set_block_round_trips(e: Env, block: bool) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        storage::set_block_round_trips(&e, &block);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_block_round_trips"), admin), block);
+    }
+
+    // @dev: view
+    fn get_block_round_trips(e: Env) -> bool {
+        storage::get_block_round_trips(&e)
+    }
+
+    fn set_backstop_version(e: Env, version: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_backstop_version(&e, version);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_backstop_version"), admin), version);
+    }
+
+    // @dev: view
+    fn get_backstop_version(e: Env) -> u32 {
+        storage::get_backstop_version(&e)
+    }
+
+    fn set_co_signer(e: Env, co_signer: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_initial_co_signer(&e, &co_signer);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_co_signer"), admin), co_signer);
+    }
+
+    fn approve_action(e: Env, from: Address, action: AdminAction) -> bool {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let executed = pool::execute_approve_action(&e, &from, &action);
+
+        e.events().publish(
+            (Symbol::new(&e, "approve_action"), from),
+            (action, executed),
+        );
+        executed
+    }
+
+    fn revoke_approval(e: Env, from: Address, action: AdminAction) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        pool::execute_revoke_approval(&e, &from, &action);
+
+        e.events()
+            .publish((Symbol::new(&e, "revoke_approval"), from), action);
+    }
+
+    // @dev: view
+    fn get_co_signer(e: Env) -> Option<Address> {
+        storage::get_co_signer(&e)
+    }
+
+    fn migrate(e: Env, auction_users: Vec<Address>) -> u32 {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let from_version = pool::execute_migrate(&e, &auction_users);
+
+        e.events()
+            .publish((Symbol::new(&e, "migrate"), admin), from_version);
+        from_version
+    }
+
+    // @dev: view
+    fn get_storage_version(e: Env) -> u32 {
+        storage::get_storage_version(&e)
+    }
+
+    fn set_allowed(e: Env, user: Address, allowed: bool) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_allowed(&e, &user, allowed);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_allowed"), admin, user), allowed);
+    }
+
+    // @dev: view
+    fn is_allowed(e: Env, user: Address) -> bool {
+        !storage::get_permissioned(&e) || storage::is_allowed(&e, &user)
+    }
+
+    fn set_denylist(e: Env, denylist: Option<Address>) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_denylist(&e, &denylist);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_denylist"), admin), denylist);
+    }
+
+    // @dev: view
+    fn get_denylist(e: Env) -> Option<Address> {
+        storage::get_denylist(&e)
+    }
+
+    // @dev: view
+    fn get_approvals(e: Env, action: AdminAction) -> Vec<Address> {
+        storage::get_action_approvals(&e, &action)
+    }
+
+    fn init_reserve(e: Env, asset: Address, config: ReserveConfig) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::initialize_reserve(&e, &admin, &asset, &config);
+
+        e.events()
+            .publish((Symbol::new(&e, "init_reserve"), admin), asset);
+    }
+
+    fn update_reserve(e: Env, asset: Address, config: ReserveConfig) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_update_reserve(&e, &admin, &asset, &config);
+
+        e.events()
+            .publish((Symbol::new(&e, "update_reserve"), admin), asset);
+    }
+
+    fn update_reserves(e: Env, configs: Map<Address, ReserveConfig>) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_update_reserves(&e, &admin, &configs);
+
+        e.events()
+            .publish((Symbol::new(&e, "update_reserves"), admin), configs.keys());
+    }
+
+    fn set_reserve_liq_threshold(e: Env, asset: Address, liq_threshold: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_reserve_liq_threshold(&e, &asset, liq_threshold);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_reserve_liq_threshold"), admin),
+            (asset, liq_threshold),
+        );
+    }
+
+    fn set_reserve_adapter(e: Env, asset: Address, adapter: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_reserve_exchange_rate_adapter(&e, &asset, &adapter);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_reserve_adapter"), admin),
+            (asset, adapter),
+        );
+    }
+
+    fn redeem_frozen(e: Env, from: Address, asset: Address) -> i128 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let amount_out = pool::execute_redeem_frozen(&e, &from, &asset);
+
+        e.events()
+            .publish((Symbol::new(&e, "redeem_frozen"), asset, from), amount_out);
+        amount_out
+    }
+
+    fn set_reserve_withdraw_util(e: Env, asset: Address, max_util: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_reserve_withdraw_util(&e, &asset, max_util);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_reserve_withdraw_util"), admin),
+            (asset, max_util),
+        );
+    }
+
+    // @dev: view
+    fn get_max_withdrawal(e: Env, user: Address, asset: Address) -> i128 {
+        pool::calc_max_withdrawal(&e, &user, &asset)
+    }
+
+    fn set_reserve_large_withdrawal(e: Env, asset: Address, threshold: i128) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_reserve_large_withdrawal(&e, &asset, threshold);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_reserve_large_withdrawal"), admin),
+            (asset, threshold),
+        );
+    }
+
+    fn set_reserve_exposure_cap(e: Env, asset: Address, cap: i128) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_reserve_exposure_cap(&e, &asset, cap);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_reserve_exposure_cap"), admin),
+            (asset, cap),
+        );
+    }
+
+    // @dev: view
+    fn get_reserve_exposure(e: Env, asset: Address) -> Option<ReserveExposure> {
+        storage::get_res_exposure(&e, &asset)
+    }
+
+    fn set_reserve_pause_flags(e: Env, asset: Address, flags: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_reserve_pause_flags(&e, &asset, flags);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_reserve_pause_flags"), admin),
+            (asset, flags),
+        );
+    }
+
+    // @dev: view
+    fn get_reserve_pause_flags(e: Env, asset: Address) -> u32 {
+        storage::get_res_pause_flags(&e, &asset)
+    }
+
+    fn set_reserve_borrow_fee(e: Env, asset: Address, fee: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_reserve_borrow_fee(&e, &asset, fee);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_reserve_borrow_fee"), admin),
+            (asset, fee),
+        );
+    }
+
+    // @dev: view
+    fn get_reserve_borrow_fee(e: Env, asset: Address) -> Option<u32> {
+        storage::get_res_borrow_fee(&e, &asset)
+    }
+
+    fn queue_withdrawal(e: Env, from: Address, asset: Address, amount: i128) -> QueuedWithdrawal {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let withdrawal = pool::execute_queue_withdrawal(&e, &from, &asset, amount);
+
+        e.events().publish(
+            (Symbol::new(&e, "queue_withdrawal"), from),
+            (asset, withdrawal.clone()),
+        );
+        withdrawal
+    }
+
+    fn cancel_withdrawal(e: Env, from: Address, asset: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        pool::execute_cancel_withdrawal(&e, &from, &asset);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_withdrawal"), from), asset);
+    }
+
+    // @dev: view
+    fn get_queued_withdrawal(e: Env, user: Address, asset: Address) -> Option<QueuedWithdrawal> {
+        let reserve_config = storage::get_res_config(&e, &asset);
+        storage::get_queued_withdrawal(&e, &user, reserve_config.index)
+    }
+
+    fn supply_pol(e: Env, asset: Address, amount: i128) -> i128 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let b_tokens_minted = pool::execute_supply_pol(&e, &admin, &asset, amount);
+
+        e.events().publish(
+            (Symbol::new(&e, "supply_pol"), admin),
+            (asset, amount, b_tokens_minted),
+        );
+        b_tokens_minted
+    }
+
+    fn queue_pol_withdrawal(e: Env, asset: Address, b_tokens: i128, to: Address) -> PolWithdrawal {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let withdrawal = pool::execute_queue_pol_withdrawal(&e, &asset, b_tokens, &to);
+
+        e.events().publish(
+            (Symbol::new(&e, "queue_pol_withdrawal"), admin),
+            (asset, withdrawal.clone()),
+        );
+        withdrawal
+    }
+
+    fn cancel_pol_withdrawal(e: Env, asset: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_pol_withdrawal(&e, &asset);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_pol_withdrawal"), admin), asset);
+    }
+
+    fn withdraw_pol(e: Env, asset: Address) -> i128 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let amount_out = pool::execute_withdraw_pol(&e, &asset);
+
+        e.events().publish(
+            (Symbol::new(&e, "withdraw_pol"), admin),
+            (asset, amount_out),
+        );
+        amount_out
+    }
+
+    // @dev: view
+    fn get_pol(e: Env, asset: Address) -> i128 {
+        storage::get_pol(&e, &asset)
+    }
+
+    // @dev: view
+    fn get_pol_withdrawal(e: Env, asset: Address) -> Option<PolWithdrawal> {
+        storage::get_pol_withdrawal(&e, &asset)
+    }
+
+    fn get_reserve_config(e: Env, asset: Address) -> ReserveConfig {
+        storage::get_res_config(&e, &asset)
+    }
+
+    // @dev: view
+    fn get_reserve_config_history(e: Env, asset: Address) -> Vec<ReserveConfigVersion> {
+        storage::get_res_config_history(&e, &asset)
+    }
+
+    fn get_reserve_data(e: Env, asset: Address) -> ReserveData {
+        storage::get_res_data(&e, &asset)
+    }
+
+    // @dev: view
+    fn get_reserve_rates(e: Env, asset: Address) -> ReserveRates {
+        pool::calculate_reserve_rates(&e, &asset)
+    }
+
+    // @dev: view
+    fn quote_borrow(e: Env, asset: Address, amount: i128) -> BorrowQuote {
+        pool::quote_borrow(&e, &asset, amount)
+    }
+
+    // @dev: view
+    fn get_util_report(e: Env, asset: Address) -> UtilReport {
+        pool::build_util_report(&e, &asset)
+    }
+
+    // @dev: view
+    fn get_risk_manifest(e: Env) -> RiskManifest {
+        pool::build_risk_manifest(&e)
+    }
+
+    // @dev: view
+    fn get_positions(e: Env, user: Address) -> Positions {
+        storage::get_user_positions(&e, &user)
+    }
+
+    // @dev: view
+    fn is_collateral(e: Env, user: Address, reserve_index: u32) -> bool {
+        storage::get_user_positions(&e, &user).is_collateral(reserve_index)
+    }
+
+    // @dev: view
+    fn is_borrowing(e: Env, user: Address, reserve_index: u32) -> bool {
+        storage::get_user_positions(&e, &user).is_borrowing(reserve_index)
+    }
+
+    // @dev: view
+    fn active_reserves(e: Env, user: Address) -> Vec<u32> {
+        storage::get_user_positions(&e, &user).active_reserves(&e)
+    }
+
+    // @dev: view
+    fn get_position_risk(e: Env, user: Address) -> PositionRisk {
+        let mut pool = pool::Pool::load(&e);
+        let positions = storage::get_user_positions(&e, &user);
+        pool::PositionData::calculate_risk(
+            &e,
+            &mut pool,
+            &user,
+            &positions,
+            pool::get_trusted_credit(&e, &user),
+        )
+    }
+
+    // @dev: view
+    fn get_user_history(e: Env, user: Address) -> Option<UserHistory> {
+        storage::get_user_history(&e, &user)
+    }
+
+    fn snapshot_position(e: Env, user: Address) -> PositionSnapshot {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        user.require_auth();
+
+        let snapshot = pool::build_position_snapshot(&e, &user);
+
+        e.events().publish(
+            (Symbol::new(&e, "position_snapshot"), user, snapshot.version),
+            snapshot.clone(),
+        );
+        snapshot
+    }
+
+    fn submit(
+        e: Env,
+        from: Address,
+        spender: Address,
+        to: Address,
+        requests: Vec<Request>,
+    ) -> Positions {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+        if from != spender {
+            spender.require_auth();
+        }
+
+        pool::execute_submit(&e, &from, &spender, &to, requests)
+    }
+
+    fn swap_collateral(
+        e: Env,
+        from: Address,
+        executor: Address,
+        asset_out: Address,
+        amount_out: i128,
+        asset_in: Address,
+        min_amount_in: i128,
+    ) -> Positions {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let positions = pool::execute_collateral_swap(
+            &e,
+            &from,
+            &executor,
+            &asset_out,
+            amount_out,
+            &asset_in,
+            min_amount_in,
+        );
+
+        e.events().publish(
+            (Symbol::new(&e, "swap_collateral"), from),
+            (asset_out, amount_out, asset_in, executor),
+        );
+        positions
+    }
+
+    fn set_borrow_allowance(
+        e: Env,
+        owner: Address,
+        delegate: Address,
+        asset: Address,
+        amount: i128,
+    ) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        owner.require_auth();
+
+        pool::set_borrow_allowance(&e, &owner, &delegate, &asset, amount);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_borrow_allowance"), owner, delegate),
+            (asset, amount),
+        );
+    }
+
+    // @dev: view
+    fn get_borrow_allowance(e: Env, owner: Address, delegate: Address, asset: Address) -> i128 {
+        storage::get_borrow_allowance(&e, &owner, &delegate, &asset)
+    }
+
+    fn submit_delegated(
+        e: Env,
+        delegate: Address,
+        owner: Address,
+        to: Address,
+        requests: Vec<Request>,
+    ) -> Positions {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        delegate.require_auth();
+
+        let positions = pool::execute_submit_delegated(&e, &delegate, &owner, &to, requests);
+
+        e.events()
+            .publish((Symbol::new(&e, "submit_delegated"), owner, delegate), to);
+        positions
+    }
+
+    fn bad_debt(e: Env, user: Address) {
+        pool::emit_heartbeat(&e);
+        pool::transfer_bad_debt_to_backstop(&e, &user);
+    }
+
+    fn update_status(e: Env) -> u32 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let new_status = pool::execute_update_pool_status(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_status"),), new_status);
+        new_status
+    }
+
+    fn set_status(e: Env, pool_status: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_pool_status(&e, pool_status);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_status"), admin), pool_status);
+    }
+
+    fn wind_down(e: Env) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_wind_down(&e);
+
+        e.events().publish((Symbol::new(&e, "wind_down"), admin), ());
+    }
+
+    fn freeze_interest(e: Env, from: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        pool::execute_freeze_interest(&e, &from);
+
+        e.events()
+            .publish((Symbol::new(&e, "freeze_interest"), from), ());
+    }
+
+    fn unfreeze_interest(e: Env) -> u64 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let frozen_time = pool::execute_unfreeze_interest(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "unfreeze_interest"), admin), frozen_time);
+        frozen_time
+    }
+
+    // @dev: view
+    fn get_interest_freeze(e: Env) -> Option<u64> {
+        storage::get_interest_freeze(&e)
+    }
+
+    fn set_auction_mode(e: Env, mode: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        auctions::set_auction_mode(&e, mode);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_auction_mode"), admin), mode);
+    }
+
+    fn set_auction_valuation(e: Env, valuation: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        auctions::set_auction_valuation(&e, valuation);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_auction_valuation"), admin), valuation);
+    }
+
+    fn set_liquidation_priority(e: Env, priority: Vec<Address>) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        auctions::set_liquidation_priority(&e, priority.clone());
+
+        e.events().publish(
+            (Symbol::new(&e, "set_liquidation_priority"), admin),
+            priority,
+        );
+    }
+
+    // @dev: view
+    fn get_liquidation_priority(e: Env) -> Vec<Address> {
+        storage::get_liq_priority(&e)
+    }
+
+    fn get_pool_config(e: Env) -> PoolConfig {
+        storage::get_pool_config(&e)
+    }
+
+    /********* Trusted Borrower Functions **********/
+
+    fn set_trusted_borrower(e: Env, borrower: Address, cap: i128, premium: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_trusted_borrower(&e, &borrower, cap, premium);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_trusted_borrower"), admin),
+            (borrower, cap, premium),
+        );
+    }
+
+    fn remove_trusted_borrower(e: Env, borrower: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::remove_trusted_borrower(&e, &borrower);
+
+        e.events().publish(
+            (Symbol::new(&e, "remove_trusted_borrower"), admin),
+            borrower,
+        );
+    }
+
+    fn get_trusted_borrower(e: Env, borrower: Address) -> Option<TrustedBorrower> {
+        storage::get_trusted_borrower(&e, &borrower)
+    }
+
+    /********* Emission Functions **********/
+
+    // @dev: view
+    fn get_emissions_config(e: Env) -> Map<u32, u64> {
+        storage::get_pool_emissions(&e)
+    }
+
+    fn update_emissions(e: Env) -> u64 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let next_expiration = pool::update_pool_emissions(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "update_emissions"),), next_expiration);
+        next_expiration
+    }
+
+    fn set_emissions_config(e: Env, res_emission_metadata: Vec<ReserveEmissionMetadata>) {
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        emissions::set_pool_emissions(&e, res_emission_metadata);
+    }
+
+    fn set_emissions_by_target_apr(
+        e: Env,
+        targets: Vec<ReserveEmissionTarget>,
+    ) -> Vec<ReserveEmissionMetadata> {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let res_emission_metadata = pool::set_emissions_by_target_apr(&e, targets);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_emissions_by_target_apr"), admin),
+            res_emission_metadata.clone(),
+        );
+        res_emission_metadata
+    }
+
+    fn pause_emissions(e: Env, from: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        emissions::execute_pause_emissions(&e, &from);
+
+        e.events()
+            .publish((Symbol::new(&e, "pause_emissions"), from), ());
+    }
+
+    fn resume_emissions(e: Env) -> u64 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let paused_time = emissions::execute_resume_emissions(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "resume_emissions"), admin), paused_time);
+        paused_time
+    }
+
+    // @dev: view
+    fn get_emissions_pause(e: Env) -> Option<u64> {
+        storage::get_emissions_pause(&e)
+    }
+
+    fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let amount_claimed = emissions::execute_claim(&e, &from, &reserve_token_ids, &to);
+
+        e.events().publish(
+            (Symbol::new(&e, "claim"), from),
+            (reserve_token_ids, amount_claimed),
+        );
+
+        amount_claimed
     }
 
-    fn submit(
+    fn claim_page(
         e: Env,
         from: Address,
-        spender: Address,
+        cursor: u32,
+        limit: u32,
         to: Address,
-        requests: Vec<Request>,
-    ) -> Positions {
+    ) -> (i128, Option<u32>) {
         storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
         from.require_auth();
-        if from != spender {
-            spender.require_auth();
-        }
 
-        pool::execute_submit(&e, &from, &spender, &to, requests)
-    }
+        let (amount_claimed, next_cursor) =
+            emissions::execute_claim_page(&e, &from, cursor, limit, &to);
 
-    fn bad_debt(e: Env, user: Address) {
-        pool::transfer_bad_debt_to_backstop(&e, &user);
+        e.events().publish(
+            (Symbol::new(&e, "claim_page"), from),
+            (cursor, amount_claimed),
+        );
+
+        (amount_claimed, next_cursor)
     }
 
-    fn update_status(e: Env) -> u32 {
+    fn claim_and_swap(
+        e: Env,
+        from: Address,
+        reserve_token_ids: Vec<u32>,
+        asset_out: Address,
+        min_amount_out: i128,
+    ) -> (i128, i128) {
         storage::bump_instance(&e);
-        let new_status = pool::execute_update_pool_status(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
 
-        e.events()
-            .publish((Symbol::new(&e, "set_status"),), new_status);
-        new_status
+        let (amount_claimed, amount_out) = emissions::execute_claim_and_swap(
+            &e,
+            &from,
+            &reserve_token_ids,
+            &asset_out,
+            min_amount_out,
+        );
+
+        e.events().publish(
+            (Symbol::new(&e, "claim_and_swap"), from),
+            (reserve_token_ids, amount_claimed, asset_out, amount_out),
+        );
+
+        (amount_claimed, amount_out)
     }
 
-    fn set_status(e: Env, pool_status: u32) {
+    fn set_claim_swap_adapter(e: Env, adapter: Address) {
         storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
         let admin = storage::get_admin(&e);
         admin.require_auth();
 
-        pool::set_pool_status(&e, pool_status);
+        storage::set_claim_swap_adapter(&e, &adapter);
 
         e.events()
-            .publish((Symbol::new(&e, "set_status"), admin), pool_status);
+            .publish((Symbol::new(&e, "set_claim_swap_adapter"), admin), adapter);
     }
 
-    fn get_pool_config(e: Env) -> PoolConfig {
-        storage::get_pool_config(&e)
+    // @dev: view
+    fn get_claim_swap_adapter(e: Env) -> Option<Address> {
+        storage::get_claim_swap_adapter(&e)
     }
 
-    /********* Emission Functions **********/
+    fn set_claim_operator(e: Env, from: Address, operator: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        emissions::set_claim_operator(&e, &from, &operator);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_claim_operator"), from), operator);
+    }
+
+    fn remove_claim_operator(e: Env, from: Address) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        storage::del_claim_operator(&e, &from);
+
+        e.events()
+            .publish((Symbol::new(&e, "remove_claim_operator"), from), ());
+    }
 
     // @dev: view
-    fn get_emissions_config(e: Env) -> Map<u32, u64> {
-        storage::get_pool_emissions(&e)
+    fn get_claim_operator(e: Env, user: Address) -> Option<Address> {
+        storage::get_claim_operator(&e, &user)
     }
 
-    fn update_emissions(e: Env) -> u64 {
+    fn claim_for(e: Env, operator: Address, from: Address, reserve_token_ids: Vec<u32>) -> i128 {
         storage::bump_instance(&e);
-        let next_expiration = pool::update_pool_emissions(&e);
+        pool::emit_heartbeat(&e);
+        operator.require_auth();
+
+        let amount_claimed = emissions::execute_claim_for(&e, &operator, &from, &reserve_token_ids);
+
+        e.events().publish(
+            (Symbol::new(&e, "claim"), from),
+            (reserve_token_ids, amount_claimed),
+        );
+
+        amount_claimed
+    }
+
+    fn set_vesting_config(e: Env, period: u64, cliff: u64, penalty: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        emissions::set_vesting_config(&e, period, cliff, penalty);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_vesting_config"), admin),
+            (period, cliff, penalty),
+        );
+    }
+
+    fn set_liquidation_emissions(e: Env, mode: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        emissions::set_liquidation_emissions(&e, mode);
 
         e.events()
-            .publish((Symbol::new(&e, "update_emissions"),), next_expiration);
-        next_expiration
+            .publish((Symbol::new(&e, "set_liquidation_emissions"), admin), mode);
     }
 
-    fn set_emissions_config(e: Env, res_emission_metadata: Vec<ReserveEmissionMetadata>) {
+    fn claim_vested(e: Env, from: Address, to: Address) -> i128 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let amount_released = emissions::execute_claim_vested(&e, &from, &to);
+
+        e.events()
+            .publish((Symbol::new(&e, "claim_vested"), from), amount_released);
+
+        amount_released
+    }
+
+    fn exit_vesting(e: Env, from: Address, to: Address) -> i128 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let amount_released = emissions::execute_exit_vesting(&e, &from, &to);
+
+        e.events()
+            .publish((Symbol::new(&e, "exit_vesting"), from), amount_released);
+
+        amount_released
+    }
+
+    // @dev: view
+    fn get_vesting(e: Env, user: Address) -> Option<UserVesting> {
+        storage::get_user_vesting(&e, &user)
+    }
+
+    fn set_attached_min(e: Env, token: Address, min_amount: Option<i128>) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
         let admin = storage::get_admin(&e);
         admin.require_auth();
 
-        emissions::set_pool_emissions(&e, res_emission_metadata);
+        emissions::set_attached_min(&e, &token, min_amount);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_attached_min"), admin),
+            (token, min_amount),
+        );
     }
 
-    fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128 {
+    // @dev: view
+    fn get_attached_min(e: Env, token: Address) -> Option<i128> {
+        storage::get_attached_min(&e, &token)
+    }
+
+    fn attach_emission(
+        e: Env,
+        from: Address,
+        reserve_token_id: u32,
+        token: Address,
+        eps: u64,
+        duration: u64,
+    ) -> u32 {
         storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
         from.require_auth();
 
-        let amount_claimed = emissions::execute_claim(&e, &from, &reserve_token_ids, &to);
+        let stream_id =
+            emissions::attach_emission(&e, &from, reserve_token_id, &token, eps, duration);
 
         e.events().publish(
-            (Symbol::new(&e, "claim"), from),
-            (reserve_token_ids, amount_claimed),
+            (Symbol::new(&e, "attach_emission"), from),
+            (reserve_token_id, token, eps, duration, stream_id),
         );
 
-        amount_claimed
+        stream_id
+    }
+
+    fn claim_attached(
+        e: Env,
+        from: Address,
+        reserve_token_ids: Vec<u32>,
+        to: Address,
+    ) -> Map<Address, i128> {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let amounts_claimed = emissions::execute_claim_attached(&e, &from, &reserve_token_ids, &to);
+
+        e.events().publish(
+            (Symbol::new(&e, "claim_attached"), from),
+            (reserve_token_ids, amounts_claimed.clone()),
+        );
+
+        amounts_claimed
+    }
+
+    // @dev: view
+    fn get_attached_emissions(e: Env, reserve_token_id: u32) -> Vec<AttachedEmission> {
+        storage::get_attached_emissions(&e, &reserve_token_id)
+    }
+
+    fn sweep_attached(e: Env, reserve_token_id: u32) -> Map<u32, i128> {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+
+        let refunded = emissions::execute_sweep_attached(&e, reserve_token_id);
+
+        e.events().publish(
+            (Symbol::new(&e, "sweep_attached"), reserve_token_id),
+            refunded.clone(),
+        );
+        refunded
     }
 
     // @dev: view
@@ -390,9 +2840,15 @@ impl PoolTrait for Pool {
         emissions::get_reserve_emissions(&e, &asset, token_type)
     }
 
+    // @dev: view
+    fn get_emission_apr(e: Env, asset: Address, res_type: u32) -> i128 {
+        emissions::calc_emission_apr(&e, &asset, res_type)
+    }
+
     /***** Auction / Liquidation Functions *****/
 
     fn new_liquidation_auction(e: Env, user: Address, percent_liquidated: u64) -> AuctionData {
+        pool::emit_heartbeat(&e);
         let auction_data = auctions::create_liquidation(&e, &user, percent_liquidated);
 
         e.events().publish(
@@ -403,6 +2859,7 @@ impl PoolTrait for Pool {
     }
 
     fn del_liquidation_auction(e: Env, user: Address) {
+        pool::emit_heartbeat(&e);
         auctions::delete_liquidation(&e, &user);
 
         e.events()
@@ -410,11 +2867,141 @@ impl PoolTrait for Pool {
     }
 
     fn get_auction(e: Env, auction_type: u32, user: Address) -> AuctionData {
-        storage::get_auction(&e, &auction_type, &user)
+        auctions::load_auction(&e, auction_type, &user)
+    }
+
+    // @dev: view
+    fn get_auction_stats(e: Env) -> AuctionStats {
+        storage::get_auction_stats(&e)
+    }
+
+    // @dev: view
+    fn get_recent_auctions(e: Env, auction_type: u32, count: u32) -> Vec<AuctionRecord> {
+        auctions::get_recent_auctions(&e, auction_type, count)
+    }
+
+    fn fill_with_executor(
+        e: Env,
+        executor: Address,
+        auction_type: u32,
+        user: Address,
+        percent_filled: u64,
+    ) -> Positions {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        executor.require_auth();
+
+        let positions = auctions::execute_fill_with_executor(
+            &e,
+            &executor,
+            auction_type,
+            &user,
+            percent_filled,
+        );
+
+        e.events().publish(
+            (Symbol::new(&e, "fill_auction"), user, auction_type),
+            (executor, percent_filled as i128),
+        );
+        positions
+    }
+
+    fn set_coop_fill_window(e: Env, window: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        auctions::set_coop_fill_window(&e, window);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_coop_fill_window"), admin), window);
+    }
+
+    // @dev: view
+    fn get_coop_fill_window(e: Env) -> Option<u32> {
+        storage::get_coop_fill_window(&e)
+    }
+
+    fn register_fill_bid(e: Env, filler: Address, user: Address, percent_filled: u64) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        filler.require_auth();
+
+        auctions::execute_register_fill_bid(&e, &filler, &user, percent_filled);
+
+        e.events().publish(
+            (Symbol::new(&e, "register_fill_bid"), user),
+            (filler, percent_filled),
+        );
+    }
+
+    fn settle_fill_bids(e: Env, user: Address) -> Map<Address, i128> {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+
+        let filled = auctions::execute_settle_fill_bids(&e, &user);
+
+        e.events()
+            .publish((Symbol::new(&e, "settle_fill_bids"), user), filled.clone());
+        filled
+    }
+
+    // @dev: view
+    fn get_fill_bids(e: Env, user: Address) -> Option<FillBids> {
+        storage::get_fill_bids(&e, &user)
+    }
+
+    fn set_soft_liquidation(e: Env, asset: Address, config: SoftLiqConfig) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        soft_liquidation::set_soft_liquidation(&e, &asset, &config);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_soft_liquidation"), admin, asset),
+            config,
+        );
+    }
+
+    // @dev: view
+    fn get_soft_liquidation(e: Env, asset: Address) -> Option<SoftLiqConfig> {
+        storage::get_soft_liq_config(&e, &asset)
+    }
+
+    fn soft_liquidate(
+        e: Env,
+        filler: Address,
+        user: Address,
+        collateral_asset: Address,
+        liability_asset: Address,
+        d_tokens: i128,
+    ) -> i128 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        filler.require_auth();
+
+        let b_tokens = soft_liquidation::execute_soft_liquidate(
+            &e,
+            &filler,
+            &user,
+            &collateral_asset,
+            &liability_asset,
+            d_tokens,
+        );
+
+        e.events().publish(
+            (Symbol::new(&e, "soft_liquidate"), user, filler),
+            (collateral_asset, b_tokens, liability_asset, d_tokens),
+        );
+        b_tokens
     }
 
     fn new_auction(e: Env, auction_type: u32) -> AuctionData {
         storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
         let auction_data = auctions::create(&e, auction_type);
 
         e.events().publish(
@@ -424,4 +3011,85 @@ impl PoolTrait for Pool {
 
         auction_data
     }
+
+    // @dev: view
+    fn can_create_auction(e: Env, auction_type: u32, user: Address) -> AuctionCheck {
+        auctions::can_create_auction(&e, auction_type, &user)
+    }
+
+    fn claim_escrow(e: Env, from: Address, asset: Address) -> i128 {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        from.require_auth();
+
+        let amount = auctions::execute_claim_escrow(&e, &from, &asset);
+
+        e.events()
+            .publish((Symbol::new(&e, "claim_escrow"), from), (asset, amount));
+        amount
+    }
+
+    // @dev: view
+    fn get_escrow(e: Env, user: Address) -> Map<Address, i128> {
+        storage::get_user_escrow(&e, &user)
+    }
+
+    fn reverse_bad_debt_fill(e: Env, draw_id: u32) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+
+        let fill = auctions::execute_reverse_bad_debt_fill(&e, draw_id);
+
+        e.events().publish(
+            (Symbol::new(&e, "reverse_bad_debt_fill"), fill.filler),
+            (draw_id, fill.bid),
+        );
+    }
+
+    fn set_sweep_config(e: Env, interval: u64, threshold: i128) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_sweep_config(&e, interval, threshold);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_sweep_config"), admin),
+            (interval, threshold),
+        );
+    }
+
+    // @dev: view
+    fn get_sweep_config(e: Env) -> Option<SweepConfig> {
+        storage::get_sweep_config(&e)
+    }
+
+    fn sweep_interest(e: Env) -> Map<Address, i128> {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        pool::execute_sweep_interest(&e)
+    }
+
+    // @dev: view
+    fn get_unswept_interest(e: Env) -> Map<Address, i128> {
+        pool::get_unswept_interest(&e)
+    }
+
+    fn set_direct_interest(e: Env, direct: bool) {
+        storage::bump_instance(&e);
+        pool::emit_heartbeat(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::set_direct_interest(&e, direct);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_direct_interest"), admin), direct);
+    }
+
+    // @dev: view
+    fn get_direct_interest(e: Env) -> bool {
+        storage::get_direct_interest(&e)
+    }
 }
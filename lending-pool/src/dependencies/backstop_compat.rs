@@ -0,0 +1,158 @@
+use soroban_sdk::{contractclient, panic_with_error, Address, Env};
+
+use crate::{
+    constants::{BACKSTOP_INTERFACE_V0, BACKSTOP_INTERFACE_V1},
+    errors::PoolError,
+    storage,
+};
+
+use super::backstop::{Client as BackstopClient, PoolBalance};
+
+/// The backstop interface before draws were held in escrow. Only the methods whose signature
+/// differs from the current interface are included.
+#[contractclient(name = "BackstopV0Client")]
+pub trait BackstopV0Trait {
+    /// Take backstop token from a pools backstop and transfer them to `to` immediately
+    fn draw(env: Env, pool_address: Address, amount: i128, to: Address);
+}
+
+/// A backstop client that calls the pool's backstop through the interface version stored
+/// for the pool, such that the pool and backstop can be upgraded independently
+pub struct VersionedBackstopClient {
+    env: Env,
+    address: Address,
+    version: u32,
+}
+
+impl VersionedBackstopClient {
+    /// Create a client for the backstop using the pool's stored interface version
+    ///
+    /// ### Arguments
+    /// * `address` - The address of the backstop
+    pub fn new(e: &Env, address: &Address) -> Self {
+        VersionedBackstopClient {
+            env: e.clone(),
+            address: address.clone(),
+            version: storage::get_backstop_version(e),
+        }
+    }
+
+    /// Fetch the backstop token for the backstop
+    pub fn backstop_token(&self) -> Address {
+        BackstopClient::new(&self.env, &self.address).backstop_token()
+    }
+
+    /// Fetch the balance of backstop tokens and shares held for a pool
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    pub fn pool_balance(&self, pool: &Address) -> PoolBalance {
+        BackstopClient::new(&self.env, &self.address).pool_balance(pool)
+    }
+
+    /// Fetch the EPS (emissions per second) and expiration for the current distribution
+    /// window of a pool
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    pub fn pool_eps(&self, pool: &Address) -> (i128, u64) {
+        BackstopClient::new(&self.env, &self.address).pool_eps(pool)
+    }
+
+    /// Draw backstop tokens from a pool's backstop to `to`
+    ///
+    /// Returns the id of the pending draw, or None if the backstop transferred the tokens
+    /// immediately
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    /// * `amount` - The amount of backstop tokens to draw
+    /// * `to` - The address receiving the backstop tokens
+    pub fn draw(&self, pool: &Address, amount: &i128, to: &Address) -> Option<u32> {
+        match self.version {
+            BACKSTOP_INTERFACE_V0 => {
+                BackstopV0Client::new(&self.env, &self.address).draw(pool, amount, to);
+                None
+            }
+            BACKSTOP_INTERFACE_V1 => {
+                Some(BackstopClient::new(&self.env, &self.address).draw(pool, amount, to))
+            }
+            _ => panic_with_error!(&self.env, PoolError::BadRequest),
+        }
+    }
+
+    /// Reverse a pending draw from a pool's backstop during its dispute window
+    ///
+    /// ### Arguments
+    /// * `id` - The id of the pending draw
+    ///
+    /// ### Panics
+    /// If the backstop interface does not hold draws in escrow
+    pub fn reverse_draw(&self, id: &u32) {
+        match self.version {
+            BACKSTOP_INTERFACE_V1 => BackstopClient::new(&self.env, &self.address).reverse_draw(id),
+            _ => panic_with_error!(&self.env, PoolError::BadRequest),
+        }
+    }
+
+    /// Distribute interest in a pool asset to a pool's backstop depositors in kind. The pool
+    /// must approve the backstop to transfer the interest first.
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    /// * `asset` - The asset the interest is paid in
+    /// * `amount` - The amount of interest
+    ///
+    /// ### Panics
+    /// If the backstop interface does not support distributing interest
+    pub fn distribute_pool_interest(&self, pool: &Address, asset: &Address, amount: &i128) {
+        match self.version {
+            BACKSTOP_INTERFACE_V1 => BackstopClient::new(&self.env, &self.address)
+                .distribute_pool_interest(pool, asset, amount),
+            _ => panic_with_error!(&self.env, PoolError::BadRequest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _, Symbol};
+
+    #[contract]
+    struct MockBackstopV0;
+
+    #[contractimpl]
+    impl MockBackstopV0 {
+        pub fn draw(e: Env, _pool_address: Address, amount: i128, _to: Address) {
+            e.storage()
+                .instance()
+                .set(&Symbol::new(&e, "drawn"), &amount);
+        }
+    }
+
+    #[test]
+    fn test_draw_v0_interface() {
+        let e = Env::default();
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        let backstop_address = e.register_contract(None, MockBackstopV0 {});
+
+        e.as_contract(&pool_address, || {
+            storage::set_backstop_version(&e, &BACKSTOP_INTERFACE_V0);
+
+            let backstop_client = VersionedBackstopClient::new(&e, &backstop_address);
+            let draw_id = backstop_client.draw(&pool_address, &12_0000000, &samwise);
+            assert!(draw_id.is_none());
+        });
+        e.as_contract(&backstop_address, || {
+            let drawn: i128 = e
+                .storage()
+                .instance()
+                .get(&Symbol::new(&e, "drawn"))
+                .unwrap();
+            assert_eq!(drawn, 12_0000000);
+        });
+    }
+}
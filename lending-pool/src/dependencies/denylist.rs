@@ -0,0 +1,11 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface for contracts that screen addresses for a pool, such as a sanctions list
+#[contractclient(name = "DenylistClient")]
+pub trait DenylistTrait {
+    /// Check if an address is denied from interacting with the pool
+    ///
+    /// ### Arguments
+    /// * `address` - The address to check
+    fn is_denied(env: Env, address: Address) -> bool;
+}
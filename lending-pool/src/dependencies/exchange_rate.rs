@@ -0,0 +1,9 @@
+use soroban_sdk::{contractclient, Env};
+
+/// Exchange rate adapter interface for reserves whose underlying token rebases or
+/// accrues yield externally
+#[contractclient(name = "ExchangeRateClient")]
+pub trait ExchangeRateTrait {
+    /// Fetch the amount of underlying tokens one share unit is worth, expressed in 9 decimals
+    fn rate(env: Env) -> i128;
+}
@@ -0,0 +1,24 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+use crate::auctions::AuctionData;
+
+/// Executor interface for contracts that fill auctions on their own behalf, such as on-chain
+/// solvers that fund fills from a flash swap
+#[contractclient(name = "FillExecutorClient")]
+pub trait FillExecutorTrait {
+    /// Review a quote for an auction fill before the pool executes it. The executor can fund
+    /// the fill during the callback, and must return true to approve the fill.
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    /// * `auction_type` - The type of auction being filled
+    /// * `user` - The user involved in the auction
+    /// * `quote` - The bid the executor will take on and the lot it will receive
+    fn exec_fill(
+        env: Env,
+        pool: Address,
+        auction_type: u32,
+        user: Address,
+        quote: AuctionData,
+    ) -> bool;
+}
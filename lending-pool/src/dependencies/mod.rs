@@ -4,6 +4,23 @@ pub use token::Client as TokenClient;
 pub use token::WASM as TOKEN_WASM;
 
 mod backstop;
-pub use backstop::Client as BackstopClient;
 #[cfg(any(test, feature = "testutils"))]
 pub use backstop::{BackstopDataKey, WASM as BACKSTOP_WASM};
+
+mod backstop_compat;
+pub use backstop_compat::VersionedBackstopClient;
+
+mod denylist;
+pub use denylist::DenylistClient;
+
+mod exchange_rate;
+pub use exchange_rate::ExchangeRateClient;
+
+mod fill_executor;
+pub use fill_executor::FillExecutorClient;
+
+mod swap_executor;
+pub use swap_executor::SwapExecutorClient;
+
+mod swap_adapter;
+pub use swap_adapter::SwapAdapterClient;
@@ -0,0 +1,24 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Adapter interface for the exchanges claimed emissions can be swapped through
+#[contractclient(name = "SwapAdapterClient")]
+pub trait SwapAdapterTrait {
+    /// Swap an exact amount of "token_in" held by "from" for "token_out", which is sent to "from"
+    ///
+    /// Returns the amount of "token_out" received
+    ///
+    /// ### Arguments
+    /// * `from` - The address swapping tokens
+    /// * `token_in` - The token being sold
+    /// * `token_out` - The token being bought
+    /// * `amount_in` - The amount of "token_in" to sell
+    /// * `min_amount_out` - The minimum amount of "token_out" to receive
+    fn swap_exact_in(
+        env: Env,
+        from: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_amount_out: i128,
+    ) -> i128;
+}
@@ -0,0 +1,24 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Executor interface for contracts that swap a user's collateral between reserves, such as
+/// routers that sell the withdrawn collateral through an exchange
+#[contractclient(name = "SwapExecutorClient")]
+pub trait SwapExecutorTrait {
+    /// Swap collateral withdrawn from the pool. The executor holds the withdrawn "token_out"
+    /// during the callback, and must transfer the "token_in" it receives to the pool.
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    /// * `user` - The user whose collateral is being swapped
+    /// * `token_out` - The collateral asset withdrawn to the executor
+    /// * `amount_out` - The amount of "token_out" withdrawn to the executor
+    /// * `token_in` - The collateral asset the pool expects to receive
+    fn exec_swap(
+        env: Env,
+        pool: Address,
+        user: Address,
+        token_out: Address,
+        amount_out: i128,
+        token_in: Address,
+    );
+}
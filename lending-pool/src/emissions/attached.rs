@@ -0,0 +1,594 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, Map, Vec};
+
+use crate::{
+    dependencies::TokenClient,
+    errors::PoolError,
+    pool::User,
+    storage::{self, AttachedEmission, UserEmissionData},
+    validator::require_not_denied,
+};
+
+use super::distributor::get_b_emission_supply;
+
+/// The maximum number of unexpired emission streams attached to a reserve token
+const MAX_ATTACHED_EMISSIONS: u32 = 4;
+
+/// The time an expired emission stream is kept for users to claim against, in seconds. Any
+/// reward tokens left unclaimed after are refunded to the stream's funder.
+const ATTACHED_EMISSION_GRACE: u64 = 30 * 24 * 60 * 60; // 30 days
+
+/// Approve a reward token for attached emissions, with the minimum amount of reward tokens a
+/// stream must deposit, such that the limited stream slots cannot be filled with worthless
+/// streams. Removing the approval leaves existing streams of the token in place.
+///
+/// ### Arguments
+/// * `token` - The reward token
+/// * `min_amount` - The minimum amount a stream must deposit, or None to remove the approval
+///
+/// ### Panics
+/// If the token is a reserve asset or the minimum amount is negative
+pub fn set_attached_min(e: &Env, token: &Address, min_amount: Option<i128>) {
+    match min_amount {
+        Some(min_amount) => {
+            if min_amount < 0 || storage::has_res(e, token) {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            storage::set_attached_min(e, token, &min_amount);
+        }
+        None => storage::del_attached_min(e, token),
+    }
+}
+
+/// Attach an emission stream of an approved reward token to a reserve's b or d token. The full
+/// amount of reward tokens for the stream (eps * duration) is transferred from "from" to the
+/// pool, and any amount left unclaimed once the stream is swept is refunded to "from".
+///
+/// Returns the id of the attached emission stream
+///
+/// ### Arguments
+/// * `from` - The address funding the stream
+/// * `res_token_id` - The reserve token being incentivized => (reserve index * 2 + (0 for debtToken or 1 for blendToken))
+/// * `token` - The reward token being emitted
+/// * `eps` - The reward tokens emitted per second
+/// * `duration` - The length of the stream, in seconds
+///
+/// ### Panics
+/// If the reserve token does not exist, the stream is empty, the reward token is a reserve
+/// asset or is not approved, the stream is below the token's minimum amount, or the reserve
+/// token already has the maximum number of unexpired streams attached
+pub fn attach_emission(
+    e: &Env,
+    from: &Address,
+    res_token_id: u32,
+    token: &Address,
+    eps: u64,
+    duration: u64,
+) -> u32 {
+    if res_token_id / 2 >= storage::get_res_list(e).len() || eps == 0 || duration == 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    // reward tokens held by the pool for a reserve asset would be counted as reserve interest
+    if storage::has_res(e, token) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let amount = i128(eps) * i128(duration);
+    match storage::get_attached_min(e, token) {
+        Some(min_amount) if amount >= min_amount => {}
+        _ => panic_with_error!(e, PoolError::BadRequest),
+    }
+
+    let now = e.ledger().timestamp();
+    let (mut streams, _) = sweep_streams(e, res_token_id);
+    let active = streams
+        .iter()
+        .filter(|stream| stream.expiration > now)
+        .count();
+    if active as u32 >= MAX_ATTACHED_EMISSIONS {
+        panic_with_error!(e, PoolError::MaxAttachedEmissions);
+    }
+
+    TokenClient::new(e, token).transfer(from, &e.current_contract_address(), &amount);
+
+    let id = storage::next_attached_emission_id(e);
+    streams.push_back(AttachedEmission {
+        id,
+        token: token.clone(),
+        funder: from.clone(),
+        amount,
+        claimed: 0,
+        eps,
+        expiration: now + duration,
+        index: 0,
+        last_time: now,
+    });
+    storage::set_attached_emissions(e, &res_token_id, &streams);
+    id
+}
+
+/// Sweep the streams attached to a reserve token that have been expired longer than the grace
+/// period, refunding the reward tokens left unclaimed in each stream to its funder
+///
+/// Returns a map of each swept stream id to the amount refunded
+///
+/// ### Arguments
+/// * `res_token_id` - The reserve token => (reserve index * 2 + (0 for debtToken or 1 for blendToken))
+pub fn execute_sweep_attached(e: &Env, res_token_id: u32) -> Map<u32, i128> {
+    let (streams, refunded) = sweep_streams(e, res_token_id);
+    storage::set_attached_emissions(e, &res_token_id, &streams);
+    refunded
+}
+
+/// Remove the streams that have been expired longer than the grace period and refund them
+///
+/// Returns (the remaining streams, a map of each swept stream id to the amount refunded)
+fn sweep_streams(e: &Env, res_token_id: u32) -> (Vec<AttachedEmission>, Map<u32, i128>) {
+    let now = e.ledger().timestamp();
+    let mut streams: Vec<AttachedEmission> = vec![e];
+    let mut refunded: Map<u32, i128> = map![e];
+    for stream in storage::get_attached_emissions(e, &res_token_id).iter() {
+        if stream.expiration + ATTACHED_EMISSION_GRACE > now {
+            streams.push_back(stream);
+        } else {
+            let unclaimed = stream.amount - stream.claimed;
+            if unclaimed > 0 {
+                TokenClient::new(e, &stream.token).transfer(
+                    &e.current_contract_address(),
+                    &stream.funder,
+                    &unclaimed,
+                );
+            }
+            refunded.set(stream.id, unclaimed);
+        }
+    }
+    (streams, refunded)
+}
+
+/// Performs a claim of the attached emissions against the given "reserve_token_ids" for "from"
+///
+/// Returns a map of reward token to the amount claimed
+pub fn execute_claim_attached(
+    e: &Env,
+    from: &Address,
+    reserve_token_ids: &Vec<u32>,
+    to: &Address,
+) -> Map<Address, i128> {
+    require_not_denied(e, from);
+    require_not_denied(e, to);
+    let from_state = User::load(e, from);
+    let reserve_list = storage::get_res_list(e);
+    let mut to_claim: Map<Address, i128> = map![e];
+    for reserve_token_id in reserve_token_ids.clone() {
+        let reserve_index = reserve_token_id / 2;
+        let res_address = match reserve_list.get(reserve_index) {
+            Some(res_address) => res_address,
+            None => panic_with_error!(e, PoolError::BadRequest),
+        };
+        let reserve_config = storage::get_res_config(e, &res_address);
+        let reserve_data = storage::get_res_data(e, &res_address);
+        let (user_balance, supply) = match reserve_token_id % 2 {
+            0 => (
+                from_state.get_liabilities(reserve_index),
+                reserve_data.d_supply,
+            ),
+            _ => (
+                from_state.get_total_supply(reserve_index),
+                get_b_emission_supply(e, &res_address, reserve_data.b_supply),
+            ),
+        };
+        let claimed = update_attached_emissions(
+            e,
+            reserve_token_id,
+            supply,
+            10i128.pow(reserve_config.decimals),
+            from,
+            user_balance,
+            true,
+        );
+        for (token, amount) in claimed.iter() {
+            to_claim.set(token.clone(), amount + to_claim.get(token).unwrap_or(0));
+        }
+    }
+
+    for (token, amount) in to_claim.iter() {
+        if amount > 0 {
+            TokenClient::new(e, &token).transfer(&e.current_contract_address(), to, &amount);
+        }
+    }
+    to_claim
+}
+
+/// Update the attached emission streams for a reserve token. Must be called before any update
+/// is made to the supply of debtTokens or blendTokens.
+///
+/// Returns a map of reward token to the amount to claim, or an empty map if 'claim' is false
+///
+/// ### Arguments
+/// * `res_token_id` - The reserve token being acted against => (reserve index * 2 + (0 for debtToken or 1 for blendToken))
+/// * `supply` - The current supply of the reserve token
+/// * `supply_scalar` - The scalar of the reserve token
+/// * `user` - The user performing an action against the reserve
+/// * `balance` - The current balance of the user
+/// * `claim` - Whether or not to claim the user's accrued emissions
+pub fn update_attached_emissions(
+    e: &Env,
+    res_token_id: u32,
+    supply: i128,
+    supply_scalar: i128,
+    user: &Address,
+    balance: i128,
+    claim: bool,
+) -> Map<Address, i128> {
+    let mut claimed: Map<Address, i128> = map![e];
+    let mut streams = storage::get_attached_emissions(e, &res_token_id);
+    if streams.is_empty() {
+        return claimed;
+    }
+
+    for i in 0..streams.len() {
+        let mut stream = streams.get_unchecked(i);
+        update_stream_index(e, &mut stream, supply, supply_scalar);
+        let accrued = update_user_stream(
+            e,
+            &stream,
+            res_token_id,
+            supply_scalar,
+            user,
+            balance,
+            claim,
+        );
+        if accrued > 0 {
+            stream.claimed += accrued;
+            claimed.set(
+                stream.token.clone(),
+                accrued + claimed.get(stream.token.clone()).unwrap_or(0),
+            );
+        }
+        streams.set(i, stream);
+    }
+    storage::set_attached_emissions(e, &res_token_id, &streams);
+    claimed
+}
+
+fn update_stream_index(e: &Env, stream: &mut AttachedEmission, supply: i128, supply_scalar: i128) {
+    if stream.last_time >= stream.expiration
+        || e.ledger().timestamp() == stream.last_time
+        || supply == 0
+    {
+        return;
+    }
+
+    let ledger_timestamp = if e.ledger().timestamp() > stream.expiration {
+        stream.expiration
+    } else {
+        e.ledger().timestamp()
+    };
+
+    let additional_idx = (i128(ledger_timestamp - stream.last_time) * i128(stream.eps))
+        .fixed_div_floor(supply, supply_scalar)
+        .unwrap_optimized();
+    stream.index += additional_idx;
+    stream.last_time = ledger_timestamp;
+}
+
+fn update_user_stream(
+    e: &Env,
+    stream: &AttachedEmission,
+    res_token_id: u32,
+    supply_scalar: i128,
+    user: &Address,
+    balance: i128,
+    claim: bool,
+) -> i128 {
+    // a user without data has held their balance since the stream was attached
+    let user_data = storage::get_user_attached_emissions(e, user, &res_token_id, &stream.id)
+        .unwrap_or(UserEmissionData {
+            index: 0,
+            accrued: 0,
+        });
+    if user_data.index == stream.index && !claim {
+        return 0;
+    }
+
+    let mut accrued = user_data.accrued;
+    if balance != 0 {
+        accrued += balance
+            .fixed_mul_floor(stream.index - user_data.index, supply_scalar)
+            .unwrap_optimized();
+    }
+    let (to_store, to_claim) = if claim { (0, accrued) } else { (accrued, 0) };
+    storage::set_user_attached_emissions(
+        e,
+        user,
+        &res_token_id,
+        &stream.id,
+        &UserEmissionData {
+            index: stream.index,
+            accrued: to_store,
+        },
+    );
+    to_claim
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pool::Positions, testutils};
+
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    #[test]
+    fn test_attach_emission() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (reward_token, reward_token_client) = testutils::create_token_contract(&e, &bombadil);
+        reward_token_client.mint(&samwise, &1000_0000000);
+
+        e.as_contract(&pool, || {
+            set_attached_min(&e, &reward_token, Some(10_0000000));
+            let id = attach_emission(&e, &samwise, 1, &reward_token, 0_1000000, 1000);
+            assert_eq!(id, 0);
+
+            let streams = storage::get_attached_emissions(&e, &1);
+            assert_eq!(streams.len(), 1);
+            let stream = streams.get_unchecked(0);
+            assert_eq!(stream.token, reward_token);
+            assert_eq!(stream.funder, samwise);
+            assert_eq!(stream.amount, 100_0000000);
+            assert_eq!(stream.claimed, 0);
+            assert_eq!(stream.eps, 0_1000000);
+            assert_eq!(stream.expiration, 1500001000);
+            assert_eq!(stream.index, 0);
+            assert_eq!(stream.last_time, 1500000000);
+
+            assert_eq!(reward_token_client.balance(&samwise), 900_0000000);
+            assert_eq!(reward_token_client.balance(&pool), 100_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(21)")]
+    fn test_attach_emission_over_max_streams() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (reward_token, reward_token_client) = testutils::create_token_contract(&e, &bombadil);
+        reward_token_client.mint(&samwise, &1000_0000000);
+
+        e.as_contract(&pool, || {
+            set_attached_min(&e, &reward_token, Some(0));
+            for _ in 0..5 {
+                attach_emission(&e, &samwise, 1, &reward_token, 0_0100000, 1000);
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_attach_emission_below_min_amount() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (reward_token, reward_token_client) = testutils::create_token_contract(&e, &bombadil);
+        reward_token_client.mint(&samwise, &1000_0000000);
+
+        e.as_contract(&pool, || {
+            set_attached_min(&e, &reward_token, Some(100_0000000));
+            attach_emission(&e, &samwise, 1, &reward_token, 0_0100000, 1000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_attach_emission_not_approved() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (reward_token, reward_token_client) = testutils::create_token_contract(&e, &bombadil);
+        reward_token_client.mint(&samwise, &1000_0000000);
+
+        e.as_contract(&pool, || {
+            attach_emission(&e, &samwise, 1, &reward_token, 0_1000000, 1000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_attached_min_reserve_asset() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_attached_min(&e, &underlying_0, Some(0));
+        });
+    }
+
+    #[test]
+    fn test_execute_sweep_attached() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000 + ATTACHED_EMISSION_GRACE,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (reward_token, reward_token_client) = testutils::create_token_contract(&e, &bombadil);
+        reward_token_client.mint(&pool, &200_0000000);
+
+        e.as_contract(&pool, || {
+            let stream = AttachedEmission {
+                id: 0,
+                token: reward_token.clone(),
+                funder: samwise.clone(),
+                amount: 100_0000000,
+                claimed: 40_0000000,
+                eps: 0_1000000,
+                expiration: 1500000000,
+                index: 0_4000000,
+                last_time: 1500000000,
+            };
+            let mut unexpired = stream.clone();
+            unexpired.id = 1;
+            unexpired.expiration = 1500000001;
+            storage::set_attached_emissions(&e, &1, &vec![&e, stream, unexpired]);
+
+            let refunded = execute_sweep_attached(&e, 1);
+            assert_eq!(refunded.len(), 1);
+            assert_eq!(refunded.get_unchecked(0), 60_0000000);
+            assert_eq!(reward_token_client.balance(&samwise), 60_0000000);
+
+            let streams = storage::get_attached_emissions(&e, &1);
+            assert_eq!(streams.len(), 1);
+            assert_eq!(streams.get_unchecked(0).id, 1);
+        });
+    }
+
+    #[test]
+    fn test_execute_claim_attached() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000500,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.b_supply = 100_0000000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (reward_token, reward_token_client) = testutils::create_token_contract(&e, &bombadil);
+        reward_token_client.mint(&pool, &100_0000000);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e, (0, 15_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_attached_emissions(
+                &e,
+                &1,
+                &vec![
+                    &e,
+                    AttachedEmission {
+                        id: 0,
+                        token: reward_token.clone(),
+                        funder: bombadil.clone(),
+                        amount: 100_0000000,
+                        claimed: 0,
+                        eps: 0_1000000,
+                        expiration: 1500001000,
+                        index: 0,
+                        last_time: 1500000000,
+                    },
+                ],
+            );
+
+            let result = execute_claim_attached(&e, &samwise, &vec![&e, 1], &merry);
+            // 500 seconds * 0.1 eps * 25% of supply
+            assert_eq!(result.get_unchecked(reward_token.clone()), 12_5000000);
+            assert_eq!(reward_token_client.balance(&merry), 12_5000000);
+
+            let stream = storage::get_attached_emissions(&e, &1).get_unchecked(0);
+            assert_eq!(stream.claimed, 12_5000000);
+            assert_eq!(stream.index, 0_5000000);
+            assert_eq!(stream.last_time, 1500000500);
+            let user_data =
+                storage::get_user_attached_emissions(&e, &samwise, &1, &0).unwrap_optimized();
+            assert_eq!(user_data.index, 0_5000000);
+            assert_eq!(user_data.accrued, 0);
+        });
+    }
+}
@@ -1,46 +1,46 @@
 use cast::i128;
 use fixed_point_math::FixedPoint;
-use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env, Vec};
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env, Symbol, Vec};
 
 use crate::{
     dependencies::TokenClient,
     errors::PoolError,
-    pool::User,
+    pool::{Reserve, User},
     storage::{self, ReserveEmissionsData, UserEmissionData},
+    validator::{is_denied, require_not_denied},
 };
 
-/// Performs a claim against the given "reserve_token_ids" for "from"
+use super::{
+    attached::update_attached_emissions, pause::require_emissions_active, vesting::vest_emissions,
+};
+
+/// Performs a claim against the given "reserve_token_ids" for "from". If the pool vests emissions,
+/// the claimed emissions are locked into the user's vesting stream and only vested emissions
+/// are sent to "to".
+///
+/// Only the emissions of the given reserve tokens are claimed, such that emissions for any other
+/// reserve tokens continue to accrue. A "claim_reserve" event is emitted for each reserve token.
+///
+/// Returns the amount of emissions claimed
+///
+/// ### Panics
+/// If emissions are paused, a reserve token id is invalid or included more than once, or "from"
+/// or "to" are denied by the pool's denylist
 pub fn execute_claim(e: &Env, from: &Address, reserve_token_ids: &Vec<u32>, to: &Address) -> i128 {
+    require_emissions_active(e);
+    require_not_denied(e, from);
+    require_not_denied(e, to);
     let from_state = User::load(e, from);
     let reserve_list = storage::get_res_list(e);
     let mut to_claim = 0;
-    for reserve_token_id in reserve_token_ids.clone() {
-        let reserve_index = reserve_token_id / 2;
-        let reserve_addr = reserve_list.get(reserve_index);
-        match reserve_addr {
+    for (i, reserve_token_id) in reserve_token_ids.iter().enumerate() {
+        if reserve_token_ids.first_index_of(reserve_token_id) != Some(i as u32) {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+        match reserve_list.get(reserve_token_id / 2) {
             Some(res_address) => {
-                let reserve_config = storage::get_res_config(e, &res_address);
-                let reserve_data = storage::get_res_data(e, &res_address);
-                let (user_balance, supply) = match reserve_token_id % 2 {
-                    0 => (
-                        from_state.get_liabilities(reserve_index),
-                        reserve_data.d_supply,
-                    ),
-                    1 => (
-                        from_state.get_total_supply(reserve_index),
-                        reserve_data.b_supply,
-                    ),
-                    _ => panic_with_error!(e, PoolError::BadRequest),
-                };
-                to_claim += update_emissions(
-                    e,
-                    reserve_token_id,
-                    supply,
-                    10i128.pow(reserve_config.decimals),
-                    from,
-                    user_balance,
-                    true,
-                );
+                to_claim +=
+                    claim_reserve_token(e, from, &from_state, &res_address, reserve_token_id);
             }
             None => {
                 panic_with_error!(e, PoolError::BadRequest)
@@ -49,18 +49,217 @@ pub fn execute_claim(e: &Env, from: &Address, reserve_token_ids: &Vec<u32>, to:
     }
 
     if to_claim > 0 {
-        let backstop = storage::get_backstop(e);
-        let blnd_token = storage::get_blnd_token(e);
-        TokenClient::new(e, &blnd_token).transfer_from(
-            &e.current_contract_address(),
-            &backstop,
-            to,
-            &to_claim,
+        distribute_claim(e, from, to, to_claim);
+    }
+    to_claim
+}
+
+/// Performs a claim for "from" against a page of the pool's reserve tokens, starting at the
+/// reserve token id "cursor" and covering at most "limit" reserve tokens. This allows a user
+/// with positions in many reserves to claim across several transactions without exceeding
+/// the budget.
+///
+/// Each page distributes the emissions it claims. The accrual of reserve tokens outside of the
+/// page is persisted as normal, such that they can be claimed by a later page. Reserve tokens
+/// the user has never held are skipped, but count towards the limit.
+///
+/// Returns (the amount of emissions claimed, the cursor of the next page or None if the page
+/// reached the last reserve token)
+///
+/// ### Panics
+/// If emissions are paused, the cursor is past the last reserve token, the limit is zero, or
+/// "from" or "to" are denied by the pool's denylist
+pub fn execute_claim_page(
+    e: &Env,
+    from: &Address,
+    cursor: u32,
+    limit: u32,
+    to: &Address,
+) -> (i128, Option<u32>) {
+    require_emissions_active(e);
+    require_not_denied(e, from);
+    require_not_denied(e, to);
+    let reserve_list = storage::get_res_list(e);
+    let reserve_token_count = reserve_list.len() * 2;
+    if cursor >= reserve_token_count || limit == 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let from_state = User::load(e, from);
+    let page_end = cursor.saturating_add(limit).min(reserve_token_count);
+    let mut to_claim = 0;
+    for reserve_token_id in cursor..page_end {
+        let reserve_index = reserve_token_id / 2;
+        let user_balance = match reserve_token_id % 2 {
+            0 => from_state.get_liabilities(reserve_index),
+            _ => from_state.get_total_supply(reserve_index),
+        };
+        if user_balance == 0 && storage::get_user_emissions(e, from, &reserve_token_id).is_none() {
+            continue;
+        }
+        let res_address = reserve_list.get_unchecked(reserve_index);
+        to_claim += claim_reserve_token(e, from, &from_state, &res_address, reserve_token_id);
+    }
+
+    if to_claim > 0 {
+        distribute_claim(e, from, to, to_claim);
+    }
+    let next_cursor = if page_end < reserve_token_count {
+        Some(page_end)
+    } else {
+        None
+    };
+    (to_claim, next_cursor)
+}
+
+/// Settle the emissions of both reserve tokens of "reserve" for "from" against the balances in
+/// "from_state", and send them to "from". This must be called before the user's position in the
+/// reserve changes, as "from_state" and "reserve" can differ from what is stored.
+///
+/// The claim is best-effort, such that a user can always exit a reserve. If emissions are paused
+/// or "from" is denied by the pool's denylist, the emissions are only accrued to "from", and can be
+/// claimed once claims are allowed again.
+///
+/// Returns the amount of emissions claimed
+pub fn try_claim_reserve(e: &Env, from: &Address, from_state: &User, reserve: &Reserve) -> i128 {
+    let claim = storage::get_emissions_pause(e).is_none() && !is_denied(e, from);
+    let reserve_tokens = [
+        (
+            reserve.index * 2,
+            reserve.d_supply,
+            from_state.get_liabilities(reserve.index),
+        ),
+        (
+            reserve.index * 2 + 1,
+            get_b_emission_supply(e, &reserve.asset, reserve.b_supply),
+            from_state.get_total_supply(reserve.index),
+        ),
+    ];
+    let mut to_claim = 0;
+    for (reserve_token_id, supply, user_balance) in reserve_tokens {
+        let claimed = update_emissions(
+            e,
+            reserve_token_id,
+            supply,
+            reserve.scalar,
+            from,
+            user_balance,
+            claim,
         );
+        if claim {
+            e.events().publish(
+                (
+                    Symbol::new(e, "claim_reserve"),
+                    from.clone(),
+                    reserve_token_id,
+                ),
+                claimed,
+            );
+        }
+        to_claim += claimed;
+    }
+
+    if to_claim > 0 {
+        distribute_claim(e, from, from, to_claim);
     }
     to_claim
 }
 
+/// Claim the emissions of a reserve token for "from" and emit a "claim_reserve" event. The
+/// emissions are not distributed.
+///
+/// Returns the amount of emissions claimed
+fn claim_reserve_token(
+    e: &Env,
+    from: &Address,
+    from_state: &User,
+    res_address: &Address,
+    reserve_token_id: u32,
+) -> i128 {
+    let reserve_index = reserve_token_id / 2;
+    let reserve_config = storage::get_res_config(e, res_address);
+    let reserve_data = storage::get_res_data(e, res_address);
+    let (user_balance, supply) = match reserve_token_id % 2 {
+        0 => (
+            from_state.get_liabilities(reserve_index),
+            reserve_data.d_supply,
+        ),
+        1 => (
+            from_state.get_total_supply(reserve_index),
+            get_b_emission_supply(e, res_address, reserve_data.b_supply),
+        ),
+        _ => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let claimed = update_emissions(
+        e,
+        reserve_token_id,
+        supply,
+        10i128.pow(reserve_config.decimals),
+        from,
+        user_balance,
+        true,
+    );
+    e.events().publish(
+        (
+            Symbol::new(e, "claim_reserve"),
+            from.clone(),
+            reserve_token_id,
+        ),
+        claimed,
+    );
+    claimed
+}
+
+/// Distribute claimed emissions. If the pool vests emissions, the claim is locked into the
+/// vesting stream of "from" and only vested emissions are sent to "to".
+///
+/// ### Arguments
+/// * `from` - The Address the emissions were claimed for
+/// * `to` - The Address to send the emissions to
+/// * `amount` - The amount of emissions claimed
+pub(super) fn distribute_claim(e: &Env, from: &Address, to: &Address, amount: i128) {
+    let to_transfer = match storage::get_vesting_config(e) {
+        Some(config) if config.period > 0 => vest_emissions(e, from, &config, amount),
+        _ => amount,
+    };
+    if to_transfer > 0 {
+        transfer_emissions(e, to, to_transfer);
+    }
+}
+
+/// Transfer emissions from the backstop to "to". Emissions are distributed to the pool as an
+/// allowance from the backstop that is valid until an expiration ledger.
+///
+/// ### Arguments
+/// * `to` - The Address to send the emissions to
+/// * `amount` - The amount of emissions to send
+///
+/// ### Panics
+/// If the backstop's allowance is insufficient or has expired
+pub(super) fn transfer_emissions(e: &Env, to: &Address, amount: i128) {
+    let backstop = storage::get_backstop(e);
+    let blnd_token = storage::get_blnd_token(e);
+    let result = TokenClient::new(e, &blnd_token).try_transfer_from(
+        &e.current_contract_address(),
+        &backstop,
+        to,
+        &amount,
+    );
+    if !matches!(result, Ok(Ok(()))) {
+        panic_with_error!(e, PoolError::EmissionFailure);
+    }
+}
+
+/// Fetch the supply of blendTokens that earn emissions for a reserve. Protocol-owned blendTokens
+/// do not earn emissions, so they are excluded.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `b_supply` - The total supply of blendTokens of the reserve
+pub fn get_b_emission_supply(e: &Env, asset: &Address, b_supply: i128) -> i128 {
+    b_supply - storage::get_pol(e, asset)
+}
+
 /// Update the emissions information about a reserve token. Must be called before any update
 /// is made to the supply of debtTokens or blendTokens.
 ///
@@ -85,6 +284,9 @@ pub fn update_emissions(
     balance: i128,
     claim: bool,
 ) -> i128 {
+    // accrue any third party emission streams attached to the reserve token
+    update_attached_emissions(e, res_token_id, supply, supply_scalar, user, balance, false);
+
     if let Some(res_emis_data) = update_emission_data(e, res_token_id, supply, supply_scalar) {
         return update_user_emissions(
             e,
@@ -102,6 +304,11 @@ pub fn update_emissions(
 
 /// Update the reserve token emission data
 ///
+/// The index is advanced in closed form from the time elapsed since the last update, so
+/// catching up a reserve that has been inactive for a long time costs the same as an update
+/// a block later. Emissions stop accruing at the emission expiration, and while the pool's
+/// emissions are paused.
+///
 /// Returns the new ReserveEmissionData, if None if no data exists
 ///
 /// ### Arguments
@@ -127,6 +334,7 @@ pub fn update_emission_data(
         || e.ledger().timestamp() == token_emission_data.last_time
         || token_emission_config.eps == 0
         || supply == 0
+        || storage::get_emissions_pause(e).is_some()
     {
         return Some(token_emission_data);
     }
@@ -206,7 +414,10 @@ fn set_user_emissions(
 
 #[cfg(test)]
 mod tests {
-    use crate::{pool::Positions, storage::ReserveEmissionsConfig, testutils};
+    use crate::{
+        emissions::update_emissions_cycle, pool::Positions, storage::ReserveEmissionsConfig,
+        testutils,
+    };
 
     use super::*;
     use soroban_sdk::{
@@ -665,6 +876,69 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_update_emissions_after_months_inactive() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_default();
+
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let last_time: u64 = 1500000000;
+        let inactive: u64 = 180 * 24 * 60 * 60;
+        e.ledger().set(LedgerInfo {
+            timestamp: last_time + inactive,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        // an 18 decimal reserve with a large emission rate maximizes the index growth
+        let supply_scalar: i128 = 1_000_000_000_000_000_000;
+        let supply: i128 = 1_000_000 * supply_scalar;
+        let user_position: i128 = 1_000 * supply_scalar;
+        e.as_contract(&pool, || {
+            let reserve_emission_config = ReserveEmissionsConfig {
+                expiration: last_time + 2 * inactive,
+                eps: 1000_0000000,
+            };
+            let reserve_emission_data = ReserveEmissionsData {
+                index: 0,
+                last_time,
+            };
+            let user_emission_data = UserEmissionData {
+                index: 0,
+                accrued: 0,
+            };
+            let res_token_index = 1 * 2 + 1;
+            storage::set_res_emis_config(&e, &res_token_index, &reserve_emission_config);
+            storage::set_res_emis_data(&e, &res_token_index, &reserve_emission_data);
+            storage::set_user_emissions(&e, &samwise, &res_token_index, &user_emission_data);
+
+            let claimed = update_emissions(
+                &e,
+                res_token_index,
+                supply,
+                supply_scalar,
+                &samwise,
+                user_position,
+                true,
+            );
+
+            let new_reserve_emission_data =
+                storage::get_res_emis_data(&e, &res_token_index).unwrap_optimized();
+            assert_eq!(new_reserve_emission_data.last_time, last_time + inactive);
+            assert_eq!(new_reserve_emission_data.index, 155_520_000_000);
+            // samwise holds 0.1% of the supply for 180 days of 1000 tokens per second
+            assert_eq!(claimed, 15_552_000_0000000);
+        });
+    }
+
     /********** update_user_emissions **********/
 
     #[test]
@@ -1145,9 +1419,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    //#[should_panic(expected = "ContractError(2)")]
-    fn test_calc_claim_with_invalid_reserve_panics() {
+    fn test_execute_claim_across_emission_cycles() {
         let e = Env::default();
         e.mock_all_auths();
         e.budget().reset_unlimited();
@@ -1156,10 +1428,137 @@ mod tests {
         let bombadil = Address::random(&e);
         let samwise = Address::random(&e);
         let merry = Address::random(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
         let backstop = Address::random(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        testutils::set_timestamp(&e, 1500000000);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.decimals = 5;
+        reserve_data.b_supply = 100_00000;
+        reserve_data.d_supply = 50_00000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_00000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_pool_emissions(&e, &map![&e, (0, 1_0000000)]);
+
+            // cycle 1 - emits 100 BLND and expires 2000 seconds before cycle 2 starts
+            update_emissions_cycle(&e, 1500001000, 0_1000000);
+
+            // cycle 2 - emits 200 BLND and expires 6000 seconds before cycle 3 starts
+            testutils::set_timestamp(&e, 1500003000);
+            update_emissions_cycle(&e, 1500004000, 0_2000000);
+
+            // cycle 3 - emits 25 BLND before the claim
+            testutils::set_timestamp(&e, 1500010000);
+            update_emissions_cycle(&e, 1500011000, 0_0500000);
+
+            testutils::set_timestamp(&e, 1500010500);
+            let result = execute_claim(&e, &samwise, &vec![&e, 0], &merry);
+
+            // samwise holds 4% of the dTokens for every cycle
+            assert_eq!(result, 13_0000000);
+            assert_eq!(blnd_token_client.balance(&merry), 13_0000000);
+
+            let new_reserve_emission_data = storage::get_res_emis_data(&e, &0).unwrap_optimized();
+            let new_user_emission_data =
+                storage::get_user_emissions(&e, &samwise, &0).unwrap_optimized();
+            assert_eq!(new_reserve_emission_data.index, 65_000_000);
+            assert_eq!(new_reserve_emission_data.last_time, 1500010500);
+            assert_eq!(new_user_emission_data.index, 65_000_000);
+            assert_eq!(new_user_emission_data.accrued, 0);
+        });
+    }
+
+    #[test]
+    fn test_execute_claim_across_emission_cycles_with_action_between_cycles() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
 
         let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let backstop = Address::random(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        testutils::set_timestamp(&e, 1500000000);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.decimals = 5;
+        reserve_data.b_supply = 100_00000;
+        reserve_data.d_supply = 50_00000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_00000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_pool_emissions(&e, &map![&e, (0, 1_0000000)]);
+
+            update_emissions_cycle(&e, 1500001000, 0_1000000);
+
+            // samwise acts after cycle 1 has expired, before cycle 2 starts
+            testutils::set_timestamp(&e, 1500002000);
+            update_emissions(&e, 0, 50_00000, 1_00000, &samwise, 2_00000, false);
+            let user_emission_data =
+                storage::get_user_emissions(&e, &samwise, &0).unwrap_optimized();
+            assert_eq!(user_emission_data.index, 20_000_000);
+            assert_eq!(user_emission_data.accrued, 4_0000000);
+
+            testutils::set_timestamp(&e, 1500003000);
+            update_emissions_cycle(&e, 1500004000, 0_2000000);
+
+            testutils::set_timestamp(&e, 1500010000);
+            update_emissions_cycle(&e, 1500011000, 0_0500000);
+
+            testutils::set_timestamp(&e, 1500010500);
+            let result = execute_claim(&e, &samwise, &vec![&e, 0], &merry);
+
+            assert_eq!(result, 13_0000000);
+            assert_eq!(blnd_token_client.balance(&merry), 13_0000000);
+        });
+    }
+
+    #[test]
+    fn test_execute_claim_subset() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
 
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let backstop = Address::random(&e);
         // mock backstop having emissions for pool
         e.as_contract(&backstop, || {
             blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
@@ -1237,10 +1636,460 @@ mod tests {
             storage::set_res_emis_data(&e, &res_token_index_1, &reserve_emission_data_1);
             storage::set_user_emissions(&e, &samwise, &res_token_index_1, &user_emission_data_1);
 
-            let reserve_token_ids: Vec<u32> = vec![&e, res_token_index_0, res_token_index_1, 6];
-            execute_claim(&e, &samwise, &reserve_token_ids, &merry);
+            // only claim the b_token emissions of reserve 1
+            let reserve_token_ids: Vec<u32> = vec![&e, res_token_index_1];
+            let result = execute_claim(&e, &samwise, &reserve_token_ids, &merry);
+            assert_eq!(result, 301_0222222);
+            assert_eq!(blnd_token_client.balance(&merry), 301_0222222);
 
-            assert_eq!(blnd_token_client.balance(&backstop), 100_000_0000000)
+            // the d_token emissions of reserve 0 continue to accrue
+            let user_emission_data =
+                storage::get_user_emissions(&e, &samwise, &res_token_index_0).unwrap_optimized();
+            assert_eq!(user_emission_data.index, user_emission_data_0.index);
+            assert_eq!(user_emission_data.accrued, user_emission_data_0.accrued);
+            let reserve_emission_data =
+                storage::get_res_emis_data(&e, &res_token_index_0).unwrap_optimized();
+            assert_eq!(reserve_emission_data.last_time, 1500000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_execute_claim_duplicate_ids_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let backstop = Address::random(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1501000000, // 10^6 seconds have passed
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.decimals = 5;
+        reserve_data.b_supply = 100_00000;
+        reserve_data.d_supply = 50_00000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.decimals = 9;
+        reserve_config.index = 1;
+        reserve_data.b_supply = 100_000_000_000;
+        reserve_data.d_supply = 50_000_000_000;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_00000)],
+            collateral: map![&e, (1, 1_000_000_000)],
+            supply: map![&e, (1, 1_000_000_000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let reserve_emission_config_0 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0100000,
+            };
+            let reserve_emission_data_0 = ReserveEmissionsData {
+                index: 2345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_0 = UserEmissionData {
+                index: 1234567,
+                accrued: 0_1000000,
+            };
+            let res_token_index_0 = 0 * 2 + 0; // d_token for reserve 0
+
+            let reserve_emission_config_1 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0150000,
+            };
+            let reserve_emission_data_1 = ReserveEmissionsData {
+                index: 1345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_1 = UserEmissionData {
+                index: 1234567,
+                accrued: 1_0000000,
+            };
+            let res_token_index_1 = 1 * 2 + 1; // b_token for reserve 1
+
+            storage::set_res_emis_config(&e, &res_token_index_0, &reserve_emission_config_0);
+            storage::set_res_emis_data(&e, &res_token_index_0, &reserve_emission_data_0);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_0, &user_emission_data_0);
+
+            storage::set_res_emis_config(&e, &res_token_index_1, &reserve_emission_config_1);
+            storage::set_res_emis_data(&e, &res_token_index_1, &reserve_emission_data_1);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_1, &user_emission_data_1);
+
+            let reserve_token_ids: Vec<u32> =
+                vec![&e, res_token_index_0, res_token_index_1, res_token_index_0];
+            execute_claim(&e, &samwise, &reserve_token_ids, &merry);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(20)")]
+    fn test_transfer_emissions_expired_allowance_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let merry = Address::random(&e);
+        let backstop = Address::random(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        blnd_token_client.mint(&backstop, &100_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_0000000, &200);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500001000,
+            protocol_version: 1,
+            sequence_number: 201,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        assert_eq!(blnd_token_client.allowance(&backstop, &pool), 0);
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            transfer_emissions(&e, &merry, 1_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_calc_claim_with_invalid_reserve_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+        let backstop = Address::random(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1501000000, // 10^6 seconds have passed
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.decimals = 5;
+        reserve_data.b_supply = 100_00000;
+        reserve_data.d_supply = 50_00000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.decimals = 9;
+        reserve_config.index = 1;
+        reserve_data.b_supply = 100_000_000_000;
+        reserve_data.d_supply = 50_000_000_000;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_00000)],
+            collateral: map![&e, (1, 1_000_000_000)],
+            supply: map![&e, (1, 1_000_000_000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let reserve_emission_config_0 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0100000,
+            };
+            let reserve_emission_data_0 = ReserveEmissionsData {
+                index: 2345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_0 = UserEmissionData {
+                index: 1234567,
+                accrued: 0_1000000,
+            };
+            let res_token_index_0 = 0 * 2 + 0; // d_token for reserve 0
+
+            let reserve_emission_config_1 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0150000,
+            };
+            let reserve_emission_data_1 = ReserveEmissionsData {
+                index: 1345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_1 = UserEmissionData {
+                index: 1234567,
+                accrued: 1_0000000,
+            };
+            let res_token_index_1 = 1 * 2 + 1; // b_token for reserve 1
+
+            storage::set_res_emis_config(&e, &res_token_index_0, &reserve_emission_config_0);
+            storage::set_res_emis_data(&e, &res_token_index_0, &reserve_emission_data_0);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_0, &user_emission_data_0);
+
+            storage::set_res_emis_config(&e, &res_token_index_1, &reserve_emission_config_1);
+            storage::set_res_emis_data(&e, &res_token_index_1, &reserve_emission_data_1);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_1, &user_emission_data_1);
+
+            let reserve_token_ids: Vec<u32> = vec![&e, res_token_index_0, res_token_index_1, 6];
+            execute_claim(&e, &samwise, &reserve_token_ids, &merry);
+
+            assert_eq!(blnd_token_client.balance(&backstop), 100_000_0000000)
+        });
+    }
+
+    /********** execute_claim_page **********/
+
+    /// Setup `count` reserves with emissions for both reserve tokens, where samwise holds
+    /// a tenth of the supply of every reserve token
+    fn setup_many_reserves(
+        e: &Env,
+        pool: &Address,
+        backstop: &Address,
+        samwise: &Address,
+        count: u32,
+    ) {
+        let bombadil = Address::random(e);
+        let mut user_positions = Positions::env_default(e);
+        for i in 0..count {
+            let (underlying, _) = testutils::create_token_contract(e, &bombadil);
+            let (reserve_config, reserve_data) = testutils::default_reserve_meta(e);
+            testutils::create_reserve(e, pool, &underlying, &reserve_config, &reserve_data);
+            user_positions.liabilities.set(i, 7_5000000);
+            user_positions.collateral.set(i, 10_0000000);
+        }
+
+        e.as_contract(pool, || {
+            storage::set_backstop(e, backstop);
+            storage::set_user_positions(e, samwise, &user_positions);
+            for reserve_token_id in 0..count * 2 {
+                storage::set_res_emis_config(
+                    e,
+                    &reserve_token_id,
+                    &ReserveEmissionsConfig {
+                        expiration: 1600000000,
+                        eps: 0_0100000,
+                    },
+                );
+                storage::set_res_emis_data(
+                    e,
+                    &reserve_token_id,
+                    &ReserveEmissionsData {
+                        index: 0,
+                        last_time: 1500000000,
+                    },
+                );
+                storage::set_user_emissions(
+                    e,
+                    samwise,
+                    &reserve_token_id,
+                    &UserEmissionData {
+                        index: 0,
+                        accrued: 0,
+                    },
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_execute_claim_page() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let backstop = Address::random(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        testutils::set_timestamp(&e, 1500000000);
+        // 12 reserves, or 24 reserve tokens
+        setup_many_reserves(&e, &pool, &backstop, &samwise, 12);
+        testutils::set_timestamp(&e, 1501000000); // 10^6 seconds have passed
+
+        // each d_token accrues 999_9999997 and each b_token accrues 1000_0000000
+        let per_reserve = 999_9999997 + 1000_0000000;
+        e.as_contract(&pool, || {
+            let (claimed, cursor) = execute_claim_page(&e, &samwise, 0, 10, &merry);
+            assert_eq!(claimed, 5 * per_reserve);
+            assert_eq!(cursor, Some(10));
+            assert_eq!(blnd_token_client.balance(&merry), 5 * per_reserve);
+
+            // accrual outside of the page is not claimed
+            let user_data = storage::get_user_emissions(&e, &samwise, &10).unwrap_optimized();
+            assert_eq!(user_data.index, 0);
+            assert_eq!(user_data.accrued, 0);
+            let user_data = storage::get_user_emissions(&e, &samwise, &9).unwrap_optimized();
+            assert_eq!(user_data.accrued, 0);
+            assert_eq!(
+                user_data.index,
+                storage::get_res_emis_data(&e, &9).unwrap_optimized().index
+            );
+
+            let (claimed, cursor) = execute_claim_page(&e, &samwise, 10, 10, &merry);
+            assert_eq!(claimed, 5 * per_reserve);
+            assert_eq!(cursor, Some(20));
+
+            let (claimed, cursor) = execute_claim_page(&e, &samwise, 20, 10, &merry);
+            assert_eq!(claimed, 2 * per_reserve);
+            assert_eq!(cursor, None);
+
+            assert_eq!(blnd_token_client.balance(&merry), 12 * per_reserve);
+            assert_eq!(
+                blnd_token_client.balance(&backstop),
+                100_000_0000000 - 12 * per_reserve
+            );
+
+            // all reserve tokens are claimed
+            let (claimed, cursor) = execute_claim_page(&e, &samwise, 0, 24, &merry);
+            assert_eq!(claimed, 0);
+            assert_eq!(cursor, None);
+        });
+    }
+
+    #[test]
+    fn test_execute_claim_page_skips_unheld_tokens() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let backstop = Address::random(&e);
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        testutils::set_timestamp(&e, 1500000000);
+        setup_many_reserves(&e, &pool, &backstop, &samwise, 11);
+        e.as_contract(&pool, || {
+            // frodo holds a position in a single reserve
+            storage::set_user_positions(
+                &e,
+                &frodo,
+                &Positions {
+                    liabilities: map![&e],
+                    collateral: map![&e, (4, 10_0000000)],
+                    supply: map![&e],
+                },
+            );
+        });
+        testutils::set_timestamp(&e, 1501000000);
+
+        e.as_contract(&pool, || {
+            let mut paged = 0;
+            let mut cursor = Some(0);
+            while let Some(next) = cursor {
+                let (claimed, next_cursor) = execute_claim_page(&e, &frodo, next, 7, &merry);
+                paged += claimed;
+                cursor = next_cursor;
+            }
+            assert_eq!(paged, 1000_0000000);
+            assert_eq!(blnd_token_client.balance(&merry), 1000_0000000);
+
+            // reserve tokens frodo never held are skipped
+            assert!(storage::get_user_emissions(&e, &frodo, &8).is_none());
+            assert!(storage::get_user_emissions(&e, &frodo, &9).is_some());
+
+            let reserve_token_ids: Vec<u32> = vec![&e, 9];
+            let claimed = execute_claim(&e, &frodo, &reserve_token_ids, &merry);
+            assert_eq!(claimed, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_execute_claim_page_cursor_past_end_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+        let backstop = Address::random(&e);
+
+        testutils::set_timestamp(&e, 1500000000);
+        setup_many_reserves(&e, &pool, &backstop, &samwise, 2);
+
+        e.as_contract(&pool, || {
+            execute_claim_page(&e, &samwise, 4, 10, &merry);
         });
     }
 }
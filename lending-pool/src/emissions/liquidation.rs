@@ -0,0 +1,206 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map};
+
+use crate::{
+    errors::PoolError,
+    pool::User,
+    storage::{self, UserEmissionData},
+};
+
+use super::distributor::distribute_claim;
+
+/// Accrued emissions of liquidated collateral remain claimable by the liquidated user
+pub const LIQ_EMIS_KEEP: u32 = 0;
+/// Accrued emissions of liquidated collateral are included in the lot and move to the filler
+pub const LIQ_EMIS_LOT: u32 = 1;
+/// Accrued emissions of liquidated collateral are claimed and sent to the liquidated user
+pub const LIQ_EMIS_SETTLE: u32 = 2;
+
+/// Set how the pool settles the accrued emissions of a liquidated user's seized collateral
+///
+/// ### Arguments
+/// * `mode` - The liquidation emissions mode
+///
+/// ### Panics
+/// If the mode is not supported
+pub fn set_liquidation_emissions(e: &Env, mode: u32) {
+    if mode > LIQ_EMIS_SETTLE {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_liq_emis_mode(e, &mode);
+}
+
+/// Settle the accrued emissions of the liquidated user for each seized collateral reserve, based
+/// on the pool's liquidation emissions mode. Only the share of the accrued emissions proportional
+/// to the seized bTokens is settled, the rest remains with the user. Emissions for both users must
+/// be accrued up to the current ledger, and the lot removed from the user's positions, before
+/// this is called.
+///
+/// Returns the amount of emissions settled
+///
+/// ### Arguments
+/// * `user_state` - The liquidated user, after the lot was removed
+/// * `filler` - The filler of the liquidation auction
+/// * `lot` - The collateral seized from the user
+pub fn settle_liquidated_emissions(
+    e: &Env,
+    user_state: &User,
+    filler: &Address,
+    lot: &Map<Address, i128>,
+) -> i128 {
+    let user = &user_state.address;
+    let mode = storage::get_liq_emis_mode(e);
+    // emissions cannot be distributed while paused, so they remain claimable by the user
+    if mode == LIQ_EMIS_KEEP
+        || (mode == LIQ_EMIS_SETTLE && storage::get_emissions_pause(e).is_some())
+    {
+        return 0;
+    }
+
+    let mut settled = 0;
+    for (asset, seized) in lot.iter() {
+        let reserve_index = storage::get_res_config(e, &asset).index;
+        let res_token_id = reserve_index * 2 + 1;
+        let mut user_data = match storage::get_user_emissions(e, user, &res_token_id) {
+            Some(data) if data.accrued > 0 => data,
+            _ => continue,
+        };
+        // emissions accrued on both collateral and supply, so only the seized share moves
+        let balance = user_state.get_total_supply(reserve_index) + seized;
+        let accrued = user_data
+            .accrued
+            .fixed_mul_floor(seized, balance)
+            .unwrap_optimized();
+        if accrued == 0 {
+            continue;
+        }
+        user_data.accrued -= accrued;
+        storage::set_user_emissions(e, user, &res_token_id, &user_data);
+
+        if mode == LIQ_EMIS_LOT {
+            let mut filler_data =
+                storage::get_user_emissions(e, filler, &res_token_id).unwrap_or(UserEmissionData {
+                    index: user_data.index,
+                    accrued: 0,
+                });
+            filler_data.accrued += accrued;
+            storage::set_user_emissions(e, filler, &res_token_id, &filler_data);
+        }
+        settled += accrued;
+    }
+
+    if mode == LIQ_EMIS_SETTLE && settled > 0 {
+        distribute_claim(e, user, user, settled);
+    }
+    settled
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pool::Positions, testutils};
+
+    use super::*;
+    use soroban_sdk::{map, testutils::Address as _};
+
+    #[test]
+    fn test_settle_liquidated_emissions_keep() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            let user_data = UserEmissionData {
+                index: 1234567,
+                accrued: 0_1000000,
+            };
+            storage::set_user_emissions(&e, &samwise, &1, &user_data);
+
+            let lot = map![&e, (underlying.clone(), 10_0000000)];
+            let user_state = User {
+                address: samwise.clone(),
+                positions: Positions::env_default(&e),
+            };
+            let settled = settle_liquidated_emissions(&e, &user_state, &frodo, &lot);
+            assert_eq!(settled, 0);
+            let user_data = storage::get_user_emissions(&e, &samwise, &1).unwrap();
+            assert_eq!(user_data.accrued, 0_1000000);
+            assert!(storage::get_user_emissions(&e, &frodo, &1).is_none());
+        });
+    }
+
+    #[test]
+    fn test_settle_liquidated_emissions_lot() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_liquidation_emissions(&e, LIQ_EMIS_LOT);
+            storage::set_user_emissions(
+                &e,
+                &samwise,
+                &1,
+                &UserEmissionData {
+                    index: 1234567,
+                    accrued: 0_1000000,
+                },
+            );
+            storage::set_user_emissions(
+                &e,
+                &frodo,
+                &1,
+                &UserEmissionData {
+                    index: 1234567,
+                    accrued: 0_0500000,
+                },
+            );
+
+            // the user keeps 20 collateral and 10 supply after 10 collateral is seized
+            let lot = map![&e, (underlying.clone(), 10_0000000)];
+            let user_state = User {
+                address: samwise.clone(),
+                positions: Positions {
+                    liabilities: map![&e],
+                    collateral: map![&e, (0, 20_0000000)],
+                    supply: map![&e, (0, 10_0000000)],
+                },
+            };
+            let settled = settle_liquidated_emissions(&e, &user_state, &frodo, &lot);
+            assert_eq!(settled, 0_0250000);
+            let user_data = storage::get_user_emissions(&e, &samwise, &1).unwrap();
+            assert_eq!(user_data.accrued, 0_0750000);
+            assert_eq!(user_data.index, 1234567);
+            let filler_data = storage::get_user_emissions(&e, &frodo, &1).unwrap();
+            assert_eq!(filler_data.accrued, 0_0750000);
+            assert_eq!(filler_data.index, 1234567);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_liquidation_emissions_invalid_mode() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            set_liquidation_emissions(&e, 3);
+        });
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
+    constants::{SCALAR_7, SECONDS_PER_YEAR},
     errors::PoolError,
+    pool::Pool,
     storage::{self, ReserveEmissionsConfig, ReserveEmissionsData},
 };
+use cast::{i128, u64};
 use fixed_point_math::FixedPoint;
 use soroban_sdk::{
     contracttype, map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map, Symbol, Vec,
@@ -12,6 +15,7 @@ use super::distributor;
 // Types
 
 /// Metadata for a pool's reserve emission configuration
+#[derive(Clone)]
 #[contracttype]
 pub struct ReserveEmissionMetadata {
     pub res_index: u32,
@@ -19,6 +23,15 @@ pub struct ReserveEmissionMetadata {
     pub share: u64,
 }
 
+/// A target emission APR for a pool's reserve token
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveEmissionTarget {
+    pub res_index: u32,
+    pub res_type: u32,
+    pub target_apr: i128, // the targeted emission APR, expressed in 7 decimals
+}
+
 /// Get emissions information for a reserve
 pub fn get_reserve_emissions(
     e: &Env,
@@ -44,6 +57,62 @@ pub fn get_reserve_emissions(
     panic_with_error!(e, PoolError::BadRequest);
 }
 
+/// Calculate the annualized emission rate for a reserve token, as the value of a year of
+/// emissions at the current eps relative to the value of the reserve token's supply
+///
+/// Returns the emission APR expressed in 7 decimals, or zero if the reserve token is not
+/// receiving emissions or has no supply
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `token_type` - The type of reserve token (0 for dToken / 1 for bToken)
+///
+/// ### Panics
+/// If the reserve does not exist or a price is stale
+pub fn calc_emission_apr(e: &Env, asset: &Address, token_type: u32) -> i128 {
+    if token_type > 1 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let res_index = match storage::get_res_list(e).first_index_of(asset) {
+        Some(res_index) => res_index,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let emis_config = match storage::get_res_emis_config(e, &(res_index * 2 + token_type)) {
+        Some(emis_config) => emis_config,
+        None => return 0,
+    };
+    if emis_config.eps == 0 || emis_config.expiration <= e.ledger().timestamp() {
+        return 0;
+    }
+
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset);
+    let supply = match token_type {
+        0 => reserve.total_liabilities(),
+        _ => reserve.to_asset_from_b_token(distributor::get_b_emission_supply(
+            e,
+            asset,
+            reserve.b_supply,
+        )),
+    };
+    if supply <= 0 {
+        return 0;
+    }
+
+    let supply_base = pool
+        .load_price(e, asset)
+        .fixed_mul_floor(supply, reserve.scalar)
+        .unwrap_optimized();
+    let annual_emissions = i128(emis_config.eps) * SECONDS_PER_YEAR;
+    let emissions_base = pool
+        .load_price(e, &storage::get_blnd_token(e))
+        .fixed_mul_floor(annual_emissions, SCALAR_7)
+        .unwrap_optimized();
+    emissions_base
+        .fixed_div_floor(supply_base, SCALAR_7)
+        .unwrap_optimized()
+}
+
 /// Set the pool emissions
 ///
 /// These will not be applied until the next `update_emissions` is run
@@ -75,10 +144,92 @@ pub fn set_pool_emissions(e: &Env, res_emission_metadata: Vec<ReserveEmissionMet
     storage::set_pool_emissions(e, &pool_emissions);
 }
 
+/// Set the pool emissions such that each reserve token approximately receives a target emission APR,
+/// based on the current supply of each reserve token, the current prices, and the pool's eps
+///
+/// If the targets require more than the pool's eps, the shares are scaled down proportionally such
+/// that the full pool eps is distributed. These will not be applied until the next `update_emissions` is run
+///
+/// Returns the emission metadata that was set
+///
+/// ### Arguments
+/// * `targets` - A vector of `ReserveEmissionTarget` that details each reserve token's target APR
+/// * `pool_eps` - The emissions per second the pool is receiving
+///
+/// ### Panics
+/// If the pool is not receiving emissions, a reserve token does not exist, or a price is stale
+pub fn set_pool_emissions_by_target_apr(
+    e: &Env,
+    targets: Vec<ReserveEmissionTarget>,
+    pool_eps: i128,
+) -> Vec<ReserveEmissionMetadata> {
+    if pool_eps <= 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut pool = Pool::load(e);
+    let blnd_price = pool.load_price(e, &storage::get_blnd_token(e));
+    let reserve_list = storage::get_res_list(e);
+
+    let mut shares: Vec<(u32, u32, i128)> = Vec::new(e);
+    let mut total_share: i128 = 0;
+    for target in targets.iter() {
+        if target.target_apr < 0 {
+            panic_with_error!(e, PoolError::NegativeAmount);
+        }
+        let asset = match reserve_list.get(target.res_index) {
+            Some(asset) => asset,
+            None => panic_with_error!(e, PoolError::BadRequest),
+        };
+        let reserve = pool.load_reserve(e, &asset);
+        let supply = match target.res_type {
+            0 => reserve.total_liabilities(),
+            1 => reserve.total_supply(),
+            _ => panic_with_error!(e, PoolError::BadRequest),
+        };
+        let supply_base = pool
+            .load_price(e, &asset)
+            .fixed_mul_floor(supply, reserve.scalar)
+            .unwrap_optimized();
+
+        // invert the emission APR calculation to find the eps that yields the target APR
+        let eps = target
+            .target_apr
+            .fixed_mul_floor(supply_base, blnd_price)
+            .unwrap_optimized()
+            / SECONDS_PER_YEAR;
+        let share = eps.fixed_div_floor(pool_eps, SCALAR_7).unwrap_optimized();
+        shares.push_back((target.res_index, target.res_type, share));
+        total_share += share;
+    }
+
+    let mut res_emission_metadata: Vec<ReserveEmissionMetadata> = Vec::new(e);
+    for (res_index, res_type, share) in shares.iter() {
+        let share = if total_share > SCALAR_7 {
+            share
+                .fixed_mul_floor(SCALAR_7, total_share)
+                .unwrap_optimized()
+        } else {
+            share
+        };
+        res_emission_metadata.push_back(ReserveEmissionMetadata {
+            res_index,
+            res_type,
+            share: u64(share).unwrap_optimized(),
+        });
+    }
+    set_pool_emissions(e, res_emission_metadata.clone());
+    res_emission_metadata
+}
+
 /// Updates the pool's emissions for the next emission cycle
 ///
 /// Needs to be run each time a new emission cycle starts
 ///
+/// Each reserve token's emission index is checkpointed at the cycle boundary before the new config
+/// is written. Emissions of the previous cycle are settled up to its expiration, and the new cycle
+/// starts accruing from the current timestamp. Users are paid from the cumulative index, so a user
+/// who does not act for multiple cycles is still due the emissions of every cycle.
+///
 /// Returns the new expiration timestamp
 ///
 /// ### Panics
@@ -110,7 +261,7 @@ fn update_reserve_emission_data(e: &Env, asset: &Address, res_token_id: u32) {
         let reserve_data = storage::get_res_data(e, asset);
         let supply = match res_token_id % 2 {
             0 => reserve_data.d_supply,
-            1 => reserve_data.b_supply,
+            1 => distributor::get_b_emission_supply(e, asset, reserve_data.b_supply),
             _ => panic_with_error!(e, PoolError::BadRequest),
         };
         let mut emission_data = distributor::update_emission_data(
@@ -121,7 +272,8 @@ fn update_reserve_emission_data(e: &Env, asset: &Address, res_token_id: u32) {
         )
         .unwrap(); // will always return a result
         if emission_data.last_time != e.ledger().timestamp() {
-            // force the emission data to be updated to the current timestamp
+            // force the emission data to be updated to the current timestamp - the previous cycle
+            // has been settled up to its expiration, and nothing is emitted between cycles
             emission_data.last_time = e.ledger().timestamp();
             storage::set_res_emis_data(e, &res_token_id, &emission_data);
         }
@@ -162,7 +314,7 @@ fn update_reserve_emission_config(
 
 #[cfg(test)]
 mod tests {
-    use crate::testutils;
+    use crate::{storage::PoolConfig, testutils};
 
     use super::*;
     use soroban_sdk::{
@@ -456,6 +608,63 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_update_emissions_cycle_settles_expired_cycle_at_boundary() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500100000,
+            protocol_version: 1,
+            sequence_number: 20100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let next_exp = 1500704800;
+        let pool_eps = 0_5000000;
+        let pool_emissions: Map<u32, u64> = map![&e, (0, 1_0000000)];
+
+        // the last update happened 100 seconds before the previous cycle expired
+        let old_r_l_0_config = ReserveEmissionsConfig {
+            eps: 0_2000000,
+            expiration: 1500000100,
+        };
+        let old_r_l_0_data = ReserveEmissionsData {
+            index: 100,
+            last_time: 1500000000,
+        };
+
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 1499900000;
+        reserve_data.d_supply = 50_0000000;
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            storage::set_pool_emissions(&e, &pool_emissions);
+            storage::set_res_emis_config(&e, &0, &old_r_l_0_config);
+            storage::set_res_emis_data(&e, &0, &old_r_l_0_data);
+
+            update_emissions_cycle(&e, next_exp, pool_eps);
+
+            let r_0_l_config = storage::get_res_emis_config(&e, &0).unwrap_optimized();
+            assert_eq!(r_0_l_config.expiration, next_exp);
+            assert_eq!(r_0_l_config.eps, 0_5000000);
+
+            // accrues the remaining 100 seconds of the previous cycle and nothing after it
+            let r_0_l_data = storage::get_res_emis_data(&e, &0).unwrap_optimized();
+            assert_eq!(r_0_l_data.index, 4000100);
+            assert_eq!(r_0_l_data.last_time, 1500100000);
+        });
+    }
+
     #[test]
     #[should_panic]
     //#[should_panic(expected = "ContractError(2)")]
@@ -664,4 +873,224 @@ mod tests {
             assert_eq!(new_pool_emissions.get(6).unwrap_optimized(), 0_6500000);
         });
     }
+
+    /********** calc_emission_apr ********/
+
+    #[test]
+    fn test_set_pool_emissions_by_target_apr() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let blnd = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        oracle_client.set_price(&underlying, &1_0000000);
+        oracle_client.set_price(&blnd, &0_0500000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let targets = vec![
+            &e,
+            ReserveEmissionTarget {
+                res_index: 0,
+                res_type: 0,
+                target_apr: 0_2102400,
+            },
+            ReserveEmissionTarget {
+                res_index: 0,
+                res_type: 1,
+                target_apr: 0_1576800,
+            },
+        ];
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_blnd_token(&e, &blnd);
+
+            // each target requires 100 eps
+            let metadata = set_pool_emissions_by_target_apr(&e, targets, 1000);
+            assert_eq!(metadata.len(), 2);
+            assert_eq!(metadata.get_unchecked(0).share, 0_1000000);
+            assert_eq!(metadata.get_unchecked(1).share, 0_1000000);
+
+            let pool_emissions = storage::get_pool_emissions(&e);
+            assert_eq!(pool_emissions.len(), 2);
+            assert_eq!(pool_emissions.get_unchecked(0), 0_1000000);
+            assert_eq!(pool_emissions.get_unchecked(1), 0_1000000);
+        });
+    }
+
+    #[test]
+    fn test_set_pool_emissions_by_target_apr_scales_to_pool_eps() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let blnd = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        oracle_client.set_price(&underlying, &1_0000000);
+        oracle_client.set_price(&blnd, &0_0500000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let targets = vec![
+            &e,
+            ReserveEmissionTarget {
+                res_index: 0,
+                res_type: 0,
+                target_apr: 0_2102400,
+            },
+            ReserveEmissionTarget {
+                res_index: 0,
+                res_type: 1,
+                target_apr: 0_1576800,
+            },
+        ];
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_blnd_token(&e, &blnd);
+
+            // the targets require 160% of the pool eps
+            set_pool_emissions_by_target_apr(&e, targets, 125);
+
+            let pool_emissions = storage::get_pool_emissions(&e);
+            assert_eq!(pool_emissions.get_unchecked(0), 0_5000000);
+            assert_eq!(pool_emissions.get_unchecked(1), 0_5000000);
+        });
+    }
+
+    #[test]
+    fn test_calc_emission_apr() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let blnd = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        oracle_client.set_price(&underlying, &1_0000000);
+        oracle_client.set_price(&blnd, &0_0500000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_blnd_token(&e, &blnd);
+            let emis_config = ReserveEmissionsConfig {
+                expiration: 1000000,
+                eps: 0_0000100,
+            };
+            storage::set_res_emis_config(&e, &0, &emis_config);
+            storage::set_res_emis_config(&e, &1, &emis_config);
+
+            // 315.36 BLND at $0.05 over $75 of liabilities and $100 of supply
+            assert_eq!(calc_emission_apr(&e, &underlying, 0), 0_2102400);
+            assert_eq!(calc_emission_apr(&e, &underlying, 1), 0_1576800);
+        });
+    }
+
+    #[test]
+    fn test_calc_emission_apr_no_emissions() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        e.as_contract(&pool_address, || {
+            assert_eq!(calc_emission_apr(&e, &underlying, 1), 0);
+        });
+    }
 }
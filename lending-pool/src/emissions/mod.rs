@@ -1,7 +1,30 @@
 mod manager;
 pub use manager::{
-    get_reserve_emissions, set_pool_emissions, update_emissions_cycle, ReserveEmissionMetadata,
+    calc_emission_apr, get_reserve_emissions, set_pool_emissions, set_pool_emissions_by_target_apr,
+    update_emissions_cycle, ReserveEmissionMetadata, ReserveEmissionTarget,
+};
+
+mod attached;
+pub use attached::{
+    attach_emission, execute_claim_attached, execute_sweep_attached, set_attached_min,
 };
 
 mod distributor;
-pub use distributor::{execute_claim, update_emissions};
+pub use distributor::{
+    execute_claim, execute_claim_page, get_b_emission_supply, try_claim_reserve, update_emissions,
+};
+
+mod operator;
+pub use operator::{execute_claim_for, set_claim_operator};
+
+mod liquidation;
+pub use liquidation::{set_liquidation_emissions, settle_liquidated_emissions};
+
+mod pause;
+pub use pause::{execute_pause_emissions, execute_resume_emissions};
+
+mod swap;
+pub use swap::execute_claim_and_swap;
+
+mod vesting;
+pub use vesting::{execute_claim_vested, execute_exit_vesting, set_vesting_config};
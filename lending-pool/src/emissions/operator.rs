@@ -0,0 +1,103 @@
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+use crate::{errors::PoolError, storage};
+
+use super::distributor::execute_claim;
+
+/// Approve an operator to claim emissions on behalf of a user. The operator can only claim,
+/// and the claimed emissions are always sent to the user. Replaces any existing operator.
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `operator` - The address of the operator
+///
+/// ### Panics
+/// If the operator is the user
+pub fn set_claim_operator(e: &Env, user: &Address, operator: &Address) {
+    if user == operator {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_claim_operator(e, user, operator);
+}
+
+/// Performs a claim against the given "reserve_token_ids" for "from" by its approved operator.
+/// The claimed emissions are sent to "from".
+///
+/// Returns the amount of emissions claimed
+///
+/// ### Arguments
+/// * `operator` - The address of the operator
+/// * `from` - The address of the user being claimed for
+/// * `reserve_token_ids` - The reserve tokens to claim emissions for
+///
+/// ### Panics
+/// If the operator is not approved by "from", or a reserve token id is invalid or included more than once
+pub fn execute_claim_for(
+    e: &Env,
+    operator: &Address,
+    from: &Address,
+    reserve_token_ids: &Vec<u32>,
+) -> i128 {
+    match storage::get_claim_operator(e, from) {
+        Some(approved) if approved == *operator => execute_claim(e, from, reserve_token_ids, from),
+        _ => panic_with_error!(e, PoolError::NotAuthorized),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, vec};
+
+    #[test]
+    fn test_execute_claim_for() {
+        let e = Env::default();
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        let operator = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            set_claim_operator(&e, &samwise, &operator);
+            assert_eq!(
+                storage::get_claim_operator(&e, &samwise),
+                Some(operator.clone())
+            );
+
+            let claimed = execute_claim_for(&e, &operator, &samwise, &vec![&e]);
+            assert_eq!(claimed, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_execute_claim_for_not_operator() {
+        let e = Env::default();
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+        let operator = Address::random(&e);
+        let frodo = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            set_claim_operator(&e, &samwise, &operator);
+
+            execute_claim_for(&e, &frodo, &samwise, &vec![&e]);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_claim_operator_self() {
+        let e = Env::default();
+
+        let pool_address = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            set_claim_operator(&e, &samwise, &samwise);
+        });
+    }
+}
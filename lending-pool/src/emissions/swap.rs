@@ -0,0 +1,255 @@
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+use crate::{
+    dependencies::{SwapAdapterClient, TokenClient},
+    errors::PoolError,
+    storage,
+    validator::require_nonnegative,
+};
+
+use super::distributor::execute_claim;
+
+/// Performs a claim against the given "reserve_token_ids" for "from", and swaps the emissions
+/// released to "from" for the pool asset "asset_out" through the pool's claim swap adapter. If
+/// the pool vests emissions, only the vested emissions released by the claim are swapped.
+///
+/// Returns (the amount of emissions claimed, the amount of "asset_out" received)
+///
+/// ### Arguments
+/// * `from` - The address claiming
+/// * `reserve_token_ids` - The reserve tokens to claim emissions for
+/// * `asset_out` - The underlying asset of the reserve to swap the emissions for
+/// * `min_amount_out` - The minimum amount of "asset_out" to receive
+///
+/// ### Panics
+/// If no claim swap adapter is set, "asset_out" is not a reserve or is the emission token, the
+/// swap returns less than "min_amount_out", or the claim fails
+pub fn execute_claim_and_swap(
+    e: &Env,
+    from: &Address,
+    reserve_token_ids: &Vec<u32>,
+    asset_out: &Address,
+    min_amount_out: i128,
+) -> (i128, i128) {
+    require_nonnegative(e, &min_amount_out);
+    let adapter = match storage::get_claim_swap_adapter(e) {
+        Some(adapter) => adapter,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let blnd_token = storage::get_blnd_token(e);
+    if *asset_out == blnd_token || !storage::has_res(e, asset_out) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let blnd_client = TokenClient::new(e, &blnd_token);
+    let balance_before = blnd_client.balance(from);
+    let claimed = execute_claim(e, from, reserve_token_ids, from);
+    let released = blnd_client.balance(from) - balance_before;
+
+    let amount_out = if released > 0 {
+        SwapAdapterClient::new(e, &adapter).swap_exact_in(
+            from,
+            &blnd_token,
+            asset_out,
+            &released,
+            &min_amount_out,
+        )
+    } else {
+        0
+    };
+    // the adapter is not trusted to enforce the minimum
+    if amount_out < min_amount_out {
+        panic_with_error!(e, PoolError::TokenSlippageExceeded);
+    }
+    (claimed, amount_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        storage::{ReserveEmissionsConfig, ReserveEmissionsData, UserEmissionData},
+        testutils, Positions,
+    };
+
+    use super::*;
+    use soroban_sdk::{
+        contract, contractimpl, map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec,
+    };
+
+    #[contract]
+    struct MockSwapAdapter;
+
+    #[contractimpl]
+    impl MockSwapAdapter {
+        /// Swaps at a fixed rate of 2 "token_out" per "token_in"
+        pub fn swap_exact_in(
+            e: Env,
+            from: Address,
+            token_in: Address,
+            token_out: Address,
+            amount_in: i128,
+            _min_amount_out: i128,
+        ) -> i128 {
+            let amount_out = amount_in * 2;
+            TokenClient::new(&e, &token_in).transfer(
+                &from,
+                &e.current_contract_address(),
+                &amount_in,
+            );
+            TokenClient::new(&e, &token_out).transfer(
+                &e.current_contract_address(),
+                &from,
+                &amount_out,
+            );
+            amount_out
+        }
+    }
+
+    /// Setup "samwise" with 1 token of accrued emissions for reserve token 1. Returns the
+    /// underlying asset of reserve 0.
+    fn setup(e: &Env, pool: &Address, samwise: &Address, adapter: &Option<Address>) -> Address {
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(e);
+        let (_, blnd_token_client) = testutils::create_blnd_token(e, pool, &bombadil);
+        let backstop = Address::random(e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(e);
+        testutils::create_reserve(e, pool, &underlying_0, &reserve_config, &reserve_data);
+        if let Some(adapter) = adapter {
+            underlying_0_client.mint(adapter, &100_0000000);
+        }
+
+        e.as_contract(pool, || {
+            storage::set_backstop(e, &backstop);
+            if let Some(adapter) = adapter {
+                storage::set_claim_swap_adapter(e, adapter);
+            }
+            storage::set_user_positions(
+                e,
+                samwise,
+                &Positions {
+                    liabilities: map![e],
+                    collateral: map![e, (0, 10_0000000)],
+                    supply: map![e],
+                },
+            );
+            storage::set_res_emis_config(
+                e,
+                &1,
+                &ReserveEmissionsConfig {
+                    expiration: 1600000000,
+                    eps: 0_0100000,
+                },
+            );
+            storage::set_res_emis_data(
+                e,
+                &1,
+                &ReserveEmissionsData {
+                    index: 1234567,
+                    last_time: 1500000000,
+                },
+            );
+            storage::set_user_emissions(
+                e,
+                samwise,
+                &1,
+                &UserEmissionData {
+                    index: 1234567,
+                    accrued: 1_0000000,
+                },
+            );
+        });
+        underlying_0
+    }
+
+    #[test]
+    fn test_execute_claim_and_swap() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let adapter = e.register_contract(None, MockSwapAdapter {});
+        let underlying_0 = setup(&e, &pool, &samwise, &Some(adapter.clone()));
+
+        e.as_contract(&pool, || {
+            let (claimed, amount_out) =
+                execute_claim_and_swap(&e, &samwise, &vec![&e, 1], &underlying_0, 1_9000000);
+            assert_eq!(claimed, 1_0000000);
+            assert_eq!(amount_out, 2_0000000);
+
+            let blnd_token = storage::get_blnd_token(&e);
+            assert_eq!(TokenClient::new(&e, &blnd_token).balance(&samwise), 0);
+            assert_eq!(
+                TokenClient::new(&e, &blnd_token).balance(&adapter),
+                1_0000000
+            );
+            assert_eq!(
+                TokenClient::new(&e, &underlying_0).balance(&samwise),
+                2_0000000
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(17)")]
+    fn test_execute_claim_and_swap_under_min_amount_out() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let adapter = e.register_contract(None, MockSwapAdapter {});
+        let underlying_0 = setup(&e, &pool, &samwise, &Some(adapter));
+
+        e.as_contract(&pool, || {
+            execute_claim_and_swap(&e, &samwise, &vec![&e, 1], &underlying_0, 2_0000001);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_execute_claim_and_swap_no_adapter() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let underlying_0 = setup(&e, &pool, &samwise, &None);
+
+        e.as_contract(&pool, || {
+            execute_claim_and_swap(&e, &samwise, &vec![&e, 1], &underlying_0, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_execute_claim_and_swap_not_reserve() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let adapter = e.register_contract(None, MockSwapAdapter {});
+        setup(&e, &pool, &samwise, &Some(adapter));
+
+        e.as_contract(&pool, || {
+            execute_claim_and_swap(&e, &samwise, &vec![&e, 1], &Address::random(&e), 0);
+        });
+    }
+}
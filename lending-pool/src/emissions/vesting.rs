@@ -0,0 +1,431 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::SCALAR_7,
+    errors::PoolError,
+    storage::{self, UserVesting, VestingConfig},
+    validator::require_not_denied,
+};
+
+use super::{distributor::transfer_emissions, pause::require_emissions_active};
+
+/// Set the emission vesting config for the pool. A period of zero disables vesting
+/// for future claims.
+///
+/// ### Arguments
+/// * `period` - The length of time claimed emissions stream over, in seconds
+/// * `cliff` - The length of time before any vested emissions can be released, in seconds
+/// * `penalty` - The portion of unvested emissions slashed on early exit, expressed in 7 decimals
+///
+/// ### Panics
+/// If the cliff is longer than the period or the penalty is over 100%
+pub fn set_vesting_config(e: &Env, period: u64, cliff: u64, penalty: u32) {
+    if cliff > period || i128(penalty) > SCALAR_7 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_vesting_config(
+        e,
+        &VestingConfig {
+            period,
+            cliff,
+            penalty,
+        },
+    );
+}
+
+/// Lock newly claimed emissions into the user's vesting stream. Any emissions already vesting
+/// are released first, and the new end time is the amount weighted average of the remaining
+/// stream and a full vesting period for the new emissions.
+///
+/// Returns the amount of vested emissions released to the user
+///
+/// ### Arguments
+/// * `user` - The user claiming emissions
+/// * `config` - The emission vesting config
+/// * `amount` - The amount of emissions claimed
+pub fn vest_emissions(e: &Env, user: &Address, config: &VestingConfig, amount: i128) -> i128 {
+    let now = e.ledger().timestamp();
+    let mut vesting = storage::get_user_vesting(e, user).unwrap_or(UserVesting {
+        locked: 0,
+        start: now,
+        last_time: now,
+        end: now,
+    });
+    let released = release_vested(&mut vesting, config, now);
+
+    if vesting.locked == 0 {
+        vesting.start = now;
+        vesting.end = now + config.period;
+    } else {
+        let remaining_weight = vesting.locked * i128(vesting.end - now);
+        let new_weight = amount * i128(config.period);
+        let avg_duration = (remaining_weight + new_weight) / (vesting.locked + amount);
+        vesting.end = now + avg_duration as u64;
+    }
+    vesting.locked += amount;
+    vesting.last_time = now;
+    storage::set_user_vesting(e, user, &vesting);
+
+    released
+}
+
+/// Release any vested emissions for "from" and send them to "to"
+///
+/// Returns the amount of emissions released
+///
+/// ### Arguments
+/// * `from` - The user whose emissions are vesting
+/// * `to` - The Address to send the released tokens to
+///
+/// ### Panics
+/// If emissions are paused, the user has no vesting emissions, or "from" or "to" are denied by
+/// the pool's denylist
+pub fn execute_claim_vested(e: &Env, from: &Address, to: &Address) -> i128 {
+    require_emissions_active(e);
+    require_not_denied(e, from);
+    require_not_denied(e, to);
+    let mut vesting = match storage::get_user_vesting(e, from) {
+        Some(vesting) => vesting,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let config = storage::get_vesting_config(e).unwrap_optimized();
+    let released = release_vested(&mut vesting, &config, e.ledger().timestamp());
+
+    if vesting.locked == 0 {
+        storage::del_user_vesting(e, from);
+    } else {
+        storage::set_user_vesting(e, from, &vesting);
+    }
+    if released > 0 {
+        transfer_emissions(e, to, released);
+    }
+    released
+}
+
+/// Exit the vesting stream early for "from". Vested emissions are released in full, and
+/// the unvested emissions are released after the early exit penalty is slashed. Slashed
+/// emissions are never drawn from the backstop.
+///
+/// Returns the amount of emissions released
+///
+/// ### Arguments
+/// * `from` - The user whose emissions are vesting
+/// * `to` - The Address to send the released tokens to
+///
+/// ### Panics
+/// If emissions are paused, the user has no vesting emissions, or "from" or "to" are denied by
+/// the pool's denylist
+pub fn execute_exit_vesting(e: &Env, from: &Address, to: &Address) -> i128 {
+    require_emissions_active(e);
+    require_not_denied(e, from);
+    require_not_denied(e, to);
+    let mut vesting = match storage::get_user_vesting(e, from) {
+        Some(vesting) => vesting,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let config = storage::get_vesting_config(e).unwrap_optimized();
+    let released = release_vested(&mut vesting, &config, e.ledger().timestamp());
+    let unvested_released = vesting
+        .locked
+        .fixed_mul_floor(SCALAR_7 - i128(config.penalty), SCALAR_7)
+        .unwrap_optimized();
+
+    storage::del_user_vesting(e, from);
+    let to_release = released + unvested_released;
+    if to_release > 0 {
+        transfer_emissions(e, to, to_release);
+    }
+    to_release
+}
+
+/// Release the vested portion of the locked emissions, rounding down. Nothing is released
+/// before the cliff has passed.
+///
+/// Returns the amount of emissions released
+fn release_vested(vesting: &mut UserVesting, config: &VestingConfig, now: u64) -> i128 {
+    if vesting.locked == 0 || now < vesting.start + config.cliff || now <= vesting.last_time {
+        return 0;
+    }
+
+    let released = if now >= vesting.end {
+        vesting.locked
+    } else {
+        vesting
+            .locked
+            .fixed_mul_floor(
+                i128(now - vesting.last_time),
+                i128(vesting.end - vesting.last_time),
+            )
+            .unwrap_optimized()
+    };
+    vesting.locked -= released;
+    vesting.last_time = now;
+    released
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutils;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    #[test]
+    fn test_vest_emissions_first_claim() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let config = VestingConfig {
+            period: 1000,
+            cliff: 100,
+            penalty: 0_5000000,
+        };
+        e.as_contract(&pool, || {
+            let released = vest_emissions(&e, &samwise, &config, 100_0000000);
+            assert_eq!(released, 0);
+
+            let vesting = storage::get_user_vesting(&e, &samwise).unwrap_optimized();
+            assert_eq!(vesting.locked, 100_0000000);
+            assert_eq!(vesting.start, 1500000000);
+            assert_eq!(vesting.last_time, 1500000000);
+            assert_eq!(vesting.end, 1500001000);
+        });
+    }
+
+    #[test]
+    fn test_vest_emissions_merges_stream() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000500,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let config = VestingConfig {
+            period: 1000,
+            cliff: 100,
+            penalty: 0_5000000,
+        };
+        e.as_contract(&pool, || {
+            storage::set_user_vesting(
+                &e,
+                &samwise,
+                &UserVesting {
+                    locked: 100_0000000,
+                    start: 1500000000,
+                    last_time: 1500000000,
+                    end: 1500001000,
+                },
+            );
+
+            let released = vest_emissions(&e, &samwise, &config, 50_0000000);
+            assert_eq!(released, 50_0000000);
+
+            // 50 remaining over 500 seconds and 50 new over 1000 seconds
+            let vesting = storage::get_user_vesting(&e, &samwise).unwrap_optimized();
+            assert_eq!(vesting.locked, 100_0000000);
+            assert_eq!(vesting.start, 1500000000);
+            assert_eq!(vesting.last_time, 1500000500);
+            assert_eq!(vesting.end, 1500000500 + 750);
+        });
+    }
+
+    #[test]
+    fn test_execute_claim_vested() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let backstop = Address::random(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000250,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_vesting_config(
+                &e,
+                &VestingConfig {
+                    period: 1000,
+                    cliff: 100,
+                    penalty: 0_5000000,
+                },
+            );
+            storage::set_user_vesting(
+                &e,
+                &samwise,
+                &UserVesting {
+                    locked: 100_0000000,
+                    start: 1500000000,
+                    last_time: 1500000000,
+                    end: 1500001000,
+                },
+            );
+
+            let released = execute_claim_vested(&e, &samwise, &merry);
+            assert_eq!(released, 25_0000000);
+            assert_eq!(blnd_token_client.balance(&merry), 25_0000000);
+
+            let vesting = storage::get_user_vesting(&e, &samwise).unwrap_optimized();
+            assert_eq!(vesting.locked, 75_0000000);
+            assert_eq!(vesting.last_time, 1500000250);
+            assert_eq!(vesting.end, 1500001000);
+        });
+    }
+
+    #[test]
+    fn test_execute_claim_vested_before_cliff() {
+        let e = Env::default();
+
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000050,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        e.as_contract(&pool, || {
+            storage::set_vesting_config(
+                &e,
+                &VestingConfig {
+                    period: 1000,
+                    cliff: 100,
+                    penalty: 0_5000000,
+                },
+            );
+            storage::set_user_vesting(
+                &e,
+                &samwise,
+                &UserVesting {
+                    locked: 100_0000000,
+                    start: 1500000000,
+                    last_time: 1500000000,
+                    end: 1500001000,
+                },
+            );
+
+            let released = execute_claim_vested(&e, &samwise, &merry);
+            assert_eq!(released, 0);
+
+            let vesting = storage::get_user_vesting(&e, &samwise).unwrap_optimized();
+            assert_eq!(vesting.locked, 100_0000000);
+            assert_eq!(vesting.last_time, 1500000000);
+        });
+    }
+
+    #[test]
+    fn test_execute_exit_vesting() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let backstop = Address::random(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000250,
+            protocol_version: 1,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_vesting_config(
+                &e,
+                &VestingConfig {
+                    period: 1000,
+                    cliff: 100,
+                    penalty: 0_5000000,
+                },
+            );
+            storage::set_user_vesting(
+                &e,
+                &samwise,
+                &UserVesting {
+                    locked: 100_0000000,
+                    start: 1500000000,
+                    last_time: 1500000000,
+                    end: 1500001000,
+                },
+            );
+
+            // 25 vested, 75 unvested with 50% slashed
+            let released = execute_exit_vesting(&e, &samwise, &merry);
+            assert_eq!(released, 25_0000000 + 37_5000000);
+            assert_eq!(blnd_token_client.balance(&merry), 62_5000000);
+            assert_eq!(
+                blnd_token_client.balance(&backstop),
+                100_000_0000000 - 62_5000000
+            );
+            assert!(storage::get_user_vesting(&e, &samwise).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_vesting_config_cliff_too_long() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            set_vesting_config(&e, 1000, 1001, 0_5000000);
+        });
+    }
+}
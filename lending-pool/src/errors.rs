@@ -12,14 +12,32 @@ pub enum PoolError {
     NegativeAmount = 4,
     InvalidPoolInitArgs = 5,
     InvalidReserveMetadata = 6,
+    AddressDenied = 7,
+    InitConfigMismatch = 8,
+    AllowanceExceeded = 9,
     // Pool State Errors (10-19)
     InvalidHf = 10,
     InvalidPoolStatus = 11,
     InvalidUtilRate = 12,
+    WithdrawUtilTooHigh = 13,
+    MaxLeverageExceeded = 14,
+    WithdrawalNotQueued = 15,
+    BorrowRateTooHigh = 16,
+    TokenSlippageExceeded = 17,
+    ExposureCapExceeded = 18,
+    RoundTripBlocked = 19,
     // Emission Errors (20-29)
     EmissionFailure = 20,
+    MaxAttachedEmissions = 21,
+    EmissionsPaused = 22,
     // Oracle Errors (30-39)
     StalePrice = 30,
+    InvalidExchangeRate = 31,
+    OracleDisagreement = 32,
+    // Position Errors (40-49)
+    DebtTooSmall = 40,
+    // Submit Errors (50-59)
+    RequestExpired = 50,
     // Auction Errors (100-199)
     InvalidLiquidation = 100,
     InvalidLot = 101,
@@ -29,4 +47,6 @@ pub enum PoolError {
     InvalidLiqTooLarge = 105,
     InvalidLiqTooSmall = 106,
     InterestTooSmall = 107,
+    ExecutorRejected = 108,
+    AuctionNotFound = 109,
 }
@@ -9,16 +9,24 @@ mod dependencies;
 mod emissions;
 mod errors;
 mod pool;
+mod soft_liquidation;
 mod storage;
 mod testutils;
 mod validator;
 
-pub use auctions::{AuctionData, AuctionType};
+pub use auctions::{AuctionCheck, AuctionData, AuctionType};
 pub use contract::*;
-pub use emissions::ReserveEmissionMetadata;
+pub use emissions::{ReserveEmissionMetadata, ReserveEmissionTarget};
 pub use errors::PoolError;
-pub use pool::{Positions, Request};
+pub use pool::{
+    AdminAction, BorrowQuote, PositionRisk, PositionSnapshot, Positions, Request, ReserveRates,
+    ReserveRiskParams, RiskManifest, UtilReport,
+};
 pub use storage::{
-    AuctionKey, PoolConfig, PoolDataKey, PoolEmissionConfig, ReserveConfig, ReserveData,
-    ReserveEmissionsConfig, ReserveEmissionsData, UserEmissionData, UserReserveKey,
+    AttachedEmission, AuctionKey, AuctionRecord, AuctionStats, EModeCategory, FillBids,
+    IsolationConfig, PendingOracle, PolWithdrawal, PoolConfig, PoolDataKey, PoolEmissionConfig,
+    PoolInitConfig, PriceClampConfig, PriceObservation, QueuedWithdrawal, ReserveConfig,
+    ReserveConfigVersion, ReserveData, ReserveEmissionsConfig, ReserveEmissionsData,
+    ReserveExposure, SoftLiqConfig, SweepConfig, TrustedBorrower, UserEmissionData, UserHistory,
+    UserReserveKey, UserVesting, VestingConfig,
 };
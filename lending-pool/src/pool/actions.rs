@@ -1,9 +1,19 @@
 use soroban_sdk::Map;
 use soroban_sdk::{contracttype, panic_with_error, Address, Env, Symbol, Vec};
 
-use crate::{auctions, errors::PoolError, validator::require_nonnegative};
-
+use crate::{
+    auctions, constants::MAX_AMOUNT, emissions, errors::PoolError, storage,
+    validator::require_nonnegative,
+};
+
+use super::borrow_fee::{calc_borrow_shares, distribute_borrow_fee};
+use super::interest::calc_borrow_rate;
+use super::pause::require_reserve_action_allowed;
 use super::pool::Pool;
+use super::reserve::Reserve;
+use super::status::require_backstop_floor;
+use super::trusted::accrue_trusted_premium;
+use super::withdrawal::require_large_withdrawal_unlocked;
 use super::User;
 
 /// An request a user makes against the pool
@@ -45,11 +55,37 @@ impl Actions {
             amount + self.pool_transfer.get(asset.clone()).unwrap_or(0),
         );
     }
+
+    /// Net the transfers to and from the pool for each asset, such that only the difference
+    /// is transferred. Only valid when the spender and the "to" address are the same.
+    pub fn net_transfers(&mut self, e: &Env) {
+        let mut spender_transfer = Map::new(e);
+        let mut pool_transfer = self.pool_transfer.clone();
+        for (asset, amount_in) in self.spender_transfer.iter() {
+            let amount_out = pool_transfer.get(asset.clone()).unwrap_or(0);
+            if amount_in > amount_out {
+                spender_transfer.set(asset.clone(), amount_in - amount_out);
+                pool_transfer.remove(asset);
+            } else if amount_in < amount_out {
+                pool_transfer.set(asset, amount_out - amount_in);
+            } else {
+                pool_transfer.remove(asset);
+            }
+        }
+        self.spender_transfer = spender_transfer;
+        self.pool_transfer = pool_transfer;
+    }
 }
 
 /// Build a set of pool actions and the new positions from the supplied requests. Validates that the requests
 /// are valid based on the status and supported reserves in the pool.
 ///
+/// All requests are applied in order to a working copy of the user's positions and the cached reserves.
+/// Utilization limits are verified once all requests have been applied, and the health factor check is
+/// left to the caller, such that the ordering of requests within a batch does not change the outcome.
+/// A position can never become negative, however, so a request that removes more than the user holds
+/// at that point in the batch is clamped (withdrawals, repayments) or rejected.
+///
 /// ### Arguments
 /// * pool - The pool
 /// * from - The sender of the requests
@@ -71,18 +107,37 @@ pub fn build_actions_from_request(
 ) -> (Actions, User, bool) {
     let mut actions = Actions::new(e);
     let mut from_state = User::load(e, from);
+    accrue_trusted_premium(e, pool, &mut from_state);
     let mut check_health = false;
+    let mut check_util: Vec<Address> = Vec::new(e);
+    let mut check_withdraw_util: Vec<Address> = Vec::new(e);
+    let mut withdrawn: Map<Address, i128> = Map::new(e);
+    let mut max_borrow_aprs: Map<Address, i128> = Map::new(e);
+    // the reserve and the amount of b or d tokens moved by the previous request, for slippage bounds
+    let mut last_tokens: Option<(Address, i128)> = None;
+    // verify the batch has not expired before any request is processed
+    for request in requests.iter() {
+        if request.request_type == 13 && i128::from(e.ledger().sequence()) > request.amount {
+            panic_with_error!(e, PoolError::RequestExpired);
+        }
+    }
     for request in requests.iter() {
         // verify the request is allowed
         require_nonnegative(e, &request.amount);
         pool.require_action_allowed(e, request.request_type);
+        if matches!(request.request_type, 0..=5 | 12 | 14 | 15) {
+            require_reserve_action_allowed(e, &request.address, request.request_type);
+        }
+        let prev_tokens = last_tokens.take();
         match request.request_type {
             0 => {
                 // supply
+                let amount = pool.to_shares_down(e, &request.address, request.amount);
                 let mut reserve = pool.load_reserve(e, &request.address);
-                let b_tokens_minted = reserve.to_b_token_down(request.amount);
+                let b_tokens_minted = reserve.to_b_token_down(amount);
                 from_state.add_supply(e, &mut reserve, b_tokens_minted);
-                actions.add_for_spender_transfer(&reserve.asset, request.amount);
+                actions.add_for_spender_transfer(&reserve.asset, amount);
+                last_tokens = Some((reserve.asset.clone(), b_tokens_minted));
                 pool.cache_reserve(reserve, true);
                 e.events().publish(
                     (
@@ -90,21 +145,28 @@ pub fn build_actions_from_request(
                         request.address.clone(),
                         from.clone(),
                     ),
-                    (request.amount, b_tokens_minted),
+                    (
+                        pool.to_underlying_up(e, &request.address, amount),
+                        b_tokens_minted,
+                    ),
                 );
             }
             1 => {
                 // withdraw
                 let mut reserve = pool.load_reserve(e, &request.address);
                 let cur_b_tokens = from_state.get_supply(reserve.index);
-                let mut to_burn = reserve.to_b_token_up(request.amount);
-                let mut tokens_out = request.amount;
-                if to_burn > cur_b_tokens {
-                    to_burn = cur_b_tokens;
-                    tokens_out = reserve.to_asset_from_b_token(cur_b_tokens);
-                }
+                let (to_burn, tokens_out) =
+                    calc_withdrawal(e, pool, &reserve, request.amount, cur_b_tokens);
                 from_state.remove_supply(e, &mut reserve, to_burn);
+                if !check_withdraw_util.contains(&reserve.asset) {
+                    check_withdraw_util.push_back(reserve.asset.clone());
+                }
+                withdrawn.set(
+                    reserve.asset.clone(),
+                    tokens_out + withdrawn.get(reserve.asset.clone()).unwrap_or(0),
+                );
                 actions.add_for_pool_transfer(&reserve.asset, tokens_out);
+                last_tokens = Some((reserve.asset.clone(), to_burn));
                 pool.cache_reserve(reserve, true);
                 e.events().publish(
                     (
@@ -112,15 +174,20 @@ pub fn build_actions_from_request(
                         request.address.clone(),
                         from.clone(),
                     ),
-                    (tokens_out, to_burn),
+                    (
+                        pool.to_underlying_down(e, &request.address, tokens_out),
+                        to_burn,
+                    ),
                 );
             }
             2 => {
                 // supply collateral
+                let amount = pool.to_shares_down(e, &request.address, request.amount);
                 let mut reserve = pool.load_reserve(e, &request.address);
-                let b_tokens_minted = reserve.to_b_token_down(request.amount);
+                let b_tokens_minted = reserve.to_b_token_down(amount);
                 from_state.add_collateral(e, &mut reserve, b_tokens_minted);
-                actions.add_for_spender_transfer(&reserve.asset, request.amount);
+                actions.add_for_spender_transfer(&reserve.asset, amount);
+                last_tokens = Some((reserve.asset.clone(), b_tokens_minted));
                 pool.cache_reserve(reserve, true);
                 e.events().publish(
                     (
@@ -128,21 +195,28 @@ pub fn build_actions_from_request(
                         request.address.clone(),
                         from.clone(),
                     ),
-                    (request.amount, b_tokens_minted),
+                    (
+                        pool.to_underlying_up(e, &request.address, amount),
+                        b_tokens_minted,
+                    ),
                 );
             }
             3 => {
                 // withdraw collateral
                 let mut reserve = pool.load_reserve(e, &request.address);
                 let cur_b_tokens = from_state.get_collateral(reserve.index);
-                let mut to_burn = reserve.to_b_token_up(request.amount);
-                let mut tokens_out = request.amount;
-                if to_burn > cur_b_tokens {
-                    to_burn = cur_b_tokens;
-                    tokens_out = reserve.to_asset_from_b_token(cur_b_tokens);
-                }
+                let (to_burn, tokens_out) =
+                    calc_withdrawal(e, pool, &reserve, request.amount, cur_b_tokens);
                 from_state.remove_collateral(e, &mut reserve, to_burn);
+                if !check_withdraw_util.contains(&reserve.asset) {
+                    check_withdraw_util.push_back(reserve.asset.clone());
+                }
+                withdrawn.set(
+                    reserve.asset.clone(),
+                    tokens_out + withdrawn.get(reserve.asset.clone()).unwrap_or(0),
+                );
                 actions.add_for_pool_transfer(&reserve.asset, tokens_out);
+                last_tokens = Some((reserve.asset.clone(), to_burn));
                 check_health = true;
                 pool.cache_reserve(reserve, true);
                 e.events().publish(
@@ -151,16 +225,25 @@ pub fn build_actions_from_request(
                         request.address.clone(),
                         from.clone(),
                     ),
-                    (tokens_out, to_burn),
+                    (
+                        pool.to_underlying_down(e, &request.address, tokens_out),
+                        to_burn,
+                    ),
                 );
             }
             4 => {
                 // borrow
+                let (amount, fee) = calc_borrow_shares(e, pool, &request.address, request.amount);
                 let mut reserve = pool.load_reserve(e, &request.address);
-                let d_tokens_minted = reserve.to_d_token_up(request.amount);
+                // the origination fee is added to the debt, not deducted from the proceeds
+                let d_tokens_minted = reserve.to_d_token_up(amount + fee);
                 from_state.add_liabilities(e, &mut reserve, d_tokens_minted);
-                reserve.require_utilization_below_max(e);
-                actions.add_for_pool_transfer(&reserve.asset, request.amount);
+                distribute_borrow_fee(&mut reserve, pool.config.bstop_rate, fee);
+                last_tokens = Some((reserve.asset.clone(), d_tokens_minted));
+                if !check_util.contains(&reserve.asset) {
+                    check_util.push_back(reserve.asset.clone());
+                }
+                actions.add_for_pool_transfer(&reserve.asset, amount);
                 check_health = true;
                 pool.cache_reserve(reserve, true);
                 e.events().publish(
@@ -169,20 +252,39 @@ pub fn build_actions_from_request(
                         request.address.clone(),
                         from.clone(),
                     ),
-                    (request.amount, d_tokens_minted),
+                    (
+                        pool.to_underlying_down(e, &request.address, amount),
+                        d_tokens_minted,
+                    ),
                 );
+                if fee > 0 {
+                    e.events().publish(
+                        (
+                            Symbol::new(e, "borrow_fee"),
+                            request.address.clone(),
+                            from.clone(),
+                        ),
+                        pool.to_underlying_up(e, &request.address, fee),
+                    );
+                }
             }
             5 => {
                 // repay
                 let mut reserve = pool.load_reserve(e, &request.address);
                 let cur_d_tokens = from_state.get_liabilities(reserve.index);
-                let d_tokens_burnt = reserve.to_d_token_down(request.amount);
-                actions.add_for_spender_transfer(&reserve.asset, request.amount);
+                let amount = if request.amount == MAX_AMOUNT {
+                    // repay the entire liability at the current d_rate, leaving no debt behind
+                    reserve.to_asset_from_d_token(cur_d_tokens)
+                } else {
+                    pool.to_shares_down(e, &request.address, request.amount)
+                };
+                let d_tokens_burnt = reserve.to_d_token_down(amount);
+                actions.add_for_spender_transfer(&reserve.asset, amount);
                 if d_tokens_burnt > cur_d_tokens {
-                    let amount_to_refund =
-                        request.amount - reserve.to_asset_from_d_token(cur_d_tokens);
+                    let amount_to_refund = amount - reserve.to_asset_from_d_token(cur_d_tokens);
                     require_nonnegative(e, &amount_to_refund);
                     from_state.remove_liabilities(e, &mut reserve, cur_d_tokens);
+                    last_tokens = Some((reserve.asset.clone(), cur_d_tokens));
                     actions.add_for_pool_transfer(&reserve.asset, amount_to_refund);
                     e.events().publish(
                         (
@@ -190,17 +292,24 @@ pub fn build_actions_from_request(
                             request.address.clone().clone(),
                             from.clone(),
                         ),
-                        (request.amount - amount_to_refund, cur_d_tokens),
+                        (
+                            pool.to_underlying_up(e, &request.address, amount - amount_to_refund),
+                            cur_d_tokens,
+                        ),
                     );
                 } else {
                     from_state.remove_liabilities(e, &mut reserve, d_tokens_burnt);
+                    last_tokens = Some((reserve.asset.clone(), d_tokens_burnt));
                     e.events().publish(
                         (
                             Symbol::new(e, "repay"),
                             request.address.clone().clone(),
                             from.clone(),
                         ),
-                        (request.amount, d_tokens_burnt),
+                        (
+                            pool.to_underlying_up(e, &request.address, amount),
+                            d_tokens_burnt,
+                        ),
                     );
                 }
                 pool.cache_reserve(reserve, true);
@@ -268,17 +377,194 @@ pub fn build_actions_from_request(
                     (from.clone(), request.amount),
                 );
             }
+            9 => {
+                // max borrow apr - verified against the final state of the reserve
+                let max_apr = match max_borrow_aprs.get(request.address.clone()) {
+                    Some(cur_max_apr) => cur_max_apr.min(request.amount),
+                    None => request.amount,
+                };
+                max_borrow_aprs.set(request.address.clone(), max_apr);
+            }
+            10 | 11 => {
+                // min or max tokens - bounds the b or d tokens moved by the previous request
+                let tokens = match prev_tokens {
+                    Some((asset, tokens)) if asset == request.address => tokens,
+                    _ => panic_with_error!(e, PoolError::BadRequest),
+                };
+                if (request.request_type == 10 && tokens < request.amount)
+                    || (request.request_type == 11 && tokens > request.amount)
+                {
+                    panic_with_error!(e, PoolError::TokenSlippageExceeded);
+                }
+                // allow both bounds to be placed on the same request
+                last_tokens = Some((request.address.clone(), tokens));
+            }
+            12 => {
+                // close position - withdraw all supply and collateral and claim the reserve's emissions
+                let mut reserve = pool.load_reserve(e, &request.address);
+                if from_state.get_liabilities(reserve.index) > 0 {
+                    panic_with_error!(e, PoolError::BadRequest);
+                }
+                // settle the reserve's emissions against the balances being closed
+                let claimed = emissions::try_claim_reserve(e, from, &from_state, &reserve);
+                let supply = from_state.get_supply(reserve.index);
+                let collateral = from_state.get_collateral(reserve.index);
+                if supply > 0 {
+                    from_state.remove_supply(e, &mut reserve, supply);
+                }
+                if collateral > 0 {
+                    from_state.remove_collateral(e, &mut reserve, collateral);
+                    check_health = true;
+                }
+                // convert the full balance at once, such that no dust is left behind by rounding
+                let b_tokens = supply + collateral;
+                let tokens_out = reserve.to_asset_from_b_token(b_tokens);
+                if b_tokens > 0 {
+                    if !check_withdraw_util.contains(&reserve.asset) {
+                        check_withdraw_util.push_back(reserve.asset.clone());
+                    }
+                    withdrawn.set(
+                        reserve.asset.clone(),
+                        tokens_out + withdrawn.get(reserve.asset.clone()).unwrap_or(0),
+                    );
+                    actions.add_for_pool_transfer(&reserve.asset, tokens_out);
+                }
+                last_tokens = Some((reserve.asset.clone(), b_tokens));
+                pool.cache_reserve(reserve, true);
+                e.events().publish(
+                    (
+                        Symbol::new(e, "close_position"),
+                        request.address.clone(),
+                        from.clone(),
+                    ),
+                    (
+                        pool.to_underlying_down(e, &request.address, tokens_out),
+                        b_tokens,
+                        claimed,
+                    ),
+                );
+            }
+            13 => {
+                // expiration - the batch can not be executed after the ledger sequence in the
+                // amount, verified before any request is processed
+            }
+            14 => {
+                // enable collateral - move the user's entire supply of the reserve into collateral
+                let mut reserve = pool.load_reserve(e, &request.address);
+                let b_tokens = from_state.get_supply(reserve.index);
+                if b_tokens == 0 {
+                    panic_with_error!(e, PoolError::BadRequest);
+                }
+                from_state.remove_supply(e, &mut reserve, b_tokens);
+                from_state.add_collateral(e, &mut reserve, b_tokens);
+                last_tokens = Some((reserve.asset.clone(), b_tokens));
+                // enabling collateral can change the factors that apply through e-mode or
+                // isolation, so the health factor is checked in both directions
+                check_health = true;
+                pool.cache_reserve(reserve, true);
+                e.events().publish(
+                    (
+                        Symbol::new(e, "set_collateral"),
+                        request.address.clone(),
+                        from.clone(),
+                    ),
+                    true,
+                );
+            }
+            15 => {
+                // disable collateral - move the user's entire collateral of the reserve into supply
+                let mut reserve = pool.load_reserve(e, &request.address);
+                let b_tokens = from_state.get_collateral(reserve.index);
+                if b_tokens == 0 {
+                    panic_with_error!(e, PoolError::BadRequest);
+                }
+                from_state.remove_collateral(e, &mut reserve, b_tokens);
+                from_state.add_supply(e, &mut reserve, b_tokens);
+                last_tokens = Some((reserve.asset.clone(), b_tokens));
+                check_health = true;
+                pool.cache_reserve(reserve, true);
+                e.events().publish(
+                    (
+                        Symbol::new(e, "set_collateral"),
+                        request.address.clone(),
+                        from.clone(),
+                    ),
+                    false,
+                );
+            }
             _ => panic_with_error!(e, PoolError::BadRequest),
         }
     }
+
+    // verify utilization against the final state of each reserve, so supplies later in the
+    // batch can support borrows and withdrawals earlier in the batch
+    for asset in check_util.iter() {
+        pool.load_reserve(e, &asset)
+            .require_utilization_below_max(e);
+    }
+    for asset in check_withdraw_util.iter() {
+        pool.load_reserve(e, &asset)
+            .require_utilization_below_withdraw_max(e);
+    }
+
+    // new debt can only be originated once the backstop has reached the pool's floor
+    if !check_util.is_empty() {
+        require_backstop_floor(e);
+    }
+
+    // verify the borrow rate each reserve is left at after accrual and the batch does not exceed
+    // the rate the user signed for
+    for (asset, max_apr) in max_borrow_aprs.iter() {
+        let reserve = pool.load_reserve(e, &asset);
+        let reserve_config = storage::get_res_config(e, &asset);
+        let borrow_apr = calc_borrow_rate(&reserve_config, reserve.utilization(), reserve.ir_mod);
+        if borrow_apr > max_apr {
+            panic_with_error!(e, PoolError::BorrowRateTooHigh);
+        }
+    }
+
+    // verify the total withdrawn from each reserve across the batch, so a large withdrawal
+    // cannot avoid its queue by being split into several requests
+    for (asset, tokens_out) in withdrawn.iter() {
+        let reserve_index = pool.load_reserve(e, &asset).index;
+        let amount = pool.to_underlying_down(e, &asset, tokens_out);
+        require_large_withdrawal_unlocked(e, from, &asset, reserve_index, amount);
+    }
     (actions, from_state, check_health)
 }
 
+/// Calculate the b_tokens burnt and the tokens withdrawn, in share units, for a withdrawal of
+/// "amount" from a balance of "cur_b_tokens". A withdrawal of `MAX_AMOUNT` or of more than the
+/// balance withdraws the entire balance at the current b_rate, such that no dust is left behind.
+///
+/// The amount is rounded up into share units, such that at least the requested amount of
+/// underlying tokens is withdrawn.
+fn calc_withdrawal(
+    e: &Env,
+    pool: &mut Pool,
+    reserve: &Reserve,
+    amount: i128,
+    cur_b_tokens: i128,
+) -> (i128, i128) {
+    if amount != MAX_AMOUNT {
+        let amount = pool.to_shares_up(e, &reserve.asset, amount);
+        let to_burn = reserve.to_b_token_up(amount);
+        if to_burn <= cur_b_tokens {
+            return (to_burn, amount);
+        }
+    }
+    (cur_b_tokens, reserve.to_asset_from_b_token(cur_b_tokens))
+}
+
 #[cfg(test)]
 mod tests {
 
     use crate::{
-        storage::{self, PoolConfig},
+        constants::PAUSE_BORROW,
+        pool::execute_queue_withdrawal,
+        storage::{
+            self, PoolConfig, ReserveEmissionsConfig, ReserveEmissionsData, UserEmissionData,
+        },
         testutils, AuctionData, AuctionType, Positions,
     };
 
@@ -321,6 +607,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -355,15 +643,1114 @@ mod tests {
             assert_eq!(positions.supply.len(), 1);
             assert_eq!(user.get_supply(0), 10_1234488);
 
-            let reserve = pool.load_reserve(&e, &underlying);
-            assert_eq!(reserve.b_supply, reserve_data.b_supply + user.get_supply(0));
+            let reserve = pool.load_reserve(&e, &underlying);
+            assert_eq!(reserve.b_supply, reserve_data.b_supply + user.get_supply(0));
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_supply_token_bounds() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 0,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+                Request {
+                    request_type: 10,
+                    address: underlying.clone(),
+                    amount: 10_1234400,
+                },
+                Request {
+                    request_type: 11,
+                    address: underlying.clone(),
+                    amount: 10_1234488,
+                },
+            ];
+            let (_, user, _) = build_actions_from_request(&e, &mut pool, &samwise, requests);
+            assert_eq!(user.get_supply(0), 10_1234488);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(17)")]
+    fn test_build_actions_from_request_supply_min_tokens_exceeded() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 0,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+                Request {
+                    request_type: 10,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_build_actions_from_request_token_bound_without_request() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 11,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+                Request {
+                    request_type: 0,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    /***** withdraw *****/
+
+    #[test]
+    fn test_build_actions_from_request_withdraw() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e],
+            supply: map![&e, (0, 20_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 1,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(health_check, false);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 10_1234567);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.supply.len(), 1);
+            assert_eq!(user.get_supply(0), 9_8765502);
+
+            let reserve = pool.load_reserve(&e, &underlying);
+            assert_eq!(
+                reserve.b_supply,
+                reserve_data.b_supply - (20_0000000 - 9_8765502)
+            );
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_withdraw_over_balance() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e],
+            supply: map![&e, (0, 20_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 1,
+                    address: underlying.clone(),
+                    amount: 21_0000000,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(health_check, false);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 20_0000137);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.supply.len(), 0);
+
+            let reserve = pool.load_reserve(&e, &underlying.clone());
+            assert_eq!(reserve.b_supply, reserve_data.b_supply - 20_0000000);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_withdraw_max_amount() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e],
+            supply: map![&e, (0, 20_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 1,
+                    address: underlying.clone(),
+                    amount: MAX_AMOUNT,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(health_check, false);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 20_0000137);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.supply.len(), 0);
+
+            let reserve = pool.load_reserve(&e, &underlying.clone());
+            assert_eq!(reserve.b_supply, reserve_data.b_supply - 20_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(15)")]
+    fn test_build_actions_from_request_withdraw_large_not_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e, (0, 10_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_res_large_withdrawal(&e, &underlying, &5_0000000);
+
+            let mut pool = Pool::load(&e);
+
+            // each request is below the threshold, but the batch is not
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 1,
+                    address: underlying.clone(),
+                    amount: 4_0000000,
+                },
+                Request {
+                    request_type: 3,
+                    address: underlying.clone(),
+                    amount: 4_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_withdraw_large_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e],
+            supply: map![&e, (0, 20_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_res_large_withdrawal(&e, &underlying, &5_0000000);
+            execute_queue_withdrawal(&e, &samwise, &underlying, 10_1234567);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600 + 60 * 60,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        e.as_contract(&pool, || {
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 1,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            let (actions, user, _) = build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(
+                actions.pool_transfer.get_unchecked(underlying.clone()),
+                10_1234567
+            );
+            assert_eq!(user.get_supply(0), 9_8765502);
+            assert!(storage::get_queued_withdrawal(&e, &samwise, 0).is_none());
+        });
+    }
+
+    /***** supply collateral *****/
+
+    #[test]
+    fn test_build_actions_from_request_supply_collateral() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(health_check, false);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 1);
+            assert_eq!(
+                spender_transfer.get_unchecked(underlying.clone()),
+                10_1234567
+            );
+            assert_eq!(pool_transfer.len(), 0);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(positions.collateral.len(), 1);
+            assert_eq!(positions.supply.len(), 0);
+            assert_eq!(user.get_collateral(0), 10_1234488);
+
+            let reserve = pool.load_reserve(&e, &underlying.clone());
+            assert_eq!(
+                reserve.b_supply,
+                reserve_data.b_supply + user.get_collateral(0)
+            );
+        });
+    }
+
+    /***** withdraw collateral *****/
+
+    #[test]
+    fn test_build_actions_from_request_withdraw_collateral() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 3,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(health_check, true);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 10_1234567);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(positions.collateral.len(), 1);
+            assert_eq!(positions.supply.len(), 0);
+            assert_eq!(user.get_collateral(0), 9_8765502);
+
+            let reserve = pool.load_reserve(&e, &underlying);
+            assert_eq!(
+                reserve.b_supply,
+                reserve_data.b_supply - (20_0000000 - 9_8765502)
+            );
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_withdraw_collateral_over_balance() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 3,
+                    address: underlying.clone(),
+                    amount: 21_0000000,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(health_check, true);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 20_0000137);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.supply.len(), 0);
+
+            let reserve = pool.load_reserve(&e, &underlying);
+            assert_eq!(reserve.b_supply, reserve_data.b_supply - 20_0000000);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_withdraw_collateral_max_amount() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 3,
+                    address: underlying.clone(),
+                    amount: MAX_AMOUNT,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(health_check, true);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 20_0000137);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.supply.len(), 0);
+
+            let reserve = pool.load_reserve(&e, &underlying);
+            assert_eq!(reserve.b_supply, reserve_data.b_supply - 20_0000000);
+        });
+    }
+
+    /***** borrow *****/
+
+    #[test]
+    fn test_build_actions_from_request_borrow() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+            assert_eq!(health_check, true);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 10_1234567);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 1);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.supply.len(), 0);
+            assert_eq!(user.get_liabilities(0), 10_1234452);
+
+            let reserve = pool.load_reserve(&e, &underlying);
+            assert_eq!(reserve.d_supply, reserve_data.d_supply + 10_1234452);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_borrow_with_fee() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_res_borrow_fee(&e, &underlying, &0_0100000);
+
+            let mut pool = Pool::load(&e);
+            let reserve_before = pool.load_reserve(&e, &underlying);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            let (actions, user, _) = build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            // the borrower receives the full amount, and the fee is added to their debt
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 10_1234567);
+            let d_tokens = reserve_before.to_d_token_up(10_1234567 + 0_1012346);
+            assert_eq!(user.get_liabilities(0), d_tokens);
+
+            let reserve = pool.load_reserve(&e, &underlying);
+            assert_eq!(reserve.d_supply, reserve_before.d_supply + d_tokens);
+            assert_eq!(
+                reserve.backstop_credit,
+                reserve_before.backstop_credit + 0_0202469
+            );
+            assert!(reserve.b_rate > reserve_before.b_rate);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(11)")]
+    fn test_build_actions_from_request_borrow_reserve_paused() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_res_pause_flags(&e, &underlying, &PAUSE_BORROW);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_borrow_max_apr() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            // borrow rate is ~31.3% after the borrow
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+                Request {
+                    request_type: 9,
+                    address: underlying.clone(),
+                    amount: 0_3500000,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+            assert_eq!(health_check, true);
+            assert_eq!(
+                actions.pool_transfer.get_unchecked(underlying.clone()),
+                10_1234567
+            );
+            assert_eq!(user.get_liabilities(0), 10_1234452);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(16)")]
+    fn test_build_actions_from_request_borrow_max_apr_exceeded() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            // borrow rate is ~31.3% after the borrow
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+                Request {
+                    request_type: 9,
+                    address: underlying.clone(),
+                    amount: 0_3000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
         });
     }
 
-    /***** withdraw *****/
+    /***** expiration *****/
 
     #[test]
-    fn test_build_actions_from_request_withdraw() {
+    fn test_build_actions_from_request_expiration() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -374,7 +1761,6 @@ mod tests {
         let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
         let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
         testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
-
         e.ledger().set(LedgerInfo {
             timestamp: 600,
             protocol_version: 1,
@@ -389,54 +1775,44 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_200_000_000,
             status: 0,
-        };
-
-        let user_positions = Positions {
-            liabilities: map![&e],
-            collateral: map![&e],
-            supply: map![&e, (0, 20_0000000)],
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            storage::set_user_positions(&e, &samwise, &user_positions);
 
             let mut pool = Pool::load(&e);
 
+            // the batch can be executed up to and including the expiration ledger, and the
+            // expiration's address is not a reserve
             let requests = vec![
                 &e,
                 Request {
-                    request_type: 1,
+                    request_type: 13,
+                    address: Address::random(&e),
+                    amount: 1234,
+                },
+                Request {
+                    request_type: 0,
                     address: underlying.clone(),
                     amount: 10_1234567,
                 },
             ];
             let (actions, user, health_check) =
                 build_actions_from_request(&e, &mut pool, &samwise, requests);
-
             assert_eq!(health_check, false);
-
-            let spender_transfer = actions.spender_transfer;
-            let pool_transfer = actions.pool_transfer;
-            assert_eq!(spender_transfer.len(), 0);
-            assert_eq!(pool_transfer.len(), 1);
-            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 10_1234567);
-
-            let positions = user.positions.clone();
-            assert_eq!(positions.liabilities.len(), 0);
-            assert_eq!(positions.collateral.len(), 0);
-            assert_eq!(positions.supply.len(), 1);
-            assert_eq!(user.get_supply(0), 9_8765502);
-
-            let reserve = pool.load_reserve(&e, &underlying);
             assert_eq!(
-                reserve.b_supply,
-                reserve_data.b_supply - (20_0000000 - 9_8765502)
+                actions.spender_transfer.get_unchecked(underlying.clone()),
+                10_1234567
             );
+            assert_eq!(user.get_supply(0), 10_1234488);
         });
     }
 
     #[test]
-    fn test_build_actions_from_request_withdraw_over_balance() {
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(50)")]
+    fn test_build_actions_from_request_expired() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -447,7 +1823,6 @@ mod tests {
         let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
         let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
         testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
-
         e.ledger().set(LedgerInfo {
             timestamp: 600,
             protocol_version: 1,
@@ -462,51 +1837,35 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_200_000_000,
             status: 0,
-        };
-        let user_positions = Positions {
-            liabilities: map![&e],
-            collateral: map![&e],
-            supply: map![&e, (0, 20_0000000)],
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            storage::set_user_positions(&e, &samwise, &user_positions);
 
             let mut pool = Pool::load(&e);
 
             let requests = vec![
                 &e,
                 Request {
-                    request_type: 1,
+                    request_type: 0,
                     address: underlying.clone(),
-                    amount: 21_0000000,
+                    amount: 10_1234567,
+                },
+                Request {
+                    request_type: 13,
+                    address: underlying.clone(),
+                    amount: 1233,
                 },
             ];
-            let (actions, user, health_check) =
-                build_actions_from_request(&e, &mut pool, &samwise, requests);
-
-            assert_eq!(health_check, false);
-
-            let spender_transfer = actions.spender_transfer;
-            let pool_transfer = actions.pool_transfer;
-            assert_eq!(spender_transfer.len(), 0);
-            assert_eq!(pool_transfer.len(), 1);
-            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 20_0000137);
-
-            let positions = user.positions.clone();
-            assert_eq!(positions.liabilities.len(), 0);
-            assert_eq!(positions.collateral.len(), 0);
-            assert_eq!(positions.supply.len(), 0);
-
-            let reserve = pool.load_reserve(&e, &underlying.clone());
-            assert_eq!(reserve.b_supply, reserve_data.b_supply - 20_0000000);
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
         });
     }
 
-    /***** supply collateral *****/
+    /***** repay *****/
 
     #[test]
-    fn test_build_actions_from_request_supply_collateral() {
+    fn test_build_actions_from_request_repay() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -530,18 +1889,26 @@ mod tests {
         });
         let pool_config = PoolConfig {
             oracle: Address::random(&e),
-            bstop_rate: 0_100_000_000,
+            bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 20_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
 
             let mut pool = Pool::load(&e);
 
             let requests = vec![
                 &e,
                 Request {
-                    request_type: 2,
+                    request_type: 5,
                     address: underlying.clone(),
                     amount: 10_1234567,
                 },
@@ -561,23 +1928,19 @@ mod tests {
             assert_eq!(pool_transfer.len(), 0);
 
             let positions = user.positions.clone();
-            assert_eq!(positions.liabilities.len(), 0);
-            assert_eq!(positions.collateral.len(), 1);
+            assert_eq!(positions.liabilities.len(), 1);
+            assert_eq!(positions.collateral.len(), 0);
             assert_eq!(positions.supply.len(), 0);
-            assert_eq!(user.get_collateral(0), 10_1234488);
+            let d_tokens_repaid = 10_1234451;
+            assert_eq!(user.get_liabilities(0), 20_0000000 - d_tokens_repaid);
 
-            let reserve = pool.load_reserve(&e, &underlying.clone());
-            assert_eq!(
-                reserve.b_supply,
-                reserve_data.b_supply + user.get_collateral(0)
-            );
+            let reserve = pool.load_reserve(&e, &underlying);
+            assert_eq!(reserve.d_supply, reserve_data.d_supply - d_tokens_repaid);
         });
     }
 
-    /***** withdraw collateral *****/
-
     #[test]
-    fn test_build_actions_from_request_withdraw_collateral() {
+    fn test_build_actions_from_request_repay_over_balance() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -603,10 +1966,12 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let user_positions = Positions {
-            liabilities: map![&e],
-            collateral: map![&e, (0, 20_0000000)],
+            liabilities: map![&e, (0, 20_0000000)],
+            collateral: map![&e],
             supply: map![&e],
         };
         e.as_contract(&pool, || {
@@ -618,38 +1983,38 @@ mod tests {
             let requests = vec![
                 &e,
                 Request {
-                    request_type: 3,
+                    request_type: 5,
                     address: underlying.clone(),
-                    amount: 10_1234567,
+                    amount: 21_0000000,
                 },
             ];
             let (actions, user, health_check) =
                 build_actions_from_request(&e, &mut pool, &samwise, requests);
 
-            assert_eq!(health_check, true);
+            assert_eq!(health_check, false);
 
             let spender_transfer = actions.spender_transfer;
             let pool_transfer = actions.pool_transfer;
-            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(spender_transfer.len(), 1);
+            assert_eq!(
+                spender_transfer.get_unchecked(underlying.clone()),
+                21_0000000
+            );
             assert_eq!(pool_transfer.len(), 1);
-            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 10_1234567);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 0_9999771);
 
             let positions = user.positions.clone();
             assert_eq!(positions.liabilities.len(), 0);
-            assert_eq!(positions.collateral.len(), 1);
+            assert_eq!(positions.collateral.len(), 0);
             assert_eq!(positions.supply.len(), 0);
-            assert_eq!(user.get_collateral(0), 9_8765502);
 
             let reserve = pool.load_reserve(&e, &underlying);
-            assert_eq!(
-                reserve.b_supply,
-                reserve_data.b_supply - (20_0000000 - 9_8765502)
-            );
+            assert_eq!(reserve.d_supply, reserve_data.d_supply - 20_0000000);
         });
     }
 
     #[test]
-    fn test_build_actions_from_request_withdraw_collateral_over_balance() {
+    fn test_build_actions_from_request_repay_max_amount() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -675,10 +2040,12 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let user_positions = Positions {
-            liabilities: map![&e],
-            collateral: map![&e, (0, 20_0000000)],
+            liabilities: map![&e, (0, 20_0000000)],
+            collateral: map![&e],
             supply: map![&e],
         };
         e.as_contract(&pool, || {
@@ -690,21 +2057,24 @@ mod tests {
             let requests = vec![
                 &e,
                 Request {
-                    request_type: 3,
+                    request_type: 5,
                     address: underlying.clone(),
-                    amount: 21_0000000,
+                    amount: MAX_AMOUNT,
                 },
             ];
             let (actions, user, health_check) =
                 build_actions_from_request(&e, &mut pool, &samwise, requests);
 
-            assert_eq!(health_check, true);
+            assert_eq!(health_check, false);
 
             let spender_transfer = actions.spender_transfer;
             let pool_transfer = actions.pool_transfer;
-            assert_eq!(spender_transfer.len(), 0);
-            assert_eq!(pool_transfer.len(), 1);
-            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 20_0000137);
+            assert_eq!(spender_transfer.len(), 1);
+            assert_eq!(
+                spender_transfer.get_unchecked(underlying.clone()),
+                20_0000229
+            );
+            assert_eq!(pool_transfer.len(), 0);
 
             let positions = user.positions.clone();
             assert_eq!(positions.liabilities.len(), 0);
@@ -712,14 +2082,14 @@ mod tests {
             assert_eq!(positions.supply.len(), 0);
 
             let reserve = pool.load_reserve(&e, &underlying);
-            assert_eq!(reserve.b_supply, reserve_data.b_supply - 20_0000000);
+            assert_eq!(reserve.d_supply, reserve_data.d_supply - 20_0000000);
         });
     }
 
-    /***** borrow *****/
+    /***** close position *****/
 
     #[test]
-    fn test_build_actions_from_request_borrow() {
+    fn test_build_actions_from_request_close_position() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -730,6 +2100,7 @@ mod tests {
         let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
         let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
         testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
         e.ledger().set(LedgerInfo {
             timestamp: 600,
             protocol_version: 1,
@@ -744,45 +2115,59 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 5_0000000)],
+            supply: map![&e, (0, 20_0000000)],
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
 
             let mut pool = Pool::load(&e);
 
             let requests = vec![
                 &e,
                 Request {
-                    request_type: 4,
+                    request_type: 12,
                     address: underlying.clone(),
-                    amount: 10_1234567,
+                    amount: 0,
+                },
+                Request {
+                    request_type: 10,
+                    address: underlying.clone(),
+                    amount: 25_0000000,
                 },
             ];
             let (actions, user, health_check) =
                 build_actions_from_request(&e, &mut pool, &samwise, requests);
+
             assert_eq!(health_check, true);
 
+            let reserve = pool.load_reserve(&e, &underlying);
             let spender_transfer = actions.spender_transfer;
             let pool_transfer = actions.pool_transfer;
             assert_eq!(spender_transfer.len(), 0);
             assert_eq!(pool_transfer.len(), 1);
-            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 10_1234567);
+            assert_eq!(
+                pool_transfer.get_unchecked(underlying.clone()),
+                reserve.to_asset_from_b_token(25_0000000)
+            );
 
             let positions = user.positions.clone();
-            assert_eq!(positions.liabilities.len(), 1);
+            assert_eq!(positions.liabilities.len(), 0);
             assert_eq!(positions.collateral.len(), 0);
             assert_eq!(positions.supply.len(), 0);
-            assert_eq!(user.get_liabilities(0), 10_1234452);
-
-            let reserve = pool.load_reserve(&e, &underlying);
-            assert_eq!(reserve.d_supply, reserve_data.d_supply + 10_1234452);
+            assert_eq!(reserve.b_supply, reserve_data.b_supply - 25_0000000);
         });
     }
 
-    /***** repay *****/
-
     #[test]
-    fn test_build_actions_from_request_repay() {
+    fn test_build_actions_from_request_close_position_emissions_paused() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -808,54 +2193,75 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
+
         let user_positions = Positions {
-            liabilities: map![&e, (0, 20_0000000)],
-            collateral: map![&e],
-            supply: map![&e],
+            liabilities: map![&e],
+            collateral: map![&e, (0, 5_0000000)],
+            supply: map![&e, (0, 20_0000000)],
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
             storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_res_emis_config(
+                &e,
+                &1,
+                &ReserveEmissionsConfig {
+                    expiration: 1000,
+                    eps: 0_0100000,
+                },
+            );
+            storage::set_res_emis_data(
+                &e,
+                &1,
+                &ReserveEmissionsData {
+                    index: 1_0000000,
+                    last_time: 500,
+                },
+            );
+            storage::set_user_emissions(
+                &e,
+                &samwise,
+                &1,
+                &UserEmissionData {
+                    index: 0,
+                    accrued: 0,
+                },
+            );
+            storage::set_emissions_pause(&e, &500);
 
             let mut pool = Pool::load(&e);
 
             let requests = vec![
                 &e,
                 Request {
-                    request_type: 5,
+                    request_type: 12,
                     address: underlying.clone(),
-                    amount: 10_1234567,
+                    amount: 0,
                 },
             ];
-            let (actions, user, health_check) =
-                build_actions_from_request(&e, &mut pool, &samwise, requests);
-
-            assert_eq!(health_check, false);
+            let (actions, user, _) = build_actions_from_request(&e, &mut pool, &samwise, requests);
 
-            let spender_transfer = actions.spender_transfer;
-            let pool_transfer = actions.pool_transfer;
-            assert_eq!(spender_transfer.len(), 1);
+            // the position is closed and the emissions are accrued against the closed balance
+            let reserve = pool.load_reserve(&e, &underlying);
             assert_eq!(
-                spender_transfer.get_unchecked(underlying.clone()),
-                10_1234567
+                actions.pool_transfer.get_unchecked(underlying.clone()),
+                reserve.to_asset_from_b_token(25_0000000)
             );
-            assert_eq!(pool_transfer.len(), 0);
-
-            let positions = user.positions.clone();
-            assert_eq!(positions.liabilities.len(), 1);
-            assert_eq!(positions.collateral.len(), 0);
-            assert_eq!(positions.supply.len(), 0);
-            let d_tokens_repaid = 10_1234451;
-            assert_eq!(user.get_liabilities(0), 20_0000000 - d_tokens_repaid);
-
-            let reserve = pool.load_reserve(&e, &underlying);
-            assert_eq!(reserve.d_supply, reserve_data.d_supply - d_tokens_repaid);
+            assert_eq!(user.positions.supply.len(), 0);
+            assert_eq!(user.positions.collateral.len(), 0);
+            let user_emissions = storage::get_user_emissions(&e, &samwise, &1).unwrap();
+            assert_eq!(user_emissions.index, 1_0000000);
+            assert_eq!(user_emissions.accrued, 25_0000000);
         });
     }
 
     #[test]
-    fn test_build_actions_from_request_repay_over_balance() {
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_build_actions_from_request_close_position_with_liabilities() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -881,10 +2287,13 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
+
         let user_positions = Positions {
-            liabilities: map![&e, (0, 20_0000000)],
-            collateral: map![&e],
+            liabilities: map![&e, (0, 1)],
+            collateral: map![&e, (0, 5_0000000)],
             supply: map![&e],
         };
         e.as_contract(&pool, || {
@@ -896,33 +2305,12 @@ mod tests {
             let requests = vec![
                 &e,
                 Request {
-                    request_type: 5,
+                    request_type: 12,
                     address: underlying.clone(),
-                    amount: 21_0000000,
+                    amount: 0,
                 },
             ];
-            let (actions, user, health_check) =
-                build_actions_from_request(&e, &mut pool, &samwise, requests);
-
-            assert_eq!(health_check, false);
-
-            let spender_transfer = actions.spender_transfer;
-            let pool_transfer = actions.pool_transfer;
-            assert_eq!(spender_transfer.len(), 1);
-            assert_eq!(
-                spender_transfer.get_unchecked(underlying.clone()),
-                21_0000000
-            );
-            assert_eq!(pool_transfer.len(), 1);
-            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 0_9999771);
-
-            let positions = user.positions.clone();
-            assert_eq!(positions.liabilities.len(), 0);
-            assert_eq!(positions.collateral.len(), 0);
-            assert_eq!(positions.supply.len(), 0);
-
-            let reserve = pool.load_reserve(&e, &underlying);
-            assert_eq!(reserve.d_supply, reserve_data.d_supply - 20_0000000);
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
         });
     }
 
@@ -960,6 +2348,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let user_positions = Positions::env_default(&e);
         e.as_contract(&pool, || {
@@ -1105,11 +2495,16 @@ mod tests {
                 (underlying_1.clone(), 1_5395739)
             ],
             block: 176,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let pool_config = PoolConfig {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let positions: Positions = Positions {
             collateral: map![
@@ -1152,6 +2547,9 @@ mod tests {
                     (underlying_1.clone(), 7697870)
                 ],
                 block: 176,
+                timestamp: 12345,
+                mode: 0,
+                valuation: 0,
             };
             let new_auction =
                 storage::get_auction(&e, &(AuctionType::UserLiquidation as u32), &samwise);
@@ -1232,11 +2630,16 @@ mod tests {
             oracle: oracle_address,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let auction_data = AuctionData {
             bid: map![&e, (underlying_0, 10_0000000), (underlying_1, 2_5000000)],
             lot: map![&e, (backstop_token_id, 95_2000000)],
             block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         let positions: Positions = Positions {
             collateral: map![&e],
@@ -1360,6 +2763,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let auction_data = AuctionData {
             bid: map![&e, (usdc_id.clone(), 952_0000000)],
@@ -1369,6 +2774,9 @@ mod tests {
                 (underlying_1.clone(), 25_0000000)
             ],
             block: 51,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
         };
         usdc_client.mint(&samwise, &95_2000000);
         //samwise increase allowance for pool
@@ -0,0 +1,142 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{errors::PoolError, storage};
+
+/// Require that a user is allowed to interact with the pool. Permissioned pools only allow
+/// users on the pool's allowlist to submit requests and fill auctions.
+///
+/// ### Arguments
+/// * `user` - The address of the user
+///
+/// ### Panics
+/// If the pool is permissioned and the user is not on the allowlist
+pub fn require_allowed(e: &Env, user: &Address) {
+    if storage::get_permissioned(e) && !storage::is_allowed(e, user) {
+        panic_with_error!(e, PoolError::NotAuthorized);
+    }
+}
+
+/// Add or remove a user from the allowlist of a permissioned pool
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `allowed` - If the user is allowed to interact with the pool
+///
+/// ### Panics
+/// If the pool is not permissioned
+pub fn set_allowed(e: &Env, user: &Address, allowed: bool) {
+    if !storage::get_permissioned(e) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if allowed {
+        storage::set_allowed(e, user);
+    } else {
+        storage::del_allowed(e, user);
+    }
+}
+
+/// Set or remove the denylist contract used to screen the users that submit requests, claim
+/// emissions, and fill auctions
+///
+/// ### Arguments
+/// * `denylist` - The address of the denylist contract, or None to remove it
+pub fn set_denylist(e: &Env, denylist: &Option<Address>) {
+    match denylist {
+        Some(denylist) => storage::set_denylist(e, denylist),
+        None => storage::del_denylist(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{testutils, validator::require_not_denied};
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_require_allowed() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            // pools are not permissioned by default
+            require_allowed(&e, &samwise);
+
+            storage::set_permissioned(&e, &true);
+            set_allowed(&e, &samwise, true);
+            require_allowed(&e, &samwise);
+            assert!(storage::is_allowed(&e, &samwise));
+
+            set_allowed(&e, &samwise, false);
+            assert!(!storage::is_allowed(&e, &samwise));
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_require_allowed_not_on_allowlist_panics() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_permissioned(&e, &true);
+            require_allowed(&e, &samwise);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_allowed_not_permissioned_panics() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            set_allowed(&e, &samwise, true);
+        });
+    }
+
+    #[test]
+    fn test_require_not_denied() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (denylist, denylist_client) = testutils::create_mock_denylist(&e);
+        denylist_client.deny(&frodo);
+
+        e.as_contract(&pool, || {
+            // pools do not have a denylist by default
+            require_not_denied(&e, &frodo);
+
+            set_denylist(&e, &Some(denylist.clone()));
+            assert_eq!(storage::get_denylist(&e), Some(denylist.clone()));
+            require_not_denied(&e, &samwise);
+
+            set_denylist(&e, &None);
+            assert_eq!(storage::get_denylist(&e), None);
+            require_not_denied(&e, &frodo);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(7)")]
+    fn test_require_not_denied_on_denylist_panics() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (denylist, denylist_client) = testutils::create_mock_denylist(&e);
+        denylist_client.deny(&frodo);
+
+        e.as_contract(&pool, || {
+            set_denylist(&e, &Some(denylist));
+            require_not_denied(&e, &frodo);
+        });
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     storage::{self},
 };
 
-use super::{user::User, Pool};
+use super::{snapshot::record_bad_debt, user::User, Pool};
 
 /// Transfer bad debt from a user to the backstop. Validates that the user does hold bad debt
 /// and transfers all held d_tokens to the backstop.
@@ -49,6 +49,7 @@ pub fn transfer_bad_debt_to_backstop(e: &Env, user: &Address) {
     pool.store_cached_reserves(e);
     new_backstop_state.store(e);
     new_user_state.store(e);
+    record_bad_debt(e, user);
 }
 
 /// Burn bad debt from the backstop. This can only occur if the backstop module has reached a critical balance
@@ -116,6 +117,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let user_positions = Positions {
             liabilities: map![&e, (0, 24_0000000), (1, 25_0000000)],
@@ -183,6 +186,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let user_positions = Positions {
             liabilities: map![&e, (0, 24_0000000), (1, 25_0000000)],
@@ -236,6 +241,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let user_positions = Positions::env_default(&e);
         e.as_contract(&pool, || {
@@ -285,6 +292,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         let user_positions = Positions {
             liabilities: map![&e, (0, 24_0000000), (1, 25_0000000)],
@@ -340,6 +349,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
 
         let backstop_positions = Positions {
@@ -0,0 +1,357 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{
+    constants::POL_WITHDRAW_TIMELOCK,
+    dependencies::TokenClient,
+    errors::PoolError,
+    storage::{self, PolWithdrawal},
+};
+
+use super::{pause::require_reserve_action_allowed, pool::Pool};
+
+/// Supply protocol-owned liquidity into a reserve. The minted b_tokens are tracked separately
+/// from user positions, earn interest, and are excluded from the reserve's emissions. Supplying
+/// is subject to the pool status and the reserve's pause flags, as a regular supply is.
+///
+/// Returns the amount of b_tokens minted
+///
+/// ### Arguments
+/// * `from` - The address supplying the liquidity
+/// * `asset` - The underlying asset of the reserve
+/// * `amount` - The amount of underlying tokens to supply
+///
+/// ### Panics
+/// If the reserve does not exist, the amount is not positive, or supplying is not allowed by
+/// the pool status or the reserve's pause flags
+pub fn execute_supply_pol(e: &Env, from: &Address, asset: &Address, amount: i128) -> i128 {
+    if amount <= 0 || !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let mut pool = Pool::load(e);
+    pool.require_action_allowed(e, 0);
+    require_reserve_action_allowed(e, asset, 0);
+    let shares = pool.to_shares_down(e, asset, amount);
+    let mut reserve = pool.load_reserve(e, asset);
+    let b_tokens_minted = reserve.to_b_token_down(shares);
+    reserve.b_supply += b_tokens_minted;
+    reserve.store(e);
+    storage::set_pol(e, asset, &(storage::get_pol(e, asset) + b_tokens_minted));
+
+    TokenClient::new(e, asset).transfer(from, &e.current_contract_address(), &amount);
+    b_tokens_minted
+}
+
+/// Queue a withdrawal of protocol-owned liquidity from a reserve. The withdrawal can be
+/// executed once the timelock has passed. Only one withdrawal can be queued per reserve; an
+/// existing withdrawal must be cancelled before a new one is queued.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `b_tokens` - The amount of protocol-owned b_tokens to withdraw
+/// * `to` - The address receiving the underlying tokens
+///
+/// ### Panics
+/// If the amount is not positive, exceeds the protocol-owned b_tokens of the reserve, or a
+/// withdrawal is already queued
+pub fn execute_queue_pol_withdrawal(
+    e: &Env,
+    asset: &Address,
+    b_tokens: i128,
+    to: &Address,
+) -> PolWithdrawal {
+    if b_tokens <= 0
+        || b_tokens > storage::get_pol(e, asset)
+        || storage::get_pol_withdrawal(e, asset).is_some()
+    {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let withdrawal = PolWithdrawal {
+        b_tokens,
+        to: to.clone(),
+        unlock_time: e.ledger().timestamp() + POL_WITHDRAW_TIMELOCK,
+    };
+    storage::set_pol_withdrawal(e, asset, &withdrawal);
+    withdrawal
+}
+
+/// Cancel the queued withdrawal of protocol-owned liquidity from a reserve
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If no withdrawal is queued
+pub fn execute_cancel_pol_withdrawal(e: &Env, asset: &Address) {
+    if storage::get_pol_withdrawal(e, asset).is_none() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::del_pol_withdrawal(e, asset);
+}
+
+/// Execute the queued withdrawal of protocol-owned liquidity from a reserve
+///
+/// Returns the amount of underlying tokens withdrawn
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If no withdrawal is queued, the timelock has not passed, or the withdrawal would push
+/// the reserve's utilization above its maximum
+pub fn execute_withdraw_pol(e: &Env, asset: &Address) -> i128 {
+    let withdrawal = match storage::get_pol_withdrawal(e, asset) {
+        Some(withdrawal) => withdrawal,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    if withdrawal.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let pol = storage::get_pol(e, asset);
+    if withdrawal.b_tokens > pol {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let mut pool = Pool::load(e);
+    let mut reserve = pool.load_reserve(e, asset);
+    let shares_out = reserve.to_asset_from_b_token(withdrawal.b_tokens);
+    reserve.b_supply -= withdrawal.b_tokens;
+    if reserve.d_supply > 0 {
+        reserve.require_utilization_below_max(e);
+    }
+    reserve.store(e);
+    storage::set_pol(e, asset, &(pol - withdrawal.b_tokens));
+    storage::del_pol_withdrawal(e, asset);
+
+    let amount_out = pool.to_underlying_down(e, asset, shares_out);
+    TokenClient::new(e, asset).transfer(&e.current_contract_address(), &withdrawal.to, &amount_out);
+    amount_out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constants::PAUSE_SUPPLY, emissions, storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_protocol_owned_liquidity() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        reserve_data.d_supply = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_client.mint(&bombadil, &50_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let b_tokens = execute_supply_pol(&e, &bombadil, &underlying, 50_0000000);
+            assert_eq!(b_tokens, 50_0000000);
+            assert_eq!(storage::get_pol(&e, &underlying), 50_0000000);
+            assert_eq!(storage::get_res_data(&e, &underlying).b_supply, 150_0000000);
+            assert_eq!(
+                emissions::get_b_emission_supply(&e, &underlying, 150_0000000),
+                100_0000000
+            );
+            assert!(storage::get_user_positions(&e, &bombadil).supply.is_empty());
+
+            let withdrawal = execute_queue_pol_withdrawal(&e, &underlying, 20_0000000, &frodo);
+            assert_eq!(withdrawal.unlock_time, 12345 + POL_WITHDRAW_TIMELOCK);
+        });
+        assert_eq!(underlying_client.balance(&bombadil), 0);
+
+        testutils::set_timestamp(&e, 12345 + POL_WITHDRAW_TIMELOCK);
+        e.as_contract(&pool_address, || {
+            let amount_out = execute_withdraw_pol(&e, &underlying);
+            assert_eq!(amount_out, 20_0000000);
+            assert_eq!(storage::get_pol(&e, &underlying), 30_0000000);
+            assert_eq!(storage::get_res_data(&e, &underlying).b_supply, 130_0000000);
+            assert!(storage::get_pol_withdrawal(&e, &underlying).is_none());
+        });
+        assert_eq!(underlying_client.balance(&frodo), 20_0000000);
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_withdraw_pol_before_unlock() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_client.mint(&bombadil, &50_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_supply_pol(&e, &bombadil, &underlying, 50_0000000);
+            execute_queue_pol_withdrawal(&e, &underlying, 20_0000000, &bombadil);
+        });
+
+        testutils::set_timestamp(&e, 12345 + POL_WITHDRAW_TIMELOCK - 1);
+        e.as_contract(&pool_address, || {
+            execute_withdraw_pol(&e, &underlying);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_queue_pol_withdrawal_already_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_client.mint(&bombadil, &50_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_supply_pol(&e, &bombadil, &underlying, 50_0000000);
+            execute_queue_pol_withdrawal(&e, &underlying, 20_0000000, &bombadil);
+
+            execute_queue_pol_withdrawal(&e, &underlying, 10_0000000, &frodo);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(11)")]
+    fn test_supply_pol_frozen_pool() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_client.mint(&bombadil, &50_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 2,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_supply_pol(&e, &bombadil, &underlying, 50_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(11)")]
+    fn test_supply_pol_paused_reserve() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_client.mint(&bombadil, &50_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_res_pause_flags(&e, &underlying, &PAUSE_SUPPLY);
+            execute_supply_pol(&e, &bombadil, &underlying, 50_0000000);
+        });
+    }
+}
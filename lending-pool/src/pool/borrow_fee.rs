@@ -0,0 +1,147 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{SCALAR_7, SCALAR_9},
+    errors::PoolError,
+    storage,
+};
+
+use super::{pool::Pool, reserve::Reserve};
+
+/// Set the one-time origination fee charged on borrows from a reserve. The fee is added to the
+/// borrower's debt and split between the backstop and the reserve's suppliers like interest.
+/// A fee of zero removes the fee.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `fee` - The fee, as a percentage of the borrowed amount expressed in 7 decimals
+///
+/// ### Panics
+/// If the reserve does not exist or the fee is above 100%
+pub fn set_reserve_borrow_fee(e: &Env, asset: &Address, fee: u32) {
+    if !storage::has_res(e, asset) || i128(fee) > SCALAR_7 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if fee == 0 {
+        storage::del_res_borrow_fee(e, asset);
+    } else {
+        storage::set_res_borrow_fee(e, asset, &fee);
+    }
+}
+
+/// Calculate the origination fee for a borrow from a reserve, rounding up
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `amount` - The amount being borrowed
+pub fn calc_borrow_fee(e: &Env, asset: &Address, amount: i128) -> i128 {
+    match storage::get_res_borrow_fee(e, asset) {
+        Some(fee) => amount
+            .fixed_mul_ceil(i128(fee), SCALAR_7)
+            .unwrap_optimized(),
+        None => 0,
+    }
+}
+
+/// Calculate a borrow of "amount" underlying tokens from a reserve in share units. The amount is
+/// rounded up, such that at least the requested amount of underlying is borrowed, and the fee is
+/// charged on the borrowed shares.
+///
+/// Returns (the amount borrowed, the origination fee), both in share units
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `amount` - The amount of underlying tokens being borrowed
+pub fn calc_borrow_shares(e: &Env, pool: &mut Pool, asset: &Address, amount: i128) -> (i128, i128) {
+    let shares = pool.to_shares_up(e, asset, amount);
+    (shares, calc_borrow_fee(e, asset, shares))
+}
+
+/// Distribute an origination fee added to a reserve's liabilities. The backstop is credited its
+/// share of the fee based on the backstop rate, and the remainder accrues to the reserve's
+/// suppliers through the b_rate.
+///
+/// ### Arguments
+/// * `reserve` - The reserve the fee was charged by
+/// * `bstop_rate` - The pool's backstop rate, expressed in 9 decimals
+/// * `fee` - The fee added to the reserve's liabilities
+pub fn distribute_borrow_fee(reserve: &mut Reserve, bstop_rate: u64, fee: i128) {
+    if fee == 0 {
+        return;
+    }
+    let backstop_fee = fee
+        .fixed_mul_floor(i128(bstop_rate), SCALAR_9)
+        .unwrap_optimized();
+    reserve.backstop_credit += backstop_fee;
+    reserve.b_rate += (fee - backstop_fee)
+        .fixed_div_floor(reserve.b_supply, SCALAR_9)
+        .unwrap_optimized();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testutils;
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_borrow_fee() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            assert_eq!(calc_borrow_fee(&e, &underlying, 10_0000000), 0);
+
+            set_reserve_borrow_fee(&e, &underlying, 0_0050000);
+            assert_eq!(
+                storage::get_res_borrow_fee(&e, &underlying),
+                Some(0_0050000)
+            );
+            assert_eq!(calc_borrow_fee(&e, &underlying, 10_0000001), 0_0500001);
+
+            set_reserve_borrow_fee(&e, &underlying, 0);
+            assert!(storage::get_res_borrow_fee(&e, &underlying).is_none());
+        });
+    }
+
+    #[test]
+    fn test_distribute_borrow_fee() {
+        let e = Env::default();
+        let mut reserve = testutils::default_reserve(&e);
+
+        // the backstop takes 20% of the fee, and suppliers earn the rest
+        distribute_borrow_fee(&mut reserve, 0_200_000_000, 1_0000000);
+        assert_eq!(reserve.backstop_credit, 0_2000000);
+        assert_eq!(reserve.b_rate, 1_008_000_000);
+        assert_eq!(reserve.total_supply(), 100_8000000);
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_reserve_borrow_fee_over_max() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_reserve_borrow_fee(&e, &underlying, 1_0000001);
+        });
+    }
+}
@@ -0,0 +1,200 @@
+use soroban_sdk::{vec, Address, Env};
+
+use super::{actions::Request, submit::execute_submit, Positions};
+
+/// Enable or disable a user's balance of a reserve as collateral. Disabling moves the user's
+/// collateral into uncollateralized supply, which does not count towards their health factor,
+/// and enabling moves their uncollateralized supply back into collateral.
+///
+/// The change is submitted as an enable collateral (14) or disable collateral (15) request, such
+/// that it is held to the same checks as any other submit.
+///
+/// Returns the new positions for the user
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `asset` - The underlying asset of the reserve
+/// * `enabled` - If the user's balance of the reserve is used as collateral
+///
+/// ### Panics
+/// If the user has no balance of the reserve to move, or the request fails for any of the reasons
+/// a submit would
+pub fn execute_set_collateral(
+    e: &Env,
+    user: &Address,
+    asset: &Address,
+    enabled: bool,
+) -> Positions {
+    let request = Request {
+        request_type: if enabled { 14 } else { 15 },
+        address: asset.clone(),
+        amount: 0,
+    };
+    execute_submit(e, user, user, user, vec![e, request])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::PAUSE_SUPPLY,
+        storage::{self, PoolConfig},
+        testutils,
+    };
+
+    use super::*;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    /// Setup a pool with two reserves priced at 1. Returns the underlying assets of the reserves.
+    fn setup(e: &Env, pool: &Address) -> (Address, Address) {
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+
+        e.as_contract(pool, || {
+            storage::set_pool_config(
+                e,
+                &PoolConfig {
+                    oracle,
+                    bstop_rate: 0_100_000_000,
+                    status: 0,
+                    liq_hf_low: 1_0000000,
+                    liq_hf_high: 1_0000100,
+                },
+            );
+        });
+        (underlying_0, underlying_1)
+    }
+
+    #[test]
+    fn test_set_collateral() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            // 10 collateral of reserve 1 alone backs the 5 of liabilities
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 10_0000000), (1, 10_0000000)],
+                    liabilities: map![&e, (1, 5_0000000)],
+                    supply: map![&e],
+                },
+            );
+            let b_supply = storage::get_res_data(&e, &underlying_0).b_supply;
+
+            let positions = execute_set_collateral(&e, &samwise, &underlying_0, false);
+            assert_eq!(positions.collateral.get(0), None);
+            assert_eq!(positions.supply.get_unchecked(0), 10_0000000);
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise).supply,
+                positions.supply
+            );
+            assert_eq!(storage::get_res_data(&e, &underlying_0).b_supply, b_supply);
+
+            let positions = execute_set_collateral(&e, &samwise, &underlying_0, true);
+            assert_eq!(positions.collateral.get_unchecked(0), 10_0000000);
+            assert_eq!(positions.supply.get(0), None);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(10)")]
+    fn test_set_collateral_disable_unhealthy() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 10_0000000), (1, 10_0000000)],
+                    liabilities: map![&e, (1, 8_0000000)],
+                    supply: map![&e],
+                },
+            );
+            execute_set_collateral(&e, &samwise, &underlying_0, false);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_collateral_no_balance() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 10_0000000)],
+                    liabilities: map![&e],
+                    supply: map![&e],
+                },
+            );
+            execute_set_collateral(&e, &samwise, &underlying_0, true);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(11)")]
+    fn test_set_collateral_enable_reserve_paused() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e],
+                    liabilities: map![&e],
+                    supply: map![&e, (0, 10_0000000)],
+                },
+            );
+            storage::set_res_pause_flags(&e, &underlying_0, &PAUSE_SUPPLY);
+            execute_set_collateral(&e, &samwise, &underlying_0, true);
+        });
+    }
+}
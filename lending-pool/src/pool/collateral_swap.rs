@@ -0,0 +1,356 @@
+use soroban_sdk::{panic_with_error, vec, Address, Env, Symbol};
+
+use crate::{
+    dependencies::{SwapExecutorClient, TokenClient},
+    errors::PoolError,
+    storage,
+    validator::{
+        require_isolation_limits, require_max_leverage, require_nonnegative, require_not_denied,
+    },
+};
+
+use super::{
+    actions::{build_actions_from_request, Request},
+    allowlist::require_allowed,
+    exposure::require_exposure_caps,
+    health_factor::{FactorBasis, PositionData},
+    pause::require_reserve_action_allowed,
+    pool::Pool,
+    snapshot::record_submit,
+    trusted::get_trusted_credit,
+    Positions,
+};
+
+/// Move a user's collateral from one reserve to another in a single transaction. The withdrawn
+/// collateral is sent to the executor, which receives an `exec_swap` callback during which it
+/// must transfer "asset_in" to the pool. The tokens received are supplied as collateral for
+/// "from".
+///
+/// As with a submit, the health factor is only checked against the final positions, such that
+/// a position too tight to withdraw the collateral on its own can still be rebalanced.
+///
+/// Returns the new positions for "from"
+///
+/// ### Arguments
+/// * from - The address of the user whose collateral is being swapped
+/// * executor - The address of the executor contract performing the swap
+/// * asset_out - The underlying asset of the collateral being withdrawn
+/// * amount_out - The amount of "asset_out" to withdraw
+/// * asset_in - The underlying asset of the collateral being supplied
+/// * min_amount_in - The minimum amount of "asset_in" the executor must transfer to the pool
+///
+/// ### Panics
+/// If the assets are the same, the executor transfers less than "min_amount_in", or the new
+/// positions are rejected for any of the reasons a submit would be
+pub fn execute_collateral_swap(
+    e: &Env,
+    from: &Address,
+    executor: &Address,
+    asset_out: &Address,
+    amount_out: i128,
+    asset_in: &Address,
+    min_amount_in: i128,
+) -> Positions {
+    if asset_out == asset_in {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    require_nonnegative(e, &min_amount_in);
+    require_allowed(e, from);
+    require_not_denied(e, from);
+    require_not_denied(e, executor);
+    let mut pool = Pool::load(e);
+    pool.require_action_allowed(e, 2);
+    require_reserve_action_allowed(e, asset_in, 2);
+    let prev_positions = storage::get_user_positions(e, from);
+
+    // withdraw the collateral from a working copy of the user's positions
+    let requests = vec![
+        e,
+        Request {
+            request_type: 3,
+            address: asset_out.clone(),
+            amount: amount_out,
+        },
+    ];
+    let (actions, mut from_state, _) = build_actions_from_request(e, &mut pool, from, requests);
+    let shares_out = actions.pool_transfer.get(asset_out.clone()).unwrap_or(0);
+    let tokens_out = pool.to_underlying_down(e, asset_out, shares_out);
+    TokenClient::new(e, asset_out).transfer(&e.current_contract_address(), executor, &tokens_out);
+
+    // the executor swaps the withdrawn collateral and sends the proceeds to the pool
+    let token_in_client = TokenClient::new(e, asset_in);
+    let balance_before = token_in_client.balance(&e.current_contract_address());
+    SwapExecutorClient::new(e, executor).exec_swap(
+        &e.current_contract_address(),
+        from,
+        asset_out,
+        &tokens_out,
+        asset_in,
+    );
+    let amount_in = token_in_client.balance(&e.current_contract_address()) - balance_before;
+    if amount_in < min_amount_in {
+        panic_with_error!(e, PoolError::TokenSlippageExceeded);
+    }
+
+    // supply the proceeds as collateral
+    let shares_in = pool.to_shares_down(e, asset_in, amount_in);
+    let mut reserve = pool.load_reserve(e, asset_in);
+    let b_tokens_minted = reserve.to_b_token_down(shares_in);
+    from_state.add_collateral(e, &mut reserve, b_tokens_minted);
+    pool.cache_reserve(reserve, true);
+    e.events().publish(
+        (
+            Symbol::new(e, "supply_collateral"),
+            asset_in.clone(),
+            from.clone(),
+        ),
+        (shares_in, b_tokens_minted),
+    );
+
+    // panics if the new positions set does not meet the health factor requirement
+    let credit = get_trusted_credit(e, from);
+    let position_data = PositionData::calculate_for_user(
+        e,
+        &mut pool,
+        from,
+        &from_state.positions,
+        FactorBasis::Borrow,
+    );
+    position_data.require_healthy_with_credit(e, credit);
+    require_max_leverage(e, &position_data, credit);
+    require_exposure_caps(e, &mut pool, &from_state.positions);
+    require_isolation_limits(e, &mut pool, &prev_positions, &from_state.positions);
+
+    pool.store_cached_reserves(e);
+    from_state.store(e);
+    record_submit(e, from);
+    from_state.positions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        storage::{self, PoolConfig},
+        testutils,
+    };
+
+    use super::*;
+    use soroban_sdk::{
+        contract, contractimpl, map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    #[contract]
+    struct MockSwapExecutor;
+
+    #[contractimpl]
+    impl MockSwapExecutor {
+        pub fn set_amount_in(e: Env, amount_in: i128) {
+            e.storage()
+                .instance()
+                .set(&Symbol::new(&e, "amount_in"), &amount_in);
+        }
+
+        pub fn exec_swap(
+            e: Env,
+            pool: Address,
+            _user: Address,
+            _token_out: Address,
+            _amount_out: i128,
+            token_in: Address,
+        ) {
+            let amount_in: i128 = e
+                .storage()
+                .instance()
+                .get(&Symbol::new(&e, "amount_in"))
+                .unwrap_or(0);
+            TokenClient::new(&e, &token_in).transfer(
+                &e.current_contract_address(),
+                &pool,
+                &amount_in,
+            );
+        }
+    }
+
+    /// Setup a user with a health factor of ~1.02, with collateral in reserve 0 and a liability
+    /// in reserve 2. Returns the underlying assets of the reserves.
+    fn setup(
+        e: &Env,
+        pool: &Address,
+        samwise: &Address,
+        executor: &Address,
+    ) -> (Address, Address, Address) {
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let (underlying_2, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_2, &reserve_config, &reserve_data);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+        oracle_client.set_price(&underlying_2, &1_0000000);
+
+        underlying_1_client.mint(executor, &100_0000000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(pool, || {
+            storage::set_pool_config(e, &pool_config);
+            storage::set_user_positions(
+                e,
+                samwise,
+                &Positions {
+                    collateral: map![e, (0, 20_0000000)],
+                    liabilities: map![e, (2, 11_0000000)],
+                    supply: map![e],
+                },
+            );
+        });
+        (underlying_0, underlying_1, underlying_2)
+    }
+
+    #[test]
+    fn test_collateral_swap() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let executor = e.register_contract(None, MockSwapExecutor {});
+        let (underlying_0, underlying_1, _) = setup(&e, &pool, &samwise, &executor);
+        MockSwapExecutorClient::new(&e, &executor).set_amount_in(&10_0000000);
+
+        let underlying_0_client = TokenClient::new(&e, &underlying_0);
+        let underlying_1_client = TokenClient::new(&e, &underlying_1);
+        let pre_pool_balance_1 = underlying_1_client.balance(&pool);
+
+        e.as_contract(&pool, || {
+            // withdrawing the collateral on its own would leave the user unhealthy
+            let positions = execute_collateral_swap(
+                &e,
+                &samwise,
+                &executor,
+                &underlying_0,
+                10_0000000,
+                &underlying_1,
+                9_9000000,
+            );
+            assert_eq!(positions.collateral.get_unchecked(0), 10_0000000);
+            assert_eq!(positions.collateral.get_unchecked(1), 10_0000000);
+            assert_eq!(positions.liabilities.get_unchecked(2), 11_0000000);
+
+            assert_eq!(underlying_0_client.balance(&executor), 10_0000000);
+            assert_eq!(
+                underlying_1_client.balance(&pool),
+                pre_pool_balance_1 + 10_0000000
+            );
+            assert_eq!(
+                storage::get_res_data(&e, &underlying_0).b_supply,
+                90_0000000
+            );
+            assert_eq!(
+                storage::get_res_data(&e, &underlying_1).b_supply,
+                110_0000000
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(17)")]
+    fn test_collateral_swap_under_min_amount_in() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let executor = e.register_contract(None, MockSwapExecutor {});
+        let (underlying_0, underlying_1, _) = setup(&e, &pool, &samwise, &executor);
+        MockSwapExecutorClient::new(&e, &executor).set_amount_in(&9_8000000);
+
+        e.as_contract(&pool, || {
+            execute_collateral_swap(
+                &e,
+                &samwise,
+                &executor,
+                &underlying_0,
+                10_0000000,
+                &underlying_1,
+                9_9000000,
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(10)")]
+    fn test_collateral_swap_requires_healthy() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let executor = e.register_contract(None, MockSwapExecutor {});
+        let (underlying_0, underlying_1, _) = setup(&e, &pool, &samwise, &executor);
+        MockSwapExecutorClient::new(&e, &executor).set_amount_in(&5_0000000);
+
+        e.as_contract(&pool, || {
+            execute_collateral_swap(
+                &e,
+                &samwise,
+                &executor,
+                &underlying_0,
+                10_0000000,
+                &underlying_1,
+                0,
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_collateral_swap_same_asset() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let executor = e.register_contract(None, MockSwapExecutor {});
+        let (underlying_0, _, _) = setup(&e, &pool, &samwise, &executor);
+
+        e.as_contract(&pool, || {
+            execute_collateral_swap(
+                &e,
+                &samwise,
+                &executor,
+                &underlying_0,
+                10_0000000,
+                &underlying_0,
+                0,
+            );
+        });
+    }
+}
@@ -1,50 +1,66 @@
 use crate::{
-    dependencies::BackstopClient,
-    emissions,
+    constants::{
+        BACKSTOP_INTERFACE_V1, DEFAULT_LIQ_HF_HIGH, DEFAULT_LIQ_HF_LOW,
+        RESERVE_CONFIG_HISTORY_LENGTH, STORAGE_VERSION,
+    },
+    dependencies::{TokenClient, VersionedBackstopClient},
+    emissions::{self, ReserveEmissionMetadata, ReserveEmissionTarget},
     errors::PoolError,
-    storage::{self, PoolConfig, ReserveConfig, ReserveData},
+    storage::{
+        self, IsolationConfig, PoolConfig, PoolInitConfig, ReserveConfig, ReserveConfigVersion,
+        ReserveData,
+    },
 };
 use cast::u64;
-use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env, Symbol};
+use soroban_sdk::{
+    panic_with_error, unwrap::UnwrapOptimized, xdr::ToXdr, Address, Env, Map, Symbol, Vec,
+};
 
 use super::pool::Pool;
 
 /// Initialize the pool
 ///
-/// Panics if the pool is already initialized or the arguments are invalid
-#[allow(clippy::too_many_arguments)]
-pub fn execute_initialize(
-    e: &Env,
-    admin: &Address,
-    name: &Symbol,
-    oracle: &Address,
-    bstop_rate: &u64,
-    backstop_address: &Address,
-    blnd_id: &Address,
-    usdc_id: &Address,
-) {
+/// The hash of the config is recorded, such that re-invoking with the identical config is a
+/// no-op. This allows deployments that retry transactions to safely re-submit the initialization.
+///
+/// ### Arguments
+/// * `config` - The config to initialize the pool with
+///
+/// ### Panics
+/// If the pool is already initialized with a different config, or the config is invalid
+pub fn execute_initialize(e: &Env, config: &PoolInitConfig) {
+    let config_hash = e.crypto().sha256(&config.clone().to_xdr(e));
     if storage::has_admin(e) {
-        panic_with_error!(e, PoolError::AlreadyInitialized);
+        match storage::get_init_hash(e) {
+            Some(init_hash) if init_hash == config_hash => return,
+            Some(_) => panic_with_error!(e, PoolError::InitConfigMismatch),
+            None => panic_with_error!(e, PoolError::AlreadyInitialized),
+        }
     }
 
     // ensure backstop is [0,1)
-    if *bstop_rate >= 1_000_000_000 {
+    if config.bstop_rate >= 1_000_000_000 {
         panic_with_error!(e, PoolError::InvalidPoolInitArgs);
     }
 
-    storage::set_admin(e, admin);
-    storage::set_name(e, name);
-    storage::set_backstop(e, backstop_address);
+    storage::set_admin(e, &config.admin);
+    storage::set_name(e, &config.name);
+    storage::set_backstop(e, &config.backstop);
     storage::set_pool_config(
         e,
         &PoolConfig {
-            oracle: oracle.clone(),
-            bstop_rate: *bstop_rate,
+            oracle: config.oracle.clone(),
+            bstop_rate: config.bstop_rate,
             status: 1,
+            liq_hf_low: DEFAULT_LIQ_HF_LOW,
+            liq_hf_high: DEFAULT_LIQ_HF_HIGH,
         },
     );
-    storage::set_blnd_token(e, blnd_id);
-    storage::set_usdc_token(e, usdc_id);
+    storage::set_blnd_token(e, &config.blnd_id);
+    storage::set_usdc_token(e, &config.usdc_id);
+    storage::set_permissioned(e, &config.permissioned);
+    storage::set_init_hash(e, &config_hash);
+    storage::set_storage_version(e, &STORAGE_VERSION);
 }
 
 /// Update the pool
@@ -58,13 +74,112 @@ pub fn execute_update_pool(e: &Env, backstop_take_rate: u64) {
     storage::set_pool_config(e, &pool_config);
 }
 
+/// Set the health factors user liquidations can be created and deleted at. A liquidation can
+/// only be created at or below the low health factor, but can only be deleted once the user
+/// recovers to at least the high health factor, such that a user hovering around a single
+/// threshold does not churn auctions.
+///
+/// ### Arguments
+/// * `hf_low` - The health factor a liquidation can be created at or below, in 7 decimals
+/// * `hf_high` - The health factor a liquidation can be deleted at or above, in 7 decimals
+///
+/// ### Panics
+/// If the low health factor is zero or above 1, or the high health factor is not above the
+/// low health factor
+pub fn set_liquidation_thresholds(e: &Env, hf_low: u32, hf_high: u32) {
+    if hf_low == 0 || hf_low > 1_0000000 || hf_high <= hf_low {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut pool_config = storage::get_pool_config(e);
+    pool_config.liq_hf_low = hf_low;
+    pool_config.liq_hf_high = hf_high;
+    storage::set_pool_config(e, &pool_config);
+}
+
+/// Set the maximum leverage a user can take on in the pool
+///
+/// ### Arguments
+/// * `max_leverage` - The maximum leverage, expressed in 7 decimals. 0 removes the limit.
+///
+/// ### Panics
+/// If the max leverage is negative
+pub fn set_max_leverage(e: &Env, max_leverage: i128) {
+    if max_leverage < 0 {
+        panic_with_error!(e, PoolError::NegativeAmount);
+    }
+    if max_leverage == 0 {
+        storage::del_max_leverage(e);
+    } else {
+        storage::set_max_leverage(e, &max_leverage);
+    }
+}
+
+/// Set the minimum liability value a user with liabilities can hold
+///
+/// ### Arguments
+/// * `min_debt` - The minimum liability value, in the oracle's base asset. 0 removes the minimum.
+///
+/// ### Panics
+/// If the min debt is negative
+pub fn set_min_debt(e: &Env, min_debt: i128) {
+    if min_debt < 0 {
+        panic_with_error!(e, PoolError::NegativeAmount);
+    }
+    if min_debt == 0 {
+        storage::del_min_debt(e);
+    } else {
+        storage::set_min_debt(e, &min_debt);
+    }
+}
+
+/// Set the borrowing limits of users with collateral in an isolated reserve
+///
+/// ### Arguments
+/// * `config` - The max liability value of an isolated user, in the base asset, and the reserves
+///              they can borrow from
+///
+/// ### Panics
+/// If the debt cap is negative or a borrowable asset is not a reserve
+pub fn set_isolation_config(e: &Env, config: &IsolationConfig) {
+    if config.debt_cap < 0 {
+        panic_with_error!(e, PoolError::NegativeAmount);
+    }
+    for asset in config.borrowable.iter() {
+        if !storage::has_res(e, &asset) {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+    }
+    storage::set_isolation_config(e, config);
+}
+
+/// Set the interface version the pool uses to call its backstop
+///
+/// ### Arguments
+/// * `version` - The backstop interface version
+///
+/// ### Panics
+/// If the version is not a known backstop interface
+pub fn set_backstop_version(e: &Env, version: u32) {
+    if version > BACKSTOP_INTERFACE_V1 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_backstop_version(e, &version);
+}
+
 /// Initialize a reserve for the pool
-pub fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) {
+///
+/// Panics if the reserve already exists, the metadata is invalid, or the metadata's decimals
+/// do not match the decimals of the underlying token contract
+pub fn initialize_reserve(e: &Env, actor: &Address, asset: &Address, config: &ReserveConfig) {
     if storage::has_res(e, asset) {
         panic_with_error!(e, PoolError::AlreadyInitialized);
     }
 
     require_valid_reserve_metadata(e, config);
+    // b_tokens and d_tokens are tracked by the pool and share the underlying's decimals
+    if TokenClient::new(e, asset).decimals() != config.decimals {
+        panic_with_error!(e, PoolError::InvalidReserveMetadata);
+    }
     let index = storage::push_res_list(e, asset);
 
     let reserve_config = ReserveConfig {
@@ -78,8 +193,11 @@ pub fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) {
         r_two: config.r_two,
         r_three: config.r_three,
         reactivity: config.reactivity,
+        oracle_asset: config.oracle_asset.clone(),
+        isolated: config.isolated,
     };
     storage::set_res_config(e, asset, &reserve_config);
+    record_reserve_config(e, actor, asset, &reserve_config);
     let init_data = ReserveData {
         b_rate: 1_000_000_000,
         d_rate: 1_000_000_000,
@@ -93,10 +211,16 @@ pub fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) {
 }
 
 /// Update a reserve in the pool
-pub fn execute_update_reserve(e: &Env, asset: &Address, config: &ReserveConfig) {
+pub fn execute_update_reserve(e: &Env, actor: &Address, asset: &Address, config: &ReserveConfig) {
     require_valid_reserve_metadata(e, config);
+    // the collateral factor cannot be raised above the reserve's liquidation threshold
+    if let Some(liq_threshold) = storage::get_res_liq_threshold(e, asset) {
+        if config.c_factor > liq_threshold {
+            panic_with_error!(e, PoolError::InvalidReserveMetadata);
+        }
+    }
 
-    let pool = Pool::load(e);
+    let mut pool = Pool::load(e);
     if pool.config.status == 2 {
         panic_with_error!(e, PoolError::InvalidPoolStatus);
     }
@@ -110,16 +234,97 @@ pub fn execute_update_reserve(e: &Env, asset: &Address, config: &ReserveConfig)
     new_config.index = reserve.index;
 
     storage::set_res_config(e, asset, &new_config);
+    record_reserve_config(e, actor, asset, &new_config);
+}
+
+/// Update several reserves in the pool at once. Every config is validated before any reserve
+/// is updated, such that a coordinated change is applied to all of the reserves or none of them.
+///
+/// ### Arguments
+/// * `actor` - The address updating the reserves
+/// * `configs` - The new config of each reserve, by underlying asset
+///
+/// ### Panics
+/// If no configs are supplied, any of the reserves do not exist, or any of the configs are invalid
+pub fn execute_update_reserves(e: &Env, actor: &Address, configs: &Map<Address, ReserveConfig>) {
+    if configs.is_empty() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    for (asset, config) in configs.iter() {
+        if !storage::has_res(e, &asset) {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+        require_valid_reserve_metadata(e, &config);
+    }
+
+    for (asset, config) in configs.iter() {
+        execute_update_reserve(e, actor, &asset, &config);
+    }
+}
+
+/// Set the liquidation threshold of a reserve. Positions can borrow against collateral up to
+/// the reserve's collateral factor, but only become eligible for liquidation once they exceed
+/// the liquidation threshold. A threshold of zero removes it, such that the reserve is
+/// liquidated at its collateral factor.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `liq_threshold` - The liquidation threshold, expressed in 7 decimals
+///
+/// ### Panics
+/// If the reserve does not exist, or the threshold is below the reserve's collateral factor
+/// or above 100%
+pub fn set_reserve_liq_threshold(e: &Env, asset: &Address, liq_threshold: u32) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if liq_threshold == 0 {
+        storage::del_res_liq_threshold(e, asset);
+        return;
+    }
+
+    let reserve_config = storage::get_res_config(e, asset);
+    if liq_threshold < reserve_config.c_factor || liq_threshold > 1_0000000 {
+        panic_with_error!(e, PoolError::InvalidReserveMetadata);
+    }
+    storage::set_res_liq_threshold(e, asset, &liq_threshold);
+}
+
+/// Record a new version of a reserve's config, keeping the most recent
+/// `RESERVE_CONFIG_HISTORY_LENGTH` versions
+fn record_reserve_config(e: &Env, actor: &Address, asset: &Address, config: &ReserveConfig) {
+    let mut history = storage::get_res_config_history(e, asset);
+    history.push_back(ReserveConfigVersion {
+        config: config.clone(),
+        sequence: e.ledger().sequence(),
+        timestamp: e.ledger().timestamp(),
+        actor: actor.clone(),
+    });
+    if history.len() > RESERVE_CONFIG_HISTORY_LENGTH {
+        history.pop_front();
+    }
+    storage::set_res_config_history(e, asset, &history);
 }
 
 // Update the pool emission information from the backstop
 pub fn update_pool_emissions(e: &Env) -> u64 {
     let backstop_address = storage::get_backstop(e);
-    let backstop_client = BackstopClient::new(e, &backstop_address);
+    let backstop_client = VersionedBackstopClient::new(e, &backstop_address);
     let (pool_eps, next_exp) = backstop_client.pool_eps(&e.current_contract_address());
     emissions::update_emissions_cycle(e, next_exp, u64(pool_eps).unwrap_optimized())
 }
 
+// Set the pool emissions to approximate target APRs based on the pool's eps from the backstop
+pub fn set_emissions_by_target_apr(
+    e: &Env,
+    targets: Vec<ReserveEmissionTarget>,
+) -> Vec<ReserveEmissionMetadata> {
+    let backstop_address = storage::get_backstop(e);
+    let backstop_client = VersionedBackstopClient::new(e, &backstop_address);
+    let (pool_eps, _) = backstop_client.pool_eps(&e.current_contract_address());
+    emissions::set_pool_emissions_by_target_apr(e, targets, pool_eps)
+}
+
 #[allow(clippy::zero_prefixed_literal)]
 fn require_valid_reserve_metadata(e: &Env, metadata: &ReserveConfig) {
     if metadata.decimals > 18
@@ -139,41 +344,102 @@ mod tests {
     use crate::testutils;
 
     use super::*;
-    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec,
+    };
+
+    fn default_init_config(e: &Env) -> PoolInitConfig {
+        PoolInitConfig {
+            admin: Address::random(e),
+            name: Symbol::new(e, "pool_name"),
+            oracle: Address::random(e),
+            bstop_rate: 0_100_000_000,
+            permissioned: true,
+            backstop: Address::random(e),
+            blnd_id: Address::random(e),
+            usdc_id: Address::random(e),
+        }
+    }
 
     #[test]
     fn test_execute_initialize() {
         let e = Env::default();
         let pool = Address::random(&e);
 
-        let admin = Address::random(&e);
-        let name = Symbol::new(&e, "pool_name");
-        let oracle = Address::random(&e);
-        let bstop_rate = 0_100_000_000u64;
-        let backstop_address = Address::random(&e);
-        let blnd_id = Address::random(&e);
-        let usdc_id = Address::random(&e);
+        let config = default_init_config(&e);
 
         e.as_contract(&pool, || {
-            execute_initialize(
-                &e,
-                &admin,
-                &name,
-                &oracle,
-                &bstop_rate,
-                &backstop_address,
-                &blnd_id,
-                &usdc_id,
-            );
+            execute_initialize(&e, &config);
 
-            assert_eq!(storage::get_admin(&e), admin);
+            assert_eq!(storage::get_admin(&e), config.admin);
             let pool_config = storage::get_pool_config(&e);
-            assert_eq!(pool_config.oracle, oracle);
-            assert_eq!(pool_config.bstop_rate, bstop_rate);
+            assert_eq!(pool_config.oracle, config.oracle);
+            assert_eq!(pool_config.bstop_rate, config.bstop_rate);
             assert_eq!(pool_config.status, 1);
-            assert_eq!(storage::get_backstop(&e), backstop_address);
-            assert_eq!(storage::get_blnd_token(&e), blnd_id);
-            assert_eq!(storage::get_usdc_token(&e), usdc_id);
+            assert_eq!(pool_config.liq_hf_low, DEFAULT_LIQ_HF_LOW);
+            assert_eq!(pool_config.liq_hf_high, DEFAULT_LIQ_HF_HIGH);
+            assert_eq!(storage::get_backstop(&e), config.backstop);
+            assert_eq!(storage::get_blnd_token(&e), config.blnd_id);
+            assert_eq!(storage::get_usdc_token(&e), config.usdc_id);
+            assert!(storage::get_permissioned(&e));
+            assert!(storage::get_init_hash(&e).is_some());
+            assert_eq!(storage::get_storage_version(&e), STORAGE_VERSION);
+        });
+    }
+
+    #[test]
+    fn test_execute_initialize_same_config_is_noop() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        let config = default_init_config(&e);
+
+        e.as_contract(&pool, || {
+            execute_initialize(&e, &config);
+            let init_hash = storage::get_init_hash(&e);
+
+            // a pool update between attempts is not reverted by a retried initialization
+            execute_update_pool(&e, 0_200_000_000);
+            execute_initialize(&e, &config);
+
+            assert_eq!(storage::get_init_hash(&e), init_hash);
+            assert_eq!(storage::get_pool_config(&e).bstop_rate, 0_200_000_000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(8)")]
+    fn test_execute_initialize_different_config() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        let config = default_init_config(&e);
+
+        e.as_contract(&pool, || {
+            execute_initialize(&e, &config);
+
+            let mut new_config = config.clone();
+            new_config.oracle = Address::random(&e);
+            execute_initialize(&e, &new_config);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(3)")]
+    fn test_execute_initialize_without_recorded_config() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        let config = default_init_config(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_admin(&e, &config.admin);
+
+            execute_initialize(&e, &config);
         });
     }
 
@@ -186,6 +452,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -210,6 +478,8 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -218,6 +488,51 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_set_liquidation_thresholds() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            set_liquidation_thresholds(&e, 0_9800000, 1_0500000);
+            let new_pool_config = storage::get_pool_config(&e);
+            assert_eq!(new_pool_config.liq_hf_low, 0_9800000);
+            assert_eq!(new_pool_config.liq_hf_high, 1_0500000);
+            assert_eq!(new_pool_config.bstop_rate, pool_config.bstop_rate);
+            assert_eq!(new_pool_config.status, pool_config.status);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_liquidation_thresholds_high_not_above_low() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            set_liquidation_thresholds(&e, 0_9800000, 0_9800000);
+        });
+    }
+
     #[test]
     fn test_initialize_reserve() {
         let e = Env::default();
@@ -238,11 +553,13 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         e.as_contract(&pool, || {
-            initialize_reserve(&e, &asset_id_0, &metadata);
+            initialize_reserve(&e, &bombadil, &asset_id_0, &metadata);
 
-            initialize_reserve(&e, &asset_id_1, &metadata);
+            initialize_reserve(&e, &bombadil, &asset_id_1, &metadata);
             let res_config_0 = storage::get_res_config(&e, &asset_id_0);
             let res_config_1 = storage::get_res_config(&e, &asset_id_1);
             assert_eq!(res_config_0.decimals, metadata.decimals);
@@ -279,12 +596,14 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         e.as_contract(&pool, || {
-            initialize_reserve(&e, &asset_id, &metadata);
+            initialize_reserve(&e, &bombadil, &asset_id, &metadata);
             let res_config = storage::get_res_config(&e, &asset_id);
             assert_eq!(res_config.index, 0);
-            initialize_reserve(&e, &asset_id, &metadata);
+            initialize_reserve(&e, &bombadil, &asset_id, &metadata);
         });
     }
 
@@ -308,12 +627,42 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         e.as_contract(&pool, || {
-            initialize_reserve(&e, &asset_id, &metadata);
+            initialize_reserve(&e, &bombadil, &asset_id, &metadata);
             let res_config = storage::get_res_config(&e, &asset_id);
             assert_eq!(res_config.index, 0);
-            initialize_reserve(&e, &asset_id, &metadata);
+            initialize_reserve(&e, &bombadil, &asset_id, &metadata);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(6))")]
+    fn test_initialize_reserve_validates_decimals() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let (asset_id, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 6,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
+        };
+        e.as_contract(&pool, || {
+            initialize_reserve(&e, &bombadil, &asset_id, &metadata);
         });
     }
 
@@ -350,6 +699,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 105,
+            oracle_asset: None,
+            isolated: false,
         };
 
         e.ledger().set(LedgerInfo {
@@ -367,13 +718,15 @@ mod tests {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
 
             let res_config_old = storage::get_res_config(&e, &underlying);
 
-            execute_update_reserve(&e, &underlying, &new_metadata);
+            execute_update_reserve(&e, &bombadil, &underlying, &new_metadata);
             let res_config_updated = storage::get_res_config(&e, &underlying);
             assert_eq!(res_config_updated.decimals, new_metadata.decimals);
             assert_eq!(res_config_updated.c_factor, new_metadata.c_factor);
@@ -391,6 +744,23 @@ mod tests {
             assert!(res_data.d_rate > 1_000_000_000);
             assert!(res_data.backstop_credit > 0);
             assert_eq!(res_data.last_time, 10000);
+
+            // validate the new config version was recorded
+            let history = storage::get_res_config_history(&e, &underlying);
+            assert_eq!(history.len(), 1);
+            let version = history.get_unchecked(0);
+            assert_eq!(version.config.util, new_metadata.util);
+            assert_eq!(version.config.index, res_config_old.index);
+            assert_eq!(version.sequence, 100);
+            assert_eq!(version.timestamp, 10000);
+            assert_eq!(version.actor, bombadil);
+
+            // only the most recent versions are kept
+            for _ in 0..RESERVE_CONFIG_HISTORY_LENGTH {
+                execute_update_reserve(&e, &bombadil, &underlying, &new_metadata);
+            }
+            let history = storage::get_res_config_history(&e, &underlying);
+            assert_eq!(history.len(), RESERVE_CONFIG_HISTORY_LENGTH);
         });
     }
 
@@ -429,17 +799,147 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 105,
+            oracle_asset: None,
+            isolated: false,
+        };
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            execute_update_reserve(&e, &bombadil, &underlying, &new_metadata);
+        });
+    }
+
+    #[test]
+    fn test_execute_update_reserves() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let mut new_config_0 = reserve_config.clone();
+        new_config_0.c_factor = 0_6000000;
+        let mut new_config_1 = reserve_config.clone();
+        new_config_1.c_factor = 0_5000000;
 
         let pool_config = PoolConfig {
             oracle: Address::random(&e),
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
 
-            execute_update_reserve(&e, &underlying, &new_metadata);
+            let configs = map![
+                &e,
+                (underlying_0.clone(), new_config_0),
+                (underlying_1.clone(), new_config_1)
+            ];
+            execute_update_reserves(&e, &bombadil, &configs);
+
+            let res_config_0 = storage::get_res_config(&e, &underlying_0);
+            assert_eq!(res_config_0.c_factor, 0_6000000);
+            assert_eq!(res_config_0.index, 0);
+            let res_config_1 = storage::get_res_config(&e, &underlying_1);
+            assert_eq!(res_config_1.c_factor, 0_5000000);
+            assert_eq!(res_config_1.index, 1);
+            assert_eq!(storage::get_res_config_history(&e, &underlying_1).len(), 1);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(6))")]
+    fn test_execute_update_reserves_validates_all_metadata() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let mut new_config_0 = reserve_config.clone();
+        new_config_0.c_factor = 0_6000000;
+        let mut new_config_1 = reserve_config.clone();
+        new_config_1.util = 1_0777777;
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let configs = map![
+                &e,
+                (underlying_0.clone(), new_config_0),
+                (underlying_1.clone(), new_config_1)
+            ];
+            execute_update_reserves(&e, &bombadil, &configs);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(2))")]
+    fn test_execute_update_reserves_requires_reserves() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let configs = map![
+                &e,
+                (underlying_0.clone(), reserve_config.clone()),
+                (Address::random(&e), reserve_config)
+            ];
+            execute_update_reserves(&e, &bombadil, &configs);
         });
     }
 
@@ -459,6 +959,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         require_valid_reserve_metadata(&e, &metadata);
         // no panic
@@ -482,6 +984,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -503,6 +1007,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -524,6 +1030,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -545,6 +1053,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -566,6 +1076,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -587,6 +1099,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            oracle_asset: None,
+            isolated: false,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -608,7 +1122,141 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 5001,
+            oracle_asset: None,
+            isolated: false,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
+
+    #[test]
+    fn test_set_reserve_liq_threshold() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_reserve_liq_threshold(&e, &underlying, 0_8000000);
+            assert_eq!(
+                storage::get_res_liq_threshold(&e, &underlying),
+                Some(0_8000000)
+            );
+
+            // the threshold can equal the collateral factor
+            set_reserve_liq_threshold(&e, &underlying, 0_7500000);
+            assert_eq!(
+                storage::get_res_liq_threshold(&e, &underlying),
+                Some(0_7500000)
+            );
+
+            set_reserve_liq_threshold(&e, &underlying, 0);
+            assert!(storage::get_res_liq_threshold(&e, &underlying).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(6))")]
+    fn test_set_reserve_liq_threshold_below_c_factor() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_reserve_liq_threshold(&e, &underlying, 0_7499999);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(6))")]
+    fn test_execute_update_reserve_c_factor_above_liq_threshold() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let mut new_config = reserve_config.clone();
+        new_config.c_factor = 0_8500000;
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            set_reserve_liq_threshold(&e, &underlying, 0_8000000);
+            execute_update_reserve(&e, &bombadil, &underlying, &new_config);
+        });
+    }
+
+    #[test]
+    fn test_set_isolation_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_isolation_config(
+                &e,
+                &IsolationConfig {
+                    debt_cap: 1000_0000000,
+                    borrowable: vec![&e, underlying.clone()],
+                },
+            );
+            let config = storage::get_isolation_config(&e).unwrap();
+            assert_eq!(config.debt_cap, 1000_0000000);
+            assert_eq!(config.borrowable, vec![&e, underlying.clone()]);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(2))")]
+    fn test_set_isolation_config_not_reserve() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            set_isolation_config(
+                &e,
+                &IsolationConfig {
+                    debt_cap: 1000_0000000,
+                    borrowable: vec![&e, Address::random(&e)],
+                },
+            );
+        });
+    }
 }
@@ -0,0 +1,239 @@
+use soroban_sdk::{contracttype, panic_with_error, vec, Address, BytesN, Env, Vec};
+
+use crate::{errors::PoolError, storage};
+
+use super::oracle_change::execute_queue_oracle;
+
+/// A high impact admin action that requires the approval of both the admin and the co-signer
+#[derive(Clone)]
+#[contracttype]
+pub enum AdminAction {
+    // Upgrade the pool to a new wasm hash
+    Upgrade(BytesN<32>),
+    // Queue a change of the pool's oracle, which can be set once its dual-read period passes
+    QueueOracle(Address),
+    // Change the pool's backstop
+    SetBackstop(Address),
+    // Change the pool's co-signer
+    SetCoSigner(Address),
+}
+
+/// Set the initial co-signer of the pool. Once set, the co-signer can only be changed
+/// with the approval of both the admin and the current co-signer.
+///
+/// ### Arguments
+/// * `co_signer` - The address of the co-signer
+///
+/// ### Panics
+/// If a co-signer is already set or the co-signer is the admin
+pub fn set_initial_co_signer(e: &Env, co_signer: &Address) {
+    if storage::get_co_signer(e).is_some() {
+        panic_with_error!(e, PoolError::AlreadyInitialized);
+    }
+    if *co_signer == storage::get_admin(e) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_co_signer(e, co_signer);
+}
+
+/// Approve an admin action. The action is executed once it has been approved by the admin
+/// and the co-signer, if one is set. Approvals are collected across transactions.
+///
+/// Returns true if the action was executed
+///
+/// ### Arguments
+/// * `from` - The address approving the action
+/// * `action` - The action to approve
+///
+/// ### Panics
+/// If `from` is not the admin or co-signer
+pub fn execute_approve_action(e: &Env, from: &Address, action: &AdminAction) -> bool {
+    let admin = storage::get_admin(e);
+    let co_signer = storage::get_co_signer(e);
+    if *from != admin && Some(from.clone()) != co_signer {
+        panic_with_error!(e, PoolError::NotAuthorized);
+    }
+
+    let mut approvals = storage::get_action_approvals(e, action);
+    if !approvals.contains(from) {
+        approvals.push_back(from.clone());
+    }
+
+    let mut required: Vec<Address> = vec![e, admin];
+    if let Some(co_signer) = co_signer {
+        required.push_back(co_signer);
+    }
+    for signer in required.iter() {
+        if !approvals.contains(&signer) {
+            storage::set_action_approvals(e, action, &approvals);
+            return false;
+        }
+    }
+
+    storage::del_action_approvals(e, action);
+    execute_action(e, action);
+    true
+}
+
+/// Revoke an approval for an admin action that has not yet been executed
+///
+/// ### Arguments
+/// * `from` - The address revoking their approval
+/// * `action` - The action to revoke the approval for
+///
+/// ### Panics
+/// If `from` has not approved the action
+pub fn execute_revoke_approval(e: &Env, from: &Address, action: &AdminAction) {
+    let mut approvals = storage::get_action_approvals(e, action);
+    match approvals.first_index_of(from) {
+        Some(index) => approvals.remove(index),
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    if approvals.is_empty() {
+        storage::del_action_approvals(e, action);
+    } else {
+        storage::set_action_approvals(e, action, &approvals);
+    }
+}
+
+fn execute_action(e: &Env, action: &AdminAction) {
+    match action {
+        AdminAction::Upgrade(wasm_hash) => {
+            e.deployer().update_current_contract_wasm(wasm_hash.clone());
+        }
+        AdminAction::QueueOracle(oracle) => execute_queue_oracle(e, oracle),
+        AdminAction::SetBackstop(backstop) => {
+            storage::set_backstop(e, backstop);
+        }
+        AdminAction::SetCoSigner(co_signer) => {
+            if *co_signer == storage::get_admin(e) {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            storage::set_co_signer(e, co_signer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::PoolConfig;
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    fn setup(e: &Env, pool: &Address, admin: &Address) {
+        e.as_contract(pool, || {
+            storage::set_admin(e, admin);
+            storage::set_pool_config(
+                e,
+                &PoolConfig {
+                    oracle: Address::random(e),
+                    bstop_rate: 0_100_000_000,
+                    status: 0,
+                    liq_hf_low: 1_0000000,
+                    liq_hf_high: 1_0000100,
+                },
+            );
+        });
+    }
+
+    #[test]
+    fn test_approve_action_requires_both_signers() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let new_oracle = Address::random(&e);
+        setup(&e, &pool, &bombadil);
+
+        e.as_contract(&pool, || {
+            set_initial_co_signer(&e, &samwise);
+            let action = AdminAction::QueueOracle(new_oracle.clone());
+
+            assert!(!execute_approve_action(&e, &bombadil, &action));
+            // approving twice does not count as a second signer
+            assert!(!execute_approve_action(&e, &bombadil, &action));
+            assert!(storage::get_pending_oracle(&e).is_none());
+            assert_eq!(storage::get_action_approvals(&e, &action).len(), 1);
+
+            // the oracle change is queued for its dual-read period, not set directly
+            assert!(execute_approve_action(&e, &samwise, &action));
+            assert_eq!(storage::get_pending_oracle(&e).unwrap().oracle, new_oracle);
+            assert_ne!(storage::get_pool_config(&e).oracle, new_oracle);
+            assert!(storage::get_action_approvals(&e, &action).is_empty());
+        });
+    }
+
+    #[test]
+    fn test_approve_action_no_co_signer() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let new_backstop = Address::random(&e);
+        setup(&e, &pool, &bombadil);
+
+        e.as_contract(&pool, || {
+            let action = AdminAction::SetBackstop(new_backstop.clone());
+            assert!(execute_approve_action(&e, &bombadil, &action));
+            assert_eq!(storage::get_backstop(&e), new_backstop);
+        });
+    }
+
+    #[test]
+    fn test_revoke_approval() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        setup(&e, &pool, &bombadil);
+
+        e.as_contract(&pool, || {
+            set_initial_co_signer(&e, &samwise);
+            let action = AdminAction::SetCoSigner(frodo.clone());
+
+            assert!(!execute_approve_action(&e, &samwise, &action));
+            execute_revoke_approval(&e, &samwise, &action);
+            assert!(storage::get_action_approvals(&e, &action).is_empty());
+
+            assert!(!execute_approve_action(&e, &bombadil, &action));
+            assert_eq!(storage::get_co_signer(&e), Some(samwise.clone()));
+            assert!(execute_approve_action(&e, &samwise, &action));
+            assert_eq!(storage::get_co_signer(&e), Some(frodo.clone()));
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_approve_action_not_signer() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        setup(&e, &pool, &bombadil);
+
+        e.as_contract(&pool, || {
+            set_initial_co_signer(&e, &samwise);
+            execute_approve_action(&e, &frodo, &AdminAction::SetBackstop(frodo.clone()));
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(3)")]
+    fn test_set_initial_co_signer_twice() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        setup(&e, &pool, &bombadil);
+
+        e.as_contract(&pool, || {
+            set_initial_co_signer(&e, &samwise);
+            set_initial_co_signer(&e, &frodo);
+        });
+    }
+}
@@ -0,0 +1,421 @@
+use soroban_sdk::{map, panic_with_error, Address, Env, Map, Vec};
+
+use crate::{errors::PoolError, storage, validator::require_not_denied};
+
+use super::{
+    actions::Request, allowlist::require_allowed, borrow_fee::calc_borrow_shares, pool::Pool,
+    submit::execute_submit, Positions,
+};
+
+/// Set the amount of underlying tokens "delegate" can borrow from a reserve on behalf of "owner".
+/// Liabilities borrowed by the delegate are taken on by the owner and backed by the owner's
+/// collateral. Setting the allowance to zero removes it.
+///
+/// ### Arguments
+/// * `owner` - The address of the position owner
+/// * `delegate` - The address allowed to borrow on the owner's behalf
+/// * `asset` - The underlying asset of the reserve
+/// * `amount` - The amount of underlying tokens the delegate can borrow
+///
+/// ### Panics
+/// If the owner and delegate are the same, the asset is not a reserve, or the amount is negative
+pub fn set_borrow_allowance(
+    e: &Env,
+    owner: &Address,
+    delegate: &Address,
+    asset: &Address,
+    amount: i128,
+) {
+    if owner == delegate || !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if amount < 0 {
+        panic_with_error!(e, PoolError::NegativeAmount);
+    } else if amount == 0 {
+        storage::del_borrow_allowance(e, owner, delegate, asset);
+    } else {
+        storage::set_borrow_allowance(e, owner, delegate, asset, &amount);
+    }
+}
+
+/// Execute a set of borrow requests from "delegate" on behalf of "owner". The liabilities are
+/// attributed to "owner" and the borrowed tokens are sent to "to". Each borrow, along with the
+/// origination fee added to the owner's debt, is deducted from the allowance "owner" has granted
+/// "delegate" for the reserve.
+///
+/// Only borrows and the bounds that can be placed on them (max borrow apr, min and max tokens,
+/// expiration) can be delegated.
+///
+/// Returns the new positions for "owner"
+///
+/// ### Arguments
+/// * `delegate` - The address borrowing on behalf of the owner
+/// * `owner` - The address of the user whose positions are being modified
+/// * `to` - The address receiving the borrowed tokens
+/// * `requests` - A vec of requests to be processed
+///
+/// ### Panics
+/// If any request is not delegable, the borrows and their fees exceed the delegate's allowance,
+/// the pool is permissioned and the delegate is not on the allowlist, the delegate is denied by
+/// the pool's denylist, or the requests fail for any of the reasons a submit would
+pub fn execute_submit_delegated(
+    e: &Env,
+    delegate: &Address,
+    owner: &Address,
+    to: &Address,
+    requests: Vec<Request>,
+) -> Positions {
+    if delegate == owner {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    require_allowed(e, delegate);
+    require_not_denied(e, delegate);
+
+    let mut pool = Pool::load(e);
+    let mut borrowed: Map<Address, i128> = map![e];
+    for request in requests.iter() {
+        match request.request_type {
+            4 => {
+                // charge the fee as it is added to the owner's debt, in underlying tokens
+                let (_, fee) = calc_borrow_shares(e, &mut pool, &request.address, request.amount);
+                let total = borrowed.get(request.address.clone()).unwrap_or(0)
+                    + request.amount
+                    + pool.to_underlying_up(e, &request.address, fee);
+                borrowed.set(request.address.clone(), total);
+            }
+            9..=11 | 13 => {}
+            _ => panic_with_error!(e, PoolError::BadRequest),
+        }
+    }
+    for (asset, amount) in borrowed.iter() {
+        let allowance = storage::get_borrow_allowance(e, owner, delegate, &asset);
+        if amount > allowance {
+            panic_with_error!(e, PoolError::AllowanceExceeded);
+        }
+        set_borrow_allowance(e, owner, delegate, &asset, allowance - amount);
+    }
+
+    // borrows do not require any tokens from the spender
+    execute_submit(e, owner, owner, to, requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dependencies::TokenClient,
+        storage::{self, PoolConfig},
+        testutils,
+    };
+
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec,
+    };
+
+    /// Setup "samwise" with 20 tokens of collateral in reserve 0. Returns the underlying assets
+    /// of the reserves.
+    fn setup(e: &Env, pool: &Address, samwise: &Address) -> (Address, Address) {
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(pool, || {
+            storage::set_pool_config(e, &pool_config);
+            storage::set_user_positions(
+                e,
+                samwise,
+                &Positions {
+                    collateral: map![e, (0, 20_0000000)],
+                    liabilities: map![e],
+                    supply: map![e],
+                },
+            );
+        });
+        (underlying_0, underlying_1)
+    }
+
+    #[test]
+    fn test_set_borrow_allowance() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (_, underlying_1) = setup(&e, &pool, &samwise);
+
+        e.as_contract(&pool, || {
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_1, 5_0000000);
+            assert_eq!(
+                storage::get_borrow_allowance(&e, &samwise, &frodo, &underlying_1),
+                5_0000000
+            );
+            assert_eq!(
+                storage::get_borrow_allowance(&e, &frodo, &samwise, &underlying_1),
+                0
+            );
+
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_1, 0);
+            assert_eq!(
+                storage::get_borrow_allowance(&e, &samwise, &frodo, &underlying_1),
+                0
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_borrow_allowance_not_reserve() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        setup(&e, &pool, &samwise);
+
+        e.as_contract(&pool, || {
+            set_borrow_allowance(&e, &samwise, &frodo, &Address::random(&e), 5_0000000);
+        });
+    }
+
+    #[test]
+    fn test_submit_delegated() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (_, underlying_1) = setup(&e, &pool, &samwise);
+
+        e.as_contract(&pool, || {
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_1, 5_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying_1.clone(),
+                    amount: 2_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_1.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            let positions = execute_submit_delegated(&e, &frodo, &samwise, &frodo, requests);
+            assert_eq!(positions.liabilities.get_unchecked(1), 3_0000000);
+            assert_eq!(positions.collateral.get_unchecked(0), 20_0000000);
+            assert!(storage::get_user_positions(&e, &frodo)
+                .liabilities
+                .is_empty());
+
+            assert_eq!(
+                TokenClient::new(&e, &underlying_1).balance(&frodo),
+                3_0000000
+            );
+            assert_eq!(
+                storage::get_borrow_allowance(&e, &samwise, &frodo, &underlying_1),
+                2_0000000
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(9)")]
+    fn test_submit_delegated_over_allowance() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (_, underlying_1) = setup(&e, &pool, &samwise);
+
+        e.as_contract(&pool, || {
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_1, 5_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying_1.clone(),
+                    amount: 5_0000001,
+                },
+            ];
+            execute_submit_delegated(&e, &frodo, &samwise, &frodo, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_submit_delegated_withdraw() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool, &samwise);
+
+        e.as_contract(&pool, || {
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_0, 5_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 3,
+                    address: underlying_0.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            execute_submit_delegated(&e, &frodo, &samwise, &frodo, requests);
+        });
+    }
+
+    #[test]
+    fn test_submit_delegated_with_borrow_fee() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (_, underlying_1) = setup(&e, &pool, &samwise);
+
+        e.as_contract(&pool, || {
+            storage::set_res_borrow_fee(&e, &underlying_1, &0_0100000);
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_1, 5_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying_1.clone(),
+                    amount: 3_0000000,
+                },
+            ];
+            let positions = execute_submit_delegated(&e, &frodo, &samwise, &frodo, requests);
+            assert_eq!(positions.liabilities.get_unchecked(1), 3_0300000);
+
+            // the fee added to the owner's debt is charged to the allowance
+            assert_eq!(
+                storage::get_borrow_allowance(&e, &samwise, &frodo, &underlying_1),
+                1_9700000
+            );
+        });
+    }
+
+    #[test]
+    fn test_submit_delegated_with_borrow_fee_and_exchange_rate() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (_, underlying_1) = setup(&e, &pool, &samwise);
+        let (adapter, adapter_client) = testutils::create_mock_exchange_rate(&e);
+        adapter_client.set_rate(&1_100_000_000);
+
+        e.as_contract(&pool, || {
+            storage::set_res_adapter(&e, &underlying_1, &adapter);
+            storage::set_res_borrow_fee(&e, &underlying_1, &0_0100000);
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_1, 5_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying_1.clone(),
+                    amount: 3_0000000,
+                },
+            ];
+            let positions = execute_submit_delegated(&e, &frodo, &samwise, &frodo, requests);
+            assert_eq!(positions.liabilities.get_unchecked(1), 2_7545456);
+
+            // the fee is charged on the borrowed shares, and to the allowance in underlying tokens
+            assert_eq!(
+                storage::get_borrow_allowance(&e, &samwise, &frodo, &underlying_1),
+                1_9699999
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(9)")]
+    fn test_submit_delegated_borrow_fee_over_allowance() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (_, underlying_1) = setup(&e, &pool, &samwise);
+
+        e.as_contract(&pool, || {
+            storage::set_res_borrow_fee(&e, &underlying_1, &0_0100000);
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_1, 5_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying_1.clone(),
+                    amount: 5_0000000,
+                },
+            ];
+            execute_submit_delegated(&e, &frodo, &samwise, &frodo, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_submit_delegated_delegate_not_allowed() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let (_, underlying_1) = setup(&e, &pool, &samwise);
+
+        e.as_contract(&pool, || {
+            storage::set_permissioned(&e, &true);
+            storage::set_allowed(&e, &samwise);
+            set_borrow_allowance(&e, &samwise, &frodo, &underlying_1, 5_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying_1.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            execute_submit_delegated(&e, &frodo, &samwise, &frodo, requests);
+        });
+    }
+}
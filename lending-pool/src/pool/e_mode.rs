@@ -0,0 +1,307 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{
+    errors::PoolError,
+    storage::{self, EModeCategory},
+    validator::require_max_leverage,
+};
+
+use super::{
+    health_factor::{FactorBasis, PositionData},
+    pool::Pool,
+    trusted::get_trusted_credit,
+    Positions,
+};
+
+/// Set an e-mode category of correlated assets. A category with no assets removes it, and
+/// users opted into a removed category use each reserve's factors.
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+/// * `category` - The e-mode category
+///
+/// ### Panics
+/// If an asset is not a reserve, or the category's factors are invalid
+pub fn set_e_mode_category(e: &Env, category_id: u32, category: &EModeCategory) {
+    if category.assets.is_empty() {
+        storage::del_e_mode_category(e, category_id);
+        return;
+    }
+    for asset in category.assets.iter() {
+        if !storage::has_res(e, &asset) {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+    }
+    if category.c_factor > category.liq_threshold
+        || category.liq_threshold > 1_0000000
+        || category.l_factor == 0
+        || category.l_factor > 1_0000000
+    {
+        panic_with_error!(e, PoolError::InvalidReserveMetadata);
+    }
+    storage::set_e_mode_category(e, category_id, category);
+}
+
+/// Opt a user into an e-mode category, or out of e-mode with `None`. The user's positions
+/// must remain healthy under the factors they use after the change.
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `category_id` - The id of the e-mode category, or `None` to leave e-mode
+///
+/// ### Panics
+/// If the category does not exist, or the user's positions are unhealthy after the change
+pub fn execute_set_user_e_mode(e: &Env, user: &Address, category_id: Option<u32>) {
+    match category_id {
+        Some(category_id) => {
+            if storage::get_e_mode_category(e, category_id).is_none() {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            storage::set_user_e_mode(e, user, &category_id);
+        }
+        None => storage::del_user_e_mode(e, user),
+    }
+
+    let positions = storage::get_user_positions(e, user);
+    if !positions.liabilities.is_empty() {
+        let mut pool = Pool::load(e);
+        let credit = get_trusted_credit(e, user);
+        let position_data =
+            PositionData::calculate_for_user(e, &mut pool, user, &positions, FactorBasis::Borrow);
+        position_data.require_healthy_with_credit(e, credit);
+        require_max_leverage(e, &position_data, credit);
+    }
+}
+
+/// Load the e-mode category that applies to a user's positions. A category only applies if
+/// the user has opted into it and all of their collateral and liabilities are in its assets.
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `positions` - The user's positions
+pub fn load_user_e_mode(e: &Env, user: &Address, positions: &Positions) -> Option<EModeCategory> {
+    let category = storage::get_e_mode_category(e, storage::get_user_e_mode(e, user)?)?;
+    let reserve_list = storage::get_res_list(e);
+    for index in positions
+        .collateral
+        .keys()
+        .iter()
+        .chain(positions.liabilities.keys().iter())
+    {
+        if !category.assets.contains(reserve_list.get_unchecked(index)) {
+            return None;
+        }
+    }
+    Some(category)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec,
+    };
+
+    /// Setup a pool with reserves 0 and 1 in e-mode category 1, and reserve 2 outside of it.
+    /// Returns the underlying assets of the reserves.
+    fn setup(e: &Env, pool: &Address) -> (Address, Address, Address) {
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let (underlying_2, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, pool, &underlying_2, &reserve_config, &reserve_data);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+        oracle_client.set_price(&underlying_2, &1_0000000);
+
+        e.as_contract(pool, || {
+            storage::set_pool_config(
+                e,
+                &PoolConfig {
+                    oracle,
+                    bstop_rate: 0_100_000_000,
+                    status: 0,
+                    liq_hf_low: 1_0000000,
+                    liq_hf_high: 1_0000100,
+                },
+            );
+            set_e_mode_category(
+                e,
+                1,
+                &EModeCategory {
+                    c_factor: 0_9500000,
+                    l_factor: 0_9500000,
+                    liq_threshold: 0_9700000,
+                    assets: vec![e, underlying_0.clone(), underlying_1.clone()],
+                },
+            );
+        });
+        (underlying_0, underlying_1, underlying_2)
+    }
+
+    #[test]
+    fn test_e_mode_position_data() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            // 10 collateral backs 5.625 of liabilities with the reserve factors of 0.75
+            let positions = Positions {
+                collateral: map![&e, (0, 10_0000000)],
+                liabilities: map![&e, (1, 8_0000000)],
+                supply: map![&e],
+            };
+            storage::set_user_positions(&e, &samwise, &positions);
+            let mut pool = Pool::load(&e);
+
+            let position_data = PositionData::calculate_for_user(
+                &e,
+                &mut pool,
+                &samwise,
+                &positions,
+                FactorBasis::Borrow,
+            );
+            assert_eq!(position_data.collateral_base, 7_5000000);
+            assert_eq!(position_data.liability_base, 10_6666667);
+
+            execute_set_user_e_mode(&e, &samwise, Some(1));
+            assert_eq!(storage::get_user_e_mode(&e, &samwise), Some(1));
+            let position_data = PositionData::calculate_for_user(
+                &e,
+                &mut pool,
+                &samwise,
+                &positions,
+                FactorBasis::Borrow,
+            );
+            assert_eq!(position_data.collateral_base, 9_5000000);
+            assert_eq!(position_data.liability_base, 8_4210527);
+            assert_eq!(position_data.collateral_raw, 10_0000000);
+            assert_eq!(position_data.liability_raw, 8_0000000);
+            let position_data = PositionData::calculate_for_user(
+                &e,
+                &mut pool,
+                &samwise,
+                &positions,
+                FactorBasis::Liquidation,
+            );
+            assert_eq!(position_data.collateral_base, 9_7000000);
+        });
+    }
+
+    #[test]
+    fn test_e_mode_position_outside_category() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            storage::set_user_e_mode(&e, &samwise, &1);
+            let positions = Positions {
+                collateral: map![&e, (0, 10_0000000), (2, 1_0000000)],
+                liabilities: map![&e, (1, 1_0000000)],
+                supply: map![&e],
+            };
+            assert!(load_user_e_mode(&e, &samwise, &positions).is_none());
+
+            let positions = Positions {
+                collateral: map![&e, (0, 10_0000000)],
+                liabilities: map![&e, (1, 1_0000000)],
+                supply: map![&e, (2, 1_0000000)],
+            };
+            // supply without collateral does not affect the category
+            assert!(load_user_e_mode(&e, &samwise, &positions).is_some());
+
+            // removing the category removes its factors
+            set_e_mode_category(
+                &e,
+                1,
+                &EModeCategory {
+                    c_factor: 0,
+                    l_factor: 0,
+                    liq_threshold: 0,
+                    assets: vec![&e],
+                },
+            );
+            assert!(load_user_e_mode(&e, &samwise, &positions).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(10)")]
+    fn test_set_user_e_mode_leave_unhealthy() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            storage::set_user_e_mode(&e, &samwise, &1);
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 10_0000000)],
+                    liabilities: map![&e, (1, 8_0000000)],
+                    supply: map![&e],
+                },
+            );
+            execute_set_user_e_mode(&e, &samwise, None);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(6)")]
+    fn test_set_e_mode_category_c_factor_over_threshold() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let (underlying_0, _, _) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            set_e_mode_category(
+                &e,
+                2,
+                &EModeCategory {
+                    c_factor: 0_9800000,
+                    l_factor: 0_9500000,
+                    liq_threshold: 0_9700000,
+                    assets: vec![&e, underlying_0],
+                },
+            );
+        });
+    }
+}
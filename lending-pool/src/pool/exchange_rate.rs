@@ -0,0 +1,122 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{constants::SCALAR_9, dependencies::ExchangeRateClient, errors::PoolError, storage};
+
+/// Fetch the exchange rate from share units to underlying tokens for a reserve's asset, expressed
+/// in 9 decimals.
+///
+/// Reserves without an exchange rate adapter are accounted for directly in the underlying
+/// asset and have a rate of 1.
+///
+/// ### Arguments
+/// * `asset` - The address of the underlying asset
+///
+/// ### Panics
+/// If the adapter reports a non-positive rate
+pub fn load_exchange_rate(e: &Env, asset: &Address) -> i128 {
+    match storage::get_res_adapter(e, asset) {
+        Some(adapter) => {
+            let rate = ExchangeRateClient::new(e, &adapter).rate();
+            if rate <= 0 {
+                panic_with_error!(e, PoolError::InvalidExchangeRate);
+            }
+            rate
+        }
+        None => SCALAR_9,
+    }
+}
+
+/// Set the exchange rate adapter for a reserve. The adapter normalizes the underlying token into
+/// a fixed share unit before any pool accounting occurs.
+///
+/// ### Arguments
+/// * `asset` - The address of the underlying asset
+/// * `adapter` - The address of the exchange rate adapter
+///
+/// ### Panics
+/// If the reserve does not exist or already has outstanding supply or liabilities
+pub fn set_reserve_exchange_rate_adapter(e: &Env, asset: &Address, adapter: &Address) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    // switching the unit of account for an active reserve would corrupt existing balances
+    let reserve_data = storage::get_res_data(e, asset);
+    if reserve_data.b_supply != 0 || reserve_data.d_supply != 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    storage::set_res_adapter(e, asset, adapter);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testutils;
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_load_exchange_rate_no_adapter() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let asset = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(load_exchange_rate(&e, &asset), SCALAR_9);
+        });
+    }
+
+    #[test]
+    fn test_load_exchange_rate_with_adapter() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let asset = Address::random(&e);
+        let (adapter, adapter_client) = testutils::create_mock_exchange_rate(&e);
+        adapter_client.set_rate(&1_050_000_000);
+
+        e.as_contract(&pool, || {
+            storage::set_res_adapter(&e, &asset, &adapter);
+            assert_eq!(load_exchange_rate(&e, &asset), 1_050_000_000);
+        });
+    }
+
+    #[test]
+    fn test_set_reserve_exchange_rate_adapter() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+        let adapter = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.b_supply = 0;
+        reserve_data.d_supply = 0;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_reserve_exchange_rate_adapter(&e, &underlying, &adapter);
+            assert_eq!(storage::get_res_adapter(&e, &underlying), Some(adapter));
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_reserve_exchange_rate_adapter_active_reserve() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+        let adapter = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_reserve_exchange_rate_adapter(&e, &underlying, &adapter);
+        });
+    }
+}
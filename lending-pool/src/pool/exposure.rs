@@ -0,0 +1,280 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    errors::PoolError,
+    storage::{self, ReserveExposure},
+};
+
+use super::{pool::Pool, Positions};
+
+/// Set the maximum liability value, denominated in the oracle's base asset, that can be backed
+/// by a collateral reserve pool-wide. A cap of zero removes the guard.
+///
+/// The collateral tracked against the reserve is kept when the cap is updated.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `cap` - The maximum liability value backed by the reserve
+///
+/// ### Panics
+/// If the reserve does not exist or the cap is negative
+pub fn set_reserve_exposure_cap(e: &Env, asset: &Address, cap: i128) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if cap < 0 {
+        panic_with_error!(e, PoolError::NegativeAmount);
+    }
+
+    let mut exposure = load_exposure(e, asset);
+    exposure.cap = cap;
+    storage::set_res_exposure(e, asset, &exposure);
+}
+
+/// Update the blendTokens of a reserve held as collateral by users with liabilities. Called
+/// by the user's position updates, such that every path that moves collateral or opens or
+/// closes a borrow is tracked without pricing the reserve.
+///
+/// The tracked collateral never drops below zero.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `b_tokens` - The change in blendTokens held as collateral by users with liabilities
+pub fn update_exposure(e: &Env, asset: &Address, b_tokens: i128) {
+    if b_tokens == 0 {
+        return;
+    }
+    let mut exposure = load_exposure(e, asset);
+    exposure.collateral = (exposure.collateral + b_tokens).max(0);
+    storage::set_res_exposure(e, asset, &exposure);
+}
+
+/// Require that the liability value the user's capped collateral reserves can back pool-wide
+/// is within their caps. The tracked collateral of each reserve is valued at its effective
+/// collateral value, such that it bounds the liabilities it backs.
+///
+/// Only called for actions that increase the user's risk, as only these need a price.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `positions` - The user's positions after the action
+///
+/// ### Panics
+/// If the collateral of a reserve the user holds can back more than the reserve's cap
+pub fn require_exposure_caps(e: &Env, pool: &mut Pool, positions: &Positions) {
+    if positions.liabilities.is_empty() || positions.collateral.is_empty() {
+        return;
+    }
+    let reserve_list = storage::get_res_list(e);
+    for (reserve_index, _) in positions.collateral.iter() {
+        let asset = reserve_list.get_unchecked(reserve_index);
+        let exposure = match storage::get_res_exposure(e, &asset) {
+            Some(exposure) if exposure.cap > 0 => exposure,
+            _ => continue,
+        };
+        let reserve = pool.load_reserve(e, &asset);
+        let backed = pool
+            .load_price(e, &asset)
+            .fixed_mul_floor(
+                reserve.to_effective_asset_from_b_token(exposure.collateral),
+                reserve.scalar,
+            )
+            .unwrap_optimized();
+        if backed > exposure.cap {
+            panic_with_error!(e, PoolError::ExposureCapExceeded);
+        }
+    }
+}
+
+/// Load the exposure of a reserve, defaulting to an uncapped reserve with no tracked collateral
+fn load_exposure(e: &Env, asset: &Address) -> ReserveExposure {
+    storage::get_res_exposure(e, asset).unwrap_or(ReserveExposure {
+        cap: 0,
+        collateral: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pool::User, storage::PoolConfig, testutils};
+    use soroban_sdk::map;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    fn setup(e: &Env, pool: &Address) -> (Address, Address) {
+        let bombadil = Address::random(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(e);
+        testutils::create_reserve(e, pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(e);
+        testutils::create_reserve(e, pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &2_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(pool, || {
+            storage::set_pool_config(e, &pool_config);
+        });
+        (underlying_0, underlying_1)
+    }
+
+    #[test]
+    fn test_set_reserve_exposure_cap() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            set_reserve_exposure_cap(&e, &underlying_0, 100_0000000);
+            let exposure = storage::get_res_exposure(&e, &underlying_0).unwrap_optimized();
+            assert_eq!(exposure.cap, 100_0000000);
+            assert_eq!(exposure.collateral, 0);
+
+            // tracked collateral is kept when the cap is updated
+            update_exposure(&e, &underlying_0, 40_0000000);
+            set_reserve_exposure_cap(&e, &underlying_0, 50_0000000);
+            let exposure = storage::get_res_exposure(&e, &underlying_0).unwrap_optimized();
+            assert_eq!(exposure.cap, 50_0000000);
+            assert_eq!(exposure.collateral, 40_0000000);
+
+            set_reserve_exposure_cap(&e, &underlying_0, 0);
+            let exposure = storage::get_res_exposure(&e, &underlying_0).unwrap_optimized();
+            assert_eq!(exposure.cap, 0);
+            assert_eq!(exposure.collateral, 40_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(4)")]
+    fn test_set_reserve_exposure_cap_negative() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            set_reserve_exposure_cap(&e, &underlying_0, -1);
+        });
+    }
+
+    #[test]
+    fn test_update_exposure_tracks_borrower_collateral() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let (underlying_0, underlying_1) = setup(&e, &pool);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            let mut pool = Pool::load(&e);
+            let mut reserve_0 = pool.load_reserve(&e, &underlying_0);
+            let mut reserve_1 = pool.load_reserve(&e, &underlying_1);
+            let mut user = User {
+                address: samwise.clone(),
+                positions: Positions::env_default(&e),
+            };
+
+            // collateral is not tracked until the user borrows
+            user.add_collateral(&e, &mut reserve_0, 50_0000000);
+            assert!(storage::get_res_exposure(&e, &underlying_0).is_none());
+
+            user.add_liabilities(&e, &mut reserve_1, 5_0000000);
+            let exposure = storage::get_res_exposure(&e, &underlying_0).unwrap_optimized();
+            assert_eq!(exposure.collateral, 50_0000000);
+
+            user.remove_collateral(&e, &mut reserve_0, 10_0000000);
+            let exposure = storage::get_res_exposure(&e, &underlying_0).unwrap_optimized();
+            assert_eq!(exposure.collateral, 40_0000000);
+
+            // repaying part of the debt keeps the collateral tracked
+            user.remove_liabilities(&e, &mut reserve_1, 4_0000000);
+            let exposure = storage::get_res_exposure(&e, &underlying_0).unwrap_optimized();
+            assert_eq!(exposure.collateral, 40_0000000);
+
+            user.remove_liabilities(&e, &mut reserve_1, 1_0000000);
+            let exposure = storage::get_res_exposure(&e, &underlying_0).unwrap_optimized();
+            assert_eq!(exposure.collateral, 0);
+        });
+    }
+
+    #[test]
+    fn test_require_exposure_caps() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let (underlying_0, underlying_1) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            // 40 b_tokens back 30 of effective collateral at a price of 1
+            set_reserve_exposure_cap(&e, &underlying_0, 30_0000000);
+            update_exposure(&e, &underlying_0, 40_0000000);
+            // uncapped reserves are not checked
+            update_exposure(&e, &underlying_1, 1000_0000000);
+            let mut pool = Pool::load(&e);
+
+            let positions = Positions {
+                liabilities: map![&e, (1, 5_0000000)],
+                collateral: map![&e, (0, 40_0000000), (1, 10_0000000)],
+                supply: map![&e],
+            };
+            require_exposure_caps(&e, &mut pool, &positions);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(18)")]
+    fn test_require_exposure_caps_over_cap() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let pool = Address::random(&e);
+        let (underlying_0, _) = setup(&e, &pool);
+
+        e.as_contract(&pool, || {
+            set_reserve_exposure_cap(&e, &underlying_0, 29_9999999);
+            update_exposure(&e, &underlying_0, 40_0000000);
+            let mut pool = Pool::load(&e);
+
+            let positions = Positions {
+                liabilities: map![&e, (1, 5_0000000)],
+                collateral: map![&e, (0, 40_0000000)],
+                supply: map![&e],
+            };
+            require_exposure_caps(&e, &mut pool, &positions);
+        });
+    }
+}
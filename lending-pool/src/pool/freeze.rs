@@ -0,0 +1,151 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{errors::PoolError, storage};
+
+use super::pool::Pool;
+
+/// Freeze interest accrual for every reserve in the pool. Interest is accrued up to the current
+/// ledger, after which rates and rate modifiers are pinned until the freeze is lifted. Time
+/// spent frozen is never accrued.
+///
+/// ### Arguments
+/// * `from` - The admin or co-signer freezing interest accrual
+///
+/// ### Panics
+/// If `from` is not the admin or co-signer, or interest is already frozen
+pub fn execute_freeze_interest(e: &Env, from: &Address) {
+    if *from != storage::get_admin(e) && Some(from.clone()) != storage::get_co_signer(e) {
+        panic_with_error!(e, PoolError::NotAuthorized);
+    }
+    if storage::get_interest_freeze(e).is_some() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    update_all_reserves(e);
+    storage::set_interest_freeze(e, &e.ledger().timestamp());
+}
+
+/// Lift the interest freeze, resuming interest accrual from the current ledger
+///
+/// Returns the number of seconds interest accrual was frozen for
+///
+/// ### Panics
+/// If interest is not frozen
+pub fn execute_unfreeze_interest(e: &Env) -> u64 {
+    let start = match storage::get_interest_freeze(e) {
+        Some(start) => start,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+
+    // bring each reserve's last accrual time up to date while still frozen
+    update_all_reserves(e);
+    storage::del_interest_freeze(e);
+    e.ledger().timestamp() - start
+}
+
+fn update_all_reserves(e: &Env) {
+    let mut pool = Pool::load(e);
+    for asset in storage::get_res_list(e).iter() {
+        let reserve = pool.load_reserve(e, &asset);
+        pool.cache_reserve(reserve, true);
+    }
+    pool.store_cached_reserves(e);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_freeze_interest() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_admin(&e, &bombadil);
+            storage::set_pool_config(&e, &pool_config);
+
+            // interest up to the freeze is accrued
+            execute_freeze_interest(&e, &bombadil);
+            assert_eq!(storage::get_interest_freeze(&e), Some(12345));
+            let frozen_data = storage::get_res_data(&e, &underlying);
+            assert_eq!(frozen_data.last_time, 12345);
+            assert!(frozen_data.d_rate > 1_000_000_000);
+        });
+
+        testutils::set_timestamp(&e, 12345 + 60 * 60 * 24);
+        e.as_contract(&pool_address, || {
+            let frozen_data = storage::get_res_data(&e, &underlying);
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying);
+            assert_eq!(reserve.d_rate, frozen_data.d_rate);
+            assert_eq!(reserve.b_rate, frozen_data.b_rate);
+            assert_eq!(reserve.ir_mod, frozen_data.ir_mod);
+
+            let frozen_time = execute_unfreeze_interest(&e);
+            assert_eq!(frozen_time, 60 * 60 * 24);
+            assert!(storage::get_interest_freeze(&e).is_none());
+            let unfrozen_data = storage::get_res_data(&e, &underlying);
+            assert_eq!(unfrozen_data.last_time, 12345 + 60 * 60 * 24);
+            assert_eq!(unfrozen_data.d_rate, frozen_data.d_rate);
+        });
+
+        testutils::set_timestamp(&e, 12345 + 2 * 60 * 60 * 24);
+        e.as_contract(&pool_address, || {
+            let unfrozen_data = storage::get_res_data(&e, &underlying);
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying);
+            assert!(reserve.d_rate > unfrozen_data.d_rate);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(1)")]
+    fn test_freeze_interest_not_signer() {
+        let e = Env::default();
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            storage::set_admin(&e, &bombadil);
+            execute_freeze_interest(&e, &samwise);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_unfreeze_interest_not_frozen() {
+        let e = Env::default();
+        let pool_address = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            execute_unfreeze_interest(&e);
+        });
+    }
+}
@@ -1,30 +1,107 @@
+use cast::i128;
 use fixed_point_math::FixedPoint;
-use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Env};
+use soroban_sdk::{
+    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map,
+};
 
-use crate::{constants::SCALAR_7, errors::PoolError, storage};
+use crate::{
+    constants::{SCALAR_7, SCALAR_9},
+    errors::PoolError,
+    storage::{self, EModeCategory},
+};
 
-use super::{pool::Pool, Positions};
+use super::{e_mode::load_user_e_mode, interest::calc_borrow_rate, pool::Pool, Positions, Reserve};
+
+/// The liquidation risk of a set of positions
+#[derive(Clone)]
+#[contracttype]
+pub struct PositionRisk {
+    /// The price drop of each collateral asset, in isolation, that would make the position
+    /// liquidatable (7 decimals). A value of 1_0000000 means no price drop can do so.
+    pub price_drops: Map<Address, i128>,
+    /// The number of days of interest accrual at current rates until the position is
+    /// liquidatable, or u64::MAX if interest accrual alone never makes it so
+    pub days_to_liquidation: u64,
+}
+
+/// The factors collateral is weighted by when calculating effective balances
+#[derive(Clone, Copy, PartialEq)]
+pub enum FactorBasis {
+    /// Weight collateral by the factors that bound how much can be borrowed against it
+    Borrow,
+    /// Weight collateral by the factors that bound when a position can be liquidated
+    Liquidation,
+}
 
 pub struct PositionData {
     /// The effective collateral balance denominated in the base asset
     pub collateral_base: i128,
-    // The raw collateral balance demoninated in the base asset
+    /// The raw collateral balance demoninated in the base asset
     pub collateral_raw: i128,
     /// The effective liability balance denominated in the base asset
     pub liability_base: i128,
-    // The raw liability balance demoninated in the base asset
+    /// The raw liability balance demoninated in the base asset
     pub liability_raw: i128,
     /// The scalar for the base asset
     pub scalar: i128,
 }
 
 impl PositionData {
-    /// Calculate the position data for a given set of of positions
+    /// Calculate the position data for a given set of of positions, with the effective
+    /// balances weighted by the factors that bound borrowing
     ///
     /// ### Arguments
     /// * pool - The pool
     /// * positions - The positions to calculate the health factor for
     pub fn calculate_from_positions(e: &Env, pool: &mut Pool, positions: &Positions) -> Self {
+        PositionData::calculate_from_positions_with_basis(e, pool, positions, FactorBasis::Borrow)
+    }
+
+    /// Calculate the position data for a given set of of positions, with the effective
+    /// balances weighted by the factors of the given basis. The raw balances do not depend
+    /// on the basis.
+    ///
+    /// ### Arguments
+    /// * pool - The pool
+    /// * positions - The positions to calculate the health factor for
+    /// * basis - The factors to weight the effective balances by
+    pub fn calculate_from_positions_with_basis(
+        e: &Env,
+        pool: &mut Pool,
+        positions: &Positions,
+        basis: FactorBasis,
+    ) -> Self {
+        PositionData::calculate(e, pool, positions, basis, None)
+    }
+
+    /// Calculate the position data for a user's positions, with the effective balances
+    /// weighted by the factors of the given basis. If the user has opted into an e-mode
+    /// category that contains all of their collateral and liabilities, the category's
+    /// factors are used in place of each reserve's.
+    ///
+    /// ### Arguments
+    /// * pool - The pool
+    /// * user - The address of the user who owns the positions
+    /// * positions - The positions to calculate the health factor for
+    /// * basis - The factors to weight the effective balances by
+    pub fn calculate_for_user(
+        e: &Env,
+        pool: &mut Pool,
+        user: &Address,
+        positions: &Positions,
+        basis: FactorBasis,
+    ) -> Self {
+        let e_mode = load_user_e_mode(e, user, positions);
+        PositionData::calculate(e, pool, positions, basis, e_mode.as_ref())
+    }
+
+    fn calculate(
+        e: &Env,
+        pool: &mut Pool,
+        positions: &Positions,
+        basis: FactorBasis,
+        e_mode: Option<&EModeCategory>,
+    ) -> Self {
         let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
 
         let reserve_list = storage::get_res_list(e);
@@ -43,7 +120,8 @@ impl PositionData {
 
             if b_token_balance > 0 {
                 // append users effective collateral to collateral_base
-                let asset_collateral = reserve.to_effective_asset_from_b_token(b_token_balance);
+                let asset_collateral =
+                    effective_collateral(&reserve, b_token_balance, basis, e_mode);
                 collateral_base += asset_to_base
                     .fixed_mul_floor(asset_collateral, reserve.scalar)
                     .unwrap_optimized();
@@ -57,7 +135,7 @@ impl PositionData {
 
             if d_token_balance > 0 {
                 // append users effective liability to liability_base
-                let asset_liability = reserve.to_effective_asset_from_d_token(d_token_balance);
+                let asset_liability = effective_liability(&reserve, d_token_balance, e_mode);
                 liability_base += asset_to_base
                     .fixed_mul_floor(asset_liability, reserve.scalar)
                     .unwrap_optimized();
@@ -90,6 +168,15 @@ impl PositionData {
 
     /// Check if the position data meets the minimum health factor, panic if not
     pub fn require_healthy(&self, e: &Env) {
+        self.require_healthy_with_credit(e, 0);
+    }
+
+    /// Check if the position data meets the minimum health factor when an uncollateralized
+    /// credit line is counted as collateral, panic if not
+    ///
+    /// ### Arguments
+    /// * credit - The uncollateralized credit available, denominated in the base asset
+    pub fn require_healthy_with_credit(&self, e: &Env, credit: i128) {
         if self.liability_base == 0 {
             return;
         }
@@ -99,10 +186,202 @@ impl PositionData {
             .scalar
             .fixed_mul_floor(1_0000100, SCALAR_7)
             .unwrap_optimized();
-        if self.as_health_factor() < min_health_factor {
+        let health_factor = (self.collateral_base + credit)
+            .fixed_div_ceil(self.liability_base, self.scalar)
+            .unwrap_optimized();
+        if health_factor < min_health_factor {
+            panic_with_error!(e, PoolError::InvalidHf);
+        }
+    }
+
+    /// Check if the position data meets a minimum health factor when an uncollateralized
+    /// credit line is counted as collateral, panic if not
+    ///
+    /// ### Arguments
+    /// * credit - The uncollateralized credit available, denominated in the base asset
+    /// * min_health_factor - The minimum health factor, expressed in 7 decimals
+    pub fn require_min_hf(&self, e: &Env, credit: i128, min_health_factor: u32) {
+        if self.liability_base == 0 {
+            return;
+        }
+
+        let min_health_factor = self
+            .scalar
+            .fixed_mul_floor(i128(min_health_factor), SCALAR_7)
+            .unwrap_optimized();
+        let health_factor = (self.collateral_base + credit)
+            .fixed_div_ceil(self.liability_base, self.scalar)
+            .unwrap_optimized();
+        if health_factor < min_health_factor {
             panic_with_error!(e, PoolError::InvalidHf);
         }
     }
+
+    /// Check if the position data is at or below a health factor when an uncollateralized
+    /// credit line is counted as collateral
+    ///
+    /// ### Arguments
+    /// * credit - The uncollateralized credit available, denominated in the base asset
+    /// * health_factor - The health factor, expressed in 7 decimals
+    pub fn is_at_or_below_hf(&self, credit: i128, health_factor: u32) -> bool {
+        let max_collateral = self
+            .liability_base
+            .fixed_mul_floor(i128(health_factor), SCALAR_7)
+            .unwrap_optimized();
+        self.collateral_base + credit <= max_collateral
+    }
+
+    /// Calculate the liquidation risk for a given set of positions. A position becomes
+    /// liquidatable once its effective collateral plus any uncollateralized credit falls to the
+    /// pool's liquidation health factor (`liq_hf_low`) times its effective liabilities.
+    ///
+    /// Interest accrual is estimated linearly from the current borrow rates and the supply
+    /// rates paid on collateral, with prices held constant.
+    ///
+    /// ### Arguments
+    /// * pool - The pool
+    /// * user - The address of the user who owns the positions
+    /// * positions - The positions to calculate the risk for
+    /// * credit - The uncollateralized credit available, denominated in the base asset
+    pub fn calculate_risk(
+        e: &Env,
+        pool: &mut Pool,
+        user: &Address,
+        positions: &Positions,
+        credit: i128,
+    ) -> PositionRisk {
+        let e_mode = load_user_e_mode(e, user, positions);
+        let position_data = PositionData::calculate(
+            e,
+            pool,
+            positions,
+            FactorBasis::Liquidation,
+            e_mode.as_ref(),
+        );
+        let liq_hf = i128(pool.config.liq_hf_low);
+        let buffer = position_data.collateral_base + credit
+            - position_data
+                .liability_base
+                .fixed_mul_ceil(liq_hf, SCALAR_7)
+                .unwrap_optimized();
+
+        let reserve_list = storage::get_res_list(e);
+        let mut price_drops = map![e];
+        // the net annual change in effective liabilities less effective collateral
+        let mut annual_growth: i128 = 0;
+        for i in 0..reserve_list.len() {
+            let b_token_balance = positions.collateral.get(i).unwrap_or(0);
+            let d_token_balance = positions.liabilities.get(i).unwrap_or(0);
+            if b_token_balance == 0 && d_token_balance == 0 {
+                continue;
+            }
+            let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(i));
+            let asset_to_base = pool.load_price(e, &reserve.asset);
+
+            let collateral = asset_to_base
+                .fixed_mul_floor(
+                    effective_collateral(
+                        &reserve,
+                        b_token_balance,
+                        FactorBasis::Liquidation,
+                        e_mode.as_ref(),
+                    ),
+                    reserve.scalar,
+                )
+                .unwrap_optimized();
+            // liabilities are scaled by the liquidation health factor, such that the buffer
+            // reaches zero when the position can be liquidated
+            let liability = asset_to_base
+                .fixed_mul_floor(
+                    effective_liability(&reserve, d_token_balance, e_mode.as_ref()),
+                    reserve.scalar,
+                )
+                .unwrap_optimized()
+                .fixed_mul_ceil(liq_hf, SCALAR_7)
+                .unwrap_optimized();
+
+            if b_token_balance > 0 {
+                // a price drop reduces both the collateral and any liability in the same asset
+                let price_drop = if buffer <= 0 {
+                    0
+                } else if collateral <= liability {
+                    SCALAR_7
+                } else {
+                    buffer
+                        .fixed_div_floor(collateral - liability, SCALAR_7)
+                        .unwrap_optimized()
+                        .min(SCALAR_7)
+                };
+                price_drops.set(reserve.asset.clone(), price_drop);
+            }
+
+            if reserve.b_supply > 0 {
+                let config = storage::get_res_config(e, &reserve.asset);
+                let cur_util = reserve.utilization();
+                let borrow_rate = calc_borrow_rate(&config, cur_util, reserve.ir_mod);
+                let supply_rate = borrow_rate
+                    .fixed_mul_floor(cur_util, SCALAR_7)
+                    .unwrap_optimized()
+                    .fixed_mul_floor(SCALAR_9 - i128(pool.config.bstop_rate), SCALAR_9)
+                    .unwrap_optimized();
+                annual_growth += liability
+                    .fixed_mul_ceil(borrow_rate, SCALAR_7)
+                    .unwrap_optimized();
+                annual_growth -= collateral
+                    .fixed_mul_floor(supply_rate, SCALAR_7)
+                    .unwrap_optimized();
+            }
+
+            pool.cache_reserve(reserve, false);
+        }
+
+        let days_to_liquidation = if buffer <= 0 {
+            0
+        } else if annual_growth <= 0 {
+            u64::MAX
+        } else {
+            (buffer * 365 / annual_growth) as u64
+        };
+
+        PositionRisk {
+            price_drops,
+            days_to_liquidation,
+        }
+    }
+}
+
+/// Convert b_tokens to their effective asset value under the factors of the basis, using the
+/// e-mode category's factors if one applies
+fn effective_collateral(
+    reserve: &Reserve,
+    b_tokens: i128,
+    basis: FactorBasis,
+    e_mode: Option<&EModeCategory>,
+) -> i128 {
+    match (e_mode, basis) {
+        (Some(category), FactorBasis::Borrow) => reserve
+            .to_asset_from_b_token(b_tokens)
+            .fixed_mul_floor(i128(category.c_factor), SCALAR_7)
+            .unwrap_optimized(),
+        (Some(category), FactorBasis::Liquidation) => reserve
+            .to_asset_from_b_token(b_tokens)
+            .fixed_mul_floor(i128(category.liq_threshold), SCALAR_7)
+            .unwrap_optimized(),
+        (None, FactorBasis::Borrow) => reserve.to_effective_asset_from_b_token(b_tokens),
+        (None, FactorBasis::Liquidation) => reserve.to_liq_effective_asset_from_b_token(b_tokens),
+    }
+}
+
+/// Convert d_tokens to their effective asset value, using the e-mode category's liability
+/// factor if one applies
+fn effective_liability(reserve: &Reserve, d_tokens: i128, e_mode: Option<&EModeCategory>) -> i128 {
+    match e_mode {
+        Some(category) => reserve
+            .to_asset_from_d_token(d_tokens)
+            .fixed_div_ceil(i128(category.l_factor), SCALAR_7)
+            .unwrap_optimized(),
+        None => reserve.to_effective_asset_from_d_token(d_tokens),
+    }
 }
 
 #[cfg(test)]
@@ -169,6 +448,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
 
         let positions = Positions {
@@ -188,6 +469,168 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_calculate_from_positions_with_basis() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.index = 1;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &2_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 10_0000000)],
+            collateral: map![&e, (0, 100_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_res_liq_threshold(&e, &underlying_0, &0_8000000);
+            let mut pool = Pool::load(&e);
+            let borrow_data = PositionData::calculate_from_positions_with_basis(
+                &e,
+                &mut pool,
+                &positions,
+                FactorBasis::Borrow,
+            );
+            assert_eq!(borrow_data.collateral_base, 75_0000000);
+            assert_eq!(borrow_data.collateral_raw, 100_0000000);
+            assert_eq!(borrow_data.liability_base, 26_6666668);
+            assert_eq!(borrow_data.liability_raw, 20_0000000);
+
+            let liq_data = PositionData::calculate_from_positions_with_basis(
+                &e,
+                &mut pool,
+                &positions,
+                FactorBasis::Liquidation,
+            );
+            assert_eq!(liq_data.collateral_base, 80_0000000);
+            assert_eq!(liq_data.collateral_raw, 100_0000000);
+            assert_eq!(liq_data.liability_base, 26_6666668);
+            assert_eq!(liq_data.liability_raw, 20_0000000);
+        });
+    }
+
+    #[test]
+    fn test_calculate_risk() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+        let samwise = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.index = 1;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 45_0000000)],
+            collateral: map![&e, (0, 100_0000000)],
+            supply: map![&e],
+        };
+        let unhealthy_positions = Positions {
+            liabilities: map![&e, (1, 60_0000000)],
+            collateral: map![&e, (0, 100_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+
+            // buffer of 15 against 75 collateral, 3.6 of annual interest against 2.7 of supply yield
+            let risk = PositionData::calculate_risk(&e, &mut pool, &samwise, &positions, 0);
+            assert_eq!(risk.price_drops.len(), 1);
+            assert_eq!(
+                risk.price_drops.get_unchecked(underlying_0.clone()),
+                0_2000000
+            );
+            assert_eq!(risk.days_to_liquidation, 6083);
+
+            // credit extends the buffer
+            let risk =
+                PositionData::calculate_risk(&e, &mut pool, &samwise, &positions, 60_0000000);
+            assert_eq!(
+                risk.price_drops.get_unchecked(underlying_0.clone()),
+                1_0000000
+            );
+            assert_eq!(risk.days_to_liquidation, 30416);
+
+            let risk =
+                PositionData::calculate_risk(&e, &mut pool, &samwise, &unhealthy_positions, 0);
+            assert_eq!(risk.price_drops.get_unchecked(underlying_0.clone()), 0);
+            assert_eq!(risk.days_to_liquidation, 0);
+
+            // liabilities are scaled by the liquidation health factor
+            pool.config.liq_hf_low = 1_1000000;
+            let risk = PositionData::calculate_risk(&e, &mut pool, &samwise, &positions, 0);
+            assert_eq!(
+                risk.price_drops.get_unchecked(underlying_0.clone()),
+                0_1200000
+            );
+            assert_eq!(risk.days_to_liquidation, 2607);
+        });
+    }
+
     #[test]
     fn test_require_healthy() {
         let e = Env::default();
@@ -222,6 +665,40 @@ mod tests {
         assert!(true);
     }
 
+    #[test]
+    fn test_require_healthy_with_credit() {
+        let e = Env::default();
+
+        let position_data = PositionData {
+            collateral_base: 0,
+            collateral_raw: 0,
+            liability_base: 9_1233333,
+            liability_raw: 10_0000000,
+            scalar: 1_0000000,
+        };
+
+        position_data.require_healthy_with_credit(&e, 9_1234567);
+        // no panic
+        assert!(true);
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(10))")]
+    fn test_require_healthy_with_credit_panics() {
+        let e = Env::default();
+
+        let position_data = PositionData {
+            collateral_base: 1_0000000,
+            collateral_raw: 2_0000000,
+            liability_base: 9_1234567,
+            liability_raw: 10_0000000,
+            scalar: 1_0000000,
+        };
+
+        position_data.require_healthy_with_credit(&e, 8_1234567);
+    }
+
     #[test]
     #[should_panic]
     //#[should_panic(expected = "Status(ContractError(10))")]
@@ -0,0 +1,183 @@
+use soroban_sdk::{map, Env, Symbol};
+
+use crate::{
+    constants::{HEARTBEAT_INTERVAL, UTIL_HISTORY_LENGTH},
+    storage::{self, UtilSample},
+};
+
+use super::pool::Pool;
+
+/// Emit a heartbeat event with each reserve's total supply, total liabilities and backstop
+/// credit in underlying tokens, along with the pool status, if a heartbeat has not been emitted
+/// within the last `HEARTBEAT_INTERVAL` ledgers. Every state-changing call emits the heartbeat
+/// before it makes any changes, such that monitors can track the pool's solvency from events.
+///
+/// The totals are not priced, such that the heartbeat never queries the oracle and never causes
+/// the calling action to fail.
+///
+/// Each heartbeat also records a utilization sample for every reserve, keeping the most recent
+/// `UTIL_HISTORY_LENGTH` samples.
+pub fn emit_heartbeat(e: &Env) {
+    let cur_sequence = e.ledger().sequence();
+    if let Some(last_sequence) = storage::get_last_heartbeat(e) {
+        if cur_sequence < last_sequence + HEARTBEAT_INTERVAL {
+            return;
+        }
+    }
+
+    let mut pool = Pool::load(e);
+    let reserve_list = storage::get_res_list(e);
+    let mut totals = map![e];
+    let mut samples = map![e];
+    for res_asset_address in reserve_list.iter() {
+        let reserve = pool.load_reserve(e, &res_asset_address);
+        let util = if reserve.b_supply == 0 {
+            0
+        } else {
+            reserve.utilization()
+        };
+        samples.set(
+            res_asset_address.clone(),
+            UtilSample {
+                timestamp: e.ledger().timestamp(),
+                util,
+                ir_mod: reserve.ir_mod,
+            },
+        );
+        totals.set(
+            res_asset_address.clone(),
+            (
+                pool.to_underlying_down(e, &res_asset_address, reserve.total_supply()),
+                pool.to_underlying_down(e, &res_asset_address, reserve.total_liabilities()),
+                pool.to_underlying_down(e, &res_asset_address, reserve.backstop_credit),
+            ),
+        );
+    }
+
+    storage::set_last_heartbeat(e, &cur_sequence);
+    for (asset, sample) in samples.iter() {
+        let mut history = storage::get_util_history(e, &asset);
+        history.push_back(sample);
+        if history.len() > UTIL_HISTORY_LENGTH {
+            history.pop_front();
+        }
+        storage::set_util_history(e, &asset, &history);
+    }
+    e.events()
+        .publish((Symbol::new(e, "heartbeat"),), (totals, pool.config.status));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{storage::PoolConfig, testutils};
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger, LedgerInfo},
+        vec, Address, IntoVal,
+    };
+
+    fn set_ledger(e: &Env, sequence_number: u32, timestamp: u64) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 1,
+            sequence_number,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+    }
+
+    #[test]
+    fn test_emit_heartbeat() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.backstop_credit = 1_0000000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        set_ledger(&e, 1000, 0);
+        // no prices are set, as the heartbeat does not query the oracle
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 1,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            emit_heartbeat(&e);
+            assert_eq!(storage::get_last_heartbeat(&e), Some(1000));
+            let history = storage::get_util_history(&e, &underlying_0);
+            assert_eq!(history.len(), 1);
+            assert_eq!(history.get_unchecked(0).util, 0_7500000);
+        });
+        let events = e.events().all();
+        let totals = map![
+            &e,
+            (
+                underlying_0.clone(),
+                (100_0000000_i128, 75_0000000_i128, 1_0000000_i128)
+            ),
+            (
+                underlying_1.clone(),
+                (100_0000000_i128, 75_0000000_i128, 0_i128)
+            )
+        ];
+        assert_eq!(
+            vec![&e, events.get_unchecked(events.len() - 1)],
+            vec![
+                &e,
+                (
+                    pool_address.clone(),
+                    (Symbol::new(&e, "heartbeat"),).into_val(&e),
+                    (totals, 1_u32).into_val(&e)
+                )
+            ]
+        );
+
+        // rate limited until the interval passes
+        set_ledger(&e, 1000 + HEARTBEAT_INTERVAL - 1, 0);
+        e.as_contract(&pool_address, || {
+            emit_heartbeat(&e);
+            assert_eq!(storage::get_last_heartbeat(&e), Some(1000));
+            assert_eq!(storage::get_util_history(&e, &underlying_0).len(), 1);
+        });
+
+        set_ledger(&e, 1000 + HEARTBEAT_INTERVAL, 0);
+        e.as_contract(&pool_address, || {
+            emit_heartbeat(&e);
+            assert_eq!(
+                storage::get_last_heartbeat(&e),
+                Some(1000 + HEARTBEAT_INTERVAL)
+            );
+            assert_eq!(storage::get_util_history(&e, &underlying_0).len(), 2);
+        });
+    }
+}
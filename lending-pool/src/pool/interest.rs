@@ -7,25 +7,18 @@ use crate::{
     storage::ReserveConfig,
 };
 
-/// Calculates the loan accrual ratio for the Reserve based on the current utilization and
-/// rate modifier for the reserve.
+/// Calculates the current annual borrow rate for the Reserve based on the current utilization
+/// and rate modifier for the reserve.
 ///
 /// ### Arguments
-/// * `config` - The Reserve config to calculate an accrual for
+/// * `config` - The Reserve config to calculate the rate for
 /// * `cur_util` - The current utilization rate of the reserve (7 decimals)
 /// * `ir_mod` - The current interest rate modifier of the reserve (9 decimals)
-/// * `last_block` - The last block an accrual was performed
 ///
 /// ### Returns
-/// * (i128, i128) - (accrual amount scaled to 9 decimal places, new interest rate modifier scaled to 9 decimal places)
+/// * i128 - The annual borrow rate scaled to 7 decimal places
 #[allow(clippy::zero_prefixed_literal)]
-pub fn calc_accrual(
-    e: &Env,
-    config: &ReserveConfig,
-    cur_util: i128,
-    ir_mod: i128,
-    last_time: u64,
-) -> (i128, i128) {
+pub fn calc_borrow_rate(config: &ReserveConfig, cur_util: i128, ir_mod: i128) -> i128 {
     let cur_ir: i128;
     let target_util: i128 = i128(config.util);
     if cur_util <= target_util {
@@ -66,6 +59,30 @@ pub fn calc_accrual(
             .unwrap_optimized();
         cur_ir = extra_rate + intersection;
     }
+    cur_ir
+}
+
+/// Calculates the loan accrual ratio for the Reserve based on the current utilization and
+/// rate modifier for the reserve.
+///
+/// ### Arguments
+/// * `config` - The Reserve config to calculate an accrual for
+/// * `cur_util` - The current utilization rate of the reserve (7 decimals)
+/// * `ir_mod` - The current interest rate modifier of the reserve (9 decimals)
+/// * `last_block` - The last block an accrual was performed
+///
+/// ### Returns
+/// * (i128, i128) - (accrual amount scaled to 9 decimal places, new interest rate modifier scaled to 9 decimal places)
+#[allow(clippy::zero_prefixed_literal)]
+pub fn calc_accrual(
+    e: &Env,
+    config: &ReserveConfig,
+    cur_util: i128,
+    ir_mod: i128,
+    last_time: u64,
+) -> (i128, i128) {
+    let cur_ir = calc_borrow_rate(config, cur_util, ir_mod);
+    let target_util: i128 = i128(config.util);
 
     // update rate_modifier
     // scale delta blocks and util dif to 9 decimals
@@ -102,17 +119,29 @@ pub fn calc_accrual(
         }
     }
 
-    // calc accrual amount over blocks
-    let time_weight = delta_time_scaled / SECONDS_PER_YEAR;
     (
-        1_000_000_000
-            + time_weight
-                .fixed_mul_ceil(cur_ir * 100, SCALAR_9)
-                .unwrap_optimized(),
+        calc_period_accrual(cur_ir, e.ledger().timestamp() - last_time),
         new_ir_mod,
     )
 }
 
+/// Calculates the loan accrual ratio over a period of time at a fixed annual borrow rate
+///
+/// ### Arguments
+/// * `cur_ir` - The annual borrow rate (7 decimals)
+/// * `delta_time` - The length of the period in seconds
+///
+/// ### Returns
+/// * i128 - The accrual amount scaled to 9 decimal places
+pub fn calc_period_accrual(cur_ir: i128, delta_time: u64) -> i128 {
+    // calc accrual amount over blocks
+    let time_weight = i128(delta_time) * SCALAR_9 / SECONDS_PER_YEAR;
+    1_000_000_000
+        + time_weight
+            .fixed_mul_ceil(cur_ir * 100, SCALAR_9)
+            .unwrap_optimized()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,6 +161,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_000_002_000,
+            oracle_asset: None,
+            isolated: false,
             index: 0,
         };
         let ir_mod: i128 = 1_000_000_000;
@@ -167,6 +198,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_000_002_000,
+            oracle_asset: None,
+            isolated: false,
             index: 0,
         };
         let ir_mod: i128 = 1_000_000_000;
@@ -202,6 +235,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_000_002_000,
+            oracle_asset: None,
+            isolated: false,
             index: 0,
         };
         let ir_mod: i128 = 1_000_000_000;
@@ -237,6 +272,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_000_002_000,
+            oracle_asset: None,
+            isolated: false,
             index: 0,
         };
         let ir_mod: i128 = 9_997_000_000;
@@ -271,6 +308,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_000_002_000,
+            oracle_asset: None,
+            isolated: false,
             index: 0,
         };
         let ir_mod: i128 = 0_150_000_000;
@@ -305,6 +344,8 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_000_002_000,
+            oracle_asset: None,
+            isolated: false,
             index: 0,
         };
         let ir_mod: i128 = 0_100_000_000;
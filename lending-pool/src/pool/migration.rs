@@ -0,0 +1,302 @@
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+use crate::{
+    auctions::{AuctionData, AuctionMode},
+    constants::{DEFAULT_LIQ_HF_HIGH, DEFAULT_LIQ_HF_LOW, STORAGE_VERSION},
+    errors::PoolError,
+    storage::{self, PoolConfig, ReserveConfig},
+};
+
+/// Migrate the pool's storage from the layout written by a previous version of the pool
+/// to the current layout. Must be run once after the pool's wasm is upgraded, before the pool
+/// is used.
+///
+/// The backstop's bad debt and interest auctions are migrated automatically. User liquidation
+/// auctions are keyed by user, so every user with an in-flight liquidation auction must be
+/// included in `auction_users`.
+///
+/// Returns the storage version the pool was migrated from
+///
+/// ### Arguments
+/// * `auction_users` - The users with an in-flight liquidation auction
+///
+/// ### Panics
+/// If the pool's storage is already at the current version, or if a user in `auction_users`
+/// does not have a liquidation auction
+pub fn execute_migrate(e: &Env, auction_users: &Vec<Address>) -> u32 {
+    let version = storage::get_storage_version(e);
+    if version >= STORAGE_VERSION {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    if version == 0 {
+        migrate_v0(e, auction_users);
+    }
+
+    storage::set_storage_version(e, &STORAGE_VERSION);
+    version
+}
+
+/// Migrate from version 0, where the pool config did not have configurable liquidation health
+/// factors, reserve configs did not have oracle assets or isolation, and auctions did not have
+/// a start timestamp or valuation
+fn migrate_v0(e: &Env, auction_users: &Vec<Address>) {
+    let pool_config = storage::get_pool_config_v0(e);
+    storage::set_pool_config(
+        e,
+        &PoolConfig {
+            oracle: pool_config.oracle,
+            bstop_rate: pool_config.bstop_rate,
+            status: pool_config.status,
+            liq_hf_low: DEFAULT_LIQ_HF_LOW,
+            liq_hf_high: DEFAULT_LIQ_HF_HIGH,
+        },
+    );
+
+    for asset in storage::get_res_list(e).iter() {
+        let config = storage::get_res_config_v0(e, &asset);
+        storage::set_res_config(
+            e,
+            &asset,
+            &ReserveConfig {
+                index: config.index,
+                decimals: config.decimals,
+                c_factor: config.c_factor,
+                l_factor: config.l_factor,
+                util: config.util,
+                max_util: config.max_util,
+                r_one: config.r_one,
+                r_two: config.r_two,
+                r_three: config.r_three,
+                reactivity: config.reactivity,
+                oracle_asset: None,
+                isolated: false,
+            },
+        );
+    }
+
+    let backstop = storage::get_backstop(e);
+    for auction_type in [1, 2] {
+        if storage::has_auction(e, &auction_type, &backstop) {
+            migrate_auction_v0(e, auction_type, &backstop);
+        }
+    }
+    for user in auction_users.iter() {
+        if !storage::has_auction(e, &0, &user) {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+        migrate_auction_v0(e, 0, &user);
+    }
+}
+
+/// Migrate a version 0 auction. Auctions created by version 0 were valued at raw oracle prices.
+fn migrate_auction_v0(e: &Env, auction_type: u32, user: &Address) {
+    let auction_data = storage::get_auction_v0(e, &auction_type, user);
+    storage::set_auction(
+        e,
+        &auction_type,
+        user,
+        &AuctionData {
+            bid: auction_data.bid,
+            lot: auction_data.lot,
+            block: auction_data.block,
+            timestamp: e.ledger().timestamp(),
+            mode: AuctionMode::Sequence as u32,
+            valuation: 0,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::{AuctionDataV0, AuctionKey, PoolConfigV0, PoolDataKey, ReserveConfigV0};
+
+    use super::*;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Address, Symbol,
+    };
+
+    fn reserve_config_v0(index: u32) -> ReserveConfigV0 {
+        ReserveConfigV0 {
+            index,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7000000,
+            util: 0_7500000,
+            max_util: 0_9500000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 0_000_002_000,
+        }
+    }
+
+    fn write_pool_v0(e: &Env, oracle: &Address, backstop: &Address) {
+        e.storage().persistent().set(
+            &Symbol::new(e, "PoolConfig"),
+            &PoolConfigV0 {
+                oracle: oracle.clone(),
+                bstop_rate: 0_200_000_000,
+                status: 2,
+            },
+        );
+        storage::set_backstop(e, backstop);
+    }
+
+    fn write_auction_v0(e: &Env, auction_type: u32, user: &Address, auction_data: &AuctionDataV0) {
+        e.storage().temporary().set(
+            &PoolDataKey::Auction(AuctionKey {
+                user: user.clone(),
+                auct_type: auction_type,
+            }),
+            auction_data,
+        );
+    }
+
+    #[test]
+    fn test_migrate_v0() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let oracle = Address::random(&e);
+        let backstop = Address::random(&e);
+        let underlying_0 = Address::random(&e);
+        let underlying_1 = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            // write the version 0 layout
+            write_pool_v0(&e, &oracle, &backstop);
+            storage::push_res_list(&e, &underlying_0);
+            storage::push_res_list(&e, &underlying_1);
+            e.storage().persistent().set(
+                &storage::PoolDataKey::ResConfig(underlying_0.clone()),
+                &reserve_config_v0(0),
+            );
+            e.storage().persistent().set(
+                &storage::PoolDataKey::ResConfig(underlying_1.clone()),
+                &reserve_config_v0(1),
+            );
+
+            assert_eq!(execute_migrate(&e, &vec![&e]), 0);
+            assert_eq!(storage::get_storage_version(&e), STORAGE_VERSION);
+
+            let pool_config = storage::get_pool_config(&e);
+            assert_eq!(pool_config.oracle, oracle);
+            assert_eq!(pool_config.bstop_rate, 0_200_000_000);
+            assert_eq!(pool_config.status, 2);
+            assert_eq!(pool_config.liq_hf_low, DEFAULT_LIQ_HF_LOW);
+            assert_eq!(pool_config.liq_hf_high, DEFAULT_LIQ_HF_HIGH);
+
+            let config_0 = storage::get_res_config(&e, &underlying_0);
+            assert_eq!(config_0.index, 0);
+            assert_eq!(config_0.c_factor, 0_7500000);
+            assert_eq!(config_0.l_factor, 0_7000000);
+            assert_eq!(config_0.reactivity, 0_000_002_000);
+            assert!(config_0.oracle_asset.is_none());
+            assert!(!config_0.isolated);
+
+            let config_1 = storage::get_res_config(&e, &underlying_1);
+            assert_eq!(config_1.index, 1);
+            assert_eq!(config_1.max_util, 0_9500000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_migrate_current_version() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_storage_version(&e, &STORAGE_VERSION);
+            execute_migrate(&e, &vec![&e]);
+        });
+    }
+
+    #[test]
+    fn test_migrate_v0_auctions() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool = Address::random(&e);
+        let oracle = Address::random(&e);
+        let backstop = Address::random(&e);
+        let samwise = Address::random(&e);
+        let underlying_0 = Address::random(&e);
+        let underlying_1 = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            write_pool_v0(&e, &oracle, &backstop);
+            write_auction_v0(
+                &e,
+                0,
+                &samwise,
+                &AuctionDataV0 {
+                    bid: map![&e, (underlying_0.clone(), 100_0000000)],
+                    lot: map![&e, (underlying_1.clone(), 150_0000000)],
+                    block: 90,
+                },
+            );
+            write_auction_v0(
+                &e,
+                2,
+                &backstop,
+                &AuctionDataV0 {
+                    bid: map![&e, (backstop.clone(), 10_0000000)],
+                    lot: map![&e, (underlying_0.clone(), 12_0000000)],
+                    block: 95,
+                },
+            );
+
+            execute_migrate(&e, &vec![&e, samwise.clone()]);
+
+            let liquidation = storage::get_auction(&e, &0, &samwise);
+            assert_eq!(
+                liquidation.bid.get_unchecked(underlying_0.clone()),
+                100_0000000
+            );
+            assert_eq!(
+                liquidation.lot.get_unchecked(underlying_1.clone()),
+                150_0000000
+            );
+            assert_eq!(liquidation.block, 90);
+            assert_eq!(liquidation.timestamp, 12345);
+            assert_eq!(liquidation.mode, 0);
+            assert_eq!(liquidation.valuation, 0);
+
+            let interest = storage::get_auction(&e, &2, &backstop);
+            assert_eq!(interest.bid.get_unchecked(backstop.clone()), 10_0000000);
+            assert_eq!(interest.lot.get_unchecked(underlying_0.clone()), 12_0000000);
+            assert_eq!(interest.block, 95);
+
+            assert!(!storage::has_auction(&e, &1, &backstop));
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_migrate_v0_user_without_auction() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+        let oracle = Address::random(&e);
+        let backstop = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            write_pool_v0(&e, &oracle, &backstop);
+
+            execute_migrate(&e, &vec![&e, samwise]);
+        });
+    }
+}
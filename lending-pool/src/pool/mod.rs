@@ -1,20 +1,87 @@
 mod actions;
 pub use actions::Request;
 
+mod allowlist;
+pub use allowlist::{require_allowed, set_allowed, set_denylist};
+
 mod bad_debt;
 pub use bad_debt::{burn_backstop_bad_debt, transfer_bad_debt_to_backstop};
 
+mod bootstrap;
+pub use bootstrap::{
+    execute_cancel_pol_withdrawal, execute_queue_pol_withdrawal, execute_supply_pol,
+    execute_withdraw_pol,
+};
+
+mod borrow_fee;
+pub use borrow_fee::set_reserve_borrow_fee;
+
+mod collateral;
+pub use collateral::execute_set_collateral;
+
+mod collateral_swap;
+pub use collateral_swap::execute_collateral_swap;
+
 mod config;
 pub use config::{
-    execute_initialize, execute_update_pool, execute_update_reserve, initialize_reserve,
+    execute_initialize, execute_update_pool, execute_update_reserve, execute_update_reserves,
+    initialize_reserve, set_backstop_version, set_emissions_by_target_apr, set_isolation_config,
+    set_liquidation_thresholds, set_max_leverage, set_min_debt, set_reserve_liq_threshold,
     update_pool_emissions,
 };
 
+mod cosign;
+pub use cosign::{
+    execute_approve_action, execute_revoke_approval, set_initial_co_signer, AdminAction,
+};
+
+mod delegation;
+pub use delegation::{execute_submit_delegated, set_borrow_allowance};
+
+mod e_mode;
+pub use e_mode::{execute_set_user_e_mode, set_e_mode_category};
+
+mod exchange_rate;
+pub use exchange_rate::set_reserve_exchange_rate_adapter;
+
+mod exposure;
+pub use exposure::set_reserve_exposure_cap;
+
+mod freeze;
+pub use freeze::{execute_freeze_interest, execute_unfreeze_interest};
+
 mod health_factor;
-pub use health_factor::PositionData;
+pub use health_factor::{FactorBasis, PositionData, PositionRisk};
+
+mod heartbeat;
+pub use heartbeat::emit_heartbeat;
 
 mod interest;
 
+#[cfg(test)]
+mod interest_vectors;
+
+mod migration;
+pub use migration::execute_migrate;
+
+mod oracle_change;
+pub use oracle_change::{execute_cancel_oracle, execute_check_oracle, execute_set_oracle};
+
+mod pause;
+pub use pause::set_reserve_pause_flags;
+
+mod price_clamp;
+pub use price_clamp::set_price_clamp_config;
+
+mod rates;
+pub use rates::{calculate_reserve_rates, quote_borrow, BorrowQuote, ReserveRates};
+
+mod risk_manifest;
+pub use risk_manifest::{build_risk_manifest, ReserveRiskParams, RiskManifest};
+
+mod snapshot;
+pub use snapshot::{build_position_snapshot, record_liquidation, PositionSnapshot};
+
 mod submit;
 
 pub use submit::execute_submit;
@@ -23,11 +90,34 @@ pub use submit::execute_submit;
 mod pool;
 pub use pool::Pool;
 
+mod redeem;
+pub use redeem::execute_redeem_frozen;
+
 mod reserve;
 pub use reserve::Reserve;
 
 mod user;
 pub use user::{Positions, User};
 
+mod util_report;
+pub use util_report::{build_util_report, UtilReport};
+
+mod withdrawal;
+pub use withdrawal::{
+    calc_max_withdrawal, execute_cancel_withdrawal, execute_queue_withdrawal,
+    set_reserve_large_withdrawal, set_reserve_withdraw_util,
+};
+
 mod status;
-pub use status::{execute_update_pool_status, set_pool_status};
+pub use status::{
+    execute_update_pool_status, execute_wind_down, get_backstop_shortfall, set_min_backstop,
+    set_pool_status,
+};
+
+mod sweep;
+pub use sweep::{
+    execute_sweep_interest, get_unswept_interest, set_direct_interest, set_sweep_config,
+};
+
+mod trusted;
+pub use trusted::{get_trusted_credit, remove_trusted_borrower, set_trusted_borrower};
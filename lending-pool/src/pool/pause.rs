@@ -0,0 +1,125 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{
+    constants::{PAUSE_BORROW, PAUSE_SUPPLY, PAUSE_WITHDRAW},
+    errors::PoolError,
+    storage,
+};
+
+/// Set the actions paused for a single reserve, independent of the pool's status. Repayments
+/// and liquidations are never paused, such that users can always reduce their risk. Setting the
+/// flags to zero unpauses the reserve.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `flags` - The bit flags of the paused actions (`PAUSE_SUPPLY`, `PAUSE_BORROW`, `PAUSE_WITHDRAW`)
+///
+/// ### Panics
+/// If the reserve does not exist or the flags contain an unknown action
+pub fn set_reserve_pause_flags(e: &Env, asset: &Address, flags: u32) {
+    if !storage::has_res(e, asset) || flags & !(PAUSE_SUPPLY | PAUSE_BORROW | PAUSE_WITHDRAW) != 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if flags == 0 {
+        storage::del_res_pause_flags(e, asset);
+    } else {
+        storage::set_res_pause_flags(e, asset, &flags);
+    }
+}
+
+/// Require that the action is not paused for the reserve, or panic.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `action_type` - The type of action being performed
+///
+/// ### Panics
+/// If the action is paused for the reserve
+pub fn require_reserve_action_allowed(e: &Env, asset: &Address, action_type: u32) {
+    let pause_flag = match action_type {
+        0 | 2 | 14 => PAUSE_SUPPLY,
+        4 => PAUSE_BORROW,
+        1 | 3 | 12 | 15 => PAUSE_WITHDRAW,
+        _ => return,
+    };
+    if storage::get_res_pause_flags(e, asset) & pause_flag != 0 {
+        panic_with_error!(e, PoolError::InvalidPoolStatus);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testutils;
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_set_reserve_pause_flags() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_reserve_pause_flags(&e, &underlying, PAUSE_BORROW | PAUSE_WITHDRAW);
+            assert_eq!(
+                storage::get_res_pause_flags(&e, &underlying),
+                PAUSE_BORROW | PAUSE_WITHDRAW
+            );
+
+            // only the paused actions are rejected
+            require_reserve_action_allowed(&e, &underlying, 0);
+            require_reserve_action_allowed(&e, &underlying, 2);
+            require_reserve_action_allowed(&e, &underlying, 5);
+
+            set_reserve_pause_flags(&e, &underlying, 0);
+            assert_eq!(storage::get_res_pause_flags(&e, &underlying), 0);
+            require_reserve_action_allowed(&e, &underlying, 4);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(11)")]
+    fn test_require_reserve_action_allowed_paused() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_reserve_pause_flags(&e, &underlying, PAUSE_WITHDRAW);
+            require_reserve_action_allowed(&e, &underlying, 12);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_reserve_pause_flags_unknown_flag() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            set_reserve_pause_flags(&e, &underlying, 1 << 3);
+        });
+    }
+}
@@ -1,20 +1,26 @@
+use fixed_point_math::FixedPoint;
 use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, Map, Vec};
 
 use oracle::OracleClient;
 
 use crate::{
+    constants::SCALAR_9,
     errors::PoolError,
     storage::{self, PoolConfig},
 };
 
-use super::reserve::Reserve;
+use super::{exchange_rate::load_exchange_rate, price_clamp::clamp_price, reserve::Reserve};
 
+/// The pool's state for a single invocation. Reserves and prices are cached once loaded, such
+/// that the health checks, auction math and other valuations of a submit or fill share a single
+/// oracle read per asset.
 pub struct Pool {
     pub config: PoolConfig,
     pub reserves: Map<Address, Reserve>,
     reserves_to_store: Vec<Address>,
     price_decimals: Option<u32>,
     prices: Map<Address, i128>,
+    exchange_rates: Map<Address, i128>,
 }
 
 impl Pool {
@@ -27,6 +33,7 @@ impl Pool {
             reserves_to_store: vec![e],
             price_decimals: None,
             prices: map![e],
+            exchange_rates: map![e],
         }
     }
 
@@ -35,11 +42,12 @@ impl Pool {
     ///
     /// ### Arguments
     /// * asset - The address of the underlying asset
-    pub fn load_reserve(&self, e: &Env, asset: &Address) -> Reserve {
+    pub fn load_reserve(&mut self, e: &Env, asset: &Address) -> Reserve {
         if let Some(reserve) = self.reserves.get(asset.clone()) {
             return reserve;
         }
-        Reserve::load(e, &self.config, asset)
+        let exchange_rate = self.load_exchange_rate(e, asset);
+        Reserve::load(e, &self.config, asset, exchange_rate)
     }
 
     /// Cache the updated reserve in the pool.
@@ -67,9 +75,10 @@ impl Pool {
     /// ### Arguments
     /// * `action_type` - The type of action being performed
     pub fn require_action_allowed(&self, e: &Env, action_type: u32) {
-        // disable borrowing for any non-active pool and disable supplying for any frozen pool
+        // disable borrowing for any non-active pool and disable supplying or enabling collateral
+        // for any frozen pool
         if (self.config.status > 0 && action_type == 4)
-            || (self.config.status > 1 && (action_type == 2 || action_type == 0))
+            || (self.config.status > 1 && matches!(action_type, 0 | 2 | 14))
         {
             panic_with_error!(e, PoolError::InvalidPoolStatus);
         }
@@ -89,6 +98,10 @@ impl Pool {
 
     /// Load a price from the Pool's oracle. Returns a cached version if one already exists.
     ///
+    /// If the asset has an exchange rate adapter, the price is returned per share unit. If the
+    /// asset is a reserve with an oracle asset, the price of the oracle asset is used. If a price
+    /// clamp is set, the price is clamped to the band around the asset's last observation.
+    ///
     /// ### Arguments
     /// * asset - The address of the underlying asset
     ///
@@ -99,20 +112,140 @@ impl Pool {
             return price;
         }
         let oracle_client = OracleClient::new(e, &self.config.oracle);
-        let price_data = oracle_client.lastprice(asset).unwrap_optimized();
+        let price_data = oracle_client
+            .lastprice(&load_oracle_asset(e, asset))
+            .unwrap_optimized();
         if price_data.timestamp + 24 * 60 * 60 < e.ledger().timestamp() {
             panic_with_error!(e, PoolError::StalePrice);
         }
-        self.prices.set(asset.clone(), price_data.price);
-        price_data.price
+        let price = price_data
+            .price
+            .fixed_mul_floor(self.load_exchange_rate(e, asset), SCALAR_9)
+            .unwrap_optimized();
+        let price = clamp_price(e, asset, price);
+        self.prices.set(asset.clone(), price);
+        price
+    }
+
+    /// Load a price from the Pool's oracle without panicking. Returns a cached version if one
+    /// already exists.
+    ///
+    /// Returns None if the oracle fails to return a price or the price is stale.
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    pub fn try_load_price(&mut self, e: &Env, asset: &Address) -> Option<i128> {
+        if let Some(price) = self.prices.get(asset.clone()) {
+            return Some(price);
+        }
+        let oracle_client = OracleClient::new(e, &self.config.oracle);
+        let price_data = match oracle_client.try_lastprice(&load_oracle_asset(e, asset)) {
+            Ok(Ok(Some(price_data))) => price_data,
+            _ => return None,
+        };
+        if price_data.timestamp + 24 * 60 * 60 < e.ledger().timestamp() {
+            return None;
+        }
+        let price = price_data
+            .price
+            .fixed_mul_floor(self.load_exchange_rate(e, asset), SCALAR_9)
+            .unwrap_optimized();
+        let price = clamp_price(e, asset, price);
+        self.prices.set(asset.clone(), price);
+        Some(price)
+    }
+
+    /// Load the exchange rate from share units to underlying tokens for an asset, expressed in 9
+    /// decimals. Returns a cached version if one already exists.
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    pub fn load_exchange_rate(&mut self, e: &Env, asset: &Address) -> i128 {
+        if let Some(rate) = self.exchange_rates.get(asset.clone()) {
+            return rate;
+        }
+        let rate = load_exchange_rate(e, asset);
+        self.exchange_rates.set(asset.clone(), rate);
+        rate
+    }
+
+    /// Convert an amount of underlying tokens to share units - rounding down
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    /// * amount - The amount of underlying tokens
+    pub fn to_shares_down(&mut self, e: &Env, asset: &Address, amount: i128) -> i128 {
+        let rate = self.load_exchange_rate(e, asset);
+        if rate == SCALAR_9 {
+            return amount;
+        }
+        amount.fixed_div_floor(rate, SCALAR_9).unwrap_optimized()
+    }
+
+    /// Convert an amount of underlying tokens to share units - rounding up
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    /// * amount - The amount of underlying tokens
+    pub fn to_shares_up(&mut self, e: &Env, asset: &Address, amount: i128) -> i128 {
+        let rate = self.load_exchange_rate(e, asset);
+        if rate == SCALAR_9 {
+            return amount;
+        }
+        amount.fixed_div_ceil(rate, SCALAR_9).unwrap_optimized()
+    }
+
+    /// Convert an amount of share units to underlying tokens - rounding down
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    /// * shares - The amount of share units
+    pub fn to_underlying_down(&mut self, e: &Env, asset: &Address, shares: i128) -> i128 {
+        let rate = self.load_exchange_rate(e, asset);
+        if rate == SCALAR_9 {
+            return shares;
+        }
+        shares.fixed_mul_floor(rate, SCALAR_9).unwrap_optimized()
+    }
+
+    /// Convert an amount of share units to underlying tokens - rounding up
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    /// * shares - The amount of share units
+    pub fn to_underlying_up(&mut self, e: &Env, asset: &Address, shares: i128) -> i128 {
+        let rate = self.load_exchange_rate(e, asset);
+        if rate == SCALAR_9 {
+            return shares;
+        }
+        shares.fixed_mul_ceil(rate, SCALAR_9).unwrap_optimized()
     }
 }
 
+/// Load the asset the oracle prices an asset as. A reserve can be priced as a different
+/// asset, such as a bridged token priced as its canonical asset. Otherwise, the asset is
+/// priced as itself.
+///
+/// ### Arguments
+/// * asset - The address of the underlying asset
+pub fn load_oracle_asset(e: &Env, asset: &Address) -> Address {
+    if storage::has_res(e, asset) {
+        if let Some(oracle_asset) = storage::get_res_config(e, asset).oracle_asset {
+            return oracle_asset;
+        }
+    }
+    asset.clone()
+}
+
 #[cfg(test)]
 mod tests {
     use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
 
-    use crate::{storage::ReserveData, testutils};
+    use crate::{
+        pool::{PositionData, Positions},
+        storage::ReserveData,
+        testutils,
+    };
 
     use super::*;
 
@@ -143,6 +276,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -207,6 +342,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -262,6 +399,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 1,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -281,6 +420,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -302,6 +443,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 2,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -323,6 +466,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 2,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -342,6 +487,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 2,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -365,6 +512,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -389,6 +538,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -407,6 +558,130 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_load_price_with_oracle_asset() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+        let canonical_asset = Address::random(&e);
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.oracle_asset = Some(canonical_asset.clone());
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        oracle_client.set_price(&canonical_asset, &1_0000000);
+        oracle_client.set_price(&underlying, &2_0000000);
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+
+            assert_eq!(pool.load_price(&e, &underlying), 1_0000000);
+            assert_eq!(pool.try_load_price(&e, &canonical_asset), Some(1_0000000));
+            assert_eq!(load_oracle_asset(&e, &underlying), canonical_asset);
+            assert_eq!(load_oracle_asset(&e, &canonical_asset), canonical_asset);
+        });
+    }
+
+    #[test]
+    fn test_price_cache_saves_budget_six_reserves() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // creating reserves exhausts the budget
+
+        let bombadil = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let mut collateral = map![&e];
+        let mut liabilities = map![&e];
+        for i in 0..6 {
+            let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+            let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+            testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+            oracle_client.set_price(&underlying, &(i128::from(i + 1) * 1_0000000));
+            collateral.set(i, 10_0000000);
+            liabilities.set(i, 1_0000000);
+        }
+        let positions = Positions {
+            collateral,
+            liabilities,
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            for asset in storage::get_res_list(&e).iter() {
+                let reserve = pool.load_reserve(&e, &asset);
+                pool.cache_reserve(reserve, false);
+            }
+
+            // the first valuation reads each price from the oracle
+            e.budget().reset_unlimited();
+            let cold = PositionData::calculate_from_positions(&e, &mut pool, &positions);
+            let cold_cost = e.budget().cpu_instruction_cost();
+
+            // later valuations in the same invocation reuse the cached prices
+            e.budget().reset_unlimited();
+            let warm = PositionData::calculate_from_positions(&e, &mut pool, &positions);
+            let warm_cost = e.budget().cpu_instruction_cost();
+
+            assert_eq!(warm.collateral_base, cold.collateral_base);
+            assert_eq!(warm.liability_base, cold.liability_base);
+            assert!(warm_cost < cold_cost / 2);
+        });
+    }
+
+    #[test]
+    fn test_load_price_with_exchange_rate() {
+        let e = Env::default();
+
+        let pool = Address::random(&e);
+        let asset = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        oracle_client.set_price(&asset, &1_0000000);
+        let (adapter, adapter_client) = testutils::create_mock_exchange_rate(&e);
+        adapter_client.set_rate(&1_100_000_000);
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_res_adapter(&e, &asset, &adapter);
+            let mut pool = Pool::load(&e);
+
+            let price = pool.load_price(&e, &asset);
+            assert_eq!(price, 1_1000000);
+
+            assert_eq!(pool.to_shares_down(&e, &asset, 11_0000000), 10_0000000);
+            assert_eq!(pool.to_shares_down(&e, &asset, 1_0000000), 0_9090909);
+            assert_eq!(pool.to_underlying_down(&e, &asset, 0_9090909), 0_9999999);
+            assert_eq!(pool.to_underlying_up(&e, &asset, 0_9090909), 1_0000000);
+            assert_eq!(pool.to_shares_up(&e, &asset, 1_0000000), 0_9090910);
+            assert_eq!(pool.to_underlying_down(&e, &asset, 0_9090910), 1_0000001);
+        });
+    }
+
     #[test]
     #[should_panic]
     fn test_load_price_panics_if_stale() {
@@ -431,6 +706,8 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
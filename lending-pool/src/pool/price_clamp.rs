@@ -0,0 +1,159 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::SCALAR_7,
+    errors::PoolError,
+    storage::{self, PriceClampConfig, PriceObservation},
+};
+
+/// Set the band the price used to value an asset can move within each interval. A config
+/// with a max change of zero removes the clamp.
+///
+/// ### Arguments
+/// * `config` - The price clamp config
+///
+/// ### Panics
+/// If the max change is over 100% or the interval is zero
+pub fn set_price_clamp_config(e: &Env, config: &PriceClampConfig) {
+    if config.max_change == 0 {
+        storage::del_price_clamp_config(e);
+        return;
+    }
+    if config.max_change > 1_0000000 || config.interval == 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_price_clamp_config(e, config);
+}
+
+/// Clamp an asset's price to the band around its last observation. The price can move by the
+/// max change for each interval elapsed since the observation, and by at least the max change.
+///
+/// Once an interval has elapsed, the clamped and raw prices are recorded as the asset's new
+/// observation. Returns the clamped price, or the price if no clamp is set.
+///
+/// ### Arguments
+/// * `asset` - The address of the asset
+/// * `price` - The price of the asset reported by the oracle
+pub fn clamp_price(e: &Env, asset: &Address, price: i128) -> i128 {
+    let config = match storage::get_price_clamp_config(e) {
+        Some(config) => config,
+        None => return price,
+    };
+    let now = e.ledger().timestamp();
+    let observation = match storage::get_price_observation(e, asset) {
+        Some(observation) => observation,
+        None => {
+            record_observation(e, asset, price, price, now);
+            return price;
+        }
+    };
+
+    let intervals = (now - observation.timestamp) / config.interval;
+    let max_change = i128(config.max_change) * i128(intervals.max(1));
+    let band = observation
+        .price
+        .fixed_mul_ceil(max_change, SCALAR_7)
+        .unwrap_optimized();
+    let clamped = price
+        .min(observation.price + band)
+        .max((observation.price - band).max(0));
+
+    if intervals > 0 {
+        record_observation(e, asset, clamped, price, now);
+    }
+    clamped
+}
+
+fn record_observation(e: &Env, asset: &Address, price: i128, raw_price: i128, timestamp: u64) {
+    storage::set_price_observation(
+        e,
+        asset,
+        &PriceObservation {
+            price,
+            raw_price,
+            timestamp,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutils::set_timestamp;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_clamp_price() {
+        let e = Env::default();
+        set_timestamp(&e, 1000);
+
+        let pool = Address::random(&e);
+        let asset = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            // prices are unclamped without a config
+            assert_eq!(clamp_price(&e, &asset, 1_0000000), 1_0000000);
+            assert!(storage::get_price_observation(&e, &asset).is_none());
+
+            set_price_clamp_config(
+                &e,
+                &PriceClampConfig {
+                    max_change: 0_1000000,
+                    interval: 300,
+                },
+            );
+            // the first price read is recorded as is
+            assert_eq!(clamp_price(&e, &asset, 1_0000000), 1_0000000);
+
+            // a spike within the first interval is clamped to the band and not recorded
+            assert_eq!(clamp_price(&e, &asset, 2_0000000), 1_1000000);
+            assert_eq!(clamp_price(&e, &asset, 0_5000000), 0_9000000);
+            let observation = storage::get_price_observation(&e, &asset).unwrap();
+            assert_eq!(observation.price, 1_0000000);
+            assert_eq!(observation.timestamp, 1000);
+        });
+
+        // the band widens with each elapsed interval
+        set_timestamp(&e, 1000 + 2 * 300);
+        e.as_contract(&pool, || {
+            assert_eq!(clamp_price(&e, &asset, 2_0000000), 1_2000000);
+            let observation = storage::get_price_observation(&e, &asset).unwrap();
+            assert_eq!(observation.price, 1_2000000);
+            assert_eq!(observation.raw_price, 2_0000000);
+            assert_eq!(observation.timestamp, 1600);
+
+            // prices within the band are not clamped
+            assert_eq!(clamp_price(&e, &asset, 1_1500000), 1_1500000);
+
+            // a max change of zero removes the clamp
+            set_price_clamp_config(
+                &e,
+                &PriceClampConfig {
+                    max_change: 0,
+                    interval: 0,
+                },
+            );
+            assert_eq!(clamp_price(&e, &asset, 2_0000000), 2_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_price_clamp_config_zero_interval() {
+        let e = Env::default();
+        let pool = Address::random(&e);
+
+        e.as_contract(&pool, || {
+            set_price_clamp_config(
+                &e,
+                &PriceClampConfig {
+                    max_change: 0_1000000,
+                    interval: 0,
+                },
+            );
+        });
+    }
+}
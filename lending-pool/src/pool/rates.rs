@@ -0,0 +1,274 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{contracttype, panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{SCALAR_7, SCALAR_9, SECONDS_PER_DAY, SECONDS_PER_YEAR},
+    errors::PoolError,
+    storage,
+};
+
+use super::{
+    borrow_fee::calc_borrow_shares,
+    interest::{calc_borrow_rate, calc_period_accrual},
+    pool::Pool,
+};
+
+/// The current interest rates of a reserve
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveRates {
+    pub borrow_apr: i128,     // the annual borrow rate, expressed in 7 decimals
+    pub supply_apr: i128, // the annual supply rate after the backstop take, expressed in 7 decimals
+    pub borrow_apy: i128, // the borrow rate compounded daily over a year, expressed in 7 decimals
+    pub supply_apy: i128, // the supply rate compounded daily over a year, expressed in 7 decimals
+    pub borrow_per_sec: i128, // the borrow rate per second, expressed in 18 decimals
+    pub supply_per_sec: i128, // the supply rate per second, expressed in 18 decimals
+}
+
+/// Calculate the current interest rates of a reserve. The rates assume the reserve's
+/// utilization and rate modifier remain unchanged.
+///
+/// Yields compound the daily accrual the pool would apply to the reserve's d_rate, such that
+/// the quoted APY matches the reserve's interest accrual for actions spaced a day apart.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If the reserve does not exist
+pub fn calculate_reserve_rates(e: &Env, asset: &Address) -> ReserveRates {
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset);
+    let reserve_config = storage::get_res_config(e, asset);
+
+    let cur_util = if reserve.b_supply == 0 {
+        0
+    } else {
+        reserve.utilization()
+    };
+    let borrow_apr = calc_borrow_rate(&reserve_config, cur_util, reserve.ir_mod);
+    let supply_take = SCALAR_9 - i128(pool.config.bstop_rate);
+    let supply_apr = borrow_apr
+        .fixed_mul_floor(cur_util, SCALAR_7)
+        .unwrap_optimized()
+        .fixed_mul_floor(supply_take, SCALAR_9)
+        .unwrap_optimized();
+
+    // the pool accrues interest to suppliers from the interest paid by borrowers
+    let borrow_accrual = calc_period_accrual(borrow_apr, SECONDS_PER_DAY);
+    let supply_accrual = SCALAR_9
+        + (borrow_accrual - SCALAR_9)
+            .fixed_mul_floor(cur_util, SCALAR_7)
+            .unwrap_optimized()
+            .fixed_mul_floor(supply_take, SCALAR_9)
+            .unwrap_optimized();
+
+    ReserveRates {
+        borrow_apr,
+        supply_apr,
+        borrow_apy: calc_annual_yield(borrow_accrual),
+        supply_apy: calc_annual_yield(supply_accrual),
+        borrow_per_sec: calc_per_sec_rate(borrow_apr),
+        supply_per_sec: calc_per_sec_rate(supply_apr),
+    }
+}
+
+/// The impact of a borrow on a reserve's utilization and borrow rate
+#[derive(Clone)]
+#[contracttype]
+pub struct BorrowQuote {
+    pub util: i128,             // the current utilization, expressed in 7 decimals
+    pub borrow_apr: i128,       // the current annual borrow rate, expressed in 7 decimals
+    pub new_util: i128,         // the utilization after the borrow, expressed in 7 decimals
+    pub new_borrow_apr: i128,   // the annual borrow rate after the borrow, expressed in 7 decimals
+    pub exceeds_max_util: bool, // true if the borrow would exceed the reserve's max utilization
+    pub fee: i128,              // the origination fee added to the debt, in underlying tokens
+}
+
+/// Quote the utilization, annual borrow rate and origination fee of a reserve after a borrow,
+/// such that users can see the impact of their own borrow before submitting it. The quote
+/// assumes the reserve's rate modifier remains unchanged.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `amount` - The amount of underlying tokens to borrow
+///
+/// ### Panics
+/// If the reserve does not exist or the amount is negative
+pub fn quote_borrow(e: &Env, asset: &Address, amount: i128) -> BorrowQuote {
+    if amount < 0 {
+        panic_with_error!(e, PoolError::NegativeAmount);
+    }
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset);
+    let reserve_config = storage::get_res_config(e, asset);
+
+    // the borrow and its fee are calculated in share units, as they are when borrowing
+    let (shares, fee) = calc_borrow_shares(e, &mut pool, asset, amount);
+    let total_supply = reserve.total_supply();
+    let (util, new_util) = if total_supply == 0 {
+        (0, 0)
+    } else {
+        // the fee is added to the reserve's liabilities and earned by its suppliers
+        let new_util = (reserve.total_liabilities() + shares + fee)
+            .fixed_div_floor(total_supply + fee, SCALAR_7)
+            .unwrap_optimized();
+        (reserve.utilization(), new_util)
+    };
+
+    BorrowQuote {
+        util,
+        borrow_apr: calc_borrow_rate(&reserve_config, util, reserve.ir_mod),
+        new_util,
+        new_borrow_apr: calc_borrow_rate(&reserve_config, new_util, reserve.ir_mod),
+        exceeds_max_util: new_util > i128(reserve.max_util),
+        fee: pool.to_underlying_up(e, asset, fee),
+    }
+}
+
+/// Compound a daily accrual ratio (9 decimals) over a year, returning the yield in 7 decimals
+fn calc_annual_yield(daily_accrual: i128) -> i128 {
+    let periods = SECONDS_PER_YEAR / i128(SECONDS_PER_DAY);
+    let mut rate = SCALAR_9;
+    for _ in 0..periods {
+        rate = daily_accrual
+            .fixed_mul_ceil(rate, SCALAR_9)
+            .unwrap_optimized();
+    }
+    (rate - SCALAR_9) / 100
+}
+
+/// Convert an annual rate (7 decimals) to a per second rate (18 decimals)
+fn calc_per_sec_rate(annual_rate: i128) -> i128 {
+    (annual_rate * 100)
+        .fixed_div_floor(SECONDS_PER_YEAR, SCALAR_9)
+        .unwrap_optimized()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    #[test]
+    fn test_calculate_reserve_rates() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let rates = calculate_reserve_rates(&e, &underlying);
+            assert_eq!(rates.borrow_apr, 0_0600000);
+            assert_eq!(rates.supply_apr, 0_0405000);
+            assert_eq!(rates.borrow_apy, 0_0618316);
+            assert_eq!(rates.supply_apy, 0_0413291);
+            assert_eq!(rates.borrow_per_sec, 1_902_587_519);
+            assert_eq!(rates.supply_per_sec, 1_284_246_575);
+            assert!(rates.borrow_apy > rates.borrow_apr);
+            assert!(rates.supply_apy > rates.supply_apr);
+        });
+    }
+
+    #[test]
+    fn test_quote_borrow() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let quote = quote_borrow(&e, &underlying, 15_0000000);
+            assert_eq!(quote.util, 0_7500000);
+            assert_eq!(quote.borrow_apr, 0_0600000);
+            assert_eq!(quote.new_util, 0_9000000);
+            assert!(quote.new_borrow_apr > quote.borrow_apr);
+            assert!(!quote.exceeds_max_util);
+            assert_eq!(quote.fee, 0);
+
+            let quote = quote_borrow(&e, &underlying, 25_0000000);
+            assert_eq!(quote.new_util, 1_0000000);
+            assert!(quote.exceeds_max_util);
+
+            storage::set_res_borrow_fee(&e, &underlying, &0_0100000);
+            let quote = quote_borrow(&e, &underlying, 15_0000000);
+            assert_eq!(quote.fee, 0_1500000);
+            assert!(quote.new_util > 0_9000000);
+
+            // the fee is charged on the borrowed shares and quoted in underlying tokens
+            let (adapter, adapter_client) = testutils::create_mock_exchange_rate(&e);
+            adapter_client.set_rate(&1_100_000_000);
+            storage::set_res_adapter(&e, &underlying, &adapter);
+            let quote = quote_borrow(&e, &underlying, 11_0000000);
+            assert_eq!(quote.fee, 0_1100000);
+            assert_eq!(quote.new_util, 0_8501498);
+        });
+    }
+}
@@ -0,0 +1,228 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{dependencies::TokenClient, errors::PoolError, storage};
+
+use super::{pool::Pool, user::User};
+
+/// Redeem a user's b_tokens for their pro-rata share of the underlying tokens held by a
+/// wound down pool. A reserve can only be redeemed from once all of its liabilities have been
+/// repaid, liquidated or written off, such that every supplier redeems against the same
+/// settled balance.
+///
+/// Returns the amount of underlying tokens redeemed
+///
+/// ### Arguments
+/// * `from` - The address redeeming their b_tokens
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If the pool is not wound down, the reserve has outstanding liabilities, the user
+/// has outstanding liabilities, or the user has no b_tokens for the reserve
+pub fn execute_redeem_frozen(e: &Env, from: &Address, asset: &Address) -> i128 {
+    let mut pool = Pool::load(e);
+    if pool.config.status != 4 {
+        panic_with_error!(e, PoolError::InvalidPoolStatus);
+    }
+    let mut user = User::load(e, from);
+    if !user.positions.liabilities.is_empty() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let mut reserve = pool.load_reserve(e, asset);
+    if reserve.d_supply > 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let supply = user.get_supply(reserve.index);
+    let collateral = user.get_collateral(reserve.index);
+    let b_tokens = supply + collateral;
+    if b_tokens == 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    // the backstop credit is owed to the backstop and is not available to suppliers
+    let token_client = TokenClient::new(e, asset);
+    let balance = token_client.balance(&e.current_contract_address());
+    let available = (balance - pool.to_underlying_up(e, asset, reserve.backstop_credit)).max(0);
+    let amount_out = available
+        .fixed_mul_floor(b_tokens, reserve.b_supply)
+        .unwrap_optimized();
+
+    if supply > 0 {
+        user.remove_supply(e, &mut reserve, supply);
+    }
+    if collateral > 0 {
+        user.remove_collateral(e, &mut reserve, collateral);
+    }
+    reserve.store(e);
+    user.store(e);
+
+    token_client.transfer(&e.current_contract_address(), from, &amount_out);
+    amount_out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pool::Positions, storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    #[test]
+    fn test_redeem_frozen() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, underlying_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        reserve_data.d_supply = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 4,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 4_0000000)],
+            supply: map![&e, (0, 6_0000000)],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            let pool_balance = underlying_client.balance(&pool_address);
+            let amount_out = execute_redeem_frozen(&e, &samwise, &underlying);
+            assert_eq!(amount_out, pool_balance / 10);
+            assert_eq!(underlying_client.balance(&samwise), amount_out);
+
+            let positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.supply.len(), 0);
+            let reserve_data = storage::get_res_data(&e, &underlying);
+            assert_eq!(reserve_data.b_supply, 90_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_redeem_frozen_outstanding_liabilities() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 4,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e],
+            supply: map![&e, (0, 6_0000000)],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            execute_redeem_frozen(&e, &samwise, &underlying);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(11)")]
+    fn test_redeem_frozen_pool_not_wound_down() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 3,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e],
+            supply: map![&e, (0, 6_0000000)],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            execute_redeem_frozen(&e, &samwise, &underlying);
+        });
+    }
+}
@@ -18,6 +18,7 @@ pub struct Reserve {
     pub index: u32,            // the reserve index in the pool
     pub l_factor: u32,         // the liability factor for the reserve
     pub c_factor: u32,         // the collateral factor for the reserve
+    pub liq_threshold: u32,    // the collateral factor a position is liquidated at
     pub max_util: u32,         // the maximum utilization rate for the reserve
     pub last_time: u64,        // the last block the data was updated
     pub scalar: i128,          // scalar used for balances
@@ -37,11 +38,17 @@ impl Reserve {
     /// ### Arguments
     /// * pool_config - The pool configuration
     /// * asset - The address of the underlying asset
+    /// * exchange_rate - The exchange rate from share units to underlying tokens (9 decimals)
     ///
     /// ### Panics
     /// Panics if the asset is not supported, if emissions cannot be updated, or if the reserve
     /// cannot be updated to the current ledger timestamp.
-    pub fn load(e: &Env, pool_config: &PoolConfig, asset: &Address) -> Reserve {
+    pub fn load(
+        e: &Env,
+        pool_config: &PoolConfig,
+        asset: &Address,
+        exchange_rate: i128,
+    ) -> Reserve {
         let reserve_config = storage::get_res_config(e, asset);
         let reserve_data = storage::get_res_data(e, asset);
         let mut reserve = Reserve {
@@ -49,6 +56,8 @@ impl Reserve {
             index: reserve_config.index,
             l_factor: reserve_config.l_factor,
             c_factor: reserve_config.c_factor,
+            liq_threshold: storage::get_res_liq_threshold(e, asset)
+                .unwrap_or(reserve_config.c_factor),
             max_util: reserve_config.max_util,
             last_time: reserve_data.last_time,
             scalar: 10i128.pow(reserve_config.decimals),
@@ -70,6 +79,12 @@ impl Reserve {
             return reserve;
         }
 
+        // interest does not accrue while the pool's interest rates are frozen
+        if storage::get_interest_freeze(e).is_some() {
+            reserve.last_time = e.ledger().timestamp();
+            return reserve;
+        }
+
         let cur_util = reserve.utilization();
         let (loan_accrual, new_ir_mod) = calc_accrual(
             e,
@@ -87,7 +102,11 @@ impl Reserve {
         // TODO: Is it safe to calculate b_rate from accrual? If any unexpected token loss occurs
         //       the transfer rate will become unrecoverable.
         let pre_update_supply = reserve.total_supply();
-        let token_bal = TokenClient::new(e, asset).balance(&e.current_contract_address());
+        // normalize the pool's balance into share units in case the underlying rebases
+        let token_bal = TokenClient::new(e, asset)
+            .balance(&e.current_contract_address())
+            .fixed_div_floor(exchange_rate, SCALAR_9)
+            .unwrap_optimized();
 
         // credit the backstop underlying from the accrued interest based on the backstop rate
         let accrued_supply =
@@ -135,6 +154,19 @@ impl Reserve {
         }
     }
 
+    /// Require that the utilization rate is below the reserve's maximum utilization after a
+    /// withdrawal, if one is set, or panic.
+    pub fn require_utilization_below_withdraw_max(&self, e: &Env) {
+        if self.d_supply == 0 {
+            return;
+        }
+        if let Some(max_util) = storage::get_res_withdraw_util(e, &self.asset) {
+            if self.utilization() > i128(max_util) {
+                panic_with_error!(e, PoolError::WithdrawUtilTooHigh)
+            }
+        }
+    }
+
     /// Fetch the total liabilities for the reserve in underlying tokens
     pub fn total_liabilities(&self) -> i128 {
         self.to_asset_from_d_token(self.d_supply)
@@ -191,6 +223,18 @@ impl Reserve {
             .unwrap_optimized()
     }
 
+    /// Convert b_tokens to the corresponding effective asset value used to determine
+    /// if a position can be liquidated. This takes into account the liquidation threshold.
+    ///
+    /// ### Arguments
+    /// * `b_tokens` - The amount of tokens to convert
+    pub fn to_liq_effective_asset_from_b_token(&self, b_tokens: i128) -> i128 {
+        let assets = self.to_asset_from_b_token(b_tokens);
+        assets
+            .fixed_mul_floor(i128(self.liq_threshold), SCALAR_7)
+            .unwrap_optimized()
+    }
+
     /// Convert asset tokens to the corresponding d token value - rounding up
     ///
     /// ### Arguments
@@ -269,10 +313,12 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let reserve = Reserve::load(&e, &pool_config, &underlying);
+            let reserve = Reserve::load(&e, &pool_config, &underlying, SCALAR_9);
 
             // (accrual: 1_002_957_369, util: .7864352)
             assert_eq!(reserve.d_rate, 1_349_657_792);
@@ -316,10 +362,12 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let reserve = Reserve::load(&e, &pool_config, &underlying);
+            let reserve = Reserve::load(&e, &pool_config, &underlying, SCALAR_9);
 
             // (accrual: 1_002_957_369, util: .7864352)q
             assert_eq!(reserve.d_rate, 0);
@@ -363,10 +411,12 @@ mod tests {
             oracle,
             bstop_rate: 0_200_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let reserve = Reserve::load(&e, &pool_config, &underlying);
+            let reserve = Reserve::load(&e, &pool_config, &underlying, SCALAR_9);
             reserve.store(&e);
 
             let reserve_data = storage::get_res_data(&e, &underlying);
@@ -0,0 +1,201 @@
+use soroban_sdk::{contracttype, map, Address, Env, Map, Vec};
+
+use crate::{
+    constants::RISK_MANIFEST_VERSION,
+    storage::{self, IsolationConfig, PriceClampConfig, ReserveConfig, SoftLiqConfig},
+};
+
+/// The risk parameters of a reserve
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveRiskParams {
+    pub config: ReserveConfig,           // the reserve's config
+    pub liq_threshold: Option<u32>,      // the collateral factor the reserve is liquidated at
+    pub pause_flags: u32,                // the actions paused for the reserve
+    pub borrow_fee: Option<u32>,         // the origination fee charged on borrows, in 7 decimals
+    pub exposure_cap: Option<i128>,      // the cap on the liability value backed by the reserve
+    pub withdraw_util: Option<u32>,      // the max utilization after a withdrawal, in 7 decimals
+    pub large_withdrawal: Option<i128>,  // the amount above which withdrawals are queued
+    pub soft_liq: Option<SoftLiqConfig>, // the soft liquidation band of the reserve
+    pub adapter: Option<Address>,        // the exchange rate adapter of the reserve
+}
+
+/// A versioned manifest of every risk relevant parameter of the pool
+#[derive(Clone)]
+#[contracttype]
+pub struct RiskManifest {
+    pub version: u32,                    // the version of the manifest format
+    pub status: u32,                     // the status of the pool
+    pub oracle: Address,                 // the oracle the pool is priced with
+    pub pending_oracle: Option<Address>, // the oracle being verified to replace the oracle
+    pub bstop_rate: u64, // the rate the backstop takes on accrued debt interest, in 9 decimals
+    pub liq_hf_low: u32, // the health factor a liquidation can be created at, in 7 decimals
+    pub liq_hf_high: u32, // the health factor a liquidation can be deleted at, in 7 decimals
+    pub max_leverage: Option<i128>, // the max leverage a user can take on, in 7 decimals
+    pub min_debt: Option<i128>, // the min liability value a user with liabilities can hold
+    pub min_backstop: Option<i128>, // the backstop deposits required before borrowing is enabled
+    pub block_round_trips: bool, // if submits that round trip an asset are rejected
+    pub permissioned: bool, // if only allowlisted users can interact with the pool
+    pub interest_freeze: Option<u64>, // the time interest accrual was frozen at, if frozen
+    pub auction_mode: u32, // the auction modifier mode
+    pub auction_valuation: u32, // the auction valuation
+    pub coop_fill_window: Option<u32>, // the blocks fill bids are collected for, if cooperative
+    pub liq_priority: Vec<Address>, // the order collateral is seized in by liquidations
+    pub direct_interest: bool, // if swept interest is distributed in kind to backstop depositors
+    // the borrowing limits of users with collateral in an isolated reserve
+    pub isolation: Option<IsolationConfig>,
+    // the band the price used to value each asset can move within an interval
+    pub price_clamp: Option<PriceClampConfig>,
+    // the risk parameters of each reserve, by asset
+    pub reserves: Map<Address, ReserveRiskParams>,
+}
+
+/// Build a manifest of the pool's current risk parameters. The manifest only contains
+/// configuration, not state, such that manifests can be compared across pools and over time.
+pub fn build_risk_manifest(e: &Env) -> RiskManifest {
+    let pool_config = storage::get_pool_config(e);
+
+    let mut reserves = map![e];
+    for asset in storage::get_res_list(e).iter() {
+        let params = ReserveRiskParams {
+            config: storage::get_res_config(e, &asset),
+            liq_threshold: storage::get_res_liq_threshold(e, &asset),
+            pause_flags: storage::get_res_pause_flags(e, &asset),
+            borrow_fee: storage::get_res_borrow_fee(e, &asset),
+            exposure_cap: storage::get_res_exposure(e, &asset)
+                .map(|exposure| exposure.cap)
+                .filter(|cap| *cap > 0),
+            withdraw_util: storage::get_res_withdraw_util(e, &asset),
+            large_withdrawal: storage::get_res_large_withdrawal(e, &asset),
+            soft_liq: storage::get_soft_liq_config(e, &asset),
+            adapter: storage::get_res_adapter(e, &asset),
+        };
+        reserves.set(asset, params);
+    }
+
+    RiskManifest {
+        version: RISK_MANIFEST_VERSION,
+        status: pool_config.status,
+        oracle: pool_config.oracle,
+        pending_oracle: storage::get_pending_oracle(e).map(|pending| pending.oracle),
+        bstop_rate: pool_config.bstop_rate,
+        liq_hf_low: pool_config.liq_hf_low,
+        liq_hf_high: pool_config.liq_hf_high,
+        max_leverage: storage::get_max_leverage(e),
+        min_debt: storage::get_min_debt(e),
+        min_backstop: storage::get_min_backstop(e),
+        block_round_trips: storage::get_block_round_trips(e),
+        permissioned: storage::get_permissioned(e),
+        interest_freeze: storage::get_interest_freeze(e),
+        auction_mode: storage::get_auction_mode(e),
+        auction_valuation: storage::get_auction_valuation(e),
+        coop_fill_window: storage::get_coop_fill_window(e),
+        liq_priority: storage::get_liq_priority(e),
+        direct_interest: storage::get_direct_interest(e),
+        isolation: storage::get_isolation_config(e),
+        price_clamp: storage::get_price_clamp_config(e),
+        reserves,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pool::set_reserve_exposure_cap, storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_build_risk_manifest() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let oracle = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: oracle.clone(),
+            bstop_rate: 0_100_000_000,
+            status: 1,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_max_leverage(&e, &5_0000000);
+            storage::set_block_round_trips(&e, &true);
+            storage::set_auction_mode(&e, &1);
+            set_reserve_exposure_cap(&e, &underlying_0, 100_0000000);
+            storage::set_soft_liq_config(
+                &e,
+                &underlying_1,
+                &SoftLiqConfig {
+                    upper_hf: 1_0500000,
+                    lower_hf: 1_0000000,
+                    max_discount: 0_0500000,
+                },
+            );
+
+            let manifest = build_risk_manifest(&e);
+            assert_eq!(manifest.version, RISK_MANIFEST_VERSION);
+            assert_eq!(manifest.status, 1);
+            assert_eq!(manifest.oracle, oracle);
+            assert!(manifest.pending_oracle.is_none());
+            assert_eq!(manifest.bstop_rate, 0_100_000_000);
+            assert_eq!(manifest.liq_hf_low, 1_0000000);
+            assert_eq!(manifest.liq_hf_high, 1_0000100);
+            assert_eq!(manifest.max_leverage, Some(5_0000000));
+            assert!(manifest.min_debt.is_none());
+            assert!(manifest.min_backstop.is_none());
+            assert!(manifest.block_round_trips);
+            assert!(!manifest.permissioned);
+            assert!(manifest.interest_freeze.is_none());
+            assert_eq!(manifest.auction_mode, 1);
+            assert_eq!(manifest.auction_valuation, 0);
+            assert!(manifest.coop_fill_window.is_none());
+            assert!(manifest.liq_priority.is_empty());
+            assert!(!manifest.direct_interest);
+            assert!(manifest.isolation.is_none());
+            assert!(manifest.price_clamp.is_none());
+            assert_eq!(manifest.reserves.len(), 2);
+
+            let params_0 = manifest.reserves.get_unchecked(underlying_0.clone());
+            assert_eq!(params_0.config.index, 0);
+            assert_eq!(params_0.config.c_factor, reserve_config.c_factor);
+            assert_eq!(params_0.exposure_cap, Some(100_0000000));
+            assert!(params_0.soft_liq.is_none());
+
+            let params_1 = manifest.reserves.get_unchecked(underlying_1.clone());
+            assert_eq!(params_1.config.index, 1);
+            assert!(params_1.exposure_cap.is_none());
+            assert!(params_1.liq_threshold.is_none());
+            assert_eq!(params_1.pause_flags, 0);
+            assert!(params_1.borrow_fee.is_none());
+            assert!(params_1.withdraw_util.is_none());
+            assert!(params_1.large_withdrawal.is_none());
+            assert!(params_1.adapter.is_none());
+            assert_eq!(params_1.soft_liq.unwrap().max_discount, 0_0500000);
+        });
+    }
+}
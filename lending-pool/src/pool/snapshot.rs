@@ -0,0 +1,204 @@
+use soroban_sdk::{contracttype, map, Address, Env, Map};
+
+use crate::{
+    constants::SNAPSHOT_VERSION,
+    storage::{self, UserHistory},
+};
+
+use super::{
+    health_factor::{FactorBasis, PositionData},
+    pool::Pool,
+};
+
+/// A versioned snapshot of a user's position and history with the pool
+#[derive(Clone)]
+#[contracttype]
+pub struct PositionSnapshot {
+    pub version: u32,                    // the version of the snapshot format
+    pub user: Address,                   // the user the snapshot is of
+    pub timestamp: u64,                  // the time the snapshot was taken
+    pub sequence: u32,                   // the ledger sequence the snapshot was taken
+    pub collateral: Map<Address, i128>,  // the underlying tokens supplied as collateral, by asset
+    pub liabilities: Map<Address, i128>, // the underlying tokens borrowed, by asset
+    pub supply: Map<Address, i128>, // the underlying tokens supplied as non-collateral, by asset
+    pub collateral_base: i128, // the effective collateral, denominated in the oracle's base asset
+    pub collateral_raw: i128,  // the raw collateral, denominated in the oracle's base asset
+    pub liability_base: i128,  // the effective liabilities, denominated in the oracle's base asset
+    pub liability_raw: i128,   // the raw liabilities, denominated in the oracle's base asset
+    pub history: UserHistory,  // the user's history counters
+}
+
+/// Record a submission by a user in their history counters
+pub fn record_submit(e: &Env, user: &Address) {
+    let mut history = load_history(e, user);
+    history.submits += 1;
+    storage::set_user_history(e, user, &history);
+}
+
+/// Record a liquidation auction created against a user in their history counters
+pub fn record_liquidation(e: &Env, user: &Address) {
+    let mut history = load_history(e, user);
+    history.liquidations += 1;
+    storage::set_user_history(e, user, &history);
+}
+
+/// Record the transfer of a user's debt to the backstop in their history counters
+pub fn record_bad_debt(e: &Env, user: &Address) {
+    let mut history = load_history(e, user);
+    history.bad_debt += 1;
+    storage::set_user_history(e, user, &history);
+}
+
+fn load_history(e: &Env, user: &Address) -> UserHistory {
+    storage::get_user_history(e, user).unwrap_or(UserHistory {
+        first_time: e.ledger().timestamp(),
+        submits: 0,
+        liquidations: 0,
+        bad_debt: 0,
+    })
+}
+
+/// Build a snapshot of a user's current position and history counters. Balances are
+/// expressed in underlying tokens and keyed by asset, such that the snapshot can be
+/// consumed without knowledge of the pool's internal accounting.
+///
+/// ### Arguments
+/// * `user` - The user to snapshot
+///
+/// ### Panics
+/// If a price is unavailable for any of the user's positions
+pub fn build_position_snapshot(e: &Env, user: &Address) -> PositionSnapshot {
+    let mut pool = Pool::load(e);
+    let positions = storage::get_user_positions(e, user);
+    let reserve_list = storage::get_res_list(e);
+
+    let mut collateral = map![e];
+    for (index, b_tokens) in positions.collateral.iter() {
+        let asset = reserve_list.get_unchecked(index);
+        let reserve = pool.load_reserve(e, &asset);
+        let shares = reserve.to_asset_from_b_token(b_tokens);
+        collateral.set(asset.clone(), pool.to_underlying_down(e, &asset, shares));
+    }
+    let mut liabilities = map![e];
+    for (index, d_tokens) in positions.liabilities.iter() {
+        let asset = reserve_list.get_unchecked(index);
+        let reserve = pool.load_reserve(e, &asset);
+        let shares = reserve.to_asset_from_d_token(d_tokens);
+        liabilities.set(asset.clone(), pool.to_underlying_up(e, &asset, shares));
+    }
+    let mut supply = map![e];
+    for (index, b_tokens) in positions.supply.iter() {
+        let asset = reserve_list.get_unchecked(index);
+        let reserve = pool.load_reserve(e, &asset);
+        let shares = reserve.to_asset_from_b_token(b_tokens);
+        supply.set(asset.clone(), pool.to_underlying_down(e, &asset, shares));
+    }
+
+    let position_data =
+        PositionData::calculate_for_user(e, &mut pool, user, &positions, FactorBasis::Borrow);
+    PositionSnapshot {
+        version: SNAPSHOT_VERSION,
+        user: user.clone(),
+        timestamp: e.ledger().timestamp(),
+        sequence: e.ledger().sequence(),
+        collateral,
+        liabilities,
+        supply,
+        collateral_base: position_data.collateral_base,
+        collateral_raw: position_data.collateral_raw,
+        liability_base: position_data.liability_base,
+        liability_raw: position_data.liability_raw,
+        history: storage::get_user_history(e, user).unwrap_or(UserHistory {
+            first_time: 0,
+            submits: 0,
+            liquidations: 0,
+            bad_debt: 0,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pool::Positions, storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    #[test]
+    fn test_build_position_snapshot() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+        oracle_client.set_price(&underlying, &2_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions = Positions {
+            collateral: map![&e, (0, 10_0000000)],
+            liabilities: map![&e, (0, 5_0000000)],
+            supply: map![&e, (0, 1_0000000)],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+            record_submit(&e, &samwise);
+            record_submit(&e, &samwise);
+            record_liquidation(&e, &samwise);
+
+            let snapshot = build_position_snapshot(&e, &samwise);
+            assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+            assert_eq!(snapshot.user, samwise);
+            assert_eq!(snapshot.timestamp, 12345);
+            assert_eq!(snapshot.sequence, 100);
+            assert_eq!(
+                snapshot.collateral.get_unchecked(underlying.clone()),
+                10_0000000
+            );
+            assert_eq!(
+                snapshot.liabilities.get_unchecked(underlying.clone()),
+                5_0000000
+            );
+            assert_eq!(snapshot.supply.get_unchecked(underlying.clone()), 1_0000000);
+            assert_eq!(snapshot.collateral_base, 15_0000000);
+            assert_eq!(snapshot.collateral_raw, 20_0000000);
+            assert_eq!(snapshot.liability_base, 13_3333334);
+            assert_eq!(snapshot.liability_raw, 10_0000000);
+            assert_eq!(snapshot.history.first_time, 12345);
+            assert_eq!(snapshot.history.submits, 2);
+            assert_eq!(snapshot.history.liquidations, 1);
+            assert_eq!(snapshot.history.bad_debt, 0);
+        });
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{constants::SCALAR_7, dependencies::BackstopClient, errors::PoolError, storage};
+use crate::{
+    constants::SCALAR_7, dependencies::VersionedBackstopClient, errors::PoolError, storage,
+};
 use fixed_point_math::FixedPoint;
 use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Env};
 
@@ -13,7 +15,7 @@ pub fn execute_update_pool_status(e: &Env) -> u32 {
     }
 
     let backstop_id = storage::get_backstop(e);
-    let backstop_client = BackstopClient::new(e, &backstop_id);
+    let backstop_client = VersionedBackstopClient::new(e, &backstop_id);
 
     let pool_balance = backstop_client.pool_balance(&e.current_contract_address());
     let q4w_pct = pool_balance
@@ -24,7 +26,10 @@ pub fn execute_update_pool_status(e: &Env) -> u32 {
     if q4w_pct >= 0_5000000 {
         pool_config.status = 2;
         //TODO: this token check needs to check for k-value of over 200,000 for pool balance LP tokens
-    } else if q4w_pct >= 0_2500000 || pool_balance.tokens < 1_000_000_0000000 {
+    } else if q4w_pct >= 0_2500000
+        || pool_balance.tokens < 1_000_000_0000000
+        || pool_balance.tokens < storage::get_min_backstop(e).unwrap_or(0)
+    {
         pool_config.status = 1;
     } else {
         pool_config.status = 0;
@@ -35,24 +40,94 @@ pub fn execute_update_pool_status(e: &Env) -> u32 {
 }
 
 /// Update the pool status
+///
+/// ### Panics
+/// If the pool has been wound down, or the status is not a status an admin can set
 #[allow(clippy::inconsistent_digit_grouping)]
 pub fn set_pool_status(e: &Env, pool_status: u32) {
+    let mut pool_config = storage::get_pool_config(e);
+    if pool_config.status == 4 {
+        // a wound down pool can not be restored
+        panic_with_error!(e, PoolError::InvalidPoolStatus);
+    }
+    if pool_status > 3 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
     if pool_status == 0 {
         // check the pool has met minimum backstop deposits before being turned on
         let backstop_id = storage::get_backstop(e);
-        let backstop_client = BackstopClient::new(e, &backstop_id);
+        let backstop_client = VersionedBackstopClient::new(e, &backstop_id);
 
         let pool_balance = backstop_client.pool_balance(&e.current_contract_address());
-        if pool_balance.tokens < 200_000_000_0000 {
+        if pool_balance.tokens < 200_000_000_0000
+            || pool_balance.tokens < storage::get_min_backstop(e).unwrap_or(0)
+        {
             panic_with_error!(e, PoolError::InvalidPoolStatus);
         }
     }
 
-    let mut pool_config = storage::get_pool_config(e);
     pool_config.status = pool_status;
     storage::set_pool_config(e, &pool_config);
 }
 
+/// Permanently wind down the pool. Borrowing and supplying are disabled, and the status can
+/// not be changed again. Liabilities can still be repaid or liquidated, and suppliers can
+/// redeem their share of a reserve once all of its liabilities are settled.
+///
+/// ### Panics
+/// If the pool has already been wound down
+pub fn execute_wind_down(e: &Env) {
+    let mut pool_config = storage::get_pool_config(e);
+    if pool_config.status == 4 {
+        panic_with_error!(e, PoolError::InvalidPoolStatus);
+    }
+    pool_config.status = 4;
+    storage::set_pool_config(e, &pool_config);
+}
+
+/// Set the backstop deposits required before borrowing is enabled. Until the backstop reaches
+/// the floor, the pool can not be made active and borrow requests are rejected.
+///
+/// ### Arguments
+/// * `min_backstop` - The minimum backstop deposits, in backstop tokens. 0 removes the floor.
+///
+/// ### Panics
+/// If the floor is negative
+pub fn set_min_backstop(e: &Env, min_backstop: i128) {
+    if min_backstop < 0 {
+        panic_with_error!(e, PoolError::NegativeAmount);
+    }
+    if min_backstop == 0 {
+        storage::del_min_backstop(e);
+    } else {
+        storage::set_min_backstop(e, &min_backstop);
+    }
+}
+
+/// Fetch the backstop tokens that still need to be deposited before the backstop reaches the
+/// pool's floor. Returns 0 if no floor is set or the floor has been reached.
+pub fn get_backstop_shortfall(e: &Env) -> i128 {
+    match storage::get_min_backstop(e) {
+        Some(min_backstop) => {
+            let backstop_client = VersionedBackstopClient::new(e, &storage::get_backstop(e));
+            let pool_balance = backstop_client.pool_balance(&e.current_contract_address());
+            (min_backstop - pool_balance.tokens).max(0)
+        }
+        None => 0,
+    }
+}
+
+/// Require that the backstop has reached the pool's floor, such that debt can be originated
+///
+/// ### Panics
+/// If the backstop deposits are below the floor
+pub fn require_backstop_floor(e: &Env) {
+    if get_backstop_shortfall(e) > 0 {
+        panic_with_error!(e, PoolError::InvalidPoolStatus);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -90,6 +165,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0,
             status: 1,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_id, || {
             storage::set_admin(&e, &bombadil);
@@ -131,6 +208,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0,
             status: 1,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_id, || {
             storage::set_admin(&e, &bombadil);
@@ -140,6 +219,51 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(11))")]
+    fn test_set_pool_status_wound_down() {
+        let e = Env::default();
+        let pool_id = Address::random(&e);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0,
+            status: 3,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            execute_wind_down(&e);
+            assert_eq!(storage::get_pool_config(&e).status, 4);
+
+            set_pool_status(&e, 2);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(2))")]
+    fn test_set_pool_status_invalid() {
+        let e = Env::default();
+        let pool_id = Address::random(&e);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0,
+            status: 3,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            set_pool_status(&e, 4);
+        });
+    }
+
     #[test]
     fn test_update_pool_status_active() {
         let e = Env::default();
@@ -167,6 +291,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0,
             status: 1,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_id, || {
             storage::set_admin(&e, &bombadil);
@@ -207,6 +333,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_id, || {
             storage::set_admin(&e, &bombadil);
@@ -248,6 +376,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_id, || {
             storage::set_admin(&e, &bombadil);
@@ -289,6 +419,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_id, || {
             storage::set_admin(&e, &bombadil);
@@ -331,6 +463,8 @@ mod tests {
             oracle: oracle_id,
             bstop_rate: 0,
             status: 3,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool_id, || {
             storage::set_admin(&e, &bombadil);
@@ -339,4 +473,144 @@ mod tests {
             execute_update_pool_status(&e);
         });
     }
+
+    #[test]
+    fn test_update_pool_status_on_ice_min_backstop() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+        let pool_id = Address::random(&e);
+        let oracle_id = Address::random(&e);
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (backstop_token_id, backstop_token_client) = create_token_contract(&e, &bombadil);
+        let (backstop_id, backstop_client) = create_backstop(&e);
+        setup_backstop(
+            &e,
+            &pool_id,
+            &backstop_id,
+            &backstop_token_id,
+            &Address::random(&e),
+        );
+        backstop_token_client.mint(&samwise, &1_100_000_0000000);
+        backstop_client.deposit(&samwise, &pool_id, &1_100_000_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_admin(&e, &bombadil);
+            storage::set_pool_config(&e, &pool_config);
+            assert_eq!(get_backstop_shortfall(&e), 0);
+
+            set_min_backstop(&e, 2_000_000_0000000);
+            assert_eq!(get_backstop_shortfall(&e), 900_000_0000000);
+
+            let status = execute_update_pool_status(&e);
+            assert_eq!(status, 1);
+        });
+
+        backstop_token_client.mint(&samwise, &1_000_000_0000000);
+        backstop_client.deposit(&samwise, &pool_id, &1_000_000_0000000);
+        e.as_contract(&pool_id, || {
+            assert_eq!(get_backstop_shortfall(&e), 0);
+            require_backstop_floor(&e);
+
+            let status = execute_update_pool_status(&e);
+            assert_eq!(status, 0);
+
+            set_min_backstop(&e, 0);
+            assert!(storage::get_min_backstop(&e).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(11))")]
+    fn test_set_pool_status_blocks_below_min_backstop() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+        let pool_id = Address::random(&e);
+        let oracle_id = Address::random(&e);
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (backstop_token_id, backstop_token_client) = create_token_contract(&e, &bombadil);
+        let (backstop_id, backstop_client) = create_backstop(&e);
+        setup_backstop(
+            &e,
+            &pool_id,
+            &backstop_id,
+            &backstop_token_id,
+            &Address::random(&e),
+        );
+        backstop_token_client.mint(&samwise, &1_100_000_0000000);
+        backstop_client.deposit(&samwise, &pool_id, &1_100_000_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0,
+            status: 1,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_admin(&e, &bombadil);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_min_backstop(&e, &2_000_000_0000000);
+
+            set_pool_status(&e, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(11))")]
+    fn test_require_backstop_floor_below_floor() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+        let pool_id = Address::random(&e);
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+
+        let (backstop_token_id, backstop_token_client) = create_token_contract(&e, &bombadil);
+        let (backstop_id, backstop_client) = create_backstop(&e);
+        setup_backstop(
+            &e,
+            &pool_id,
+            &backstop_id,
+            &backstop_token_id,
+            &Address::random(&e),
+        );
+        backstop_token_client.mint(&samwise, &1_100_000_0000000);
+        backstop_client.deposit(&samwise, &pool_id, &1_100_000_0000000);
+
+        e.as_contract(&pool_id, || {
+            storage::set_min_backstop(&e, &1_100_000_0000001);
+
+            require_backstop_floor(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "Status(ContractError(4))")]
+    fn test_set_min_backstop_negative() {
+        let e = Env::default();
+        let pool_id = Address::random(&e);
+
+        e.as_contract(&pool_id, || {
+            set_min_backstop(&e, -1);
+        });
+    }
 }
@@ -1,15 +1,37 @@
-use crate::dependencies::TokenClient;
+use crate::{
+    dependencies::TokenClient,
+    storage,
+    validator::{
+        require_isolation_limits, require_max_leverage, require_min_debt, require_no_round_trips,
+        require_not_denied,
+    },
+};
 use soroban_sdk::{Address, Env, Vec};
 
 use super::{
     actions::{build_actions_from_request, Request},
-    health_factor::PositionData,
+    allowlist::require_allowed,
+    exposure::require_exposure_caps,
+    health_factor::{FactorBasis, PositionData},
     pool::Pool,
+    snapshot::record_submit,
+    trusted::get_trusted_credit,
     Positions,
 };
 
 /// Execute a set of updates for a user against the pool.
 ///
+/// Requests are applied in order to a working copy of the user's positions, and the health
+/// factor is only checked once against the final positions. This allows requests like a repay
+/// followed by a borrow to be submitted in any order. If the spender and "to" are the same
+/// address, transfers of the same asset in and out of the pool are netted.
+///
+/// A submit of only supplies, supplies of collateral and repays strictly reduces the user's risk,
+/// so it does not check the user's health factor, which would query the oracle. The minimum
+/// debt check is still priced by the oracle unless the submit leaves the user's liabilities
+/// unchanged or fully repaid, so supplies and full repays can be made while the oracle is
+/// unavailable.
+///
 /// ### Arguments
 /// * from - The address of the user whose positions are being modified
 /// * spender - The address of the user who is sending tokens to the pool
@@ -17,7 +39,11 @@ use super::{
 /// * requests - A vec of requests to be processed
 ///
 /// ### Panics
-/// If the request is unable to be fully executed
+/// If the request is unable to be fully executed, the new positions exceed the pool's maximum
+/// leverage or a collateral reserve's exposure cap, the new liabilities are below the pool's
+/// minimum debt, the requests round trip an asset while the pool blocks round trips, the pool is
+/// permissioned and "from" is not on the allowlist, or any of the addresses are denied by the
+/// pool's denylist
 pub fn execute_submit(
     e: &Env,
     from: &Address,
@@ -25,29 +51,58 @@ pub fn execute_submit(
     to: &Address,
     requests: Vec<Request>,
 ) -> Positions {
+    require_allowed(e, from);
+    require_not_denied(e, from);
+    require_not_denied(e, spender);
+    require_not_denied(e, to);
+    require_no_round_trips(e, &requests);
     let mut pool = Pool::load(e);
+    let prev_positions = storage::get_user_positions(e, from);
 
-    let (actions, new_from_state, check_health) =
+    let (mut actions, new_from_state, check_health) =
         build_actions_from_request(e, &mut pool, from, requests);
 
     if check_health {
         // panics if the new positions set does not meet the health factor requirement
-        PositionData::calculate_from_positions(e, &mut pool, &new_from_state.positions)
-            .require_healthy(e);
+        // trusted borrowers can borrow against their uncollateralized credit
+        let credit = get_trusted_credit(e, from);
+        let position_data = PositionData::calculate_for_user(
+            e,
+            &mut pool,
+            from,
+            &new_from_state.positions,
+            FactorBasis::Borrow,
+        );
+        position_data.require_healthy_with_credit(e, credit);
+        require_max_leverage(e, &position_data, credit);
+        // panics if a capped collateral reserve the user holds can back more than its cap
+        require_exposure_caps(e, &mut pool, &new_from_state.positions);
+    }
+    // panics if an isolated user's liabilities exceed the pool's isolation config
+    require_isolation_limits(e, &mut pool, &prev_positions, &new_from_state.positions);
+    // panics if the user's liabilities are left below the pool's minimum debt
+    require_min_debt(e, &mut pool, &prev_positions, &new_from_state.positions);
+
+    if spender == to {
+        actions.net_transfers(e);
     }
 
     // transfer tokens from sender to pool
+    // actions are expressed in share units, so convert back into underlying tokens
     for (address, amount) in actions.spender_transfer.iter() {
-        TokenClient::new(e, &address).transfer(spender, &e.current_contract_address(), &amount);
+        let amount_in = pool.to_underlying_up(e, &address, amount);
+        TokenClient::new(e, &address).transfer(spender, &e.current_contract_address(), &amount_in);
     }
 
     // store updated info to ledger
     pool.store_cached_reserves(e);
     new_from_state.store(e);
+    record_submit(e, from);
 
     // transfer tokens from pool to "to"
     for (address, amount) in actions.pool_transfer.iter() {
-        TokenClient::new(e, &address).transfer(&e.current_contract_address(), to, &amount);
+        let amount_out = pool.to_underlying_down(e, &address, amount);
+        TokenClient::new(e, &address).transfer(&e.current_contract_address(), to, &amount_out);
     }
 
     new_from_state.positions
@@ -56,12 +111,14 @@ pub fn execute_submit(
 #[cfg(test)]
 mod tests {
     use crate::{
-        storage::{self, PoolConfig},
+        constants::MAX_AMOUNT,
+        storage::{self, IsolationConfig, PoolConfig},
         testutils,
     };
 
     use super::*;
     use soroban_sdk::{
+        map,
         testutils::{Address as _, Ledger, LedgerInfo},
         vec,
     };
@@ -106,6 +163,8 @@ mod tests {
             oracle,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -189,6 +248,8 @@ mod tests {
             oracle,
             bstop_rate: 0_100_000_000,
             status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
@@ -209,4 +270,724 @@ mod tests {
             execute_submit(&e, &samwise, &frodo, &merry, requests);
         });
     }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(14)")]
+    fn test_submit_exceeds_max_leverage() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            // the position is healthy, but has a leverage of 8x
+            storage::set_max_leverage(&e, &5_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_1,
+                    amount: 1_5000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &frodo, &merry, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(40)")]
+    fn test_submit_below_min_debt() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            // the position is healthy, but only has a liability value of 0.5
+            storage::set_min_debt(&e, &1_0000000);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_1,
+                    amount: 0_1000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &frodo, &merry, requests);
+        });
+    }
+
+    #[test]
+    fn test_submit_risk_reducing_without_oracle() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool = Address::random(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &1_0000000);
+        underlying_1_client.mint(&frodo, &3_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        // the oracle is not a contract, so any price lookup fails
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_min_debt(&e, &1_0000000);
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 10_0000000)],
+                    liabilities: map![&e, (1, 2_0000000)],
+                    supply: map![&e],
+                },
+            );
+
+            // the repay leaves no liabilities, so the minimum debt does not need to be priced
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying_0,
+                    amount: 1_0000000,
+                },
+                Request {
+                    request_type: 5,
+                    address: underlying_1,
+                    amount: MAX_AMOUNT,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &frodo, &frodo, requests);
+
+            assert!(positions.collateral.get_unchecked(0) > 10_0000000);
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(underlying_0_client.balance(&frodo), 0);
+            assert!(underlying_1_client.balance(&frodo) < 1_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(40)")]
+    fn test_submit_partial_repay_below_min_debt() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_1_client.mint(&frodo, &2_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_min_debt(&e, &1_0000000);
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    collateral: map![&e, (0, 10_0000000)],
+                    liabilities: map![&e, (1, 2_0000000)],
+                    supply: map![&e],
+                },
+            );
+
+            // the repay leaves the liabilities below the minimum debt
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 5,
+                    address: underlying_1,
+                    amount: 1_5000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &frodo, &frodo, requests);
+        });
+    }
+
+    #[test]
+    fn test_submit_isolated() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.isolated = true;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_isolation_config(
+                &e,
+                &IsolationConfig {
+                    debt_cap: 8_0000000,
+                    borrowable: vec![&e, underlying_1.clone()],
+                },
+            );
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_1,
+                    amount: 1_5000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &frodo, &frodo, requests);
+            assert!(positions.is_isolated(&e));
+            assert_eq!(positions.liabilities.get_unchecked(1), 1_4999983);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_submit_isolated_not_borrowable() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.isolated = true;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_isolation_config(
+                &e,
+                &IsolationConfig {
+                    debt_cap: 8_0000000,
+                    borrowable: vec![&e, underlying_0.clone()],
+                },
+            );
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_1,
+                    amount: 1_5000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &frodo, &frodo, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(18)")]
+    fn test_submit_isolated_exceeds_debt_cap() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_config.isolated = true;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_isolation_config(
+                &e,
+                &IsolationConfig {
+                    debt_cap: 7_0000000,
+                    borrowable: vec![&e, underlying_1.clone()],
+                },
+            );
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_1,
+                    amount: 1_5000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &frodo, &frodo, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(19)")]
+    fn test_submit_round_trip_blocked() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_block_round_trips(&e, &true);
+
+            // supply and then borrow the same asset
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying_0.clone(),
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_0,
+                    amount: 1_0000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &frodo, &merry, requests);
+        });
+    }
+
+    #[test]
+    fn test_submit_round_trip_blocked_allows_other_assets() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let merry = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &5_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_block_round_trips(&e, &true);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 2,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_1,
+                    amount: 1_5000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &frodo, &merry, requests);
+            assert_eq!(positions.collateral.get_unchecked(0), 14_9999884);
+            assert_eq!(positions.liabilities.get_unchecked(1), 1_4999983);
+        });
+    }
+
+    #[test]
+    fn test_submit_order_independent() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let merry = Address::random(&e);
+        let pool = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&samwise, &100_0000000);
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let pre_pool_balance = underlying_0_client.balance(&pool);
+
+            // the borrow is both unhealthy and above the max utilization until the collateral
+            // is supplied later in the batch
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 4,
+                    address: underlying_0.clone(),
+                    amount: 25_0000000,
+                },
+                Request {
+                    request_type: 2,
+                    address: underlying_0.clone(),
+                    amount: 50_0000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &samwise, &samwise, requests);
+            assert_eq!(positions.liabilities.len(), 1);
+            assert_eq!(positions.collateral.len(), 1);
+
+            // transfers in and out of the pool are netted
+            assert_eq!(underlying_0_client.balance(&samwise), 75_0000000);
+            assert_eq!(
+                underlying_0_client.balance(&pool),
+                pre_pool_balance + 25_0000000
+            );
+
+            // a repay funded by a borrow later in the batch does not require a balance
+            underlying_0_client.transfer(&samwise, &merry, &75_0000000);
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: 5,
+                    address: underlying_0.clone(),
+                    amount: 10_0000000,
+                },
+                Request {
+                    request_type: 4,
+                    address: underlying_0.clone(),
+                    amount: 10_0000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &samwise, &samwise, requests);
+            assert_eq!(positions.liabilities.len(), 1);
+            assert_eq!(underlying_0_client.balance(&samwise), 0);
+            assert_eq!(
+                underlying_0_client.balance(&pool),
+                pre_pool_balance + 25_0000000
+            );
+        });
+    }
 }
@@ -0,0 +1,465 @@
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map, Symbol};
+
+use crate::{
+    auctions::AuctionType,
+    dependencies::{TokenClient, VersionedBackstopClient},
+    errors::PoolError,
+    storage::{self, SweepConfig},
+};
+
+use super::pool::Pool;
+
+/// Set the schedule on which accrued backstop credit can be swept to the backstop
+///
+/// ### Arguments
+/// * `interval` - The minimum time between sweeps, in seconds
+/// * `threshold` - The backstop credit value that allows a sweep before the interval has passed,
+///                 in the oracle's base asset. A threshold of 0 disables early sweeps.
+///
+/// ### Panics
+/// If the threshold is negative
+pub fn set_sweep_config(e: &Env, interval: u64, threshold: i128) {
+    if threshold < 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_sweep_config(
+        e,
+        &SweepConfig {
+            interval,
+            threshold,
+        },
+    );
+}
+
+/// Set if swept interest is distributed in kind to the backstop's depositors instead of being
+/// auctioned.
+///
+/// When direct interest is enabled, the backstop credit accrued for interest auctions up to
+/// now is distributed to the backstop's depositors immediately, such that it is not held back
+/// until the next sweep. When it is disabled, any unswept credit carries over to the next
+/// interest auction.
+///
+/// ### Arguments
+/// * `direct` - If swept interest is distributed in kind
+///
+/// ### Panics
+/// If direct interest is being enabled while an interest auction is in progress, or the
+/// accrued credit cannot be distributed to the backstop
+pub fn set_direct_interest(e: &Env, direct: bool) {
+    if direct && !storage::get_direct_interest(e) {
+        let backstop = storage::get_backstop(e);
+        // the auction's lot is fixed on creation, so it must be filled under the auction mode
+        if storage::has_auction(e, &(AuctionType::InterestAuction as u32), &backstop) {
+            panic_with_error!(e, PoolError::AuctionInProgress);
+        }
+
+        let mut pool = Pool::load(e);
+        let available_credit = load_available_credit(e, &mut pool);
+        if !available_credit.is_empty() {
+            distribute_credit(e, &mut pool, &backstop, &available_credit);
+            pool.store_cached_reserves(e);
+            storage::set_last_sweep(e, &e.ledger().timestamp());
+        }
+    }
+    storage::set_direct_interest(e, &direct);
+}
+
+/// Fetch the backstop credit accrued by each reserve that has not been swept or auctioned,
+/// in underlying tokens. Credit held in escrow for auction fillers is excluded.
+pub fn get_unswept_interest(e: &Env) -> Map<Address, i128> {
+    let mut pool = Pool::load(e);
+    let mut unswept = map![e];
+    for (asset, credit) in load_available_credit(e, &mut pool).iter() {
+        unswept.set(asset.clone(), pool.to_underlying_down(e, &asset, credit));
+    }
+    unswept
+}
+
+/// Distribute the backstop credit accrued by each reserve through the backstop to its depositors
+/// in kind. A sweep can occur once the sweep interval has passed since the last sweep, or earlier
+/// if the value of the credit exceeds the sweep threshold. Sweeps are only available with direct
+/// interest enabled, as the backstop only accounts for reserve assets distributed to its
+/// depositors.
+///
+/// Returns a map of the underlying tokens swept, by asset
+///
+/// ### Panics
+/// If direct interest is not enabled, no sweep config is set, an interest auction is in
+/// progress, there is no credit to sweep, the sweep schedule has not been met, or the backstop
+/// has no depositors
+pub fn execute_sweep_interest(e: &Env) -> Map<Address, i128> {
+    if !storage::get_direct_interest(e) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let config = match storage::get_sweep_config(e) {
+        Some(config) => config,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let backstop = storage::get_backstop(e);
+    // the lot of an interest auction is fixed on creation, so the credit cannot move until it is filled
+    if storage::has_auction(e, &(AuctionType::InterestAuction as u32), &backstop) {
+        panic_with_error!(e, PoolError::AuctionInProgress);
+    }
+
+    let mut pool = Pool::load(e);
+    let available_credit = load_available_credit(e, &mut pool);
+    if available_credit.is_empty() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let now = e.ledger().timestamp();
+    if now < storage::get_last_sweep(e) + config.interval {
+        let mut credit_value: i128 = 0;
+        for (asset, credit) in available_credit.iter() {
+            let reserve = pool.load_reserve(e, &asset);
+            credit_value += pool
+                .load_price(e, &asset)
+                .fixed_mul_floor(credit, reserve.scalar)
+                .unwrap_optimized();
+        }
+        if config.threshold == 0 || credit_value < config.threshold {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+    }
+
+    let swept = distribute_credit(e, &mut pool, &backstop, &available_credit);
+    pool.store_cached_reserves(e);
+    storage::set_last_sweep(e, &now);
+    swept
+}
+
+/// Distribute backstop credit through the backstop to its depositors in kind. The updated
+/// reserves are cached in the pool and must be stored by the caller.
+///
+/// Returns a map of the underlying tokens distributed, by asset
+fn distribute_credit(
+    e: &Env,
+    pool: &mut Pool,
+    backstop: &Address,
+    available_credit: &Map<Address, i128>,
+) -> Map<Address, i128> {
+    let backstop_client = VersionedBackstopClient::new(e, backstop);
+    let mut swept = map![e];
+    for (asset, credit) in available_credit.iter() {
+        let mut reserve = pool.load_reserve(e, &asset);
+        reserve.backstop_credit -= credit;
+        pool.cache_reserve(reserve, true);
+
+        let amount_out = pool.to_underlying_down(e, &asset, credit);
+        TokenClient::new(e, &asset).approve(
+            &e.current_contract_address(),
+            backstop,
+            &amount_out,
+            &e.ledger().sequence(),
+        );
+        backstop_client.distribute_pool_interest(
+            &e.current_contract_address(),
+            &asset,
+            &amount_out,
+        );
+        swept.set(asset.clone(), amount_out);
+
+        e.events().publish(
+            (Symbol::new(e, "sweep_interest"), asset),
+            (backstop.clone(), amount_out),
+        );
+    }
+    swept
+}
+
+/// Load the backstop credit available to sweep for each reserve, excluding escrowed credit
+fn load_available_credit(e: &Env, pool: &mut Pool) -> Map<Address, i128> {
+    let mut available_credit = map![e];
+    for asset in storage::get_res_list(e).iter() {
+        let reserve = pool.load_reserve(e, &asset);
+        let credit = reserve.backstop_credit - storage::get_escrow_total(e, &asset);
+        if credit > 0 {
+            available_credit.set(asset.clone(), credit);
+        }
+        pool.cache_reserve(reserve, false);
+    }
+    available_credit
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{auctions::AuctionData, storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_sweep_interest() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (backstop_token, backstop_token_client) =
+            testutils::create_token_contract(&e, &bombadil);
+        let (backstop, backstop_client) = testutils::create_backstop(&e);
+        testutils::setup_backstop(
+            &e,
+            &pool_address,
+            &backstop,
+            &backstop_token,
+            &Address::random(&e),
+        );
+        backstop_token_client.mint(&frodo, &100_0000000);
+        backstop_client.deposit(&frodo, &pool_address, &100_0000000);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        reserve_data.backstop_credit = 10_0000000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_0_client.mint(&pool_address, &10_0000000);
+        oracle_client.set_price(&underlying_0, &2_0000000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_direct_interest(&e, &true);
+            storage::set_escrow_total(&e, &underlying_0, 4_0000000);
+            storage::set_user_escrow(&e, &samwise, &map![&e, (underlying_0.clone(), 4_0000000)]);
+            set_sweep_config(&e, 7 * 24 * 60 * 60, 10_0000000);
+
+            let unswept = get_unswept_interest(&e);
+            assert_eq!(unswept.get_unchecked(underlying_0.clone()), 6_0000000);
+
+            // the credit is worth 12, so the threshold allows an early sweep
+            let swept = execute_sweep_interest(&e);
+            assert_eq!(swept.get_unchecked(underlying_0.clone()), 6_0000000);
+            assert_eq!(underlying_0_client.balance(&backstop), 6_0000000);
+            assert_eq!(storage::get_last_sweep(&e), 12345);
+            let reserve_data = storage::get_res_data(&e, &underlying_0);
+            assert_eq!(reserve_data.backstop_credit, 4_0000000);
+            assert!(get_unswept_interest(&e).is_empty());
+        });
+
+        // the interest is held by the backstop for its depositors
+        let claimed = backstop_client.claim_pool_interest(&frodo, &pool_address, &frodo);
+        assert_eq!(claimed.get_unchecked(underlying_0.clone()), 6_0000000);
+        assert_eq!(underlying_0_client.balance(&frodo), 6_0000000);
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_sweep_interest_not_direct() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let backstop = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        reserve_data.backstop_credit = 10_0000000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_0_client.mint(&pool_address, &10_0000000);
+        oracle_client.set_price(&underlying_0, &1_0000000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop);
+            set_sweep_config(&e, 60 * 60, 0);
+
+            execute_sweep_interest(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_sweep_interest_before_schedule() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let backstop = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        reserve_data.backstop_credit = 10_0000000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_0_client.mint(&pool_address, &10_0000000);
+        oracle_client.set_price(&underlying_0, &1_0000000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop);
+            storage::set_direct_interest(&e, &true);
+            storage::set_last_sweep(&e, &10000);
+            set_sweep_config(&e, 7 * 24 * 60 * 60, 100_0000000);
+
+            execute_sweep_interest(&e);
+        });
+    }
+
+    #[test]
+    fn test_set_direct_interest_settles_credit() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (backstop_token, backstop_token_client) =
+            testutils::create_token_contract(&e, &bombadil);
+        let (backstop, backstop_client) = testutils::create_backstop(&e);
+        testutils::setup_backstop(
+            &e,
+            &pool_address,
+            &backstop,
+            &backstop_token,
+            &Address::random(&e),
+        );
+        backstop_token_client.mint(&frodo, &100_0000000);
+        backstop_client.deposit(&frodo, &pool_address, &100_0000000);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        reserve_data.backstop_credit = 10_0000000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+        underlying_0_client.mint(&pool_address, &10_0000000);
+        oracle_client.set_price(&underlying_0, &1_0000000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            // the credit accrued for interest auctions is distributed when switching modes
+            set_direct_interest(&e, true);
+            assert!(storage::get_direct_interest(&e));
+            assert_eq!(underlying_0_client.balance(&backstop), 10_0000000);
+            assert_eq!(storage::get_last_sweep(&e), 12345);
+            let reserve_data = storage::get_res_data(&e, &underlying_0);
+            assert_eq!(reserve_data.backstop_credit, 0);
+
+            set_direct_interest(&e, false);
+            assert!(!storage::get_direct_interest(&e));
+        });
+
+        let claimed = backstop_client.claim_pool_interest(&frodo, &pool_address, &frodo);
+        assert_eq!(claimed.get_unchecked(underlying_0.clone()), 10_0000000);
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(103)")]
+    fn test_set_direct_interest_auction_in_progress() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        testutils::set_timestamp(&e, 12345);
+
+        let bombadil = Address::random(&e);
+        let backstop = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        reserve_data.backstop_credit = 10_0000000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let auction_data = AuctionData {
+            bid: map![&e],
+            lot: map![&e, (underlying_0.clone(), 10_0000000)],
+            block: 100,
+            timestamp: 12345,
+            mode: 0,
+            valuation: 0,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_auction(
+                &e,
+                &(AuctionType::InterestAuction as u32),
+                &backstop,
+                &auction_data,
+            );
+
+            set_direct_interest(&e, true);
+        });
+    }
+}
@@ -0,0 +1,281 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{SCALAR_7, SECONDS_PER_YEAR},
+    errors::PoolError,
+    storage::{self, TrustedBorrower},
+};
+
+use super::{Pool, User};
+
+/// Set or update the credit facility for a trusted borrower. Any premium owed under the
+/// previous facility is accrued before the update.
+///
+/// Only available in pools built with the `trusted-borrowers` feature.
+///
+/// ### Arguments
+/// * `borrower` - The address of the borrower
+/// * `cap` - The maximum uncollateralized liability value, denominated in the oracle's base asset
+/// * `premium` - The additional interest rate charged on liabilities, expressed in 7 decimals
+///
+/// ### Panics
+/// If the feature is not enabled or the facility parameters are invalid
+pub fn set_trusted_borrower(e: &Env, borrower: &Address, cap: i128, premium: u32) {
+    require_trusted_borrowers_enabled(e);
+    if cap < 0 || premium > 1_0000000 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let mut pool = Pool::load(e);
+    let mut user = User::load(e, borrower);
+    accrue_trusted_premium(e, &mut pool, &mut user);
+    pool.store_cached_reserves(e);
+    user.store(e);
+
+    storage::set_trusted_borrower(
+        e,
+        borrower,
+        &TrustedBorrower {
+            cap,
+            premium,
+            last_time: e.ledger().timestamp(),
+        },
+    );
+}
+
+/// Remove the credit facility for a trusted borrower. Any premium owed is accrued before
+/// the removal, after which the borrower's positions are subject to the normal health factor.
+///
+/// ### Arguments
+/// * `borrower` - The address of the borrower
+///
+/// ### Panics
+/// If the feature is not enabled or the borrower does not have a facility
+pub fn remove_trusted_borrower(e: &Env, borrower: &Address) {
+    require_trusted_borrowers_enabled(e);
+    if storage::get_trusted_borrower(e, borrower).is_none() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let mut pool = Pool::load(e);
+    let mut user = User::load(e, borrower);
+    accrue_trusted_premium(e, &mut pool, &mut user);
+    pool.store_cached_reserves(e);
+    user.store(e);
+
+    storage::del_trusted_borrower(e, borrower);
+}
+
+/// Accrue the interest premium for a trusted borrower by adding dTokens to each of their
+/// liabilities. Does nothing if the user does not have a credit facility.
+///
+/// The premium is accounted for as regular liabilities, so it is repaid to suppliers as interest.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `user` - The user to accrue the premium for
+pub fn accrue_trusted_premium(e: &Env, pool: &mut Pool, user: &mut User) {
+    let mut trusted = match storage::get_trusted_borrower(e, &user.address) {
+        Some(trusted) => trusted,
+        None => return,
+    };
+    let cur_time = e.ledger().timestamp();
+    if cur_time == trusted.last_time {
+        return;
+    }
+
+    let time_weighted_premium = i128(trusted.premium) * i128(cur_time - trusted.last_time);
+    if time_weighted_premium > 0 {
+        let reserve_list = storage::get_res_list(e);
+        for (reserve_index, d_tokens) in user.positions.liabilities.iter() {
+            let premium_d_tokens = d_tokens
+                .fixed_mul_ceil(time_weighted_premium, SCALAR_7 * SECONDS_PER_YEAR)
+                .unwrap_optimized();
+            if premium_d_tokens > 0 {
+                let mut reserve = pool.load_reserve(e, &reserve_list.get_unchecked(reserve_index));
+                user.add_liabilities(e, &mut reserve, premium_d_tokens);
+                pool.cache_reserve(reserve, true);
+            }
+        }
+    }
+
+    trusted.last_time = cur_time;
+    storage::set_trusted_borrower(e, &user.address, &trusted);
+}
+
+/// Fetch the uncollateralized credit available to a user, denominated in the oracle's base asset
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_trusted_credit(e: &Env, user: &Address) -> i128 {
+    match storage::get_trusted_borrower(e, user) {
+        Some(trusted) => trusted.cap,
+        None => 0,
+    }
+}
+
+fn require_trusted_borrowers_enabled(e: &Env) {
+    if !cfg!(feature = "trusted-borrowers") {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{storage::PoolConfig, testutils};
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    #[test]
+    fn test_accrue_trusted_premium() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 31536000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 31536000,
+            protocol_version: 1,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &crate::pool::Positions {
+                    liabilities: map![&e, (0, 10_0000000)],
+                    collateral: map![&e],
+                    supply: map![&e],
+                },
+            );
+            storage::set_trusted_borrower(
+                &e,
+                &samwise,
+                &TrustedBorrower {
+                    cap: 1000_0000000,
+                    premium: 0_0500000,
+                    last_time: 0,
+                },
+            );
+
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            accrue_trusted_premium(&e, &mut pool, &mut user);
+            pool.store_cached_reserves(&e);
+
+            assert_eq!(user.get_liabilities(0), 10_5000000);
+            let reserve_data = storage::get_res_data(&e, &underlying_0);
+            assert_eq!(reserve_data.d_supply, 75_0000000 + 0_5000000);
+            let trusted = storage::get_trusted_borrower(&e, &samwise).unwrap_optimized();
+            assert_eq!(trusted.last_time, 31536000);
+            assert_eq!(get_trusted_credit(&e, &samwise), 1000_0000000);
+        });
+    }
+
+    #[test]
+    fn test_accrue_trusted_premium_untrusted_user() {
+        let e = Env::default();
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &crate::pool::Positions {
+                    liabilities: map![&e, (0, 10_0000000)],
+                    collateral: map![&e],
+                    supply: map![&e],
+                },
+            );
+
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            accrue_trusted_premium(&e, &mut pool, &mut user);
+
+            assert_eq!(user.get_liabilities(0), 10_0000000);
+            assert_eq!(get_trusted_credit(&e, &samwise), 0);
+        });
+    }
+
+    #[cfg(feature = "trusted-borrowers")]
+    #[test]
+    fn test_set_and_remove_trusted_borrower() {
+        let e = Env::default();
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_200_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            set_trusted_borrower(&e, &samwise, 500_0000000, 0_0200000);
+            let trusted = storage::get_trusted_borrower(&e, &samwise).unwrap_optimized();
+            assert_eq!(trusted.cap, 500_0000000);
+            assert_eq!(trusted.premium, 0_0200000);
+
+            remove_trusted_borrower(&e, &samwise);
+            assert!(storage::get_trusted_borrower(&e, &samwise).is_none());
+        });
+    }
+
+    #[cfg(not(feature = "trusted-borrowers"))]
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_trusted_borrower_feature_disabled() {
+        let e = Env::default();
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        e.as_contract(&pool_address, || {
+            set_trusted_borrower(&e, &samwise, 500_0000000, 0_0200000);
+        });
+    }
+}
@@ -1,8 +1,8 @@
-use soroban_sdk::{contracttype, Address, Env, Map};
+use soroban_sdk::{contracttype, Address, Env, Map, Vec};
 
 use crate::{emissions, storage, validator::require_nonnegative};
 
-use super::{Pool, Reserve};
+use super::{exposure::update_exposure, Pool, Reserve};
 
 /// A user / contracts position's with the pool, stored in the Reserve's decimals
 #[derive(Clone)]
@@ -22,6 +22,47 @@ impl Positions {
             supply: Map::new(e),
         }
     }
+
+    /// Check if the reserve at the given index is used as collateral
+    pub fn is_collateral(&self, reserve_index: u32) -> bool {
+        self.collateral.get(reserve_index).unwrap_or(0) > 0
+    }
+
+    /// Check if the reserve at the given index is being borrowed
+    pub fn is_borrowing(&self, reserve_index: u32) -> bool {
+        self.liabilities.get(reserve_index).unwrap_or(0) > 0
+    }
+
+    /// Check if any of the collateral is in an isolated reserve, such that borrowing is limited
+    /// by the pool's isolation config
+    pub fn is_isolated(&self, e: &Env) -> bool {
+        if self.collateral.is_empty() {
+            return false;
+        }
+        let reserve_list = storage::get_res_list(e);
+        for (reserve_index, _) in self.collateral.iter() {
+            if storage::get_res_config(e, &reserve_list.get_unchecked(reserve_index)).isolated {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Fetch the indexes of the reserves with any liability, collateral or supply position,
+    /// sorted in ascending order
+    pub fn active_reserves(&self, e: &Env) -> Vec<u32> {
+        let mut active = Map::<u32, bool>::new(e);
+        for (index, _) in self.liabilities.iter() {
+            active.set(index, true);
+        }
+        for (index, _) in self.collateral.iter() {
+            active.set(index, true);
+        }
+        for (index, _) in self.supply.iter() {
+            active.set(index, true);
+        }
+        active.keys()
+    }
 }
 
 /// A user / contracts position's with the pool
@@ -51,18 +92,25 @@ impl User {
     }
 
     /// Add liabilities to the position expressed in debtTokens. Accrues emissions
-    /// against the balance if necessary and updates the reserve's d_supply.
+    /// against the balance if necessary and updates the reserve's d_supply. If this is the
+    /// user's first liability, their collateral is tracked against each reserve's exposure.
     pub fn add_liabilities(&mut self, e: &Env, reserve: &mut Reserve, amount: i128) {
         let balance = self.get_liabilities(reserve.index);
         self.update_d_emissions(e, reserve, balance);
+        let opened = self.positions.liabilities.is_empty();
         self.positions
             .liabilities
             .set(reserve.index, balance + amount);
         reserve.d_supply += amount;
+        if opened {
+            self.update_collateral_exposure(e, 1);
+        }
     }
 
     /// Remove liabilities from the position expressed in debtTokens. Accrues emissions
-    /// against the balance if necessary and updates the reserve's d_supply.
+    /// against the balance if necessary and updates the reserve's d_supply. If this is the
+    /// user's last liability, their collateral is no longer tracked against any reserve's
+    /// exposure.
     pub fn remove_liabilities(&mut self, e: &Env, reserve: &mut Reserve, amount: i128) {
         let balance = self.get_liabilities(reserve.index);
         self.update_d_emissions(e, reserve, balance);
@@ -74,6 +122,9 @@ impl User {
             self.positions.liabilities.set(reserve.index, new_balance);
         }
         reserve.d_supply -= amount;
+        if self.positions.liabilities.is_empty() {
+            self.update_collateral_exposure(e, -1);
+        }
     }
 
     /// Get the collateralized blendToken position for the reserve at the given index
@@ -82,7 +133,8 @@ impl User {
     }
 
     /// Add collateral to the position expressed in blendTokens. Accrues emissions
-    /// against the balance if necessary and updates the reserve's b_supply.
+    /// against the balance if necessary and updates the reserve's b_supply and, if the
+    /// user has liabilities, its exposure.
     pub fn add_collateral(&mut self, e: &Env, reserve: &mut Reserve, amount: i128) {
         let balance = self.get_collateral(reserve.index);
         self.update_b_emissions(e, reserve, self.get_total_supply(reserve.index));
@@ -90,10 +142,14 @@ impl User {
             .collateral
             .set(reserve.index, balance + amount);
         reserve.b_supply += amount;
+        if !self.positions.liabilities.is_empty() {
+            update_exposure(e, &reserve.asset, amount);
+        }
     }
 
     /// Remove collateral from the position expressed in blendTokens. Accrues emissions
-    /// against the balance if necessary and updates the reserve's d_supply.
+    /// against the balance if necessary and updates the reserve's d_supply and, if the
+    /// user has liabilities, its exposure.
     pub fn remove_collateral(&mut self, e: &Env, reserve: &mut Reserve, amount: i128) {
         let balance = self.get_collateral(reserve.index);
         self.update_b_emissions(e, reserve, self.get_total_supply(reserve.index));
@@ -105,6 +161,9 @@ impl User {
             self.positions.collateral.set(reserve.index, new_balance);
         }
         reserve.b_supply -= amount;
+        if !self.positions.liabilities.is_empty() {
+            update_exposure(e, &reserve.asset, -amount);
+        }
     }
 
     /// Get the uncollateralized blendToken position for the reserve at the given index
@@ -181,6 +240,22 @@ impl User {
         }
     }
 
+    /// Add (sign = 1) or remove (sign = -1) all of the user's collateral from the exposure
+    /// of each collateral reserve
+    fn update_collateral_exposure(&self, e: &Env, sign: i128) {
+        if self.positions.collateral.is_empty() {
+            return;
+        }
+        let reserve_list = storage::get_res_list(e);
+        for (reserve_index, b_tokens) in self.positions.collateral.iter() {
+            update_exposure(
+                e,
+                &reserve_list.get_unchecked(reserve_index),
+                sign * b_tokens,
+            );
+        }
+    }
+
     fn update_d_emissions(&self, e: &Env, reserve: &Reserve, amount: i128) {
         emissions::update_emissions(
             e,
@@ -196,7 +271,7 @@ impl User {
         emissions::update_emissions(
             e,
             reserve.index * 2 + 1,
-            reserve.b_supply,
+            emissions::get_b_emission_supply(e, &reserve.asset, reserve.b_supply),
             reserve.scalar,
             &self.address,
             amount,
@@ -242,6 +317,30 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_positions_introspection() {
+        let e = Env::default();
+
+        let positions = Positions {
+            liabilities: map![&e, (3, 1000)],
+            collateral: map![&e, (0, 10000), (3, 500)],
+            supply: map![&e, (5, 1234)],
+        };
+
+        assert!(positions.is_collateral(0));
+        assert!(positions.is_collateral(3));
+        assert!(!positions.is_collateral(5));
+        assert!(positions.is_borrowing(3));
+        assert!(!positions.is_borrowing(0));
+        assert!(!positions.is_borrowing(7));
+
+        let active = positions.active_reserves(&e);
+        assert_eq!(active.len(), 3);
+        assert_eq!(active.get_unchecked(0), 0);
+        assert_eq!(active.get_unchecked(1), 3);
+        assert_eq!(active.get_unchecked(2), 5);
+    }
+
     #[test]
     fn test_liabilities() {
         let e = Env::default();
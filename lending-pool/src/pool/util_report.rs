@@ -0,0 +1,150 @@
+use cast::i128;
+use soroban_sdk::{contracttype, vec, Address, Env, Vec};
+
+use crate::storage;
+
+use super::pool::Pool;
+
+/// A report of a reserve's realized utilization against its target utilization
+#[derive(Clone)]
+#[contracttype]
+pub struct UtilReport {
+    pub target_util: i128, // the target utilization of the reserve, expressed in 7 decimals
+    pub reactivity: i128,  // the reactivity of the rate modifier, expressed in 9 decimals
+    pub util: i128,        // the current utilization, expressed in 7 decimals
+    pub avg_util: i128, // the time weighted average utilization over the window, expressed in 7 decimals
+    pub window: u64,    // the length of the window, in seconds
+    pub ir_mods: Vec<i128>, // the rate modifier at each sample and currently, expressed in 9 decimals
+}
+
+/// Build a report of a reserve's realized utilization over the window of utilization samples
+/// recorded with the pool heartbeat, along with the trajectory of its rate modifier.
+///
+/// Each sample's utilization is weighted by the time until the next sample, with the most
+/// recent sample weighted until the current time. If no time has passed over the window,
+/// the average utilization is the current utilization.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If the reserve does not exist
+pub fn build_util_report(e: &Env, asset: &Address) -> UtilReport {
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset);
+    let reserve_config = storage::get_res_config(e, asset);
+    let util = if reserve.b_supply == 0 {
+        0
+    } else {
+        reserve.utilization()
+    };
+
+    let now = e.ledger().timestamp();
+    let history = storage::get_util_history(e, asset);
+    let mut ir_mods = vec![e];
+    let mut util_time: i128 = 0;
+    let mut window: u64 = 0;
+    for (i, sample) in history.iter().enumerate() {
+        let end = match history.get(i as u32 + 1) {
+            Some(next) => next.timestamp,
+            None => now,
+        };
+        let duration = end - sample.timestamp;
+        util_time += sample.util * i128(duration);
+        window += duration;
+        ir_mods.push_back(sample.ir_mod);
+    }
+    ir_mods.push_back(reserve.ir_mod);
+
+    let avg_util = if window == 0 {
+        util
+    } else {
+        util_time / i128(window)
+    };
+    UtilReport {
+        target_util: i128(reserve_config.util),
+        reactivity: i128(reserve_config.reactivity),
+        util,
+        avg_util,
+        window,
+        ir_mods,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        storage::{PoolConfig, UtilSample},
+        testutils,
+    };
+
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    #[test]
+    fn test_build_util_report() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 10000;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let history = vec![
+            &e,
+            UtilSample {
+                timestamp: 7000,
+                util: 0_5000000,
+                ir_mod: 0_900_000_000,
+            },
+            UtilSample {
+                timestamp: 8000,
+                util: 0_9000000,
+                ir_mod: 0_950_000_000,
+            },
+        ];
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_util_history(&e, &underlying, &history);
+
+            let report = build_util_report(&e, &underlying);
+            assert_eq!(report.target_util, 0_7500000);
+            assert_eq!(report.util, 0_7500000);
+            assert_eq!(report.window, 3000);
+            // 0.5 for 1000 seconds and 0.9 for 2000 seconds
+            assert_eq!(report.avg_util, 0_7666666);
+            assert_eq!(
+                report.ir_mods,
+                vec![&e, 0_900_000_000, 0_950_000_000, 1_000_000_000]
+            );
+        });
+    }
+}
@@ -0,0 +1,293 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{LARGE_WITHDRAW_DELAY, SCALAR_7},
+    errors::PoolError,
+    storage::{self, QueuedWithdrawal},
+};
+
+use super::{pool::Pool, user::User};
+
+/// Set the maximum utilization rate allowed after a withdrawal from a reserve. Withdrawals
+/// that would push utilization above the cap are rejected. A cap of zero removes the guard.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `max_util` - The maximum utilization rate after a withdrawal, expressed in 7 decimals
+///
+/// ### Panics
+/// If the reserve does not exist, or the cap is below the reserve's max utilization or above 100%
+pub fn set_reserve_withdraw_util(e: &Env, asset: &Address, max_util: u32) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if max_util == 0 {
+        storage::del_res_withdraw_util(e, asset);
+        return;
+    }
+
+    let reserve_config = storage::get_res_config(e, asset);
+    if max_util < reserve_config.max_util || i128(max_util) > SCALAR_7 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_res_withdraw_util(e, asset, &max_util);
+}
+
+/// Set the amount of underlying tokens above which a withdrawal from a reserve must be queued
+/// before it can be executed. A threshold of zero removes the guard.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `threshold` - The amount of underlying tokens
+///
+/// ### Panics
+/// If the reserve does not exist or the threshold is negative
+pub fn set_reserve_large_withdrawal(e: &Env, asset: &Address, threshold: i128) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if threshold < 0 {
+        panic_with_error!(e, PoolError::NegativeAmount);
+    }
+    if threshold == 0 {
+        storage::del_res_large_withdrawal(e, asset);
+    } else {
+        storage::set_res_large_withdrawal(e, asset, &threshold);
+    }
+}
+
+/// Queue a withdrawal from a reserve above the reserve's large withdrawal threshold. The
+/// withdrawal can be submitted once the delay has passed. Replaces any existing queued
+/// withdrawal for the reserve.
+///
+/// ### Arguments
+/// * `from` - The address of the user
+/// * `asset` - The underlying asset of the reserve
+/// * `amount` - The maximum amount of underlying tokens to withdraw
+///
+/// ### Panics
+/// If the reserve does not exist or the amount is not positive
+pub fn execute_queue_withdrawal(
+    e: &Env,
+    from: &Address,
+    asset: &Address,
+    amount: i128,
+) -> QueuedWithdrawal {
+    if amount <= 0 || !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let reserve_config = storage::get_res_config(e, asset);
+    let withdrawal = QueuedWithdrawal {
+        amount,
+        unlock_time: e.ledger().timestamp() + LARGE_WITHDRAW_DELAY,
+    };
+    storage::set_queued_withdrawal(e, from, reserve_config.index, &withdrawal);
+    withdrawal
+}
+
+/// Cancel a user's queued withdrawal from a reserve
+///
+/// ### Arguments
+/// * `from` - The address of the user
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If the reserve does not exist or no withdrawal is queued
+pub fn execute_cancel_withdrawal(e: &Env, from: &Address, asset: &Address) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let reserve_config = storage::get_res_config(e, asset);
+    if storage::get_queued_withdrawal(e, from, reserve_config.index).is_none() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::del_queued_withdrawal(e, from, reserve_config.index);
+}
+
+/// Require that a withdrawal above the reserve's large withdrawal threshold was queued and its
+/// delay has passed, consuming the queued withdrawal. Withdrawals at or below the threshold
+/// are not affected.
+///
+/// ### Arguments
+/// * `from` - The address of the user
+/// * `asset` - The underlying asset of the reserve
+/// * `reserve_id` - The index of the reserve
+/// * `amount` - The amount of underlying tokens withdrawn
+///
+/// ### Panics
+/// If the withdrawal exceeds the threshold and no matching withdrawal is unlocked
+pub fn require_large_withdrawal_unlocked(
+    e: &Env,
+    from: &Address,
+    asset: &Address,
+    reserve_id: u32,
+    amount: i128,
+) {
+    match storage::get_res_large_withdrawal(e, asset) {
+        Some(threshold) if amount > threshold => {}
+        _ => return,
+    }
+    match storage::get_queued_withdrawal(e, from, reserve_id) {
+        Some(withdrawal)
+            if withdrawal.amount >= amount && withdrawal.unlock_time <= e.ledger().timestamp() =>
+        {
+            storage::del_queued_withdrawal(e, from, reserve_id);
+        }
+        _ => panic_with_error!(e, PoolError::WithdrawalNotQueued),
+    }
+}
+
+/// Calculate the maximum amount of underlying tokens a user can withdraw from a reserve. The
+/// amount is limited by the user's supplied and collateralized balance, and the reserve's
+/// available liquidity under its maximum utilization after a withdrawal, if one is set.
+///
+/// Does not consider the health factor of the user.
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `asset` - The underlying asset of the reserve
+pub fn calc_max_withdrawal(e: &Env, user: &Address, asset: &Address) -> i128 {
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset);
+    let user_state = User::load(e, user);
+
+    let position = reserve.to_asset_from_b_token(user_state.get_total_supply(reserve.index));
+    let liabilities = reserve.total_liabilities();
+    let min_supply = match storage::get_res_withdraw_util(e, asset) {
+        Some(max_util) if liabilities > 0 => liabilities
+            .fixed_div_ceil(i128(max_util), SCALAR_7)
+            .unwrap_optimized(),
+        _ => liabilities,
+    };
+    let available = (reserve.total_supply() - min_supply).max(0);
+    pool.to_underlying_down(e, asset, position.min(available))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pool::Positions, storage::PoolConfig, testutils};
+
+    use super::*;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    #[test]
+    fn test_calc_max_withdrawal() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta(&e);
+        reserve_data.last_time = 12345;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::random(&e),
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        let positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 4_0000000)],
+            supply: map![&e, (0, 6_0000000)],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            // limited by the user's position
+            assert_eq!(calc_max_withdrawal(&e, &samwise, &underlying), 10_0000000);
+
+            // limited by the reserve's utilization after withdrawal
+            set_reserve_withdraw_util(&e, &underlying, 0_8000000);
+            assert_eq!(calc_max_withdrawal(&e, &samwise, &underlying), 6_2500000);
+
+            set_reserve_withdraw_util(&e, &underlying, 0);
+            assert!(storage::get_res_withdraw_util(&e, &underlying).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(13)")]
+    fn test_require_utilization_below_withdraw_max_panic() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        e.as_contract(&pool_address, || {
+            set_reserve_withdraw_util(&e, &underlying, 0_9500000);
+
+            let mut reserve = testutils::default_reserve(&e);
+            reserve.asset = underlying.clone();
+            reserve.b_supply = 100_0000000;
+            reserve.d_supply = 95_0000100;
+            reserve.require_utilization_below_withdraw_max(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(2)")]
+    fn test_set_reserve_withdraw_util_below_max_util() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let pool_address = Address::random(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta(&e);
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        e.as_contract(&pool_address, || {
+            set_reserve_withdraw_util(&e, &underlying, 0_9000000);
+        });
+    }
+}
@@ -0,0 +1,341 @@
+use cast::i128;
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::SCALAR_7,
+    emissions,
+    errors::PoolError,
+    pool::{get_trusted_credit, require_allowed, FactorBasis, Pool, PositionData, User},
+    storage::{self, SoftLiqConfig},
+    validator::{require_max_leverage, require_not_denied},
+};
+
+/// Set the soft liquidation band of a collateral reserve. An upper health factor of zero
+/// removes the band.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the collateral reserve
+/// * `config` - The soft liquidation band
+///
+/// ### Panics
+/// If the reserve does not exist, the band does not end at or above a health factor of 1,
+/// the band is empty, or the discount is over 100%
+pub fn set_soft_liquidation(e: &Env, asset: &Address, config: &SoftLiqConfig) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if config.upper_hf == 0 {
+        storage::del_soft_liq_config(e, asset);
+        return;
+    }
+    if i128(config.lower_hf) < SCALAR_7
+        || config.upper_hf <= config.lower_hf
+        || i128(config.max_discount) > SCALAR_7
+    {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_soft_liq_config(e, asset, config);
+}
+
+/// Soft liquidate a user whose health factor is within the soft liquidation band of a collateral
+/// reserve. The filler assumes a portion of the user's liabilities and receives the user's
+/// collateral at a discount. The discount scales linearly from zero at the top of the band to
+/// the band's max discount at the bottom, such that collateral is gradually converted as prices
+/// move through the band rather than all at once through a liquidation auction.
+///
+/// Users below the band can only be liquidated through a liquidation auction.
+///
+/// Returns the amount of collateral b_tokens transferred to the filler
+///
+/// ### Arguments
+/// * `filler` - The address assuming the liabilities
+/// * `user` - The user being soft liquidated
+/// * `collateral_asset` - The underlying asset of the collateral reserve with the band
+/// * `liability_asset` - The underlying asset of the liability being repaid
+/// * `d_tokens` - The amount of the user's d_tokens to assume
+///
+/// ### Panics
+/// If the collateral reserve has no band, the user is not within the band, the conversion
+/// does not improve the user's health factor, or the filler is unhealthy afterwards
+pub fn execute_soft_liquidate(
+    e: &Env,
+    filler: &Address,
+    user: &Address,
+    collateral_asset: &Address,
+    liability_asset: &Address,
+    d_tokens: i128,
+) -> i128 {
+    let config = match storage::get_soft_liq_config(e, collateral_asset) {
+        Some(config) => config,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    if filler == user || d_tokens <= 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    // permissioned pools only allow allowlisted fillers to acquire collateral
+    require_allowed(e, filler);
+    require_not_denied(e, filler);
+
+    let mut pool = Pool::load(e);
+    let mut user_state = User::load(e, user);
+    let position_data = PositionData::calculate_for_user(
+        e,
+        &mut pool,
+        user,
+        &user_state.positions,
+        FactorBasis::Liquidation,
+    );
+    let health_factor = to_7_decimals(position_data.as_health_factor(), position_data.scalar);
+    let upper_hf = i128(config.upper_hf);
+    let lower_hf = i128(config.lower_hf);
+    if health_factor >= upper_hf || health_factor < lower_hf {
+        panic_with_error!(e, PoolError::InvalidLiquidation);
+    }
+
+    // the discount grows as the health factor moves through the band
+    let band_progress = (upper_hf - health_factor)
+        .fixed_div_floor(upper_hf - lower_hf, SCALAR_7)
+        .unwrap_optimized();
+    let discount = i128(config.max_discount)
+        .fixed_mul_floor(band_progress, SCALAR_7)
+        .unwrap_optimized();
+
+    let liability_reserve = pool.load_reserve(e, liability_asset);
+    let d_tokens = d_tokens.min(user_state.get_liabilities(liability_reserve.index));
+    if d_tokens == 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let liability_value = pool
+        .load_price(e, liability_asset)
+        .fixed_mul_floor(
+            liability_reserve.to_asset_from_d_token(d_tokens),
+            liability_reserve.scalar,
+        )
+        .unwrap_optimized();
+
+    let collateral_reserve = pool.load_reserve(e, collateral_asset);
+    let collateral_value = liability_value
+        .fixed_mul_floor(SCALAR_7 + discount, SCALAR_7)
+        .unwrap_optimized();
+    let collateral_amount = collateral_value
+        .fixed_div_floor(
+            pool.load_price(e, collateral_asset),
+            collateral_reserve.scalar,
+        )
+        .unwrap_optimized();
+    let b_tokens = collateral_reserve
+        .to_b_token_down(collateral_amount)
+        .min(user_state.get_collateral(collateral_reserve.index));
+
+    let lot = map![e, (collateral_asset.clone(), b_tokens)];
+    let bid = map![e, (liability_asset.clone(), d_tokens)];
+    user_state.rm_positions(e, &mut pool, lot.clone(), bid.clone());
+    let new_position_data = PositionData::calculate_for_user(
+        e,
+        &mut pool,
+        user,
+        &user_state.positions,
+        FactorBasis::Liquidation,
+    );
+    if new_position_data.liability_base > 0
+        && new_position_data.as_health_factor() <= position_data.as_health_factor()
+    {
+        panic_with_error!(e, PoolError::InvalidLiquidation);
+    }
+
+    let mut filler_state = User::load(e, filler);
+    filler_state.add_positions(e, &mut pool, lot.clone(), bid);
+    let credit = get_trusted_credit(e, filler);
+    let filler_data = PositionData::calculate_for_user(
+        e,
+        &mut pool,
+        filler,
+        &filler_state.positions,
+        FactorBasis::Borrow,
+    );
+    filler_data.require_healthy_with_credit(e, credit);
+    require_max_leverage(e, &filler_data, credit);
+
+    emissions::settle_liquidated_emissions(e, &user_state, filler, &lot);
+    pool.store_cached_reserves(e);
+    user_state.store(e);
+    filler_state.store(e);
+    b_tokens
+}
+
+/// Convert a health factor expressed in the oracle's decimals to 7 decimals
+fn to_7_decimals(health_factor: i128, oracle_scalar: i128) -> i128 {
+    health_factor
+        .fixed_div_floor(oracle_scalar, SCALAR_7)
+        .unwrap_optimized()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pool::Positions,
+        storage::{PoolConfig, ReserveConfig, ReserveData},
+        testutils,
+    };
+
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    fn setup_band(
+        e: &Env,
+        pool_address: &Address,
+        bombadil: &Address,
+    ) -> (Address, Address, PoolConfig) {
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 1,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_expiration: 10,
+            min_persistent_entry_expiration: 10,
+            max_entry_expiration: 2000000,
+        });
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(e);
+
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(e, bombadil);
+        let (reserve_config_0, reserve_data_0) = reserve_meta(e, 0);
+        testutils::create_reserve(
+            e,
+            pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(e, bombadil);
+        let (reserve_config_1, reserve_data_1) = reserve_meta(e, 1);
+        testutils::create_reserve(
+            e,
+            pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        oracle_client.set_price(&underlying_0, &1_0000000);
+        oracle_client.set_price(&underlying_1, &1_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_100_000_000,
+            status: 0,
+            liq_hf_low: 1_0000000,
+            liq_hf_high: 1_0000100,
+        };
+        (underlying_0, underlying_1, pool_config)
+    }
+
+    fn reserve_meta(e: &Env, index: u32) -> (ReserveConfig, ReserveData) {
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta(e);
+        reserve_config.index = index;
+        reserve_data.last_time = 12345;
+        (reserve_config, reserve_data)
+    }
+
+    #[test]
+    fn test_soft_liquidate() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let (underlying_0, underlying_1, pool_config) = setup_band(&e, &pool_address, &bombadil);
+
+        // health factor of 1.05
+        let samwise_positions = Positions {
+            liabilities: map![&e, (1, 7_5000000)],
+            collateral: map![&e, (0, 14_0000000)],
+            supply: map![&e],
+        };
+        let frodo_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        let config = SoftLiqConfig {
+            upper_hf: 1_1000000,
+            lower_hf: 1_0000000,
+            max_discount: 0_0400000,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &samwise_positions);
+            storage::set_user_positions(&e, &frodo, &frodo_positions);
+            set_soft_liquidation(&e, &underlying_0, &config);
+
+            // halfway through the band, so half of the max discount
+            let b_tokens = execute_soft_liquidate(
+                &e,
+                &frodo,
+                &samwise,
+                &underlying_0,
+                &underlying_1,
+                1_0000000,
+            );
+            assert_eq!(b_tokens, 1_0200000);
+
+            let samwise_positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(samwise_positions.collateral.get_unchecked(0), 12_9800000);
+            assert_eq!(samwise_positions.liabilities.get_unchecked(1), 6_5000000);
+            let frodo_positions = storage::get_user_positions(&e, &frodo);
+            assert_eq!(frodo_positions.collateral.get_unchecked(0), 11_0200000);
+            assert_eq!(frodo_positions.liabilities.get_unchecked(1), 1_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    //#[should_panic(expected = "ContractError(100)")]
+    fn test_soft_liquidate_above_band() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::random(&e);
+        let samwise = Address::random(&e);
+        let frodo = Address::random(&e);
+        let pool_address = Address::random(&e);
+        let (underlying_0, underlying_1, pool_config) = setup_band(&e, &pool_address, &bombadil);
+
+        // health factor of 1.2
+        let samwise_positions = Positions {
+            liabilities: map![&e, (1, 7_5000000)],
+            collateral: map![&e, (0, 16_0000000)],
+            supply: map![&e],
+        };
+        let frodo_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        let config = SoftLiqConfig {
+            upper_hf: 1_1000000,
+            lower_hf: 1_0000000,
+            max_discount: 0_0400000,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &samwise_positions);
+            storage::set_user_positions(&e, &frodo, &frodo_positions);
+            set_soft_liquidation(&e, &underlying_0, &config);
+
+            execute_soft_liquidate(
+                &e,
+                &frodo,
+                &samwise,
+                &underlying_0,
+                &underlying_1,
+                1_0000000,
+            );
+        });
+    }
+}
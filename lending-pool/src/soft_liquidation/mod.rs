@@ -0,0 +1,2 @@
+mod band;
+pub use band::{execute_soft_liquidate, set_soft_liquidation};
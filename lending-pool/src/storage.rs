@@ -1,8 +1,12 @@
 use soroban_sdk::{
-    contracttype, map, unwrap::UnwrapOptimized, vec, Address, Env, Map, Symbol, Vec,
+    contracttype, map, unwrap::UnwrapOptimized, vec, Address, BytesN, Env, Map, Symbol, Vec,
 };
 
-use crate::{auctions::AuctionData, pool::Positions};
+use crate::{
+    auctions::AuctionData,
+    constants::BACKSTOP_INTERFACE_V1,
+    pool::{AdminAction, Positions},
+};
 
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 34560; // 2 days
 pub(crate) const SHARED_BUMP_AMOUNT: u32 = 69120; // 4 days
@@ -18,6 +22,22 @@ pub struct PoolConfig {
     pub oracle: Address,
     pub bstop_rate: u64, // the rate the backstop takes on accrued debt interest, expressed in 9 decimals
     pub status: u32,
+    pub liq_hf_low: u32, // the health factor a liquidation can be created below, expressed in 7 decimals
+    pub liq_hf_high: u32, // the health factor a liquidation can be deleted above, expressed in 7 decimals
+}
+
+/// The config a pool is initialized with
+#[derive(Clone)]
+#[contracttype]
+pub struct PoolInitConfig {
+    pub admin: Address,
+    pub name: Symbol,
+    pub oracle: Address,
+    pub bstop_rate: u64, // the rate the backstop takes on accrued debt interest, expressed in 9 decimals
+    pub permissioned: bool, // if only allowlisted users can submit requests and fill auctions
+    pub backstop: Address,
+    pub blnd_id: Address,
+    pub usdc_id: Address,
 }
 
 /// The pool's emission config
@@ -42,6 +62,10 @@ pub struct ReserveConfig {
     pub r_two: u32,      // the R2 value in the interest rate formula scaled expressed in 7 decimals
     pub r_three: u32,    // the R3 value in the interest rate formula scaled expressed in 7 decimals
     pub reactivity: u32, // the reactivity constant for the reserve scaled expressed in 9 decimals
+    // the asset the oracle prices the reserve as, if not the reserve's asset
+    pub oracle_asset: Option<Address>,
+    // true if users collateralizing the reserve are limited to the pool's isolation config
+    pub isolated: bool,
 }
 
 /// The data for a reserve asset
@@ -83,6 +107,242 @@ pub struct UserEmissionData {
     pub accrued: i128,
 }
 
+/// An emission stream attached to a reserve b or d token by a third party
+#[derive(Clone)]
+#[contracttype]
+pub struct AttachedEmission {
+    pub id: u32,         // the unique id of the stream
+    pub token: Address,  // the reward token being emitted
+    pub funder: Address, // the address that funded the stream, refunded any unclaimed tokens
+    pub amount: i128,    // the reward tokens deposited for the stream
+    pub claimed: i128,   // the reward tokens claimed from the stream
+    pub eps: u64,        // the reward tokens emitted per second
+    pub expiration: u64, // the time the stream ends
+    pub index: i128,     // the emission index of the stream
+    pub last_time: u64,  // the last time the index was updated
+}
+
+/// The pool's emission vesting config
+#[derive(Clone)]
+#[contracttype]
+pub struct VestingConfig {
+    pub period: u64,  // the length of time claimed emissions stream over, in seconds
+    pub cliff: u64,   // the length of time before any vested emissions can be released, in seconds
+    pub penalty: u32, // the portion of unvested emissions slashed on early exit, expressed in 7 decimals
+}
+
+/// A user's vesting emissions
+#[derive(Clone)]
+#[contracttype]
+pub struct UserVesting {
+    pub locked: i128,   // the amount of emissions that have not been released
+    pub start: u64,     // the time the user began vesting
+    pub last_time: u64, // the last time vested emissions were released
+    pub end: u64,       // the time the locked emissions are fully vested
+}
+
+/// A trusted borrower's uncollateralized credit facility
+#[derive(Clone)]
+#[contracttype]
+pub struct TrustedBorrower {
+    pub cap: i128,    // the max uncollateralized liability value in the oracle's base asset
+    pub premium: u32, // the additional interest rate on liabilities, expressed in 7 decimals
+    pub last_time: u64, // the last time the premium was accrued
+}
+
+/// A queued withdrawal of protocol-owned liquidity from a reserve
+#[derive(Clone)]
+#[contracttype]
+pub struct PolWithdrawal {
+    pub b_tokens: i128,   // the amount of b_tokens to withdraw
+    pub to: Address,      // the address receiving the underlying tokens
+    pub unlock_time: u64, // the time the withdrawal can be executed
+}
+
+/// A user's queued withdrawal from a reserve above the reserve's large withdrawal threshold
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedWithdrawal {
+    pub amount: i128, // the maximum amount of underlying tokens that can be withdrawn
+    pub unlock_time: u64, // the time the withdrawal can be executed
+}
+
+/// The pool's statistics for user liquidation auctions
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionStats {
+    pub created: u32,           // the number of liquidation auctions created
+    pub fills: u32,             // the number of fills, including partial fills
+    pub completed: u32,         // the number of liquidation auctions filled in full
+    pub fill_blocks: u64,       // the total blocks between each auction's start and its fills
+    pub avg_fill_blocks: u32,   // the average blocks between an auction's start and a fill
+    pub liquidated_value: i128, // the total oracle value of the liabilities filled
+}
+
+/// A fill of an auction, kept in the pool's recent auction history
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionRecord {
+    pub user: Address,   // the Address whose assets were auctioned
+    pub filler: Address, // the Address that filled the auction
+    pub block: u32,      // the block the auction was created at
+    pub fill_block: u32, // the block the auction was filled at
+    pub value: i128,     // the oracle value of the reserve assets filled, in the base asset
+    pub completed: bool, // if the fill completed the auction
+}
+
+/// The bids registered to cooperatively fill a user liquidation auction
+#[derive(Clone)]
+#[contracttype]
+pub struct FillBids {
+    pub block: u32,              // the block the first bid was registered at
+    pub bids: Map<Address, u64>, // the percentage of the auction each filler bid for
+}
+
+/// A bad debt auction fill whose backstop token lot is held in escrow by the backstop
+#[derive(Clone)]
+#[contracttype]
+pub struct BadDebtFill {
+    pub filler: Address,         // the Address that filled the auction
+    pub bid: Map<Address, i128>, // the liabilities the filler assumed, in d_tokens
+}
+
+/// The history counters of a user's interactions with the pool
+#[derive(Clone)]
+#[contracttype]
+pub struct UserHistory {
+    pub first_time: u64,   // the time of the user's first submission
+    pub submits: u32,      // the number of submissions the user has made
+    pub liquidations: u32, // the number of liquidation auctions created against the user
+    pub bad_debt: u32,     // the number of times the user's debt was moved to the backstop
+}
+
+/// The schedule on which accrued backstop credit can be swept to the backstop
+#[derive(Clone)]
+#[contracttype]
+pub struct SweepConfig {
+    pub interval: u64,   // the minimum time between sweeps, in seconds
+    pub threshold: i128, // the backstop credit value that allows an early sweep, in the oracle's base asset
+}
+
+/// A sample of a reserve's utilization and rate modifier, recorded with the pool heartbeat
+#[derive(Clone)]
+#[contracttype]
+pub struct UtilSample {
+    pub timestamp: u64, // the time the sample was recorded
+    pub util: i128,     // the utilization of the reserve, expressed in 7 decimals
+    pub ir_mod: i128,   // the interest rate modifier of the reserve, expressed in 9 decimals
+}
+
+/// A version of a reserve's config, along with when and by whom it was set
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveConfigVersion {
+    pub config: ReserveConfig, // the reserve config
+    pub sequence: u32,         // the ledger sequence the config was set at
+    pub timestamp: u64,        // the time the config was set
+    pub actor: Address,        // the address that set the config
+}
+
+/// The soft liquidation band of a collateral reserve. Within the band, a user's collateral in
+/// the reserve can be gradually converted to repay their liabilities.
+#[derive(Clone)]
+#[contracttype]
+pub struct SoftLiqConfig {
+    pub upper_hf: u32, // the health factor the band starts at, expressed in 7 decimals
+    pub lower_hf: u32, // the health factor the band ends at, expressed in 7 decimals
+    pub max_discount: u32, // the collateral discount at the band's end, expressed in 7 decimals
+}
+
+/// The cap on the liability value that can be backed by a collateral reserve pool-wide
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveExposure {
+    pub cap: i128, // the max liability value backed, in the base asset, or 0 for no cap
+    pub collateral: i128, // the blendTokens held as collateral by users with liabilities
+}
+
+/// The borrowing limits of users with collateral in an isolated reserve
+#[derive(Clone)]
+#[contracttype]
+pub struct IsolationConfig {
+    pub debt_cap: i128, // the max liability value an isolated user can hold, in the base asset
+    pub borrowable: Vec<Address>, // the reserves isolated users can borrow from
+}
+
+/// An efficiency mode category of correlated assets. Users opted into the category whose
+/// collateral and liabilities are all in the category's assets use the category's factors.
+#[derive(Clone)]
+#[contracttype]
+pub struct EModeCategory {
+    pub c_factor: u32, // the collateral factor of the category's assets, expressed in 7 decimals
+    pub l_factor: u32, // the liability factor of the category's assets, expressed in 7 decimals
+    pub liq_threshold: u32, // the collateral factor the category's assets are liquidated at, expressed in 7 decimals
+    pub assets: Vec<Address>, // the underlying assets of the reserves in the category
+}
+
+/// The band the price used to value an asset can move within each interval
+#[derive(Clone)]
+#[contracttype]
+pub struct PriceClampConfig {
+    pub max_change: u32, // the max change in an asset's price per interval, expressed in 7 decimals
+    pub interval: u64,   // the length of an interval, in seconds
+}
+
+/// The last observation of an asset's price, recorded at most once per clamp interval
+#[derive(Clone)]
+#[contracttype]
+pub struct PriceObservation {
+    pub price: i128,     // the clamped price assets were valued at
+    pub raw_price: i128, // the price reported by the oracle
+    pub timestamp: u64,  // the time the observation was recorded
+}
+
+/// An oracle change that is being verified against the pool's current oracle
+#[derive(Clone)]
+#[contracttype]
+pub struct PendingOracle {
+    pub oracle: Address, // the address of the new oracle
+    pub start_time: u64, // the time the dual-read period started
+    pub blocked: bool,   // true if the oracles disagreed during the dual-read period
+}
+
+/********** Legacy Storage Types **********/
+
+/// The pool's config before the liquidation health factors were configurable (storage version 0)
+#[derive(Clone)]
+#[contracttype]
+pub struct PoolConfigV0 {
+    pub oracle: Address,
+    pub bstop_rate: u64, // the rate the backstop takes on accrued debt interest, expressed in 9 decimals
+    pub status: u32,
+}
+
+/// The configuration of a reserve before oracle assets and isolation (storage version 0)
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveConfigV0 {
+    pub index: u32,      // the index of the reserve in the list
+    pub decimals: u32,   // the decimals used in both the bToken and underlying contract
+    pub c_factor: u32,   // the collateral factor for the reserve scaled expressed in 7 decimals
+    pub l_factor: u32,   // the liability factor for the reserve scaled expressed in 7 decimals
+    pub util: u32,       // the target utilization rate scaled expressed in 7 decimals
+    pub max_util: u32,   // the maximum allowed utilization rate scaled expressed in 7 decimals
+    pub r_one: u32,      // the R1 value in the interest rate formula scaled expressed in 7 decimals
+    pub r_two: u32,      // the R2 value in the interest rate formula scaled expressed in 7 decimals
+    pub r_three: u32,    // the R3 value in the interest rate formula scaled expressed in 7 decimals
+    pub reactivity: u32, // the reactivity constant for the reserve scaled expressed in 9 decimals
+}
+
+/// An auction before auction modes and valuations (storage version 0)
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionDataV0 {
+    pub bid: Map<Address, i128>,
+    pub lot: Map<Address, i128>,
+    pub block: u32,
+}
+
 /********** Storage Key Types **********/
 
 #[derive(Clone)]
@@ -92,11 +352,27 @@ pub struct UserReserveKey {
     reserve_id: u32,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct UserAttachedKey {
+    user: Address,
+    reserve_id: u32,
+    stream_id: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct BorrowAllowanceKey {
+    owner: Address,    // the Address whose collateral backs the borrows
+    delegate: Address, // the Address allowed to borrow on the owner's behalf
+    asset: Address,    // the underlying asset of the reserve that can be borrowed
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub struct AuctionKey {
-    user: Address,  // the Address whose assets are involved in the auction
-    auct_type: u32, // the type of auction taking place
+    pub(crate) user: Address, // the Address whose assets are involved in the auction
+    pub(crate) auct_type: u32, // the type of auction taking place
 }
 
 // TODO: See if we can avoid publishing this
@@ -107,6 +383,16 @@ pub enum PoolDataKey {
     ResConfig(Address),
     // A map of underlying asset's contract address to reserve data
     ResData(Address),
+    // A map of underlying asset's contract address to its exchange rate adapter
+    ResAdapter(Address),
+    // A map of underlying asset's contract address to its maximum utilization after a withdrawal
+    WdUtil(Address),
+    // A map of underlying asset's contract address to its liquidation threshold
+    LiqThr(Address),
+    // A map of underlying asset's contract address to its pause flags
+    ResPause(Address),
+    // A map of underlying asset's contract address to its borrow fee
+    BorrowFee(Address),
     // The reserve's emission config
     EmisConfig(u32),
     // The reserve's emission data
@@ -119,6 +405,58 @@ pub enum PoolDataKey {
     Auction(AuctionKey),
     // A list of auctions and their associated data
     AuctData(Address),
+    // The credit facility for a trusted borrower
+    Trusted(Address),
+    // The vesting emissions for a user
+    Vesting(Address),
+    // The emission streams attached to a reserve token
+    AttEmis(u32),
+    // The attached emission information for a reserve token for a user
+    UserAttEmis(UserAttachedKey),
+    // The minimum stream amount of a reward token approved for attached emissions
+    AttMin(Address),
+    // The auction lots held in escrow for a user
+    Escrow(Address),
+    // The total amount of a reserve's backstop credit held in escrow
+    EscrowTot(Address),
+    // The signers that have approved an admin action
+    Approvals(AdminAction),
+    // The protocol-owned b_tokens of a reserve
+    Pol(Address),
+    // The queued withdrawal of protocol-owned liquidity from a reserve
+    PolWd(Address),
+    // The history counters of a user
+    History(Address),
+    // Whether a user is on the allowlist of a permissioned pool
+    Allowed(Address),
+    // The recent utilization samples of a reserve
+    UtilHist(Address),
+    // A map of underlying asset's contract address to the withdrawal amount that must be queued
+    WdLarge(Address),
+    // The queued large withdrawal of a user from a reserve
+    WdQueue(UserReserveKey),
+    // The operator a user has approved to claim emissions on their behalf
+    ClaimOp(Address),
+    // The recent config versions of a reserve
+    ResCfgHist(Address),
+    // A map of underlying asset's contract address to its soft liquidation band
+    SoftLiq(Address),
+    // A map of underlying asset's contract address to its exposure cap and tracked collateral
+    ExpCap(Address),
+    // The recent fills of an auction type
+    AuctHist(u32),
+    // The amount a delegate can borrow from a reserve on behalf of a position owner
+    BorrowAllow(BorrowAllowanceKey),
+    // The e-mode category with an id
+    EMode(u32),
+    // The e-mode category a user has opted into
+    UserEMode(Address),
+    // The last price observation of an asset
+    PriceObs(Address),
+    // The bids registered to cooperatively fill a user's liquidation auction
+    FillBids(Address),
+    // The bad debt auction fill paid by a pending backstop draw
+    BDFill(u32),
 }
 
 /********** Storage **********/
@@ -155,6 +493,69 @@ pub fn set_user_positions(e: &Env, user: &Address, positions: &Positions) {
         .set::<PoolDataKey, Positions>(&key, positions);
 }
 
+/// Fetch the user's history counters, if the user has interacted with the pool
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_user_history(e: &Env, user: &Address) -> Option<UserHistory> {
+    let key = PoolDataKey::History(user.clone());
+    if let Some(history) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, UserHistory>(&key)
+    {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return Some(history);
+    }
+    None
+}
+
+/// Set the user's history counters
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `history` - The new history counters for the user
+pub fn set_user_history(e: &Env, user: &Address, history: &UserHistory) {
+    let key = PoolDataKey::History(user.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, UserHistory>(&key, history);
+}
+
+/// Fetch the id of the e-mode category the user has opted into, if any
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_user_e_mode(e: &Env, user: &Address) -> Option<u32> {
+    let key = PoolDataKey::UserEMode(user.clone());
+    if let Some(category_id) = e.storage().persistent().get::<PoolDataKey, u32>(&key) {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return Some(category_id);
+    }
+    None
+}
+
+/// Set the id of the e-mode category the user has opted into
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `category_id` - The id of the e-mode category
+pub fn set_user_e_mode(e: &Env, user: &Address, category_id: &u32) {
+    let key = PoolDataKey::UserEMode(user.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, u32>(&key, category_id);
+}
+
+/// Remove the e-mode category the user has opted into
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn del_user_e_mode(e: &Env, user: &Address) {
+    let key = PoolDataKey::UserEMode(user.clone());
+    e.storage().persistent().remove(&key);
+}
+
 /********** Admin **********/
 
 // Fetch the current admin Address
@@ -187,6 +588,23 @@ pub fn has_admin(e: &Env) -> bool {
     e.storage().persistent().has(&Symbol::new(e, "Admin"))
 }
 
+/// Fetch the hash of the config the pool was initialized with, if one was recorded
+pub fn get_init_hash(e: &Env) -> Option<BytesN<32>> {
+    e.storage()
+        .persistent()
+        .get::<Symbol, BytesN<32>>(&Symbol::new(e, "InitHash"))
+}
+
+/// Set the hash of the config the pool was initialized with
+///
+/// ### Arguments
+/// * `hash` - The hash of the config
+pub fn set_init_hash(e: &Env, hash: &BytesN<32>) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, BytesN<32>>(&Symbol::new(e, "InitHash"), hash);
+}
+
 /********** Metadata **********/
 
 /// Set a pool name
@@ -230,6 +648,144 @@ pub fn set_backstop(e: &Env, backstop: &Address) {
         .set::<Symbol, Address>(&Symbol::new(e, "Backstop"), backstop);
 }
 
+/// Fetch the interface version of the pool's backstop, defaulting to the current interface
+pub fn get_backstop_version(e: &Env) -> u32 {
+    let key = Symbol::new(e, "BstopVer");
+    if let Some(version) = e.storage().persistent().get::<Symbol, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return version;
+    }
+    BACKSTOP_INTERFACE_V1
+}
+
+/// Set the interface version of the pool's backstop
+///
+/// ### Arguments
+/// * `version` - The interface version
+pub fn set_backstop_version(e: &Env, version: &u32) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, u32>(&Symbol::new(e, "BstopVer"), version);
+}
+
+/********** Interest Freeze **********/
+
+/// Fetch the timestamp the pool's interest accrual was frozen at, if it is frozen
+pub fn get_interest_freeze(e: &Env) -> Option<u64> {
+    let key = Symbol::new(e, "IntFreeze");
+    if let Some(start) = e.storage().persistent().get::<Symbol, u64>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(start);
+    }
+    None
+}
+
+/// Set the timestamp the pool's interest accrual was frozen at
+///
+/// ### Arguments
+/// * `start` - The timestamp the freeze started
+pub fn set_interest_freeze(e: &Env, start: &u64) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, u64>(&Symbol::new(e, "IntFreeze"), start);
+}
+
+/// Remove the pool's interest freeze
+pub fn del_interest_freeze(e: &Env) {
+    e.storage()
+        .persistent()
+        .remove(&Symbol::new(e, "IntFreeze"));
+}
+
+/********** Emissions Pause **********/
+
+/// Fetch the timestamp the pool's emissions were paused at, if they are paused
+pub fn get_emissions_pause(e: &Env) -> Option<u64> {
+    let key = Symbol::new(e, "EmisPause");
+    if let Some(start) = e.storage().persistent().get::<Symbol, u64>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(start);
+    }
+    None
+}
+
+/// Set the timestamp the pool's emissions were paused at
+///
+/// ### Arguments
+/// * `start` - The timestamp the pause started
+pub fn set_emissions_pause(e: &Env, start: &u64) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, u64>(&Symbol::new(e, "EmisPause"), start);
+}
+
+/// Remove the pool's emissions pause
+pub fn del_emissions_pause(e: &Env) {
+    e.storage()
+        .persistent()
+        .remove(&Symbol::new(e, "EmisPause"));
+}
+
+/********** Co-Signer **********/
+
+/// Fetch the co-signer of the pool, if one is set
+pub fn get_co_signer(e: &Env) -> Option<Address> {
+    let key = Symbol::new(e, "CoSigner");
+    if let Some(co_signer) = e.storage().persistent().get::<Symbol, Address>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(co_signer);
+    }
+    None
+}
+
+/// Set the co-signer of the pool
+///
+/// ### Arguments
+/// * `co_signer` - The address of the co-signer
+pub fn set_co_signer(e: &Env, co_signer: &Address) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, Address>(&Symbol::new(e, "CoSigner"), co_signer);
+}
+
+/// Fetch the signers that have approved an admin action
+///
+/// ### Arguments
+/// * `action` - The admin action
+pub fn get_action_approvals(e: &Env, action: &AdminAction) -> Vec<Address> {
+    let key = PoolDataKey::Approvals(action.clone());
+    if let Some(approvals) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, Vec<Address>>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return approvals;
+    }
+    vec![e]
+}
+
+/// Set the signers that have approved an admin action
+///
+/// ### Arguments
+/// * `action` - The admin action
+/// * `approvals` - The signers that have approved the action
+pub fn set_action_approvals(e: &Env, action: &AdminAction, approvals: &Vec<Address>) {
+    let key = PoolDataKey::Approvals(action.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Vec<Address>>(&key, approvals);
+}
+
+/// Delete the approvals for an admin action
+///
+/// ### Arguments
+/// * `action` - The admin action
+pub fn del_action_approvals(e: &Env, action: &AdminAction) {
+    let key = PoolDataKey::Approvals(action.clone());
+    e.storage().persistent().remove(&key);
+}
+
 /********** External Token Contracts **********/
 
 /// Fetch the BLND token ID
@@ -276,31 +832,294 @@ pub fn set_usdc_token(e: &Env, usdc_token_id: &Address) {
         .set::<Symbol, Address>(&Symbol::new(e, "USDCTkn"), usdc_token_id);
 }
 
-/********** Pool Config **********/
+/********** Storage Version **********/
 
-/// Fetch the pool configuration
+/// Fetch the version of the pool's storage layout. Pools initialized before storage was
+/// versioned are at version 0.
+pub fn get_storage_version(e: &Env) -> u32 {
+    let key = Symbol::new(e, "StorVer");
+    if let Some(version) = e.storage().persistent().get::<Symbol, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return version;
+    }
+    0
+}
+
+/// Set the version of the pool's storage layout
 ///
-/// ### Panics
-/// If the pool's config is not set
-pub fn get_pool_config(e: &Env) -> PoolConfig {
-    // TODO: Change to instance - https://github.com/stellar/rs-soroban-sdk/issues/1040
+/// ### Arguments
+/// * `version` - The storage version
+pub fn set_storage_version(e: &Env, version: &u32) {
     e.storage()
         .persistent()
-        .bump(&Symbol::new(e, "PoolConfig"), SHARED_BUMP_AMOUNT);
+        .set::<Symbol, u32>(&Symbol::new(e, "StorVer"), version);
+}
+
+/// Fetch the pool configuration stored in the version 0 layout
+///
+/// ### Panics
+/// If the pool's config is not set
+pub fn get_pool_config_v0(e: &Env) -> PoolConfigV0 {
     e.storage()
         .persistent()
         .get(&Symbol::new(e, "PoolConfig"))
         .unwrap_optimized()
 }
 
-/// Set the pool configuration
+/// Fetch the reserve configuration for an asset stored in the version 0 layout
 ///
 /// ### Arguments
-/// * `config` - The contract address of the oracle
-pub fn set_pool_config(e: &Env, config: &PoolConfig) {
+/// * `asset` - The contract address of the asset
+///
+/// ### Panics
+/// If the reserve does not exist
+pub fn get_res_config_v0(e: &Env, asset: &Address) -> ReserveConfigV0 {
     e.storage()
         .persistent()
-        .set::<Symbol, PoolConfig>(&Symbol::new(e, "PoolConfig"), config);
+        .get::<PoolDataKey, ReserveConfigV0>(&PoolDataKey::ResConfig(asset.clone()))
+        .unwrap_optimized()
+}
+
+/// Fetch the auction data for an auction stored in the version 0 layout
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user who is auctioning off assets
+///
+/// ### Panics
+/// If the auction does not exist
+pub fn get_auction_v0(e: &Env, auction_type: &u32, user: &Address) -> AuctionDataV0 {
+    let key = PoolDataKey::Auction(AuctionKey {
+        user: user.clone(),
+        auct_type: *auction_type,
+    });
+    e.storage()
+        .temporary()
+        .get::<PoolDataKey, AuctionDataV0>(&key)
+        .unwrap_optimized()
+}
+
+/********** Pool Config **********/
+
+/// Fetch the pool configuration
+///
+/// ### Panics
+/// If the pool's config is not set
+pub fn get_pool_config(e: &Env) -> PoolConfig {
+    // TODO: Change to instance - https://github.com/stellar/rs-soroban-sdk/issues/1040
+    e.storage()
+        .persistent()
+        .bump(&Symbol::new(e, "PoolConfig"), SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get(&Symbol::new(e, "PoolConfig"))
+        .unwrap_optimized()
+}
+
+/// Set the pool configuration
+///
+/// ### Arguments
+/// * `config` - The contract address of the oracle
+pub fn set_pool_config(e: &Env, config: &PoolConfig) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, PoolConfig>(&Symbol::new(e, "PoolConfig"), config);
+}
+
+/********** Max Leverage **********/
+
+/// Fetch the maximum leverage a user can take on in the pool, if one is set
+pub fn get_max_leverage(e: &Env) -> Option<i128> {
+    let key = Symbol::new(e, "MaxLev");
+    if let Some(max_leverage) = e.storage().persistent().get::<Symbol, i128>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(max_leverage);
+    }
+    None
+}
+
+/// Set the maximum leverage a user can take on in the pool
+///
+/// ### Arguments
+/// * `max_leverage` - The maximum leverage, expressed in 7 decimals
+pub fn set_max_leverage(e: &Env, max_leverage: &i128) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, i128>(&Symbol::new(e, "MaxLev"), max_leverage);
+}
+
+/// Remove the maximum leverage a user can take on in the pool
+pub fn del_max_leverage(e: &Env) {
+    e.storage().persistent().remove(&Symbol::new(e, "MaxLev"));
+}
+
+/********** Min Debt **********/
+
+/// Fetch the minimum liability value a user with liabilities can hold, if one is set
+pub fn get_min_debt(e: &Env) -> Option<i128> {
+    let key = Symbol::new(e, "MinDebt");
+    if let Some(min_debt) = e.storage().persistent().get::<Symbol, i128>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(min_debt);
+    }
+    None
+}
+
+/// Set the minimum liability value a user with liabilities can hold
+///
+/// ### Arguments
+/// * `min_debt` - The minimum liability value, in the oracle's base asset
+pub fn set_min_debt(e: &Env, min_debt: &i128) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, i128>(&Symbol::new(e, "MinDebt"), min_debt);
+}
+
+/// Remove the minimum liability value a user with liabilities can hold
+pub fn del_min_debt(e: &Env) {
+    e.storage().persistent().remove(&Symbol::new(e, "MinDebt"));
+}
+
+/********** Backstop Floor **********/
+
+/// Fetch the backstop deposits required before borrowing is enabled in the pool, if one is set
+pub fn get_min_backstop(e: &Env) -> Option<i128> {
+    let key = Symbol::new(e, "MinBstop");
+    if let Some(min_backstop) = e.storage().persistent().get::<Symbol, i128>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(min_backstop);
+    }
+    None
+}
+
+/// Set the backstop deposits required before borrowing is enabled in the pool
+///
+/// ### Arguments
+/// * `min_backstop` - The minimum backstop deposits, in backstop tokens
+pub fn set_min_backstop(e: &Env, min_backstop: &i128) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, i128>(&Symbol::new(e, "MinBstop"), min_backstop);
+}
+
+/// Remove the backstop deposits required before borrowing is enabled in the pool
+pub fn del_min_backstop(e: &Env) {
+    e.storage().persistent().remove(&Symbol::new(e, "MinBstop"));
+}
+
+/********** Round Trip Block **********/
+
+/// Fetch if the pool rejects submits that round trip an asset
+pub fn get_block_round_trips(e: &Env) -> bool {
+    let key = Symbol::new(e, "BlockRT");
+    if let Some(block) = e.storage().persistent().get::<Symbol, bool>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return block;
+    }
+    false
+}
+
+/// Set if the pool rejects submits that round trip an asset
+///
+/// ### Arguments
+/// * `block` - If round trips are rejected
+pub fn set_block_round_trips(e: &Env, block: &bool) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, bool>(&Symbol::new(e, "BlockRT"), block);
+}
+
+/********** Denylist **********/
+
+/// Fetch the address of the denylist contract used to screen users, if one is set
+pub fn get_denylist(e: &Env) -> Option<Address> {
+    let key = Symbol::new(e, "Denylist");
+    if let Some(denylist) = e.storage().persistent().get::<Symbol, Address>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(denylist);
+    }
+    None
+}
+
+/// Set the address of the denylist contract used to screen users
+///
+/// ### Arguments
+/// * `denylist` - The address of the denylist contract
+pub fn set_denylist(e: &Env, denylist: &Address) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, Address>(&Symbol::new(e, "Denylist"), denylist);
+}
+
+/// Remove the denylist contract used to screen users
+pub fn del_denylist(e: &Env) {
+    e.storage().persistent().remove(&Symbol::new(e, "Denylist"));
+}
+
+/********** Isolation **********/
+
+/// Fetch the borrowing limits of isolated users, if any are set
+pub fn get_isolation_config(e: &Env) -> Option<IsolationConfig> {
+    let key = Symbol::new(e, "IsoConfig");
+    if let Some(config) = e
+        .storage()
+        .persistent()
+        .get::<Symbol, IsolationConfig>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(config);
+    }
+    None
+}
+
+/// Set the borrowing limits of isolated users
+///
+/// ### Arguments
+/// * `config` - The isolation config
+pub fn set_isolation_config(e: &Env, config: &IsolationConfig) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, IsolationConfig>(&Symbol::new(e, "IsoConfig"), config);
+}
+
+/********** E-Mode Categories (EMode) **********/
+
+/// Fetch an e-mode category, if it exists
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+pub fn get_e_mode_category(e: &Env, category_id: u32) -> Option<EModeCategory> {
+    let key = PoolDataKey::EMode(category_id);
+    if let Some(category) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, EModeCategory>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(category);
+    }
+    None
+}
+
+/// Set an e-mode category
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+/// * `category` - The e-mode category
+pub fn set_e_mode_category(e: &Env, category_id: u32, category: &EModeCategory) {
+    let key = PoolDataKey::EMode(category_id);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, EModeCategory>(&key, category);
+}
+
+/// Remove an e-mode category
+///
+/// ### Arguments
+/// * `category_id` - The id of the e-mode category
+pub fn del_e_mode_category(e: &Env, category_id: u32) {
+    let key = PoolDataKey::EMode(category_id);
+    e.storage().persistent().remove(&key);
 }
 
 /********** Reserve Config (ResConfig) **********/
@@ -308,249 +1127,1459 @@ pub fn set_pool_config(e: &Env, config: &PoolConfig) {
 /// Fetch the reserve data for an asset
 ///
 /// ### Arguments
-/// * `asset` - The contract address of the asset
-///
-/// ### Panics
-/// If the reserve does not exist
-pub fn get_res_config(e: &Env, asset: &Address) -> ReserveConfig {
-    let key = PoolDataKey::ResConfig(asset.clone());
-    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+/// * `asset` - The contract address of the asset
+///
+/// ### Panics
+/// If the reserve does not exist
+pub fn get_res_config(e: &Env, asset: &Address) -> ReserveConfig {
+    let key = PoolDataKey::ResConfig(asset.clone());
+    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get::<PoolDataKey, ReserveConfig>(&key)
+        .unwrap_optimized()
+}
+
+/// Set the reserve configuration for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `config` - The reserve configuration for the asset
+pub fn set_res_config(e: &Env, asset: &Address, config: &ReserveConfig) {
+    let key = PoolDataKey::ResConfig(asset.clone());
+
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveConfig>(&key, config);
+}
+
+/// Checks if a reserve exists for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn has_res(e: &Env, asset: &Address) -> bool {
+    let key = PoolDataKey::ResConfig(asset.clone());
+    e.storage().persistent().has(&key)
+}
+
+/// Fetch the recent config versions of a reserve, oldest first
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_config_history(e: &Env, asset: &Address) -> Vec<ReserveConfigVersion> {
+    let key = PoolDataKey::ResCfgHist(asset.clone());
+    if let Some(versions) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, Vec<ReserveConfigVersion>>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return versions;
+    }
+    vec![e]
+}
+
+/// Set the recent config versions of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `versions` - The recent config versions, oldest first
+pub fn set_res_config_history(e: &Env, asset: &Address, versions: &Vec<ReserveConfigVersion>) {
+    let key = PoolDataKey::ResCfgHist(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Vec<ReserveConfigVersion>>(&key, versions);
+}
+
+/// Fetch the soft liquidation band of a collateral reserve, if one is set
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_soft_liq_config(e: &Env, asset: &Address) -> Option<SoftLiqConfig> {
+    let key = PoolDataKey::SoftLiq(asset.clone());
+    if let Some(config) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, SoftLiqConfig>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(config);
+    }
+    None
+}
+
+/// Set the soft liquidation band of a collateral reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `config` - The soft liquidation band
+pub fn set_soft_liq_config(e: &Env, asset: &Address, config: &SoftLiqConfig) {
+    let key = PoolDataKey::SoftLiq(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, SoftLiqConfig>(&key, config);
+}
+
+/// Remove the soft liquidation band of a collateral reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_soft_liq_config(e: &Env, asset: &Address) {
+    let key = PoolDataKey::SoftLiq(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Reserve Data (ResData) **********/
+
+/// Fetch the reserve data for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+///
+/// ### Panics
+/// If the reserve does not exist
+pub fn get_res_data(e: &Env, asset: &Address) -> ReserveData {
+    let key = PoolDataKey::ResData(asset.clone());
+    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get::<PoolDataKey, ReserveData>(&key)
+        .unwrap_optimized()
+}
+
+/// Set the reserve data for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `data` - The reserve data for the asset
+pub fn set_res_data(e: &Env, asset: &Address, data: &ReserveData) {
+    let key = PoolDataKey::ResData(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveData>(&key, data);
+}
+
+/********** Reserve Exchange Rate Adapter (ResAdapter) **********/
+
+/// Fetch the exchange rate adapter for an asset, if one exists
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_adapter(e: &Env, asset: &Address) -> Option<Address> {
+    let key = PoolDataKey::ResAdapter(asset.clone());
+    if let Some(adapter) = e.storage().persistent().get::<PoolDataKey, Address>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(adapter);
+    }
+    None
+}
+
+/// Set the exchange rate adapter for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `adapter` - The contract address of the exchange rate adapter
+pub fn set_res_adapter(e: &Env, asset: &Address, adapter: &Address) {
+    let key = PoolDataKey::ResAdapter(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Address>(&key, adapter);
+}
+
+/********** Reserve Liquidation Threshold (LiqThr) **********/
+
+/// Fetch the liquidation threshold of a reserve, if one exists. Reserves without a
+/// liquidation threshold use their collateral factor.
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_liq_threshold(e: &Env, asset: &Address) -> Option<u32> {
+    let key = PoolDataKey::LiqThr(asset.clone());
+    if let Some(liq_threshold) = e.storage().persistent().get::<PoolDataKey, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(liq_threshold);
+    }
+    None
+}
+
+/// Set the liquidation threshold of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `liq_threshold` - The liquidation threshold expressed in 7 decimals
+pub fn set_res_liq_threshold(e: &Env, asset: &Address, liq_threshold: &u32) {
+    let key = PoolDataKey::LiqThr(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, u32>(&key, liq_threshold);
+}
+
+/// Remove the liquidation threshold of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_res_liq_threshold(e: &Env, asset: &Address) {
+    let key = PoolDataKey::LiqThr(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Reserve Pause Flags (ResPause) **********/
+
+/// Fetch the pause flags of a reserve. Reserves without pause flags have no actions paused.
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_pause_flags(e: &Env, asset: &Address) -> u32 {
+    let key = PoolDataKey::ResPause(asset.clone());
+    if let Some(flags) = e.storage().persistent().get::<PoolDataKey, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return flags;
+    }
+    0
+}
+
+/// Set the pause flags of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `flags` - The bit flags of the paused actions
+pub fn set_res_pause_flags(e: &Env, asset: &Address, flags: &u32) {
+    let key = PoolDataKey::ResPause(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, u32>(&key, flags);
+}
+
+/// Remove the pause flags of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_res_pause_flags(e: &Env, asset: &Address) {
+    let key = PoolDataKey::ResPause(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Reserve Borrow Fee (BorrowFee) **********/
+
+/// Fetch the origination fee charged on borrows from a reserve, if one exists
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_borrow_fee(e: &Env, asset: &Address) -> Option<u32> {
+    let key = PoolDataKey::BorrowFee(asset.clone());
+    if let Some(fee) = e.storage().persistent().get::<PoolDataKey, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(fee);
+    }
+    None
+}
+
+/// Set the origination fee charged on borrows from a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `fee` - The fee, as a percentage of the borrowed amount expressed in 7 decimals
+pub fn set_res_borrow_fee(e: &Env, asset: &Address, fee: &u32) {
+    let key = PoolDataKey::BorrowFee(asset.clone());
+    e.storage().persistent().set::<PoolDataKey, u32>(&key, fee);
+}
+
+/// Remove the origination fee charged on borrows from a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_res_borrow_fee(e: &Env, asset: &Address) {
+    let key = PoolDataKey::BorrowFee(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Reserve Withdrawal Utilization Cap (WdUtil) **********/
+
+/// Fetch the maximum utilization rate allowed after a withdrawal from a reserve, if one exists
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_withdraw_util(e: &Env, asset: &Address) -> Option<u32> {
+    let key = PoolDataKey::WdUtil(asset.clone());
+    if let Some(max_util) = e.storage().persistent().get::<PoolDataKey, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(max_util);
+    }
+    None
+}
+
+/// Set the maximum utilization rate allowed after a withdrawal from a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `max_util` - The maximum utilization rate expressed in 7 decimals
+pub fn set_res_withdraw_util(e: &Env, asset: &Address, max_util: &u32) {
+    let key = PoolDataKey::WdUtil(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, u32>(&key, max_util);
+}
+
+/// Remove the maximum utilization rate allowed after a withdrawal from a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_res_withdraw_util(e: &Env, asset: &Address) {
+    let key = PoolDataKey::WdUtil(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch the amount of underlying tokens above which a withdrawal from a reserve must be queued
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_large_withdrawal(e: &Env, asset: &Address) -> Option<i128> {
+    let key = PoolDataKey::WdLarge(asset.clone());
+    if let Some(threshold) = e.storage().persistent().get::<PoolDataKey, i128>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(threshold);
+    }
+    None
+}
+
+/// Set the amount of underlying tokens above which a withdrawal from a reserve must be queued
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `threshold` - The amount of underlying tokens
+pub fn set_res_large_withdrawal(e: &Env, asset: &Address, threshold: &i128) {
+    let key = PoolDataKey::WdLarge(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, i128>(&key, threshold);
+}
+
+/// Remove the large withdrawal threshold of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_res_large_withdrawal(e: &Env, asset: &Address) {
+    let key = PoolDataKey::WdLarge(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch a user's queued large withdrawal from a reserve
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `reserve_id` - The index of the reserve
+pub fn get_queued_withdrawal(e: &Env, user: &Address, reserve_id: u32) -> Option<QueuedWithdrawal> {
+    let key = PoolDataKey::WdQueue(UserReserveKey {
+        user: user.clone(),
+        reserve_id,
+    });
+    if let Some(withdrawal) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, QueuedWithdrawal>(&key)
+    {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return Some(withdrawal);
+    }
+    None
+}
+
+/// Set a user's queued large withdrawal from a reserve
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `reserve_id` - The index of the reserve
+/// * `withdrawal` - The queued withdrawal
+pub fn set_queued_withdrawal(
+    e: &Env,
+    user: &Address,
+    reserve_id: u32,
+    withdrawal: &QueuedWithdrawal,
+) {
+    let key = PoolDataKey::WdQueue(UserReserveKey {
+        user: user.clone(),
+        reserve_id,
+    });
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, QueuedWithdrawal>(&key, withdrawal);
+}
+
+/// Remove a user's queued large withdrawal from a reserve
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `reserve_id` - The index of the reserve
+pub fn del_queued_withdrawal(e: &Env, user: &Address, reserve_id: u32) {
+    let key = PoolDataKey::WdQueue(UserReserveKey {
+        user: user.clone(),
+        reserve_id,
+    });
+    e.storage().persistent().remove(&key);
+}
+
+/********** Reserve Exposure Cap (ExpCap) **********/
+
+/// Fetch the exposure cap and tracked collateral of a reserve, if it has been set
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_res_exposure(e: &Env, asset: &Address) -> Option<ReserveExposure> {
+    let key = PoolDataKey::ExpCap(asset.clone());
+    if let Some(exposure) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, ReserveExposure>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(exposure);
+    }
+    None
+}
+
+/// Set the exposure cap and tracked collateral of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `exposure` - The exposure cap and tracked collateral
+pub fn set_res_exposure(e: &Env, asset: &Address, exposure: &ReserveExposure) {
+    let key = PoolDataKey::ExpCap(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveExposure>(&key, exposure);
+}
+
+/********** Protocol-Owned Liquidity **********/
+
+/// Fetch the protocol-owned b_tokens of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_pol(e: &Env, asset: &Address) -> i128 {
+    let key = PoolDataKey::Pol(asset.clone());
+    if let Some(b_tokens) = e.storage().persistent().get::<PoolDataKey, i128>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return b_tokens;
+    }
+    0
+}
+
+/// Set the protocol-owned b_tokens of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `b_tokens` - The amount of protocol-owned b_tokens
+pub fn set_pol(e: &Env, asset: &Address, b_tokens: &i128) {
+    let key = PoolDataKey::Pol(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, i128>(&key, b_tokens);
+}
+
+/// Fetch the queued withdrawal of protocol-owned liquidity from a reserve, if one exists
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_pol_withdrawal(e: &Env, asset: &Address) -> Option<PolWithdrawal> {
+    let key = PoolDataKey::PolWd(asset.clone());
+    if let Some(withdrawal) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, PolWithdrawal>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(withdrawal);
+    }
+    None
+}
+
+/// Set the queued withdrawal of protocol-owned liquidity from a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `withdrawal` - The queued withdrawal
+pub fn set_pol_withdrawal(e: &Env, asset: &Address, withdrawal: &PolWithdrawal) {
+    let key = PoolDataKey::PolWd(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, PolWithdrawal>(&key, withdrawal);
+}
+
+/// Remove the queued withdrawal of protocol-owned liquidity from a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_pol_withdrawal(e: &Env, asset: &Address) {
+    let key = PoolDataKey::PolWd(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Reserve List (ResList) **********/
+
+/// Fetch the list of reserves
+pub fn get_res_list(e: &Env) -> Vec<Address> {
+    let key = Symbol::new(e, "ResList");
+    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get::<Symbol, Vec<Address>>(&key)
+        .unwrap_or(vec![e]) // empty vec if nothing exists
+}
+
+/// Add a reserve to the back of the list and returns the index
+///
+/// ### Arguments
+/// * `asset` - The contract address of the underlying asset
+///
+/// ### Panics
+/// If the number of reserves in the list exceeds 32
+///
+// @dev: Once added it can't be removed
+pub fn push_res_list(e: &Env, asset: &Address) -> u32 {
+    let mut res_list = get_res_list(e);
+    if res_list.len() == 32 {
+        panic!("too many reserves")
+    }
+    res_list.push_back(asset.clone());
+    let new_index = res_list.len() - 1;
+    e.storage()
+        .persistent()
+        .set::<Symbol, Vec<Address>>(&Symbol::new(e, "ResList"), &res_list);
+    new_index
+}
+
+/********** Reserve Emissions **********/
+
+/// Fetch the emission config for the reserve b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+pub fn get_res_emis_config(e: &Env, res_token_index: &u32) -> Option<ReserveEmissionsConfig> {
+    let key = PoolDataKey::EmisConfig(*res_token_index);
+    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get::<PoolDataKey, ReserveEmissionsConfig>(&key)
+}
+
+/// Set the emission config for the reserve b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `res_emis_config` - The new emission config for the reserve token
+pub fn set_res_emis_config(
+    e: &Env,
+    res_token_index: &u32,
+    res_emis_config: &ReserveEmissionsConfig,
+) {
+    let key = PoolDataKey::EmisConfig(*res_token_index);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveEmissionsConfig>(&key, res_emis_config);
+}
+
+/// Fetch the emission data for the reserve b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+pub fn get_res_emis_data(e: &Env, res_token_index: &u32) -> Option<ReserveEmissionsData> {
+    let key = PoolDataKey::EmisData(*res_token_index);
+    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get::<PoolDataKey, ReserveEmissionsData>(&key)
+}
+
+/// Checks if the reserve token has emissions data
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+pub fn has_res_emis_data(e: &Env, res_token_index: &u32) -> bool {
+    let key = PoolDataKey::EmisData(*res_token_index);
+    e.storage().persistent().has(&key)
+}
+
+/// Set the emission data for the reserve b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `res_emis_data` - The new emission data for the reserve token
+pub fn set_res_emis_data(e: &Env, res_token_index: &u32, res_emis_data: &ReserveEmissionsData) {
+    let key = PoolDataKey::EmisData(*res_token_index);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveEmissionsData>(&key, res_emis_data);
+}
+
+/********** User Emissions **********/
+
+/// Fetch the users emission data for a reserve's b or d token
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `res_token_index` - The d/bToken index for the reserve
+pub fn get_user_emissions(
+    e: &Env,
+    user: &Address,
+    res_token_index: &u32,
+) -> Option<UserEmissionData> {
+    let key = PoolDataKey::UserEmis(UserReserveKey {
+        user: user.clone(),
+        reserve_id: *res_token_index,
+    });
+    e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get::<PoolDataKey, UserEmissionData>(&key)
+}
+
+/// Set the users emission data for a reserve's d or d token
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `data` - The new user emission d ata for the d/bToken
+pub fn set_user_emissions(e: &Env, user: &Address, res_token_index: &u32, data: &UserEmissionData) {
+    let key = PoolDataKey::UserEmis(UserReserveKey {
+        user: user.clone(),
+        reserve_id: *res_token_index,
+    });
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, UserEmissionData>(&key, data)
+}
+
+/********** Attached Emissions **********/
+
+/// Fetch the emission streams attached to a reserve's b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+pub fn get_attached_emissions(e: &Env, res_token_index: &u32) -> Vec<AttachedEmission> {
+    let key = PoolDataKey::AttEmis(*res_token_index);
+    if let Some(streams) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, Vec<AttachedEmission>>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return streams;
+    }
+    vec![e]
+}
+
+/// Set the emission streams attached to a reserve's b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `streams` - The attached emission streams
+pub fn set_attached_emissions(e: &Env, res_token_index: &u32, streams: &Vec<AttachedEmission>) {
+    let key = PoolDataKey::AttEmis(*res_token_index);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Vec<AttachedEmission>>(&key, streams);
+}
+
+/// Fetch the minimum stream amount of a reward token approved for attached emissions, if the
+/// token is approved
+///
+/// ### Arguments
+/// * `token` - The reward token
+pub fn get_attached_min(e: &Env, token: &Address) -> Option<i128> {
+    let key = PoolDataKey::AttMin(token.clone());
+    if let Some(min_amount) = e.storage().persistent().get::<PoolDataKey, i128>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(min_amount);
+    }
+    None
+}
+
+/// Set the minimum stream amount of a reward token approved for attached emissions
+///
+/// ### Arguments
+/// * `token` - The reward token
+/// * `min_amount` - The minimum amount of reward tokens a stream must deposit
+pub fn set_attached_min(e: &Env, token: &Address, min_amount: &i128) {
+    let key = PoolDataKey::AttMin(token.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, i128>(&key, min_amount);
+}
+
+/// Remove the approval of a reward token for attached emissions
+///
+/// ### Arguments
+/// * `token` - The reward token
+pub fn del_attached_min(e: &Env, token: &Address) {
+    let key = PoolDataKey::AttMin(token.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch and increment the next id for an attached emission stream
+pub fn next_attached_emission_id(e: &Env) -> u32 {
+    let key = Symbol::new(e, "AttEmisId");
+    let id = e
+        .storage()
+        .persistent()
+        .get::<Symbol, u32>(&key)
+        .unwrap_or(0);
+    e.storage().persistent().set::<Symbol, u32>(&key, &(id + 1));
+    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    id
+}
+
+/// Fetch the users emission data for a stream attached to a reserve's b or d token
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `stream_id` - The id of the attached emission stream
+pub fn get_user_attached_emissions(
+    e: &Env,
+    user: &Address,
+    res_token_index: &u32,
+    stream_id: &u32,
+) -> Option<UserEmissionData> {
+    let key = PoolDataKey::UserAttEmis(UserAttachedKey {
+        user: user.clone(),
+        reserve_id: *res_token_index,
+        stream_id: *stream_id,
+    });
+    if let Some(data) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, UserEmissionData>(&key)
+    {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return Some(data);
+    }
+    None
+}
+
+/// Set the users emission data for a stream attached to a reserve's b or d token
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `stream_id` - The id of the attached emission stream
+/// * `data` - The new user emission data for the stream
+pub fn set_user_attached_emissions(
+    e: &Env,
+    user: &Address,
+    res_token_index: &u32,
+    stream_id: &u32,
+    data: &UserEmissionData,
+) {
+    let key = PoolDataKey::UserAttEmis(UserAttachedKey {
+        user: user.clone(),
+        reserve_id: *res_token_index,
+        stream_id: *stream_id,
+    });
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, UserEmissionData>(&key, data)
+}
+
+/********** Trusted Borrowers **********/
+
+/// Fetch the credit facility for a trusted borrower, if one exists
+///
+/// ### Arguments
+/// * `borrower` - The address of the borrower
+pub fn get_trusted_borrower(e: &Env, borrower: &Address) -> Option<TrustedBorrower> {
+    let key = PoolDataKey::Trusted(borrower.clone());
+    if let Some(trusted) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, TrustedBorrower>(&key)
+    {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return Some(trusted);
+    }
+    None
+}
+
+/// Set the credit facility for a trusted borrower
+///
+/// ### Arguments
+/// * `borrower` - The address of the borrower
+/// * `trusted` - The credit facility
+pub fn set_trusted_borrower(e: &Env, borrower: &Address, trusted: &TrustedBorrower) {
+    let key = PoolDataKey::Trusted(borrower.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, TrustedBorrower>(&key, trusted);
+}
+
+/// Remove the credit facility for a trusted borrower
+///
+/// ### Arguments
+/// * `borrower` - The address of the borrower
+pub fn del_trusted_borrower(e: &Env, borrower: &Address) {
+    let key = PoolDataKey::Trusted(borrower.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Allowlist **********/
+
+/// Fetch if the pool is permissioned, such that only allowlisted users can interact with it
+pub fn get_permissioned(e: &Env) -> bool {
+    let key = Symbol::new(e, "Permission");
+    if let Some(permissioned) = e.storage().persistent().get::<Symbol, bool>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return permissioned;
+    }
+    false
+}
+
+/// Set if the pool is permissioned
+///
+/// ### Arguments
+/// * `permissioned` - If the pool is permissioned
+pub fn set_permissioned(e: &Env, permissioned: &bool) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, bool>(&Symbol::new(e, "Permission"), permissioned);
+}
+
+/// Fetch if a user is on the pool's allowlist
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn is_allowed(e: &Env, user: &Address) -> bool {
+    let key = PoolDataKey::Allowed(user.clone());
+    if e.storage().persistent().has(&key) {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return true;
+    }
+    false
+}
+
+/// Add a user to the pool's allowlist
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn set_allowed(e: &Env, user: &Address) {
+    let key = PoolDataKey::Allowed(user.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, bool>(&key, &true);
+}
+
+/// Remove a user from the pool's allowlist
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn del_allowed(e: &Env, user: &Address) {
+    let key = PoolDataKey::Allowed(user.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Emission Vesting **********/
+
+/// Fetch the emission vesting config for the pool, if one exists
+pub fn get_vesting_config(e: &Env) -> Option<VestingConfig> {
+    let key = Symbol::new(e, "VestConfig");
+    if let Some(config) = e.storage().persistent().get::<Symbol, VestingConfig>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(config);
+    }
+    None
+}
+
+/// Set the emission vesting config for the pool
+///
+/// ### Arguments
+/// * `config` - The emission vesting config
+pub fn set_vesting_config(e: &Env, config: &VestingConfig) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, VestingConfig>(&Symbol::new(e, "VestConfig"), config);
+}
+
+/// Fetch how the pool settles the accrued emissions of liquidated collateral. Defaults to 0,
+/// leaving the emissions claimable by the liquidated user.
+pub fn get_liq_emis_mode(e: &Env) -> u32 {
+    let key = Symbol::new(e, "LiqEmis");
+    if let Some(mode) = e.storage().persistent().get::<Symbol, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return mode;
+    }
+    0
+}
+
+/// Set how the pool settles the accrued emissions of liquidated collateral
+///
+/// ### Arguments
+/// * `mode` - The liquidation emissions mode
+pub fn set_liq_emis_mode(e: &Env, mode: &u32) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, u32>(&Symbol::new(e, "LiqEmis"), mode);
+}
+
+/// Fetch the vesting emissions for a user, if they exist
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_user_vesting(e: &Env, user: &Address) -> Option<UserVesting> {
+    let key = PoolDataKey::Vesting(user.clone());
+    if let Some(vesting) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, UserVesting>(&key)
+    {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return Some(vesting);
+    }
+    None
+}
+
+/// Set the vesting emissions for a user
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `vesting` - The vesting emissions
+pub fn set_user_vesting(e: &Env, user: &Address, vesting: &UserVesting) {
+    let key = PoolDataKey::Vesting(user.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, UserVesting>(&key, vesting);
+}
+
+/// Remove the vesting emissions for a user
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn del_user_vesting(e: &Env, user: &Address) {
+    let key = PoolDataKey::Vesting(user.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch the operator a user has approved to claim emissions on their behalf, if one exists
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_claim_operator(e: &Env, user: &Address) -> Option<Address> {
+    let key = PoolDataKey::ClaimOp(user.clone());
+    if let Some(operator) = e.storage().persistent().get::<PoolDataKey, Address>(&key) {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return Some(operator);
+    }
+    None
+}
+
+/// Set the operator a user has approved to claim emissions on their behalf
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `operator` - The address of the operator
+pub fn set_claim_operator(e: &Env, user: &Address, operator: &Address) {
+    let key = PoolDataKey::ClaimOp(user.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Address>(&key, operator);
+}
+
+/// Remove the operator a user has approved to claim emissions on their behalf
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn del_claim_operator(e: &Env, user: &Address) {
+    let key = PoolDataKey::ClaimOp(user.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Borrow Allowances **********/
+
+/// Fetch the amount of underlying tokens a delegate can borrow from a reserve on behalf of an owner
+///
+/// ### Arguments
+/// * `owner` - The address of the position owner
+/// * `delegate` - The address of the delegate
+/// * `asset` - The underlying asset of the reserve
+pub fn get_borrow_allowance(e: &Env, owner: &Address, delegate: &Address, asset: &Address) -> i128 {
+    let key = PoolDataKey::BorrowAllow(BorrowAllowanceKey {
+        owner: owner.clone(),
+        delegate: delegate.clone(),
+        asset: asset.clone(),
+    });
+    if let Some(allowance) = e.storage().persistent().get::<PoolDataKey, i128>(&key) {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return allowance;
+    }
+    0
+}
+
+/// Set the amount of underlying tokens a delegate can borrow from a reserve on behalf of an owner
+///
+/// ### Arguments
+/// * `owner` - The address of the position owner
+/// * `delegate` - The address of the delegate
+/// * `asset` - The underlying asset of the reserve
+/// * `allowance` - The amount of underlying tokens
+pub fn set_borrow_allowance(
+    e: &Env,
+    owner: &Address,
+    delegate: &Address,
+    asset: &Address,
+    allowance: &i128,
+) {
+    let key = PoolDataKey::BorrowAllow(BorrowAllowanceKey {
+        owner: owner.clone(),
+        delegate: delegate.clone(),
+        asset: asset.clone(),
+    });
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, i128>(&key, allowance);
+}
+
+/// Remove the amount of underlying tokens a delegate can borrow from a reserve on behalf of an owner
+///
+/// ### Arguments
+/// * `owner` - The address of the position owner
+/// * `delegate` - The address of the delegate
+/// * `asset` - The underlying asset of the reserve
+pub fn del_borrow_allowance(e: &Env, owner: &Address, delegate: &Address, asset: &Address) {
+    let key = PoolDataKey::BorrowAllow(BorrowAllowanceKey {
+        owner: owner.clone(),
+        delegate: delegate.clone(),
+        asset: asset.clone(),
+    });
+    e.storage().persistent().remove(&key);
+}
+
+/********** Claim Swap Adapter **********/
+
+/// Fetch the swap adapter claimed emissions can be swapped through, if one is set
+pub fn get_claim_swap_adapter(e: &Env) -> Option<Address> {
+    let key = Symbol::new(e, "ClaimSwap");
+    if let Some(adapter) = e.storage().persistent().get::<Symbol, Address>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(adapter);
+    }
+    None
+}
+
+/// Set the swap adapter claimed emissions can be swapped through
+///
+/// ### Arguments
+/// * `adapter` - The contract address of the swap adapter
+pub fn set_claim_swap_adapter(e: &Env, adapter: &Address) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, Address>(&Symbol::new(e, "ClaimSwap"), adapter);
+}
+
+/********** Pool Emissions **********/
+
+/// Fetch the pool reserve emissions
+pub fn get_pool_emissions(e: &Env) -> Map<u32, u64> {
+    let key = Symbol::new(e, "PoolEmis");
+    e.storage().persistent().bump(&key, CYCLE_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get::<Symbol, Map<u32, u64>>(&key)
+        .unwrap_or(map![e])
+}
+
+/// Set the pool reserve emissions
+///
+/// ### Arguments
+/// * `emissions` - The map of emissions by reserve token id to EPS
+pub fn set_pool_emissions(e: &Env, emissions: &Map<u32, u64>) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, Map<u32, u64>>(&Symbol::new(e, "PoolEmis"), emissions);
+}
+
+/// Fetch the pool emission expiration timestamps
+pub fn get_pool_emissions_expiration(e: &Env) -> u64 {
+    let key = Symbol::new(e, "EmisExp");
+    e.storage().persistent().bump(&key, CYCLE_BUMP_AMOUNT);
+    e.storage()
+        .temporary()
+        .get::<Symbol, u64>(&key)
+        .unwrap_or(0)
+}
+
+/// Set the pool emission configuration
+///
+/// ### Arguments
+/// * `expiration` - The pool's emission configuration
+pub fn set_pool_emissions_expiration(e: &Env, expiration: &u64) {
+    e.storage()
+        .temporary()
+        .set::<Symbol, u64>(&Symbol::new(e, "EmisExp"), expiration);
+}
+
+/********** Heartbeat ***********/
+
+/// Fetch the ledger sequence of the last pool heartbeat, if one has been emitted
+pub fn get_last_heartbeat(e: &Env) -> Option<u32> {
+    let key = Symbol::new(e, "Heartbeat");
+    if let Some(result) = e.storage().persistent().get::<Symbol, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(result);
+    }
+    None
+}
+
+/// Set the ledger sequence of the last pool heartbeat
+///
+/// ### Arguments
+/// * `sequence` - The ledger sequence the heartbeat was emitted
+pub fn set_last_heartbeat(e: &Env, sequence: &u32) {
     e.storage()
         .persistent()
-        .get::<PoolDataKey, ReserveConfig>(&key)
-        .unwrap_optimized()
+        .set::<Symbol, u32>(&Symbol::new(e, "Heartbeat"), sequence);
 }
 
-/// Set the reserve configuration for an asset
+/********** Price Clamp **********/
+
+/// Fetch the band prices can move within each interval, if one is set
+pub fn get_price_clamp_config(e: &Env) -> Option<PriceClampConfig> {
+    let key = Symbol::new(e, "PriceClamp");
+    if let Some(config) = e
+        .storage()
+        .persistent()
+        .get::<Symbol, PriceClampConfig>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(config);
+    }
+    None
+}
+
+/// Set the band prices can move within each interval
 ///
 /// ### Arguments
-/// * `asset` - The contract address of the asset
-/// * `config` - The reserve configuration for the asset
-pub fn set_res_config(e: &Env, asset: &Address, config: &ReserveConfig) {
-    let key = PoolDataKey::ResConfig(asset.clone());
+/// * `config` - The price clamp config
+pub fn set_price_clamp_config(e: &Env, config: &PriceClampConfig) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, PriceClampConfig>(&Symbol::new(e, "PriceClamp"), config);
+}
 
+/// Remove the band prices can move within each interval
+pub fn del_price_clamp_config(e: &Env) {
     e.storage()
         .persistent()
-        .set::<PoolDataKey, ReserveConfig>(&key, config);
+        .remove(&Symbol::new(e, "PriceClamp"));
 }
 
-/// Checks if a reserve exists for an asset
+/// Fetch the last price observation of an asset, if one was recorded
 ///
 /// ### Arguments
 /// * `asset` - The contract address of the asset
-pub fn has_res(e: &Env, asset: &Address) -> bool {
-    let key = PoolDataKey::ResConfig(asset.clone());
-    e.storage().persistent().has(&key)
+pub fn get_price_observation(e: &Env, asset: &Address) -> Option<PriceObservation> {
+    let key = PoolDataKey::PriceObs(asset.clone());
+    if let Some(observation) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, PriceObservation>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(observation);
+    }
+    None
 }
 
-/********** Reserve Data (ResData) **********/
-
-/// Fetch the reserve data for an asset
+/// Set the last price observation of an asset
 ///
 /// ### Arguments
 /// * `asset` - The contract address of the asset
-///
-/// ### Panics
-/// If the reserve does not exist
-pub fn get_res_data(e: &Env, asset: &Address) -> ReserveData {
-    let key = PoolDataKey::ResData(asset.clone());
-    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+/// * `observation` - The price observation
+pub fn set_price_observation(e: &Env, asset: &Address, observation: &PriceObservation) {
+    let key = PoolDataKey::PriceObs(asset.clone());
     e.storage()
         .persistent()
-        .get::<PoolDataKey, ReserveData>(&key)
-        .unwrap_optimized()
+        .set::<PoolDataKey, PriceObservation>(&key, observation);
 }
 
-/// Set the reserve data for an asset
+/********** Oracle Change **********/
+
+/// Fetch the pending oracle change, if one exists
+pub fn get_pending_oracle(e: &Env) -> Option<PendingOracle> {
+    let key = Symbol::new(e, "PendOracle");
+    if let Some(pending) = e.storage().persistent().get::<Symbol, PendingOracle>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(pending);
+    }
+    None
+}
+
+/// Set the pending oracle change
 ///
 /// ### Arguments
-/// * `asset` - The contract address of the asset
-/// * `data` - The reserve data for the asset
-pub fn set_res_data(e: &Env, asset: &Address, data: &ReserveData) {
-    let key = PoolDataKey::ResData(asset.clone());
+/// * `pending` - The pending oracle change
+pub fn set_pending_oracle(e: &Env, pending: &PendingOracle) {
     e.storage()
         .persistent()
-        .set::<PoolDataKey, ReserveData>(&key, data);
+        .set::<Symbol, PendingOracle>(&Symbol::new(e, "PendOracle"), pending);
 }
 
-/********** Reserve List (ResList) **********/
-
-/// Fetch the list of reserves
-pub fn get_res_list(e: &Env) -> Vec<Address> {
-    let key = Symbol::new(e, "ResList");
-    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+/// Remove the pending oracle change
+pub fn del_pending_oracle(e: &Env) {
     e.storage()
         .persistent()
-        .get::<Symbol, Vec<Address>>(&key)
-        .unwrap_or(vec![e]) // empty vec if nothing exists
+        .remove(&Symbol::new(e, "PendOracle"));
 }
 
-/// Add a reserve to the back of the list and returns the index
+/********** Utilization History **********/
+
+/// Fetch the recent utilization samples of a reserve, oldest first. Defaults to an empty vec.
 ///
 /// ### Arguments
-/// * `asset` - The contract address of the underlying asset
-///
-/// ### Panics
-/// If the number of reserves in the list exceeds 32
-///
-// @dev: Once added it can't be removed
-pub fn push_res_list(e: &Env, asset: &Address) -> u32 {
-    let mut res_list = get_res_list(e);
-    if res_list.len() == 32 {
-        panic!("too many reserves")
+/// * `asset` - The contract address of the asset
+pub fn get_util_history(e: &Env, asset: &Address) -> Vec<UtilSample> {
+    let key = PoolDataKey::UtilHist(asset.clone());
+    if let Some(samples) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, Vec<UtilSample>>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return samples;
     }
-    res_list.push_back(asset.clone());
-    let new_index = res_list.len() - 1;
+    vec![e]
+}
+
+/// Set the recent utilization samples of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `samples` - The utilization samples, oldest first
+pub fn set_util_history(e: &Env, asset: &Address, samples: &Vec<UtilSample>) {
+    let key = PoolDataKey::UtilHist(asset.clone());
     e.storage()
         .persistent()
-        .set::<Symbol, Vec<Address>>(&Symbol::new(e, "ResList"), &res_list);
-    new_index
+        .set::<PoolDataKey, Vec<UtilSample>>(&key, samples);
 }
 
-/********** Reserve Emissions **********/
+/********** Interest Sweep ***********/
 
-/// Fetch the emission config for the reserve b or d token
+/// Fetch the interest sweep config for the pool, if one exists
+pub fn get_sweep_config(e: &Env) -> Option<SweepConfig> {
+    let key = Symbol::new(e, "SweepCfg");
+    if let Some(config) = e.storage().persistent().get::<Symbol, SweepConfig>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(config);
+    }
+    None
+}
+
+/// Set the interest sweep config for the pool
 ///
 /// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-pub fn get_res_emis_config(e: &Env, res_token_index: &u32) -> Option<ReserveEmissionsConfig> {
-    let key = PoolDataKey::EmisConfig(*res_token_index);
-    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+/// * `config` - The interest sweep config
+pub fn set_sweep_config(e: &Env, config: &SweepConfig) {
     e.storage()
         .persistent()
-        .get::<PoolDataKey, ReserveEmissionsConfig>(&key)
+        .set::<Symbol, SweepConfig>(&Symbol::new(e, "SweepCfg"), config);
 }
 
-/// Set the emission config for the reserve b or d token
+/// Fetch the time of the last interest sweep. Defaults to 0 if no sweep has occurred.
+pub fn get_last_sweep(e: &Env) -> u64 {
+    let key = Symbol::new(e, "LastSweep");
+    if let Some(result) = e.storage().persistent().get::<Symbol, u64>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        result
+    } else {
+        0
+    }
+}
+
+/// Set the time of the last interest sweep
 ///
 /// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-/// * `res_emis_config` - The new emission config for the reserve token
-pub fn set_res_emis_config(
-    e: &Env,
-    res_token_index: &u32,
-    res_emis_config: &ReserveEmissionsConfig,
-) {
-    let key = PoolDataKey::EmisConfig(*res_token_index);
+/// * `timestamp` - The time of the sweep
+pub fn set_last_sweep(e: &Env, timestamp: &u64) {
     e.storage()
         .persistent()
-        .set::<PoolDataKey, ReserveEmissionsConfig>(&key, res_emis_config);
+        .set::<Symbol, u64>(&Symbol::new(e, "LastSweep"), timestamp);
 }
 
-/// Fetch the emission data for the reserve b or d token
+/// Fetch if swept interest is distributed in kind to the backstop's depositors, instead of
+/// being auctioned. Defaults to false if unset.
+pub fn get_direct_interest(e: &Env) -> bool {
+    let key = Symbol::new(e, "DirectInt");
+    if let Some(direct) = e.storage().persistent().get::<Symbol, bool>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return direct;
+    }
+    false
+}
+
+/// Set if swept interest is distributed in kind to the backstop's depositors
 ///
 /// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-pub fn get_res_emis_data(e: &Env, res_token_index: &u32) -> Option<ReserveEmissionsData> {
-    let key = PoolDataKey::EmisData(*res_token_index);
-    e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+/// * `direct` - If swept interest is distributed in kind
+pub fn set_direct_interest(e: &Env, direct: &bool) {
     e.storage()
         .persistent()
-        .get::<PoolDataKey, ReserveEmissionsData>(&key)
+        .set::<Symbol, bool>(&Symbol::new(e, "DirectInt"), direct);
 }
 
-/// Checks if the reserve token has emissions data
-///
-/// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-pub fn has_res_emis_data(e: &Env, res_token_index: &u32) -> bool {
-    let key = PoolDataKey::EmisData(*res_token_index);
-    e.storage().persistent().has(&key)
+/********** Liquidation Priority **********/
+
+/// Fetch the order collateral assets are seized in by liquidations. Defaults to an
+/// empty list if unset, such that collateral is seized pro-rata.
+pub fn get_liq_priority(e: &Env) -> Vec<Address> {
+    let key = Symbol::new(e, "LiqPrio");
+    if let Some(priority) = e.storage().persistent().get::<Symbol, Vec<Address>>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return priority;
+    }
+    vec![e]
 }
 
-/// Set the emission data for the reserve b or d token
+/// Set the order collateral assets are seized in by liquidations
 ///
 /// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-/// * `res_emis_data` - The new emission data for the reserve token
-pub fn set_res_emis_data(e: &Env, res_token_index: &u32, res_emis_data: &ReserveEmissionsData) {
-    let key = PoolDataKey::EmisData(*res_token_index);
+/// * `priority` - The collateral assets, in the order they are seized
+pub fn set_liq_priority(e: &Env, priority: &Vec<Address>) {
     e.storage()
         .persistent()
-        .set::<PoolDataKey, ReserveEmissionsData>(&key, res_emis_data);
+        .set::<Symbol, Vec<Address>>(&Symbol::new(e, "LiqPrio"), priority);
 }
 
-/********** User Emissions **********/
+/********** Auctions ***********/
 
-/// Fetch the users emission data for a reserve's b or d token
+/// Fetch the auction modifier mode for the pool. Defaults to 0 (sequence based) if unset.
+pub fn get_auction_mode(e: &Env) -> u32 {
+    let key = Symbol::new(e, "AuctMode");
+    if let Some(result) = e.storage().persistent().get::<Symbol, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        result
+    } else {
+        0
+    }
+}
+
+/// Set the auction modifier mode for the pool
 ///
 /// ### Arguments
-/// * `user` - The address of the user
-/// * `res_token_index` - The d/bToken index for the reserve
-pub fn get_user_emissions(
-    e: &Env,
-    user: &Address,
-    res_token_index: &u32,
-) -> Option<UserEmissionData> {
-    let key = PoolDataKey::UserEmis(UserReserveKey {
-        user: user.clone(),
-        reserve_id: *res_token_index,
-    });
-    e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+/// * `mode` - The auction modifier mode
+pub fn set_auction_mode(e: &Env, mode: &u32) {
     e.storage()
         .persistent()
-        .get::<PoolDataKey, UserEmissionData>(&key)
+        .set::<Symbol, u32>(&Symbol::new(e, "AuctMode"), mode);
 }
 
-/// Set the users emission data for a reserve's d or d token
+/// Fetch the auction valuation for the pool. Defaults to 0 (raw oracle value) if unset.
+pub fn get_auction_valuation(e: &Env) -> u32 {
+    let key = Symbol::new(e, "AuctValue");
+    if let Some(result) = e.storage().persistent().get::<Symbol, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        result
+    } else {
+        0
+    }
+}
+
+/// Set the auction valuation for the pool
 ///
 /// ### Arguments
-/// * `user` - The address of the user
-/// * `res_token_index` - The d/bToken index for the reserve
-/// * `data` - The new user emission d ata for the d/bToken
-pub fn set_user_emissions(e: &Env, user: &Address, res_token_index: &u32, data: &UserEmissionData) {
-    let key = PoolDataKey::UserEmis(UserReserveKey {
-        user: user.clone(),
-        reserve_id: *res_token_index,
-    });
+/// * `valuation` - The auction valuation
+pub fn set_auction_valuation(e: &Env, valuation: &u32) {
     e.storage()
         .persistent()
-        .set::<PoolDataKey, UserEmissionData>(&key, data)
+        .set::<Symbol, u32>(&Symbol::new(e, "AuctValue"), valuation);
 }
 
-/********** Pool Emissions **********/
+/// Fetch the number of blocks fill bids are collected for before a user liquidation auction is
+/// filled cooperatively, if cooperative fills are enabled
+pub fn get_coop_fill_window(e: &Env) -> Option<u32> {
+    let key = Symbol::new(e, "CoopWin");
+    if let Some(window) = e.storage().persistent().get::<Symbol, u32>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return Some(window);
+    }
+    None
+}
 
-/// Fetch the pool reserve emissions
-pub fn get_pool_emissions(e: &Env) -> Map<u32, u64> {
-    let key = Symbol::new(e, "PoolEmis");
-    e.storage().persistent().bump(&key, CYCLE_BUMP_AMOUNT);
+/// Set the number of blocks fill bids are collected for before a user liquidation auction is
+/// filled cooperatively
+///
+/// ### Arguments
+/// * `window` - The number of blocks
+pub fn set_coop_fill_window(e: &Env, window: &u32) {
     e.storage()
         .persistent()
-        .get::<Symbol, Map<u32, u64>>(&key)
-        .unwrap_or(map![e])
+        .set::<Symbol, u32>(&Symbol::new(e, "CoopWin"), window);
 }
 
-/// Set the pool reserve emissions
+/// Remove the cooperative fill window, such that user liquidation auctions are filled directly
+pub fn del_coop_fill_window(e: &Env) {
+    e.storage().persistent().remove(&Symbol::new(e, "CoopWin"));
+}
+
+/// Fetch the bids registered to cooperatively fill a user's liquidation auction, if any
 ///
 /// ### Arguments
-/// * `emissions` - The map of emissions by reserve token id to EPS
-pub fn set_pool_emissions(e: &Env, emissions: &Map<u32, u64>) {
+/// * `user` - The user being liquidated
+pub fn get_fill_bids(e: &Env, user: &Address) -> Option<FillBids> {
+    let key = PoolDataKey::FillBids(user.clone());
+    e.storage().temporary().get::<PoolDataKey, FillBids>(&key)
+}
+
+/// Set the bids registered to cooperatively fill a user's liquidation auction
+///
+/// ### Arguments
+/// * `user` - The user being liquidated
+/// * `fill_bids` - The registered bids
+pub fn set_fill_bids(e: &Env, user: &Address, fill_bids: &FillBids) {
+    let key = PoolDataKey::FillBids(user.clone());
     e.storage()
-        .persistent()
-        .set::<Symbol, Map<u32, u64>>(&Symbol::new(e, "PoolEmis"), emissions);
+        .temporary()
+        .set::<PoolDataKey, FillBids>(&key, fill_bids);
+    e.storage().temporary().bump(&key, INSTANCE_BUMP_AMOUNT);
 }
 
-/// Fetch the pool emission expiration timestamps
-pub fn get_pool_emissions_expiration(e: &Env) -> u64 {
-    let key = Symbol::new(e, "EmisExp");
-    e.storage().persistent().bump(&key, CYCLE_BUMP_AMOUNT);
+/// Remove the bids registered to cooperatively fill a user's liquidation auction
+///
+/// ### Arguments
+/// * `user` - The user being liquidated
+pub fn del_fill_bids(e: &Env, user: &Address) {
+    let key = PoolDataKey::FillBids(user.clone());
+    e.storage().temporary().remove(&key);
+}
+
+/// Fetch the bad debt auction fill paid by a pending backstop draw, if any
+///
+/// ### Arguments
+/// * `draw_id` - The id of the backstop draw
+pub fn get_bad_debt_fill(e: &Env, draw_id: u32) -> Option<BadDebtFill> {
+    let key = PoolDataKey::BDFill(draw_id);
     e.storage()
         .temporary()
-        .get::<Symbol, u64>(&key)
-        .unwrap_or(0)
+        .get::<PoolDataKey, BadDebtFill>(&key)
 }
 
-/// Set the pool emission configuration
+/// Set the bad debt auction fill paid by a pending backstop draw. The fill only needs to be
+/// kept for the draw's dispute window.
 ///
 /// ### Arguments
-/// * `expiration` - The pool's emission configuration
-pub fn set_pool_emissions_expiration(e: &Env, expiration: &u64) {
+/// * `draw_id` - The id of the backstop draw
+/// * `fill` - The bad debt auction fill
+pub fn set_bad_debt_fill(e: &Env, draw_id: u32, fill: &BadDebtFill) {
+    let key = PoolDataKey::BDFill(draw_id);
     e.storage()
         .temporary()
-        .set::<Symbol, u64>(&Symbol::new(e, "EmisExp"), expiration);
+        .set::<PoolDataKey, BadDebtFill>(&key, fill);
+    e.storage().temporary().bump(&key, INSTANCE_BUMP_AMOUNT);
 }
 
-/********** Auctions ***********/
+/// Remove the bad debt auction fill paid by a backstop draw
+///
+/// ### Arguments
+/// * `draw_id` - The id of the backstop draw
+pub fn del_bad_debt_fill(e: &Env, draw_id: u32) {
+    let key = PoolDataKey::BDFill(draw_id);
+    e.storage().temporary().remove(&key);
+}
 
 /// Fetch the auction data for an auction
 ///
@@ -613,3 +2642,120 @@ pub fn del_auction(e: &Env, auction_type: &u32, user: &Address) {
     });
     e.storage().temporary().remove(&key);
 }
+
+/// Fetch the pool's liquidation auction statistics
+pub fn get_auction_stats(e: &Env) -> AuctionStats {
+    let key = Symbol::new(e, "AuctStats");
+    if let Some(stats) = e.storage().persistent().get::<Symbol, AuctionStats>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return stats;
+    }
+    AuctionStats {
+        created: 0,
+        fills: 0,
+        completed: 0,
+        fill_blocks: 0,
+        avg_fill_blocks: 0,
+        liquidated_value: 0,
+    }
+}
+
+/// Set the pool's liquidation auction statistics
+///
+/// ### Arguments
+/// * `stats` - The liquidation auction statistics
+pub fn set_auction_stats(e: &Env, stats: &AuctionStats) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, AuctionStats>(&Symbol::new(e, "AuctStats"), stats);
+}
+
+/// Fetch the recent fills of an auction type, oldest first
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+pub fn get_auction_history(e: &Env, auction_type: u32) -> Vec<AuctionRecord> {
+    let key = PoolDataKey::AuctHist(auction_type);
+    if let Some(records) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, Vec<AuctionRecord>>(&key)
+    {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return records;
+    }
+    vec![e]
+}
+
+/// Set the recent fills of an auction type
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `records` - The recent fills, oldest first
+pub fn set_auction_history(e: &Env, auction_type: u32, records: &Vec<AuctionRecord>) {
+    let key = PoolDataKey::AuctHist(auction_type);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Vec<AuctionRecord>>(&key, records);
+}
+
+/********** Auction Escrow **********/
+
+/// Fetch the auction lots held in escrow for a user, as a map of asset to the amount
+/// of backstop credit owed
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_user_escrow(e: &Env, user: &Address) -> Map<Address, i128> {
+    let key = PoolDataKey::Escrow(user.clone());
+    if let Some(escrow) = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, Map<Address, i128>>(&key)
+    {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return escrow;
+    }
+    map![e]
+}
+
+/// Set the auction lots held in escrow for a user
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `escrow` - The map of asset to the amount of backstop credit owed
+pub fn set_user_escrow(e: &Env, user: &Address, escrow: &Map<Address, i128>) {
+    let key = PoolDataKey::Escrow(user.clone());
+    if escrow.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage()
+            .persistent()
+            .set::<PoolDataKey, Map<Address, i128>>(&key, escrow);
+    }
+}
+
+/// Fetch the total amount of a reserve's backstop credit held in escrow
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+pub fn get_escrow_total(e: &Env, asset: &Address) -> i128 {
+    let key = PoolDataKey::EscrowTot(asset.clone());
+    if let Some(total) = e.storage().persistent().get::<PoolDataKey, i128>(&key) {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+        return total;
+    }
+    0
+}
+
+/// Set the total amount of a reserve's backstop credit held in escrow
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `total` - The total amount held in escrow
+pub fn set_escrow_total(e: &Env, asset: &Address, total: i128) {
+    let key = PoolDataKey::EscrowTot(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, i128>(&key, &total);
+}
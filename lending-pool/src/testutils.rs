@@ -7,7 +7,12 @@ use crate::{
     storage::{self, ReserveConfig, ReserveData},
 };
 use fixed_point_math::FixedPoint;
-use soroban_sdk::{map, testutils::Address as _, unwrap::UnwrapOptimized, Address, Env, IntoVal};
+use soroban_sdk::{
+    contract, contractimpl, map,
+    testutils::{Address as _, Ledger, LedgerInfo},
+    unwrap::UnwrapOptimized,
+    Address, Env, IntoVal, Symbol,
+};
 
 use backstop_module::{BackstopModule, BackstopModuleClient};
 use mock_oracle::{MockOracle, MockOracleClient};
@@ -63,6 +68,62 @@ pub(crate) fn create_mock_oracle(e: &Env) -> (Address, MockOracleClient) {
     )
 }
 
+//***** Exchange Rate Adapter ******
+
+#[contract]
+pub struct MockExchangeRate;
+
+#[contractimpl]
+impl MockExchangeRate {
+    pub fn set_rate(e: Env, rate: i128) {
+        e.storage()
+            .instance()
+            .set::<Symbol, i128>(&Symbol::new(&e, "Rate"), &rate);
+    }
+
+    pub fn rate(e: Env) -> i128 {
+        e.storage()
+            .instance()
+            .get::<Symbol, i128>(&Symbol::new(&e, "Rate"))
+            .unwrap_optimized()
+    }
+}
+
+pub(crate) fn create_mock_exchange_rate(e: &Env) -> (Address, MockExchangeRateClient) {
+    let contract_address = e.register_contract(None, MockExchangeRate {});
+    (
+        contract_address.clone(),
+        MockExchangeRateClient::new(e, &contract_address),
+    )
+}
+
+//***** Denylist ******
+
+#[contract]
+pub struct MockDenylist;
+
+#[contractimpl]
+impl MockDenylist {
+    pub fn deny(e: Env, address: Address) {
+        e.storage().instance().set::<Address, bool>(&address, &true);
+    }
+
+    pub fn is_denied(e: Env, address: Address) -> bool {
+        e.storage()
+            .instance()
+            .get::<Address, bool>(&address)
+            .unwrap_or(false)
+    }
+}
+
+pub(crate) fn create_mock_denylist(e: &Env) -> (Address, MockDenylistClient) {
+    let contract_address = e.register_contract(None, MockDenylist {});
+    (
+        contract_address.clone(),
+        MockDenylistClient::new(e, &contract_address),
+    )
+}
+
 //***** Pool Factory ******
 
 pub(crate) fn create_mock_pool_factory(e: &Env) -> (Address, MockPoolFactoryClient) {
@@ -93,6 +154,7 @@ pub(crate) fn setup_backstop(
     let (pool_factory, mock_pool_factory_client) = create_mock_pool_factory(e);
     mock_pool_factory_client.set_pool(pool_address);
     BackstopModuleClient::new(e, backstop_id).initialize(
+        &Address::random(e),
         backstop_token,
         blnd_token,
         &pool_factory,
@@ -115,6 +177,7 @@ pub(crate) fn default_reserve(e: &Env) -> Reserve {
         index: 0,
         l_factor: 0_7500000,
         c_factor: 0_7500000,
+        liq_threshold: 0_7500000,
         max_util: 0_9500000,
         last_time: 0,
         scalar: 1_0000000,
@@ -139,6 +202,8 @@ pub(crate) fn default_reserve_meta(e: &Env) -> (ReserveConfig, ReserveData) {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_000_002_000, // 10e-5
+            oracle_asset: None,
+            isolated: false,
             index: 0,
         },
         ReserveData {
@@ -189,3 +254,109 @@ pub(crate) fn create_reserve(
         .mock_all_auths()
         .mint(&pool_address, &to_mint_pool);
 }
+
+//************************************************
+//                Ledger Helpers
+//************************************************
+
+/// Set the ledger timestamp, keeping the sequence number and entry expirations constant
+pub(crate) fn set_timestamp(e: &Env, timestamp: u64) {
+    e.ledger().set(LedgerInfo {
+        timestamp,
+        protocol_version: 1,
+        sequence_number: 100,
+        network_id: Default::default(),
+        base_reserve: 10,
+        min_temp_entry_expiration: 10,
+        min_persistent_entry_expiration: 10,
+        max_entry_expiration: 2000000,
+    });
+}
+
+//************************************************
+//              Keeper Simulation
+//************************************************
+
+/// A strategy run by a `KeeperSim` once per block. Strategies are invoked as the pool
+/// with the number of blocks the simulation has stepped.
+pub(crate) type SimStrategy<'a> = std::boxed::Box<dyn FnMut(&Env, u32) + 'a>;
+
+/// Steps the environment block by block, running each user strategy and then each keeper
+/// strategy against the pool every block. Allows the economic behavior of auction parameters
+/// to be tested by simulating users moving the market and keepers filling auctions under
+/// their own fill rules.
+///
+/// Keepers are run in the order they were added, such that earlier keepers win any contention
+/// for a fill within a block.
+pub(crate) struct KeeperSim<'a> {
+    e: Env,
+    pool_address: Address,
+    block_time: u64,
+    blocks: u32,
+    users: std::vec::Vec<SimStrategy<'a>>,
+    keepers: std::vec::Vec<SimStrategy<'a>>,
+}
+
+impl<'a> KeeperSim<'a> {
+    /// Create a simulation against the pool, with a block time of 5 seconds
+    pub(crate) fn new(e: &Env, pool_address: &Address) -> Self {
+        KeeperSim {
+            e: e.clone(),
+            pool_address: pool_address.clone(),
+            block_time: 5,
+            blocks: 0,
+            users: std::vec::Vec::new(),
+            keepers: std::vec::Vec::new(),
+        }
+    }
+
+    /// Add a user strategy, run before the keepers each block
+    pub(crate) fn add_user(&mut self, strategy: impl FnMut(&Env, u32) + 'a) {
+        self.users.push(std::boxed::Box::new(strategy));
+    }
+
+    /// Add a keeper strategy, run after the users each block
+    pub(crate) fn add_keeper(&mut self, strategy: impl FnMut(&Env, u32) + 'a) {
+        self.keepers.push(std::boxed::Box::new(strategy));
+    }
+
+    /// The number of blocks the simulation has stepped
+    pub(crate) fn blocks(&self) -> u32 {
+        self.blocks
+    }
+
+    /// Advance the ledger by one block and run every strategy
+    pub(crate) fn step(&mut self) {
+        let ledger = self.e.ledger().get();
+        self.e.ledger().set(LedgerInfo {
+            timestamp: ledger.timestamp + self.block_time,
+            sequence_number: ledger.sequence_number + 1,
+            ..ledger
+        });
+        self.blocks += 1;
+
+        let e = &self.e;
+        let blocks = self.blocks;
+        for strategy in self.users.iter_mut().chain(self.keepers.iter_mut()) {
+            e.as_contract(&self.pool_address, || strategy(e, blocks));
+        }
+    }
+
+    /// Step the simulation until "done" returns true or "max_blocks" have been stepped.
+    ///
+    /// Returns the number of blocks stepped by the simulation when "done" returned true, or
+    /// None if it never did
+    pub(crate) fn run_until(
+        &mut self,
+        max_blocks: u32,
+        mut done: impl FnMut(&Env) -> bool,
+    ) -> Option<u32> {
+        for _ in 0..max_blocks {
+            self.step();
+            if self.e.as_contract(&self.pool_address, || done(&self.e)) {
+                return Some(self.blocks);
+            }
+        }
+        None
+    }
+}
@@ -1,6 +1,13 @@
-use soroban_sdk::{panic_with_error, Env};
+use fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, Vec};
 
-use crate::errors::PoolError;
+use crate::{
+    constants::SCALAR_7,
+    dependencies::DenylistClient,
+    errors::PoolError,
+    pool::{Pool, PositionData, Positions, Request},
+    storage,
+};
 
 /// Require that an incoming amount is not negative
 ///
@@ -15,6 +22,193 @@ pub fn require_nonnegative(e: &Env, amount: &i128) {
     }
 }
 
+/// Require that a user's leverage does not exceed the pool's maximum leverage, if one is set.
+/// Leverage is the ratio of a user's effective liabilities to their effective equity, such that
+/// recursive borrowing is capped independently of each asset's factors.
+///
+/// ### Arguments
+/// * `position_data` - The position data of the user
+/// * `credit` - The uncollateralized credit available to the user, denominated in the base asset
+///
+/// ### Panics
+/// If the user's leverage exceeds the pool's maximum leverage
+pub fn require_max_leverage(e: &Env, position_data: &PositionData, credit: i128) {
+    if exceeds_max_leverage(e, position_data, credit) {
+        panic_with_error!(e, PoolError::MaxLeverageExceeded);
+    }
+}
+
+/// Check if a user's leverage exceeds the pool's maximum leverage, if one is set
+///
+/// ### Arguments
+/// * `position_data` - The position data of the user
+/// * `credit` - The uncollateralized credit available to the user, denominated in the base asset
+pub fn exceeds_max_leverage(e: &Env, position_data: &PositionData, credit: i128) -> bool {
+    if position_data.liability_base == 0 {
+        return false;
+    }
+    if let Some(max_leverage) = storage::get_max_leverage(e) {
+        let equity = position_data.collateral_base + credit - position_data.liability_base;
+        if equity <= 0 {
+            return true;
+        }
+        let leverage = position_data
+            .liability_base
+            .fixed_div_ceil(equity, SCALAR_7)
+            .unwrap_optimized();
+        return leverage > max_leverage;
+    }
+    false
+}
+
+/// Require that an isolated user's liabilities are within the pool's isolation config. A user is
+/// isolated if any of their collateral is in an isolated reserve, and can then only borrow from
+/// the reserves the config allows, up to the config's debt cap.
+///
+/// The limits are only enforced when the user's liabilities grow or the user becomes isolated,
+/// such that an isolated user can always reduce their positions.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `prev_positions` - The user's positions before the change
+/// * `new_positions` - The user's positions after the change
+///
+/// ### Panics
+/// If the user is isolated and borrows from a reserve the config does not allow, or their
+/// liability value exceeds the config's debt cap
+pub fn require_isolation_limits(
+    e: &Env,
+    pool: &mut Pool,
+    prev_positions: &Positions,
+    new_positions: &Positions,
+) {
+    if new_positions.liabilities.is_empty() || !new_positions.is_isolated(e) {
+        return;
+    }
+    let mut increased = !prev_positions.is_isolated(e);
+    for (reserve_index, d_tokens) in new_positions.liabilities.iter() {
+        if d_tokens > prev_positions.liabilities.get(reserve_index).unwrap_or(0) {
+            increased = true;
+        }
+    }
+    if !increased {
+        return;
+    }
+
+    // isolated users cannot borrow until an isolation config is set
+    let config = match storage::get_isolation_config(e) {
+        Some(config) => config,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    let reserve_list = storage::get_res_list(e);
+    for (reserve_index, _) in new_positions.liabilities.iter() {
+        if !config
+            .borrowable
+            .contains(&reserve_list.get_unchecked(reserve_index))
+        {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+    }
+    let position_data = PositionData::calculate_from_positions(e, pool, new_positions);
+    if position_data.liability_raw > config.debt_cap {
+        panic_with_error!(e, PoolError::ExposureCapExceeded);
+    }
+}
+
+/// Require that a user's liability value is not below the pool's minimum debt, if one is set,
+/// such that positions too small to be profitably liquidated cannot be created.
+///
+/// The minimum is only enforced when the user's liabilities change, and a user can always repay
+/// their liabilities in full.
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `prev_positions` - The user's positions before the change
+/// * `new_positions` - The user's positions after the change
+///
+/// ### Panics
+/// If the user's liability value is greater than zero but less than the pool's minimum debt
+pub fn require_min_debt(
+    e: &Env,
+    pool: &mut Pool,
+    prev_positions: &Positions,
+    new_positions: &Positions,
+) {
+    if new_positions.liabilities.is_empty()
+        || new_positions.liabilities == prev_positions.liabilities
+    {
+        return;
+    }
+    if let Some(min_debt) = storage::get_min_debt(e) {
+        let position_data = PositionData::calculate_from_positions(e, pool, new_positions);
+        if position_data.liability_raw < min_debt {
+            panic_with_error!(e, PoolError::DebtTooSmall);
+        }
+    }
+}
+
+/// Require that a set of requests does not round trip an asset, if the pool blocks round trips.
+/// A round trip supplies and then borrows, or borrows and then withdraws, the same asset within
+/// a single submit, which can be used to manipulate a reserve's utilization or the price of its
+/// tokens within a single transaction.
+///
+/// ### Arguments
+/// * `requests` - The requests being submitted
+///
+/// ### Panics
+/// If the pool blocks round trips and the requests round trip an asset
+pub fn require_no_round_trips(e: &Env, requests: &Vec<Request>) {
+    if !storage::get_block_round_trips(e) {
+        return;
+    }
+    let mut supplied: Vec<Address> = vec![e];
+    let mut borrowed: Vec<Address> = vec![e];
+    for request in requests.iter() {
+        match request.request_type {
+            // supply or supply collateral
+            0 | 2 => supplied.push_back(request.address),
+            // withdraw, withdraw collateral, or close position
+            1 | 3 | 12 => {
+                if borrowed.contains(&request.address) {
+                    panic_with_error!(e, PoolError::RoundTripBlocked);
+                }
+            }
+            // borrow
+            4 => {
+                if supplied.contains(&request.address) {
+                    panic_with_error!(e, PoolError::RoundTripBlocked);
+                }
+                borrowed.push_back(request.address);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Require that an address is not denied by the pool's denylist contract, if one is set
+///
+/// ### Arguments
+/// * `address` - The address to check
+///
+/// ### Panics
+/// If the pool has a denylist and the address is denied
+pub fn require_not_denied(e: &Env, address: &Address) {
+    if is_denied(e, address) {
+        panic_with_error!(e, PoolError::AddressDenied);
+    }
+}
+
+/// Check if an address is denied by the pool's denylist contract, if one is set
+///
+/// ### Arguments
+/// * `address` - The address to check
+pub fn is_denied(e: &Env, address: &Address) -> bool {
+    match storage::get_denylist(e) {
+        Some(denylist) => DenylistClient::new(e, &denylist).is_denied(address),
+        None => false,
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use soroban_sdk::testutils::Address as _;
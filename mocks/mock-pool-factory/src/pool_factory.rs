@@ -6,7 +6,7 @@ use soroban_sdk::{
     contract, contractimpl, panic_with_error, vec, Address, BytesN, Env, IntoVal, Symbol, Val, Vec,
 };
 
-use lending_pool::Pool;
+use lending_pool::{Pool, PoolInitConfig};
 
 #[contract]
 pub struct MockPoolFactory;
@@ -74,14 +74,17 @@ impl MockPoolFactoryTrait for MockPoolFactory {
             panic_with_error!(&e, PoolFactoryError::InvalidPoolInitArgs);
         }
 
-        let mut init_args: Vec<Val> = vec![&e];
-        init_args.push_back(admin.to_val());
-        init_args.push_back(name.to_val());
-        init_args.push_back(oracle.to_val());
-        init_args.push_back(backstop_take_rate.into_val(&e));
-        init_args.push_back(pool_init_meta.backstop.to_val());
-        init_args.push_back(pool_init_meta.blnd_id.to_val());
-        init_args.push_back(pool_init_meta.usdc_id.to_val());
+        let init_config = PoolInitConfig {
+            admin,
+            name,
+            oracle,
+            bstop_rate: backstop_take_rate,
+            permissioned: false,
+            backstop: pool_init_meta.backstop,
+            blnd_id: pool_init_meta.blnd_id,
+            usdc_id: pool_init_meta.usdc_id,
+        };
+        let init_args: Vec<Val> = vec![&e, init_config.into_val(&e)];
 
         let pool_address = e.register_contract(None, Pool {});
         e.invoke_contract::<Val>(&pool_address, &Symbol::new(&e, "initialize"), init_args);
@@ -6,4 +6,6 @@ use soroban_sdk::contracterror;
 pub enum PoolFactoryError {
     AlreadyInitialized = 40,
     InvalidPoolInitArgs = 50,
+    InvalidPreset = 60,
+    InvalidReserveConfig = 61,
 }
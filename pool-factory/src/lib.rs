@@ -5,9 +5,10 @@ extern crate std;
 
 mod errors;
 mod pool_factory;
+mod presets;
 mod storage;
 mod test;
 
 pub use errors::PoolFactoryError;
 pub use pool_factory::*;
-pub use storage::{PoolFactoryDataKey, PoolInitMeta};
+pub use storage::{PoolFactoryDataKey, PoolInitConfig, PoolInitMeta, ReserveConfig, ReservePreset};
@@ -1,16 +1,18 @@
 use crate::{
     errors::PoolFactoryError,
-    storage::{self, PoolInitMeta},
+    presets,
+    storage::{self, PoolInitConfig, PoolInitMeta, ReserveConfig, ReservePreset},
 };
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, vec, Address, BytesN, Env, IntoVal, Symbol, Val, Vec,
+    contract, contractimpl, panic_with_error, vec, Address, BytesN, Env, IntoVal, Map, Symbol, Val,
+    Vec,
 };
 
 #[contract]
 pub struct PoolFactory;
 
 pub trait PoolFactoryTrait {
-    /// Setup the pool factory
+    /// Setup the pool factory and store the default reserve presets
     ///
     /// ### Arguments
     /// * `pool_init_meta` - The pool initialization metadata
@@ -23,6 +25,7 @@ pub trait PoolFactoryTrait {
     /// * `name` - The name of the pool
     /// * `oracle` - The oracle address for the pool
     /// * `backstop_take_rate` - The backstop take rate for the pool
+    /// * `permissioned` - If only allowlisted users can submit requests and fill auctions
     fn deploy(
         e: Env,
         admin: Address,
@@ -30,6 +33,7 @@ pub trait PoolFactoryTrait {
         salt: BytesN<32>,
         oracle: Address,
         backstop_take_rate: u64,
+        permissioned: bool,
     ) -> Address;
 
     /// Checks if contract address was deployed by the factory
@@ -39,6 +43,33 @@ pub trait PoolFactoryTrait {
     /// # Arguments
     /// * `pool_id` - The contract address to be checked
     fn is_pool(e: Env, pool_id: Address) -> bool;
+
+    /// Fetch a reserve preset
+    ///
+    /// # Arguments
+    /// * `name` - The name of the preset ("conservative", "standard" or "aggressive")
+    ///
+    /// # Panics
+    /// If the preset does not exist
+    fn get_reserve_preset(e: Env, name: Symbol) -> ReservePreset;
+
+    /// Build a reserve config from a preset that can be passed to a pool's `init_reserve`
+    /// or `update_reserve`, with any fields overridden by name
+    ///
+    /// # Arguments
+    /// * `name` - The name of the preset
+    /// * `decimals` - The decimals of the reserve's underlying token
+    /// * `overrides` - A map of field name (e.g. "c_factor") to value for fields to override
+    ///
+    /// # Panics
+    /// If the preset does not exist, an override does not name a preset field, or the
+    /// resulting config is invalid
+    fn reserve_config(
+        e: Env,
+        name: Symbol,
+        decimals: u32,
+        overrides: Map<Symbol, u32>,
+    ) -> ReserveConfig;
 }
 
 #[contractimpl]
@@ -48,6 +79,7 @@ impl PoolFactoryTrait for PoolFactory {
             panic_with_error!(&e, PoolFactoryError::AlreadyInitialized);
         }
         storage::set_pool_init_meta(&e, &pool_init_meta);
+        presets::set_default_presets(&e);
     }
 
     fn deploy(
@@ -57,6 +89,7 @@ impl PoolFactoryTrait for PoolFactory {
         salt: BytesN<32>,
         oracle: Address,
         backstop_take_rate: u64,
+        permissioned: bool,
     ) -> Address {
         storage::bump_instance(&e);
         let pool_init_meta = storage::get_pool_init_meta(&e);
@@ -66,14 +99,17 @@ impl PoolFactoryTrait for PoolFactory {
             panic_with_error!(&e, PoolFactoryError::InvalidPoolInitArgs);
         }
 
-        let mut init_args: Vec<Val> = vec![&e];
-        init_args.push_back(admin.to_val());
-        init_args.push_back(name.to_val());
-        init_args.push_back(oracle.to_val());
-        init_args.push_back(backstop_take_rate.into_val(&e));
-        init_args.push_back(pool_init_meta.backstop.to_val());
-        init_args.push_back(pool_init_meta.blnd_id.to_val());
-        init_args.push_back(pool_init_meta.usdc_id.to_val());
+        let init_config = PoolInitConfig {
+            admin,
+            name,
+            oracle,
+            bstop_rate: backstop_take_rate,
+            permissioned,
+            backstop: pool_init_meta.backstop,
+            blnd_id: pool_init_meta.blnd_id,
+            usdc_id: pool_init_meta.usdc_id,
+        };
+        let init_args: Vec<Val> = vec![&e, init_config.into_val(&e)];
         let pool_address = e
             .deployer()
             .with_current_contract(salt)
@@ -91,4 +127,19 @@ impl PoolFactoryTrait for PoolFactory {
         storage::bump_instance(&e);
         storage::is_deployed(&e, &pool_address)
     }
+
+    fn get_reserve_preset(e: Env, name: Symbol) -> ReservePreset {
+        storage::bump_instance(&e);
+        presets::load_preset(&e, &name)
+    }
+
+    fn reserve_config(
+        e: Env,
+        name: Symbol,
+        decimals: u32,
+        overrides: Map<Symbol, u32>,
+    ) -> ReserveConfig {
+        storage::bump_instance(&e);
+        presets::build_reserve_config(&e, &name, decimals, &overrides)
+    }
 }
@@ -0,0 +1,137 @@
+use soroban_sdk::{panic_with_error, Env, Map, Symbol};
+
+use crate::{
+    errors::PoolFactoryError,
+    storage::{self, ReserveConfig, ReservePreset},
+};
+
+/// Store the default reserve presets
+///
+/// * `conservative` - Low collateral factors and a low target utilization for volatile assets
+/// * `standard` - Moderate parameters suited to most assets
+/// * `aggressive` - High collateral factors and a high target utilization for stable assets
+pub fn set_default_presets(e: &Env) {
+    storage::set_preset(
+        e,
+        &Symbol::new(e, "conservative"),
+        &ReservePreset {
+            c_factor: 0_6000000,
+            l_factor: 0_7000000,
+            util: 0_6000000,
+            max_util: 0_9000000,
+            r_one: 0_0300000,
+            r_two: 0_3000000,
+            r_three: 1_0000000,
+            reactivity: 0_000_001_000,
+        },
+    );
+    storage::set_preset(
+        e,
+        &Symbol::new(e, "standard"),
+        &ReservePreset {
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_7500000,
+            max_util: 0_9500000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 0_000_002_000,
+        },
+    );
+    storage::set_preset(
+        e,
+        &Symbol::new(e, "aggressive"),
+        &ReservePreset {
+            c_factor: 0_9000000,
+            l_factor: 0_9000000,
+            util: 0_8500000,
+            max_util: 0_9800000,
+            r_one: 0_0800000,
+            r_two: 0_8000000,
+            r_three: 2_0000000,
+            reactivity: 0_000_004_000,
+        },
+    );
+}
+
+/// Fetch a reserve preset
+///
+/// ### Panics
+/// If the preset does not exist
+pub fn load_preset(e: &Env, name: &Symbol) -> ReservePreset {
+    match storage::get_preset(e, name) {
+        Some(preset) => preset,
+        None => panic_with_error!(e, PoolFactoryError::InvalidPreset),
+    }
+}
+
+/// Build a reserve config from a preset, with any fields overridden by name
+///
+/// ### Arguments
+/// * `name` - The name of the preset
+/// * `decimals` - The decimals of the reserve's underlying token
+/// * `overrides` - A map of field name to value for fields to override
+///
+/// ### Panics
+/// If the preset does not exist, an override does not name a preset field, or the
+/// resulting config is invalid
+pub fn build_reserve_config(
+    e: &Env,
+    name: &Symbol,
+    decimals: u32,
+    overrides: &Map<Symbol, u32>,
+) -> ReserveConfig {
+    let preset = load_preset(e, name);
+    let mut config = ReserveConfig {
+        index: 0,
+        decimals,
+        c_factor: preset.c_factor,
+        l_factor: preset.l_factor,
+        util: preset.util,
+        max_util: preset.max_util,
+        r_one: preset.r_one,
+        r_two: preset.r_two,
+        r_three: preset.r_three,
+        reactivity: preset.reactivity,
+        oracle_asset: None,
+        isolated: false,
+    };
+    for (field, value) in overrides.iter() {
+        if field == Symbol::new(e, "c_factor") {
+            config.c_factor = value;
+        } else if field == Symbol::new(e, "l_factor") {
+            config.l_factor = value;
+        } else if field == Symbol::new(e, "util") {
+            config.util = value;
+        } else if field == Symbol::new(e, "max_util") {
+            config.max_util = value;
+        } else if field == Symbol::new(e, "r_one") {
+            config.r_one = value;
+        } else if field == Symbol::new(e, "r_two") {
+            config.r_two = value;
+        } else if field == Symbol::new(e, "r_three") {
+            config.r_three = value;
+        } else if field == Symbol::new(e, "reactivity") {
+            config.reactivity = value;
+        } else {
+            panic_with_error!(e, PoolFactoryError::InvalidReserveConfig);
+        }
+    }
+    require_valid_reserve_config(e, &config);
+    config
+}
+
+/// Require that a reserve config would be accepted by the lending pool
+fn require_valid_reserve_config(e: &Env, config: &ReserveConfig) {
+    if config.decimals > 18
+        || config.c_factor > 1_0000000
+        || config.l_factor > 1_0000000
+        || config.util > 0_9500000
+        || (config.max_util > 1_0000000 || config.max_util <= config.util)
+        || (config.r_one > config.r_two || config.r_two > config.r_three)
+        || (config.reactivity > 0_0005000)
+    {
+        panic_with_error!(e, PoolFactoryError::InvalidReserveConfig);
+    }
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, BytesN, Env};
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, BytesN, Env, Symbol};
 
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 34560; // 2 days
 pub(crate) const CYCLE_BUMP_AMOUNT: u32 = 69120; // 10 days - use for shared data accessed on the 7-day cycle window
@@ -9,6 +9,7 @@ pub(crate) const USER_BUMP_AMOUNT: u32 = 518400; // 30 days
 pub enum PoolFactoryDataKey {
     Contracts(Address),
     PoolInitMeta,
+    Preset(Symbol),
 }
 
 #[derive(Clone)]
@@ -20,6 +21,54 @@ pub struct PoolInitMeta {
     pub usdc_id: Address, //Must have 7 token decimals due to lot decimal restriction in backstop interest auctions
 }
 
+/// The config a pool is initialized with, matching the layout of the lending pool's PoolInitConfig
+#[derive(Clone)]
+#[contracttype]
+pub struct PoolInitConfig {
+    pub admin: Address,
+    pub name: Symbol,
+    pub oracle: Address,
+    pub bstop_rate: u64, // the rate the backstop takes on accrued debt interest, expressed in 9 decimals
+    pub permissioned: bool, // if only allowlisted users can submit requests and fill auctions
+    pub backstop: Address,
+    pub blnd_id: Address,
+    pub usdc_id: Address,
+}
+
+/// A template of reserve risk parameters that pool admins can reference when adding reserves
+#[derive(Clone)]
+#[contracttype]
+pub struct ReservePreset {
+    pub c_factor: u32, // the collateral factor for the reserve scaled expressed in 7 decimals
+    pub l_factor: u32, // the liability factor for the reserve scaled expressed in 7 decimals
+    pub util: u32,     // the target utilization rate scaled expressed in 7 decimals
+    pub max_util: u32, // the maximum allowed utilization rate scaled expressed in 7 decimals
+    pub r_one: u32,    // the R1 value in the interest rate formula scaled expressed in 7 decimals
+    pub r_two: u32,    // the R2 value in the interest rate formula scaled expressed in 7 decimals
+    pub r_three: u32,  // the R3 value in the interest rate formula scaled expressed in 7 decimals
+    pub reactivity: u32, // the reactivity constant for the reserve scaled expressed in 9 decimals
+}
+
+/// The configuration of a reserve, matching the layout of the lending pool's ReserveConfig
+#[derive(Clone)]
+#[contracttype]
+pub struct ReserveConfig {
+    pub index: u32,      // the index of the reserve in the list (set by the pool)
+    pub decimals: u32,   // the decimals used in both the bToken and underlying contract
+    pub c_factor: u32,   // the collateral factor for the reserve scaled expressed in 7 decimals
+    pub l_factor: u32,   // the liability factor for the reserve scaled expressed in 7 decimals
+    pub util: u32,       // the target utilization rate scaled expressed in 7 decimals
+    pub max_util: u32,   // the maximum allowed utilization rate scaled expressed in 7 decimals
+    pub r_one: u32,      // the R1 value in the interest rate formula scaled expressed in 7 decimals
+    pub r_two: u32,      // the R2 value in the interest rate formula scaled expressed in 7 decimals
+    pub r_three: u32,    // the R3 value in the interest rate formula scaled expressed in 7 decimals
+    pub reactivity: u32, // the reactivity constant for the reserve scaled expressed in 9 decimals
+    // the asset the oracle prices the reserve as, if not the reserve's asset
+    pub oracle_asset: Option<Address>,
+    // true if users collateralizing the reserve are limited to the pool's isolation config
+    pub isolated: bool,
+}
+
 /// Bump the instance rent for the contract
 pub fn bump_instance(e: &Env) {
     e.storage().instance().bump(INSTANCE_BUMP_AMOUNT);
@@ -76,3 +125,32 @@ pub fn set_deployed(e: &Env, contract_id: &Address) {
         .persistent()
         .set::<PoolFactoryDataKey, bool>(&key, &true);
 }
+
+/// Fetch a reserve preset, if it exists
+///
+/// ### Arguments
+/// * `name` - The name of the preset
+pub fn get_preset(e: &Env, name: &Symbol) -> Option<ReservePreset> {
+    let key = PoolFactoryDataKey::Preset(name.clone());
+    if let Some(preset) = e
+        .storage()
+        .persistent()
+        .get::<PoolFactoryDataKey, ReservePreset>(&key)
+    {
+        e.storage().persistent().bump(&key, USER_BUMP_AMOUNT);
+        return Some(preset);
+    }
+    None
+}
+
+/// Set a reserve preset
+///
+/// ### Arguments
+/// * `name` - The name of the preset
+/// * `preset` - The reserve preset
+pub fn set_preset(e: &Env, name: &Symbol, preset: &ReservePreset) {
+    let key = PoolFactoryDataKey::Preset(name.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolFactoryDataKey, ReservePreset>(&key, preset);
+}
@@ -1,6 +1,7 @@
 #![cfg(test)]
 
 use soroban_sdk::{
+    map,
     testutils::{Address as _, BytesN as _, Events},
     vec, Address, BytesN, Env, IntoVal, Symbol,
 };
@@ -51,7 +52,7 @@ fn test_pool_factory() {
     let name2 = Symbol::new(&e, "pool2");
     let salt = BytesN::<32>::random(&e);
     let deployed_pool_address_1 =
-        pool_factory_client.deploy(&bombadil, &name1, &salt, &oracle, &backstop_rate);
+        pool_factory_client.deploy(&bombadil, &name1, &salt, &oracle, &backstop_rate, &false);
 
     let event = vec![&e, e.events().all().last_unchecked()];
     assert_eq!(
@@ -68,7 +69,7 @@ fn test_pool_factory() {
 
     let salt = BytesN::<32>::random(&e);
     let deployed_pool_address_2 =
-        pool_factory_client.deploy(&bombadil, &name2, &salt, &oracle, &backstop_rate);
+        pool_factory_client.deploy(&bombadil, &name2, &salt, &oracle, &backstop_rate, &true);
 
     let zero_address = Address::from_contract_id(&BytesN::from_array(&e, &[0; 32]));
     e.as_contract(&deployed_pool_address_1, || {
@@ -92,9 +93,11 @@ fn test_pool_factory() {
                 .get::<_, lending_pool::PoolConfig>(&Symbol::new(&e, "PoolConfig"))
                 .unwrap(),
             lending_pool::PoolConfig {
-                oracle: oracle,
+                oracle: oracle.clone(),
                 bstop_rate: backstop_rate,
-                status: 1
+                status: 1,
+                liq_hf_low: 1_0000000,
+                liq_hf_high: 1_0000100
             }
         );
         assert_eq!(
@@ -111,6 +114,36 @@ fn test_pool_factory() {
                 .unwrap(),
             usdc_id.clone()
         );
+        assert!(!e
+            .storage()
+            .persistent()
+            .get::<_, bool>(&Symbol::new(&e, "Permission"))
+            .unwrap());
+    });
+
+    // verify a retried initialization with the same config is a no-op
+    let pool_client_1 = lending_pool::Client::new(&e, &deployed_pool_address_1);
+    let mut init_config = lending_pool::PoolInitConfig {
+        admin: bombadil.clone(),
+        name: name1.clone(),
+        oracle: oracle.clone(),
+        bstop_rate: backstop_rate,
+        permissioned: false,
+        backstop: backstop_id.clone(),
+        blnd_id: blnd_id.clone(),
+        usdc_id: usdc_id.clone(),
+    };
+    pool_client_1.initialize(&init_config);
+    init_config.permissioned = true;
+    let result = pool_client_1.try_initialize(&init_config);
+    assert!(result.is_err());
+
+    e.as_contract(&deployed_pool_address_2, || {
+        assert!(e
+            .storage()
+            .persistent()
+            .get::<_, bool>(&Symbol::new(&e, "Permission"))
+            .unwrap());
     });
     assert_ne!(deployed_pool_address_1, zero_address);
     assert_ne!(deployed_pool_address_2, zero_address);
@@ -119,3 +152,51 @@ fn test_pool_factory() {
     assert!(pool_factory_client.is_pool(&deployed_pool_address_2));
     assert!(!pool_factory_client.is_pool(&zero_address));
 }
+
+#[test]
+fn test_reserve_presets() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    let (_, pool_factory_client) = create_pool_factory(&e);
+
+    let pool_init_meta = PoolInitMeta {
+        backstop: Address::random(&e),
+        pool_hash: BytesN::<32>::random(&e),
+        blnd_id: Address::random(&e),
+        usdc_id: Address::random(&e),
+    };
+    pool_factory_client.initialize(&pool_init_meta);
+
+    let standard = pool_factory_client.get_reserve_preset(&Symbol::new(&e, "standard"));
+    assert_eq!(standard.c_factor, 0_7500000);
+    assert_eq!(standard.max_util, 0_9500000);
+
+    let config = pool_factory_client.reserve_config(
+        &Symbol::new(&e, "conservative"),
+        &6,
+        &map![&e, (Symbol::new(&e, "c_factor"), 0_5000000)],
+    );
+    assert_eq!(config.index, 0);
+    assert_eq!(config.decimals, 6);
+    assert_eq!(config.c_factor, 0_5000000);
+    assert_eq!(config.l_factor, 0_7000000);
+    assert_eq!(config.util, 0_6000000);
+
+    // verify unknown presets and fields are rejected
+    let result = pool_factory_client.try_get_reserve_preset(&Symbol::new(&e, "degen"));
+    assert!(result.is_err());
+    let result = pool_factory_client.try_reserve_config(
+        &Symbol::new(&e, "standard"),
+        &7,
+        &map![&e, (Symbol::new(&e, "index"), 3)],
+    );
+    assert!(result.is_err());
+
+    // verify overrides that produce an invalid config are rejected
+    let result = pool_factory_client.try_reserve_config(
+        &Symbol::new(&e, "standard"),
+        &7,
+        &map![&e, (Symbol::new(&e, "util"), 0_9600000)],
+    );
+    assert!(result.is_err());
+}
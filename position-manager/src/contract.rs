@@ -0,0 +1,264 @@
+use crate::{
+    errors::ManagerError,
+    manager,
+    storage::{self, StrategyCap},
+};
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env, Symbol};
+
+/// ### Position Manager
+///
+/// Performs composite strategies, like leverage loops, against a lending pool on behalf of users
+#[contract]
+pub struct PositionManager;
+
+pub trait PositionManagerTrait {
+    /// Initialize the position manager
+    ///
+    /// ### Arguments
+    /// * `admin` - The Address for the admin
+    /// * `adapter` - The Address of the swap adapter contract
+    ///
+    /// ### Panics
+    /// If the position manager is already initialized
+    fn initialize(e: Env, admin: Address, adapter: Address);
+
+    /// (Admin only) Set the swap adapter contract
+    ///
+    /// ### Arguments
+    /// * `adapter` - The Address of the swap adapter contract
+    fn set_adapter(e: Env, adapter: Address);
+
+    /// (Admin only) Set the safety caps for a strategy. A cap with no loops or no amount disables
+    /// the strategy. Strategies are disabled until caps are set.
+    ///
+    /// ### Arguments
+    /// * `strategy` - The strategy, 0 for leverage, 1 for unwind, and 2 for collateral swap
+    /// * `cap` - The safety caps
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the strategy does not exist, or the cap is invalid
+    fn set_strategy_cap(e: Env, strategy: u32, cap: StrategyCap);
+
+    /// Fetch the safety caps for a strategy, or None if the strategy is disabled
+    ///
+    /// ### Arguments
+    /// * `strategy` - The strategy
+    fn get_strategy_cap(e: Env, strategy: u32) -> Option<StrategyCap>;
+
+    /// Leverage a position by borrowing "liability", swapping it for "collateral", and supplying
+    /// the collateral, once per loop
+    ///
+    /// Returns the total amount of collateral supplied
+    ///
+    /// ### Arguments
+    /// * `user` - The user whose position is being leveraged
+    /// * `pool` - The pool the position is in
+    /// * `collateral` - The collateral asset
+    /// * `liability` - The liability asset
+    /// * `borrow_amount` - The amount of "liability" borrowed each loop
+    /// * `loops` - The number of loops
+    /// * `min_amount_out` - The minimum amount of "collateral" received from each swap
+    ///
+    /// ### Panics
+    /// If the strategy is disabled, the call exceeds its caps, a swap returns less than the
+    /// minimum, or the pool rejects a step
+    #[allow(clippy::too_many_arguments)]
+    fn leverage(
+        e: Env,
+        user: Address,
+        pool: Address,
+        collateral: Address,
+        liability: Address,
+        borrow_amount: i128,
+        loops: u32,
+        min_amount_out: i128,
+    ) -> i128;
+
+    /// Unwind a position by withdrawing "collateral", swapping it for "liability", and repaying
+    /// the liability, once per loop
+    ///
+    /// Returns the total amount of liability repaid
+    ///
+    /// ### Arguments
+    /// * `user` - The user whose position is being unwound
+    /// * `pool` - The pool the position is in
+    /// * `collateral` - The collateral asset
+    /// * `liability` - The liability asset
+    /// * `withdraw_amount` - The amount of "collateral" withdrawn each loop
+    /// * `loops` - The number of loops
+    /// * `min_amount_out` - The minimum amount of "liability" received from each swap
+    ///
+    /// ### Panics
+    /// If the strategy is disabled, the call exceeds its caps, a swap returns less than the
+    /// minimum, or the pool rejects a step
+    #[allow(clippy::too_many_arguments)]
+    fn unwind(
+        e: Env,
+        user: Address,
+        pool: Address,
+        collateral: Address,
+        liability: Address,
+        withdraw_amount: i128,
+        loops: u32,
+        min_amount_out: i128,
+    ) -> i128;
+
+    /// Swap one collateral asset for another
+    ///
+    /// Returns the amount of "to_asset" supplied
+    ///
+    /// ### Arguments
+    /// * `user` - The user whose collateral is being swapped
+    /// * `pool` - The pool the position is in
+    /// * `from_asset` - The collateral asset being sold
+    /// * `to_asset` - The collateral asset being bought
+    /// * `amount` - The amount of "from_asset" to swap
+    /// * `min_amount_out` - The minimum amount of "to_asset" received from the swap
+    ///
+    /// ### Panics
+    /// If the strategy is disabled, the call exceeds its caps, the swap returns less than the
+    /// minimum, or the pool rejects a step
+    fn swap_collateral(
+        e: Env,
+        user: Address,
+        pool: Address,
+        from_asset: Address,
+        to_asset: Address,
+        amount: i128,
+        min_amount_out: i128,
+    ) -> i128;
+}
+
+#[contractimpl]
+impl PositionManagerTrait for PositionManager {
+    fn initialize(e: Env, admin: Address, adapter: Address) {
+        if storage::has_admin(&e) {
+            panic_with_error!(&e, ManagerError::AlreadyInitialized);
+        }
+
+        storage::set_admin(&e, &admin);
+        storage::set_adapter(&e, &adapter);
+    }
+
+    fn set_adapter(e: Env, adapter: Address) {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        storage::set_adapter(&e, &adapter);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_adapter"), admin), adapter);
+    }
+
+    fn set_strategy_cap(e: Env, strategy: u32, cap: StrategyCap) {
+        storage::bump_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        manager::set_strategy_cap(&e, strategy, &cap);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_strategy_cap"), admin, strategy), cap);
+    }
+
+    fn get_strategy_cap(e: Env, strategy: u32) -> Option<StrategyCap> {
+        storage::get_strategy_cap(&e, strategy)
+    }
+
+    fn leverage(
+        e: Env,
+        user: Address,
+        pool: Address,
+        collateral: Address,
+        liability: Address,
+        borrow_amount: i128,
+        loops: u32,
+        min_amount_out: i128,
+    ) -> i128 {
+        storage::bump_instance(&e);
+        user.require_auth();
+
+        let supplied = manager::execute_leverage(
+            &e,
+            &user,
+            &pool,
+            &collateral,
+            &liability,
+            borrow_amount,
+            loops,
+            min_amount_out,
+        );
+
+        e.events().publish(
+            (Symbol::new(&e, "leverage"), pool, user),
+            (
+                collateral,
+                supplied,
+                liability,
+                borrow_amount * i128::from(loops),
+            ),
+        );
+        supplied
+    }
+
+    fn unwind(
+        e: Env,
+        user: Address,
+        pool: Address,
+        collateral: Address,
+        liability: Address,
+        withdraw_amount: i128,
+        loops: u32,
+        min_amount_out: i128,
+    ) -> i128 {
+        storage::bump_instance(&e);
+        user.require_auth();
+
+        let repaid = manager::execute_unwind(
+            &e,
+            &user,
+            &pool,
+            &collateral,
+            &liability,
+            withdraw_amount,
+            loops,
+            min_amount_out,
+        );
+
+        e.events().publish(
+            (Symbol::new(&e, "unwind"), pool, user),
+            (collateral, liability, repaid),
+        );
+        repaid
+    }
+
+    fn swap_collateral(
+        e: Env,
+        user: Address,
+        pool: Address,
+        from_asset: Address,
+        to_asset: Address,
+        amount: i128,
+        min_amount_out: i128,
+    ) -> i128 {
+        storage::bump_instance(&e);
+        user.require_auth();
+
+        let supplied = manager::execute_collateral_swap(
+            &e,
+            &user,
+            &pool,
+            &from_asset,
+            &to_asset,
+            amount,
+            min_amount_out,
+        );
+
+        e.events().publish(
+            (Symbol::new(&e, "swap_collateral"), pool, user),
+            (from_asset, amount, to_asset, supplied),
+        );
+        supplied
+    }
+}
@@ -0,0 +1,8 @@
+mod pool;
+pub use pool::{Client as PoolClient, Request};
+
+mod swap_adapter;
+pub use swap_adapter::SwapAdapterClient;
+
+mod token;
+pub use token::Client as TokenClient;
@@ -0,0 +1,3 @@
+use soroban_sdk::contractimport;
+
+contractimport!(file = "../soroban_token_contract.wasm");
@@ -0,0 +1,12 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ManagerError {
+    AlreadyInitialized = 1,
+    BadRequest = 2,
+    NegativeAmount = 3,
+    StrategyDisabled = 10,
+    StrategyCapExceeded = 11,
+}
@@ -0,0 +1,14 @@
+#![no_std]
+
+#[cfg(any(test, feature = "testutils"))]
+extern crate std;
+
+mod contract;
+mod dependencies;
+mod errors;
+mod manager;
+mod storage;
+
+pub use contract::*;
+pub use errors::ManagerError;
+pub use storage::{ManagerDataKey, StrategyCap};
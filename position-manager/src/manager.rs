@@ -0,0 +1,308 @@
+use soroban_sdk::{panic_with_error, vec, Address, Env};
+
+use crate::{
+    dependencies::{PoolClient, Request, SwapAdapterClient, TokenClient},
+    errors::ManagerError,
+    storage::{self, StrategyCap},
+};
+
+/// Repeatedly borrow an asset and swap it for collateral
+pub const STRATEGY_LEVERAGE: u32 = 0;
+/// Repeatedly withdraw collateral and swap it to repay a liability
+pub const STRATEGY_UNWIND: u32 = 1;
+/// Swap one collateral asset for another
+pub const STRATEGY_COLLATERAL_SWAP: u32 = 2;
+
+// pool request types
+const REQUEST_SUPPLY_COLLATERAL: u32 = 2;
+const REQUEST_WITHDRAW_COLLATERAL: u32 = 3;
+const REQUEST_BORROW: u32 = 4;
+const REQUEST_REPAY: u32 = 5;
+
+/// Set the safety caps for a strategy. A cap with no loops or no amount disables the strategy.
+///
+/// ### Arguments
+/// * `strategy` - The strategy
+/// * `cap` - The safety caps
+///
+/// ### Panics
+/// If the strategy does not exist or the max amount is negative
+pub fn set_strategy_cap(e: &Env, strategy: u32, cap: &StrategyCap) {
+    if strategy > STRATEGY_COLLATERAL_SWAP {
+        panic_with_error!(e, ManagerError::BadRequest);
+    }
+    if cap.max_amount < 0 {
+        panic_with_error!(e, ManagerError::NegativeAmount);
+    }
+    if cap.max_loops == 0 || cap.max_amount == 0 {
+        storage::del_strategy_cap(e, strategy);
+    } else {
+        storage::set_strategy_cap(e, strategy, cap);
+    }
+}
+
+/// Leverage a position by borrowing "liability", swapping it for "collateral", and supplying the
+/// collateral, once per loop. The pool verifies the user's health after each step.
+///
+/// Returns the total amount of collateral supplied
+///
+/// ### Arguments
+/// * `user` - The user whose position is being leveraged
+/// * `pool` - The pool the position is in
+/// * `collateral` - The collateral asset
+/// * `liability` - The liability asset
+/// * `borrow_amount` - The amount of "liability" borrowed each loop
+/// * `loops` - The number of loops
+/// * `min_amount_out` - The minimum amount of "collateral" received from each swap
+///
+/// ### Panics
+/// If the strategy is disabled or the call exceeds its caps
+#[allow(clippy::too_many_arguments)]
+pub fn execute_leverage(
+    e: &Env,
+    user: &Address,
+    pool: &Address,
+    collateral: &Address,
+    liability: &Address,
+    borrow_amount: i128,
+    loops: u32,
+    min_amount_out: i128,
+) -> i128 {
+    require_within_cap(e, STRATEGY_LEVERAGE, borrow_amount, loops);
+    let mut total_supplied = 0;
+    for _ in 0..loops {
+        let borrowed = submit_for_tokens(e, user, pool, REQUEST_BORROW, liability, borrow_amount);
+        let amount_out = swap(e, user, liability, collateral, borrowed, min_amount_out);
+        submit(
+            e,
+            user,
+            pool,
+            REQUEST_SUPPLY_COLLATERAL,
+            collateral,
+            amount_out,
+        );
+        total_supplied += amount_out;
+    }
+    total_supplied
+}
+
+/// Unwind a position by withdrawing "collateral", swapping it for "liability", and repaying the
+/// liability, once per loop. The pool verifies the user's health after each step.
+///
+/// Returns the total amount of liability repaid
+///
+/// ### Arguments
+/// * `user` - The user whose position is being unwound
+/// * `pool` - The pool the position is in
+/// * `collateral` - The collateral asset
+/// * `liability` - The liability asset
+/// * `withdraw_amount` - The amount of "collateral" withdrawn each loop
+/// * `loops` - The number of loops
+/// * `min_amount_out` - The minimum amount of "liability" received from each swap
+///
+/// ### Panics
+/// If the strategy is disabled or the call exceeds its caps
+#[allow(clippy::too_many_arguments)]
+pub fn execute_unwind(
+    e: &Env,
+    user: &Address,
+    pool: &Address,
+    collateral: &Address,
+    liability: &Address,
+    withdraw_amount: i128,
+    loops: u32,
+    min_amount_out: i128,
+) -> i128 {
+    require_within_cap(e, STRATEGY_UNWIND, withdraw_amount, loops);
+    let mut total_repaid = 0;
+    for _ in 0..loops {
+        let withdrawn = submit_for_tokens(
+            e,
+            user,
+            pool,
+            REQUEST_WITHDRAW_COLLATERAL,
+            collateral,
+            withdraw_amount,
+        );
+        let amount_out = swap(e, user, collateral, liability, withdrawn, min_amount_out);
+        submit(e, user, pool, REQUEST_REPAY, liability, amount_out);
+        total_repaid += amount_out;
+    }
+    total_repaid
+}
+
+/// Swap "amount" of the collateral asset "from_asset" for "to_asset" and supply it as collateral
+///
+/// Returns the amount of "to_asset" supplied
+///
+/// ### Arguments
+/// * `user` - The user whose collateral is being swapped
+/// * `pool` - The pool the position is in
+/// * `from_asset` - The collateral asset being sold
+/// * `to_asset` - The collateral asset being bought
+/// * `amount` - The amount of "from_asset" to swap
+/// * `min_amount_out` - The minimum amount of "to_asset" received from the swap
+///
+/// ### Panics
+/// If the strategy is disabled or the call exceeds its caps
+pub fn execute_collateral_swap(
+    e: &Env,
+    user: &Address,
+    pool: &Address,
+    from_asset: &Address,
+    to_asset: &Address,
+    amount: i128,
+    min_amount_out: i128,
+) -> i128 {
+    require_within_cap(e, STRATEGY_COLLATERAL_SWAP, amount, 1);
+    let withdrawn = submit_for_tokens(
+        e,
+        user,
+        pool,
+        REQUEST_WITHDRAW_COLLATERAL,
+        from_asset,
+        amount,
+    );
+    let amount_out = swap(e, user, from_asset, to_asset, withdrawn, min_amount_out);
+    submit(
+        e,
+        user,
+        pool,
+        REQUEST_SUPPLY_COLLATERAL,
+        to_asset,
+        amount_out,
+    );
+    amount_out
+}
+
+/// Require that the strategy is enabled and the call is within the strategy's caps
+fn require_within_cap(e: &Env, strategy: u32, amount: i128, loops: u32) {
+    if amount <= 0 || loops == 0 {
+        panic_with_error!(e, ManagerError::BadRequest);
+    }
+    let cap = match storage::get_strategy_cap(e, strategy) {
+        Some(cap) => cap,
+        None => panic_with_error!(e, ManagerError::StrategyDisabled),
+    };
+    if loops > cap.max_loops || amount * i128::from(loops) > cap.max_amount {
+        panic_with_error!(e, ManagerError::StrategyCapExceeded);
+    }
+}
+
+/// Submit a single request to the pool for the user, with the user sending and receiving tokens
+fn submit(
+    e: &Env,
+    user: &Address,
+    pool: &Address,
+    request_type: u32,
+    asset: &Address,
+    amount: i128,
+) {
+    let requests = vec![
+        e,
+        Request {
+            request_type,
+            address: asset.clone(),
+            amount,
+        },
+    ];
+    PoolClient::new(e, pool).submit(user, user, user, &requests);
+}
+
+/// Submit a single request to the pool for the user, returning the tokens the user received,
+/// such that withdrawals clamped by the pool only forward what was received
+fn submit_for_tokens(
+    e: &Env,
+    user: &Address,
+    pool: &Address,
+    request_type: u32,
+    asset: &Address,
+    amount: i128,
+) -> i128 {
+    let token_client = TokenClient::new(e, asset);
+    let balance_before = token_client.balance(user);
+    submit(e, user, pool, request_type, asset, amount);
+    token_client.balance(user) - balance_before
+}
+
+/// Swap tokens held by the user through the swap adapter
+fn swap(
+    e: &Env,
+    user: &Address,
+    token_in: &Address,
+    token_out: &Address,
+    amount_in: i128,
+    min_amount_out: i128,
+) -> i128 {
+    SwapAdapterClient::new(e, &storage::get_adapter(e)).swap_exact_in(
+        user,
+        token_in,
+        token_out,
+        &amount_in,
+        &min_amount_out,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_set_strategy_cap() {
+        let e = Env::default();
+        let manager = Address::random(&e);
+
+        let cap = StrategyCap {
+            max_loops: 5,
+            max_amount: 1000_0000000,
+        };
+        e.as_contract(&manager, || {
+            set_strategy_cap(&e, STRATEGY_LEVERAGE, &cap);
+            let stored_cap = storage::get_strategy_cap(&e, STRATEGY_LEVERAGE).unwrap();
+            assert_eq!(stored_cap.max_loops, 5);
+            assert_eq!(stored_cap.max_amount, 1000_0000000);
+            require_within_cap(&e, STRATEGY_LEVERAGE, 200_0000000, 5);
+
+            // a cap without any loops disables the strategy
+            set_strategy_cap(
+                &e,
+                STRATEGY_LEVERAGE,
+                &StrategyCap {
+                    max_loops: 0,
+                    max_amount: 1000_0000000,
+                },
+            );
+            assert!(storage::get_strategy_cap(&e, STRATEGY_LEVERAGE).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(10)")]
+    fn test_strategy_disabled() {
+        let e = Env::default();
+        let manager = Address::random(&e);
+
+        e.as_contract(&manager, || {
+            require_within_cap(&e, STRATEGY_UNWIND, 1_0000000, 1);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    // #[should_panic(expected = "ContractError(11)")]
+    fn test_strategy_cap_exceeded() {
+        let e = Env::default();
+        let manager = Address::random(&e);
+
+        let cap = StrategyCap {
+            max_loops: 5,
+            max_amount: 1000_0000000,
+        };
+        e.as_contract(&manager, || {
+            set_strategy_cap(&e, STRATEGY_LEVERAGE, &cap);
+            require_within_cap(&e, STRATEGY_LEVERAGE, 250_0000000, 5);
+        });
+    }
+}
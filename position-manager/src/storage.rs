@@ -0,0 +1,129 @@
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, Env};
+
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 34560; // 2 days
+pub(crate) const SHARED_BUMP_AMOUNT: u32 = 69120; // 4 days
+
+/********** Storage Types **********/
+
+/// The safety caps for a strategy
+#[derive(Clone)]
+#[contracttype]
+pub struct StrategyCap {
+    pub max_loops: u32,   // the max number of borrow or repay loops in a single call
+    pub max_amount: i128, // the max amount of tokens moved across all loops of a single call
+}
+
+/********** Storage **********/
+
+// Position Manager Data Keys
+#[derive(Clone)]
+#[contracttype]
+pub enum ManagerDataKey {
+    // The address of the admin
+    Admin,
+    // The address of the swap adapter contract
+    Adapter,
+    // The safety caps for a strategy
+    Cap(u32),
+}
+
+/// Bump the instance rent for the contract
+pub fn bump_instance(e: &Env) {
+    e.storage().instance().bump(INSTANCE_BUMP_AMOUNT);
+}
+
+/********** Admin **********/
+
+/// Fetch the current admin Address
+///
+/// ### Panics
+/// If the admin does not exist
+pub fn get_admin(e: &Env) -> Address {
+    e.storage()
+        .persistent()
+        .bump(&ManagerDataKey::Admin, SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get(&ManagerDataKey::Admin)
+        .unwrap_optimized()
+}
+
+/// Set a new admin
+///
+/// ### Arguments
+/// * `new_admin` - The Address for the admin
+pub fn set_admin(e: &Env, new_admin: &Address) {
+    e.storage()
+        .persistent()
+        .set::<ManagerDataKey, Address>(&ManagerDataKey::Admin, new_admin);
+}
+
+/// Check if an admin has been set
+pub fn has_admin(e: &Env) -> bool {
+    e.storage().persistent().has(&ManagerDataKey::Admin)
+}
+
+/********** Swap Adapter **********/
+
+/// Fetch the swap adapter Address
+///
+/// ### Panics
+/// If the swap adapter does not exist
+pub fn get_adapter(e: &Env) -> Address {
+    e.storage()
+        .persistent()
+        .bump(&ManagerDataKey::Adapter, SHARED_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .get(&ManagerDataKey::Adapter)
+        .unwrap_optimized()
+}
+
+/// Set the swap adapter
+///
+/// ### Arguments
+/// * `adapter` - The Address of the swap adapter contract
+pub fn set_adapter(e: &Env, adapter: &Address) {
+    e.storage()
+        .persistent()
+        .set::<ManagerDataKey, Address>(&ManagerDataKey::Adapter, adapter);
+}
+
+/********** Strategy Caps **********/
+
+/// Fetch the safety caps for a strategy, or None if the strategy is disabled
+///
+/// ### Arguments
+/// * `strategy` - The strategy
+pub fn get_strategy_cap(e: &Env, strategy: u32) -> Option<StrategyCap> {
+    let key = ManagerDataKey::Cap(strategy);
+    let result = e
+        .storage()
+        .persistent()
+        .get::<ManagerDataKey, StrategyCap>(&key);
+    if result.is_some() {
+        e.storage().persistent().bump(&key, SHARED_BUMP_AMOUNT);
+    }
+    result
+}
+
+/// Set the safety caps for a strategy
+///
+/// ### Arguments
+/// * `strategy` - The strategy
+/// * `cap` - The safety caps
+pub fn set_strategy_cap(e: &Env, strategy: u32, cap: &StrategyCap) {
+    e.storage()
+        .persistent()
+        .set::<ManagerDataKey, StrategyCap>(&ManagerDataKey::Cap(strategy), cap);
+}
+
+/// Remove the safety caps for a strategy, disabling it
+///
+/// ### Arguments
+/// * `strategy` - The strategy
+pub fn del_strategy_cap(e: &Env, strategy: u32) {
+    e.storage()
+        .persistent()
+        .remove(&ManagerDataKey::Cap(strategy));
+}
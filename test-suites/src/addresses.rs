@@ -0,0 +1,78 @@
+use std::cell::RefCell;
+
+use soroban_sdk::{Address, BytesN, Env};
+
+thread_local! {
+    static REGISTRY: RefCell<Vec<(String, String)>> = RefCell::new(Vec::new());
+}
+
+/// Create a deterministic address for a label, such that test output references the same
+/// address on every run. The address is recorded in the registry under its label.
+///
+/// The same label always yields the same address, so labels must be unique within a test.
+///
+/// ### Arguments
+/// * `label` - The name of the address (e.g. "frodo")
+pub fn addr(e: &Env, label: &str) -> Address {
+    let address = Address::from_contract_id(&BytesN::from_array(e, &label_to_bytes(label)));
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if !registry.iter().any(|(name, _)| name == label) {
+            registry.push((label.to_string(), format!("{:?}", address)));
+        }
+    });
+    address
+}
+
+/// Fetch the label an address was created with, if it was created with `addr`
+///
+/// ### Arguments
+/// * `address` - The address to look up
+pub fn label_of(address: &Address) -> Option<String> {
+    let address = format!("{:?}", address);
+    REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .iter()
+            .find(|(_, value)| *value == address)
+            .map(|(name, _)| name.clone())
+    })
+}
+
+/// Format the labeled addresses created on the current thread, one per line
+pub fn registry_to_string() -> String {
+    REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .iter()
+            .map(|(name, value)| format!("{}: {}\n", name, value))
+            .collect()
+    })
+}
+
+/// Prints the labeled addresses when dropped during a panic, such that a failing test
+/// can map the addresses in its output back to their labels
+#[derive(Default)]
+pub struct AddressRegistryGuard;
+
+impl Drop for AddressRegistryGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!("labeled addresses:\n{}", registry_to_string());
+        }
+    }
+}
+
+/// Expand a label into 32 bytes with FNV-1a, seeded differently for each 8 byte word
+fn label_to_bytes(label: &str) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (word, chunk) in bytes.chunks_mut(8).enumerate() {
+        let mut hash: u64 = 0xcbf29ce484222325 ^ (word as u64);
+        for byte in label.as_bytes() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        chunk.copy_from_slice(&hash.to_be_bytes());
+    }
+    bytes
+}
@@ -1,4 +1,6 @@
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{Address, Env};
+
+use crate::addresses::addr;
 
 mod backstop_contract_wasm {
     soroban_sdk::contractimport!(
@@ -8,7 +10,7 @@ mod backstop_contract_wasm {
 use backstop_module::{BackstopModule, BackstopModuleClient};
 
 pub fn create_backstop<'a>(e: &Env, wasm: bool) -> (Address, BackstopModuleClient<'a>) {
-    let contract_id = Address::random(e);
+    let contract_id = addr(e, "backstop");
     if wasm {
         e.register_contract_wasm(&contract_id, backstop_contract_wasm::WASM);
     } else {
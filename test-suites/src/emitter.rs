@@ -1,4 +1,6 @@
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{Address, Env};
+
+use crate::addresses::addr;
 
 mod emitter_contract {
     soroban_sdk::contractimport!(file = "../target/wasm32-unknown-unknown/release/emitter.wasm");
@@ -7,7 +9,7 @@ mod emitter_contract {
 use emitter::{Emitter, EmitterClient};
 
 pub fn create_emitter<'a>(e: &Env, wasm: bool) -> (Address, EmitterClient<'a>) {
-    let contract_id = Address::random(e);
+    let contract_id = addr(e, "emitter");
     if wasm {
         e.register_contract_wasm(&contract_id, emitter_contract::WASM);
     } else {
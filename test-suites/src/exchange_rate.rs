@@ -0,0 +1,35 @@
+use crate::addresses::addr;
+use soroban_sdk::{contract, contractimpl, Address, Env, Symbol};
+
+/// An exchange rate adapter test double that reports a rate set by the test
+#[contract]
+pub struct MockExchangeRate;
+
+#[contractimpl]
+impl MockExchangeRate {
+    /// Set the exchange rate from share units to underlying tokens
+    ///
+    /// ### Arguments
+    /// * `rate` - The exchange rate, in 9 decimals
+    pub fn set_rate(e: Env, rate: i128) {
+        e.storage()
+            .instance()
+            .set::<Symbol, i128>(&Symbol::new(&e, "Rate"), &rate);
+    }
+
+    pub fn rate(e: Env) -> i128 {
+        e.storage()
+            .instance()
+            .get::<Symbol, i128>(&Symbol::new(&e, "Rate"))
+            .unwrap()
+    }
+}
+
+pub fn create_exchange_rate_adapter<'a>(e: &Env) -> (Address, MockExchangeRateClient<'a>) {
+    let contract_id = addr(e, "exchange_rate");
+    e.register_contract(&contract_id, MockExchangeRate {});
+    (
+        contract_id.clone(),
+        MockExchangeRateClient::new(e, &contract_id),
+    )
+}
@@ -1,11 +1,17 @@
 #![allow(clippy::all)]
+pub mod addresses;
 pub mod backstop;
 pub mod emitter;
+pub mod exchange_rate;
+pub mod malicious_token;
 pub mod mock_oracle;
 pub mod pool;
 pub mod pool_factory;
+pub mod position_manager;
 mod setup;
 pub use setup::create_fixture_with_data;
 pub mod assertions;
+pub mod snapshot;
 pub mod test_fixture;
 pub mod token;
+pub mod upgrade;
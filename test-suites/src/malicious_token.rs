@@ -0,0 +1,132 @@
+use crate::addresses::addr;
+use lending_pool::PoolClient;
+use soroban_sdk::{contract, contractimpl, contracttype, vec, Address, Env};
+
+/// The adversarial behavior of a MaliciousToken
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[contracttype]
+#[repr(u32)]
+pub enum MaliciousMode {
+    /// Behave like a normal token
+    Honest = 0,
+    /// Call back into the target pool during transfers
+    Reenter = 1,
+    /// Report a balance of zero for every address
+    ZeroBalance = 2,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum MaliciousTokenDataKey {
+    Balance(Address),
+    Blocked(Address),
+    Mode,
+    Target,
+}
+
+/// A token test double that misbehaves based on its configured mode, and panics on any
+/// transfer to or from a blocked address.
+#[contract]
+pub struct MaliciousToken;
+
+#[contractimpl]
+impl MaliciousToken {
+    /// Set the adversarial behavior of the token
+    ///
+    /// ### Arguments
+    /// * `mode` - The behavior of the token
+    /// * `target` - The pool to call back into when reentering
+    pub fn set_mode(e: Env, mode: MaliciousMode, target: Address) {
+        e.storage()
+            .instance()
+            .set(&MaliciousTokenDataKey::Mode, &mode);
+        e.storage()
+            .instance()
+            .set(&MaliciousTokenDataKey::Target, &target);
+    }
+
+    /// Block an address, causing any transfer to or from it to panic
+    ///
+    /// ### Arguments
+    /// * `id` - The address to block
+    pub fn block(e: Env, id: Address) {
+        e.storage()
+            .persistent()
+            .set(&MaliciousTokenDataKey::Blocked(id), &true);
+    }
+
+    pub fn mint(e: Env, to: Address, amount: i128) {
+        let balance = read_balance(&e, &to);
+        write_balance(&e, &to, balance + amount);
+    }
+
+    pub fn balance(e: Env, id: Address) -> i128 {
+        if read_mode(&e) == MaliciousMode::ZeroBalance {
+            return 0;
+        }
+        read_balance(&e, &id)
+    }
+
+    pub fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        if is_blocked(&e, &from) || is_blocked(&e, &to) {
+            panic!("blocked");
+        }
+
+        if read_mode(&e) == MaliciousMode::Reenter {
+            let target = e
+                .storage()
+                .instance()
+                .get::<MaliciousTokenDataKey, Address>(&MaliciousTokenDataKey::Target)
+                .unwrap();
+            PoolClient::new(&e, &target).submit(&from, &from, &from, &vec![&e]);
+        }
+
+        let from_balance = read_balance(&e, &from);
+        if from_balance < amount {
+            panic!("insufficient balance");
+        }
+        write_balance(&e, &from, from_balance - amount);
+        let to_balance = read_balance(&e, &to);
+        write_balance(&e, &to, to_balance + amount);
+    }
+
+    pub fn decimals(_e: Env) -> u32 {
+        7
+    }
+}
+
+fn read_mode(e: &Env) -> MaliciousMode {
+    e.storage()
+        .instance()
+        .get::<MaliciousTokenDataKey, MaliciousMode>(&MaliciousTokenDataKey::Mode)
+        .unwrap_or(MaliciousMode::Honest)
+}
+
+fn read_balance(e: &Env, id: &Address) -> i128 {
+    e.storage()
+        .persistent()
+        .get::<MaliciousTokenDataKey, i128>(&MaliciousTokenDataKey::Balance(id.clone()))
+        .unwrap_or(0)
+}
+
+fn write_balance(e: &Env, id: &Address, amount: i128) {
+    e.storage()
+        .persistent()
+        .set(&MaliciousTokenDataKey::Balance(id.clone()), &amount);
+}
+
+fn is_blocked(e: &Env, id: &Address) -> bool {
+    e.storage()
+        .persistent()
+        .has(&MaliciousTokenDataKey::Blocked(id.clone()))
+}
+
+pub fn create_malicious_token<'a>(e: &Env) -> (Address, MaliciousTokenClient<'a>) {
+    let contract_id = addr(e, "malicious_token");
+    e.register_contract(&contract_id, MaliciousToken {});
+    (
+        contract_id.clone(),
+        MaliciousTokenClient::new(e, &contract_id),
+    )
+}
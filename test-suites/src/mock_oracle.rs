@@ -1,4 +1,6 @@
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{Address, Env};
+
+use crate::addresses::addr;
 
 mod mock_blend_oracle_wasm {
     soroban_sdk::contractimport!(
@@ -9,7 +11,7 @@ mod mock_blend_oracle_wasm {
 use mock_oracle::{MockOracle, MockOracleClient};
 
 pub fn create_mock_oracle<'a>(e: &Env, wasm: bool) -> (Address, MockOracleClient<'a>) {
-    let contract_id = Address::random(e);
+    let contract_id = addr(e, "oracle");
     if wasm {
         e.register_contract_wasm(&contract_id, mock_blend_oracle_wasm::WASM);
     } else {
@@ -18,6 +18,8 @@ pub fn default_reserve_metadata() -> ReserveConfig {
         r_two: 0_5000000,
         r_three: 1_5000000,
         reactivity: 0_000_002_000, // 10e-5
+        oracle_asset: None,
+        isolated: false,
         index: 0,
     }
 }
@@ -1,4 +1,6 @@
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{Address, Env};
+
+use crate::addresses::addr;
 
 mod pool_factory_contract {
     soroban_sdk::contractimport!(
@@ -10,7 +12,7 @@ use pool_factory::PoolFactoryClient;
 use mock_pool_factory::MockPoolFactory;
 
 pub fn create_pool_factory<'a>(e: &Env, wasm: bool) -> (Address, PoolFactoryClient<'a>) {
-    let contract_id = Address::random(e);
+    let contract_id = addr(e, "pool_factory");
     if wasm {
         e.register_contract_wasm(&contract_id, pool_factory_contract::WASM);
     } else {
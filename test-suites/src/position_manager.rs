@@ -0,0 +1,89 @@
+use fixed_point_math::FixedPoint;
+use position_manager::{PositionManager, PositionManagerClient};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+use crate::{addresses::addr, test_fixture::SCALAR_7, token::TokenClient};
+
+mod position_manager_wasm {
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32-unknown-unknown/release/position_manager.wasm"
+    );
+}
+
+pub fn create_position_manager<'a>(e: &Env, wasm: bool) -> (Address, PositionManagerClient<'a>) {
+    let contract_id = addr(e, "position_manager");
+    if wasm {
+        e.register_contract_wasm(&contract_id, position_manager_wasm::WASM);
+    } else {
+        e.register_contract(&contract_id, PositionManager {});
+    }
+    (
+        contract_id.clone(),
+        PositionManagerClient::new(e, &contract_id),
+    )
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct SwapPair {
+    pub token_in: Address,
+    pub token_out: Address,
+}
+
+/// A swap adapter test double that swaps at fixed rates from its own token balances
+#[contract]
+pub struct MockSwapAdapter;
+
+#[contractimpl]
+impl MockSwapAdapter {
+    /// Set the rate "token_in" is swapped to "token_out" at, as the amount of "token_out"
+    /// received per unit of "token_in", expressed in 7 decimals
+    pub fn set_rate(e: Env, token_in: Address, token_out: Address, rate: i128) {
+        e.storage().instance().set::<SwapPair, i128>(
+            &SwapPair {
+                token_in,
+                token_out,
+            },
+            &rate,
+        );
+    }
+
+    pub fn swap_exact_in(
+        e: Env,
+        from: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_amount_out: i128,
+    ) -> i128 {
+        from.require_auth();
+        let rate = e
+            .storage()
+            .instance()
+            .get::<SwapPair, i128>(&SwapPair {
+                token_in: token_in.clone(),
+                token_out: token_out.clone(),
+            })
+            .unwrap();
+        let amount_out = amount_in.fixed_mul_floor(rate, SCALAR_7).unwrap();
+        if amount_out < min_amount_out {
+            panic!("slippage exceeded");
+        }
+        TokenClient::new(&e, &token_in).transfer(&from, &e.current_contract_address(), &amount_in);
+        TokenClient::new(&e, &token_out).transfer(
+            &e.current_contract_address(),
+            &from,
+            &amount_out,
+        );
+        amount_out
+    }
+}
+
+pub fn create_mock_swap_adapter<'a>(e: &Env) -> (Address, MockSwapAdapterClient<'a>) {
+    let contract_id = addr(e, "swap_adapter");
+    e.register_contract(&contract_id, MockSwapAdapter {});
+    (
+        contract_id.clone(),
+        MockSwapAdapterClient::new(e, &contract_id),
+    )
+}
@@ -1,7 +1,8 @@
 use lending_pool::{Request, ReserveEmissionMetadata};
-use soroban_sdk::{testutils::Address as _, vec, Address, Symbol, Vec};
+use soroban_sdk::{vec, Address, Symbol, Vec};
 
 use crate::{
+    addresses::addr,
     pool::default_reserve_metadata,
     test_fixture::{TestFixture, TokenIndex, SCALAR_7},
 };
@@ -53,7 +54,7 @@ pub fn create_fixture_with_data<'a>(wasm: bool) -> (TestFixture<'a>, Address) {
     pool_fixture.pool.set_emissions_config(&reserve_emissions);
 
     // mint whale tokens
-    let frodo = Address::random(&fixture.env);
+    let frodo = addr(&fixture.env, "frodo");
     fixture.tokens[TokenIndex::USDC].mint(&frodo, &(100_000 * 10i128.pow(6)));
     fixture.tokens[TokenIndex::XLM].mint(&frodo, &(1_000_000 * SCALAR_7));
     fixture.tokens[TokenIndex::WETH].mint(&frodo, &(100 * 10i128.pow(9)));
@@ -65,7 +66,7 @@ pub fn create_fixture_with_data<'a>(wasm: bool) -> (TestFixture<'a>, Address) {
         .deposit(&frodo, &pool_fixture.pool.address, &(2_000_000 * SCALAR_7));
     fixture
         .backstop
-        .add_reward(&pool_fixture.pool.address, &Address::random(&fixture.env));
+        .add_reward(&pool_fixture.pool.address, &addr(&fixture.env, "no_pool"));
     pool_fixture.pool.update_status();
 
     // enable emissions
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use lending_pool::PoolClient;
+use soroban_sdk::testutils::{Ledger, LedgerInfo};
+use soroban_sdk::{Address, BytesN, Env};
+
+pub use soroban_sdk::testutils::LedgerSnapshot;
+
+/// A pool loaded from a ledger snapshot, such as the serialized state of a deployed pool.
+///
+/// Any contracts invoked must have their wasm code included in the snapshot. All auths
+/// are mocked, so governance actions and fills can be replayed as any address.
+pub struct SnapshotFixture<'a> {
+    pub env: Env,
+    pub pool: PoolClient<'a>,
+}
+
+impl SnapshotFixture<'_> {
+    /// Load a SnapshotFixture from a ledger snapshot file
+    ///
+    /// ### Arguments
+    /// * `path` - The path to the ledger snapshot file
+    /// * `pool_id` - The contract id of the pool
+    pub fn load<'a>(path: impl AsRef<Path>, pool_id: &[u8; 32]) -> SnapshotFixture<'a> {
+        SnapshotFixture::from_snapshot(LedgerSnapshot::read_file(path).unwrap(), pool_id)
+    }
+
+    /// Create a SnapshotFixture from a ledger snapshot
+    ///
+    /// ### Arguments
+    /// * `snapshot` - The ledger snapshot
+    /// * `pool_id` - The contract id of the pool
+    pub fn from_snapshot<'a>(snapshot: LedgerSnapshot, pool_id: &[u8; 32]) -> SnapshotFixture<'a> {
+        let e = Env::from_snapshot(snapshot);
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        let pool_address = Address::from_contract_id(&BytesN::from_array(&e, pool_id));
+        SnapshotFixture {
+            pool: PoolClient::new(&e, &pool_address),
+            env: e,
+        }
+    }
+
+    /// Write the current state of the ledger to a snapshot file, such that the
+    /// replayed actions can be inspected or replayed further
+    ///
+    /// ### Arguments
+    /// * `path` - The path to write the ledger snapshot file to
+    pub fn save(&self, path: impl AsRef<Path>) {
+        self.env.to_snapshot().write_file(path).unwrap();
+    }
+
+    /********** Chain Helpers ***********/
+
+    pub fn jump(&self, time: u64) {
+        let blocks = time / 5;
+        let ledger = self.env.ledger().get();
+        self.env.ledger().set(LedgerInfo {
+            timestamp: ledger.timestamp + time,
+            sequence_number: ledger.sequence_number + (blocks as u32),
+            ..ledger
+        });
+    }
+}
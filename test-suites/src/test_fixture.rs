@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::ops::Index;
 
+use crate::addresses::{addr, AddressRegistryGuard};
 use crate::backstop::create_backstop;
 use crate::emitter::create_emitter;
 use crate::mock_oracle::create_mock_oracle;
@@ -12,7 +13,7 @@ use emitter::EmitterClient;
 use lending_pool::{PoolClient, ReserveConfig};
 use mock_oracle::MockOracleClient;
 use pool_factory::{PoolFactoryClient, PoolInitMeta};
-use soroban_sdk::testutils::{Address as _, BytesN as _, Ledger, LedgerInfo};
+use soroban_sdk::testutils::{Ledger, LedgerInfo};
 use soroban_sdk::{Address, BytesN, Env, Map, Symbol};
 
 pub const SCALAR_7: i128 = 1_000_0000;
@@ -49,6 +50,7 @@ pub struct TestFixture<'a> {
     pub oracle: MockOracleClient<'a>,
     pub pools: Vec<PoolFixture<'a>>,
     pub tokens: Vec<TokenClient<'a>>,
+    pub registry: AddressRegistryGuard,
 }
 
 impl TestFixture<'_> {
@@ -61,7 +63,7 @@ impl TestFixture<'_> {
         e.mock_all_auths();
         e.budget().reset_unlimited();
 
-        let bombadil = Address::random(&e);
+        let bombadil = addr(&e, "bombadil");
 
         e.ledger().set(LedgerInfo {
             timestamp: 1441065600, // Sept 1st, 2015 (backstop epoch)
@@ -94,6 +96,7 @@ impl TestFixture<'_> {
         // initialize backstop
         let (backstop_token_id, backstop_token_client) = create_token(&e, &bombadil, 7, "BSTOP");
         backstop_client.initialize(
+            &bombadil,
             &backstop_token_id,
             &blnd_id,
             &pool_factory_id,
@@ -145,6 +148,7 @@ impl TestFixture<'_> {
                 xlm_client,
                 backstop_token_client,
             ],
+            registry: AddressRegistryGuard::default(),
         }
     }
 
@@ -152,9 +156,10 @@ impl TestFixture<'_> {
         let pool_id = self.pool_factory.deploy(
             &self.bombadil,
             &name,
-            &BytesN::<32>::random(&self.env),
+            &BytesN::from_array(&self.env, &[self.pools.len() as u8; 32]),
             &self.oracle.address,
             &backstop_take_rate,
+            &false,
         );
         self.pools.push(PoolFixture {
             pool: PoolClient::new(&self.env, &pool_id),
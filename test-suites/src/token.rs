@@ -1,4 +1,6 @@
-use soroban_sdk::{testutils::Address as _, Address, Env, IntoVal};
+use soroban_sdk::{Address, Env, IntoVal};
+
+use crate::addresses::addr;
 
 mod token_contract {
     soroban_sdk::contractimport!(file = "../soroban_token_contract.wasm");
@@ -17,7 +19,7 @@ pub fn create_token<'a>(
     decimals: u32,
     symbol: &str,
 ) -> (Address, TokenClient<'a>) {
-    let contract_id = Address::random(e);
+    let contract_id = addr(e, symbol);
     e.register_contract_wasm(&contract_id, TOKEN_WASM);
     let client = TokenClient::new(e, &contract_id);
     client.initialize(
@@ -0,0 +1,103 @@
+use lending_pool::PoolClient;
+use soroban_sdk::{Address, Symbol, Vec};
+
+use crate::{
+    addresses::addr,
+    pool::POOL_WASM,
+    test_fixture::{TestFixture, TokenIndex},
+};
+
+pub mod pool_v0_contract {
+    // the pool at storage version 0, built from the previous release with `make pool-v0`
+    soroban_sdk::contractimport!(file = "wasm/lending_pool_v0.wasm");
+}
+pub use pool_v0_contract::{Client as PoolV0Client, WASM as POOL_V0_WASM};
+
+/// A pool deployed with the previous release's wasm, alongside the current versions of all
+/// other Blend Protocol contracts.
+///
+/// State is populated through the previous pool's interface, then the pool is upgraded in place
+/// to the current wasm and migrated, such that the upgrade can be rehearsed against real state.
+pub struct UpgradeFixture<'a> {
+    pub fixture: TestFixture<'a>,
+    pub pool: PoolV0Client<'a>,
+}
+
+impl UpgradeFixture<'_> {
+    /// Create a new UpgradeFixture, with the previous pool initialized against the
+    /// fixture's oracle and backstop
+    ///
+    /// ### Arguments
+    /// * `backstop_take_rate` - The backstop take rate of the pool, in 9 decimals
+    pub fn create<'a>(backstop_take_rate: u64) -> UpgradeFixture<'a> {
+        let fixture = TestFixture::create(true);
+        let e = &fixture.env;
+
+        let pool_id = addr(e, "pool_v0");
+        e.register_contract_wasm(&pool_id, POOL_V0_WASM);
+        let pool = PoolV0Client::new(e, &pool_id);
+        pool.initialize(
+            &fixture.bombadil,
+            &Symbol::new(e, "Teapot"),
+            &fixture.oracle.address,
+            &backstop_take_rate,
+            &fixture.backstop.address,
+            &fixture.tokens[TokenIndex::BLND].address,
+            &fixture.tokens[TokenIndex::USDC].address,
+        );
+
+        UpgradeFixture { fixture, pool }
+    }
+
+    /// Create a reserve in the previous pool
+    ///
+    /// ### Arguments
+    /// * `asset_index` - The token to create the reserve for
+    /// * `config` - The reserve config, in the previous pool's layout
+    pub fn create_reserve(
+        &self,
+        asset_index: TokenIndex,
+        config: &pool_v0_contract::ReserveConfig,
+    ) {
+        self.pool
+            .init_reserve(&self.fixture.tokens[asset_index].address, config);
+    }
+
+    /// Upgrade the pool in place to the current wasm and migrate its storage, as the pool's
+    /// admin would with an approved `AdminAction::Upgrade` followed by `migrate`.
+    ///
+    /// Returns a client for the upgraded pool and the storage version it was migrated from
+    ///
+    /// ### Arguments
+    /// * `auction_users` - The users with an in-flight liquidation auction in the previous pool
+    pub fn upgrade<'a>(&self, auction_users: &Vec<Address>) -> (PoolClient<'a>, u32) {
+        let e = &self.fixture.env;
+        let pool_id: Address = self.pool.address.clone();
+
+        let pool_hash = e.deployer().upload_contract_wasm(POOL_WASM);
+        // the previous pool has no upgrade entrypoint, so update the wasm as the pool itself
+        e.as_contract(&pool_id, || {
+            e.deployer().update_current_contract_wasm(pool_hash);
+        });
+
+        let pool = PoolClient::new(e, &pool_id);
+        let from_version = pool.migrate(auction_users);
+        (pool, from_version)
+    }
+}
+
+/// The reserve config used for reserves created in the previous pool
+pub fn default_reserve_config_v0() -> pool_v0_contract::ReserveConfig {
+    pool_v0_contract::ReserveConfig {
+        decimals: 7,
+        c_factor: 0_7500000,
+        l_factor: 0_7500000,
+        util: 0_7500000,
+        max_util: 0_9500000,
+        r_one: 0_0500000,
+        r_two: 0_5000000,
+        r_three: 1_5000000,
+        reactivity: 0_000_002_000, // 10e-5
+        index: 0,
+    }
+}
@@ -2,10 +2,11 @@
 
 use fixed_point_math::FixedPoint;
 use soroban_sdk::{
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events},
-    vec, Address, IntoVal, Map, Symbol,
+    testutils::{AuthorizedFunction, AuthorizedInvocation, Events},
+    vec, IntoVal, Map, Symbol,
 };
 use test_suites::{
+    addresses::addr,
     assertions::assert_approx_eq_abs,
     create_fixture_with_data,
     test_fixture::{TokenIndex, SCALAR_7},
@@ -19,13 +20,14 @@ fn test_backstop() {
 
     let pool = &fixture.pools[0].pool;
     let bstop_token = &fixture.tokens[TokenIndex::BSTOP];
-    let sam = Address::random(&fixture.env);
+    let sam = addr(&fixture.env, "sam");
 
     // Verify initialization can't be re-run
     let result = fixture.backstop.try_initialize(
-        &Address::random(&fixture.env),
-        &Address::random(&fixture.env),
-        &Address::random(&fixture.env),
+        &addr(&fixture.env, "admin"),
+        &addr(&fixture.env, "backstop_token"),
+        &addr(&fixture.env, "blnd_token"),
+        &addr(&fixture.env, "pool_factory"),
         &Map::new(&fixture.env),
     );
     assert!(result.is_err());
@@ -280,9 +282,8 @@ fn test_backstop() {
 
     // Backstop loses money
     let amount = 1_000 * SCALAR_7;
-    fixture.backstop.draw(&pool.address, &amount, &frodo);
-    frodo_bstop_token_balance += amount;
-    bstop_bstop_token_balance -= amount;
+    let draw_id = fixture.backstop.draw(&pool.address, &amount, &frodo);
+    assert_eq!(draw_id, 0);
     assert_eq!(
         fixture.env.auths()[0],
         (
@@ -315,15 +316,34 @@ fn test_backstop() {
             (
                 fixture.backstop.address.clone(),
                 (Symbol::new(&fixture.env, "draw"), pool.address.clone()).into_val(&fixture.env),
-                vec![&fixture.env, frodo.to_val(), amount.into_val(&fixture.env),]
-                    .into_val(&fixture.env)
+                vec![
+                    &fixture.env,
+                    frodo.to_val(),
+                    amount.into_val(&fixture.env),
+                    draw_id.into_val(&fixture.env)
+                ]
+                .into_val(&fixture.env)
             )
         ]
     );
+    let pending_draw = fixture.backstop.get_pending_draw(&draw_id).unwrap();
+    assert_eq!(pending_draw.amount, amount);
+    assert_eq!(pending_draw.to, frodo);
 
     // Jump to the end of the withdrawal period (27d23hr total emissions for sam)
     fixture.jump(60 * 60 * 24 * 16 + 1);
 
+    // The dispute window has passed, so the draw is released to frodo
+    fixture.backstop.release_draw(&draw_id);
+    frodo_bstop_token_balance += amount;
+    bstop_bstop_token_balance -= amount;
+    assert_eq!(bstop_token.balance(&frodo), frodo_bstop_token_balance);
+    assert_eq!(
+        bstop_token.balance(&fixture.backstop.address),
+        bstop_bstop_token_balance
+    );
+    assert!(fixture.backstop.get_pending_draw(&draw_id).is_none());
+
     // Sam withdraws the queue position
     let amount = 250_000 * SCALAR_7; // shares
     let result = fixture.backstop.withdraw(&sam, &pool.address, &amount);
@@ -1,10 +1,11 @@
 #![cfg(test)]
 
 use soroban_sdk::{
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events},
-    vec, Address, IntoVal, Symbol,
+    testutils::{AuthorizedFunction, AuthorizedInvocation, Events},
+    vec, IntoVal, Symbol,
 };
 use test_suites::{
+    addresses::addr,
     create_fixture_with_data,
     test_fixture::{TokenIndex, SCALAR_7},
 };
@@ -27,8 +28,8 @@ fn test_emitter() {
 
     // Verify initialization can't be re-run
     let result = fixture.emitter.try_initialize(
-        &Address::random(&fixture.env),
-        &Address::random(&fixture.env),
+        &addr(&fixture.env, "backstop"),
+        &addr(&fixture.env, "blnd_token"),
     );
     assert!(result.is_err());
     assert_eq!(
@@ -84,7 +85,7 @@ fn test_emitter() {
 
     // Mint enough tokens to a new backstop address to perform a swap, then swap the backstops
     let old_backstop_balance = bstop_token.balance(&fixture.backstop.address);
-    let new_backstop = Address::random(&fixture.env);
+    let new_backstop = addr(&fixture.env, "new_backstop");
     bstop_token.mint(&new_backstop, &(old_backstop_balance + 1));
     fixture.emitter.swap_backstop(&new_backstop);
     assert_eq!(fixture.env.auths().len(), 0);
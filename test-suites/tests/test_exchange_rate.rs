@@ -0,0 +1,98 @@
+#![cfg(test)]
+use lending_pool::Request;
+use soroban_sdk::{testutils::Events, vec, IntoVal, Symbol, Val, Vec};
+use test_suites::{
+    addresses::addr, create_fixture_with_data, exchange_rate::create_exchange_rate_adapter,
+    pool::default_reserve_metadata, test_fixture::SCALAR_7, token::create_token,
+};
+
+#[test]
+fn test_exchange_rate_adapter_rebasing_reserve() {
+    let (fixture, _) = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let bombadil = &fixture.bombadil;
+    let sam = addr(&fixture.env, "sam");
+
+    // a rebasing token, where 1 share unit is worth 1.1 tokens
+    let (steth_id, steth) = create_token(&fixture.env, bombadil, 7, "stETH");
+    let (adapter_id, adapter) = create_exchange_rate_adapter(&fixture.env);
+    adapter.set_rate(&1_100_000_000);
+    pool_fixture
+        .pool
+        .init_reserve(&steth_id, &default_reserve_metadata());
+    pool_fixture
+        .pool
+        .set_reserve_adapter(&steth_id, &adapter_id);
+    fixture.oracle.set_price(&steth_id, &2_0000000);
+    let reserve_index = pool_fixture.pool.get_reserve_config(&steth_id).index;
+
+    // supply 110 tokens, which are accounted for as 100 share units
+    steth.mint(&sam, &(110 * SCALAR_7));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: 2,
+            address: steth_id.clone(),
+            amount: 110 * SCALAR_7,
+        },
+    ];
+    let positions = pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+    assert_eq!(
+        positions.collateral.get_unchecked(reserve_index),
+        100 * SCALAR_7
+    );
+    assert_eq!(steth.balance(&sam), 0);
+    assert_eq!(steth.balance(&pool_fixture.pool.address), 110 * SCALAR_7);
+
+    // the supply event reports the underlying tokens transferred
+    let topics: Vec<Val> = (
+        Symbol::new(&fixture.env, "supply_collateral"),
+        steth_id.clone(),
+        sam.clone(),
+    )
+        .into_val(&fixture.env);
+    let supply_event = fixture
+        .env
+        .events()
+        .all()
+        .iter()
+        .find(|event| event.1 == topics)
+        .unwrap();
+    assert_eq!(
+        vec![&fixture.env, supply_event.2],
+        vec![
+            &fixture.env,
+            (110 * SCALAR_7, 100 * SCALAR_7).into_val(&fixture.env)
+        ]
+    );
+
+    // the token rebases, growing each share unit to 1.2 tokens
+    adapter.set_rate(&1_200_000_000);
+    steth.mint(&pool_fixture.pool.address, &(10 * SCALAR_7));
+
+    // withdrawing a specific amount pays out at least the amount requested
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: 3,
+            address: steth_id.clone(),
+            amount: 1_0000000,
+        },
+    ];
+    pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+    assert_eq!(steth.balance(&sam), 1_0000000);
+
+    // withdrawing the rest pays out the rebased value of the remaining share units
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: 3,
+            address: steth_id.clone(),
+            amount: i128::MAX,
+        },
+    ];
+    let positions = pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+    assert!(positions.collateral.get(reserve_index).is_none());
+    assert_eq!(steth.balance(&sam), 119_9999999);
+    assert_eq!(steth.balance(&pool_fixture.pool.address), 1);
+}
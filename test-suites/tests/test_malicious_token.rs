@@ -0,0 +1,249 @@
+#![cfg(test)]
+use lending_pool::{Request, ReserveConfig};
+use soroban_sdk::{
+    testutils::{AuthorizedFunction, AuthorizedInvocation},
+    vec, IntoVal, Symbol,
+};
+use test_suites::{
+    addresses::addr,
+    create_fixture_with_data,
+    malicious_token::{create_malicious_token, MaliciousMode, MaliciousTokenClient},
+    pool::default_reserve_metadata,
+    test_fixture::{TestFixture, TokenIndex, SCALAR_7},
+};
+
+/// Add a malicious token as a reserve of the fixture's pool
+fn setup_malicious_reserve<'a>(fixture: &TestFixture<'a>) -> (MaliciousTokenClient<'a>, u32) {
+    let pool_fixture = &fixture.pools[0];
+    let (mal_id, mal_client) = create_malicious_token(&fixture.env);
+    let reserve_config: ReserveConfig = default_reserve_metadata();
+    pool_fixture.pool.init_reserve(&mal_id, &reserve_config);
+    fixture.oracle.set_price(&mal_id, &1_0000000);
+    let index = pool_fixture.pool.get_reserve_config(&mal_id).index;
+    (mal_client, index)
+}
+
+#[test]
+fn test_malicious_token_strict_auth() {
+    let (fixture, _) = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let (mal, _) = setup_malicious_reserve(&fixture);
+
+    let sam = addr(&fixture.env, "sam");
+    mal.mint(&sam, &(1_000 * SCALAR_7));
+
+    // only the "from" address authorizes the supply, and only for the exact transfer
+    let amount = 1_000 * SCALAR_7;
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: 2,
+            address: mal.address.clone(),
+            amount,
+        },
+    ];
+    pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+    assert_eq!(fixture.env.auths().len(), 1);
+    assert_eq!(
+        fixture.env.auths()[0],
+        (
+            sam.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    pool_fixture.pool.address.clone(),
+                    Symbol::new(&fixture.env, "submit"),
+                    vec![
+                        &fixture.env,
+                        sam.to_val(),
+                        sam.to_val(),
+                        sam.to_val(),
+                        requests.to_val()
+                    ]
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        mal.address.clone(),
+                        Symbol::new(&fixture.env, "transfer"),
+                        vec![
+                            &fixture.env,
+                            sam.to_val(),
+                            pool_fixture.pool.address.to_val(),
+                            amount.into_val(&fixture.env)
+                        ]
+                    )),
+                    sub_invocations: std::vec![]
+                }]
+            }
+        )
+    );
+}
+
+#[test]
+fn test_malicious_token_reentrant_transfer() {
+    let (fixture, _) = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let (mal, mal_index) = setup_malicious_reserve(&fixture);
+
+    let sam = addr(&fixture.env, "sam");
+    mal.mint(&sam, &(1_000 * SCALAR_7));
+    mal.set_mode(&MaliciousMode::Reenter, &pool_fixture.pool.address);
+
+    // the token calling back into the pool during the transfer aborts the whole submit
+    let result = pool_fixture.pool.try_submit(
+        &sam,
+        &sam,
+        &sam,
+        &vec![
+            &fixture.env,
+            Request {
+                request_type: 2,
+                address: mal.address.clone(),
+                amount: 1_000 * SCALAR_7,
+            },
+        ],
+    );
+    assert!(result.is_err());
+    assert_eq!(mal.balance(&sam), 1_000 * SCALAR_7);
+    assert_eq!(mal.balance(&pool_fixture.pool.address), 0);
+    let positions = pool_fixture.pool.get_positions(&sam);
+    assert!(positions.collateral.get(mal_index).is_none());
+    let reserve_data = pool_fixture.pool.get_reserve_data(&mal.address);
+    assert_eq!(reserve_data.b_supply, 0);
+}
+
+#[test]
+fn test_malicious_token_zero_balance() {
+    let (fixture, _) = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let (mal, mal_index) = setup_malicious_reserve(&fixture);
+
+    let sam = addr(&fixture.env, "sam");
+    mal.mint(&sam, &(100 * SCALAR_7));
+    pool_fixture.pool.submit(
+        &sam,
+        &sam,
+        &sam,
+        &vec![
+            &fixture.env,
+            Request {
+                request_type: 0,
+                address: mal.address.clone(),
+                amount: 100 * SCALAR_7,
+            },
+        ],
+    );
+    let b_rate = pool_fixture.pool.get_reserve_data(&mal.address).b_rate;
+
+    // the token under reports the pool's balance
+    mal.set_mode(&MaliciousMode::ZeroBalance, &pool_fixture.pool.address);
+    fixture.jump(60 * 60 * 24);
+
+    let b_tokens = pool_fixture
+        .pool
+        .get_positions(&sam)
+        .supply
+        .get_unchecked(mal_index);
+    pool_fixture.pool.submit(
+        &sam,
+        &sam,
+        &sam,
+        &vec![
+            &fixture.env,
+            Request {
+                request_type: 1,
+                address: mal.address.clone(),
+                amount: b_tokens,
+            },
+        ],
+    );
+    assert_eq!(
+        pool_fixture.pool.get_reserve_data(&mal.address).b_rate,
+        b_rate
+    );
+    mal.set_mode(&MaliciousMode::Honest, &pool_fixture.pool.address);
+    assert_eq!(mal.balance(&sam), 100 * SCALAR_7);
+    assert_eq!(mal.balance(&pool_fixture.pool.address), 0);
+}
+
+#[test]
+fn test_malicious_token_blocked_user_liquidation() {
+    let (fixture, frodo) = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let (mal, mal_index) = setup_malicious_reserve(&fixture);
+    let usdc = &fixture.tokens[TokenIndex::USDC];
+    let usdc_index = pool_fixture.reserves[&TokenIndex::USDC];
+
+    // sam supplies the malicious token as collateral and borrows USDC
+    let sam = addr(&fixture.env, "sam");
+    mal.mint(&sam, &(1_000 * SCALAR_7));
+    pool_fixture.pool.submit(
+        &sam,
+        &sam,
+        &sam,
+        &vec![
+            &fixture.env,
+            Request {
+                request_type: 2,
+                address: mal.address.clone(),
+                amount: 1_000 * SCALAR_7,
+            },
+            Request {
+                request_type: 4,
+                address: usdc.address.clone(),
+                amount: 500 * 10i128.pow(6),
+            },
+        ],
+    );
+
+    // the token blocks sam, so sam can no longer move collateral
+    mal.block(&sam);
+    let result = pool_fixture.pool.try_submit(
+        &sam,
+        &sam,
+        &sam,
+        &vec![
+            &fixture.env,
+            Request {
+                request_type: 3,
+                address: mal.address.clone(),
+                amount: 1 * SCALAR_7,
+            },
+        ],
+    );
+    assert!(result.is_err());
+
+    // liquidations transfer positions, not tokens, so a blocked user can still be liquidated
+    fixture.oracle.set_price(&mal.address, &0_6000000);
+    let auction_data = pool_fixture.pool.new_liquidation_auction(&sam, &85);
+    assert!(auction_data.lot.contains_key(mal.address.clone()));
+
+    let sam_positions_pre = pool_fixture.pool.get_positions(&sam);
+    fixture.jump(201 * 5);
+    pool_fixture.pool.submit(
+        &frodo,
+        &frodo,
+        &frodo,
+        &vec![
+            &fixture.env,
+            Request {
+                request_type: 6,
+                address: sam.clone(),
+                amount: 100,
+            },
+        ],
+    );
+
+    let frodo_positions = pool_fixture.pool.get_positions(&frodo);
+    assert!(frodo_positions.collateral.get_unchecked(mal_index) > 0);
+    let sam_positions = pool_fixture.pool.get_positions(&sam);
+    assert!(
+        sam_positions.liabilities.get_unchecked(usdc_index)
+            < sam_positions_pre.liabilities.get_unchecked(usdc_index)
+    );
+    assert!(
+        sam_positions.collateral.get_unchecked(mal_index)
+            < sam_positions_pre.collateral.get_unchecked(mal_index)
+    );
+    assert_eq!(mal.balance(&sam), 0);
+    assert_eq!(mal.balance(&pool_fixture.pool.address), 1_000 * SCALAR_7);
+}
@@ -3,10 +3,11 @@
 use fixed_point_math::FixedPoint;
 use lending_pool::{Request, ReserveEmissionMetadata};
 use soroban_sdk::{
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events},
-    vec, Address, IntoVal, Symbol, Val,
+    testutils::{AuthorizedFunction, AuthorizedInvocation, Events},
+    vec, IntoVal, Symbol, Val,
 };
 use test_suites::{
+    addresses::addr,
     assertions::assert_approx_eq_abs,
     create_fixture_with_data,
     pool::default_reserve_metadata,
@@ -25,7 +26,7 @@ fn test_pool_user() {
     let weth = &fixture.tokens[TokenIndex::WETH];
     let weth_scalar: i128 = 10i128.pow(weth.decimals());
 
-    let sam = Address::random(&fixture.env);
+    let sam = addr(&fixture.env, "sam");
 
     // Mint sam tokens
     let mut sam_xlm_balance = 10_000 * SCALAR_7;
@@ -540,13 +541,14 @@ fn test_pool_config() {
 
     // Verify initialize can't be run again
     let result = pool_fixture.pool.try_initialize(
-        &Address::random(&fixture.env),
+        &addr(&fixture.env, "admin"),
         &Symbol::new(&fixture.env, "teapot"),
-        &Address::random(&fixture.env),
+        &addr(&fixture.env, "oracle"),
         &10000,
-        &Address::random(&fixture.env),
-        &Address::random(&fixture.env),
-        &Address::random(&fixture.env),
+        &false,
+        &addr(&fixture.env, "backstop"),
+        &addr(&fixture.env, "blnd_token"),
+        &addr(&fixture.env, "usdc_token"),
     );
     assert!(result.is_err());
 
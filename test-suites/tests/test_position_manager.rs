@@ -0,0 +1,117 @@
+#![cfg(test)]
+use lending_pool::Request;
+use position_manager::StrategyCap;
+use soroban_sdk::vec;
+use test_suites::{
+    addresses::addr,
+    create_fixture_with_data,
+    position_manager::{create_mock_swap_adapter, create_position_manager},
+    test_fixture::{TokenIndex, SCALAR_7},
+};
+
+/// Test the position manager leveraging, unwinding, and swapping the collateral of a position
+/// against the pool with the user's auth
+#[test]
+fn test_position_manager_strategies() {
+    let (fixture, _) = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let usdc = &fixture.tokens[TokenIndex::USDC];
+    let weth = &fixture.tokens[TokenIndex::WETH];
+    let xlm_pool_index = pool_fixture.reserves[&TokenIndex::XLM];
+    let usdc_pool_index = pool_fixture.reserves[&TokenIndex::USDC];
+    let weth_pool_index = pool_fixture.reserves[&TokenIndex::WETH];
+
+    let (adapter_id, adapter_client) = create_mock_swap_adapter(&fixture.env);
+    let (_, manager_client) = create_position_manager(&fixture.env, false);
+    manager_client.initialize(&fixture.bombadil, &adapter_id);
+    for strategy in 0..3 {
+        manager_client.set_strategy_cap(
+            &strategy,
+            &StrategyCap {
+                max_loops: 5,
+                max_amount: 1_000_000 * SCALAR_7,
+            },
+        );
+    }
+
+    // 1 USDC swaps for 10 XLM, 1 XLM swaps for 0.1 USDC, and 2000 XLM swaps for 0.1 wETH
+    adapter_client.set_rate(&usdc.address, &xlm.address, &100_0000000);
+    adapter_client.set_rate(&xlm.address, &usdc.address, &0_0100000);
+    adapter_client.set_rate(&xlm.address, &weth.address, &0_0050000);
+    xlm.mint(&adapter_id, &(100_000 * SCALAR_7));
+    usdc.mint(&adapter_id, &(10_000 * 10i128.pow(6)));
+    weth.mint(&adapter_id, &(10 * 10i128.pow(9)));
+
+    // Sam supplies XLM collateral
+    let sam = addr(&fixture.env, "sam");
+    xlm.mint(&sam, &(10_000 * SCALAR_7));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: 2,
+            address: xlm.address.clone(),
+            amount: 10_000 * SCALAR_7,
+        },
+    ];
+    pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+
+    // Sam leverages their XLM by borrowing USDC three times
+    let supplied = manager_client.leverage(
+        &sam,
+        &pool_fixture.pool.address,
+        &xlm.address,
+        &usdc.address,
+        &(100 * 10i128.pow(6)),
+        &3,
+        &(1_000 * SCALAR_7),
+    );
+    assert_eq!(supplied, 3_000 * SCALAR_7);
+    assert_eq!(xlm.balance(&sam), 0);
+    assert_eq!(usdc.balance(&sam), 0);
+    let positions = pool_fixture.pool.get_positions(&sam);
+    assert!(positions.collateral.get_unchecked(xlm_pool_index) > 12_999 * SCALAR_7);
+    assert!(positions.liabilities.get_unchecked(usdc_pool_index) > 299 * 10i128.pow(6));
+
+    // Sam unwinds part of the position
+    let repaid = manager_client.unwind(
+        &sam,
+        &pool_fixture.pool.address,
+        &xlm.address,
+        &usdc.address,
+        &(1_000 * SCALAR_7),
+        &1,
+        &(100 * 10i128.pow(6)),
+    );
+    assert_eq!(repaid, 100 * 10i128.pow(6));
+    let positions = pool_fixture.pool.get_positions(&sam);
+    assert!(positions.collateral.get_unchecked(xlm_pool_index) < 12_001 * SCALAR_7);
+    assert!(positions.liabilities.get_unchecked(usdc_pool_index) < 201 * 10i128.pow(6));
+
+    // Sam swaps some XLM collateral for wETH collateral
+    let supplied = manager_client.swap_collateral(
+        &sam,
+        &pool_fixture.pool.address,
+        &xlm.address,
+        &weth.address,
+        &(2_000 * SCALAR_7),
+        &(1 * 10i128.pow(8)),
+    );
+    assert_eq!(supplied, 1 * 10i128.pow(8));
+    let positions = pool_fixture.pool.get_positions(&sam);
+    assert!(positions.collateral.get_unchecked(weth_pool_index) > 0);
+    assert_eq!(xlm.balance(&sam), 0);
+    assert_eq!(weth.balance(&sam), 0);
+
+    // loops over the strategy cap are rejected
+    let result = manager_client.try_leverage(
+        &sam,
+        &pool_fixture.pool.address,
+        &xlm.address,
+        &usdc.address,
+        &(10 * 10i128.pow(6)),
+        &6,
+        &(100 * SCALAR_7),
+    );
+    assert!(result.is_err());
+}
@@ -0,0 +1,78 @@
+#![cfg(test)]
+
+use lending_pool::Request;
+use soroban_sdk::{vec, Address, BytesN};
+use test_suites::{
+    addresses::addr,
+    create_fixture_with_data,
+    snapshot::SnapshotFixture,
+    test_fixture::{TokenIndex, SCALAR_7},
+    token::TokenClient,
+};
+
+/// Test that a pool can be loaded from a ledger snapshot and have governance actions and
+/// user actions replayed against it
+#[test]
+fn test_snapshot_replay() {
+    let (fixture, _) = create_fixture_with_data(true);
+    let pool_fixture = &fixture.pools[0];
+    let pool_id = pool_fixture.pool.address.contract_id().to_array();
+    let xlm_id = fixture.tokens[TokenIndex::XLM]
+        .address
+        .contract_id()
+        .to_array();
+    let xlm_config = pool_fixture
+        .pool
+        .get_reserve_config(&fixture.tokens[TokenIndex::XLM].address);
+    let xlm_data = pool_fixture
+        .pool
+        .get_reserve_data(&fixture.tokens[TokenIndex::XLM].address);
+
+    let path = std::env::temp_dir().join("blend_test_snapshot_replay.json");
+    fixture.env.to_snapshot().write_file(&path).unwrap();
+
+    let snapshot = SnapshotFixture::load(&path, &pool_id);
+    let xlm_address = Address::from_contract_id(&BytesN::from_array(&snapshot.env, &xlm_id));
+    let xlm = TokenClient::new(&snapshot.env, &xlm_address);
+
+    // state is restored from the snapshot
+    let restored_data = snapshot.pool.get_reserve_data(&xlm_address);
+    assert_eq!(restored_data.b_supply, xlm_data.b_supply);
+    assert_eq!(restored_data.d_supply, xlm_data.d_supply);
+
+    // replay a governance action
+    let mut new_xlm_config = xlm_config.clone();
+    new_xlm_config.c_factor = 0_500_0000;
+    snapshot.pool.update_reserve(&xlm_address, &new_xlm_config);
+    assert_eq!(
+        snapshot.pool.get_reserve_config(&xlm_address).c_factor,
+        0_500_0000
+    );
+
+    // replay a user action against the updated state
+    snapshot.jump(60);
+    let sam = addr(&snapshot.env, "sam");
+    xlm.mint(&sam, &(1_000 * SCALAR_7));
+    let requests = vec![
+        &snapshot.env,
+        Request {
+            request_type: 2,
+            address: xlm_address.clone(),
+            amount: 1_000 * SCALAR_7,
+        },
+    ];
+    let positions = snapshot.pool.submit(&sam, &sam, &sam, &requests);
+    assert!(positions.collateral.get(xlm_config.index).unwrap() > 0);
+    assert_eq!(xlm.balance(&sam), 0);
+
+    // the original fixture is unaffected by the replay
+    assert_eq!(
+        pool_fixture
+            .pool
+            .get_reserve_config(&fixture.tokens[TokenIndex::XLM].address)
+            .c_factor,
+        xlm_config.c_factor
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,148 @@
+#![cfg(test)]
+
+use lending_pool::Request;
+use soroban_sdk::vec;
+use test_suites::{
+    addresses::addr,
+    test_fixture::{TokenIndex, SCALAR_7},
+    upgrade::{default_reserve_config_v0, pool_v0_contract, UpgradeFixture},
+};
+
+/// Setup a pool at the previous release with USDC and XLM reserves, where "frodo" has
+/// supplied XLM as collateral and borrowed USDC against it
+fn setup_upgrade_fixture<'a>() -> UpgradeFixture<'a> {
+    let upgrade_fixture = UpgradeFixture::create(0_100_000_000);
+    let fixture = &upgrade_fixture.fixture;
+
+    let mut usdc_config = default_reserve_config_v0();
+    usdc_config.decimals = 6;
+    usdc_config.c_factor = 0_900_0000;
+    usdc_config.l_factor = 0_950_0000;
+    upgrade_fixture.create_reserve(TokenIndex::USDC, &usdc_config);
+    upgrade_fixture.create_reserve(TokenIndex::XLM, &default_reserve_config_v0());
+
+    let frodo = addr(&fixture.env, "frodo");
+    fixture.tokens[TokenIndex::USDC].mint(&frodo, &(100_000 * 10i128.pow(6)));
+    fixture.tokens[TokenIndex::XLM].mint(&frodo, &(1_000_000 * SCALAR_7));
+    fixture.tokens[TokenIndex::BSTOP].mint(&frodo, &(2_000_000 * SCALAR_7));
+
+    // activate the pool
+    fixture.backstop.deposit(
+        &frodo,
+        &upgrade_fixture.pool.address,
+        &(2_000_000 * SCALAR_7),
+    );
+    upgrade_fixture.pool.set_status(&0);
+
+    let requests = vec![
+        &fixture.env,
+        pool_v0_contract::Request {
+            request_type: 0,
+            address: fixture.tokens[TokenIndex::USDC].address.clone(),
+            amount: 10_000 * 10i128.pow(6),
+        },
+        pool_v0_contract::Request {
+            request_type: 2,
+            address: fixture.tokens[TokenIndex::XLM].address.clone(),
+            amount: 100_000 * SCALAR_7,
+        },
+        pool_v0_contract::Request {
+            request_type: 4,
+            address: fixture.tokens[TokenIndex::USDC].address.clone(),
+            amount: 5_000 * 10i128.pow(6),
+        },
+    ];
+    upgrade_fixture
+        .pool
+        .submit(&frodo, &frodo, &frodo, &requests);
+
+    fixture.jump(60 * 60); // 1 hr
+    upgrade_fixture
+}
+
+#[test]
+fn test_upgrade_from_v0() {
+    let upgrade_fixture = setup_upgrade_fixture();
+    let fixture = &upgrade_fixture.fixture;
+    let usdc = &fixture.tokens[TokenIndex::USDC];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let frodo = addr(&fixture.env, "frodo");
+
+    let usdc_data_v0 = upgrade_fixture.pool.get_reserve_data(&usdc.address);
+    let xlm_data_v0 = upgrade_fixture.pool.get_reserve_data(&xlm.address);
+
+    let (pool, from_version) = upgrade_fixture.upgrade(&vec![&fixture.env]);
+    assert_eq!(from_version, 0);
+    assert_eq!(pool.get_storage_version(), 1);
+
+    // the pool config is migrated with the default liquidation health factors
+    let pool_config = pool.get_pool_config();
+    assert_eq!(pool_config.oracle, fixture.oracle.address);
+    assert_eq!(pool_config.bstop_rate, 0_100_000_000);
+    assert_eq!(pool_config.status, 0);
+    assert_eq!(pool_config.liq_hf_low, 1_0000000);
+    assert_eq!(pool_config.liq_hf_high, 1_0000100);
+
+    // reserve configs are migrated without an oracle asset or isolation
+    let usdc_config = pool.get_reserve_config(&usdc.address);
+    assert_eq!(usdc_config.index, 0);
+    assert_eq!(usdc_config.decimals, 6);
+    assert_eq!(usdc_config.c_factor, 0_900_0000);
+    assert_eq!(usdc_config.l_factor, 0_950_0000);
+    assert!(usdc_config.oracle_asset.is_none());
+    assert!(!usdc_config.isolated);
+    let xlm_config = pool.get_reserve_config(&xlm.address);
+    assert_eq!(xlm_config.index, 1);
+    assert_eq!(xlm_config.c_factor, 0_7500000);
+
+    // reserve data and positions are read unchanged
+    let usdc_data = pool.get_reserve_data(&usdc.address);
+    assert_eq!(usdc_data.b_supply, usdc_data_v0.b_supply);
+    assert_eq!(usdc_data.d_supply, usdc_data_v0.d_supply);
+    assert_eq!(usdc_data.d_rate, usdc_data_v0.d_rate);
+    assert_eq!(usdc_data.last_time, usdc_data_v0.last_time);
+    let xlm_data = pool.get_reserve_data(&xlm.address);
+    assert_eq!(xlm_data.b_supply, xlm_data_v0.b_supply);
+    let positions = pool.get_positions(&frodo);
+    assert_eq!(positions.supply.get_unchecked(0), usdc_data_v0.b_supply);
+    assert_eq!(positions.collateral.get_unchecked(1), xlm_data_v0.b_supply);
+    assert_eq!(
+        positions.liabilities.get_unchecked(0),
+        usdc_data_v0.d_supply
+    );
+
+    // the upgraded pool accrues interest and accepts writes
+    let usdc_balance = usdc.balance(&frodo);
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: 5,
+            address: usdc.address.clone(),
+            amount: 1_000 * 10i128.pow(6),
+        },
+        Request {
+            request_type: 4,
+            address: usdc.address.clone(),
+            amount: 500 * 10i128.pow(6),
+        },
+    ];
+    let new_positions = pool.submit(&frodo, &frodo, &frodo, &requests);
+    assert_eq!(usdc.balance(&frodo), usdc_balance - 500 * 10i128.pow(6));
+    assert!(new_positions.liabilities.get_unchecked(0) < positions.liabilities.get_unchecked(0));
+    assert!(pool.get_reserve_data(&usdc.address).d_rate > usdc_data_v0.d_rate);
+
+    // settings added after the previous release can be set on migrated reserves
+    let mut new_xlm_config = xlm_config.clone();
+    new_xlm_config.isolated = true;
+    pool.update_reserve(&xlm.address, &new_xlm_config);
+    assert!(pool.get_reserve_config(&xlm.address).isolated);
+}
+
+#[test]
+#[should_panic]
+//#[should_panic(expected = "ContractError(2)")]
+fn test_upgrade_migrate_twice() {
+    let upgrade_fixture = setup_upgrade_fixture();
+    let (pool, _) = upgrade_fixture.upgrade(&vec![&upgrade_fixture.fixture.env]);
+    pool.migrate(&vec![&upgrade_fixture.fixture.env]);
+}
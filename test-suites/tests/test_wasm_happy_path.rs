@@ -2,8 +2,9 @@
 
 use fixed_point_math::FixedPoint;
 use lending_pool::Request;
-use soroban_sdk::{testutils::Address as _, vec, Address};
+use soroban_sdk::vec;
 use test_suites::{
+    addresses::addr,
     assertions::assert_approx_eq_abs,
     create_fixture_with_data,
     test_fixture::{TokenIndex, SCALAR_7, SCALAR_9},
@@ -18,8 +19,8 @@ fn test_wasm_happy_path() {
     let xlm_pool_index = pool_fixture.reserves[&TokenIndex::XLM];
 
     // Create two new users
-    let sam = Address::random(&fixture.env); // sam will be supplying XLM and borrowing USDC
-    let merry = Address::random(&fixture.env); // merry will be supplying USDC and borrowing XLM
+    let sam = addr(&fixture.env, "sam"); // sam will be supplying XLM and borrowing USDC
+    let merry = addr(&fixture.env, "merry"); // merry will be supplying USDC and borrowing XLM
 
     // Mint users tokens
     let usdc = &fixture.tokens[TokenIndex::USDC];